@@ -1,24 +1,135 @@
-use std::sync::Arc;
-use tokio::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio::time::{Duration, Instant, MissedTickBehavior};
+use crate::db::get_db;
+use crate::pacing::Pacer;
+use crate::JobState;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tauri::Emitter;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// Requests awaiting a response at most. A target slower than this allows
+/// holds the achieved rate below the target, which the result shows.
+const MAX_IN_FLIGHT: usize = 256;
+
+/// Interval between `rate-limit-progress` events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
 pub struct RateLimitResult {
+    /// Job id the test ran under
+    pub id: String,
     pub url: String,
-    pub total_requests: usize,
+    pub duration_secs: i64,
+    /// Requests sent; fewer than planned when cancelled
+    pub total_requests: i64,
+    pub success_count: i64,
+    pub rate_limited_count: i64,
+    /// Timeouts and connection failures
+    pub error_count: i64,
+    /// Mean over requests that got a response
+    pub avg_latency_ms: i64,
+    pub target_rps: i64,
+    /// Requests sent per second over the sending window
+    pub achieved_rps: f64,
+    pub is_vulnerable: bool,
+    pub cancelled: bool,
+    pub created_at: Option<String>,
+}
+
+/// Partial stats streamed while a test runs.
+#[derive(Serialize, Clone, Debug)]
+pub struct RateLimitProgress {
+    pub job_id: String,
+    pub sent: usize,
+    pub completed: usize,
+    pub total: usize,
     pub success_count: usize,
     pub rate_limited_count: usize,
-    pub avg_latency_ms: u64,
-    pub is_vulnerable: bool,
+    pub error_count: usize,
+    /// Requests sent per second since the previous event
+    pub current_rps: f64,
 }
 
-pub async fn test_rate_limit(
+/// Sent with `rate-limit-complete`.
+#[derive(Serialize, Clone, Debug)]
+pub struct RateLimitComplete {
+    pub job_id: String,
+    pub result: Option<RateLimitResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Tally {
+    completed: usize,
+    success: usize,
+    rate_limited: usize,
+    errors: usize,
+    latency_ms: u64,
+}
+
+impl Tally {
+    fn record(&mut self, outcome: Result<(Result<u16, reqwest::Error>, Duration), tokio::task::JoinError>) {
+        self.completed += 1;
+        match outcome {
+            Ok((Ok(status), latency)) => {
+                if status == 429 {
+                    self.rate_limited += 1;
+                } else if (200..300).contains(&status) {
+                    self.success += 1;
+                }
+                self.latency_ms += latency.as_millis() as u64;
+            }
+            _ => self.errors += 1,
+        }
+    }
+}
+
+/// Emits `rate-limit-progress` at most every `PROGRESS_INTERVAL`.
+struct Reporter {
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    total: usize,
+    last_emit: Instant,
+    last_sent: usize,
+}
+
+impl Reporter {
+    fn report(&mut self, sent: usize, tally: &Tally, force: bool) {
+        let elapsed = self.last_emit.elapsed();
+        if !force && elapsed < PROGRESS_INTERVAL {
+            return;
+        }
+        let progress = RateLimitProgress {
+            job_id: self.job_id.clone(),
+            sent,
+            completed: tally.completed,
+            total: self.total,
+            success_count: tally.success,
+            rate_limited_count: tally.rate_limited,
+            error_count: tally.errors,
+            current_rps: (sent - self.last_sent) as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        };
+        let _ = self.app_handle.emit("rate-limit-progress", progress);
+        self.last_emit = Instant::now();
+        self.last_sent = sent;
+    }
+}
+
+/// Sends `target_rps` GETs a second for `duration_secs`. Requests go out on
+/// a fixed schedule rather than one after another, so a slow response
+/// doesn't delay the next request. Cancelling stops sending and drops the
+/// requests still in flight.
+async fn test_rate_limit(
     app_handle: tauri::AppHandle,
+    job_id: &str,
     url: String,
     target_rps: usize,
-    duration_secs: u64
+    duration_secs: u64,
+    cancel: &AtomicBool,
 ) -> Result<RateLimitResult, String> {
     let client = Client::builder()
         .timeout(Duration::from_secs(5))
@@ -26,52 +137,523 @@ pub async fn test_rate_limit(
         .build()
         .map_err(|e| e.to_string())?;
 
-    let mut success_count = 0;
-    let mut rate_limited_count = 0;
-    let mut total_latency = 0;
-    let start_time = Instant::now();
     let total_to_send = target_rps * duration_secs as usize;
+    let mut reporter = Reporter { app_handle, job_id: job_id.to_string(), total: total_to_send, last_emit: Instant::now(), last_sent: 0 };
+    let mut tally = Tally::default();
+    let mut in_flight = JoinSet::new();
+    let mut sent = 0;
 
-    for i in 0..total_to_send {
-        let req_start = Instant::now();
-        let res = client.get(&url).send().await;
-        
-        match res {
-            Ok(resp) => {
-                if resp.status() == 429 {
-                    rate_limited_count += 1;
-                } else if resp.status().is_success() {
-                    success_count += 1;
-                }
-                total_latency += req_start.elapsed().as_millis() as u64;
+    // Timer ticks missed under load fire at once, keeping the average on target
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / target_rps as f64));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+    let start_time = Instant::now();
+    while sent < total_to_send && !cancel.load(Ordering::Relaxed) {
+        ticker.tick().await;
+        while in_flight.len() >= MAX_IN_FLIGHT {
+            if let Some(outcome) = in_flight.join_next().await {
+                tally.record(outcome);
             }
-            Err(_) => {}
         }
-
-        // Progress update
-        let _ = app_handle.emit("rate-limit-progress", json!({
-            "current": i + 1,
-            "total": total_to_send
-        }));
-
-        // Simple throttle to hit Target RPS
-        let elapsed = start_time.elapsed().as_secs_f64();
-        let expected = (i + 1) as f64 / target_rps as f64;
-        if expected > elapsed {
-            tokio::time::sleep(Duration::from_secs_f128((expected - elapsed) as f128)).await;
+        let (client, url) = (client.clone(), url.clone());
+        in_flight.spawn(async move {
+            let req_start = Instant::now();
+            let status = client.get(&url).send().await.map(|resp| resp.status().as_u16());
+            (status, req_start.elapsed())
+        });
+        sent += 1;
+        while let Some(outcome) = in_flight.try_join_next() {
+            tally.record(outcome);
         }
+        reporter.report(sent, &tally, false);
+    }
+    let send_window = start_time.elapsed().as_secs_f64();
+    let cancelled = cancel.load(Ordering::Relaxed);
+    if cancelled {
+        in_flight.shutdown().await;
     }
+    while let Some(outcome) = in_flight.join_next().await {
+        tally.record(outcome);
+        reporter.report(sent, &tally, false);
+    }
+    reporter.report(sent, &tally, true);
+
+    let responded = tally.completed - tally.errors;
+    let avg_latency = if responded > 0 { tally.latency_ms / responded as u64 } else { 0 };
+    // The first request goes out at once, so n requests span n - 1 intervals
+    let achieved_rps = match sent {
+        0 => 0.0,
+        1 => target_rps as f64,
+        n => (n - 1) as f64 / send_window.max(f64::EPSILON),
+    };
 
-    let avg_latency = if total_to_send > 0 { total_latency / total_to_send as u64 } else { 0 };
-    
     Ok(RateLimitResult {
+        id: job_id.to_string(),
         url,
-        total_requests: total_to_send,
-        success_count,
-        rate_limited_count,
-        avg_latency_ms: avg_latency,
-        is_vulnerable: rate_limited_count == 0 && success_count > 10,
+        duration_secs: duration_secs as i64,
+        total_requests: sent as i64,
+        success_count: tally.success as i64,
+        rate_limited_count: tally.rate_limited as i64,
+        error_count: tally.errors as i64,
+        avg_latency_ms: avg_latency as i64,
+        target_rps: target_rps as i64,
+        achieved_rps,
+        is_vulnerable: tally.rate_limited == 0 && tally.success > 10,
+        cancelled,
+        created_at: None,
+    })
+}
+
+async fn save_result(result: &RateLimitResult) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO rate_limit_tests (id, url, duration_secs, total_requests, success_count, rate_limited_count, error_count, avg_latency_ms, target_rps, achieved_rps, is_vulnerable, cancelled)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&result.id)
+    .bind(&result.url)
+    .bind(result.duration_secs)
+    .bind(result.total_requests)
+    .bind(result.success_count)
+    .bind(result.rate_limited_count)
+    .bind(result.error_count)
+    .bind(result.avg_latency_ms)
+    .bind(result.target_rps)
+    .bind(result.achieved_rps)
+    .bind(result.is_vulnerable)
+    .bind(result.cancelled)
+    .execute(&get_db())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Starts a rate limit test in the background and returns its job id.
+/// Partial stats stream through `rate-limit-progress`, the stored result
+/// arrives with `rate-limit-complete`, and `cancel_fuzz` stops it. `rps`
+/// may not exceed the scan profile's rate.
+#[tauri::command]
+pub async fn run_rate_limit_test(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<JobState>>,
+    url: String,
+    rps: usize,
+    duration: u64,
+    scan_profile_id: Option<i64>,
+) -> Result<String, String> {
+    if rps == 0 || duration == 0 {
+        return Err("Rate and duration must be at least 1".to_string());
+    }
+    let profile = crate::scan_profiles::authorize(scan_profile_id, crate::scan_profiles::ActiveCheck::RateLimit).await?;
+    let url = crate::variables::Resolver::active().await?.resolve(&url)?;
+    let max_rps = profile.pacing.requests_per_second;
+    if max_rps > 0.0 && rps as f64 > max_rps {
+        return Err(format!("The '{}' scan profile allows at most {} requests per second", profile.name, max_rps));
+    }
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "rate-limit-complete",
+        move |app_handle, job_id, cancel| async move {
+            let result = test_rate_limit(app_handle, &job_id, url, rps, duration, &cancel).await?;
+            save_result(&result).await.map(|()| result)
+        },
+        |job_id, outcome, _| {
+            let (result, error) = match outcome {
+                Ok(result) => (Some(result), None),
+                Err(e) => (None, Some(e)),
+            };
+            RateLimitComplete { job_id, result, error }
+        },
+    ))
+}
+
+/// Stored rate limit tests, newest first.
+#[tauri::command]
+pub async fn list_rate_limit_tests() -> Result<Vec<RateLimitResult>, String> {
+    sqlx::query_as::<_, RateLimitResult>("SELECT * FROM rate_limit_tests ORDER BY created_at DESC, rowid DESC LIMIT 50")
+        .fetch_all(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Plain requests sent after a bypass attempt to check the limit was still
+/// in force while it ran.
+const CONTROL_REQUESTS: usize = 3;
+
+fn default_burst() -> usize {
+    50
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RateLimitBypassRequest {
+    pub url: String,
+    /// Sent with every request, e.g. the API key the limit is counted against
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Requests per attempt, and the most sent to trigger the limit
+    #[serde(default = "default_burst")]
+    pub burst: usize,
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BypassTechnique {
+    /// A new client address per request in X-Forwarded-For
+    ForwardedFor,
+    /// A new client address per request in X-Real-IP
+    RealIp,
+    /// One path letter in the other case per request
+    PathCase,
+    /// HTTP/1.1 and HTTP/2 alternately
+    HttpVersion,
+    /// The API key padded, renamed or moved to the query string
+    ApiKeyHeader,
+}
+
+impl BypassTechnique {
+    pub const ALL: [BypassTechnique; 5] =
+        [BypassTechnique::ForwardedFor, BypassTechnique::RealIp, BypassTechnique::PathCase, BypassTechnique::HttpVersion, BypassTechnique::ApiKeyHeader];
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BypassVerdict {
+    /// Every request got through while plain requests stayed limited
+    Evaded,
+    /// Some requests got through while plain requests stayed limited
+    Partial,
+    Blocked,
+    /// The limit lapsed during the attempt, so it proves nothing
+    Inconclusive,
+    /// Nothing to vary, e.g. no API key header or no letters in the path
+    NotApplicable,
+}
+
+/// One request of a bypass attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    /// Forces HTTP/1.1; otherwise HTTP/2 is used where the server offers it
+    pub http1: bool,
+    /// Shown in the report for the first request
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BypassAttempt {
+    pub technique: BypassTechnique,
+    pub sent: usize,
+    pub success_count: usize,
+    pub rate_limited_count: usize,
+    pub error_count: usize,
+    /// Whether plain requests right after the attempt were still limited
+    pub control_limited: bool,
+    pub verdict: BypassVerdict,
+    /// How the first request was varied
+    pub example: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, FromRow)]
+pub struct BypassReport {
+    pub job_id: String,
+    pub url: String,
+    /// Plain requests answered before the first 429; `None` when the limit
+    /// was never hit and there was nothing to bypass
+    pub limit_after: Option<i64>,
+    #[sqlx(json)]
+    pub attempts: Vec<BypassAttempt>,
+    pub cancelled: bool,
+    pub created_at: Option<String>,
+}
+
+/// Sent with `rate-limit-bypass-complete`.
+#[derive(Serialize, Clone, Debug)]
+pub struct BypassComplete {
+    pub job_id: String,
+    pub report: Option<BypassReport>,
+    pub error: Option<String>,
+}
+
+/// A private address distinct for every `i` below 2^24.
+pub fn spoofed_ip(i: usize) -> String {
+    format!("10.{}.{}.{}", (i >> 16) & 255, (i >> 8) & 255, i & 255)
+}
+
+/// `url` with the case of one path letter flipped, a different one for
+/// successive `i`; `None` when the path has no letters.
+pub fn case_mutated(url: &str, i: usize) -> Option<String> {
+    let mut parsed = url::Url::parse(url).ok()?;
+    let mut path = parsed.path().as_bytes().to_vec();
+    let letters: Vec<usize> = path.iter().enumerate().filter(|(_, b)| b.is_ascii_alphabetic()).map(|(k, _)| k).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    path[letters[i % letters.len()]] ^= 0x20;
+    parsed.set_path(std::str::from_utf8(&path).ok()?);
+    Some(parsed.to_string())
+}
+
+/// The API key header the limit is likely counted against.
+fn api_key_header(headers: &HashMap<String, String>) -> Option<(&String, &String)> {
+    headers.iter().find(|(k, _)| {
+        crate::unauth::is_credential(k) && !matches!(k.to_lowercase().as_str(), "authorization" | "cookie" | "proxy-authorization")
     })
 }
 
-use serde_json::json;
+/// Request `i` of an attempt with `technique`, or `None` when it doesn't apply.
+pub fn variant(technique: BypassTechnique, i: usize, url: &str, headers: &HashMap<String, String>) -> Option<Variant> {
+    let mut v = Variant { url: url.to_string(), headers: headers.clone(), http1: false, description: String::new() };
+    match technique {
+        BypassTechnique::ForwardedFor | BypassTechnique::RealIp => {
+            let name = if technique == BypassTechnique::ForwardedFor { "X-Forwarded-For" } else { "X-Real-IP" };
+            let ip = spoofed_ip(i + 1);
+            v.description = format!("{}: {}", name, ip);
+            v.headers.insert(name.to_string(), ip);
+        }
+        BypassTechnique::PathCase => {
+            v.url = case_mutated(url, i)?;
+            v.description = v.url.clone();
+        }
+        BypassTechnique::HttpVersion => {
+            v.http1 = i.is_multiple_of(2);
+            v.description = if v.http1 { "HTTP/1.1" } else { "HTTP/2 where offered" }.to_string();
+        }
+        BypassTechnique::ApiKeyHeader => {
+            let (name, value) = api_key_header(headers).map(|(k, v)| (k.clone(), v.clone()))?;
+            let mut parsed = url::Url::parse(url).ok()?;
+            match i % 4 {
+                0 => {
+                    v.headers.insert(name.clone(), format!("{} ", value));
+                    v.description = format!("{} with trailing whitespace", name);
+                }
+                // Without a dash to swap, renaming would resend the plain request
+                1 if !name.contains('-') => return None,
+                1 => {
+                    v.headers.remove(&name);
+                    let renamed = name.replace('-', "_");
+                    v.description = format!("{} renamed to {}", name, renamed);
+                    v.headers.insert(renamed, value);
+                }
+                n => {
+                    v.headers.remove(&name);
+                    let param = if n == 2 { "api_key".to_string() } else { name.to_lowercase() };
+                    parsed.query_pairs_mut().append_pair(&param, &value);
+                    v.url = parsed.to_string();
+                    v.description = format!("{} moved to the {} query parameter", name, param);
+                }
+            }
+        }
+    }
+    Some(v)
+}
+
+pub fn judge_bypass(success_count: usize, rate_limited_count: usize, control_limited: bool) -> BypassVerdict {
+    if success_count == 0 {
+        BypassVerdict::Blocked
+    } else if !control_limited {
+        BypassVerdict::Inconclusive
+    } else if rate_limited_count == 0 {
+        BypassVerdict::Evaded
+    } else {
+        BypassVerdict::Partial
+    }
+}
+
+struct BypassClients {
+    http1: Client,
+    any: Client,
+}
+
+impl BypassClients {
+    fn new() -> Result<Self, String> {
+        let builder = || Client::builder().timeout(Duration::from_secs(5)).danger_accept_invalid_certs(true);
+        Ok(Self {
+            http1: builder().http1_only().build().map_err(|e| e.to_string())?,
+            any: builder().build().map_err(|e| e.to_string())?,
+        })
+    }
+
+    /// The response status, or `None` when the request failed. Deferred
+    /// `$` builtins get fresh values.
+    async fn send(&self, v: &Variant, pacer: &Pacer) -> Option<u16> {
+        pacer.wait().await;
+        let client = if v.http1 { &self.http1 } else { &self.any };
+        let mut req = client.get(crate::variables::fresh(&v.url));
+        for (k, value) in crate::variables::fresh_headers(&v.headers) {
+            req = req.header(k, value);
+        }
+        req.send().await.ok().map(|res| res.status().as_u16())
+    }
+
+    /// Sends the plain request until it's throttled, at most `burst` times,
+    /// and returns how many were answered before the first 429.
+    async fn trigger_limit(&self, plain: &Variant, burst: usize, pacer: &Pacer, cancel: &AtomicBool) -> Option<usize> {
+        for i in 0..burst {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            if self.send(plain, pacer).await == Some(429) {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+/// Triggers the limit with plain requests, then for each technique sends a
+/// burst of varied requests followed by plain controls. Getting through
+/// only counts when the controls show the limit was still in force.
+async fn test_bypasses(app_handle: tauri::AppHandle, job_id: &str, request: RateLimitBypassRequest, pacer: Pacer, cancel: &AtomicBool) -> Result<BypassReport, String> {
+    let clients = BypassClients::new()?;
+    let plain = Variant { url: request.url.clone(), headers: request.headers.clone(), http1: false, description: String::new() };
+    let mut report =
+        BypassReport { job_id: job_id.to_string(), url: request.url.clone(), limit_after: None, attempts: Vec::new(), cancelled: false, created_at: None };
+
+    report.limit_after = clients.trigger_limit(&plain, request.burst, &pacer, cancel).await.map(|n| n as i64);
+    if report.limit_after.is_some() {
+        let total = BypassTechnique::ALL.len();
+        for (n, technique) in BypassTechnique::ALL.into_iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let variants: Vec<Variant> = (0..request.burst).filter_map(|i| variant(technique, i, &request.url, &request.headers)).collect();
+            let mut attempt = BypassAttempt {
+                technique,
+                sent: 0,
+                success_count: 0,
+                rate_limited_count: 0,
+                error_count: 0,
+                control_limited: false,
+                verdict: BypassVerdict::NotApplicable,
+                example: variants.first().map(|v| v.description.clone()),
+            };
+            // The limit may have lapsed since the last attempt
+            if !variants.is_empty() && (n == 0 || clients.trigger_limit(&plain, request.burst, &pacer, cancel).await.is_some()) {
+                for v in &variants {
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    attempt.sent += 1;
+                    match clients.send(v, &pacer).await {
+                        Some(429) => attempt.rate_limited_count += 1,
+                        Some(status) if (200..300).contains(&status) => attempt.success_count += 1,
+                        Some(_) => {}
+                        None => attempt.error_count += 1,
+                    }
+                }
+                for _ in 0..CONTROL_REQUESTS {
+                    attempt.control_limited |= clients.send(&plain, &pacer).await == Some(429);
+                }
+                attempt.verdict = judge_bypass(attempt.success_count, attempt.rate_limited_count, attempt.control_limited);
+            } else if !variants.is_empty() {
+                attempt.verdict = BypassVerdict::Inconclusive;
+            }
+            let _ = app_handle.emit("rate-limit-bypass-progress", (n + 1, total, attempt.clone()));
+            report.attempts.push(attempt);
+        }
+    }
+    report.cancelled = cancel.load(Ordering::Relaxed);
+    Ok(report)
+}
+
+async fn save_bypass_report(report: &BypassReport) -> Result<(), String> {
+    sqlx::query("INSERT INTO rate_limit_bypass_tests (job_id, url, limit_after, attempts, cancelled) VALUES (?, ?, ?, ?, ?)")
+        .bind(&report.job_id)
+        .bind(&report.url)
+        .bind(report.limit_after)
+        .bind(sqlx::types::Json(&report.attempts))
+        .bind(report.cancelled)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Tries to evade a rate limit in the background and returns the job id.
+/// Attempts stream through `rate-limit-bypass-progress`, the stored report
+/// arrives with `rate-limit-bypass-complete`, and `cancel_fuzz` stops it.
+#[tauri::command]
+pub async fn run_rate_limit_bypass(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<JobState>>, mut request: RateLimitBypassRequest) -> Result<String, String> {
+    if request.burst == 0 {
+        return Err("Burst must be at least 1".to_string());
+    }
+    crate::env_auth::EnvAuth::inject_active(&mut request.headers).await?;
+    let mut resolver = crate::variables::Resolver::active().await?.defer_builtins();
+    request.url = resolver.resolve(&request.url)?;
+    request.headers = resolver.resolve_headers(std::mem::take(&mut request.headers))?;
+    url::Url::parse(&request.url).map_err(|e| e.to_string())?;
+    let profile = crate::scan_profiles::authorize(request.scan_profile_id, crate::scan_profiles::ActiveCheck::RateLimit).await?;
+    let pacer = Pacer::new(&profile.pacing);
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "rate-limit-bypass-complete",
+        move |app_handle, job_id, cancel| async move {
+            let report = test_bypasses(app_handle, &job_id, request, pacer, &cancel).await?;
+            save_bypass_report(&report).await.map(|()| report)
+        },
+        |job_id, outcome, _| {
+            let (report, error) = match outcome {
+                Ok(report) => (Some(report), None),
+                Err(e) => (None, Some(e)),
+            };
+            BypassComplete { job_id, report, error }
+        },
+    ))
+}
+
+/// Stored rate limit bypass tests, newest first.
+#[tauri::command]
+pub async fn list_rate_limit_bypasses() -> Result<Vec<BypassReport>, String> {
+    sqlx::query_as::<_, BypassReport>("SELECT * FROM rate_limit_bypass_tests ORDER BY created_at DESC, rowid DESC LIMIT 50")
+        .fetch_all(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants() {
+        let url = "https://api.example.com/v1/users?page=2";
+        let headers: HashMap<String, String> = [("X-Api-Key".to_string(), "k-123".to_string())].into_iter().collect();
+
+        assert_eq!(spoofed_ip(1), "10.0.0.1");
+        assert_eq!(spoofed_ip(300), "10.0.1.44");
+        let forwarded = variant(BypassTechnique::ForwardedFor, 0, url, &headers).unwrap();
+        assert_eq!(forwarded.headers["X-Forwarded-For"], "10.0.0.1");
+
+        assert_eq!(case_mutated(url, 0).unwrap(), "https://api.example.com/V1/users?page=2");
+        assert_eq!(case_mutated(url, 1).unwrap(), "https://api.example.com/v1/Users?page=2");
+        assert_eq!(case_mutated("https://api.example.com/123", 0), None);
+
+        let versions: Vec<bool> = (0..3).map(|i| variant(BypassTechnique::HttpVersion, i, url, &headers).unwrap().http1).collect();
+        assert_eq!(versions, [true, false, true]);
+
+        let keys: Vec<Variant> = (0..4).map(|i| variant(BypassTechnique::ApiKeyHeader, i, url, &headers).unwrap()).collect();
+        assert_eq!(keys[0].headers["X-Api-Key"], "k-123 ");
+        assert_eq!(keys[1].headers.get("X-Api-Key"), None);
+        assert_eq!(keys[1].headers["X_Api_Key"], "k-123");
+        assert_eq!(keys[2].url, "https://api.example.com/v1/users?page=2&api_key=k-123");
+        assert_eq!(keys[3].url, "https://api.example.com/v1/users?page=2&x-api-key=k-123");
+        assert!(variant(BypassTechnique::ApiKeyHeader, 0, url, &HashMap::new()).is_none());
+        // Renaming a dashless header would change nothing
+        let apikey: HashMap<String, String> = [("apikey".to_string(), "k-123".to_string())].into_iter().collect();
+        assert!(variant(BypassTechnique::ApiKeyHeader, 1, url, &apikey).is_none());
+        assert!(variant(BypassTechnique::ApiKeyHeader, 2, url, &apikey).is_some());
+    }
+
+    #[test]
+    fn test_judge_bypass() {
+        assert_eq!(judge_bypass(20, 0, true), BypassVerdict::Evaded);
+        assert_eq!(judge_bypass(5, 15, true), BypassVerdict::Partial);
+        assert_eq!(judge_bypass(0, 20, true), BypassVerdict::Blocked);
+        // Getting through is expected once the limit's window has passed
+        assert_eq!(judge_bypass(20, 0, false), BypassVerdict::Inconclusive);
+    }
+}
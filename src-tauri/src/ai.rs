@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use reqwest;
+use std::sync::LazyLock;
+use tauri::Emitter;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LLMRequest {
@@ -12,15 +13,111 @@ pub struct LLMRequest {
 pub struct LLMResponse {
     pub response: String,
     pub done: bool,
+    /// Tokens in the prompt, when the server reports it
+    #[serde(default)]
+    pub prompt_eval_count: Option<i64>,
+    /// Tokens generated, when the server reports it
+    #[serde(default)]
+    pub eval_count: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// What a model call is for. Picks the model and labels usage metrics.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AiTask {
+    Triage,
+    Report,
+    Payloads,
+    Openapi,
+    Chat,
+    Embedding,
+}
+
+impl AiTask {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AiTask::Triage => "triage",
+            AiTask::Report => "report",
+            AiTask::Payloads => "payloads",
+            AiTask::Openapi => "openapi",
+            AiTask::Chat => "chat",
+            AiTask::Embedding => "embedding",
+        }
+    }
+
+    fn model_setting(self) -> &'static str {
+        match self {
+            AiTask::Triage => "ai_triage_model",
+            AiTask::Report => "ai_report_model",
+            AiTask::Embedding => "ai_embedding_model",
+            _ => "ai_model",
+        }
+    }
+}
+
+/// Model configured for `task`; an empty per-task setting means `ai_model`.
+pub(crate) async fn model_for(task: AiTask) -> Result<String, String> {
+    let model: String = crate::settings::get(task.model_setting()).await?;
+    if model.trim().is_empty() {
+        crate::settings::get("ai_model").await
+    } else {
+        Ok(model.trim().to_string())
+    }
+}
+
+/// Records one model call in `ai_usage`. Failures to record are ignored so
+/// metrics never break the feature itself.
+async fn record_usage(task: AiTask, model: &str, started: std::time::Instant, outcome: Result<(Option<i64>, Option<i64>), &str>) {
+    let (tokens, error) = match outcome {
+        Ok(tokens) => (tokens, None),
+        Err(e) => ((None, None), Some(e)),
+    };
+    let _ = sqlx::query(
+        "INSERT INTO ai_usage (task, model, prompt_tokens, completion_tokens, latency_ms, success, error) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(task.as_str())
+    .bind(model)
+    .bind(tokens.0)
+    .bind(tokens.1)
+    .bind(started.elapsed().as_millis() as i64)
+    .bind(error.is_none())
+    .bind(error)
+    .execute(&crate::db::get_db())
+    .await;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TriageSuggestion {
     pub severity_assessment: String,
     pub false_positive_likelihood: String,
     pub owasp_category: String,
     pub remediation_hint: String,
     pub similar_cves: Vec<String>,
+    /// Fields the model left out, filled with a placeholder; empty for a
+    /// complete answer
+    #[serde(default)]
+    pub missing_fields: Vec<String>,
+    /// The reply had no usable JSON and only its prose was kept. Never
+    /// reused for other findings, so a later triage can do better.
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+const TRIAGE_FIELDS: [&str; 4] = ["severity_assessment", "false_positive_likelihood", "owasp_category", "remediation_hint"];
+
+/// Placeholder for triage fields the model didn't provide.
+const NOT_PROVIDED: &str = "Not provided";
+
+/// Longest raw reply kept as the remediation hint when nothing parses.
+const MAX_FALLBACK_CHARS: usize = 1000;
+
+/// Base URL of the LLM server from the `ai_endpoint` setting.
+pub(crate) async fn ai_endpoint() -> Result<String, String> {
+    let endpoint: String = crate::settings::get("ai_endpoint").await?;
+    if endpoint.is_empty() {
+        return Err("AI endpoint is not configured in settings".to_string());
+    }
+    Ok(endpoint.trim_end_matches('/').to_string())
 }
 
 /// Query local LLM (Ollama/LM Studio) for finding triage suggestions
@@ -30,42 +127,149 @@ pub async fn get_triage_suggestion(
     evidence: &str,
     endpoint_url: &str,
 ) -> Result<TriageSuggestion, String> {
-    let prompt = format!(
-        r#"You are an expert API security analyst. Analyze this security finding and provide triage guidance.
-        Specifically, identify which OWASP Top 10 API Security category it falls under (e.g., API1:2023 Broken Object Level Authorization).
-
-**Finding:** {}
-**Description:** {}
-**Evidence:** {}
-**Endpoint:** {}
-
-Provide your analysis in the following JSON format:
-{{
-  "severity_assessment": "Critical/High/Medium/Low with justification",
-  "false_positive_likelihood": "High/Medium/Low with reasoning",
-  "owasp_category": "API X:2023 Category Name",
-  "remediation_hint": "Specific actionable fix recommendation",
-  "similar_cves": ["CVE-XXXX-XXXX", ...]
-}}
-
-Be concise and actionable. Focus on practical security impact."#,
-        finding_name, finding_description, evidence, endpoint_url
+    let prompt = crate::prompts::prompt(
+        "triage",
+        &[("name", finding_name), ("description", finding_description), ("evidence", evidence), ("endpoint", endpoint_url)],
+    )
+    .await?;
+
+    match generate_structured(AiTask::Triage, prompt, 30, triage_from_response).await {
+        Ok(suggestion) => Ok(suggestion),
+        Err(StructuredError::Unparseable(response)) => triage_fallback(&response),
+        Err(StructuredError::Failed(e)) => Err(e),
+    }
+}
+
+/// Builds a suggestion from whichever expected fields the object has,
+/// accepting numbers or lists where text is expected. Errors when none of
+/// them are present.
+pub fn triage_from_value(value: &serde_json::Value) -> Result<TriageSuggestion, String> {
+    let text = |field: &str| match value.get(field) {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(s)) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        Some(serde_json::Value::Array(items)) => {
+            Some(items.iter().map(|i| i.as_str().map(str::to_string).unwrap_or_else(|| i.to_string())).collect::<Vec<_>>().join("; "))
+        }
+        Some(other) => Some(other.to_string()),
+    };
+    let similar_cves: Option<Vec<String>> = match value.get("similar_cves") {
+        Some(serde_json::Value::Array(items)) => Some(items.iter().filter_map(|i| i.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()),
+        Some(serde_json::Value::String(s)) => Some(s.split([',', ';', ' ', '\n']).map(str::trim).filter(|c| c.to_uppercase().starts_with("CVE-")).map(str::to_string).collect()),
+        _ => None,
+    };
+
+    let mut missing_fields = Vec::new();
+    let mut field = |name: &str| {
+        text(name).unwrap_or_else(|| {
+            missing_fields.push(name.to_string());
+            NOT_PROVIDED.to_string()
+        })
+    };
+    let suggestion = TriageSuggestion {
+        severity_assessment: field(TRIAGE_FIELDS[0]),
+        false_positive_likelihood: field(TRIAGE_FIELDS[1]),
+        owasp_category: field(TRIAGE_FIELDS[2]),
+        remediation_hint: field(TRIAGE_FIELDS[3]),
+        similar_cves: similar_cves.clone().unwrap_or_default(),
+        missing_fields,
+        fallback: false,
+    };
+    if suggestion.missing_fields.len() == TRIAGE_FIELDS.len() && similar_cves.is_none() {
+        return Err("LLM response had none of the triage fields".to_string());
+    }
+    Ok(suggestion)
+}
+
+fn triage_from_response(response: &str) -> Result<TriageSuggestion, String> {
+    let json = extract_json(response).ok_or("LLM response contained no JSON")?;
+    triage_from_value(&serde_json::from_str(json).map_err(|e| e.to_string())?)
+}
+
+/// Last resort when even the retry gave no usable JSON: keep the model's
+/// prose as the remediation hint and mark every other field missing.
+pub fn triage_fallback(response: &str) -> Result<TriageSuggestion, String> {
+    let prose = response.trim();
+    if prose.is_empty() {
+        return Err("LLM returned an empty response".to_string());
+    }
+    let mut missing_fields: Vec<String> = TRIAGE_FIELDS[..3].iter().map(|f| f.to_string()).collect();
+    missing_fields.push("similar_cves".to_string());
+    Ok(TriageSuggestion {
+        severity_assessment: NOT_PROVIDED.to_string(),
+        false_positive_likelihood: NOT_PROVIDED.to_string(),
+        owasp_category: NOT_PROVIDED.to_string(),
+        remediation_hint: prose.chars().take(MAX_FALLBACK_CHARS).collect(),
+        similar_cves: Vec::new(),
+        missing_fields,
+        fallback: true,
+    })
+}
+
+pub(crate) enum StructuredError {
+    /// The model answered twice but neither reply could be used; holds the
+    /// last reply
+    Unparseable(String),
+    /// The model couldn't be reached
+    Failed(String),
+}
+
+impl From<StructuredError> for String {
+    fn from(e: StructuredError) -> String {
+        match e {
+            StructuredError::Unparseable(_) => "LLM did not return usable JSON, even when asked again".to_string(),
+            StructuredError::Failed(e) => e,
+        }
+    }
+}
+
+/// Generates a completion and parses it with `parse`. A reply that doesn't
+/// parse gets one retry that shows the model its answer and the error and
+/// asks for the JSON object alone.
+pub(crate) async fn generate_structured<T>(
+    task: AiTask,
+    prompt: String,
+    timeout_secs: u64,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, StructuredError> {
+    let first = generate(task, prompt.clone(), timeout_secs).await.map_err(StructuredError::Failed)?;
+    let error = match parse(&first) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+    let retry = format!(
+        "{}\n\nYour previous reply was:\n{}\n\nIt could not be used ({}). Reply again with only the JSON object in the format requested above: no prose, no code fences.",
+        prompt,
+        first.chars().take(MAX_FALLBACK_CHARS).collect::<String>(),
+        error
     );
+    let second = generate(task, retry, timeout_secs).await.map_err(StructuredError::Failed)?;
+    parse(&second).map_err(|_| StructuredError::Unparseable(second))
+}
 
-    // Try Ollama first (default port 11434)
-    let ollama_url = "http://localhost:11434/api/generate";
+/// Sends `prompt` to the model configured for `task` and returns its
+/// completion.
+async fn generate(task: AiTask, prompt: String, timeout_secs: u64) -> Result<String, String> {
+    let model = model_for(task).await?;
+    let started = std::time::Instant::now();
+    let result = generate_with(&model, prompt, timeout_secs).await;
+    record_usage(task, &model, started, result.as_ref().map(|r| (r.prompt_eval_count, r.eval_count)).map_err(String::as_str)).await;
+    result.map(|r| r.response)
+}
+
+async fn generate_with(model: &str, prompt: String, timeout_secs: u64) -> Result<LLMResponse, String> {
+    let ollama_url = format!("{}/api/generate", ai_endpoint().await?);
     
     let request = LLMRequest {
-        model: "llama3.2:latest".to_string(),
+        model: model.to_string(),
         prompt,
         stream: false,
     };
 
     let client = reqwest::Client::new();
     let response = client
-        .post(ollama_url)
+        .post(&ollama_url)
         .json(&request)
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(std::time::Duration::from_secs(timeout_secs))
         .send()
         .await
         .map_err(|e| format!("LLM connection failed: {}. Is Ollama running?", e))?;
@@ -74,16 +278,10 @@ Be concise and actionable. Focus on practical security impact."#,
         return Err(format!("LLM returned error: {}", response.status()));
     }
 
-    let llm_response: LLMResponse = response
+    response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
-
-    // Parse JSON from LLM response
-    let suggestion: TriageSuggestion = serde_json::from_str(&llm_response.response)
-        .map_err(|e| format!("LLM returned invalid JSON: {}", e))?;
-
-    Ok(suggestion)
+        .map_err(|e| format!("Failed to parse LLM response: {}", e))
 }
 
 #[tauri::command]
@@ -94,14 +292,284 @@ pub async fn ai_triage_finding(
     evidence: String,
     url: String,
 ) -> Result<TriageSuggestion, String> {
-    get_triage_suggestion(&finding_name, &description, &evidence, &url).await
+    let suggestion = get_triage_suggestion(&finding_name, &description, &evidence, &url).await?;
+    sqlx::query("UPDATE findings SET ai_triage = ?, ai_triaged_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(sqlx::types::Json(&suggestion))
+        .bind(finding_id)
+        .execute(&crate::db::get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(suggestion)
+}
+
+/// The stored suggestion for a finding, from a single or batch triage.
+#[tauri::command]
+pub async fn get_ai_triage(finding_id: i64) -> Result<Option<TriageSuggestion>, String> {
+    let triage: Option<Option<sqlx::types::Json<TriageSuggestion>>> = sqlx::query_scalar("SELECT ai_triage FROM findings WHERE id = ?")
+        .bind(finding_id)
+        .fetch_optional(&crate::db::get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(triage.flatten().map(|t| t.0))
+}
+
+/// Untriaged open findings sharing a rule and matched content, which get
+/// one suggestion between them.
+#[derive(Debug, sqlx::FromRow)]
+struct TriageGroup {
+    rule_id: Option<String>,
+    match_content: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    url: String,
+}
+
+/// Sent with `ai-triage-progress` after each group and once more, with
+/// `done` set, when the batch ends.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TriageProgress {
+    pub processed: usize,
+    pub total: usize,
+    /// Groups answered from an earlier suggestion without calling the model
+    pub reused: usize,
+    pub failed: usize,
+    pub done: bool,
+    pub cancelled: bool,
+    /// Last model error, if any
+    pub error: Option<String>,
+}
+
+async fn triage_group(group: &TriageGroup, pacer: &mut crate::pacing::Pacer) -> Result<(TriageSuggestion, bool), String> {
+    let pool = crate::db::get_db();
+    let cached: Option<sqlx::types::Json<TriageSuggestion>> = sqlx::query_scalar(
+        "SELECT ai_triage FROM findings
+         WHERE rule_id IS ? AND match_content IS ? AND ai_triage IS NOT NULL AND COALESCE(json_extract(ai_triage, '$.fallback'), 0) = 0
+         LIMIT 1",
+    )
+    .bind(&group.rule_id)
+    .bind(&group.match_content)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (suggestion, reused) = match cached {
+        Some(cached) => (cached.0, true),
+        None => {
+            pacer.wait().await;
+            let name = group.name.as_deref().or(group.rule_id.as_deref()).unwrap_or("Finding");
+            let evidence = mask_evidence(group.match_content.as_deref().unwrap_or(""));
+            (get_triage_suggestion(name, group.description.as_deref().unwrap_or(""), &evidence, &group.url).await?, false)
+        }
+    };
+    if suggestion.fallback {
+        return Err("LLM reply had no usable triage JSON; the group is left for a later run".to_string());
+    }
+    sqlx::query(
+        "UPDATE findings SET ai_triage = ?, ai_triaged_at = CURRENT_TIMESTAMP
+         WHERE rule_id IS ? AND match_content IS ? AND ai_triage IS NULL",
+    )
+    .bind(sqlx::types::Json(&suggestion))
+    .bind(&group.rule_id)
+    .bind(&group.match_content)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok((suggestion, reused))
+}
+
+/// Job id of the batch triage; only one runs at a time.
+const TRIAGE_JOB: &str = "ai-triage";
+
+async fn run_batch_triage(app_handle: &tauri::AppHandle, groups: &[TriageGroup], requests_per_minute: Option<u32>, cancel: &std::sync::atomic::AtomicBool) -> TriageProgress {
+    let pacing = crate::pacing::Pacing { requests_per_second: requests_per_minute.unwrap_or(20).max(1) as f64 / 60.0, ..Default::default() };
+    let mut pacer = crate::pacing::Pacer::new(&pacing);
+    let mut progress = TriageProgress { total: groups.len(), ..Default::default() };
+    for group in groups {
+        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            break;
+        }
+        match triage_group(group, &mut pacer).await {
+            Ok((_, reused)) => progress.reused += reused as usize,
+            Err(e) => {
+                progress.failed += 1;
+                progress.error = Some(e);
+            }
+        }
+        progress.processed += 1;
+        let _ = app_handle.emit("ai-triage-progress", progress.clone());
+    }
+    progress
+}
+
+/// Triages every untriaged open finding in the background, at most
+/// `requests_per_minute` model calls (default 20). Findings with the same
+/// rule and matched content share one suggestion. Progress arrives through
+/// `ai-triage-progress`; returns the number of groups to triage.
+#[tauri::command]
+pub async fn start_batch_triage(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, std::sync::Arc<crate::JobState>>,
+    requests_per_minute: Option<u32>,
+) -> Result<usize, String> {
+    ai_endpoint().await?;
+    let already_running = || "A batch triage is already running".to_string();
+    if state.cancel_flags.contains_key(TRIAGE_JOB) {
+        return Err(already_running());
+    }
+    let groups = sqlx::query_as::<_, TriageGroup>(
+        "SELECT f.rule_id, f.match_content, MIN(f.name) AS name, MIN(f.description) AS description, MIN(a.url) AS url
+         FROM findings f JOIN assets a ON a.id = f.asset_id
+         WHERE f.ai_triage IS NULL AND f.is_false_positive = 0 AND a.deleted_at IS NULL
+         GROUP BY f.rule_id, f.match_content",
+    )
+    .fetch_all(&crate::db::get_db())
+    .await
+    .map_err(|e| e.to_string())?;
+    let total = groups.len();
+
+    crate::jobs::spawn_single_job(
+        app_handle,
+        state.inner(),
+        TRIAGE_JOB,
+        "ai-triage-progress",
+        move |app_handle, _, cancel| async move { run_batch_triage(&app_handle, &groups, requests_per_minute, &cancel).await },
+        |_, progress, cancelled| TriageProgress { cancelled, done: true, ..progress },
+    )
+    .map_err(|_| already_running())?;
+    Ok(total)
+}
+
+/// Stops a batch triage after the group in progress.
+#[tauri::command]
+pub fn cancel_batch_triage(state: tauri::State<'_, std::sync::Arc<crate::JobState>>) -> Result<(), String> {
+    crate::jobs::cancel_job(&state, TRIAGE_JOB).map_err(|_| "No batch triage is running".to_string())
+}
+
+/// Embeds `texts` with the `ai_embedding_model`, one vector per text.
+pub(crate) async fn embed(model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let started = std::time::Instant::now();
+    let result = embed_with(model, texts).await;
+    record_usage(AiTask::Embedding, model, started, result.as_ref().map(|r| (r.1, None)).map_err(String::as_str)).await;
+    result.map(|r| r.0)
+}
+
+async fn embed_with(model: &str, texts: &[String]) -> Result<(Vec<Vec<f32>>, Option<i64>), String> {
+    #[derive(Serialize)]
+    struct EmbedRequest<'a> {
+        model: &'a str,
+        input: &'a [String],
+    }
+
+    #[derive(Deserialize)]
+    struct EmbedResponse {
+        embeddings: Vec<Vec<f32>>,
+        #[serde(default)]
+        prompt_eval_count: Option<i64>,
+    }
+
+    let request = EmbedRequest { model, input: texts };
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/embed", ai_endpoint().await?))
+        .json(&request)
+        .timeout(std::time::Duration::from_secs(120))
+        .send()
+        .await
+        .map_err(|e| format!("LLM connection failed: {}. Is Ollama running?", e))?;
+    if !response.status().is_success() {
+        return Err(format!("LLM returned error: {}", response.status()));
+    }
+    let body: EmbedResponse = response.json().await.map_err(|e| format!("Failed to parse embeddings: {}", e))?;
+    if body.embeddings.len() != texts.len() {
+        return Err(format!("Expected {} embeddings, got {}", texts.len(), body.embeddings.len()));
+    }
+    Ok((body.embeddings, body.prompt_eval_count))
+}
+
+/// One message of a multi-turn conversation, as `/api/chat` expects it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatTurn {
+    pub role: String,
+    pub content: String,
+}
+
+/// Sends a conversation to the chat model and returns its reply.
+pub(crate) async fn chat(messages: &[ChatTurn]) -> Result<String, String> {
+    #[derive(Serialize)]
+    struct ChatRequest<'a> {
+        model: &'a str,
+        messages: &'a [ChatTurn],
+        stream: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct ChatResponse {
+        message: ChatTurn,
+        #[serde(default)]
+        prompt_eval_count: Option<i64>,
+        #[serde(default)]
+        eval_count: Option<i64>,
+    }
+
+    let model = model_for(AiTask::Chat).await?;
+    let started = std::time::Instant::now();
+    let result: Result<ChatResponse, String> = async {
+        let request = ChatRequest { model: &model, messages, stream: false };
+        let response = reqwest::Client::new()
+            .post(format!("{}/api/chat", ai_endpoint().await?))
+            .json(&request)
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("LLM connection failed: {}. Is Ollama running?", e))?;
+        if !response.status().is_success() {
+            return Err(format!("LLM returned error: {}", response.status()));
+        }
+        response.json().await.map_err(|e| format!("Failed to parse LLM response: {}", e))
+    }
+    .await;
+    record_usage(AiTask::Chat, &model, started, result.as_ref().map(|r| (r.prompt_eval_count, r.eval_count)).map_err(String::as_str)).await;
+    result.map(|r| r.message.content)
+}
+
+/// Calls, tokens and latency per task and model.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct AiUsageStats {
+    pub task: String,
+    pub model: String,
+    pub calls: i64,
+    pub failures: i64,
+    /// Summed over calls where the server reported them
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: i64,
+    pub last_used: String,
+}
+
+/// Usage per task and model, optionally only for the last `days` days,
+/// busiest first.
+#[tauri::command]
+pub async fn get_ai_usage_stats(days: Option<i64>) -> Result<Vec<AiUsageStats>, String> {
+    sqlx::query_as::<_, AiUsageStats>(
+        "SELECT task, model, COUNT(*) AS calls, SUM(success = 0) AS failures,
+                COALESCE(SUM(prompt_tokens), 0) AS prompt_tokens, COALESCE(SUM(completion_tokens), 0) AS completion_tokens,
+                AVG(latency_ms) AS avg_latency_ms, MAX(latency_ms) AS max_latency_ms, MAX(created_at) AS last_used
+         FROM ai_usage
+         WHERE ?1 IS NULL OR created_at >= datetime('now', '-' || ?1 || ' days')
+         GROUP BY task, model
+         ORDER BY calls DESC",
+    )
+    .bind(days)
+    .fetch_all(&crate::db::get_db())
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn check_llm_availability() -> Result<bool, String> {
+    let Ok(endpoint) = ai_endpoint().await else { return Ok(false) };
     let client = reqwest::Client::new();
     let result = client
-        .get("http://localhost:11434/api/tags")
+        .get(format!("{}/api/tags", endpoint))
         .timeout(std::time::Duration::from_secs(2))
         .send()
         .await;
@@ -116,7 +584,7 @@ pub async fn check_llm_availability() -> Result<bool, String> {
 pub async fn get_available_models() -> Result<Vec<String>, String> {
     let client = reqwest::Client::new();
     let response = client
-        .get("http://localhost:11434/api/tags")
+        .get(format!("{}/api/tags", ai_endpoint().await?))
         .timeout(std::time::Duration::from_secs(5))
         .send()
         .await
@@ -139,3 +607,651 @@ pub async fn get_available_models() -> Result<Vec<String>, String> {
 
     Ok(model_list.models.iter().map(|m| m.name.clone()).collect())
 }
+
+/// Which part of the workspace a report covers; unset fields don't filter.
+#[derive(Deserialize, Debug, Default)]
+pub struct ReportScope {
+    pub folder_id: Option<i64>,
+    /// Only assets whose URL contains this host
+    pub host: Option<String>,
+    /// Finding groups narrated, most severe first (default 10)
+    pub top_findings: Option<usize>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ScopedFinding {
+    rule_id: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    severity: String,
+    match_content: Option<String>,
+    remediation: Option<String>,
+    method: Option<String>,
+    url: String,
+}
+
+/// Open findings of one rule and every endpoint they were seen on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportFinding {
+    pub rule_id: String,
+    pub name: String,
+    pub severity: String,
+    pub description: String,
+    pub remediation: Option<String>,
+    /// Masked and truncated sample of the matched content
+    pub evidence: String,
+    pub endpoints: Vec<String>,
+}
+
+/// Workspace facts the report prompt and Markdown are built from.
+#[derive(Debug)]
+pub struct ReportContext {
+    pub workspace: String,
+    pub assets: i64,
+    /// Open findings per severity, most severe first
+    pub severity_counts: Vec<(String, usize)>,
+    pub findings: Vec<ReportFinding>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ReportNarrative {
+    #[serde(default)]
+    pub executive_summary: String,
+    #[serde(default)]
+    pub findings: Vec<FindingNarrative>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FindingNarrative {
+    pub rule_id: String,
+    pub narrative: String,
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity.to_lowercase().as_str() {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    }
+}
+
+static TOKEN_RUN: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r"[A-Za-z0-9_\-+/=.]{20,}").unwrap());
+
+/// Keeps the first four characters of each token-like run and masks the rest.
+pub(crate) fn mask_tokens(text: &str) -> String {
+    TOKEN_RUN.replace_all(text, |caps: &regex::Captures| format!("{}****", caps[0].chars().take(4).collect::<String>())).into_owned()
+}
+
+/// Truncates evidence and masks token-like runs so secrets caught by the
+/// scanners never reach the model or the report.
+pub fn mask_evidence(evidence: &str) -> String {
+    let short: String = evidence.chars().take(160).collect();
+    let masked = mask_tokens(&short);
+    if evidence.chars().count() > 160 { format!("{}...", masked) } else { masked }
+}
+
+/// Groups findings by rule, most severe and widespread first.
+fn group_findings(rows: Vec<ScopedFinding>) -> Vec<ReportFinding> {
+    let mut groups: Vec<ReportFinding> = Vec::new();
+    for row in rows {
+        let rule_id = row.rule_id.unwrap_or_else(|| "unknown".to_string());
+        let endpoint = format!("{} {}", row.method.unwrap_or_else(|| "GET".to_string()).to_uppercase(), row.url);
+        match groups.iter_mut().find(|g| g.rule_id == rule_id) {
+            Some(group) => {
+                if severity_rank(&row.severity) < severity_rank(&group.severity) {
+                    group.severity = row.severity;
+                }
+                if !group.endpoints.contains(&endpoint) {
+                    group.endpoints.push(endpoint);
+                }
+            }
+            None => groups.push(ReportFinding {
+                name: row.name.unwrap_or_else(|| rule_id.clone()),
+                rule_id,
+                severity: row.severity,
+                description: row.description.unwrap_or_default(),
+                remediation: row.remediation.filter(|r| !r.is_empty()),
+                evidence: mask_evidence(row.match_content.as_deref().unwrap_or("")),
+                endpoints: vec![endpoint],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| severity_rank(&a.severity).cmp(&severity_rank(&b.severity)).then(b.endpoints.len().cmp(&a.endpoints.len())));
+    groups
+}
+
+async fn report_prompt(ctx: &ReportContext) -> Result<String, String> {
+    let counts: Vec<String> = ctx.severity_counts.iter().map(|(s, n)| format!("{}: {}", s, n)).collect();
+    let findings: Vec<String> = ctx
+        .findings
+        .iter()
+        .map(|f| {
+            format!(
+                "- rule_id: {}\n  name: {}\n  severity: {}\n  description: {}\n  evidence: {}\n  endpoints ({}): {}",
+                f.rule_id,
+                f.name,
+                f.severity,
+                f.description,
+                f.evidence,
+                f.endpoints.len(),
+                f.endpoints.iter().take(5).cloned().collect::<Vec<_>>().join(", ")
+            )
+        })
+        .collect();
+    let counts = if counts.is_empty() { "none".to_string() } else { counts.join(", ") };
+    let findings = if findings.is_empty() { "none".to_string() } else { findings.join("\n") };
+    crate::prompts::prompt(
+        "report",
+        &[
+            ("workspace", &ctx.workspace),
+            ("assets", &ctx.assets.to_string()),
+            ("severity_counts", &counts),
+            ("findings", &findings),
+        ],
+    )
+    .await
+}
+
+/// Byte length of the JSON object or array `text` starts with, counting
+/// brackets outside string literals. `None` if it never closes.
+fn balanced_end(text: &str) -> Option<usize> {
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + c.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The first complete JSON object in a completion. A fenced code block is
+/// searched before the surrounding text, and braces in prose (`{id}`) or
+/// inside strings don't confuse the scan.
+pub fn extract_json(response: &str) -> Option<&str> {
+    let fenced = response.split("```").skip(1).step_by(2).map(|block| {
+        // Drop the language tag on the opening fence line
+        match block.split_once('\n') {
+            Some((tag, rest)) if !tag.contains('{') => rest,
+            _ => block,
+        }
+    });
+    fenced.chain(std::iter::once(response)).find_map(|text| {
+        text.match_indices('{').find_map(|(start, _)| {
+            let candidate = &text[start..start + balanced_end(&text[start..])?];
+            serde_json::from_str::<serde_json::Value>(candidate).ok().map(|_| candidate)
+        })
+    })
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(response: &str) -> Result<T, String> {
+    let json = extract_json(response).ok_or("LLM response contained no JSON")?;
+    serde_json::from_str(json).map_err(|e| format!("LLM returned invalid JSON: {}", e))
+}
+
+pub fn render_report(ctx: &ReportContext, narrative: &ReportNarrative, date: &str) -> String {
+    let mut md = format!("# Security Assessment: {}\n\n_Generated {}_\n\n", ctx.workspace, date);
+    md.push_str("## Executive Summary\n\n");
+    md.push_str(if narrative.executive_summary.trim().is_empty() { "No summary was generated." } else { narrative.executive_summary.trim() });
+    md.push_str("\n\n## Overview\n\n| Metric | Value |\n| --- | --- |\n");
+    md.push_str(&format!("| Assets in scope | {} |\n", ctx.assets));
+    for (severity, count) in &ctx.severity_counts {
+        md.push_str(&format!("| {} findings | {} |\n", severity, count));
+    }
+    md.push_str("\n## Findings\n");
+    if ctx.findings.is_empty() {
+        md.push_str("\nNo open findings in scope.\n");
+    }
+    for (i, f) in ctx.findings.iter().enumerate() {
+        md.push_str(&format!("\n### {}. {} ({})\n\n", i + 1, f.name, f.severity));
+        md.push_str(&format!("**Rule:** `{}`\n\n", f.rule_id));
+        match narrative.findings.iter().find(|n| n.rule_id == f.rule_id).map(|n| n.narrative.trim()).filter(|n| !n.is_empty()) {
+            Some(text) => md.push_str(&format!("{}\n\n", text)),
+            None => md.push_str(&format!("{}\n\n", f.description)),
+        }
+        md.push_str("**Affected endpoints:**\n\n");
+        for endpoint in &f.endpoints {
+            md.push_str(&format!("- `{}`\n", endpoint));
+        }
+        if !f.evidence.is_empty() {
+            md.push_str(&format!("\n**Evidence:** `{}`\n", f.evidence.replace('`', "'")));
+        }
+        if let Some(remediation) = &f.remediation {
+            md.push_str(&format!("\n**Remediation:** {}\n", remediation));
+        }
+    }
+    md
+}
+
+async fn report_context(scope: &ReportScope) -> Result<ReportContext, String> {
+    let pool = crate::db::get_db();
+    let host = scope.host.as_deref().map(str::trim).filter(|h| !h.is_empty());
+    let filter = "a.deleted_at IS NULL AND (? IS NULL OR a.folder_id = ?) AND (? IS NULL OR instr(a.url, ?) > 0)";
+
+    let assets: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM assets a WHERE {}", filter))
+        .bind(scope.folder_id)
+        .bind(scope.folder_id)
+        .bind(host)
+        .bind(host)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let rows = sqlx::query_as::<_, ScopedFinding>(&format!(
+        "SELECT f.rule_id, f.name, f.description, COALESCE(f.severity_override, f.severity, 'Info') AS severity,
+                f.match_content, f.remediation, a.method, a.url
+         FROM findings f JOIN assets a ON a.id = f.asset_id
+         WHERE f.is_false_positive = 0 AND {}
+         ORDER BY f.created_at",
+        filter
+    ))
+    .bind(scope.folder_id)
+    .bind(scope.folder_id)
+    .bind(host)
+    .bind(host)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut severity_counts: Vec<(String, usize)> = Vec::new();
+    for row in &rows {
+        match severity_counts.iter_mut().find(|(s, _)| s.eq_ignore_ascii_case(&row.severity)) {
+            Some((_, n)) => *n += 1,
+            None => severity_counts.push((row.severity.clone(), 1)),
+        }
+    }
+    severity_counts.sort_by_key(|(s, _)| severity_rank(s));
+    let mut findings = group_findings(rows);
+    findings.truncate(scope.top_findings.unwrap_or(10).max(1));
+
+    Ok(ReportContext { workspace: crate::db::get_current_workspace(), assets, severity_counts, findings })
+}
+
+/// Summarises the findings in `scope` with the configured model and
+/// returns a Markdown report: executive summary, overview table and a
+/// narrative per finding group. Evidence is masked before it is sent.
+#[tauri::command]
+pub async fn ai_generate_report(scope: Option<ReportScope>) -> Result<String, String> {
+    let ctx = report_context(&scope.unwrap_or_default()).await?;
+    let narrative: ReportNarrative = generate_structured(AiTask::Report, report_prompt(&ctx).await?, 120, parse_json).await?;
+    Ok(render_report(&ctx, &narrative, &chrono::Local::now().format("%Y-%m-%d").to_string()))
+}
+
+/// One request parameter as described to the model.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ParamInfo {
+    /// `path`, `query` or `body`
+    pub location: String,
+    pub name: String,
+    /// `integer`, `number`, `boolean`, `uuid`, `string`, `array`, `object` or `null`
+    pub kind: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PayloadSuggestion {
+    #[serde(default)]
+    pub hypotheses: Vec<String>,
+    #[serde(default)]
+    pub payloads: Vec<String>,
+    /// Set when the payloads were saved as a wordlist
+    #[serde(default)]
+    pub wordlist_id: Option<i64>,
+}
+
+/// Payloads kept from one suggestion.
+const MAX_SUGGESTED_PAYLOADS: usize = 200;
+
+const SENSITIVE_NAMES: [&str; 9] = ["pass", "secret", "token", "key", "auth", "session", "cookie", "sig", "credential"];
+
+pub(crate) fn is_sensitive(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_NAMES.iter().any(|s| name.contains(s))
+}
+
+fn value_kind(value: &str) -> &'static str {
+    if value.parse::<i64>().is_ok() {
+        "integer"
+    } else if value.parse::<f64>().is_ok() {
+        "number"
+    } else if value == "true" || value == "false" {
+        "boolean"
+    } else if uuid::Uuid::parse_str(value).is_ok() {
+        "uuid"
+    } else {
+        "string"
+    }
+}
+
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(s) if uuid::Uuid::parse_str(s).is_ok() => "uuid",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+fn json_params(prefix: &str, value: &serde_json::Value, out: &mut Vec<ParamInfo>) {
+    if let serde_json::Value::Object(map) = value {
+        for (key, child) in map {
+            let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            out.push(ParamInfo { location: "body".to_string(), name: name.clone(), kind: json_kind(child).to_string() });
+            json_params(&name, child, out);
+        }
+    }
+}
+
+/// Path ids, query parameters and body fields of `task`, with their types
+/// guessed from the captured values.
+pub fn request_parameters(task: &crate::fuzzer::FuzzTask) -> Vec<ParamInfo> {
+    let task = crate::fuzzer::render_task(task, usize::MAX, "");
+    let mut params = Vec::new();
+    if let Ok(url) = url::Url::parse(&task.url) {
+        for (i, segment) in url.path_segments().into_iter().flatten().enumerate() {
+            let kind = value_kind(segment);
+            if kind == "integer" || kind == "uuid" {
+                params.push(ParamInfo { location: "path".to_string(), name: format!("segment {}", i + 1), kind: kind.to_string() });
+            }
+        }
+        for (name, value) in url.query_pairs() {
+            params.push(ParamInfo { location: "query".to_string(), name: name.into_owned(), kind: value_kind(&value).to_string() });
+        }
+    }
+    match task.body.as_deref().map(|b| (b, serde_json::from_str::<serde_json::Value>(b))) {
+        Some((_, Ok(json))) => json_params("", &json, &mut params),
+        Some((body, Err(_))) if body.contains('=') => {
+            for (name, value) in url::form_urlencoded::parse(body.as_bytes()) {
+                params.push(ParamInfo { location: "body".to_string(), name: name.into_owned(), kind: value_kind(&value).to_string() });
+            }
+        }
+        _ => {}
+    }
+    params.truncate(50);
+    params
+}
+
+fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if is_sensitive(key) && !child.is_object() && !child.is_array() {
+                    *child = serde_json::Value::String("REDACTED".to_string());
+                } else {
+                    redact_json(child);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// `url` with the values of sensitive query parameters redacted.
+pub fn sanitize_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) if parsed.query().is_some() => {
+            let pairs: Vec<(String, String)> = parsed
+                .query_pairs()
+                .map(|(k, v)| {
+                    let v = if is_sensitive(&k) { "REDACTED".to_string() } else { v.into_owned() };
+                    (k.into_owned(), v)
+                })
+                .collect();
+            parsed.query_pairs_mut().clear().extend_pairs(pairs);
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// Whether a header carries credentials or another sensitive value.
+pub(crate) fn is_sensitive_header(name: &str) -> bool {
+    crate::risk::AUTH_HEADERS.contains(&name.to_lowercase().as_str()) || is_sensitive(name)
+}
+
+/// `body` with sensitive JSON fields redacted and token-like runs masked.
+pub fn sanitize_body(body: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            mask_tokens(&json.to_string())
+        }
+        Err(_) => mask_tokens(body),
+    }
+}
+
+/// `task` with credentials, sensitive parameters and token-like strings
+/// replaced, safe to hand to the model.
+pub fn sanitize_task(task: &crate::fuzzer::FuzzTask) -> crate::fuzzer::FuzzTask {
+    let task = crate::fuzzer::render_task(task, usize::MAX, "");
+    let url = sanitize_url(&task.url);
+    let headers = task
+        .headers
+        .iter()
+        .map(|(k, v)| (k.clone(), if is_sensitive_header(k) { "REDACTED".to_string() } else { mask_tokens(v) }))
+        .collect();
+    let body = task.body.as_deref().map(|body| sanitize_body(body).chars().take(2000).collect());
+    crate::fuzzer::FuzzTask { url, headers, body, ..task }
+}
+
+async fn payload_prompt(task: &crate::fuzzer::FuzzTask, params: &[ParamInfo], attack_type: Option<&str>) -> Result<String, String> {
+    let mut headers: Vec<String> = task.headers.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+    headers.sort();
+    let params: Vec<String> = params.iter().map(|p| format!("- {} `{}` ({})", p.location, p.name, p.kind)).collect();
+    let headers = if headers.is_empty() { "none".to_string() } else { headers.join("\n") };
+    let params = if params.is_empty() { "none found".to_string() } else { params.join("\n") };
+    crate::prompts::prompt(
+        "payloads",
+        &[
+            ("method", &task.method.to_uppercase()),
+            ("url", &task.url),
+            ("headers", &headers),
+            ("body", task.body.as_deref().filter(|b| !b.is_empty()).unwrap_or("none")),
+            ("parameters", &params),
+            ("focus", attack_type.unwrap_or("any vulnerability class")),
+        ],
+    )
+    .await
+}
+
+/// Asks the model for attack hypotheses and payloads tailored to `task`,
+/// which is sanitized first. With `save_as` the payloads are also stored
+/// as a wordlist, ready for `run_active_fuzz`.
+#[tauri::command]
+pub async fn ai_suggest_payloads(task: crate::fuzzer::FuzzTask, attack_type: Option<String>, save_as: Option<String>) -> Result<PayloadSuggestion, String> {
+    let params = request_parameters(&task);
+    let prompt = payload_prompt(&sanitize_task(&task), &params, attack_type.as_deref()).await?;
+    let mut suggestion: PayloadSuggestion = generate_structured(AiTask::Payloads, prompt, 60, parse_json).await?;
+    let mut seen = std::collections::HashSet::new();
+    suggestion.payloads.retain(|p| !p.is_empty() && seen.insert(p.clone()));
+    suggestion.payloads.truncate(MAX_SUGGESTED_PAYLOADS);
+    if let Some(name) = save_as.filter(|n| !n.trim().is_empty()) {
+        let category = attack_type.as_deref().unwrap_or("ai");
+        suggestion.wordlist_id = Some(crate::fuzzer::insert_wordlist(&name, category, &suggestion.payloads, Some("ai")).await?);
+    }
+    Ok(suggestion)
+}
+
+/// Longest operation JSON sent to the model; huge schemas are cut off.
+const MAX_OPERATION_CHARS: usize = 6000;
+
+async fn openapi_prompt(path: &str, method: &str, op: &serde_json::Value, fields: &[String]) -> Result<String, String> {
+    let op: String = serde_json::to_string_pretty(op).unwrap_or_default().chars().take(MAX_OPERATION_CHARS).collect();
+    let fields = if fields.is_empty() { "none".to_string() } else { fields.join(", ") };
+    crate::prompts::prompt(
+        "openapi",
+        &[("method", &method.to_uppercase()), ("path", path), ("operation", &op), ("fields", &fields)],
+    )
+    .await
+}
+
+/// Generates the traffic-derived OpenAPI document like `export_openapi`,
+/// then asks the model to document each operation in turn. Operations the
+/// model fails on are left as generated. Emits `ai-openapi-progress` with
+/// `(done, total)` after each one.
+#[tauri::command]
+pub async fn ai_describe_openapi(app_handle: tauri::AppHandle, host_filter: Option<String>) -> Result<String, String> {
+    let mut spec = crate::openapi::inventory_spec(host_filter).await?;
+    let total = spec["paths"].as_object().map(|p| p.values().filter_map(|ops| ops.as_object()).map(|ops| ops.len()).sum()).unwrap_or(0);
+    let (mut done, mut documented) = (0, 0);
+    let mut first_error = None;
+    for (path, ops) in spec["paths"].as_object_mut().into_iter().flatten() {
+        for (method, op) in ops.as_object_mut().into_iter().flatten() {
+            let prompt = openapi_prompt(path, method, op, &crate::openapi::operation_fields(op)).await?;
+            match generate_structured(AiTask::Openapi, prompt, 60, parse_json::<crate::openapi::OperationDocs>).await.map_err(String::from) {
+                Ok(docs) => {
+                    crate::openapi::apply_docs(op, &docs);
+                    documented += 1;
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+            done += 1;
+            let _ = app_handle.emit("ai-openapi-progress", (done, total));
+        }
+    }
+    // Nothing documented at all usually means the model is unreachable
+    if let (Some(e), 0) = (first_error, documented) {
+        return Err(e);
+    }
+    serde_json::to_string_pretty(&spec).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(rule: &str, severity: &str, url: &str) -> ScopedFinding {
+        ScopedFinding {
+            rule_id: Some(rule.to_string()),
+            name: Some(format!("{} name", rule)),
+            description: Some("desc".to_string()),
+            severity: severity.to_string(),
+            match_content: Some("sk_live_0123456789abcdefghijkl".to_string()),
+            remediation: None,
+            method: Some("get".to_string()),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_group_findings() {
+        let groups = group_findings(vec![
+            row("CONF-CORS-ALL", "Low", "https://a/1"),
+            row("LEAK-KEY", "Medium", "https://a/1"),
+            row("LEAK-KEY", "High", "https://a/2"),
+            row("LEAK-KEY", "High", "https://a/2"),
+        ]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].rule_id, "LEAK-KEY");
+        assert_eq!(groups[0].severity, "High");
+        assert_eq!(groups[0].endpoints, vec!["GET https://a/1", "GET https://a/2"]);
+        assert_eq!(groups[0].evidence, "sk_l****");
+        assert_eq!(mask_evidence("token=abc"), "token=abc");
+    }
+
+    #[test]
+    fn test_render_report() {
+        let ctx = ReportContext {
+            workspace: "Acme".to_string(),
+            assets: 3,
+            severity_counts: vec![("High".to_string(), 2)],
+            findings: group_findings(vec![row("LEAK-KEY", "High", "https://a/1"), row("CONF-CORS-ALL", "Low", "https://a/1")]),
+        };
+        let narrative: ReportNarrative = parse_json("```json\n{\"executive_summary\": \"Keys leak.\", \"findings\": [{\"rule_id\": \"LEAK-KEY\", \"narrative\": \"Attackers reuse the key.\"}]}\n```").unwrap();
+        let md = render_report(&ctx, &narrative, "2026-01-01");
+        assert!(md.starts_with("# Security Assessment: Acme\n"));
+        assert!(md.contains("## Executive Summary\n\nKeys leak."));
+        assert!(md.contains("### 1. LEAK-KEY name (High)\n\n**Rule:** `LEAK-KEY`\n\nAttackers reuse the key."));
+        // Groups the model skipped fall back to the rule description
+        assert!(md.contains("### 2. CONF-CORS-ALL name (Low)\n\n**Rule:** `CONF-CORS-ALL`\n\ndesc"));
+        assert!(parse_json::<ReportNarrative>("no json here").is_err());
+    }
+
+    #[test]
+    fn test_extract_json() {
+        assert_eq!(extract_json("Sure! Use {id} here:\n```json\n{\"a\": \"}{\"}\n```\nDone."), Some("{\"a\": \"}{\"}"));
+        assert_eq!(extract_json("The {user} object is {\"b\": [1, {\"c\": 2}]} and {\"d\": 3}"), Some("{\"b\": [1, {\"c\": 2}]}"));
+        assert_eq!(extract_json("{\"unterminated\": 1"), None);
+        assert_eq!(extract_json("no json"), None);
+    }
+
+    #[test]
+    fn test_triage_from_value() {
+        let partial = triage_from_response(
+            "Here you go: {\"severity_assessment\": \"High\", \"owasp_category\": [\"API1\", \"API3\"], \"similar_cves\": \"CVE-2021-1, CVE-2022-2\"}",
+        )
+        .unwrap();
+        assert_eq!(partial.severity_assessment, "High");
+        assert_eq!(partial.owasp_category, "API1; API3");
+        assert_eq!(partial.similar_cves, vec!["CVE-2021-1", "CVE-2022-2"]);
+        assert_eq!(partial.false_positive_likelihood, NOT_PROVIDED);
+        assert_eq!(partial.missing_fields, vec!["false_positive_likelihood", "remediation_hint"]);
+        assert!(!partial.fallback);
+        assert!(triage_from_response("{\"unrelated\": true}").is_err());
+
+        let fallback = triage_fallback("  Rotate the key.  ").unwrap();
+        assert_eq!(fallback.remediation_hint, "Rotate the key.");
+        assert_eq!(fallback.missing_fields.len(), 4);
+        assert!(fallback.fallback);
+        assert!(triage_fallback(" ").is_err());
+    }
+
+    #[test]
+    fn test_request_parameters_and_sanitize() {
+        let task = crate::fuzzer::FuzzTask {
+            url: "https://api.example.com/users/42/orders?limit=10&api_key=sk_live_0123456789abcdef".to_string(),
+            method: "post".to_string(),
+            headers: std::collections::HashMap::from([
+                ("Authorization".to_string(), "Bearer abc".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ]),
+            body: Some(r#"{"email": "a@b.c", "password": "hunter2", "items": [{"sku": "§X1§"}], "gift": false}"#.to_string()),
+            pacing: Default::default(),
+        };
+        let mut names: Vec<String> = request_parameters(&task).iter().map(|p| format!("{} {} {}", p.location, p.name, p.kind)).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "body email string",
+                "body gift boolean",
+                "body items array",
+                "body password string",
+                "path segment 2 integer",
+                "query api_key string",
+                "query limit integer",
+            ]
+        );
+
+        let clean = sanitize_task(&task);
+        assert_eq!(clean.url, "https://api.example.com/users/42/orders?limit=10&api_key=REDACTED");
+        assert_eq!(clean.headers["Authorization"], "REDACTED");
+        assert_eq!(clean.headers["Accept"], "application/json");
+        let body: serde_json::Value = serde_json::from_str(clean.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["password"], "REDACTED");
+        assert_eq!(body["items"][0]["sku"], "X1");
+    }
+}
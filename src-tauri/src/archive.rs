@@ -0,0 +1,338 @@
+use crate::assets::{asset_identity, BatchImportResult, CreateAssetRequest, IdentityQueryMode};
+use crate::db::get_db;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const WAYBACK_CDX: &str = "https://web.archive.org/cdx/search/cdx";
+const COMMON_CRAWL_COLLECTIONS: &str = "https://index.commoncrawl.org/collinfo.json";
+
+const DEFAULT_LIMIT: usize = 5000;
+const MAX_LIMIT: usize = 50_000;
+
+/// Archive indexes can take a while to answer wide domain queries.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Tag put on every asset imported from an archive.
+const ARCHIVE_TAG: &str = "archive";
+
+/// Path segments and suffixes that suggest an API rather than a page.
+const API_MARKERS: &[&str] = &[
+    "/api", "/rest/", "/graphql", "/gql", "/rpc", "/soap", "/ws/", "/oauth", "/auth/", "/token", "/swagger",
+    "/openapi", "/api-docs", "/actuator", "/internal/", "/admin/api", ".json", ".xml", ".wsdl", ".asmx", ".svc",
+];
+
+/// Static files never worth importing, even under an API-looking path.
+const STATIC_EXTENSIONS: &[&str] = &[
+    "css", "js", "map", "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "woff", "woff2", "ttf", "eot", "otf",
+    "mp4", "mp3", "webm", "pdf", "zip", "gz", "tar", "txt", "md",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveSource {
+    Wayback,
+    CommonCrawl,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ArchiveRequest {
+    pub domain: String,
+    /// Both sources when empty
+    #[serde(default)]
+    pub sources: Vec<ArchiveSource>,
+    /// Also harvest subdomains of `domain`
+    #[serde(default)]
+    pub include_subdomains: bool,
+    /// Captures fetched per source
+    pub limit: Option<usize>,
+}
+
+/// A capture as listed by an archive index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capture {
+    pub url: String,
+    pub status: Option<i64>,
+    pub mime: Option<String>,
+    /// `YYYYMMDDhhmmss`
+    pub timestamp: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveCandidate {
+    pub url: String,
+    pub sources: Vec<ArchiveSource>,
+    /// Status code when last archived
+    pub status: Option<i64>,
+    pub mime: Option<String>,
+    pub last_archived: Option<String>,
+    pub in_inventory: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ArchiveHarvest {
+    pub candidates: Vec<ArchiveCandidate>,
+    /// Captures returned by the indexes before filtering
+    pub captures: usize,
+    /// Sources that failed, with the reason; the others are still used
+    pub errors: Vec<String>,
+}
+
+/// Rows of a Wayback CDX `output=json` response requested with
+/// `fl=original,statuscode,mimetype,timestamp`. The first row is the header.
+pub fn parse_wayback(body: &str) -> Result<Vec<Capture>, String> {
+    if body.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let rows: Vec<Vec<String>> = serde_json::from_str(body).map_err(|e| format!("Unexpected Wayback response: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .skip(1)
+        .filter_map(|row| {
+            let mut fields = row.into_iter();
+            let url = fields.next()?;
+            let status = fields.next().and_then(|s| s.parse().ok());
+            let mime = fields.next().filter(|m| !m.is_empty() && m != "-");
+            Some(Capture { url, status, mime, timestamp: fields.next() })
+        })
+        .collect())
+}
+
+/// Lines of a Common Crawl index `output=json` response.
+pub fn parse_common_crawl(body: &str) -> Vec<Capture> {
+    #[derive(Deserialize)]
+    struct Line {
+        url: String,
+        status: Option<String>,
+        mime: Option<String>,
+        timestamp: Option<String>,
+    }
+    body.lines()
+        .filter_map(|line| serde_json::from_str::<Line>(line).ok())
+        .map(|l| Capture { url: l.url, status: l.status.and_then(|s| s.parse().ok()), mime: l.mime, timestamp: l.timestamp })
+        .collect()
+}
+
+/// The capture's URL without fragment when it is on `domain` (or a
+/// subdomain, if allowed) and its path looks like an API endpoint.
+pub fn api_candidate(raw: &str, domain: &str, include_subdomains: bool) -> Option<String> {
+    let mut url = url::Url::parse(raw.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    let in_scope = host == domain || (include_subdomains && host.ends_with(&format!(".{}", domain)));
+    if !in_scope {
+        return None;
+    }
+    let path = url.path().to_lowercase();
+    let extension = path.rsplit('/').next().and_then(|name| name.rsplit_once('.')).map(|(_, ext)| ext);
+    if extension.is_some_and(|ext| STATIC_EXTENSIONS.contains(&ext)) {
+        return None;
+    }
+    let versioned = path.split('/').any(|seg| seg.len() > 1 && seg.starts_with('v') && seg[1..].chars().all(|c| c.is_ascii_digit()));
+    if !versioned && !API_MARKERS.iter().any(|m| path.contains(m)) {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url.to_string())
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("apisec/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn wayback(client: &reqwest::Client, domain: &str, include_subdomains: bool, limit: usize) -> Result<Vec<Capture>, String> {
+    let limit = limit.to_string();
+    let response = client
+        .get(WAYBACK_CDX)
+        .query(&[
+            ("url", domain),
+            ("matchType", if include_subdomains { "domain" } else { "host" }),
+            ("output", "json"),
+            ("fl", "original,statuscode,mimetype,timestamp"),
+            ("collapse", "urlkey"),
+            ("limit", limit.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Wayback: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Wayback: HTTP {}", response.status()));
+    }
+    parse_wayback(&response.text().await.map_err(|e| format!("Wayback: {}", e))?)
+}
+
+async fn common_crawl(client: &reqwest::Client, domain: &str, include_subdomains: bool, limit: usize) -> Result<Vec<Capture>, String> {
+    #[derive(Deserialize)]
+    struct Collection {
+        #[serde(rename = "cdx-api")]
+        cdx_api: String,
+    }
+    // Collections are listed newest first; only the latest crawl is queried
+    let collections: Vec<Collection> = client
+        .get(COMMON_CRAWL_COLLECTIONS)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Common Crawl: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Common Crawl: {}", e))?;
+    let index = collections.first().ok_or("Common Crawl: no collections listed")?;
+    let pattern = if include_subdomains { format!("*.{}", domain) } else { format!("{}/*", domain) };
+    let limit = limit.to_string();
+    let response = client
+        .get(&index.cdx_api)
+        .query(&[("url", pattern.as_str()), ("output", "json"), ("fl", "url,status,mime,timestamp"), ("limit", limit.as_str())])
+        .send()
+        .await
+        .map_err(|e| format!("Common Crawl: {}", e))?;
+    // The index answers 404 when it has no captures for the pattern
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Common Crawl: HTTP {}", response.status()));
+    }
+    Ok(parse_common_crawl(&response.text().await.map_err(|e| format!("Common Crawl: {}", e))?))
+}
+
+/// Merges captures into API-looking candidates, one per asset identity,
+/// keeping the most recent capture's status and type.
+pub fn candidates(
+    captures: Vec<(ArchiveSource, Capture)>,
+    domain: &str,
+    include_subdomains: bool,
+    mode: IdentityQueryMode,
+) -> Vec<ArchiveCandidate> {
+    let mut by_identity: HashMap<String, ArchiveCandidate> = HashMap::new();
+    for (source, capture) in captures {
+        let Some(url) = api_candidate(&capture.url, domain, include_subdomains) else {
+            continue;
+        };
+        let candidate = by_identity.entry(asset_identity("GET", &url, mode)).or_insert_with(|| ArchiveCandidate {
+            url,
+            sources: Vec::new(),
+            status: None,
+            mime: None,
+            last_archived: None,
+            in_inventory: false,
+        });
+        if !candidate.sources.contains(&source) {
+            candidate.sources.push(source);
+        }
+        if capture.timestamp > candidate.last_archived {
+            candidate.last_archived = capture.timestamp;
+            candidate.status = capture.status;
+            candidate.mime = capture.mime;
+        }
+    }
+    let mut candidates: Vec<ArchiveCandidate> = by_identity.into_values().collect();
+    candidates.sort_by(|a, b| a.url.cmp(&b.url));
+    candidates
+}
+
+/// Lists historical URLs under a domain from the Wayback Machine and Common
+/// Crawl, keeping those whose path looks like an API. Nothing is imported;
+/// pass the chosen URLs to `import_archive_urls`.
+#[tauri::command]
+pub async fn harvest_archive_urls(request: ArchiveRequest) -> Result<ArchiveHarvest, String> {
+    let domain = crate::recon::normalize_domain(&request.domain)?;
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let sources = if request.sources.is_empty() { vec![ArchiveSource::Wayback, ArchiveSource::CommonCrawl] } else { request.sources };
+    let client = client()?;
+
+    let mut captures = Vec::new();
+    let mut errors = Vec::new();
+    for source in sources {
+        let fetched = match source {
+            ArchiveSource::Wayback => wayback(&client, &domain, request.include_subdomains, limit).await,
+            ArchiveSource::CommonCrawl => common_crawl(&client, &domain, request.include_subdomains, limit).await,
+        };
+        match fetched {
+            Ok(list) => captures.extend(list.into_iter().map(|c| (source, c))),
+            Err(e) => errors.push(e),
+        }
+    }
+    if captures.is_empty() && !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+
+    let pool = get_db();
+    let mode = crate::assets::identity_query_mode(&pool).await?;
+    let existing = crate::assets::existing_asset_identities().await?;
+    let total = captures.len();
+    let mut candidates = candidates(captures, &domain, request.include_subdomains, mode);
+    for c in &mut candidates {
+        c.in_inventory = existing.contains(&asset_identity("GET", &c.url, mode));
+    }
+    Ok(ArchiveHarvest { candidates, captures: total, errors })
+}
+
+/// Adds archived URLs to the inventory as GET assets tagged `archive`.
+/// URLs already in the inventory are skipped.
+#[tauri::command]
+pub async fn import_archive_urls(urls: Vec<String>) -> Result<BatchImportResult, String> {
+    let pool = get_db();
+    let mode = crate::assets::identity_query_mode(&pool).await?;
+    let mut existing = crate::assets::existing_asset_identities().await?;
+    let (mut added, mut skipped) = (0, 0);
+    for url in urls {
+        if !existing.insert(asset_identity("GET", &url, mode)) {
+            skipped += 1;
+            continue;
+        }
+        let asset_id = crate::assets::add_asset(CreateAssetRequest {
+            url,
+            source: "Archive".to_string(),
+            method: Some("GET".to_string()),
+            status_code: None,
+            req_headers: HashMap::new(),
+            res_headers: HashMap::new(),
+            req_body: None,
+            res_body: None,
+            findings: Vec::new(),
+        })
+        .await?;
+        crate::db::add_asset_tag(asset_id, ARCHIVE_TAG.to_string()).await?;
+        added += 1;
+    }
+    Ok(BatchImportResult { added, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_candidate() {
+        assert_eq!(api_candidate("http://example.com/api/users?id=1#top", "example.com", false).as_deref(), Some("http://example.com/api/users?id=1"));
+        assert!(api_candidate("https://example.com/V2/orders", "example.com", false).is_some());
+        assert!(api_candidate("https://example.com/about", "example.com", false).is_none());
+        assert!(api_candidate("https://example.com/api/app.js", "example.com", false).is_none());
+        assert!(api_candidate("https://dev.example.com/api/x", "example.com", false).is_none());
+        assert!(api_candidate("https://dev.example.com/api/x", "example.com", true).is_some());
+        assert!(api_candidate("https://notexample.com/api/x", "example.com", true).is_none());
+
+        let wayback = r#"[["original","statuscode","mimetype","timestamp"],
+            ["http://example.com/api/v1/users","200","application/json","20190101000000"],
+            ["http://example.com/api/v1/users/","404","-","20210101000000"]]"#;
+        let mut captures: Vec<_> = parse_wayback(wayback).unwrap().into_iter().map(|c| (ArchiveSource::Wayback, c)).collect();
+        let cc = "{\"url\": \"https://example.com/graphql\", \"status\": \"200\", \"mime\": \"application/json\", \"timestamp\": \"20240101000000\"}\nnot json";
+        captures.extend(parse_common_crawl(cc).into_iter().map(|c| (ArchiveSource::CommonCrawl, c)));
+        assert_eq!(captures.len(), 3);
+
+        let found = candidates(captures, "example.com", false, IdentityQueryMode::Parameterize);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].url, "http://example.com/api/v1/users");
+        assert_eq!(found[0].status, Some(404));
+        assert_eq!(found[0].mime, None);
+        assert_eq!(found[1].sources, vec![ArchiveSource::CommonCrawl]);
+        assert!(parse_wayback("").unwrap().is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use crate::db::get_db;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
-use sqlx::{Row, FromRow};
+use tauri::Emitter;
+use sqlx::{Row, FromRow, Pool, Sqlite};
 use crate::analysis::Finding;
 use crate::import_engine::ImportEntry;
 
@@ -16,7 +20,17 @@ pub struct Asset {
     pub last_seen: String, 
     pub req_body: Option<String>,
     pub res_body: Option<String>,
+    #[sqlx(json(nullable))]
+    pub req_headers: Option<HashMap<String, String>>,
+    #[sqlx(json(nullable))]
+    pub res_headers: Option<HashMap<String, String>>,
     pub notes: Option<String>,
+    /// URL with identifier segments collapsed, e.g. `/users/{id}`
+    #[sqlx(default)]
+    pub path_template: Option<String>,
+    /// 0-100, see `risk::risk_score`
+    #[sqlx(default)]
+    pub risk_score: i64,
     pub findings_count: Option<i64>,
 }
 
@@ -26,11 +40,194 @@ pub struct CreateAssetRequest {
     pub source: String,
     pub method: Option<String>,
     pub status_code: Option<i64>,
+    #[serde(default)]
+    pub req_headers: HashMap<String, String>,
+    #[serde(default)]
+    pub res_headers: HashMap<String, String>,
     pub req_body: Option<String>,
     pub res_body: Option<String>,
     pub findings: Vec<Finding>,
 }
 
+/// Analyst edits for `update_asset`. Omitted fields stay as they are; for
+/// the nullable ones an explicit `null` clears the value.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AssetUpdate {
+    #[serde(default, deserialize_with = "present")]
+    pub notes: Option<Option<String>>,
+    pub method: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub folder_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    pub status_code: Option<Option<i64>>,
+}
+
+/// Distinguishes a field sent as `null` from one left out.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Header maps are stored as JSON objects, or NULL when nothing was captured.
+pub(crate) fn headers_json(headers: &HashMap<String, String>) -> Option<sqlx::types::Json<&HashMap<String, String>>> {
+    (!headers.is_empty()).then_some(sqlx::types::Json(headers))
+}
+
+/// Captured headers about the original connection or body, which a
+/// fresh request must not copy.
+const TRANSPORT_HEADERS: &[&str] = &["host", "content-length", "transfer-encoding", "connection"];
+
+/// The stored `req_headers` of an asset as a map.
+pub(crate) fn captured_headers(stored: Option<String>) -> HashMap<String, String> {
+    stored.and_then(|h| serde_json::from_str(&h).ok()).unwrap_or_default()
+}
+
+/// The captured headers to send again on a new request to the asset.
+/// Content-Type is only kept when the captured body goes along.
+pub(crate) fn replay_headers(captured: &HashMap<String, String>, with_body: bool) -> HashMap<String, String> {
+    captured
+        .iter()
+        .filter(|(k, _)| {
+            let lower = k.to_lowercase();
+            !TRANSPORT_HEADERS.contains(&lower.as_str()) && (with_body || lower != "content-type")
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// How the query string contributes to an asset's identity.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentityQueryMode {
+    /// Parameter names count, values don't: `?id=1` and `?id=2` are one asset
+    #[default]
+    Parameterize,
+    /// The query string is ignored entirely
+    Strip,
+    /// Every distinct query string is its own asset
+    Keep,
+}
+
+/// Key assets are deduplicated on: the upper-cased method plus the URL
+/// without fragment or trailing slash, with its query reduced per `mode`.
+/// Scheme and host case and default ports are normalized by the URL parser.
+pub fn asset_identity(method: &str, url: &str, mode: IdentityQueryMode) -> String {
+    let method = match method.trim() {
+        "" => "GET".to_string(),
+        m => m.to_uppercase(),
+    };
+    let normalized = match url::Url::parse(url.trim()) {
+        Ok(mut u) => {
+            let mut pairs: Vec<(String, String)> = u.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+            let query = match mode {
+                IdentityQueryMode::Strip => None,
+                IdentityQueryMode::Parameterize => {
+                    let mut keys: Vec<String> = pairs.into_iter().map(|(k, _)| k).collect();
+                    keys.sort();
+                    keys.dedup();
+                    (!keys.is_empty()).then(|| {
+                        url::form_urlencoded::Serializer::new(String::new())
+                            .extend_pairs(keys.iter().map(|k| (k, "")))
+                            .finish()
+                    })
+                }
+                IdentityQueryMode::Keep => {
+                    pairs.sort();
+                    (!pairs.is_empty()).then(|| url::form_urlencoded::Serializer::new(String::new()).extend_pairs(pairs).finish())
+                }
+            };
+            u.set_fragment(None);
+            u.set_query(None);
+            let mut normalized = u.to_string();
+            if u.path() != "/" && normalized.ends_with('/') {
+                normalized.pop();
+            }
+            if let Some(q) = query {
+                normalized.push('?');
+                normalized.push_str(&q);
+            }
+            normalized
+        }
+        Err(_) => url.trim().to_string(),
+    };
+    format!("{} {}", method, normalized)
+}
+
+pub async fn identity_query_mode(pool: &Pool<Sqlite>) -> Result<IdentityQueryMode, String> {
+    crate::settings::read(pool, "asset_identity_query").await
+}
+
+/// Recomputes every asset's identity and merges rows that now collide. The
+/// most recently seen row survives; the others' findings, tags and history
+/// move onto it and their last response is kept as a history entry.
+/// Returns the number of rows merged away.
+pub async fn reindex_asset_identities(pool: &Pool<Sqlite>, mode: IdentityQueryMode) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DROP INDEX IF EXISTS idx_assets_identity").execute(&mut *tx).await?;
+
+    let rows: Vec<(i64, Option<String>, String)> = sqlx::query_as("SELECT id, method, url FROM assets ORDER BY last_seen DESC, id DESC")
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut survivors: HashMap<String, i64> = HashMap::new();
+    let mut merged = 0;
+    for (id, method, url) in rows {
+        let identity = asset_identity(method.as_deref().unwrap_or("GET"), &url, mode);
+        let Some(&keep) = survivors.get(&identity) else {
+            sqlx::query("UPDATE assets SET identity = ? WHERE id = ?")
+                .bind(&identity)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            survivors.insert(identity, id);
+            continue;
+        };
+
+        sqlx::query("INSERT INTO asset_history (asset_id, status_code, res_body, req_headers, res_headers, timestamp) SELECT ?, status_code, res_body, req_headers, res_headers, last_seen FROM assets WHERE id = ? AND res_body IS NOT NULL")
+            .bind(keep)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        for table in ["findings", "asset_history"] {
+            sqlx::query(&format!("UPDATE {} SET asset_id = ? WHERE asset_id = ?", table))
+                .bind(keep)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        // Tag links the survivor already has are left to cascade away
+        sqlx::query("UPDATE OR IGNORE asset_tags SET asset_id = ? WHERE asset_id = ?")
+            .bind(keep)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM assets WHERE id = ?").bind(id).execute(&mut *tx).await?;
+        merged += 1;
+    }
+
+    sqlx::query("CREATE UNIQUE INDEX idx_assets_identity ON assets(identity)").execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(merged)
+}
+
+#[tauri::command]
+pub async fn get_asset_identity_mode() -> Result<IdentityQueryMode, String> {
+    identity_query_mode(&get_db()).await
+}
+
+/// Changes how query strings affect asset identity and re-deduplicates the
+/// inventory under the new rule. Returns how many assets were merged.
+#[tauri::command]
+pub async fn set_asset_identity_mode(mode: IdentityQueryMode) -> Result<u64, String> {
+    let pool = get_db();
+    let value = serde_json::to_value(mode).map_err(|e| e.to_string())?;
+    crate::settings::write(&pool, "asset_identity_query", value).await?;
+    reindex_asset_identities(&pool, mode).await.map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn add_asset(mut asset: CreateAssetRequest) -> Result<i64, String> {
     let pool = get_db();
@@ -41,20 +238,26 @@ pub async fn add_asset(mut asset: CreateAssetRequest) -> Result<i64, String> {
         let drift_findings = crate::drift::detect_drift(
             &asset.url, 
             asset.method.as_deref().unwrap_or("GET"),
+            asset.status_code,
+            &asset.req_headers,
             asset.res_body.as_deref(),
             specs
         );
         asset.findings.extend(drift_findings);
     }
     
+    let mode = identity_query_mode(&pool).await?;
+    let identity = asset_identity(asset.method.as_deref().unwrap_or("GET"), &asset.url, mode);
+
     // Check if exists
-    let existing_id: Option<i64> = sqlx::query("SELECT id FROM assets WHERE url = ?")
-        .bind(&asset.url)
+    let existing_id: Option<i64> = sqlx::query("SELECT id FROM assets WHERE identity = ?")
+        .bind(&identity)
         .fetch_optional(&pool)
         .await
         .map_err(|e| e.to_string())?
         .map(|row| row.get(0));
 
+    // Capturing a trashed asset again brings it back out of the trash
     let asset_id = if let Some(id) = existing_id {
         // Check if content changed
         let existing_res: (Option<i64>, Option<String>) = sqlx::query_as("SELECT status_code, res_body FROM assets WHERE id = ?")
@@ -68,24 +271,24 @@ pub async fn add_asset(mut asset: CreateAssetRequest) -> Result<i64, String> {
         if changed {
             // Save current to history before updating (if not empty)
             if existing_res.1.is_some() {
-                let _ = sqlx::query("INSERT INTO asset_history (asset_id, status_code, res_body) VALUES (?, ?, ?)")
+                let _ = sqlx::query("INSERT INTO asset_history (asset_id, status_code, res_body, req_headers, res_headers) SELECT id, status_code, res_body, req_headers, res_headers FROM assets WHERE id = ?")
                     .bind(id)
-                    .bind(existing_res.0)
-                    .bind(existing_res.1)
                     .execute(&pool)
                     .await;
             }
 
-            // Update asset
-            let _ = sqlx::query("UPDATE assets SET status_code = ?, res_body = ?, last_seen = CURRENT_TIMESTAMP WHERE id = ?")
+            // Update asset; headers are only replaced when this capture has some
+            let _ = sqlx::query("UPDATE assets SET status_code = ?, res_body = ?, req_headers = COALESCE(?, req_headers), res_headers = COALESCE(?, res_headers), last_seen = CURRENT_TIMESTAMP, deleted_at = NULL WHERE id = ?")
                 .bind(asset.status_code)
                 .bind(&asset.res_body)
+                .bind(headers_json(&asset.req_headers))
+                .bind(headers_json(&asset.res_headers))
                 .bind(id)
                 .execute(&pool)
                 .await
                 .map_err(|e| e.to_string())?;
         } else {
-             let _ = sqlx::query("UPDATE assets SET last_seen = CURRENT_TIMESTAMP WHERE id = ?")
+             let _ = sqlx::query("UPDATE assets SET last_seen = CURRENT_TIMESTAMP, deleted_at = NULL WHERE id = ?")
                 .bind(id)
                 .execute(&pool)
                 .await
@@ -93,23 +296,48 @@ pub async fn add_asset(mut asset: CreateAssetRequest) -> Result<i64, String> {
         }
         id
     } else {
-        // Insert new
-        let res = sqlx::query("INSERT INTO assets (url, method, source, status_code, req_body, res_body) VALUES (?, ?, ?, ?, ?, ?)")
+        // Insert new; a concurrent capture of the same asset may have won the race
+        let (id,): (i64,) = sqlx::query_as("INSERT INTO assets (url, identity, path_template, method, source, status_code, req_body, res_body, req_headers, res_headers, first_seen) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(identity) DO UPDATE SET last_seen = CURRENT_TIMESTAMP, deleted_at = NULL RETURNING id")
             .bind(&asset.url)
+            .bind(&identity)
+            .bind(crate::templates::templater(&pool).await?.template(&asset.url))
             .bind(&asset.method)
             .bind(&asset.source)
             .bind(asset.status_code)
             .bind(&asset.req_body)
             .bind(&asset.res_body)
-            .execute(&pool)
+            .bind(headers_json(&asset.req_headers))
+            .bind(headers_json(&asset.res_headers))
+            .fetch_one(&pool)
             .await
             .map_err(|e| e.to_string())?;
-        res.last_insert_rowid()
+        id
     };
 
-    // Insert Findings
+    // Insert Findings. Drift findings are keyed on (asset, rule, field path):
+    // baselined ones are dropped and repeats update the existing row.
+    let baseline = crate::drift::baseline_keys(&pool, asset_id).await?;
     for f in asset.findings {
-        let _ = sqlx::query("INSERT INTO findings (asset_id, rule_id, name, severity, description, match_content, notes, is_false_positive, severity_override) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)")
+        if crate::drift::is_drift_rule(&f.rule_id) {
+            if baseline.contains(&(f.rule_id.clone(), f.match_content.clone())) {
+                continue;
+            }
+            let res = sqlx::query("UPDATE findings SET occurrences = occurrences + 1, last_seen = CURRENT_TIMESTAMP, name = ?, description = ?, severity = ? WHERE asset_id = ? AND rule_id = ? AND match_content = ?")
+                .bind(&f.name)
+                .bind(&f.description)
+                .bind(f.severity)
+                .bind(asset_id)
+                .bind(&f.rule_id)
+                .bind(&f.match_content)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            if res.rows_affected() > 0 {
+                continue;
+            }
+        }
+        let _ = sqlx::query("INSERT INTO findings (asset_id, rule_id, name, severity, description, match_content, notes, is_false_positive, severity_override, refs, remediation, created_at, last_seen) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
             .bind(asset_id)
             .bind(f.rule_id)
             .bind(f.name)
@@ -119,24 +347,79 @@ pub async fn add_asset(mut asset: CreateAssetRequest) -> Result<i64, String> {
             .bind(f.notes)
             .bind(f.is_false_positive.unwrap_or(false))
             .bind(f.severity_override)
+            .bind(f.references.map(sqlx::types::Json))
+            .bind(f.remediation)
             .execute(&pool)
             .await
             .map_err(|e| e.to_string())?;
     }
 
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
     Ok(asset_id)
 }
 
+/// Identities already in the inventory, used to project duplicates for import previews.
+pub async fn existing_asset_identities() -> Result<std::collections::HashSet<String>, String> {
+    let pool = get_db();
+    let identities: Vec<(String,)> = sqlx::query_as("SELECT identity FROM assets WHERE identity IS NOT NULL")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(identities.into_iter().map(|(i,)| i).collect())
+}
+
+/// Optional narrowing for `get_assets`; unset fields match everything.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AssetFilter {
+    /// Folder whose assets, including those in its subfolders, are returned
+    pub folder_id: Option<i64>,
+    /// Request or response header name (case-insensitive)
+    pub header_name: Option<String>,
+    /// Substring of a header value, limited to `header_name` when both are set
+    pub header_value: Option<String>,
+    /// Only assets scoring at least this much
+    pub min_risk: Option<i64>,
+    #[serde(default)]
+    pub sort: AssetSort,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetSort {
+    #[default]
+    LastSeen,
+    /// Riskiest first
+    Risk,
+}
+
 #[tauri::command]
-pub async fn get_assets() -> Result<Vec<Asset>, String> {
+pub async fn get_assets(filter: Option<AssetFilter>) -> Result<Vec<Asset>, String> {
     let pool = get_db();
-    let assets = sqlx::query_as::<_, Asset>(
-        "SELECT a.id, a.url, a.method, a.status_code, a.source, a.folder_id, a.last_seen, a.req_body, a.res_body, a.notes, COUNT(f.id) as findings_count \
+    let filter = filter.unwrap_or_default();
+    let assets = sqlx::query_as::<_, Asset>(&format!(
+        "{} \
+         SELECT a.id, a.url, a.method, a.status_code, a.source, a.folder_id, a.last_seen, a.req_body, a.res_body, a.req_headers, a.res_headers, a.notes, a.path_template, a.risk_score, COUNT(f.id) as findings_count \
          FROM assets a \
          LEFT JOIN findings f ON a.id = f.asset_id \
+         WHERE a.deleted_at IS NULL \
+         AND (?1 IS NULL OR a.folder_id IN (SELECT id FROM subtree)) \
+         AND ((?2 IS NULL AND ?3 IS NULL) OR EXISTS ( \
+             SELECT 1 FROM (SELECT key, value FROM json_each(a.req_headers) UNION ALL SELECT key, value FROM json_each(a.res_headers)) h \
+             WHERE (?2 IS NULL OR h.key = ?2 COLLATE NOCASE) AND (?3 IS NULL OR h.value LIKE '%' || ?3 || '%') \
+         )) \
+         AND (?4 IS NULL OR a.risk_score >= ?4) \
          GROUP BY a.id \
-         ORDER BY last_seen DESC"
-    )
+         ORDER BY {}",
+        crate::folders::FOLDER_SUBTREE,
+        match filter.sort {
+            AssetSort::LastSeen => "a.last_seen DESC",
+            AssetSort::Risk => "a.risk_score DESC, a.last_seen DESC",
+        }
+    ))
+        .bind(filter.folder_id)
+        .bind(filter.header_name)
+        .bind(filter.header_value)
+        .bind(filter.min_risk)
         .fetch_all(&pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -159,70 +442,128 @@ pub struct BatchImportResult {
 #[tauri::command]
 pub async fn batch_add_assets(request: BatchImportRequest) -> Result<BatchImportResult, String> {
     let pool = get_db();
+    let mode = identity_query_mode(&pool).await?;
+    let templater = crate::templates::templater(&pool).await?;
     let mut added = 0;
     let mut skipped = 0;
+    let mut touched = Vec::new();
 
     for url in request.urls {
+        let identity = asset_identity("GET", &url, mode);
+
         // Check if exists
-        let exists: Option<i64> = sqlx::query("SELECT id FROM assets WHERE url = ?")
-            .bind(&url)
+        let exists: Option<i64> = sqlx::query("SELECT id FROM assets WHERE identity = ?")
+            .bind(&identity)
             .fetch_optional(&pool)
             .await
             .map_err(|e| e.to_string())?
             .map(|row| row.get(0));
 
-        if exists.is_some() {
+        if let Some(id) = exists {
             // Update last_seen
-            let _ = sqlx::query("UPDATE assets SET last_seen = CURRENT_TIMESTAMP WHERE url = ?")
-                .bind(&url)
+            let _ = sqlx::query("UPDATE assets SET last_seen = CURRENT_TIMESTAMP, deleted_at = NULL WHERE id = ?")
+                .bind(id)
                 .execute(&pool)
                 .await;
+            touched.push(id);
             skipped += 1;
         } else {
             // Insert new
-            let _ = sqlx::query("INSERT INTO assets (url, method, source) VALUES (?, 'GET', ?)")
+            let (id,): (i64,) = sqlx::query_as("INSERT INTO assets (url, identity, path_template, method, source, first_seen) VALUES (?, ?, ?, 'GET', ?, CURRENT_TIMESTAMP) RETURNING id")
                 .bind(&url)
+                .bind(&identity)
+                .bind(templater.template(&url))
                 .bind(&request.source)
-                .execute(&pool)
+                .fetch_one(&pool)
                 .await
                 .map_err(|e| e.to_string())?;
+            touched.push(id);
             added += 1;
         }
     }
 
+    for id in touched {
+        crate::risk::refresh_risk_scores(&pool, Some(id)).await?;
+    }
+
     Ok(BatchImportResult { added, skipped })
 }
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct AssetSearchHit {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub asset: Asset,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct FindingSearchHit {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub finding: Finding,
+    pub asset_id: Option<i64>,
+    pub snippet: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SearchResult {
-    pub assets: Vec<Asset>,
-    pub findings: Vec<Finding>,
+    pub assets: Vec<AssetSearchHit>,
+    pub findings: Vec<FindingSearchHit>,
+}
+
+/// Turns free text into an FTS5 query where every word must match as a
+/// prefix. Each word is quoted so FTS syntax in the input (`-`, `:`, `OR`,
+/// unbalanced quotes) is searched for literally instead of parsed.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| format!("\"{}\"*", t.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
 }
 
+/// Ranked full-text search over asset URLs, headers, bodies and notes and over
+/// finding text. Matched terms in each snippet are wrapped in `«` and `»`.
 #[tauri::command]
 pub async fn global_search(query: String) -> Result<SearchResult, String> {
     let pool = get_db();
-    let q = format!("%{}%", query);
-    
-    let assets = sqlx::query_as::<_, Asset>(
-        "SELECT a.id, a.url, a.method, a.status_code, a.source, a.folder_id, a.last_seen, a.req_body, a.res_body, a.notes, 0 as findings_count \
-         FROM assets a \
-         WHERE a.url LIKE ? OR a.req_body LIKE ? OR a.res_body LIKE ? OR a.notes LIKE ?"
+    let Some(q) = fts_query(&query) else {
+        return Ok(SearchResult { assets: Vec::new(), findings: Vec::new() });
+    };
+    let limit: i64 = crate::settings::read(&pool, "search_limit").await?;
+
+    let assets = sqlx::query_as::<_, AssetSearchHit>(
+        "SELECT a.id, a.url, a.method, a.status_code, a.source, a.folder_id, a.last_seen, a.req_body, a.res_body, a.req_headers, a.res_headers, a.notes, \
+         (SELECT COUNT(*) FROM findings f WHERE f.asset_id = a.id) as findings_count, \
+         snippet(assets_fts, -1, '«', '»', '…', 16) as snippet \
+         FROM assets_fts \
+         JOIN assets a ON a.id = assets_fts.rowid \
+         WHERE assets_fts MATCH ? AND a.deleted_at IS NULL \
+         ORDER BY rank \
+         LIMIT ?"
     )
     .bind(&q)
-    .bind(&q)
-    .bind(&q)
-    .bind(&q)
+    .bind(limit)
     .fetch_all(&pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    let findings = sqlx::query_as::<_, Finding>(
-        "SELECT id, rule_id, name, description, severity, match_content, notes, is_false_positive, severity_override FROM findings \
-         WHERE name LIKE ? OR description LIKE ? OR match_content LIKE ?"
+    let findings = sqlx::query_as::<_, FindingSearchHit>(
+        "SELECT f.id, f.asset_id, f.rule_id, f.name, f.description, f.severity, f.match_content, f.notes, f.is_false_positive, f.severity_override, f.refs, f.remediation, \
+         snippet(findings_fts, -1, '«', '»', '…', 16) as snippet \
+         FROM findings_fts \
+         JOIN findings f ON f.id = findings_fts.rowid \
+         JOIN assets a ON a.id = f.asset_id \
+         WHERE findings_fts MATCH ? AND a.deleted_at IS NULL \
+         ORDER BY rank \
+         LIMIT ?"
     )
     .bind(&q)
-    .bind(&q)
-    .bind(&q)
+    .bind(limit)
     .fetch_all(&pool)
     .await
     .map_err(|e| e.to_string())?;
@@ -231,23 +572,155 @@ pub async fn global_search(query: String) -> Result<SearchResult, String> {
 }
 
 #[tauri::command]
-pub async fn batch_import_full(entries: Vec<ImportEntry>, source: String) -> Result<BatchImportResult, String> {
+pub async fn batch_import_full(app_handle: tauri::AppHandle, entries: Vec<ImportEntry>, source: String) -> Result<BatchImportResult, String> {
+    let progress = Arc::new(move |current: usize, total: usize| {
+        if current == total || current.is_multiple_of(50) {
+            let _ = app_handle.emit("import-progress", serde_json::json!({
+                "current": current,
+                "total": total
+            }));
+        }
+    });
+    let (result, _) = import_entries(entries, source, Arc::new(AtomicBool::new(false)), progress).await?;
+    Ok(result)
+}
+
+pub type ImportProgressFn = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Writes parsed entries through `add_asset` on a bounded worker pool,
+/// reporting `(processed, total)` after each entry. Once `cancel` is set the
+/// remaining entries are left unwritten. Returns the counts plus the error of
+/// every entry that failed to import.
+pub async fn import_entries(
+    entries: Vec<ImportEntry>,
+    source: String,
+    cancel: Arc<AtomicBool>,
+    on_progress: ImportProgressFn,
+) -> Result<(BatchImportResult, Vec<String>), String> {
+    let total = entries.len();
+    let pool = get_db();
+    let mode = identity_query_mode(&pool).await?;
+    let worker_count: usize = crate::settings::read(&pool, "import_workers").await?;
+
+    // Entries sharing an identity stay on the same worker so add_asset's
+    // exists-check can't race against itself.
+    let mut groups: Vec<Vec<ImportEntry>> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for entry in entries {
+        let identity = asset_identity(&entry.method, &entry.url, mode);
+        match group_index.get(&identity) {
+            Some(&i) => groups[i].push(entry),
+            None => {
+                group_index.insert(identity, groups.len());
+                groups.push(vec![entry]);
+            }
+        }
+    }
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(worker_count));
+    let processed = Arc::new(AtomicUsize::new(0));
+    let mut workers = tokio::task::JoinSet::new();
+
+    for group in groups {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let permit = semaphore.clone().acquire_owned().await.map_err(|e| e.to_string())?;
+        let source = source.clone();
+        let processed = processed.clone();
+        let cancel = cancel.clone();
+        let on_progress = on_progress.clone();
+
+        workers.spawn(async move {
+            let _permit = permit;
+            let mut added = 0;
+            let mut skipped = 0;
+            let mut errors = Vec::new();
+            for entry in group {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let url = entry.url.clone();
+                let asset = CreateAssetRequest {
+                    url: entry.url,
+                    source: source.clone(),
+                    method: Some(entry.method),
+                    status_code: entry.status_code,
+                    req_headers: crate::import_engine::fold_headers(entry.req_headers),
+                    res_headers: crate::import_engine::fold_headers(entry.res_headers),
+                    req_body: entry.req_body,
+                    res_body: entry.res_body,
+                    findings: entry.findings,
+                };
+
+                match add_asset(asset).await {
+                    Ok(_) => added += 1,
+                    Err(e) => {
+                        skipped += 1;
+                        errors.push(format!("{}: {}", url, e));
+                    }
+                }
+
+                let current = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                on_progress(current, total);
+            }
+            (added, skipped, errors)
+        });
+    }
+
     let mut added = 0;
     let mut skipped = 0;
+    let mut errors = Vec::new();
+    while let Some(res) = workers.join_next().await {
+        let (a, s, e) = res.map_err(|e| e.to_string())?;
+        added += a;
+        skipped += s;
+        errors.extend(e);
+    }
 
-    for entry in entries {
+    Ok((BatchImportResult { added, skipped }, errors))
+}
+
+/// Seeds the inventory from a stored spec, linking each created asset back to it.
+#[tauri::command]
+pub async fn import_spec_to_inventory(app_handle: tauri::AppHandle, spec_id: i64) -> Result<BatchImportResult, String> {
+    let pool = get_db();
+    let (content,): (String,) = sqlx::query_as("SELECT content FROM specs WHERE id = ?")
+        .bind(spec_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let custom_rules = crate::db::get_custom_rules().await?;
+    let plugins = crate::plugins::load_plugins(&app_handle);
+    let parsed = crate::import_engine::Parser::parse_openapi(&content, &custom_rules, &plugins)
+        .map_err(|e| e.to_string())?;
+
+    let mut added = 0;
+    let mut skipped = 0;
+    for entry in parsed.entries {
         let asset = CreateAssetRequest {
             url: entry.url,
-            source: source.clone(),
+            source: "openapi".to_string(),
             method: Some(entry.method),
             status_code: entry.status_code,
+            req_headers: crate::import_engine::fold_headers(entry.req_headers),
+            res_headers: crate::import_engine::fold_headers(entry.res_headers),
             req_body: entry.req_body,
             res_body: entry.res_body,
             findings: entry.findings,
         };
-        
+
         match add_asset(asset).await {
-            Ok(_) => added += 1,
+            Ok(asset_id) => {
+                sqlx::query("UPDATE assets SET spec_id = ? WHERE id = ?")
+                    .bind(spec_id)
+                    .bind(asset_id)
+                    .execute(&pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                added += 1;
+            }
             Err(_) => skipped += 1,
         }
     }
@@ -260,6 +733,8 @@ pub struct HistoryItem {
     pub id: i64,
     pub status_code: Option<i64>,
     pub res_body: Option<String>,
+    #[sqlx(json(nullable))]
+    pub res_headers: Option<HashMap<String, String>>,
     pub timestamp: String,
 }
 
@@ -267,7 +742,7 @@ pub struct HistoryItem {
 pub async fn get_asset_history(asset_id: i64) -> Result<Vec<HistoryItem>, String> {
     let pool = get_db();
     let history = sqlx::query_as::<_, HistoryItem>(
-        "SELECT id, status_code, res_body, timestamp FROM asset_history WHERE asset_id = ? ORDER BY timestamp DESC"
+        "SELECT id, status_code, res_body, res_headers, timestamp FROM asset_history WHERE asset_id = ? ORDER BY timestamp DESC"
     )
     .bind(asset_id)
     .fetch_all(&pool)
@@ -277,11 +752,56 @@ pub async fn get_asset_history(asset_id: i64) -> Result<Vec<HistoryItem>, String
     Ok(history)
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VersionDiff {
+    pub timestamp_a: String,
+    pub timestamp_b: String,
+    pub status_a: Option<i64>,
+    pub status_b: Option<i64>,
+    pub headers: Vec<crate::diff::HeaderChange>,
+    pub body: crate::diff::BodyDiff,
+}
+
+/// A history entry of the asset, or its current state when `version` is `None`.
+async fn asset_version(pool: &Pool<Sqlite>, asset_id: i64, version: Option<i64>) -> Result<HistoryItem, String> {
+    let item = match version {
+        Some(id) => sqlx::query_as::<_, HistoryItem>("SELECT id, status_code, res_body, res_headers, timestamp FROM asset_history WHERE id = ? AND asset_id = ?")
+            .bind(id)
+            .bind(asset_id),
+        None => sqlx::query_as::<_, HistoryItem>("SELECT id, status_code, res_body, res_headers, last_seen AS timestamp FROM assets WHERE id = ?")
+            .bind(asset_id),
+    }
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    item.ok_or_else(|| match version {
+        Some(id) => format!("Version {} of asset {} not found", id, asset_id),
+        None => format!("Asset {} not found", asset_id),
+    })
+}
+
+/// Compares two versions of an asset's response, each either a history
+/// entry id from `get_asset_history` or `None` for the current response.
+#[tauri::command]
+pub async fn diff_asset_versions(asset_id: i64, version_a: Option<i64>, version_b: Option<i64>) -> Result<VersionDiff, String> {
+    let pool = get_db();
+    let a = asset_version(&pool, asset_id, version_a).await?;
+    let b = asset_version(&pool, asset_id, version_b).await?;
+    Ok(VersionDiff {
+        headers: crate::diff::diff_headers(&a.res_headers.unwrap_or_default(), &b.res_headers.unwrap_or_default()),
+        body: crate::diff::diff_bodies(a.res_body.as_deref(), b.res_body.as_deref()),
+        timestamp_a: a.timestamp,
+        timestamp_b: b.timestamp,
+        status_a: a.status_code,
+        status_b: b.status_code,
+    })
+}
+
 #[tauri::command]
 pub async fn get_findings(asset_id: i64) -> Result<Vec<Finding>, String> {
     let pool = get_db();
     let findings = sqlx::query_as::<_, Finding>(
-        "SELECT id, rule_id, name, description, severity, match_content, notes, is_false_positive, severity_override FROM findings WHERE asset_id = ?"
+        "SELECT id, rule_id, name, description, severity, match_content, notes, is_false_positive, severity_override, refs, remediation FROM findings WHERE asset_id = ?"
     )
     .bind(asset_id)
     .fetch_all(&pool)
@@ -291,6 +811,35 @@ pub async fn get_findings(asset_id: i64) -> Result<Vec<Finding>, String> {
     Ok(findings)
 }
 
+/// Stores a finding, counting a repeat on the existing row instead.
+pub(crate) async fn store_finding(pool: &Pool<Sqlite>, asset_id: i64, f: &Finding) -> Result<(), String> {
+    let res = sqlx::query("UPDATE findings SET occurrences = occurrences + 1, last_seen = CURRENT_TIMESTAMP, description = ? WHERE asset_id = ? AND rule_id = ? AND match_content = ?")
+        .bind(&f.description)
+        .bind(asset_id)
+        .bind(&f.rule_id)
+        .bind(&f.match_content)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if res.rows_affected() > 0 {
+        return Ok(());
+    }
+    sqlx::query("INSERT INTO findings (asset_id, rule_id, name, severity, description, match_content, notes, is_false_positive, refs, remediation, created_at, last_seen) VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+        .bind(asset_id)
+        .bind(&f.rule_id)
+        .bind(&f.name)
+        .bind(f.severity)
+        .bind(&f.description)
+        .bind(&f.match_content)
+        .bind(&f.notes)
+        .bind(f.references.as_ref().map(sqlx::types::Json))
+        .bind(&f.remediation)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdateFindingRequest {
     pub id: i64,
@@ -310,15 +859,68 @@ pub async fn update_finding_annotation(request: UpdateFindingRequest) -> Result<
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
-    
+
+    let asset_id: Option<(i64,)> = sqlx::query_as("SELECT asset_id FROM findings WHERE id = ?")
+        .bind(request.id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some((asset_id,)) = asset_id {
+        crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    }
     Ok(())
 }
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct ReplayRequest {
     pub url: String,
     pub method: String,
     pub headers: std::collections::HashMap<String, String>,
     pub body: Option<String>,
+    #[serde(default)]
+    pub options: ReplayOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpVersion {
+    /// Negotiated via ALPN
+    #[default]
+    Auto,
+    Http1,
+    /// HTTP/2 with prior knowledge, also over plain http
+    Http2,
+}
+
+/// How a replayed request is sent.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReplayOptions {
+    pub follow_redirects: bool,
+    pub max_redirects: usize,
+    pub timeout_ms: u64,
+    /// Route through the `upstream_proxy` setting when one is configured
+    pub use_upstream_proxy: bool,
+    pub http_version: HttpVersion,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            follow_redirects: true,
+            max_redirects: 10,
+            timeout_ms: 10_000,
+            use_upstream_proxy: true,
+            http_version: HttpVersion::Auto,
+        }
+    }
+}
+
+/// One redirect response that was followed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    pub location: String,
 }
 
 #[derive(serde::Serialize)]
@@ -327,33 +929,110 @@ pub struct ReplayResponse {
     pub headers: std::collections::HashMap<String, String>,
     pub body: String,
     pub time_ms: u64,
+    /// URL the final response came from
+    pub final_url: String,
+    /// Redirects followed on the way there, in order
+    pub redirects: Vec<RedirectHop>,
+    /// `{{var}}` placeholders resolved against the active environment
+    #[serde(flatten)]
+    pub variables: crate::variables::Substitutions,
 }
 
+/// Sends an edited request. `{{var}}` placeholders in the URL, headers and
+/// body are first resolved against the active environment.
 #[tauri::command]
 pub async fn tamper_request(req: ReplayRequest) -> Result<ReplayResponse, String> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
+    let mut resolver = crate::variables::Resolver::active().await?;
+    send_with_variables(&req, &mut resolver).await
+}
+
+async fn replay_client(options: &ReplayOptions) -> Result<reqwest::Client, String> {
+    // Redirects are followed by hand so each hop can be reported
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_millis(options.timeout_ms.max(1)))
         .danger_accept_invalid_certs(true)
-        .build()
-        .map_err(|e| e.to_string())?;
+        .redirect(reqwest::redirect::Policy::none());
+    builder = match options.http_version {
+        HttpVersion::Auto => builder,
+        HttpVersion::Http1 => builder.http1_only(),
+        HttpVersion::Http2 => builder.http2_prior_knowledge(),
+    };
+    let upstream: String = if options.use_upstream_proxy { crate::settings::get("upstream_proxy").await? } else { String::new() };
+    builder = if upstream.is_empty() {
+        builder.no_proxy()
+    } else {
+        builder.proxy(reqwest::Proxy::all(&upstream).map_err(|e| e.to_string())?)
+    };
+    builder.build().map_err(|e| e.to_string())
+}
 
-    let method = reqwest::Method::from_bytes(req.method.as_bytes()).map_err(|e| e.to_string())?;
-    
-    let mut request_builder = client.request(method, &req.url);
-    
-    for (key, value) in req.headers {
-        request_builder = request_builder.header(key, value);
-    }
-    
-    if let Some(body) = req.body {
-        request_builder = request_builder.body(body);
-    }
+/// Resolves `{{var}}` placeholders with `resolver` and sends the request
+/// with the active environment's auth, renewing its token once on a 401.
+pub async fn send_with_variables(req: &ReplayRequest, resolver: &mut crate::variables::Resolver) -> Result<ReplayResponse, String> {
+    resolver.seen = Default::default();
+    let mut url = resolver.resolve(&req.url)?;
+    let mut raw_headers = req.headers.clone();
+    crate::env_auth::EnvAuth::inject_active(&mut raw_headers).await?;
+    let mut headers: Vec<(String, String)> = raw_headers
+        .iter()
+        .map(|(k, v)| Ok((resolver.resolve(k)?, resolver.resolve(v)?)))
+        .collect::<Result<_, String>>()?;
+    let mut body = req.body.as_deref().map(|b| resolver.resolve(b)).transpose()?;
+    let variables = resolver.seen.clone();
+
+    let client = replay_client(&req.options).await?;
+    let mut method = reqwest::Method::from_bytes(req.method.as_bytes()).map_err(|e| e.to_string())?;
+    let mut redirects = Vec::new();
 
     let start = std::time::Instant::now();
-    let response = request_builder.send().await.map_err(|e| e.to_string())?;
+    let mut renewed = false;
+    let response = loop {
+        let mut request_builder = client.request(method.clone(), &url);
+        for (key, value) in &headers {
+            request_builder = request_builder.header(key, value);
+        }
+        if let Some(body) = &body {
+            request_builder = request_builder.body(body.clone());
+        }
+        let response = request_builder.send().await.map_err(|e| e.to_string())?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED && !renewed {
+            renewed = true;
+            if let Some((_, value)) = headers.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case("authorization")) {
+                if let Some(fresh) = crate::env_auth::replacement(value, true).await {
+                    *value = fresh;
+                    continue;
+                }
+            }
+        }
+
+        let location = response.headers().get(reqwest::header::LOCATION).and_then(|l| l.to_str().ok());
+        let (true, Some(location)) = (req.options.follow_redirects && response.status().is_redirection(), location) else {
+            break response;
+        };
+        if redirects.len() >= req.options.max_redirects {
+            return Err(format!("Stopped after {} redirects", redirects.len()));
+        }
+        let next = response.url().join(location).map_err(|e| format!("Invalid redirect location '{}': {}", location, e))?;
+        let status = response.status().as_u16();
+        redirects.push(RedirectHop { url: url.clone(), status, location: location.to_string() });
+
+        // 303, and 301/302 after a POST, turn into a bodiless GET as browsers do
+        if (status == 303 && method != reqwest::Method::HEAD) || (matches!(status, 301 | 302) && method == reqwest::Method::POST) {
+            method = reqwest::Method::GET;
+            body = None;
+            headers.retain(|(k, _)| !k.eq_ignore_ascii_case("content-type") && !k.eq_ignore_ascii_case("content-length"));
+        }
+        // Credentials stay with the host they were meant for
+        if next.host_str() != response.url().host_str() {
+            headers.retain(|(k, _)| !["authorization", "cookie", "proxy-authorization"].contains(&k.to_lowercase().as_str()));
+        }
+        url = next.to_string();
+    };
     let duration = start.elapsed().as_millis() as u64;
 
     let status = response.status().as_u16();
+    let final_url = response.url().to_string();
     let mut headers = std::collections::HashMap::new();
     for (name, value) in response.headers() {
         headers.insert(
@@ -369,34 +1048,114 @@ pub async fn tamper_request(req: ReplayRequest) -> Result<ReplayResponse, String
         headers,
         body,
         time_ms: duration,
+        final_url,
+        redirects,
+        variables,
     })
 }
 
+/// Moves an asset to the trash, or deletes it outright when `permanent` is set.
 #[tauri::command]
-pub async fn delete_asset(id: i64) -> Result<(), String> {
+pub async fn delete_asset(id: i64, permanent: Option<bool>) -> Result<(), String> {
+    let permanent = permanent.unwrap_or(false);
+    let role = if permanent { crate::users::Role::Admin } else { crate::users::Role::Analyst };
+    crate::users::require_role(role).await?;
     let pool = get_db();
-    
-    // Findings are deleted automatically if ON DELETE CASCADE is set, 
-    // but we'll do it manually just in case.
-    let _ = sqlx::query("DELETE FROM findings WHERE asset_id = ?")
-        .bind(id)
-        .execute(&pool)
-        .await;
 
-    sqlx::query("DELETE FROM assets WHERE id = ?")
+    if permanent {
+        // Findings, history and tag links cascade from the asset row
+        sqlx::query("DELETE FROM assets WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    } else {
+        sqlx::query("UPDATE assets SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let action = if permanent { "delete_asset" } else { "trash_asset" };
+    crate::audit::record(action, "asset", Some(id), None).await;
+    Ok(())
+}
+
+/// Applies analyst edits to an asset. Changing the method re-keys the asset
+/// and is refused when another asset already has the resulting identity.
+#[tauri::command]
+pub async fn update_asset(id: i64, changes: AssetUpdate) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    let (url,): (String,) = sqlx::query_as("SELECT url FROM assets WHERE id = ?")
         .bind(id)
-        .execute(&pool)
+        .fetch_optional(&pool)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Asset {} not found", id))?;
 
+    if let Some(Some(folder)) = changes.folder_id {
+        crate::folders::ensure_folder(folder).await?;
+    }
+
+    let method = match changes.method.as_deref().map(str::trim) {
+        Some("") => return Err("Method must not be empty".to_string()),
+        Some(m) => Some(m.to_uppercase()),
+        None => None,
+    };
+    let identity = match &method {
+        Some(m) => {
+            let identity = asset_identity(m, &url, identity_query_mode(&pool).await?);
+            let taken: Option<(i64,)> = sqlx::query_as("SELECT id FROM assets WHERE identity = ? AND id != ?")
+                .bind(&identity)
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some((other,)) = taken {
+                return Err(format!("Asset {} already exists as {} {}", other, m, url));
+            }
+            Some(identity)
+        }
+        None => None,
+    };
+    let notes = changes.notes.map(|n| n.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()));
+
+    // ?1, ?5 and ?7 flag whether each nullable field was sent at all
+    sqlx::query(
+        "UPDATE assets SET \
+            notes = CASE WHEN ?1 THEN ?2 ELSE notes END, \
+            method = COALESCE(?3, method), \
+            identity = COALESCE(?4, identity), \
+            folder_id = CASE WHEN ?5 THEN ?6 ELSE folder_id END, \
+            status_code = CASE WHEN ?7 THEN ?8 ELSE status_code END \
+         WHERE id = ?9"
+    )
+    .bind(notes.is_some())
+    .bind(notes.flatten())
+    .bind(&method)
+    .bind(identity)
+    .bind(changes.folder_id.is_some())
+    .bind(changes.folder_id.flatten())
+    .bind(changes.status_code.is_some())
+    .bind(changes.status_code.flatten())
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    crate::audit::record("update_asset", "asset", Some(id), None).await;
     Ok(())
 }
 
 #[derive(Serialize, Deserialize, Debug, FromRow)]
 pub struct FullFinding {
     pub id: i64,
-    pub asset_id: i64,
-    pub url: String,
+    /// None for findings not tied to a captured asset, e.g. OOB callbacks
+    /// whose request was never stored
+    pub asset_id: Option<i64>,
+    pub url: Option<String>,
     pub rule_id: String,
     pub name: String,
     pub description: String,
@@ -405,15 +1164,22 @@ pub struct FullFinding {
     pub notes: Option<String>,
     pub is_false_positive: bool,
     pub severity_override: Option<String>,
+    #[sqlx(rename = "refs", json(nullable))]
+    pub references: Option<Vec<String>>,
+    pub remediation: Option<String>,
+    /// Times the finding was raised; drift findings count repeat sightings
+    pub occurrences: i64,
+    pub last_seen: Option<String>,
 }
 
 #[tauri::command]
 pub async fn get_all_findings_full() -> Result<Vec<FullFinding>, String> {
     let pool = get_db();
     let findings = sqlx::query_as::<_, FullFinding>(
-        "SELECT f.id, f.asset_id, a.url, f.rule_id, f.name, f.description, f.severity, f.match_content, f.notes, f.is_false_positive, f.severity_override \
+        "SELECT f.id, f.asset_id, a.url, f.rule_id, f.name, f.description, f.severity, f.match_content, f.notes, f.is_false_positive, f.severity_override, f.refs, f.remediation, f.occurrences, f.last_seen \
          FROM findings f \
-         JOIN assets a ON f.asset_id = a.id"
+         LEFT JOIN assets a ON f.asset_id = a.id \
+         WHERE f.asset_id IS NULL OR a.deleted_at IS NULL"
     )
     .fetch_all(&pool)
     .await
@@ -423,17 +1189,34 @@ pub async fn get_all_findings_full() -> Result<Vec<FullFinding>, String> {
 }
 
 #[tauri::command]
-pub async fn clear_inventory() -> Result<(), String> {
+pub async fn clear_inventory(app_handle: tauri::AppHandle) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    crate::backups::snapshot(&app_handle, "pre-clear").await?;
     let pool = get_db();
-    
-    let _ = sqlx::query("DELETE FROM findings")
-        .execute(&pool)
-        .await;
 
-    sqlx::query("DELETE FROM assets")
+    // Everything goes to the trash; `empty_trash` makes it permanent
+    let res = sqlx::query("UPDATE assets SET deleted_at = CURRENT_TIMESTAMP WHERE deleted_at IS NULL")
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
 
+    crate::audit::record("clear_inventory", "asset", None, Some(format!("{} assets trashed", res.rows_affected()))).await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_identity() {
+        let p = IdentityQueryMode::Parameterize;
+        assert_eq!(asset_identity("get", "HTTPS://API.example.com:443/users/?id=1#top", p), "GET https://api.example.com/users?id=");
+        assert_eq!(asset_identity("GET", "https://api.example.com/users?id=2", p), asset_identity("get", "https://api.example.com/users/?id=1", p));
+        assert_ne!(asset_identity("GET", "https://api.example.com/users", p), asset_identity("POST", "https://api.example.com/users", p));
+        assert_eq!(asset_identity("GET", "https://a.example/?b=2&a=1&b=3", p), "GET https://a.example/?a=&b=");
+        assert_eq!(asset_identity("GET", "https://a.example/x?b=2&a=1", IdentityQueryMode::Strip), "GET https://a.example/x");
+        assert_eq!(asset_identity("GET", "https://a.example/x?b=2&a=1", IdentityQueryMode::Keep), "GET https://a.example/x?a=1&b=2");
+        assert_eq!(asset_identity("", "not a url", p), "GET not a url");
+    }
+}
@@ -1,60 +1,456 @@
 use crate::db::get_db;
-use sqlx::Row;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::sync::OnceLock;
+use tokio::sync::Mutex;
 
+/// `prev_hash` of the first entry in the chain.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+fn sha256_hex(data: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, data.as_bytes());
+    digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// An audit entry as stored, with the hash chain linking it to the one
+/// before: `hash` covers the entry's content and `prev_hash`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct ChainedEntry {
+    id: i64,
+    user_id: Option<i64>,
+    action: String,
+    entity_type: String,
+    entity_id: Option<i64>,
+    details: Option<String>,
+    timestamp: String,
+    prev_hash: Option<String>,
+    hash: Option<String>,
+}
+
+impl ChainedEntry {
+    /// SHA-256 over the content and `prev_hash`, as lowercase hex. The
+    /// fields are hashed as a JSON array so they can't run into each other.
+    fn compute_hash(&self, prev_hash: &str) -> String {
+        let content = serde_json::json!([self.user_id, self.action, self.entity_type, self.entity_id, self.details, self.timestamp, prev_hash]);
+        sha256_hex(&content.to_string())
+    }
+}
+
+/// Where the chain doesn't hold.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ChainBreak {
+    pub id: i64,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditIntegrity {
+    pub entries: usize,
+    pub valid: bool,
+    /// Hash of the newest entry; recording it elsewhere lets truncation of
+    /// the log's tail be detected too
+    pub head_hash: Option<String>,
+    pub breaks: Vec<ChainBreak>,
+}
+
+/// Entries, oldest first, whose hash or link to the previous entry doesn't
+/// match.
+fn find_breaks(entries: &[ChainedEntry]) -> Vec<ChainBreak> {
+    let mut breaks = Vec::new();
+    let mut expected_prev = GENESIS.to_string();
+    for entry in entries {
+        let (Some(prev_hash), Some(hash)) = (&entry.prev_hash, &entry.hash) else {
+            breaks.push(ChainBreak { id: entry.id, reason: "Entry is not part of the chain".to_string() });
+            continue;
+        };
+        if *prev_hash != expected_prev {
+            breaks.push(ChainBreak { id: entry.id, reason: "Previous entry is missing or was altered".to_string() });
+        }
+        if entry.compute_hash(prev_hash) != *hash {
+            breaks.push(ChainBreak { id: entry.id, reason: "Content doesn't match its hash".to_string() });
+        }
+        expected_prev = hash.clone();
+    }
+    breaks
+}
+
+async fn chained_entries(pool: &Pool<Sqlite>) -> Result<Vec<ChainedEntry>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, user_id, action, entity_type, entity_id, details, CAST(timestamp AS TEXT) AS timestamp, prev_hash, hash
+         FROM audit_log ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Serializes appends so two entries can't claim the same predecessor.
+static APPEND: OnceLock<Mutex<()>> = OnceLock::new();
+
+/// Chains entries written before the chain existed, oldest first. Runs
+/// when a workspace is opened; unchained entries after the first chained
+/// one are left for `verify_audit_integrity` to report.
+pub async fn seal_unchained(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
+    let _guard = APPEND.get_or_init(|| Mutex::new(())).lock().await;
+    let mut prev = GENESIS.to_string();
+    for entry in chained_entries(pool).await? {
+        if entry.hash.is_some() {
+            break;
+        }
+        let hash = entry.compute_hash(&prev);
+        sqlx::query("UPDATE audit_log SET prev_hash = ?, hash = ? WHERE id = ?")
+            .bind(&prev)
+            .bind(&hash)
+            .bind(entry.id)
+            .execute(pool)
+            .await?;
+        prev = hash;
+    }
+    Ok(())
+}
+
+/// Walks the audit log's hash chain and reports every entry that was
+/// altered, removed from the middle or inserted out of band.
 #[tauri::command]
-pub async fn get_audit_log(limit: Option<i64>) -> Result<Vec<serde_json::Value>, String> {
-    let pool = get_db();
-    let limit_val = limit.unwrap_or(100);
-    
-    let rows = sqlx::query(
-        "SELECT 
-            a.id, a.action, a.entity_type, a.entity_id, a.details, a.timestamp,
-            u.name as user_name, u.email as user_email
+pub async fn verify_audit_integrity() -> Result<AuditIntegrity, String> {
+    let entries = chained_entries(&get_db()).await.map_err(|e| e.to_string())?;
+    let breaks = find_breaks(&entries);
+    Ok(AuditIntegrity {
+        entries: entries.len(),
+        valid: breaks.is_empty(),
+        head_hash: entries.last().and_then(|e| e.hash.clone()),
+        breaks,
+    })
+}
+
+/// Which audit entries to list or export. Date bounds are inclusive and
+/// take a date (`2026-01-31`) or a full timestamp.
+#[derive(Debug, Deserialize, Default)]
+pub struct AuditQuery {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<i64>,
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub entity_type: Option<String>,
+    #[serde(default)]
+    pub entity_id: Option<i64>,
+    #[serde(default)]
+    pub offset: i64,
+    /// Page size; exports ignore it along with `offset`
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl AuditQuery {
+    /// `to` as an upper bound for timestamps; a bare date covers the whole day.
+    fn until(&self) -> Option<String> {
+        self.to.as_ref().map(|to| if to.len() == 10 { format!("{} 23:59:59", to) } else { to.clone() })
+    }
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<i64>,
+    pub details: Option<String>,
+    pub timestamp: String,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// Entries matching the query across all pages
+    pub total: i64,
+}
+
+const FILTER: &str = "WHERE (?1 IS NULL OR a.timestamp >= ?1) AND (?2 IS NULL OR a.timestamp <= ?2) AND (?3 IS NULL OR a.user_id = ?3)
+     AND (?4 IS NULL OR a.action = ?4) AND (?5 IS NULL OR a.entity_type = ?5) AND (?6 IS NULL OR a.entity_id = ?6)";
+
+async fn query_entries(query: &AuditQuery, page: Option<(i64, i64)>) -> Result<Vec<AuditEntry>, String> {
+    let (limit, offset) = page.unwrap_or((-1, 0));
+    sqlx::query_as(&format!(
+        "SELECT a.id, a.user_id, u.name AS user_name, u.email AS user_email, a.action, a.entity_type, a.entity_id, a.details,
+                CAST(a.timestamp AS TEXT) AS timestamp, a.prev_hash, a.hash
          FROM audit_log a
          LEFT JOIN users u ON a.user_id = u.id
-         ORDER BY a.timestamp DESC
-         LIMIT ?"
-    )
-    .bind(limit_val)
-    .fetch_all(&pool)
+         {}
+         ORDER BY a.timestamp DESC, a.id DESC
+         LIMIT ?7 OFFSET ?8",
+        FILTER
+    ))
+    .bind(&query.from)
+    .bind(query.until())
+    .bind(query.user_id)
+    .bind(&query.action)
+    .bind(&query.entity_type)
+    .bind(query.entity_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&get_db())
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| e.to_string())
+}
 
-    let logs: Vec<serde_json::Value> = rows.iter().map(|row| {
-        serde_json::json!({
-            "id": row.get::<i64, _>("id"),
-            "action": row.get::<String, _>("action"),
-            "entity_type": row.get::<String, _>("entity_type"),
-            "entity_id": row.get::<Option<i64>, _>("entity_id"),
-            "details": row.get::<Option<String>, _>("details"),
-            "timestamp": row.get::<String, _>("timestamp"),
-            "user_name": row.get::<Option<String>, _>("user_name"),
-            "user_email": row.get::<Option<String>, _>("user_email")
-        })
-    }).collect();
+/// One page of audit entries matching `query`, newest first.
+#[tauri::command]
+pub async fn get_audit_log(query: Option<AuditQuery>) -> Result<AuditPage, String> {
+    let query = query.unwrap_or_default();
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM audit_log a {}", FILTER))
+        .bind(&query.from)
+        .bind(query.until())
+        .bind(query.user_id)
+        .bind(&query.action)
+        .bind(&query.entity_type)
+        .bind(query.entity_id)
+        .fetch_one(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    let entries = query_entries(&query, Some((query.limit.unwrap_or(100), query.offset.max(0)))).await?;
+    Ok(AuditPage { entries, total })
+}
 
-    Ok(logs)
+#[derive(Debug, Serialize)]
+pub struct AuditFacets {
+    pub actions: Vec<String>,
+    pub entity_types: Vec<String>,
 }
 
+/// Distinct actions and entity types, for filter pickers.
 #[tauri::command]
-pub async fn log_action(
-    user_id: Option<i64>,
-    action: String,
-    entity_type: String,
-    entity_id: Option<i64>,
-    details: Option<String>
-) -> Result<(), String> {
+pub async fn get_audit_facets() -> Result<AuditFacets, String> {
+    let pool = get_db();
+    let actions = sqlx::query_scalar("SELECT DISTINCT action FROM audit_log ORDER BY action")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let entity_types = sqlx::query_scalar("SELECT DISTINCT entity_type FROM audit_log ORDER BY entity_type")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(AuditFacets { actions, entity_types })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditExportFormat {
+    Csv,
+    Json,
+}
+
+/// A CSV cell. Values a spreadsheet would run as a formula get a leading
+/// `'` so they open as text.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) { format!("'{}", value) } else { value.to_string() };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// SHA-256 binding an export's body to the chain head it was taken at:
+/// over the head hash, a newline and the body.
+fn export_digest(head_hash: Option<&str>, body: &str) -> String {
+    sha256_hex(&format!("{}\n{}", head_hash.unwrap_or(""), body))
+}
+
+/// The entries as CSV under `#` comment lines carrying the chain head and
+/// the `export_digest` of the rows that follow them, header row included,
+/// so the export can be matched to the log it came from.
+fn to_csv(entries: &[AuditEntry], integrity: &AuditIntegrity, exported_at: &str) -> String {
+    let mut body = String::from("id,timestamp,user_id,user_name,user_email,action,entity_type,entity_id,details,prev_hash,hash\n");
+    for e in entries {
+        let fields = [
+            e.id.to_string(),
+            e.timestamp.clone(),
+            e.user_id.map(|id| id.to_string()).unwrap_or_default(),
+            e.user_name.clone().unwrap_or_default(),
+            e.user_email.clone().unwrap_or_default(),
+            e.action.clone(),
+            e.entity_type.clone(),
+            e.entity_id.map(|id| id.to_string()).unwrap_or_default(),
+            e.details.clone().unwrap_or_default(),
+            e.prev_hash.clone().unwrap_or_default(),
+            e.hash.clone().unwrap_or_default(),
+        ];
+        body.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        body.push('\n');
+    }
+    let head_hash = integrity.head_hash.as_deref();
+    format!(
+        "# apisec audit log export\n# exported_at: {}\n# chain_head_hash: {}\n# chain_valid: {}\n# body_sha256: {}\n{}",
+        exported_at,
+        head_hash.unwrap_or(""),
+        integrity.valid,
+        export_digest(head_hash, &body),
+        body
+    )
+}
+
+/// Every entry matching `query` (paging aside) as CSV or JSON, stamped with
+/// the chain head hash, whether the chain verified at export time and an
+/// `export_digest` of the entries. For JSON the digest covers `entries`
+/// serialized compactly.
+#[tauri::command]
+pub async fn export_audit_log(query: Option<AuditQuery>, format: AuditExportFormat) -> Result<String, String> {
+    let query = query.unwrap_or_default();
+    let entries = query_entries(&query, None).await?;
+    // Logged first so the head hash in the export covers the export itself
+    record("export_audit_log", "audit_log", None, Some(format!("{} entries as {:?}", entries.len(), format))).await;
+    let integrity = verify_audit_integrity().await?;
+    let exported_at = chrono::Utc::now().to_rfc3339();
+    let out = match format {
+        AuditExportFormat::Csv => to_csv(&entries, &integrity, &exported_at),
+        AuditExportFormat::Json => {
+            let body = serde_json::to_string(&entries).map_err(|e| e.to_string())?;
+            serde_json::to_string_pretty(&serde_json::json!({
+                "exported_at": exported_at,
+                "chain_head_hash": integrity.head_hash,
+                "chain_valid": integrity.valid,
+                "body_sha256": export_digest(integrity.head_hash.as_deref(), &body),
+                "entries": entries,
+            }))
+            .map_err(|e| e.to_string())?
+        }
+    };
+    Ok(out)
+}
+
+/// Appends an entry to the chain for the user selected with
+/// `set_current_user`. Only the backend writes entries, so the user can't
+/// be chosen by the caller.
+async fn append(action: &str, entity_type: &str, entity_id: Option<i64>, details: Option<String>) -> Result<(), String> {
     let pool = get_db();
+    let _guard = APPEND.get_or_init(|| Mutex::new(())).lock().await;
+    let prev_hash: Option<String> = sqlx::query_scalar("SELECT hash FROM audit_log ORDER BY id DESC LIMIT 1")
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .flatten();
+    let prev_hash = prev_hash.unwrap_or_else(|| GENESIS.to_string());
+    let mut entry = ChainedEntry {
+        id: 0,
+        user_id: crate::users::current_user_id(),
+        action: action.to_string(),
+        entity_type: entity_type.to_string(),
+        entity_id,
+        details,
+        // Same format as CURRENT_TIMESTAMP, fixed here because it is hashed
+        timestamp: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        prev_hash: None,
+        hash: None,
+    };
+    entry.hash = Some(entry.compute_hash(&prev_hash));
     sqlx::query(
-        "INSERT INTO audit_log (user_id, action, entity_type, entity_id, details) VALUES (?, ?, ?, ?, ?)"
+        "INSERT INTO audit_log (user_id, action, entity_type, entity_id, details, timestamp, prev_hash, hash) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
     )
-    .bind(user_id)
-    .bind(action)
-    .bind(entity_type)
-    .bind(entity_id)
-    .bind(details)
+    .bind(entry.user_id)
+    .bind(&entry.action)
+    .bind(&entry.entity_type)
+    .bind(entry.entity_id)
+    .bind(&entry.details)
+    .bind(&entry.timestamp)
+    .bind(&prev_hash)
+    .bind(&entry.hash)
     .execute(&pool)
     .await
     .map_err(|e| e.to_string())?;
     Ok(())
 }
+
+/// Audit entry for an action performed by a backend command. Failing to
+/// write the entry doesn't fail the action itself.
+pub async fn record(action: &str, entity_type: &str, entity_id: Option<i64>, details: Option<String>) {
+    if let Err(e) = append(action, entity_type, entity_id, details).await {
+        eprintln!("Failed to write audit entry for {}: {}", action, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(actions: &[&str]) -> Vec<ChainedEntry> {
+        let mut prev = GENESIS.to_string();
+        actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let mut entry = ChainedEntry {
+                    id: i as i64 + 1,
+                    user_id: Some(1),
+                    action: action.to_string(),
+                    entity_type: "asset".to_string(),
+                    entity_id: Some(7),
+                    details: None,
+                    timestamp: "2026-01-01 10:00:00".to_string(),
+                    prev_hash: Some(prev.clone()),
+                    hash: None,
+                };
+                let hash = entry.compute_hash(&prev);
+                entry.hash = Some(hash.clone());
+                prev = hash;
+                entry
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_breaks() {
+        let entries = chain(&["create", "update", "delete"]);
+        assert!(find_breaks(&entries).is_empty());
+        assert_eq!(entries[0].hash.as_ref().map(String::len), Some(64));
+
+        let mut edited = entries.clone();
+        edited[1].details = Some("covered up".to_string());
+        assert_eq!(find_breaks(&edited), [ChainBreak { id: 2, reason: "Content doesn't match its hash".to_string() }]);
+
+        let removed = [entries[0].clone(), entries[2].clone()];
+        assert_eq!(find_breaks(&removed), [ChainBreak { id: 3, reason: "Previous entry is missing or was altered".to_string() }]);
+        assert_eq!(find_breaks(&entries[1..])[0].id, 2);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let entry = AuditEntry {
+            id: 3,
+            user_id: Some(1),
+            user_name: Some("Ann".to_string()),
+            user_email: None,
+            action: "update_asset".to_string(),
+            entity_type: "asset".to_string(),
+            entity_id: Some(9),
+            details: Some("renamed \"a\", then b".to_string()),
+            timestamp: "2026-01-01 10:00:00".to_string(),
+            prev_hash: Some("p".to_string()),
+            hash: Some("h".to_string()),
+        };
+        let integrity = AuditIntegrity { entries: 3, valid: true, head_hash: Some("h".to_string()), breaks: Vec::new() };
+        let csv = to_csv(&[entry], &integrity, "2026-01-02T00:00:00Z");
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[2], "# chain_head_hash: h");
+        assert_eq!(lines[6], r#"3,2026-01-01 10:00:00,1,Ann,,update_asset,asset,9,"renamed ""a"", then b",p,h"#);
+        let body = csv.split_once("# body_sha256: ").unwrap().1.split_once('\n').unwrap().1;
+        assert!(body.starts_with("id,timestamp,"));
+        assert_eq!(lines[4], format!("# body_sha256: {}", export_digest(Some("h"), body)));
+        assert_ne!(export_digest(Some("h"), body), export_digest(Some("other"), body));
+
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), r#""'=HYPERLINK(""x"")""#);
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a-b"), "a-b");
+
+        let query = AuditQuery { to: Some("2026-01-31".to_string()), ..Default::default() };
+        assert_eq!(query.until().as_deref(), Some("2026-01-31 23:59:59"));
+    }
+}
@@ -0,0 +1,408 @@
+use serde::{Deserialize, Serialize};
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::fuzzer::FuzzSummary;
+use crate::pacing::{Pacer, Pacing};
+use crate::JobState;
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tauri::Emitter;
+
+/// A low-privilege response at least this alike to the most privileged
+/// one counts as the same data.
+const SAME_RESPONSE_SIMILARITY: f64 = 0.9;
+
+/// Credentials for one role within an environment.
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct AuthProfile {
+    pub id: Option<i64>,
+    pub environment_id: i64,
+    pub name: String,
+    /// Higher is more privileged, e.g. 0 anonymous, 1 user, 2 admin
+    pub privilege: i64,
+    /// Laid over each asset's captured headers after its own credentials
+    /// are removed; empty for an anonymous profile
+    #[sqlx(json)]
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuthMatrixRequest {
+    pub environment_id: i64,
+    pub asset_ids: Vec<i64>,
+    /// Lowest privilege each asset should need, by asset id. Assets not
+    /// listed that look administrative need the top profile's privilege.
+    #[serde(default)]
+    pub required_privilege: HashMap<i64, i64>,
+    #[serde(default)]
+    pub pacing: Pacing,
+    /// Scan profile the run must be allowed by; the default one when unset
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone, FromRow)]
+pub struct MatrixCell {
+    pub asset_id: i64,
+    pub profile_id: i64,
+    pub profile_name: String,
+    pub status: i64,
+    pub length: i64,
+}
+
+/// One asset replayed under every profile.
+#[derive(Debug, Serialize, Clone)]
+pub struct MatrixRow {
+    pub asset_id: i64,
+    pub method: String,
+    pub url: String,
+    pub cells: Vec<MatrixCell>,
+    pub findings: Vec<Finding>,
+}
+
+/// One profile's response to an asset, as judged by `judge_row`.
+pub struct RoleResponse<'a> {
+    pub profile: &'a str,
+    pub privilege: i64,
+    pub status: u16,
+    pub body: &'a str,
+}
+
+static ADMIN_PATH: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?i)/(admin|administrator|internal|manage|management|staff|superuser|sudo|debug|actuator|console|system)(/|\?|$)").unwrap()
+});
+
+/// Paths that are normally restricted to administrators.
+pub fn looks_administrative(url: &str) -> bool {
+    let path = url::Url::parse(url).map(|u| u.path().to_string()).unwrap_or_else(|_| url.to_string());
+    ADMIN_PATH.is_match(&path)
+}
+
+fn authz_finding(rule_id: &str, name: &str, severity: FindingSeverity, description: String, profiles: &[&str]) -> Finding {
+    Finding {
+        id: None,
+        rule_id: rule_id.to_string(),
+        name: name.to_string(),
+        description,
+        severity,
+        match_content: profiles.join(", "),
+        notes: None,
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+/// Flags profiles that were let in where a more privileged one was refused,
+/// and, given a required privilege, profiles below it that got the same
+/// response as the most privileged profile.
+pub fn judge_row(responses: &[RoleResponse], required: Option<i64>) -> Vec<Finding> {
+    let ok = |r: &&RoleResponse| (200..300).contains(&r.status);
+    let denied = |r: &&RoleResponse| matches!(r.status, 401 | 403);
+    let mut findings = Vec::new();
+
+    if let Some(refused) = responses.iter().filter(denied).max_by_key(|r| r.privilege) {
+        let mut allowed: Vec<&str> = responses.iter().filter(ok).filter(|r| r.privilege < refused.privilege).map(|r| r.profile).collect();
+        allowed.sort();
+        if !allowed.is_empty() {
+            findings.push(authz_finding(
+                "AUTHZ-INCONSISTENT",
+                "Inconsistent Authorization Across Roles",
+                FindingSeverity::Medium,
+                format!("'{}' was refused with {} while less privileged {} got through.", refused.profile, refused.status, allowed.join(", ")),
+                &allowed,
+            ));
+        }
+    }
+
+    let top = responses.iter().max_by_key(|r| r.privilege);
+    if let (Some(required), Some(top)) = (required, top.filter(ok)) {
+        let mut escalated: Vec<&str> = responses
+            .iter()
+            .filter(ok)
+            .filter(|r| r.privilege < required && crate::bola::body_similarity(r.body, top.body) >= SAME_RESPONSE_SIMILARITY)
+            .map(|r| r.profile)
+            .collect();
+        escalated.sort();
+        if !escalated.is_empty() {
+            findings.push(authz_finding(
+                "AUTHZ-PRIVILEGE-ESCALATION",
+                "Privileged Endpoint Accessible to Lower Role",
+                FindingSeverity::High,
+                format!("{} received the same response as '{}' although the endpoint needs privilege {}.", escalated.join(", "), top.profile, required),
+                &escalated,
+            ));
+        }
+    }
+    findings
+}
+
+#[tauri::command]
+pub async fn list_auth_profiles(environment_id: i64) -> Result<Vec<AuthProfile>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, AuthProfile>("SELECT id, environment_id, name, privilege, headers FROM auth_profiles WHERE environment_id = ? ORDER BY privilege, name")
+        .bind(environment_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_auth_profile(profile: AuthProfile) -> Result<i64, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    if profile.name.trim().is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+    let pool = get_db();
+    match profile.id {
+        Some(id) => {
+            sqlx::query("UPDATE auth_profiles SET name = ?, privilege = ?, headers = ? WHERE id = ?")
+                .bind(profile.name.trim())
+                .bind(profile.privilege)
+                .bind(sqlx::types::Json(&profile.headers))
+                .bind(id)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        None => {
+            let res = sqlx::query("INSERT INTO auth_profiles (environment_id, name, privilege, headers) VALUES (?, ?, ?, ?)")
+                .bind(profile.environment_id)
+                .bind(profile.name.trim())
+                .bind(profile.privilege)
+                .bind(sqlx::types::Json(&profile.headers))
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(res.last_insert_rowid())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn delete_auth_profile(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM auth_profiles WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The asset's captured headers without its own credentials, with the
+/// profile's laid over them.
+fn profile_headers(captured: &HashMap<String, String>, profile: &AuthProfile) -> HashMap<String, String> {
+    let mut headers: HashMap<String, String> = captured
+        .iter()
+        .filter(|(k, _)| {
+            let k = k.to_lowercase();
+            !crate::risk::AUTH_HEADERS.contains(&k.as_str()) && k != "content-length" && k != "host"
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    headers.extend(profile.headers.clone());
+    headers
+}
+
+/// Sends once `pacer` allows, retrying throttled responses. Status 0 means
+/// the request failed.
+pub(crate) async fn send(client: &reqwest::Client, method: &str, url: &str, headers: &HashMap<String, String>, body: Option<&String>, pacer: &Pacer) -> (u16, String) {
+    let method = reqwest::Method::from_bytes(method.as_bytes()).unwrap_or(reqwest::Method::GET);
+    let send_once = move || {
+        let mut req = client.request(method.clone(), url);
+        for (k, v) in headers {
+            req = req.header(k, v);
+        }
+        if let Some(body) = body {
+            req = req.body(body.clone());
+        }
+        async move {
+            match req.send().await {
+                Ok(res) => {
+                    let retry_after = res.headers().get("retry-after").and_then(|v| v.to_str().ok()).map(str::to_string);
+                    (res.status().as_u16(), retry_after, res.text().await.unwrap_or_default())
+                }
+                Err(_) => (0, None, String::new()),
+            }
+        }
+    };
+    let (status, _, body) = pacer.send(None, send_once, |(status, retry_after, _)| (*status, retry_after.clone())).await;
+    (status, body)
+}
+
+/// A stored request: url, method, headers and body.
+type StoredRequest = (String, Option<String>, Option<String>, Option<String>);
+
+async fn run_matrix(app_handle: tauri::AppHandle, run_id: &str, request: AuthMatrixRequest, profiles: Vec<AuthProfile>, cancel: &AtomicBool) -> Result<Vec<MatrixRow>, String> {
+    let pool = get_db();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let pacer = Pacer::new(&request.pacing);
+    let top_privilege = profiles.iter().map(|p| p.privilege).max().unwrap_or(0);
+
+    let mut rows = Vec::new();
+    let total = request.asset_ids.len();
+    for (i, asset_id) in request.asset_ids.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let asset: Option<StoredRequest> =
+            sqlx::query_as("SELECT url, method, req_headers, req_body FROM assets WHERE id = ? AND deleted_at IS NULL")
+                .bind(asset_id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        let Some((url, method, captured, body)) = asset else {
+            continue;
+        };
+        let method = method.unwrap_or_else(|| "GET".to_string()).to_uppercase();
+        let captured: HashMap<String, String> = captured.and_then(|h| serde_json::from_str(&h).ok()).unwrap_or_default();
+
+        let mut responses = Vec::new();
+        for profile in &profiles {
+            let headers = profile_headers(&captured, profile);
+            // The anonymous profile must not reuse query-string credentials either
+            let target = if profile.headers.is_empty() { crate::risk::strip_auth_params(&url) } else { url.clone() };
+            let (status, res_body) = send(&client, &method, &target, &headers, body.as_ref(), &pacer).await;
+            responses.push((profile, status, res_body));
+        }
+
+        let required = request
+            .required_privilege
+            .get(asset_id)
+            .copied()
+            .or_else(|| looks_administrative(&url).then_some(top_privilege));
+        let judged: Vec<RoleResponse> = responses
+            .iter()
+            .map(|(p, status, body)| RoleResponse { profile: &p.name, privilege: p.privilege, status: *status, body })
+            .collect();
+        let mut findings = judge_row(&judged, required);
+        crate::analysis::attach_rule_metadata(&mut findings);
+        for f in &findings {
+            crate::assets::store_finding(&pool, *asset_id, f).await?;
+        }
+        if !findings.is_empty() {
+            crate::risk::refresh_risk_scores(&pool, Some(*asset_id)).await?;
+        }
+
+        let mut cells = Vec::new();
+        for (profile, status, res_body) in &responses {
+            let profile_id = profile.id.unwrap_or_default();
+            sqlx::query("INSERT INTO auth_matrix_cells (run_id, asset_id, profile_id, status, length) VALUES (?, ?, ?, ?, ?)")
+                .bind(run_id)
+                .bind(asset_id)
+                .bind(profile_id)
+                .bind(*status as i64)
+                .bind(res_body.len() as i64)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            cells.push(MatrixCell {
+                asset_id: *asset_id,
+                profile_id,
+                profile_name: profile.name.clone(),
+                status: *status as i64,
+                length: res_body.len() as i64,
+            });
+        }
+
+        let row = MatrixRow { asset_id: *asset_id, method, url, cells, findings };
+        rows.push(row.clone());
+        let _ = app_handle.emit("authz-progress", (i + 1, total, row));
+    }
+    Ok(rows)
+}
+
+/// Replays each asset under every auth profile of the environment in the
+/// background and returns the run id. Rows stream through `authz-progress`,
+/// the summary arrives with `fuzz-complete`, and `cancel_fuzz` stops it.
+#[tauri::command]
+pub async fn run_auth_matrix(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<JobState>>, mut request: AuthMatrixRequest) -> Result<String, String> {
+    let scan_profile = crate::scan_profiles::authorize(request.scan_profile_id, crate::scan_profiles::ActiveCheck::AuthMatrix).await?;
+    request.pacing = scan_profile.cap_pacing(&request.pacing);
+    let profiles = list_auth_profiles(request.environment_id).await?;
+    if profiles.len() < 2 {
+        return Err("Add at least two auth profiles to the environment".to_string());
+    }
+    if request.asset_ids.is_empty() {
+        return Err("Select at least one asset".to_string());
+    }
+    let total = request.asset_ids.len();
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "fuzz-complete",
+        move |app_handle, job_id, cancel| async move { run_matrix(app_handle, &job_id, request, profiles, &cancel).await },
+        move |job_id, outcome, cancelled| {
+            let counts = outcome.map(|rows| (rows.len(), rows.iter().map(|r| r.findings.len()).sum(), 0));
+            FuzzSummary::new(job_id, total, counts, cancelled)
+        },
+    ))
+}
+
+/// Cells recorded by a matrix run, by asset then profile privilege.
+#[tauri::command]
+pub async fn get_auth_matrix(run_id: String) -> Result<Vec<MatrixCell>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, MatrixCell>(
+        "SELECT c.asset_id, c.profile_id, COALESCE(p.name, '') AS profile_name, c.status, c.length
+         FROM auth_matrix_cells c LEFT JOIN auth_profiles p ON p.id = c.profile_id
+         WHERE c.run_id = ? ORDER BY c.asset_id, p.privilege, p.name",
+    )
+    .bind(run_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role<'a>(profile: &'a str, privilege: i64, status: u16, body: &'a str) -> RoleResponse<'a> {
+        RoleResponse { profile, privilege, status, body }
+    }
+
+    #[test]
+    fn test_looks_administrative() {
+        assert!(looks_administrative("https://api.example.com/v1/admin/users"));
+        assert!(looks_administrative("https://api.example.com/actuator?x=1"));
+        assert!(!looks_administrative("https://api.example.com/v1/administrators-guide"));
+        assert!(!looks_administrative("https://api.example.com/v1/orders"));
+    }
+
+    #[test]
+    fn test_judge_row() {
+        let users = r#"{"users": [{"id": 1, "email": "a@example.com"}]}"#;
+        let rules = |responses: &[RoleResponse], required| judge_row(responses, required).into_iter().map(|f| (f.rule_id, f.match_content)).collect::<Vec<_>>();
+
+        let enforced = [role("anonymous", 0, 401, ""), role("user", 1, 403, ""), role("admin", 2, 200, users)];
+        assert!(rules(&enforced, Some(2)).is_empty());
+
+        let leaky = [role("anonymous", 0, 401, ""), role("user", 1, 200, users), role("admin", 2, 200, users)];
+        assert_eq!(rules(&leaky, Some(2)), vec![("AUTHZ-PRIVILEGE-ESCALATION".to_string(), "user".to_string())]);
+        // Without a required privilege user access is assumed intended
+        assert!(rules(&leaky, None).is_empty());
+
+        let inconsistent = [role("anonymous", 0, 200, users), role("user", 1, 403, ""), role("admin", 2, 200, users)];
+        assert_eq!(
+            rules(&inconsistent, None),
+            vec![("AUTHZ-INCONSISTENT".to_string(), "anonymous".to_string())]
+        );
+
+        // A filtered view for the user isn't the admin's data
+        let scoped = [role("user", 1, 200, r#"{"users": []}"#), role("admin", 2, 200, users)];
+        assert!(rules(&scoped, Some(2)).is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+/// Automatic snapshot schedule. Scheduled snapshots are off while
+/// `interval_hours` is unset; pre-destructive snapshots always run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupSettings {
+    pub interval_hours: Option<i64>,
+    /// Oldest snapshots beyond this count are deleted
+    pub keep: i64,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self { interval_hours: None, keep: 10 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub reason: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// Snapshots of a workspace live in `backups/<workspace>/` next to the workspace files.
+fn backup_dir(app_handle: &AppHandle) -> PathBuf {
+    let workspace = crate::db::get_current_workspace();
+    let safe_name = workspace.replace(|c: char| !c.is_alphanumeric(), "_");
+    app_handle.path().app_data_dir().unwrap().join("backups").join(safe_name)
+}
+
+#[tauri::command]
+pub async fn get_backup_settings() -> Result<BackupSettings, String> {
+    crate::settings::get("backup").await
+}
+
+#[tauri::command]
+pub async fn set_backup_settings(settings: BackupSettings) -> Result<(), String> {
+    let value = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    crate::settings::write(&get_db(), "backup", value).await
+}
+
+/// Copies the active workspace into its backup folder, which yields a
+/// consistent file while the workspace stays in use, then prunes snapshots
+/// beyond the configured count. Snapshots of an encrypted workspace are
+/// encrypted with the same key.
+pub async fn snapshot(app_handle: &AppHandle, reason: &str) -> Result<BackupInfo, String> {
+    let dir = backup_dir(app_handle);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    // Millisecond resolution so snapshots taken back to back don't collide
+    let file_name = format!("{}-{}.db", chrono::Local::now().format("%Y%m%d-%H%M%S%3f"), reason);
+    let path = dir.join(&file_name);
+    let pool = get_db();
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    crate::db::copy_database(&mut conn, &path, crate::db::workspace_key().as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(conn);
+
+    let keep = get_backup_settings().await?.keep.max(1) as usize;
+    let mut backups = list_backups(app_handle.clone())?;
+    if backups.len() > keep {
+        // Newest first, so everything past `keep` is the oldest
+        for old in backups.drain(keep..) {
+            let _ = fs::remove_file(dir.join(old.file_name));
+        }
+    }
+
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupInfo {
+        file_name,
+        reason: reason.to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        size_bytes,
+    })
+}
+
+#[tauri::command]
+pub async fn create_backup(app_handle: AppHandle) -> Result<BackupInfo, String> {
+    snapshot(&app_handle, "manual").await
+}
+
+/// Snapshots of the active workspace, newest first.
+#[tauri::command]
+pub fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let dir = backup_dir(&app_handle);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("db") {
+            continue;
+        }
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()).map(|s| s.to_string()) else { continue };
+        let meta = entry.metadata().map_err(|e| e.to_string())?;
+        let created: chrono::DateTime<chrono::Local> = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH).into();
+        // <date>-<time>-<reason>.db
+        let reason = file_name
+            .trim_end_matches(".db")
+            .splitn(3, '-')
+            .nth(2)
+            .unwrap_or("")
+            .to_string();
+        backups.push(BackupInfo {
+            file_name,
+            reason,
+            created_at: created.to_rfc3339(),
+            size_bytes: meta.len(),
+        });
+    }
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(backups)
+}
+
+/// Replaces the active workspace with one of its snapshots. The current
+/// state is snapshotted first so a restore can itself be undone.
+#[tauri::command]
+pub async fn restore_backup(app_handle: AppHandle, file_name: String, passphrase: Option<String>) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    if file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err("Invalid backup name".to_string());
+    }
+    let source = backup_dir(&app_handle).join(&file_name);
+    if !source.exists() {
+        return Err(format!("Backup {} not found", file_name));
+    }
+
+    if passphrase.as_deref().unwrap_or("").is_empty() && crate::db::is_encrypted_file(&source) {
+        return Err(format!("Backup {} is encrypted; a passphrase is required", file_name));
+    }
+
+    // Staged first: pruning after the safety snapshot may remove the source
+    let staged = source.with_extension("restoring");
+    fs::copy(&source, &staged).map_err(|e| e.to_string())?;
+    if let Err(e) = snapshot(&app_handle, "pre-restore").await {
+        let _ = fs::remove_file(&staged);
+        return Err(e);
+    }
+
+    let workspace = crate::db::get_current_workspace();
+    let db_path = crate::db::workspace_path(&app_handle, &workspace);
+    crate::db::release_workspace(&workspace).await;
+    crate::db::remove_sidecar_files(&db_path);
+    let copied = fs::rename(&staged, &db_path).map_err(|e| e.to_string());
+
+    crate::db::init_db(&app_handle, &workspace, passphrase.as_deref().filter(|p| !p.is_empty()))
+        .await
+        .map_err(|e| e.to_string())?;
+    copied
+}
+
+/// Takes a scheduled snapshot whenever the newest one is older than the
+/// configured interval. Runs for the lifetime of the app.
+pub async fn run_scheduler(app_handle: AppHandle) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        let Ok(settings) = get_backup_settings().await else { continue };
+        let Some(hours) = settings.interval_hours.filter(|h| *h > 0) else { continue };
+
+        let newest = list_backups(app_handle.clone())
+            .ok()
+            .and_then(|b| b.into_iter().next())
+            .and_then(|b| chrono::DateTime::parse_from_rfc3339(&b.created_at).ok());
+        let due = match newest {
+            Some(at) => chrono::Local::now().signed_duration_since(at) >= chrono::Duration::hours(hours),
+            None => true,
+        };
+        if due {
+            if let Err(e) = snapshot(&app_handle, "scheduled").await {
+                eprintln!("Scheduled backup failed: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Batch active scanning: the per-asset checks a scan profile allows, run
+//! across a selection of the inventory under the profile's concurrency and
+//! rate, and summed up in a stored scan report.
+
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::pacing::Pacer;
+use crate::scan_profiles::ActiveCheck;
+use crate::JobState;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tauri::Emitter;
+
+/// Checks a batch runs on each asset. The others need per-run input, such
+/// as payloads, a second identity or a target rate, and are skipped.
+const PER_ASSET_CHECKS: [ActiveCheck; 4] = [ActiveCheck::SecurityHeaders, ActiveCheck::WebCache, ActiveCheck::HttpMethods, ActiveCheck::UnauthAccess];
+
+/// Checks that send mutating verbs, run only when the request opts in.
+const UNSAFE_CHECKS: [ActiveCheck; 1] = [ActiveCheck::HttpMethods];
+
+/// Captured methods replayed without opting in.
+const SAFE_METHODS: [&str; 3] = ["GET", "HEAD", "OPTIONS"];
+
+/// Which assets to scan; the ids and filters narrow each other down.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BatchScanRequest {
+    #[serde(default)]
+    pub asset_ids: Vec<i64>,
+    /// Tag name
+    pub tag: Option<String>,
+    pub folder_id: Option<i64>,
+    /// Only assets on this host or its subdomains
+    pub host: Option<String>,
+    /// Profile deciding the checks, concurrency and rate; the default one when unset
+    pub scan_profile_id: Option<i64>,
+    /// Also send mutating verbs and replay assets captured with unsafe methods
+    #[serde(default)]
+    pub include_unsafe: bool,
+}
+
+impl BatchScanRequest {
+    fn host(&self) -> Option<&str> {
+        self.host.as_deref().map(str::trim).filter(|h| !h.is_empty())
+    }
+
+    fn tag(&self) -> Option<&str> {
+        self.tag.as_deref().map(str::trim).filter(|t| !t.is_empty())
+    }
+
+    fn is_unscoped(&self) -> bool {
+        self.asset_ids.is_empty() && self.tag().is_none() && self.folder_id.is_none() && self.host().is_none()
+    }
+}
+
+/// What decides which checks apply to an asset.
+#[derive(Debug, Clone, FromRow)]
+pub struct Target {
+    pub asset_id: i64,
+    pub url: String,
+    pub method: Option<String>,
+    pub req_headers: Option<String>,
+}
+
+impl Target {
+    fn is_get(&self) -> bool {
+        self.method.as_deref().unwrap_or("GET").eq_ignore_ascii_case("GET")
+    }
+
+    fn is_safe_method(&self) -> bool {
+        SAFE_METHODS.contains(&self.method.as_deref().unwrap_or("GET").to_uppercase().as_str())
+    }
+
+    fn is_credentialed(&self) -> bool {
+        crate::risk::is_authenticated(&self.url, &crate::assets::captured_headers(self.req_headers.clone()))
+    }
+}
+
+/// Whether `url`'s host is `host` or one of its subdomains.
+pub fn host_matches(url: &str, host: &str) -> bool {
+    let Some(url_host) = url::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.trim_matches(['[', ']']).to_lowercase())) else {
+        return false;
+    };
+    let host = host.trim().trim_matches('.').to_lowercase();
+    url_host == host || url_host.strip_suffix(&host).is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// The per-asset checks a batch may run under `allowed`.
+fn batch_checks(allowed: &[ActiveCheck], include_unsafe: bool) -> Vec<ActiveCheck> {
+    PER_ASSET_CHECKS.into_iter().filter(|c| allowed.contains(c) && (include_unsafe || !UNSAFE_CHECKS.contains(c))).collect()
+}
+
+/// The checks to run, in order: every allowed per-asset check on every
+/// target it applies to. Cache probes need a GET, unauthenticated replays
+/// a captured credential and, unless `include_unsafe`, a safe method.
+pub fn plan(targets: &[Target], allowed: &[ActiveCheck], include_unsafe: bool) -> Vec<(Target, ActiveCheck)> {
+    let checks = batch_checks(allowed, include_unsafe);
+    let mut queue = Vec::new();
+    for target in targets {
+        for check in &checks {
+            let applies = match check {
+                ActiveCheck::WebCache => target.is_get(),
+                ActiveCheck::UnauthAccess => target.is_credentialed() && (include_unsafe || target.is_safe_method()),
+                _ => true,
+            };
+            if applies {
+                queue.push((target.clone(), *check));
+            }
+        }
+    }
+    queue
+}
+
+/// One check run against one asset.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckOutcome {
+    pub asset_id: i64,
+    pub url: String,
+    pub check: ActiveCheck,
+    pub findings: Vec<Finding>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchScanProgress {
+    pub job_id: String,
+    pub completed: usize,
+    pub total: usize,
+    pub outcome: CheckOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct ScanReport {
+    /// Job id the scan ran under
+    pub id: String,
+    pub profile_name: String,
+    #[sqlx(json)]
+    pub request: BatchScanRequest,
+    pub asset_count: i64,
+    /// Checks queued
+    pub check_count: i64,
+    pub completed: i64,
+    pub failed: i64,
+    pub high: i64,
+    pub medium: i64,
+    pub low: i64,
+    pub info: i64,
+    /// Allowed by the profile but not run per asset
+    #[sqlx(json)]
+    pub skipped_checks: Vec<ActiveCheck>,
+    #[sqlx(json)]
+    pub outcomes: Vec<CheckOutcome>,
+    pub cancelled: bool,
+    pub created_at: Option<String>,
+}
+
+/// Sent with `batch-scan-complete`.
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchScanComplete {
+    pub job_id: String,
+    pub report: Option<ScanReport>,
+    pub error: Option<String>,
+}
+
+/// Findings of `outcomes` by severity: high, medium, low, info.
+pub fn tally(outcomes: &[CheckOutcome]) -> [i64; 4] {
+    let mut counts = [0; 4];
+    for f in outcomes.iter().flat_map(|o| &o.findings) {
+        let i = match f.severity {
+            FindingSeverity::High => 0,
+            FindingSeverity::Medium => 1,
+            FindingSeverity::Low => 2,
+            FindingSeverity::Info => 3,
+        };
+        counts[i] += 1;
+    }
+    counts
+}
+
+async fn select_targets(request: &BatchScanRequest) -> Result<Vec<Target>, String> {
+    let targets = sqlx::query_as::<_, Target>(
+        "SELECT a.id AS asset_id, a.url, a.method, a.req_headers FROM assets a
+         WHERE a.deleted_at IS NULL
+           AND (? IS NULL OR a.folder_id = ?)
+           AND (? IS NULL OR a.id IN (SELECT at.asset_id FROM asset_tags at JOIN tags t ON t.id = at.tag_id WHERE t.name = ?))
+         ORDER BY a.id",
+    )
+    .bind(request.folder_id)
+    .bind(request.folder_id)
+    .bind(request.tag())
+    .bind(request.tag())
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(targets
+        .into_iter()
+        .filter(|t| request.asset_ids.is_empty() || request.asset_ids.contains(&t.asset_id))
+        .filter(|t| request.host().is_none_or(|host| host_matches(&t.url, host)))
+        .collect())
+}
+
+async fn run_check(target: &Target, check: ActiveCheck, pacer: &Pacer, include_unsafe: bool) -> Result<Vec<Finding>, String> {
+    match check {
+        ActiveCheck::SecurityHeaders => crate::security_headers::audit_asset(target.asset_id, pacer).await,
+        ActiveCheck::WebCache => crate::cache_probe::probe_asset(target.asset_id, pacer).await,
+        ActiveCheck::HttpMethods => crate::methods::scan_asset(target.asset_id, pacer, false).await.map(|scan| scan.findings),
+        ActiveCheck::UnauthAccess => {
+            crate::unauth::replay_asset(target.asset_id, pacer, include_unsafe).await.map(|row| row.map(|r| r.findings).unwrap_or_default())
+        }
+        other => Err(format!("{} can't run in a batch", other.label())),
+    }
+}
+
+async fn save_report(report: &ScanReport) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO scan_reports (id, profile_name, request, asset_count, check_count, completed, failed, high, medium, low, info, skipped_checks, outcomes, cancelled)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&report.id)
+    .bind(&report.profile_name)
+    .bind(sqlx::types::Json(&report.request))
+    .bind(report.asset_count)
+    .bind(report.check_count)
+    .bind(report.completed)
+    .bind(report.failed)
+    .bind(report.high)
+    .bind(report.medium)
+    .bind(report.low)
+    .bind(report.info)
+    .bind(sqlx::types::Json(&report.skipped_checks))
+    .bind(sqlx::types::Json(&report.outcomes))
+    .bind(report.cancelled)
+    .execute(&get_db())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Starts a batch scan of the selected assets in the background and
+/// returns its job id. Each finished check streams through
+/// `batch-scan-progress`, the stored report arrives with
+/// `batch-scan-complete`, and `cancel_fuzz` stops queuing further checks.
+/// HTTP method enumeration, and unauthenticated replays of assets captured
+/// with unsafe methods, only run when the request sets `include_unsafe`.
+#[tauri::command]
+pub async fn run_batch_scan(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<JobState>>, request: BatchScanRequest) -> Result<String, String> {
+    if request.is_unscoped() {
+        return Err("Select assets, a tag, a folder or a host to scan".to_string());
+    }
+    let profile = crate::scan_profiles::load(request.scan_profile_id).await?;
+    let targets = select_targets(&request).await?;
+    if targets.is_empty() {
+        return Err("No assets match the selection".to_string());
+    }
+    let queue = plan(&targets, &profile.checks, request.include_unsafe);
+    if queue.is_empty() {
+        return Err(format!("The '{}' scan profile allows no check that applies to the selected assets", profile.name));
+    }
+    let checks = batch_checks(&profile.checks, request.include_unsafe);
+    let skipped_checks: Vec<ActiveCheck> = profile.checks.iter().copied().filter(|c| !checks.contains(c)).collect();
+    let total = queue.len();
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "batch-scan-complete",
+        move |app_handle, job_id, cancel| async move {
+            let pacer = Arc::new(Pacer::new(&profile.pacing));
+            let include_unsafe = request.include_unsafe;
+            let mut workers = crate::jobs::Workers::new(queue, profile.concurrency as usize, |(target, check): (Target, ActiveCheck)| {
+                let pacer = pacer.clone();
+                async move {
+                    let (findings, error) = match run_check(&target, check, &pacer, include_unsafe).await {
+                        Ok(findings) => (findings, None),
+                        Err(e) => (Vec::new(), Some(e)),
+                    };
+                    CheckOutcome { asset_id: target.asset_id, url: target.url, check, findings, error }
+                }
+            });
+
+            let mut outcomes = Vec::new();
+            while let Some(joined) = workers.next(&cancel).await {
+                let Ok(outcome) = joined else { continue };
+                outcomes.push(outcome.clone());
+                let _ = app_handle.emit("batch-scan-progress", BatchScanProgress { job_id: job_id.clone(), completed: outcomes.len(), total, outcome });
+            }
+
+            let [high, medium, low, info] = tally(&outcomes);
+            let report = ScanReport {
+                id: job_id,
+                profile_name: profile.name,
+                request,
+                asset_count: targets.len() as i64,
+                check_count: total as i64,
+                completed: outcomes.len() as i64,
+                failed: outcomes.iter().filter(|o| o.error.is_some()).count() as i64,
+                high,
+                medium,
+                low,
+                info,
+                skipped_checks,
+                outcomes,
+                cancelled: cancel.load(Ordering::Relaxed),
+                created_at: None,
+            };
+            save_report(&report).await.map(|()| report)
+        },
+        |job_id, outcome, _| {
+            let (report, error) = match outcome {
+                Ok(report) => (Some(report), None),
+                Err(e) => (None, Some(e)),
+            };
+            BatchScanComplete { job_id, report, error }
+        },
+    ))
+}
+
+/// Stored batch scan reports, newest first.
+#[tauri::command]
+pub async fn list_scan_reports() -> Result<Vec<ScanReport>, String> {
+    sqlx::query_as::<_, ScanReport>("SELECT * FROM scan_reports ORDER BY created_at DESC, rowid DESC LIMIT 50")
+        .fetch_all(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_scan_report(id: String) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    sqlx::query("DELETE FROM scan_reports WHERE id = ?")
+        .bind(id)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(asset_id: i64, method: &str, headers: &str) -> Target {
+        Target { asset_id, url: format!("https://api.example.com/{}", asset_id), method: Some(method.to_string()), req_headers: Some(headers.to_string()) }
+    }
+
+    #[test]
+    fn test_plan() {
+        let targets = [target(1, "GET", r#"{"Authorization": "Bearer x"}"#), target(2, "POST", r#"{"Accept": "*/*"}"#)];
+        let checks = |queue: Vec<(Target, ActiveCheck)>| queue.into_iter().map(|(t, c)| (t.asset_id, c)).collect::<Vec<_>>();
+
+        let safe = [ActiveCheck::SecurityHeaders, ActiveCheck::WebCache];
+        assert_eq!(
+            checks(plan(&targets, &safe, false)),
+            [(1, ActiveCheck::SecurityHeaders), (1, ActiveCheck::WebCache), (2, ActiveCheck::SecurityHeaders)]
+        );
+        let full = plan(&targets, &ActiveCheck::ALL, true);
+        assert_eq!(full.len(), 6);
+        assert!(checks(full).contains(&(1, ActiveCheck::UnauthAccess)));
+        assert!(plan(&targets, &[ActiveCheck::Fuzz, ActiveCheck::RateLimit], true).is_empty());
+
+        // Without opting in, no verb enumeration and no replay of a captured POST
+        let post = [target(3, "POST", r#"{"Cookie": "session=x"}"#)];
+        assert_eq!(checks(plan(&post, &ActiveCheck::ALL, false)), [(3, ActiveCheck::SecurityHeaders)]);
+        assert!(checks(plan(&post, &ActiveCheck::ALL, true)).contains(&(3, ActiveCheck::UnauthAccess)));
+    }
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("https://api.example.com/v1", "api.example.com"));
+        assert!(host_matches("https://v2.API.example.com:8443/", "api.example.com."));
+        assert!(!host_matches("https://other.net/?r=api.example.com", "api.example.com"));
+        assert!(!host_matches("https://api.example.com.evil.net/", "api.example.com"));
+        assert!(!host_matches("https://myapi.example.com/", "api.example.com"));
+    }
+
+    #[test]
+    fn test_tally() {
+        let finding = |severity| Finding::new("R", "R", severity, String::new(), "");
+        let outcome = |findings| CheckOutcome { asset_id: 1, url: String::new(), check: ActiveCheck::SecurityHeaders, findings, error: None };
+        let outcomes = [
+            outcome(vec![finding(FindingSeverity::High), finding(FindingSeverity::Low)]),
+            outcome(vec![finding(FindingSeverity::High)]),
+            outcome(vec![]),
+        ];
+        assert_eq!(tally(&outcomes), [2, 0, 1, 0]);
+    }
+}
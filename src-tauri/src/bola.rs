@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use crate::analysis::{Finding, FindingSeverity};
+use crate::fuzzer::{FuzzSummary, FuzzTask};
+use crate::pacing::Pacer;
+use crate::JobState;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// Body similarity above which the attacker is taken to have received the
+/// victim's object.
+const SAME_OBJECT_SIMILARITY: f64 = 0.9;
+
+fn default_neighbours() -> usize {
+    5
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BolaTask {
+    /// Request with the object id wrapped in markers, e.g. `/orders/§1001§`
+    pub task: FuzzTask,
+    /// Credentials of the object's owner
+    pub victim_headers: HashMap<String, String>,
+    /// Credentials of another user, who should be refused
+    pub attacker_headers: HashMap<String, String>,
+    /// Ids to try; when empty, the marked id and its numeric neighbours
+    #[serde(default)]
+    pub ids: Vec<String>,
+    #[serde(default = "default_neighbours")]
+    pub neighbours: usize,
+    /// Scan profile the run must be allowed by; the default one when unset
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BolaVerdict {
+    /// The attacker got the object the victim did
+    Vulnerable,
+    /// The attacker was refused
+    Enforced,
+    /// The victim couldn't read it either, or the responses differ
+    Inconclusive,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BolaResult {
+    pub object_id: String,
+    pub victim_status: u16,
+    pub attacker_status: u16,
+    /// 0-1, how alike the two response bodies are
+    pub similarity: f64,
+    pub verdict: BolaVerdict,
+    pub finding: Option<Finding>,
+}
+
+/// Ids to probe: the explicit list, else the original and, for numeric
+/// ids, up to `neighbours` on either side.
+pub fn candidate_ids(original: &str, ids: &[String], neighbours: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    if !ids.is_empty() {
+        return ids.iter().map(|id| id.trim().to_string()).filter(|id| !id.is_empty() && seen.insert(id.clone())).collect();
+    }
+    let mut out = vec![original.to_string()];
+    if let Ok(n) = original.parse::<u64>() {
+        let n = n as i128;
+        for offset in 1..=neighbours as i128 {
+            for candidate in [n - offset, n + offset] {
+                if candidate >= 0 && candidate <= u64::MAX as i128 {
+                    out.push(candidate.to_string());
+                }
+            }
+        }
+    }
+    out
+}
+
+fn tokens(s: &str) -> HashSet<&str> {
+    s.split(|c: char| !c.is_alphanumeric()).filter(|t| !t.is_empty()).collect()
+}
+
+/// 1 for identical bodies (or equal JSON), otherwise the Jaccard index of
+/// their alphanumeric tokens.
+pub fn body_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    if let (Ok(x), Ok(y)) = (serde_json::from_str::<serde_json::Value>(a), serde_json::from_str::<serde_json::Value>(b)) {
+        if x == y {
+            return 1.0;
+        }
+    }
+    let (ta, tb) = (tokens(a), tokens(b));
+    let union = ta.union(&tb).count();
+    if union == 0 {
+        return 0.0;
+    }
+    ta.intersection(&tb).count() as f64 / union as f64
+}
+
+pub fn judge(victim_status: u16, attacker_status: u16, similarity: f64) -> BolaVerdict {
+    let ok = |s: u16| (200..300).contains(&s);
+    match (ok(victim_status), attacker_status) {
+        (false, _) => BolaVerdict::Inconclusive,
+        (true, 401 | 403 | 404) => BolaVerdict::Enforced,
+        (true, s) if ok(s) && similarity >= SAME_OBJECT_SIMILARITY => BolaVerdict::Vulnerable,
+        _ => BolaVerdict::Inconclusive,
+    }
+}
+
+/// The task's headers with a credential set laid over them.
+fn with_context(task: &FuzzTask, context: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut headers: HashMap<String, String> = task
+        .headers
+        .iter()
+        .filter(|(k, _)| !context.keys().any(|c| c.eq_ignore_ascii_case(k)))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    headers.extend(context.clone());
+    headers
+}
+
+/// Sends once the pacer allows it, retrying throttled responses. Deferred
+/// `$` builtins get fresh values.
+async fn send(client: &reqwest::Client, task: &FuzzTask, headers: &HashMap<String, String>, pacer: &Pacer) -> (u16, String) {
+    let task = crate::variables::fresh_task(task);
+    let headers = crate::variables::fresh_headers(headers);
+    crate::authz::send(client, &task.method, &task.url, &headers, task.body.as_ref(), pacer).await
+}
+
+fn bola_finding(task: &FuzzTask, object_id: &str, similarity: f64) -> Finding {
+    Finding {
+        id: None,
+        rule_id: "ACTIVE-BOLA".to_string(),
+        name: "Broken Object Level Authorization Confirmed".to_string(),
+        description: format!(
+            "{} {} returned object '{}' to the attacker's credentials as well as the victim's.",
+            task.method.to_uppercase(), task.url, object_id
+        ),
+        severity: FindingSeverity::High,
+        match_content: object_id.to_string(),
+        notes: Some(format!("Attacker and victim responses were {:.0}% similar.", similarity * 100.0)),
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+async fn run_bola_test(app_handle: tauri::AppHandle, bola: BolaTask, ids: Vec<String>, cancel: &AtomicBool) -> Result<Vec<BolaResult>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let victim_headers = with_context(&bola.task, &bola.victim_headers);
+    let attacker_headers = with_context(&bola.task, &bola.attacker_headers);
+    let pacer = Pacer::new(&bola.task.pacing);
+
+    let mut results = Vec::new();
+    let total = ids.len();
+    for (i, object_id) in ids.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let task = crate::fuzzer::render_task(&bola.task, 0, object_id);
+        let (victim_status, victim_body) = send(&client, &task, &victim_headers, &pacer).await;
+        let (attacker_status, attacker_body) = send(&client, &task, &attacker_headers, &pacer).await;
+        let similarity = body_similarity(&victim_body, &attacker_body);
+        let verdict = judge(victim_status, attacker_status, similarity);
+
+        let mut finding = (verdict == BolaVerdict::Vulnerable).then(|| bola_finding(&task, object_id, similarity));
+        crate::analysis::attach_rule_metadata(finding.as_mut_slice());
+        let res = BolaResult { object_id: object_id.clone(), victim_status, attacker_status, similarity, verdict, finding };
+        results.push(res.clone());
+        let _ = app_handle.emit("bola-progress", (i + 1, total, res));
+    }
+    Ok(results)
+}
+
+/// Replays a request carrying an object id under the victim's and the
+/// attacker's credentials for each candidate id, flagging ids the attacker
+/// can read. Runs in the background like `run_active_fuzz`: results stream
+/// through `bola-progress`, the summary through `fuzz-complete`, and
+/// `cancel_fuzz` stops it.
+#[tauri::command]
+pub async fn run_bola_fuzz(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<JobState>>, mut bola: BolaTask) -> Result<String, String> {
+    let profile = crate::scan_profiles::authorize(bola.scan_profile_id, crate::scan_profiles::ActiveCheck::Bola).await?;
+    bola.task.pacing = profile.cap_pacing(&bola.task.pacing);
+    let mut resolver = crate::variables::Resolver::active().await?.defer_builtins();
+    resolver.resolve_task(&mut bola.task)?;
+    bola.victim_headers = resolver.resolve_headers(std::mem::take(&mut bola.victim_headers))?;
+    bola.attacker_headers = resolver.resolve_headers(std::mem::take(&mut bola.attacker_headers))?;
+    let points = crate::fuzzer::insertion_points(&bola.task)?;
+    let [point] = points.as_slice() else {
+        return Err(format!("Mark exactly one object id with {}, e.g. /orders/{}1001{}", crate::fuzzer::MARKER, crate::fuzzer::MARKER, crate::fuzzer::MARKER));
+    };
+    if bola.victim_headers.is_empty() || bola.attacker_headers.is_empty() {
+        return Err("Both victim and attacker credentials are required".to_string());
+    }
+    if bola.victim_headers == bola.attacker_headers {
+        return Err("Victim and attacker credentials are identical".to_string());
+    }
+    let ids = candidate_ids(&point.original, &bola.ids, bola.neighbours);
+    let total = ids.len();
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "fuzz-complete",
+        move |app_handle, _, cancel| async move { run_bola_test(app_handle, bola, ids, &cancel).await },
+        move |job_id, outcome, cancelled| {
+            let counts = outcome.map(|results| (results.len(), results.iter().filter(|r| r.finding.is_some()).count(), 0));
+            FuzzSummary::new(job_id, total, counts, cancelled)
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_ids() {
+        assert_eq!(candidate_ids("2", &[], 3), vec!["2", "1", "3", "0", "4", "5"]);
+        assert_eq!(candidate_ids("a1b2", &[], 3), vec!["a1b2"]);
+        let explicit = vec!["7".to_string(), " 7 ".to_string(), "9".to_string()];
+        assert_eq!(candidate_ids("2", &explicit, 3), vec!["7", "9"]);
+    }
+
+    #[test]
+    fn test_judge() {
+        let victim = r#"{"id": 7, "owner": "alice", "total": 12.5}"#;
+        let same = r#"{"owner": "alice", "total": 12.5, "id": 7}"#;
+        assert_eq!(body_similarity(victim, same), 1.0);
+        assert_eq!(judge(200, 200, body_similarity(victim, same)), BolaVerdict::Vulnerable);
+        assert_eq!(judge(200, 403, 0.0), BolaVerdict::Enforced);
+        assert_eq!(judge(404, 200, 1.0), BolaVerdict::Inconclusive);
+        let own = r#"{"id": 8, "owner": "mallory", "total": 3}"#;
+        assert_eq!(judge(200, 200, body_similarity(victim, own)), BolaVerdict::Inconclusive);
+    }
+}
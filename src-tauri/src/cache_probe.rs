@@ -0,0 +1,323 @@
+//! Web cache deception and poisoning: static-looking path variants that
+//! still serve the user's response, and unkeyed headers that change what
+//! the cache stores.
+
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::pacing::{Pacer, Pacing};
+use crate::scan_profiles::ActiveCheck;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A replay at least this alike to the authenticated response counts as
+/// the same content.
+const SAME_RESPONSE_SIMILARITY: f64 = 0.9;
+
+/// Headers caches commonly leave out of the key, and the value sent in
+/// each. `{canary}` is replaced by a host unique to the run.
+const UNKEYED_HEADERS: &[(&str, &str)] = &[
+    ("X-Forwarded-Host", "{canary}"),
+    ("X-Host", "{canary}"),
+    ("X-Forwarded-Server", "{canary}"),
+    ("X-Original-URL", "/{canary}"),
+    ("X-Rewrite-URL", "/{canary}"),
+    ("X-Forwarded-Scheme", "http"),
+    ("X-Forwarded-Proto", "http"),
+    ("X-Forwarded-Port", "1337"),
+];
+
+/// One response as seen by the probe.
+#[derive(Debug, Clone, Default)]
+pub struct Probe {
+    pub status: u16,
+    /// Lowercased names, as from `security_headers::normalize`
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// What a response's headers say about shared caching.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CacheSignals {
+    /// A shared cache may store it
+    pub cacheable: bool,
+    /// It was served from a cache
+    pub hit: bool,
+}
+
+pub fn cache_signals(headers: &HashMap<String, String>) -> CacheSignals {
+    let cache_control = headers.get("cache-control").map(|v| v.to_lowercase()).unwrap_or_default();
+    let directives: Vec<&str> = cache_control.split(',').map(str::trim).collect();
+    let private = directives.iter().any(|d| matches!(*d, "no-store" | "private" | "no-cache"));
+    let max_age = |prefix: &str| directives.iter().filter_map(|d| d.strip_prefix(prefix)).any(|v| v.parse::<u64>().is_ok_and(|n| n > 0));
+    let cacheable = !private && (directives.contains(&"public") || max_age("s-maxage=") || max_age("max-age="));
+
+    let says_hit = |name: &str| headers.get(name).is_some_and(|v| v.to_lowercase().contains("hit"));
+    let aged = headers.get("age").and_then(|v| v.trim().parse::<u64>().ok()).is_some_and(|age| age > 0);
+    let hit = ["x-cache", "cf-cache-status", "x-cache-status", "x-proxy-cache", "cdn-cache-status", "x-drupal-cache"].into_iter().any(says_hit) || aged;
+    CacheSignals { cacheable, hit }
+}
+
+/// Static-looking variants of `url`'s path, each with `buster` in the query
+/// so the test gets its own cache entry.
+pub fn deception_urls(url: &str, buster: &str) -> Vec<(String, String)> {
+    let Ok(base) = url::Url::parse(url) else {
+        return Vec::new();
+    };
+    let path = base.path().trim_end_matches('/').to_string();
+    let file = format!("apisec{}.css", buster);
+    [
+        ("path suffix", format!("{}/{}", path, file)),
+        ("extension", format!("{}.css", path)),
+        ("semicolon delimiter", format!("{};{}", path, file)),
+        ("encoded slash", format!("{}%2F{}", path, file)),
+        ("encoded newline", format!("{}%0A{}", path, file)),
+    ]
+    .into_iter()
+    .map(|(label, new_path)| {
+        let mut variant = base.clone();
+        variant.set_path(&new_path);
+        variant.query_pairs_mut().append_pair("apisec_cb", buster);
+        (label.to_string(), variant.to_string())
+    })
+    .collect()
+}
+
+fn same_content(a: &Probe, b: &Probe) -> bool {
+    (200..300).contains(&a.status) && (200..300).contains(&b.status) && crate::bola::body_similarity(&a.body, &b.body) >= SAME_RESPONSE_SIMILARITY
+}
+
+/// Flags an authenticated response that a shared cache stores or served,
+/// judged from two identical requests in a row.
+pub fn judge_user_content(first: &Probe, second: &Probe) -> Option<Finding> {
+    let (a, b) = (cache_signals(&first.headers), cache_signals(&second.headers));
+    if !(200..300).contains(&first.status) || !(a.cacheable || b.hit) {
+        return None;
+    }
+    let how = if b.hit { "the repeated request was answered from a cache" } else { "its Cache-Control lets shared caches store it" };
+    Some(Finding::new(
+        "CACHE-USER-CONTENT",
+        "User-Specific Response Cacheable",
+        FindingSeverity::Medium,
+        format!("The authenticated response can be cached: {}. Other users behind the same cache may receive it.", how),
+        "Cache-Control",
+    ))
+}
+
+/// Flags a static-looking `variant` that served the authenticated
+/// `baseline` and either handed it to `anonymous` from cache or allowed it
+/// to be cached.
+pub fn judge_deception(label: &str, baseline: &Probe, variant: &Probe, anonymous: &Probe) -> Option<Finding> {
+    if !same_content(baseline, variant) {
+        return None;
+    }
+    let (severity, how) = if same_content(baseline, anonymous) && cache_signals(&anonymous.headers).hit {
+        (FindingSeverity::High, "and a request without credentials then received it from the cache")
+    } else if cache_signals(&variant.headers).cacheable || cache_signals(&variant.headers).hit {
+        (FindingSeverity::Medium, "and the response is marked cacheable")
+    } else {
+        return None;
+    };
+    Some(Finding::new(
+        "CACHE-DECEPTION",
+        "Web Cache Deception",
+        severity,
+        format!("The authenticated response was served for a static-looking URL ({}) {}.", label, how),
+        label,
+    ))
+}
+
+fn reflects(probe: &Probe, canary: &str) -> bool {
+    probe.body.contains(canary) || probe.headers.values().any(|v| v.contains(canary))
+}
+
+/// Flags an unkeyed `header` whose effect on `probed` (the canary
+/// reflected, or a different status than `baseline_status`) was still
+/// there when `replay` requested the same URL without it. Rate limiting
+/// and server errors don't count as a changed status, and a repeated
+/// status only counts when the replay came from a cache.
+pub fn judge_poisoning(header: &str, canary: &str, baseline_status: u16, probed: &Probe, replay: &Probe) -> Option<Finding> {
+    let status_changed = probed.status != 0 && probed.status != 429 && probed.status < 500 && probed.status != baseline_status;
+    let reflected = reflects(probed, canary);
+    if !reflected && !status_changed {
+        return None;
+    }
+    let status_cached = status_changed && replay.status == probed.status && cache_signals(&replay.headers).hit;
+    if (reflected && reflects(replay, canary)) || status_cached {
+        let effect = if reflected { "its value".to_string() } else { format!("a {} response", probed.status) };
+        return Some(Finding::new(
+            "CACHE-POISONING",
+            "Web Cache Poisoning",
+            FindingSeverity::High,
+            format!("{} is not part of the cache key: {} was cached and served to a later request without the header.", header, effect),
+            header,
+        ));
+    }
+    if !reflected {
+        return None;
+    }
+    let (severity, how) = if cache_signals(&probed.headers).cacheable {
+        (FindingSeverity::Medium, "in a cacheable response")
+    } else {
+        (FindingSeverity::Low, "although this response wasn't cached")
+    };
+    Some(Finding::new(
+        "CACHE-UNKEYED-INPUT",
+        "Unkeyed Header Reflected",
+        severity,
+        format!("{} is reflected {}; a cache that leaves it out of the key can be poisoned through it.", header, how),
+        header,
+    ))
+}
+
+async fn fetch(client: &reqwest::Client, pacer: &Pacer, url: &str, headers: &HashMap<String, String>) -> Probe {
+    pacer.wait().await;
+    let mut req = client.get(url);
+    for (k, v) in headers {
+        req = req.header(k.as_str(), v.as_str());
+    }
+    match req.send().await {
+        Ok(res) => {
+            let status = res.status().as_u16();
+            let headers = crate::security_headers::normalize(res.headers().iter().filter_map(|(k, v)| Some((k.as_str(), v.to_str().ok()?))));
+            Probe { status, headers, body: res.text().await.unwrap_or_default() }
+        }
+        Err(_) => Probe::default(),
+    }
+}
+
+fn with_buster(url: &str, buster: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut u) => {
+            u.query_pairs_mut().append_pair("apisec_cb", buster);
+            u.to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Probes a GET asset for web cache deception and poisoning and stores
+/// the findings on it. Every request carries a unique cache buster, so
+/// poisoned entries only live under URLs nobody else requests.
+#[tauri::command]
+pub async fn probe_web_cache(asset_id: i64, pacing: Option<Pacing>, scan_profile_id: Option<i64>) -> Result<Vec<Finding>, String> {
+    let profile = crate::scan_profiles::authorize(scan_profile_id, ActiveCheck::WebCache).await?;
+    probe_asset(asset_id, &Pacer::new(&profile.cap_pacing(&pacing.unwrap_or_default()))).await
+}
+
+pub(crate) async fn probe_asset(asset_id: i64, pacer: &Pacer) -> Result<Vec<Finding>, String> {
+    let pool = get_db();
+    let asset: Option<(String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT url, method, req_headers FROM assets WHERE id = ? AND deleted_at IS NULL")
+            .bind(asset_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let (url, method, captured) = asset.ok_or("Asset not found")?;
+    if !method.as_deref().unwrap_or("GET").eq_ignore_ascii_case("GET") {
+        return Err("Cache probes only apply to GET endpoints".to_string());
+    }
+    let captured = crate::assets::captured_headers(captured);
+    let anonymous = crate::unauth::stripped_headers(&captured);
+    let authenticated = captured.keys().any(|k| crate::unauth::is_credential(k));
+    let headers = crate::assets::replay_headers(&captured, false);
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let buster = || uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+    let mut findings = Vec::new();
+
+    let first = fetch(&client, pacer, &url, &headers).await;
+    if first.status == 0 {
+        return Err("The endpoint did not respond".to_string());
+    }
+    let second = fetch(&client, pacer, &url, &headers).await;
+    if authenticated {
+        findings.extend(judge_user_content(&first, &second));
+        for (label, variant_url) in deception_urls(&url, &buster()) {
+            let variant = fetch(&client, pacer, &variant_url, &headers).await;
+            let replay = fetch(&client, pacer, &variant_url, &anonymous).await;
+            findings.extend(judge_deception(&label, &first, &variant, &replay));
+        }
+    }
+
+    // Status of a fresh cache entry, since the buster itself may change it
+    let control = fetch(&client, pacer, &with_buster(&url, &buster()), &headers).await;
+    for (header, value) in UNKEYED_HEADERS {
+        let id = buster();
+        let canary = format!("apisec{}.example", id);
+        let target = with_buster(&url, &id);
+        let mut probe_headers = headers.clone();
+        probe_headers.insert(header.to_string(), value.replace("{canary}", &canary));
+        let probed = fetch(&client, pacer, &target, &probe_headers).await;
+        let replay = fetch(&client, pacer, &target, &headers).await;
+        findings.extend(judge_poisoning(header, &canary, control.status, &probed, &replay));
+    }
+
+    crate::analysis::attach_rule_metadata(&mut findings);
+    for f in &findings {
+        crate::assets::store_finding(&pool, asset_id, f).await?;
+    }
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(status: u16, headers: &[(&str, &str)], body: &str) -> Probe {
+        Probe { status, headers: crate::security_headers::normalize(headers.iter().copied()), body: body.to_string() }
+    }
+
+    #[test]
+    fn test_cache_signals() {
+        let signals = |headers: &[(&str, &str)]| cache_signals(&probe(200, headers, "").headers);
+        assert_eq!(signals(&[("Cache-Control", "public, max-age=600")]), CacheSignals { cacheable: true, hit: false });
+        assert_eq!(signals(&[("Cache-Control", "private, max-age=600")]), CacheSignals { cacheable: false, hit: false });
+        assert_eq!(signals(&[("CF-Cache-Status", "HIT")]), CacheSignals { cacheable: false, hit: true });
+        assert!(signals(&[("Age", "42")]).hit && !signals(&[("Age", "0"), ("X-Cache", "MISS")]).hit);
+
+        let urls = deception_urls("https://api.example.com/v1/me?x=1", "abc");
+        assert_eq!(urls[0], ("path suffix".to_string(), "https://api.example.com/v1/me/apisecabc.css?x=1&apisec_cb=abc".to_string()));
+        assert_eq!(urls[1].1, "https://api.example.com/v1/me.css?x=1&apisec_cb=abc");
+        assert_eq!(urls[3].1, "https://api.example.com/v1/me%2Fapisecabc.css?x=1&apisec_cb=abc");
+    }
+
+    #[test]
+    fn test_judges() {
+        let me = r#"{"id": 7, "email": "a@example.com", "plan": "pro"}"#;
+        let baseline = probe(200, &[("Cache-Control", "no-store")], me);
+        assert!(judge_user_content(&baseline, &baseline).is_none());
+        let cached = probe(200, &[("X-Cache", "Hit from cloudfront")], me);
+        assert_eq!(judge_user_content(&baseline, &cached).unwrap().rule_id, "CACHE-USER-CONTENT");
+
+        let login = probe(302, &[], "");
+        let static_variant = probe(200, &[("Cache-Control", "public, max-age=86400")], me);
+        assert_eq!(judge_deception("path suffix", &baseline, &static_variant, &login).unwrap().severity, FindingSeverity::Medium);
+        assert_eq!(judge_deception("path suffix", &baseline, &static_variant, &cached).unwrap().severity, FindingSeverity::High);
+        assert!(judge_deception("extension", &baseline, &probe(404, &[("Cache-Control", "public, max-age=60")], "Not Found"), &login).is_none());
+
+        let canary = "apisecabc.example";
+        let page = |body: &str| probe(200, &[("Cache-Control", "public, max-age=60")], body);
+        let reflected = page(r#"<script src="https://apisecabc.example/app.js"></script>"#);
+        let clean = page(r#"<script src="https://www.example.com/app.js"></script>"#);
+        assert_eq!(judge_poisoning("X-Forwarded-Host", canary, 200, &reflected, &reflected).unwrap().rule_id, "CACHE-POISONING");
+        let unkeyed = judge_poisoning("X-Forwarded-Host", canary, 200, &reflected, &clean).unwrap();
+        assert_eq!((unkeyed.rule_id.as_str(), unkeyed.severity), ("CACHE-UNKEYED-INPUT", FindingSeverity::Medium));
+        let redirect = probe(301, &[("Location", "https://www.example.com/")], "");
+        let cached_redirect = probe(301, &[("Location", "https://www.example.com/"), ("Age", "3")], "");
+        assert_eq!(judge_poisoning("X-Forwarded-Scheme", canary, 200, &redirect, &cached_redirect).unwrap().rule_id, "CACHE-POISONING");
+        // The same redirect again without a cache hit may just be the origin
+        assert!(judge_poisoning("X-Forwarded-Scheme", canary, 200, &redirect, &redirect).is_none());
+        assert!(judge_poisoning("X-Forwarded-Scheme", canary, 200, &redirect, &clean).is_none());
+        let throttled = probe(429, &[("X-Cache", "HIT")], "");
+        assert!(judge_poisoning("X-Forwarded-Port", canary, 200, &throttled, &throttled).is_none());
+        let failed = probe(503, &[("Age", "5")], "");
+        assert!(judge_poisoning("X-Forwarded-Port", canary, 200, &failed, &failed).is_none());
+        assert!(judge_poisoning("X-Host", canary, 200, &clean, &clean).is_none());
+    }
+}
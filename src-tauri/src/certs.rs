@@ -1,66 +1,962 @@
+use crate::x509::{self, NameAttribute};
+use base64::Engine;
 use rcgen::{
-    BasicConstraints, Certificate, CertificateParams, IsCa, KeyPair,
-    KeyUsagePurpose, DistinguishedName, SanType,
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType, DnValue, ExtendedKeyUsagePurpose, IsCa,
+    KeyIdMethod, KeyPair, KeyUsagePurpose,
 };
-use std::sync::Arc;
-use tokio_rustls::rustls;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tauri::{Emitter, Manager};
 use tokio::sync::Mutex;
-use chrono::{Utc, Duration};
+use tokio_rustls::rustls;
+use chrono::{Datelike, Duration, Utc};
+
+const CA_NAME: &str = "APISec Analyst Root CA";
+const CA_CERT_FILE: &str = "root-ca.pem";
+const CA_KEY_FILE: &str = "root-ca.key";
+/// An imported CA, used instead of the generated one while present
+const IMPORTED_CERT_FILE: &str = "imported-ca.pem";
+const IMPORTED_KEY_FILE: &str = "imported-ca.key";
+/// Apple rejects server certificates valid for longer than this
+pub const MAX_LEAF_VALID_DAYS: i64 = 825;
+/// Longest CA validity and expiry warning period accepted
+pub const MAX_VALID_DAYS: i64 = 3650;
+/// Signed leaves are saved in this subdirectory of the CA's
+const LEAF_DIR: &str = "leaves";
+/// Signed leaves kept on disk per workspace; the least recently used go first
+const MAX_DISK_LEAVES: usize = 2000;
+/// Saved leaves this close to expiry are signed again
+const LEAF_RENEW_DAYS: i64 = 1;
+/// How long fetching a server's real certificate may take before a plain
+/// leaf is used instead
+const UPSTREAM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// How often the CA's expiry is checked
+const EXPIRY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// The CA that signs intercepted hosts' certificates.
+struct Authority {
+    /// Signs leaves; carries the CA's name and key identifier
+    issuer: Certificate,
+    key: KeyPair,
+    /// The certificate as handed out for trust stores
+    der: Vec<u8>,
+    pem: String,
+    imported: bool,
+}
 
 pub struct CertManager {
-    ca_cert: Certificate,
+    authority: RwLock<Arc<Authority>>,
+    settings: RwLock<CertificateSettings>,
     cache: Arc<Mutex<HashMap<String, rustls::ServerConfig>>>,
+    /// Where signed leaves are saved across restarts; unset until a
+    /// workspace's CA is loaded
+    leaf_dir: RwLock<Option<PathBuf>>,
 }
 
-impl CertManager {
-    pub fn new() -> Self {
+/// Validity of generated certificates, per workspace.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CertificateSettings {
+    /// Applies to CAs generated or rotated from now on
+    pub ca_valid_days: i64,
+    /// Leaves never outlive the CA that signed them
+    pub leaf_valid_days: i64,
+    /// Warn this many days before the CA expires
+    pub expiry_warning_days: i64,
+    /// Applies to CAs generated or rotated from now on
+    #[serde(default)]
+    pub ca_key: KeyAlgorithm,
+    #[serde(default)]
+    pub leaf_key: KeyAlgorithm,
+    /// Copy the subject, names and validity of the server's real certificate
+    #[serde(default)]
+    pub mirror_upstream: bool,
+}
+
+impl Default for CertificateSettings {
+    fn default() -> Self {
+        Self {
+            ca_valid_days: 3650,
+            leaf_valid_days: 365,
+            expiry_warning_days: 30,
+            ca_key: KeyAlgorithm::default(),
+            leaf_key: KeyAlgorithm::default(),
+            mirror_upstream: false,
+        }
+    }
+}
+
+/// Key type of generated certificates. ECDSA keys are quick to make and
+/// keep handshakes small; RSA suits clients that only accept RSA.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAlgorithm {
+    #[default]
+    EcdsaP256,
+    Rsa2048,
+    Rsa4096,
+}
+
+impl KeyAlgorithm {
+    fn generate(self) -> Result<KeyPair, String> {
+        match self {
+            Self::EcdsaP256 => KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256),
+            Self::Rsa2048 => KeyPair::generate_rsa_for(&rcgen::PKCS_RSA_SHA256, rcgen::RsaKeySize::_2048),
+            Self::Rsa4096 => KeyPair::generate_rsa_for(&rcgen::PKCS_RSA_SHA256, rcgen::RsaKeySize::_4096),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// Short form used in file names.
+    fn id(self) -> &'static str {
+        match self {
+            Self::EcdsaP256 => "p256",
+            Self::Rsa2048 => "rsa2048",
+            Self::Rsa4096 => "rsa4096",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CaInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: chrono::DateTime<Utc>,
+    pub not_after: chrono::DateTime<Utc>,
+    /// Uppercase hex SHA-1
+    pub thumbprint: String,
+    /// Imported by the user rather than generated
+    pub imported: bool,
+    /// Within the configured warning period of `not_after`, or past it
+    pub expiring: bool,
+    /// Something about the change just made needs the user's attention
+    pub warning: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CaFormat {
+    Pem,
+    Der,
+    Pkcs12,
+}
+
+/// What a forged leaf copies from the server's real certificate.
+struct Upstream {
+    subject: Vec<NameAttribute>,
+    /// DNS names and IP addresses
+    names: Vec<String>,
+    not_before: chrono::DateTime<Utc>,
+    not_after: chrono::DateTime<Utc>,
+}
+
+/// Validity by whole days, from the start of `not_before`'s to the start
+/// of `not_after`'s.
+fn set_dates(params: &mut CertificateParams, not_before: chrono::DateTime<Utc>, not_after: chrono::DateTime<Utc>) {
+    params.not_before = rcgen::date_time_ymd(not_before.year(), not_before.month() as u8, not_before.day() as u8);
+    params.not_after = rcgen::date_time_ymd(not_after.year(), not_after.month() as u8, not_after.day() as u8);
+}
+
+/// Backdates by a day so clocks running slightly behind still accept it.
+fn set_validity(params: &mut CertificateParams, days: i64) {
+    let days = days.clamp(1, MAX_VALID_DAYS);
+    set_dates(params, Utc::now() - Duration::days(1), Utc::now() + Duration::days(days));
+}
+
+/// The certificate `host:port` presents, whether or not it is trusted.
+async fn fetch_upstream(host: &str, port: u16) -> Result<Upstream, String> {
+    let config = crate::header_attacks::accept_any_cert_config()?;
+    let name = rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+    let der = tokio::time::timeout(UPSTREAM_TIMEOUT, async {
+        let tcp = tokio::net::TcpStream::connect((host, port)).await.map_err(|e| e.to_string())?;
+        let tls = tokio_rustls::TlsConnector::from(Arc::new(config)).connect(name, tcp).await.map_err(|e| e.to_string())?;
+        let (_, connection) = tls.get_ref();
+        connection
+            .peer_certificates()
+            .and_then(|chain| chain.first())
+            .map(|cert| cert.to_vec())
+            .ok_or_else(|| "The server presented no certificate".to_string())
+    })
+    .await
+    .map_err(|_| "Timed out".to_string())??;
+
+    let summary = x509::parse_certificate(&der).ok_or("Could not read the server's certificate")?;
+    let details = x509::parse_signing_details(&der).ok_or("Could not read the server's certificate")?;
+    Ok(Upstream { subject: details.subject, names: summary.sans, not_before: summary.not_before, not_after: summary.not_after })
+}
+
+fn ca_params(name: &str) -> CertificateParams {
+    let mut params = CertificateParams::default();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.distinguished_name = DistinguishedName::new();
+    params.distinguished_name.push(rcgen::DnType::CommonName, name);
+    params.key_usages.push(KeyUsagePurpose::DigitalSignature);
+    params.key_usages.push(KeyUsagePurpose::KeyCertSign);
+    params.key_usages.push(KeyUsagePurpose::CrlSign);
+    params
+}
+
+fn pem_encode(label: &str, der: &[u8]) -> String {
+    let body = base64::engine::general_purpose::STANDARD.encode(der);
+    let lines: Vec<&str> = body.as_bytes().chunks(64).map(|line| std::str::from_utf8(line).unwrap()).collect();
+    format!("-----BEGIN {label}-----\n{}\n-----END {label}-----\n", lines.join("\n"))
+}
+
+/// Base64 body of the first `label` block in `pem`.
+fn pem_block(pem: &str, label: &str) -> Option<Vec<u8>> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let start = pem.find(&begin)? + begin.len();
+    let body: String = pem[start..].split(&end).next()?.split_whitespace().collect();
+    base64::engine::general_purpose::STANDARD.decode(body).ok()
+}
+
+fn certificates_from_pem(pem: &str) -> Result<Vec<Vec<u8>>, String> {
+    rustls_pemfile::certs(&mut pem.as_bytes())
+        .map(|cert| cert.map(|cert| cert.to_vec()).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// A PKCS#8 key, decrypting it with `passphrase` when it is encrypted.
+fn key_from_pem(pem: &str, passphrase: &str) -> Result<Vec<u8>, String> {
+    if let Some(encrypted) = pem_block(pem, "ENCRYPTED PRIVATE KEY") {
+        return crate::pkcs12::decrypt_private_key(&encrypted, passphrase);
+    }
+    match rustls_pemfile::private_key(&mut pem.as_bytes()).map_err(|e| e.to_string())? {
+        Some(rustls::pki_types::PrivateKeyDer::Pkcs8(key)) => Ok(key.secret_pkcs8_der().to_vec()),
+        Some(_) => Err("Only PKCS#8 keys are supported; convert with `openssl pkcs8 -topk8`".to_string()),
+        None => Err("No private key found".to_string()),
+    }
+}
+
+/// rcgen's form of a Name, keeping each attribute's string type so the
+/// issuer of signed leaves encodes exactly like the CA's subject.
+fn distinguished_name(attributes: &[NameAttribute]) -> DistinguishedName {
+    let mut name = DistinguishedName::new();
+    for attribute in attributes {
+        let text = String::from_utf8_lossy(&attribute.value).into_owned();
+        let value = match attribute.tag {
+            0x13 => rcgen::PrintableString::try_from(text.clone()).map(DnValue::PrintableString).ok(),
+            0x16 => rcgen::Ia5String::try_from(text.clone()).map(DnValue::Ia5String).ok(),
+            0x1e => rcgen::BmpString::from_utf16be(attribute.value.clone()).map(DnValue::BmpString).ok(),
+            _ => None,
+        };
+        name.push(DnType::from_oid(&attribute.oid), value.unwrap_or(DnValue::Utf8String(text)));
+    }
+    name
+}
+
+/// ring's verifier for signatures made by `key`.
+fn verification_algorithm(key: &KeyPair) -> Option<&'static dyn ring::signature::VerificationAlgorithm> {
+    let algorithm = key.algorithm();
+    if algorithm == &rcgen::PKCS_ECDSA_P256_SHA256 {
+        Some(&ring::signature::ECDSA_P256_SHA256_ASN1)
+    } else if algorithm == &rcgen::PKCS_ECDSA_P384_SHA384 {
+        Some(&ring::signature::ECDSA_P384_SHA384_ASN1)
+    } else if algorithm == &rcgen::PKCS_ED25519 {
+        Some(&ring::signature::ED25519)
+    } else if algorithm == &rcgen::PKCS_RSA_SHA256 {
+        Some(&ring::signature::RSA_PKCS1_2048_8192_SHA256)
+    } else {
+        None
+    }
+}
+
+impl Authority {
+    fn generate(name: &str, valid_days: i64, algorithm: KeyAlgorithm) -> Result<Self, String> {
+        let key = algorithm.generate()?;
+        let mut params = ca_params(name);
+        set_validity(&mut params, valid_days);
+        let cert = params.self_signed(&key).map_err(|e| e.to_string())?;
+        Ok(Self { der: cert.der().to_vec(), pem: cert.pem(), issuer: cert, key, imported: false })
+    }
+
+    /// A CA certificate and its PKCS#8 key. Re-signing the certificate's
+    /// name with the key, under its own key identifier, yields an issuer
+    /// equivalent to the original.
+    fn from_parts(der: Vec<u8>, key_pkcs8: &[u8], imported: bool) -> Result<Self, String> {
+        let key = KeyPair::try_from(key_pkcs8).map_err(|e| format!("Unsupported private key: {}", e))?;
+        let details = x509::parse_signing_details(&der).ok_or("Could not read the CA certificate")?;
+        if details.public_key != key.public_key_raw() {
+            return Err("The private key does not belong to the CA certificate".to_string());
+        }
+        if !details.is_ca {
+            return Err("The certificate is not a CA (basic constraints do not allow signing)".to_string());
+        }
         let mut params = CertificateParams::default();
         params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
-        params.distinguished_name = DistinguishedName::new();
-        params.distinguished_name.push(rcgen::DnType::CommonName, "APISec Analyst Root CA");
-        params.key_usages.push(rcgen::KeyUsagePurpose::DigitalSignature);
-        params.key_usages.push(rcgen::KeyUsagePurpose::KeyCertSign);
-        params.key_usages.push(rcgen::KeyUsagePurpose::CrlSign);
-        
-        let ca_cert = Certificate::generate_from_params(params).unwrap();
-        
+        params.distinguished_name = distinguished_name(&details.subject);
+        if let Some(id) = details.subject_key_id {
+            // Leaves name it in their authority key identifier
+            params.key_identifier_method = KeyIdMethod::PreSpecified(id);
+        }
+        let issuer = params.self_signed(&key).map_err(|e| e.to_string())?;
+        Ok(Self { issuer, key, pem: pem_encode("CERTIFICATE", &der), der, imported })
+    }
+
+    /// A CA saved by an earlier run.
+    fn load(cert_pem: &str, key_pem: &str, imported: bool) -> Result<Self, String> {
+        let der = certificates_from_pem(cert_pem)?.into_iter().next().ok_or("No certificate in the saved CA file")?;
+        Self::from_parts(der, &key_from_pem(key_pem, "")?, imported)
+    }
+
+    /// An external CA, given as a bundle's certificates and keys. The first
+    /// key is used, with whichever certificate holds its public half.
+    fn import(certificates: Vec<Vec<u8>>, keys: Vec<Vec<u8>>) -> Result<Self, String> {
+        let key = keys.into_iter().next().ok_or("No private key found")?;
+        let public_key = KeyPair::try_from(key.as_slice()).map_err(|e| format!("Unsupported private key: {}", e))?.public_key_raw().to_vec();
+        let der = certificates
+            .into_iter()
+            .find(|der| x509::parse_signing_details(der).is_some_and(|details| details.public_key == public_key))
+            .ok_or("No certificate matches the private key")?;
+        let authority = Self::from_parts(der, &key, true)?;
+        authority.check()?;
+        Ok(authority)
+    }
+
+    /// A leaf for `domain` holding `key`, or a copy of `upstream` that also
+    /// covers it.
+    fn sign_leaf(&self, domain: &str, valid_days: i64, key: &KeyPair, upstream: Option<&Upstream>) -> Result<Certificate, String> {
+        let mut names = vec![domain.to_string()];
+        if let Some(upstream) = upstream {
+            names.extend(upstream.names.iter().filter(|name| !name.eq_ignore_ascii_case(domain)).cloned());
+        }
+        // DNS name or IP address SANs, whichever each name is
+        let mut params = CertificateParams::new(names).map_err(|e| e.to_string())?;
+        params.key_usages.push(KeyUsagePurpose::DigitalSignature);
+        params.extended_key_usages.push(ExtendedKeyUsagePurpose::ServerAuth);
+        params.use_authority_key_identifier_extension = true;
+        match upstream {
+            Some(upstream) => {
+                params.distinguished_name = distinguished_name(&upstream.subject);
+                set_dates(&mut params, upstream.not_before, upstream.not_after);
+            }
+            None => {
+                params.distinguished_name = DistinguishedName::new();
+                params.distinguished_name.push(rcgen::DnType::CommonName, domain);
+                set_validity(&mut params, valid_days);
+            }
+        }
+        if let Some(ca) = x509::parse_certificate(&self.der) {
+            let ca_not_after = rcgen::date_time_ymd(ca.not_after.year(), ca.not_after.month() as u8, ca.not_after.day() as u8);
+            params.not_after = params.not_after.min(ca_not_after);
+        }
+
+        params.signed_by(key, &self.issuer, &self.key).map_err(|e| e.to_string())
+    }
+
+    /// Signs a test leaf and checks that it names this CA as issuer, by name
+    /// and key identifier, and verifies against its key, and that the CA is
+    /// currently valid.
+    fn check(&self) -> Result<(), String> {
+        let summary = x509::parse_certificate(&self.der).ok_or("Could not read the CA certificate")?;
+        if summary.not_after < Utc::now() {
+            return Err(format!("The CA certificate expired on {}", summary.not_after.format("%Y-%m-%d")));
+        }
+        if summary.not_before > Utc::now() {
+            return Err(format!("The CA certificate is not valid until {}", summary.not_before.format("%Y-%m-%d")));
+        }
+
+        let ca = x509::parse_signing_details(&self.der).ok_or("Could not read the CA certificate")?;
+        let leaf = self.sign_leaf("signing-check.invalid", 1, &KeyAlgorithm::EcdsaP256.generate()?, None)?;
+        let leaf = x509::parse_signing_details(leaf.der()).ok_or("Could not read the test certificate")?;
+        if leaf.issuer_der != ca.subject_der {
+            return Err("The CA's name cannot be reproduced exactly, so clients would not chain to it".to_string());
+        }
+        if ca.subject_key_id.is_some() && leaf.authority_key_id != ca.subject_key_id {
+            return Err("Signed certificates would not carry the CA's key identifier, so clients would not chain to it".to_string());
+        }
+        let algorithm = verification_algorithm(&self.key).ok_or("Unsupported key algorithm")?;
+        ring::signature::UnparsedPublicKey::new(algorithm, &ca.public_key)
+            .verify(&leaf.tbs, &leaf.signature)
+            .map_err(|_| "A test certificate signed with the key did not verify".to_string())
+    }
+
+    /// Uppercase hex SHA-1 of the certificate.
+    fn thumbprint(&self) -> String {
+        let digest = ring::digest::digest(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY, &self.der);
+        digest.as_ref().iter().map(|b| format!("{:02X}", b)).collect()
+    }
+
+    fn info(&self, warning_days: i64) -> Result<CaInfo, String> {
+        let summary = x509::parse_certificate(&self.der).ok_or("Could not read the CA certificate")?;
+        Ok(CaInfo {
+            expiring: summary.not_after - Duration::days(warning_days.clamp(0, MAX_VALID_DAYS)) <= Utc::now(),
+            subject: summary.subject,
+            issuer: summary.issuer,
+            not_before: summary.not_before,
+            not_after: summary.not_after,
+            thumbprint: self.thumbprint(),
+            imported: self.imported,
+            warning: None,
+        })
+    }
+}
+
+impl CertManager {
+    /// Starts with a throwaway CA until `load_or_create` picks up the saved one.
+    pub fn new() -> Self {
         Self {
-            ca_cert,
+            authority: RwLock::new(Arc::new(Authority::generate(CA_NAME, CertificateSettings::default().ca_valid_days, KeyAlgorithm::default()).unwrap())),
+            settings: RwLock::new(CertificateSettings::default()),
             cache: Arc::new(Mutex::new(HashMap::new())),
+            leaf_dir: RwLock::new(None),
         }
     }
 
-    pub async fn get_server_config(&self, domain: &str) -> Arc<rustls::ServerConfig> {
-        let mut cache = self.cache.lock().await;
-        if let Some(config) = cache.get(domain) {
-            return Arc::new(config.clone());
+    /// Uses the CA saved in `dir`, creating and saving one called `name` on
+    /// first run so a trusted CA stays trusted across restarts. An imported
+    /// CA takes precedence over the generated one.
+    pub async fn load_or_create(&self, dir: &Path, name: &str) -> Result<(), String> {
+        let (imported_cert, imported_key) = (dir.join(IMPORTED_CERT_FILE), dir.join(IMPORTED_KEY_FILE));
+        let (cert_path, key_path) = (dir.join(CA_CERT_FILE), dir.join(CA_KEY_FILE));
+        let authority = if imported_cert.exists() && imported_key.exists() {
+            let cert_pem = std::fs::read_to_string(&imported_cert).map_err(|e| e.to_string())?;
+            let key_pem = std::fs::read_to_string(&imported_key).map_err(|e| e.to_string())?;
+            Authority::load(&cert_pem, &key_pem, true)?
+        } else if cert_path.exists() && key_path.exists() {
+            let cert_pem = std::fs::read_to_string(&cert_path).map_err(|e| e.to_string())?;
+            let key_pem = std::fs::read_to_string(&key_path).map_err(|e| e.to_string())?;
+            Authority::load(&cert_pem, &key_pem, false)?
+        } else {
+            self.generate(dir, name)?
+        };
+        self.switch(dir, authority).await;
+        Ok(())
+    }
+
+    /// A new CA called `name`, saved to `dir` as the generated one.
+    fn generate(&self, dir: &Path, name: &str) -> Result<Authority, String> {
+        let settings = self.settings();
+        let authority = Authority::generate(name, settings.ca_valid_days, settings.ca_key)?;
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        write_private(&dir.join(CA_KEY_FILE), authority.key.serialize_pem().as_bytes()).map_err(|e| e.to_string())?;
+        std::fs::write(dir.join(CA_CERT_FILE), &authority.pem).map_err(|e| e.to_string())?;
+        Ok(authority)
+    }
+
+    /// Replaces the generated CA in `dir` with a new one. Leaves signed by
+    /// the old CA are dropped, and clients must trust the new one.
+    async fn rotate(&self, dir: &Path, name: &str) -> Result<(), String> {
+        if self.authority().imported {
+            return Err("The CA is imported; renew it where it was issued, or switch back to the generated CA".to_string());
         }
+        let authority = self.generate(dir, name)?;
+        self.switch(dir, authority).await;
+        Ok(())
+    }
 
-        let mut params = CertificateParams::default();
-        params.distinguished_name = DistinguishedName::new();
-        params.distinguished_name.push(rcgen::DnType::CommonName, domain);
-        params.subject_alt_names.push(SanType::DnsName(domain.to_string()));
-        params.key_usages.push(rcgen::KeyUsagePurpose::DigitalSignature);
-        
-        let cert = Certificate::generate_from_params(params).unwrap();
-        let cert_signed = cert.serialize_der_with_signer(&self.ca_cert).unwrap();
-        let key_der = cert.get_key_pair().serialize_der();
-        
-        let cert_chain = vec![rustls::pki_types::CertificateDer::from(cert_signed)];
+    fn settings(&self) -> CertificateSettings {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// Takes new validity settings. Cached leaves are dropped so new ones
+    /// get the new validity.
+    pub async fn configure(&self, settings: CertificateSettings) {
+        *self.settings.write().unwrap() = settings;
+        self.cache.lock().await.clear();
+    }
+
+    /// Signs with `authority`, kept in `dir`, from now on. Leaves signed by
+    /// any other CA are dropped from memory and from disk.
+    async fn switch(&self, dir: &Path, authority: Authority) {
+        let prefix = format!("{}-", leaf_prefix(&authority.thumbprint()));
+        let leaf_dir = dir.join(LEAF_DIR);
+        if let Ok(entries) = std::fs::read_dir(&leaf_dir) {
+            for entry in entries.flatten() {
+                if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+        *self.authority.write().unwrap() = Arc::new(authority);
+        *self.leaf_dir.write().unwrap() = Some(leaf_dir);
+        self.cache.lock().await.clear();
+    }
+
+    /// Validates and saves an external CA to `dir`, then signs with it.
+    async fn import(&self, dir: &Path, authority: Authority) -> Result<(), String> {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        write_private(&dir.join(IMPORTED_KEY_FILE), authority.key.serialize_pem().as_bytes()).map_err(|e| e.to_string())?;
+        std::fs::write(dir.join(IMPORTED_CERT_FILE), &authority.pem).map_err(|e| e.to_string())?;
+        self.switch(dir, authority).await;
+        Ok(())
+    }
+
+    pub fn ca_info(&self) -> Result<CaInfo, String> {
+        self.authority().info(self.settings().expiry_warning_days)
+    }
+
+    fn authority(&self) -> Arc<Authority> {
+        self.authority.read().unwrap().clone()
+    }
+
+    /// TLS settings presenting a leaf for `domain`. With mirroring on, the
+    /// real certificate at `domain:port` is copied when it can be fetched
+    /// and signed; otherwise a plain leaf is used.
+    pub async fn get_server_config(&self, domain: &str, port: u16) -> Result<Arc<rustls::ServerConfig>, String> {
+        if let Some(config) = self.cache.lock().await.get(domain) {
+            return Ok(Arc::new(config.clone()));
+        }
+
+        // The cache stays unlocked while the upstream is fetched, so a slow
+        // server only holds up its own connections
+        let authority = self.authority();
+        let settings = self.settings();
+        let file_name = leaf_file_name(&authority.thumbprint(), &settings, domain);
+        let path = self.leaf_dir.read().unwrap().as_ref().map(|dir| dir.join(file_name));
+        let (cert_der, key_der) = match path.as_deref().and_then(read_leaf) {
+            Some(saved) => saved,
+            None => {
+                let upstream = match settings.mirror_upstream {
+                    true => fetch_upstream(domain, port)
+                        .await
+                        .inspect_err(|e| eprintln!("Could not mirror the certificate of {}: {}", domain, e))
+                        .ok(),
+                    false => None,
+                };
+                // RSA keys can take seconds to generate
+                let algorithm = settings.leaf_key;
+                let key = tokio::task::spawn_blocking(move || algorithm.generate()).await.map_err(|e| e.to_string())??;
+                let mirrored = upstream.and_then(|upstream| {
+                    authority
+                        .sign_leaf(domain, settings.leaf_valid_days, &key, Some(&upstream))
+                        .inspect_err(|e| eprintln!("Could not mirror the certificate of {}: {}", domain, e))
+                        .ok()
+                });
+                let cert = match mirrored {
+                    Some(cert) => cert,
+                    None => authority.sign_leaf(domain, settings.leaf_valid_days, &key, None)?,
+                };
+                if let Some(path) = path {
+                    // Writing and trimming the directory stays off the
+                    // connection's path
+                    let (pem, domain) = (format!("{}{}", cert.pem(), key.serialize_pem()), domain.to_string());
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(e) = save_leaf(&path, &pem) {
+                            eprintln!("Could not save the certificate for {}: {}", domain, e);
+                        }
+                    });
+                }
+                (cert.der().to_vec(), key.serialize_der())
+            }
+        };
+
+        let cert_chain = vec![rustls::pki_types::CertificateDer::from(cert_der)];
         let key_der_pki = rustls::pki_types::PrivatePkcs8KeyDer::from(key_der);
         let key_der_wrapped = rustls::pki_types::PrivateKeyDer::Pkcs8(key_der_pki);
 
         let config = rustls::ServerConfig::builder()
             .with_no_client_auth()
             .with_single_cert(cert_chain, key_der_wrapped)
-            .unwrap();
+            .map_err(|e| e.to_string())?;
 
-        cache.insert(domain.to_string(), config.clone());
-        Arc::new(config)
+        self.cache.lock().await.insert(domain.to_string(), config.clone());
+        Ok(Arc::new(config))
     }
 
     pub fn get_ca_pem(&self) -> String {
-        self.ca_cert.pem()
+        self.authority().pem.clone()
+    }
+
+    pub fn get_ca_der(&self) -> Vec<u8> {
+        self.authority().der.clone()
+    }
+
+    /// Certificate and key as PKCS#12, encrypted with `passphrase`.
+    pub fn get_ca_pkcs12(&self, passphrase: &str) -> Result<Vec<u8>, String> {
+        let authority = self.authority();
+        crate::pkcs12::build(&authority.der, &authority.key.serialize_der(), CA_NAME, passphrase)
+    }
+
+    /// Uppercase hex SHA-1 of the certificate, as trust stores identify it.
+    pub fn ca_thumbprint(&self) -> String {
+        self.authority().thumbprint()
+    }
+}
+
+/// Writes a file holding a private key, readable only by the current user.
+/// Files left over with looser permissions are tightened.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+fn leaf_prefix(thumbprint: &str) -> String {
+    thumbprint.get(..16).unwrap_or(thumbprint).to_lowercase()
+}
+
+/// `<CA>-<validity>-<key type>-<host>.pem`, with the host hashed since it
+/// may hold characters file systems reject. Mirrored leaves put `mirror` in
+/// place of the validity. Leaves made under other settings are never picked
+/// up and age out of the LRU.
+fn leaf_file_name(thumbprint: &str, settings: &CertificateSettings, host: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, host.to_lowercase().as_bytes());
+    let host: String = digest.as_ref()[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    let validity = match settings.mirror_upstream {
+        true => "mirror".to_string(),
+        false => settings.leaf_valid_days.to_string(),
+    };
+    format!("{}-{}-{}-{}.pem", leaf_prefix(thumbprint), validity, settings.leaf_key.id(), host)
+}
+
+/// A saved leaf's certificate and PKCS#8 key, unless it is about to expire.
+/// Marks it as recently used.
+fn read_leaf(path: &Path) -> Option<(Vec<u8>, Vec<u8>)> {
+    let pem = std::fs::read_to_string(path).ok()?;
+    let cert = certificates_from_pem(&pem).ok()?.into_iter().next()?;
+    let key = key_from_pem(&pem, "").ok()?;
+    let summary = x509::parse_certificate(&cert)?;
+    if summary.not_after - Duration::days(LEAF_RENEW_DAYS) <= Utc::now() {
+        return None;
+    }
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(std::time::SystemTime::now());
+    }
+    Some((cert, key))
+}
+
+/// Saves a leaf's certificate and key PEM, then trims the directory to
+/// `MAX_DISK_LEAVES`. Blocks on the file system.
+fn save_leaf(path: &Path, pem: &str) -> Result<(), String> {
+    let dir = path.parent().ok_or("No leaf directory")?;
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    write_private(path, pem.as_bytes()).map_err(|e| e.to_string())?;
+    evict_leaves(dir, MAX_DISK_LEAVES)
+}
+
+/// Drops the least recently used leaves in `dir` beyond `keep`.
+fn evict_leaves(dir: &Path, keep: usize) -> Result<(), String> {
+    let mut leaves: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    if leaves.len() > keep {
+        leaves.sort();
+        for (_, old) in &leaves[..leaves.len() - keep] {
+            let _ = std::fs::remove_file(old);
+        }
+    }
+    Ok(())
+}
+
+/// Where the active workspace's interception CA is kept: `ca/<workspace>/`.
+pub fn ca_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    let workspace = crate::db::get_current_workspace();
+    let safe_name = workspace.replace(|c: char| !c.is_alphanumeric(), "_");
+    app_handle.path().app_data_dir().unwrap().join("ca").join(safe_name)
+}
+
+/// Switches the proxy to the active workspace's CA, generating one named
+/// after the workspace the first time it is opened. Runs on startup and on
+/// every workspace switch, so trust roots never cross engagements.
+pub async fn load_workspace_ca(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let dir = ca_dir(app_handle);
+    // The CA shared by all workspaces in earlier versions stays with the
+    // workspace opened first, where it may already be trusted
+    let shared = app_handle.path().app_data_dir().unwrap().join("ca");
+    if !dir.exists() && shared.join(CA_CERT_FILE).exists() {
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        for file in [CA_CERT_FILE, CA_KEY_FILE, IMPORTED_CERT_FILE, IMPORTED_KEY_FILE] {
+            if shared.join(file).exists() {
+                std::fs::rename(shared.join(file), dir.join(file)).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    let state = app_handle.state::<Arc<crate::ProxyState>>();
+    state.cert_manager.configure(crate::settings::get("certificates").await?).await;
+    state.cert_manager.load_or_create(&dir, &workspace_ca_name()).await
+}
+
+fn workspace_ca_name() -> String {
+    format!("{} ({})", CA_NAME, crate::db::get_current_workspace())
+}
+
+/// Regenerates the workspace's CA, e.g. before it expires or after its key
+/// may have leaked. The new CA has to be trusted again; the old one is
+/// removed from the system trust store if it was installed from here, and
+/// `warning` says so when that fails.
+#[tauri::command]
+pub async fn rotate_ca(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<crate::ProxyState>>) -> Result<CaInfo, String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    let dir = ca_dir(&app_handle);
+    let (old_pem, old_thumbprint) = (state.cert_manager.get_ca_pem(), state.cert_manager.ca_thumbprint());
+    state.cert_manager.rotate(&dir, &workspace_ca_name()).await?;
+    let mut info = state.cert_manager.ca_info()?;
+    if dir.join(trust_store_file(&old_thumbprint)).exists() {
+        if let Err(e) = run_trust_store_commands(&dir, &old_pem, &old_thumbprint, false).await {
+            info.warning = Some(format!(
+                "The previous CA ({}) is still trusted by this system: {}. Remove it from the trust store by hand.",
+                old_thumbprint, e
+            ));
+        }
+    }
+    Ok(info)
+}
+
+/// Hourly check of the active CA's expiry. Within the warning period it
+/// emits `ca-expiring` and sends a webhook notification, once a day per CA.
+pub async fn run_expiry_watch(app_handle: tauri::AppHandle) {
+    let mut last_warned: Option<(String, chrono::NaiveDate)> = None;
+    loop {
+        let info = app_handle.state::<Arc<crate::ProxyState>>().cert_manager.ca_info();
+        if let Some(info) = info.ok().filter(|info| info.expiring) {
+            let today = Utc::now().date_naive();
+            if last_warned.as_ref() != Some(&(info.thumbprint.clone(), today)) {
+                last_warned = Some((info.thumbprint.clone(), today));
+                let _ = app_handle.emit("ca-expiring", &info);
+                let days = (info.not_after - Utc::now()).num_days();
+                let message = if days < 0 {
+                    format!("{} expired on {}. Rotate it in the proxy settings.", info.subject, info.not_after.format("%Y-%m-%d"))
+                } else {
+                    format!("{} expires in {} days ({}). Rotate it in the proxy settings.", info.subject, days, info.not_after.format("%Y-%m-%d"))
+                };
+                if matches!(crate::db::get_webhook().await, Ok(Some(_))) {
+                    if let Err(e) = crate::db::send_notification("Interception CA expiring".to_string(), message).await {
+                        eprintln!("CA expiry notification failed: {}", e);
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(EXPIRY_CHECK_INTERVAL).await;
+    }
+}
+
+/// The CA certificate in PEM, DER or PKCS#12. PKCS#12 includes the private
+/// key, so it needs a passphrase and the Admin role.
+#[tauri::command]
+pub async fn export_root_ca(
+    state: tauri::State<'_, Arc<crate::ProxyState>>,
+    format: CaFormat,
+    passphrase: Option<String>,
+) -> Result<Vec<u8>, String> {
+    if matches!(format, CaFormat::Pkcs12) {
+        // Carries the CA key, which is as sensitive as rotating or importing it
+        crate::users::require_role(crate::users::Role::Admin).await?;
+    }
+    match format {
+        CaFormat::Pem => Ok(state.cert_manager.get_ca_pem().into_bytes()),
+        CaFormat::Der => Ok(state.cert_manager.get_ca_der()),
+        CaFormat::Pkcs12 => match passphrase.filter(|p| !p.is_empty()) {
+            Some(passphrase) => state.cert_manager.get_ca_pkcs12(&passphrase),
+            None => Err("A passphrase is required to export the CA key".to_string()),
+        },
+    }
+}
+
+/// Subject, validity and origin of the CA signing intercepted hosts.
+#[tauri::command]
+pub fn get_ca_info(state: tauri::State<'_, Arc<crate::ProxyState>>) -> Result<CaInfo, String> {
+    state.cert_manager.ca_info()
+}
+
+/// Replaces the generated CA with an external one, given either as PEM
+/// certificate and key or as a PKCS#12 bundle. `passphrase` opens the
+/// bundle or an encrypted PEM key. The CA must be valid, and a test
+/// certificate signed with it must verify, before it is switched to.
+#[tauri::command]
+pub async fn import_ca(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<crate::ProxyState>>,
+    cert_pem: Option<String>,
+    key_pem: Option<String>,
+    pkcs12: Option<Vec<u8>>,
+    passphrase: Option<String>,
+) -> Result<CaInfo, String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    let passphrase = passphrase.unwrap_or_default();
+    let authority = match (pkcs12, cert_pem, key_pem) {
+        (Some(pfx), _, _) => {
+            let contents = crate::pkcs12::parse(&pfx, &passphrase)?;
+            Authority::import(contents.certificates, contents.keys)?
+        }
+        (None, Some(cert_pem), Some(key_pem)) => {
+            let certificates = certificates_from_pem(&cert_pem)?;
+            Authority::import(certificates, vec![key_from_pem(&key_pem, &passphrase)?])?
+        }
+        _ => return Err("Provide a PKCS#12 file, or a PEM certificate and key".to_string()),
+    };
+    state.cert_manager.import(&ca_dir(&app_handle), authority).await?;
+    state.cert_manager.ca_info()
+}
+
+/// Drops an imported CA and goes back to the generated one.
+#[tauri::command]
+pub async fn reset_ca(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<crate::ProxyState>>) -> Result<CaInfo, String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    let dir = ca_dir(&app_handle);
+    for file in [IMPORTED_CERT_FILE, IMPORTED_KEY_FILE] {
+        let path = dir.join(file);
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+    }
+    load_workspace_ca(&app_handle).await?;
+    state.cert_manager.ca_info()
+}
+
+/// File name used in trust stores that want one. Each workspace has its own
+/// CA, so the name carries the thumbprint to let them sit side by side.
+fn trust_store_file(thumbprint: &str) -> String {
+    format!("apisec-root-ca-{}.crt", thumbprint.get(..16).unwrap_or(thumbprint).to_lowercase())
+}
+
+/// Linux trust anchor for `file` and the refresh command, for Debian-like
+/// and Fedora/Arch-like layouts.
+fn linux_anchor(file: &str) -> Result<(PathBuf, &'static str), String> {
+    [
+        ("/usr/local/share/ca-certificates", "update-ca-certificates"),
+        ("/etc/pki/ca-trust/source/anchors", "update-ca-trust"),
+        ("/etc/ca-certificates/trust-source/anchors", "update-ca-trust"),
+    ]
+    .into_iter()
+    .find(|(dir, _)| Path::new(dir).is_dir())
+    .map(|(dir, refresh)| (Path::new(dir).join(file), refresh))
+    .ok_or_else(|| "No supported CA trust store found".to_string())
+}
+
+fn macos_keychain() -> Result<String, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+    Ok(format!("{}/Library/Keychains/login.keychain-db", home))
+}
+
+/// Commands that add the CA at `cert_path` to the current platform's trust
+/// store, or remove the one with `thumbprint`. Windows and macOS use the
+/// user's own store; Linux needs root, asked for through pkexec.
+fn trust_store_commands(install: bool, cert_path: &Path, thumbprint: &str) -> Result<Vec<Vec<String>>, String> {
+    let path = cert_path.to_string_lossy().to_string();
+    let commands: Vec<Vec<String>> = if cfg!(target_os = "windows") {
+        if install {
+            vec![vec!["certutil".into(), "-user".into(), "-addstore".into(), "Root".into(), path]]
+        } else {
+            vec![vec!["certutil".into(), "-user".into(), "-delstore".into(), "Root".into(), thumbprint.into()]]
+        }
+    } else if cfg!(target_os = "macos") {
+        let keychain = macos_keychain()?;
+        if install {
+            vec![vec!["security".into(), "add-trusted-cert".into(), "-r".into(), "trustRoot".into(), "-k".into(), keychain, path]]
+        } else {
+            vec![
+                vec!["security".into(), "remove-trusted-cert".into(), path],
+                vec!["security".into(), "delete-certificate".into(), "-Z".into(), thumbprint.into(), keychain],
+            ]
+        }
+    } else {
+        let (anchor, refresh) = linux_anchor(&trust_store_file(thumbprint))?;
+        let anchor = anchor.to_string_lossy().to_string();
+        // Paths go in as positional parameters rather than into the script
+        let script = if install {
+            format!("cp \"$1\" \"$2\" && chmod 644 \"$2\" && {}", refresh)
+        } else {
+            format!("rm -f \"$2\" && {}", refresh)
+        };
+        vec![vec!["pkexec".into(), "sh".into(), "-c".into(), script, "sh".into(), path, anchor]]
+    };
+    Ok(commands)
+}
+
+/// Installs or removes the CA with `pem` and `thumbprint`. The copy written
+/// to `dir` stays while the CA is installed, so a later rotation knows to
+/// remove it.
+async fn run_trust_store_commands(dir: &Path, pem: &str, thumbprint: &str, install: bool) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let cert_path = dir.join(trust_store_file(thumbprint));
+    std::fs::write(&cert_path, pem).map_err(|e| e.to_string())?;
+
+    for command in trust_store_commands(install, &cert_path, thumbprint)? {
+        let output = tokio::process::Command::new(&command[0])
+            .args(&command[1..])
+            .output()
+            .await
+            .map_err(|e| format!("Could not run {}: {}", command[0], e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let message = if stderr.trim().is_empty() { stdout } else { stderr };
+            return Err(format!("{} failed: {}", command[0], message.trim()));
+        }
+    }
+    if !install {
+        let _ = std::fs::remove_file(&cert_path);
+    }
+    Ok(())
+}
+
+/// Trusts the interception CA system-wide: the Windows user certificate
+/// store, the macOS login keychain, or the Linux ca-certificates anchors.
+#[tauri::command]
+pub async fn install_ca_to_system(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<crate::ProxyState>>) -> Result<(), String> {
+    let (pem, thumbprint) = (state.cert_manager.get_ca_pem(), state.cert_manager.ca_thumbprint());
+    run_trust_store_commands(&ca_dir(&app_handle), &pem, &thumbprint, true).await
+}
+
+/// Reverses `install_ca_to_system`.
+#[tauri::command]
+pub async fn uninstall_ca_from_system(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<crate::ProxyState>>) -> Result<(), String> {
+    let (pem, thumbprint) = (state.cert_manager.get_ca_pem(), state.cert_manager.ca_thumbprint());
+    run_trust_store_commands(&ca_dir(&app_handle), &pem, &thumbprint, false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::client::danger::ServerCertVerifier;
+
+    #[test]
+    fn test_imported_ca_chain() {
+        // An external CA whose key identifier isn't the one rcgen derives
+        let key = KeyAlgorithm::EcdsaP256.generate().unwrap();
+        let mut params = ca_params("Imported Test CA");
+        params.key_identifier_method = KeyIdMethod::PreSpecified(vec![0x5a; 20]);
+        set_validity(&mut params, 30);
+        let ca = params.self_signed(&key).unwrap();
+        let authority = Authority::import(vec![ca.der().to_vec()], vec![key.serialize_der()]).unwrap();
+
+        let leaf = authority.sign_leaf("api.example.com", 7, &KeyAlgorithm::EcdsaP256.generate().unwrap(), None).unwrap();
+        let details = x509::parse_signing_details(leaf.der()).unwrap();
+        assert_eq!(details.authority_key_id, Some(vec![0x5a; 20]));
+        assert_eq!(x509::parse_signing_details(ca.der()).unwrap().subject_key_id, Some(vec![0x5a; 20]));
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(ca.der().clone()).unwrap();
+        let verifier = rustls::client::WebPkiServerVerifier::builder_with_provider(
+            Arc::new(roots),
+            Arc::new(rustls::crypto::ring::default_provider()),
+        )
+        .build()
+        .unwrap();
+        let name = rustls::pki_types::ServerName::try_from("api.example.com").unwrap();
+        verifier.verify_server_cert(leaf.der(), &[], &name, &[], rustls::pki_types::UnixTime::now()).unwrap();
+    }
+
+    #[test]
+    fn test_evict_leaves() {
+        let dir = std::env::temp_dir().join(format!("apisec-leaves-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let start = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        for (i, name) in ["a", "b", "c", "d"].iter().enumerate() {
+            let path = dir.join(format!("{}.pem", name));
+            std::fs::write(&path, name).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(start + std::time::Duration::from_secs(60 * i as u64)).unwrap();
+        }
+        // Reading "a" makes it the most recently used
+        let file = std::fs::File::options().write(true).open(dir.join("a.pem")).unwrap();
+        file.set_modified(std::time::SystemTime::now()).unwrap();
+
+        evict_leaves(&dir, 2).unwrap();
+        let mut left: Vec<String> = std::fs::read_dir(&dir).unwrap().flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect();
+        left.sort();
+        assert_eq!(left, vec!["a.pem", "d.pem"]);
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use crate::assets::ReplayRequest;
+use crate::db::get_db;
+use regex::Regex;
+use serde_json::Value;
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// Where a step pulls a value from in its response.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Extractor {
+    /// Path into a JSON body such as `$.data.token` or `$.items[0].id`
+    JsonPath { path: String },
+    /// First capture group (or the whole match) in the body, or in the
+    /// named header when `header` is set
+    Regex { pattern: String, header: Option<String> },
+    /// A response header's value, matched case-insensitively
+    Header { name: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Extraction {
+    /// Later steps reference the value as `{{variable}}`
+    pub variable: String,
+    #[serde(flatten)]
+    pub extractor: Extractor,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainStep {
+    pub name: String,
+    pub request: ReplayRequest,
+    #[serde(default)]
+    pub extract: Vec<Extraction>,
+    /// The chain stops when the response has a different status
+    pub expect_status: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct RequestChain {
+    pub id: i64,
+    pub name: String,
+    #[sqlx(json)]
+    pub steps: Vec<ChainStep>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StepResult {
+    pub name: String,
+    pub request: ReplayRequest,
+    pub status: Option<u16>,
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+    pub time_ms: Option<u64>,
+    pub extracted: HashMap<String, String>,
+    #[serde(flatten)]
+    pub variables: crate::variables::Substitutions,
+    /// Why the chain stopped at this step
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChainRunResult {
+    /// Steps that ran, up to and including the one that failed
+    pub steps: Vec<StepResult>,
+    pub completed: bool,
+    /// Every value extracted during the run
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum PathToken {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses the supported JSONPath subset: dotted keys, `[n]` indexes and
+/// `['key']` brackets, with or without the leading `$`.
+fn parse_json_path(path: &str) -> Result<Vec<PathToken>, String> {
+    let invalid = || format!("Invalid JSON path '{}'", path);
+    let mut rest = path.trim().strip_prefix('$').unwrap_or(path.trim());
+    let mut tokens = Vec::new();
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']').ok_or_else(invalid)?;
+            let inner = r[..end].trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+            tokens.push(match quoted {
+                Some(key) => PathToken::Key(key.to_string()),
+                None => PathToken::Index(inner.parse().map_err(|_| invalid())?),
+            });
+            rest = &r[end + 1..];
+        } else {
+            let r = rest.strip_prefix('.').unwrap_or(rest);
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            tokens.push(PathToken::Key(r[..end].to_string()));
+            rest = &r[end..];
+        }
+    }
+    Ok(tokens)
+}
+
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Result<Option<&'a Value>, String> {
+    Ok(parse_json_path(path)?.iter().try_fold(value, |v, token| match token {
+        PathToken::Key(key) => v.get(key),
+        PathToken::Index(i) => v.get(i),
+    }))
+}
+
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a String> {
+    headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v)
+}
+
+/// Runs one extractor against a response. `Ok(None)` means the value
+/// simply wasn't there; `Err` means the extractor itself is malformed.
+pub fn extract(extractor: &Extractor, headers: &HashMap<String, String>, body: &str) -> Result<Option<String>, String> {
+    match extractor {
+        Extractor::JsonPath { path } => {
+            let Ok(json) = serde_json::from_str::<Value>(body) else {
+                return Ok(None);
+            };
+            Ok(json_path(&json, path)?.filter(|v| !v.is_null()).map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }))
+        }
+        Extractor::Regex { pattern, header: name } => {
+            let re = Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+            let haystack = match name {
+                Some(name) => match header(headers, name) {
+                    Some(v) => v.as_str(),
+                    None => return Ok(None),
+                },
+                None => body,
+            };
+            Ok(re.captures(haystack).and_then(|c| c.get(1).or_else(|| c.get(0))).map(|m| m.as_str().to_string()))
+        }
+        Extractor::Header { name } => Ok(header(headers, name).cloned()),
+    }
+}
+
+fn validate(name: &str, steps: &[ChainStep]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Chain name must not be empty".to_string());
+    }
+    for step in steps {
+        for extraction in &step.extract {
+            if extraction.variable.trim().is_empty() {
+                return Err(format!("Step '{}' has an extraction without a variable name", step.name));
+            }
+            match &extraction.extractor {
+                Extractor::JsonPath { path } => {
+                    parse_json_path(path)?;
+                }
+                Extractor::Regex { pattern, .. } => {
+                    Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+                }
+                Extractor::Header { .. } => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_chain(name: String, steps: Vec<ChainStep>) -> Result<i64, String> {
+    validate(&name, &steps)?;
+    let pool = get_db();
+    let res = sqlx::query("INSERT INTO request_chains (name, steps) VALUES (?, ?)")
+        .bind(name.trim())
+        .bind(sqlx::types::Json(&steps))
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn list_chains() -> Result<Vec<RequestChain>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, RequestChain>("SELECT id, name, steps, created_at, updated_at FROM request_chains ORDER BY name")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_chain(id: i64) -> Result<RequestChain, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, RequestChain>("SELECT id, name, steps, created_at, updated_at FROM request_chains WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Chain {} not found", id))
+}
+
+/// Replaces a chain's name and steps.
+#[tauri::command]
+pub async fn update_chain(id: i64, name: String, steps: Vec<ChainStep>) -> Result<(), String> {
+    validate(&name, &steps)?;
+    let pool = get_db();
+    let res = sqlx::query("UPDATE request_chains SET name = ?, steps = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(name.trim())
+        .bind(sqlx::types::Json(&steps))
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(format!("Chain {} not found", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_chain(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM request_chains WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Runs a chain's steps in order. Values extracted from each response are
+/// available to later steps as `{{variable}}`, taking precedence over the
+/// active environment. The run stops at the first step that can't be sent,
+/// gets an unexpected status, or is missing a value it should extract.
+#[tauri::command]
+pub async fn run_chain(id: i64) -> Result<ChainRunResult, String> {
+    let chain = get_chain(id).await?;
+    let mut resolver = crate::variables::Resolver::active().await?;
+    let mut extracted_all = HashMap::new();
+    let mut results = Vec::new();
+
+    for step in chain.steps {
+        let mut result = StepResult {
+            name: step.name.clone(),
+            request: step.request.clone(),
+            status: None,
+            headers: HashMap::new(),
+            body: None,
+            time_ms: None,
+            extracted: HashMap::new(),
+            variables: Default::default(),
+            error: None,
+        };
+
+        match crate::assets::send_with_variables(&step.request, &mut resolver).await {
+            Err(e) => result.error = Some(e),
+            Ok(res) => {
+                result.status = Some(res.status);
+                result.time_ms = Some(res.time_ms);
+                result.variables = res.variables;
+                if let Some(expected) = step.expect_status.filter(|s| *s != res.status) {
+                    result.error = Some(format!("Expected status {}, got {}", expected, res.status));
+                }
+                for extraction in &step.extract {
+                    if result.error.is_some() {
+                        break;
+                    }
+                    match extract(&extraction.extractor, &res.headers, &res.body) {
+                        Ok(Some(value)) => {
+                            result.extracted.insert(extraction.variable.clone(), value);
+                        }
+                        Ok(None) => result.error = Some(format!("Could not extract '{}'", extraction.variable)),
+                        Err(e) => result.error = Some(e),
+                    }
+                }
+                result.headers = res.headers;
+                result.body = Some(res.body);
+            }
+        }
+
+        resolver.extend(result.extracted.clone());
+        extracted_all.extend(result.extracted.clone());
+        let failed = result.error.is_some();
+        results.push(result);
+        if failed {
+            return Ok(ChainRunResult { steps: results, completed: false, variables: extracted_all });
+        }
+    }
+
+    Ok(ChainRunResult { steps: results, completed: true, variables: extracted_all })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path() {
+        let doc = json!({"data": {"token": "abc", "items": [{"id": 7}]}, "odd key": true});
+        assert_eq!(json_path(&doc, "$.data.token").unwrap(), Some(&json!("abc")));
+        assert_eq!(json_path(&doc, "data.items[0].id").unwrap(), Some(&json!(7)));
+        assert_eq!(json_path(&doc, "$['odd key']").unwrap(), Some(&json!(true)));
+        assert_eq!(json_path(&doc, "$.data.missing").unwrap(), None);
+        assert!(json_path(&doc, "$.items[x]").is_err());
+        assert!(json_path(&doc, "$..token").is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let headers = HashMap::from([("Set-Cookie".to_string(), "session=s3cr3t; Path=/".to_string())]);
+        let body = r#"{"access_token": "tok", "expires_in": 3600}"#;
+        let json_path = Extractor::JsonPath { path: "$.expires_in".to_string() };
+        assert_eq!(extract(&json_path, &headers, body).unwrap().as_deref(), Some("3600"));
+        let cookie = Extractor::Regex { pattern: "session=([^;]+)".to_string(), header: Some("set-cookie".to_string()) };
+        assert_eq!(extract(&cookie, &headers, body).unwrap().as_deref(), Some("s3cr3t"));
+        let whole = Extractor::Regex { pattern: "tok".to_string(), header: None };
+        assert_eq!(extract(&whole, &headers, body).unwrap().as_deref(), Some("tok"));
+        assert_eq!(extract(&Extractor::Header { name: "x-missing".to_string() }, &headers, body).unwrap(), None);
+    }
+}
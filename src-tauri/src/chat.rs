@@ -0,0 +1,276 @@
+use crate::ai::ChatTurn;
+use crate::db::get_db;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+
+/// Characters of a request or response body given to the model.
+const MAX_BODY_CHARS: usize = 4000;
+
+/// Most recent messages sent along with the context on each turn.
+const MAX_HISTORY: i64 = 20;
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct AiChat {
+    pub id: i64,
+    pub asset_id: i64,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Excluding the context message
+    pub message_count: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct AiChatMessage {
+    pub id: i64,
+    pub chat_id: i64,
+    /// `system`, `user` or `assistant`
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AiChatDetail {
+    pub chat: AiChat,
+    /// Oldest first, starting with the context message
+    pub messages: Vec<AiChatMessage>,
+}
+
+#[derive(FromRow)]
+struct ChatAsset {
+    url: String,
+    method: Option<String>,
+    status_code: Option<i64>,
+    req_headers: Option<String>,
+    req_body: Option<String>,
+    res_headers: Option<String>,
+    res_body: Option<String>,
+}
+
+const CHAT_COLUMNS: &str = "c.id, c.asset_id, c.title, c.created_at, c.updated_at, \
+    (SELECT COUNT(*) FROM ai_chat_messages m WHERE m.chat_id = c.id AND m.role != 'system') AS message_count";
+
+/// Stored headers as `Name: value` lines, sorted, with credentials and
+/// token-like values masked.
+pub fn context_headers(raw: Option<&str>) -> String {
+    let headers: BTreeMap<String, String> = raw.and_then(|h| serde_json::from_str(h).ok()).unwrap_or_default();
+    headers
+        .iter()
+        .map(|(k, v)| if crate::ai::is_sensitive_header(k) { format!("{}: ****", k) } else { format!("{}: {}", k, crate::ai::mask_tokens(v)) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A stored body with secrets masked, truncated to `MAX_BODY_CHARS`.
+fn context_body(body: Option<&str>) -> String {
+    match body.filter(|b| !b.trim().is_empty()).map(crate::ai::sanitize_body) {
+        Some(b) if b.chars().count() > MAX_BODY_CHARS => format!("{}\n[truncated]", b.chars().take(MAX_BODY_CHARS).collect::<String>()),
+        Some(b) => b,
+        None => "(empty body)".to_string(),
+    }
+}
+
+/// An open finding: name, effective severity, description and match.
+type FindingRow = (Option<String>, String, Option<String>, Option<String>);
+
+/// The opening system prompt: the asset's request, response and open findings.
+async fn asset_context(asset_id: i64) -> Result<(String, String), String> {
+    let pool = get_db();
+    let asset = sqlx::query_as::<_, ChatAsset>(
+        "SELECT url, method, status_code, req_headers, req_body, res_headers, res_body FROM assets WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(asset_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Asset {} not found", asset_id))?;
+    let findings: Vec<FindingRow> = sqlx::query_as(
+        "SELECT name, COALESCE(severity_override, severity, 'Info'), description, match_content
+         FROM findings WHERE asset_id = ? AND is_false_positive = 0 ORDER BY id",
+    )
+    .bind(asset_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let method = asset.method.as_deref().unwrap_or("GET").to_uppercase();
+    let request = format!(
+        "{} {}\n{}\n\n{}",
+        method,
+        crate::ai::sanitize_url(&asset.url),
+        context_headers(asset.req_headers.as_deref()),
+        context_body(asset.req_body.as_deref())
+    );
+    let response = format!(
+        "Status {}\n{}\n\n{}",
+        asset.status_code.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+        context_headers(asset.res_headers.as_deref()),
+        context_body(asset.res_body.as_deref())
+    );
+    let findings: Vec<String> = findings
+        .iter()
+        .map(|(name, severity, description, evidence)| {
+            format!(
+                "- [{}] {}: {} (evidence: {})",
+                severity,
+                name.as_deref().unwrap_or("Unnamed finding"),
+                description.as_deref().unwrap_or(""),
+                crate::ai::mask_evidence(evidence.as_deref().unwrap_or(""))
+            )
+        })
+        .collect();
+    let findings = if findings.is_empty() { "none".to_string() } else { findings.join("\n") };
+    let prompt = crate::prompts::prompt("chat", &[("request", &request), ("response", &response), ("findings", &findings)]).await?;
+    Ok((format!("{} {}", method, asset.url), prompt))
+}
+
+async fn fetch_chat(id: i64) -> Result<AiChat, String> {
+    sqlx::query_as::<_, AiChat>(&format!("SELECT {} FROM ai_chats c WHERE c.id = ?", CHAT_COLUMNS))
+        .bind(id)
+        .fetch_optional(&get_db())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Chat {} not found", id))
+}
+
+/// Starts a conversation about an asset, seeded with its request, response
+/// and open findings. Nothing is sent to the model until the first message.
+#[tauri::command]
+pub async fn start_ai_chat(asset_id: i64) -> Result<AiChatDetail, String> {
+    let (title, context) = asset_context(asset_id).await?;
+    let pool = get_db();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let chat_id = sqlx::query("INSERT INTO ai_chats (asset_id, title) VALUES (?, ?)")
+        .bind(asset_id)
+        .bind(&title)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+    sqlx::query("INSERT INTO ai_chat_messages (chat_id, role, content) VALUES (?, 'system', ?)")
+        .bind(chat_id)
+        .bind(&context)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get_ai_chat(chat_id).await
+}
+
+/// Sends `content` with the chat's context and recent history, and returns
+/// the model's reply. Both messages are stored only once the reply arrives,
+/// so a failed call leaves the conversation unchanged.
+#[tauri::command]
+pub async fn send_ai_chat_message(chat_id: i64, content: String) -> Result<AiChatMessage, String> {
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return Err("Message must not be empty".to_string());
+    }
+    fetch_chat(chat_id).await?;
+    let pool = get_db();
+    let context: Option<String> =
+        sqlx::query_scalar("SELECT content FROM ai_chat_messages WHERE chat_id = ? AND role = 'system' ORDER BY id LIMIT 1")
+            .bind(chat_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let mut history: Vec<ChatTurn> = sqlx::query_as::<_, (String, String)>(
+        "SELECT role, content FROM ai_chat_messages WHERE chat_id = ? AND role != 'system' ORDER BY id DESC LIMIT ?",
+    )
+    .bind(chat_id)
+    .bind(MAX_HISTORY)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|(role, content)| ChatTurn { role, content })
+    .collect();
+    history.reverse();
+
+    let mut messages: Vec<ChatTurn> = context.map(|content| ChatTurn { role: "system".to_string(), content }).into_iter().collect();
+    messages.extend(history);
+    messages.push(ChatTurn { role: "user".to_string(), content: content.clone() });
+    let reply = crate::ai::chat(&messages).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut reply_id = 0;
+    for (role, text) in [("user", &content), ("assistant", &reply)] {
+        reply_id = sqlx::query_scalar("INSERT INTO ai_chat_messages (chat_id, role, content) VALUES (?, ?, ?) RETURNING id")
+            .bind(chat_id)
+            .bind(role)
+            .bind(text)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    sqlx::query("UPDATE ai_chats SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(chat_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    sqlx::query_as::<_, AiChatMessage>(
+        "SELECT id, chat_id, role, content, created_at FROM ai_chat_messages WHERE id = ?",
+    )
+    .bind(reply_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Conversations about an asset, most recently active first.
+#[tauri::command]
+pub async fn list_ai_chats(asset_id: i64) -> Result<Vec<AiChat>, String> {
+    sqlx::query_as::<_, AiChat>(&format!(
+        "SELECT {} FROM ai_chats c WHERE c.asset_id = ? ORDER BY c.updated_at DESC, c.id DESC",
+        CHAT_COLUMNS
+    ))
+    .bind(asset_id)
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_ai_chat(id: i64) -> Result<AiChatDetail, String> {
+    let chat = fetch_chat(id).await?;
+    let messages = sqlx::query_as::<_, AiChatMessage>(
+        "SELECT id, chat_id, role, content, created_at FROM ai_chat_messages WHERE chat_id = ? ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(AiChatDetail { chat, messages })
+}
+
+/// Deletes a conversation together with its messages.
+#[tauri::command]
+pub async fn delete_ai_chat(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    sqlx::query("DELETE FROM ai_chats WHERE id = ?")
+        .bind(id)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_headers() {
+        let raw = r#"{"Content-Type": "application/json", "Authorization": "Bearer abc", "X-Api-Key": "k"}"#;
+        assert_eq!(context_headers(Some(raw)), "Authorization: ****\nContent-Type: application/json\nX-Api-Key: ****");
+        assert_eq!(context_headers(None), "");
+        assert!(context_body(Some(&"a".repeat(MAX_BODY_CHARS + 1))).ends_with("[truncated]"));
+        assert_eq!(context_body(Some("  ")), "(empty body)");
+        assert_eq!(context_body(Some(r#"{"password": "hunter2"}"#)), r#"{"password":"REDACTED"}"#);
+        assert_eq!(context_body(Some("Bearer eyJhbGciOiJIUzI1NiJ9.payload")), "Bearer eyJh****");
+    }
+}
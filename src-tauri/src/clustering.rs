@@ -0,0 +1,210 @@
+use crate::analysis::FindingSeverity;
+use crate::db::get_db;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+
+/// Texts embedded per model call.
+const EMBED_BATCH: usize = 32;
+
+/// Cosine similarity at which two findings share a cluster by default.
+const DEFAULT_THRESHOLD: f32 = 0.92;
+
+/// Characters of a finding embedded; the rest rarely changes its meaning.
+const MAX_TEXT: usize = 1000;
+
+/// Cluster assignment of the last call, keyed by `assignment_key` and
+/// reused until the findings, the model or the threshold change.
+static ASSIGNMENT: Mutex<Option<(u64, Vec<usize>)>> = Mutex::new(None);
+
+#[derive(Debug, sqlx::FromRow)]
+struct ClusterRow {
+    id: i64,
+    asset_id: i64,
+    rule_id: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    severity: String,
+    match_content: Option<String>,
+    model: Option<String>,
+    text: Option<String>,
+    vector: Option<sqlx::types::Json<Vec<f32>>>,
+}
+
+/// Near-duplicate open findings, possibly spread over many assets.
+#[derive(Serialize, Debug)]
+pub struct FindingCluster {
+    /// The finding that seeded the cluster
+    pub representative_id: i64,
+    pub name: String,
+    /// Most severe member, using analyst overrides
+    pub severity: FindingSeverity,
+    pub rule_ids: Vec<String>,
+    pub finding_ids: Vec<i64>,
+    pub asset_count: usize,
+    pub sample_match: String,
+}
+
+/// What gets embedded for a finding.
+pub fn finding_text(name: &str, description: &str, match_content: &str) -> String {
+    format!("{}\n{}\n{}", name, description, match_content).chars().take(MAX_TEXT).collect()
+}
+
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 { 0.0 } else { dot / denom }
+}
+
+/// Greedy leader clustering: each vector joins the first cluster whose
+/// leader is at least `threshold` similar, or starts a new one. Returns
+/// the cluster index of every vector.
+pub fn cluster(vectors: &[Vec<f32>], threshold: f32) -> Vec<usize> {
+    let mut leaders: Vec<usize> = Vec::new();
+    vectors
+        .iter()
+        .enumerate()
+        .map(|(i, v)| match leaders.iter().position(|&l| cosine(&vectors[l], v) >= threshold) {
+            Some(c) => c,
+            None => {
+                leaders.push(i);
+                leaders.len() - 1
+            }
+        })
+        .collect()
+}
+
+/// Identifies what an assignment was computed from: the model, the
+/// threshold and every finding's id and embedded fields.
+fn assignment_key(rows: &[ClusterRow], model: &str, threshold: f32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (model, threshold.to_bits()).hash(&mut hasher);
+    for row in rows {
+        (row.id, &row.name, &row.description, &row.match_content).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Embeds findings whose cached vector is missing or stale, sharing one
+/// call between findings with identical text.
+async fn refresh_embeddings(rows: &mut [ClusterRow], model: &str) -> Result<(), String> {
+    let mut pending: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        let text = finding_text(
+            row.name.as_deref().unwrap_or(""),
+            row.description.as_deref().unwrap_or(""),
+            row.match_content.as_deref().unwrap_or(""),
+        );
+        let fresh = row.vector.is_some() && row.model.as_deref() == Some(model) && row.text.as_deref() == Some(text.as_str());
+        if !fresh {
+            pending.entry(text).or_default().push(i);
+        }
+    }
+    let texts: Vec<String> = pending.keys().cloned().collect();
+    let pool = get_db();
+    for batch in texts.chunks(EMBED_BATCH) {
+        let vectors = crate::ai::embed(model, batch).await?;
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        for (text, vector) in batch.iter().zip(vectors) {
+            for &i in &pending[text] {
+                sqlx::query("INSERT OR REPLACE INTO finding_embeddings (finding_id, model, text, vector) VALUES (?, ?, ?, ?)")
+                    .bind(rows[i].id)
+                    .bind(model)
+                    .bind(text)
+                    .bind(sqlx::types::Json(&vector))
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                rows[i].vector = Some(sqlx::types::Json(vector.clone()));
+            }
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Groups open findings whose embedded name, description and match are at
+/// least `threshold` cosine-similar (default 0.92), largest first. Clusters
+/// smaller than `min_size` (default 2) are left out. Missing embeddings are
+/// computed first, so the first call on a large workspace takes a while;
+/// the clustering itself is reused until the findings change.
+#[tauri::command]
+pub async fn get_finding_clusters(threshold: Option<f32>, min_size: Option<usize>) -> Result<Vec<FindingCluster>, String> {
+    let model = crate::ai::model_for(crate::ai::AiTask::Embedding).await?;
+    let mut rows = sqlx::query_as::<_, ClusterRow>(
+        "SELECT f.id, f.asset_id, f.rule_id, f.name, f.description, COALESCE(f.severity_override, f.severity, 'Info') AS severity,
+                f.match_content, e.model, e.text, e.vector
+         FROM findings f
+         JOIN assets a ON a.id = f.asset_id
+         LEFT JOIN finding_embeddings e ON e.finding_id = f.id
+         WHERE f.is_false_positive = 0 AND a.deleted_at IS NULL
+         ORDER BY f.id",
+    )
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())?;
+    refresh_embeddings(&mut rows, &model).await?;
+
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD).clamp(0.0, 1.0);
+    let key = assignment_key(&rows, &model, threshold);
+    let cached = ASSIGNMENT.lock().unwrap().as_ref().filter(|(k, _)| *k == key).map(|(_, a)| a.clone());
+    let assignment = match cached {
+        Some(assignment) => assignment,
+        None => {
+            let vectors: Vec<Vec<f32>> = rows.iter_mut().map(|r| r.vector.take().map(|v| v.0).unwrap_or_default()).collect();
+            let assignment = tauri::async_runtime::spawn_blocking(move || cluster(&vectors, threshold)).await.map_err(|e| e.to_string())?;
+            *ASSIGNMENT.lock().unwrap() = Some((key, assignment.clone()));
+            assignment
+        }
+    };
+
+    let mut clusters: Vec<FindingCluster> = Vec::new();
+    let mut assets: Vec<std::collections::HashSet<i64>> = Vec::new();
+    for (row, c) in rows.iter().zip(assignment) {
+        let severity = FindingSeverity::from_str(&row.severity);
+        let rule_id = row.rule_id.clone().unwrap_or_else(|| "unknown".to_string());
+        if c == clusters.len() {
+            clusters.push(FindingCluster {
+                representative_id: row.id,
+                name: row.name.clone().unwrap_or_else(|| rule_id.clone()),
+                severity,
+                rule_ids: Vec::new(),
+                finding_ids: Vec::new(),
+                asset_count: 0,
+                sample_match: row.match_content.as_deref().unwrap_or("").chars().take(200).collect(),
+            });
+            assets.push(Default::default());
+        }
+        let cluster = &mut clusters[c];
+        if (severity as u8) < (cluster.severity as u8) {
+            cluster.severity = severity;
+        }
+        if !cluster.rule_ids.contains(&rule_id) {
+            cluster.rule_ids.push(rule_id);
+        }
+        cluster.finding_ids.push(row.id);
+        assets[c].insert(row.asset_id);
+        cluster.asset_count = assets[c].len();
+    }
+    let min_size = min_size.unwrap_or(2).max(1);
+    clusters.retain(|c| c.finding_ids.len() >= min_size);
+    clusters.sort_by(|a, b| b.finding_ids.len().cmp(&a.finding_ids.len()).then((a.severity as u8).cmp(&(b.severity as u8))));
+    Ok(clusters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster() {
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.99, 0.05], vec![0.7, 0.7], vec![0.0, 0.0]];
+        assert_eq!(cluster(&vectors, 0.95), vec![0, 1, 0, 2, 3]);
+        assert_eq!(cluster(&vectors, 0.5), vec![0, 1, 0, 0, 2]);
+        assert!((cosine(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(finding_text("a", "b", &"x".repeat(2000)).chars().count(), MAX_TEXT);
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use crate::diff::JsonChange;
+use crate::openapi::Schema;
+use crate::templates::{TemplateRules, Templater};
+use std::collections::{BTreeMap, BTreeSet};
+use tauri::AppHandle;
+
+/// One asset as read from a workspace.
+pub struct EndpointSample {
+    pub method: String,
+    /// Templated URL, e.g. `https://api.example.com/users/{id}`
+    pub template: String,
+    pub status_code: Option<i64>,
+    pub res_body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EndpointKey {
+    pub method: String,
+    /// Path template without scheme and host, so captures of the same API
+    /// on different hosts line up
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShapeDifference {
+    pub endpoint: EndpointKey,
+    pub status_codes_a: Vec<i64>,
+    pub status_codes_b: Vec<i64>,
+    /// Differences between the JSON schemas inferred from each side's
+    /// response bodies, as paths into the schema
+    pub schema_changes: Vec<JsonChange>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WorkspaceComparison {
+    pub only_in_a: Vec<EndpointKey>,
+    pub only_in_b: Vec<EndpointKey>,
+    /// Endpoints in both whose status codes or response shapes differ
+    pub changed: Vec<ShapeDifference>,
+    /// Endpoints in both that look the same
+    pub unchanged: usize,
+}
+
+#[derive(Default)]
+struct Shape {
+    statuses: BTreeSet<i64>,
+    schema: Option<Schema>,
+}
+
+fn endpoint_path(template: &str) -> String {
+    let rest = template.split_once("://").map(|(_, r)| r).unwrap_or(template);
+    match rest.find('/') {
+        Some(i) => rest[i..].to_string(),
+        None => "/".to_string(),
+    }
+}
+
+fn shapes(samples: &[EndpointSample]) -> BTreeMap<EndpointKey, Shape> {
+    let mut out: BTreeMap<EndpointKey, Shape> = BTreeMap::new();
+    for sample in samples {
+        let key = EndpointKey { method: sample.method.to_uppercase(), path: endpoint_path(&sample.template) };
+        let shape = out.entry(key).or_default();
+        shape.statuses.extend(sample.status_code);
+        if let Some(body) = sample.res_body.as_deref().and_then(|b| serde_json::from_str(b).ok()) {
+            let schema = Schema::infer(&body);
+            shape.schema = Some(match shape.schema.take() {
+                Some(existing) => existing.merge(schema),
+                None => schema,
+            });
+        }
+    }
+    out
+}
+
+/// Matches endpoints by method and host-less path template and compares
+/// the status codes and inferred response schemas of those in both.
+pub fn compare_inventories(a: &[EndpointSample], b: &[EndpointSample]) -> WorkspaceComparison {
+    let (a, mut b) = (shapes(a), shapes(b));
+    let mut comparison = WorkspaceComparison { only_in_a: Vec::new(), only_in_b: Vec::new(), changed: Vec::new(), unchanged: 0 };
+
+    for (key, shape_a) in a {
+        let Some(shape_b) = b.remove(&key) else {
+            comparison.only_in_a.push(key);
+            continue;
+        };
+        let schema = |s: &Shape| s.schema.as_ref().map(Schema::to_value).unwrap_or_default();
+        let schema_changes = crate::diff::diff_json(&schema(&shape_a), &schema(&shape_b));
+        if schema_changes.is_empty() && shape_a.statuses == shape_b.statuses {
+            comparison.unchanged += 1;
+        } else {
+            comparison.changed.push(ShapeDifference {
+                endpoint: key,
+                status_codes_a: shape_a.statuses.into_iter().collect(),
+                status_codes_b: shape_b.statuses.into_iter().collect(),
+                schema_changes,
+            });
+        }
+    }
+    comparison.only_in_b = b.into_keys().collect();
+    comparison
+}
+
+/// An asset row: url, path template, method, status code and response body.
+type SampleRow = (String, Option<String>, Option<String>, Option<i64>, Option<String>);
+
+/// Live assets of a workspace. Workspaces not opened since soft delete or
+/// path templates were added lack those columns and are read without them.
+async fn load_samples(app_handle: &AppHandle, name: &str, passphrase: Option<&str>) -> Result<Vec<EndpointSample>, String> {
+    let mut conn = crate::db::open_workspace_read_only(app_handle, name, passphrase).await?;
+    let columns: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info('assets')")
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let has = |c: &str| columns.iter().any(|(n,)| n == c);
+
+    let rows: Vec<SampleRow> = sqlx::query_as(&format!(
+        "SELECT url, {}, method, status_code, res_body FROM assets {}",
+        if has("path_template") { "path_template" } else { "NULL" },
+        if has("deleted_at") { "WHERE deleted_at IS NULL" } else { "" },
+    ))
+    .fetch_all(&mut conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let templater = Templater::new(TemplateRules::default())?;
+    Ok(rows
+        .into_iter()
+        .map(|(url, template, method, status_code, res_body)| EndpointSample {
+            template: template.unwrap_or_else(|| templater.template(&url)),
+            method: method.unwrap_or_else(|| "GET".to_string()),
+            status_code,
+            res_body,
+        })
+        .collect())
+}
+
+/// Diffs the endpoint inventories of two workspaces, e.g. a production
+/// capture against a staging one. Either may be the active workspace.
+#[tauri::command]
+pub async fn compare_workspaces(
+    app_handle: AppHandle,
+    ws_a: String,
+    ws_b: String,
+    passphrase_a: Option<String>,
+    passphrase_b: Option<String>,
+) -> Result<WorkspaceComparison, String> {
+    let a = load_samples(&app_handle, &ws_a, passphrase_a.as_deref()).await?;
+    let b = load_samples(&app_handle, &ws_b, passphrase_b.as_deref()).await?;
+    Ok(compare_inventories(&a, &b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(method: &str, template: &str, status: i64, body: &str) -> EndpointSample {
+        EndpointSample {
+            method: method.to_string(),
+            template: template.to_string(),
+            status_code: Some(status),
+            res_body: Some(body.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_compare_inventories() {
+        let prod = [
+            sample("GET", "https://api.example.com/users/{id}", 200, r#"{"id": 1, "name": "a"}"#),
+            sample("GET", "https://api.example.com/health", 200, "ok"),
+            sample("DELETE", "https://api.example.com/users/{id}", 204, ""),
+        ];
+        let staging = [
+            sample("GET", "https://staging.example.com/users/{id}", 200, r#"{"id": 1, "name": "a", "debug": {}}"#),
+            sample("get", "https://staging.example.com/health", 200, "ok"),
+            sample("POST", "https://staging.example.com/admin", 201, "{}"),
+        ];
+        let result = compare_inventories(&prod, &staging);
+
+        assert_eq!(result.only_in_a, vec![EndpointKey { method: "DELETE".to_string(), path: "/users/{id}".to_string() }]);
+        assert_eq!(result.only_in_b, vec![EndpointKey { method: "POST".to_string(), path: "/admin".to_string() }]);
+        assert_eq!(result.unchanged, 1);
+        assert_eq!(result.changed.len(), 1);
+        let paths: Vec<&str> = result.changed[0].schema_changes.iter().map(|c| c.path.as_str()).collect();
+        assert!(paths.contains(&"$.properties.debug"));
+    }
+}
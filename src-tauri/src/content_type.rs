@@ -0,0 +1,275 @@
+use crate::analysis::{Finding, FindingSeverity};
+use crate::fuzz_baseline::{Baseline, ResponseDiff};
+use crate::fuzzer::{FuzzResult, FuzzTask};
+use crate::pacing::Pacer;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::Emitter;
+
+/// Baseline samples taken with the original Content-Type.
+const SAMPLES: usize = 2;
+
+/// One way of resending the task's body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub label: String,
+    /// `None` sends no Content-Type at all
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyKind {
+    Json,
+    Form,
+    Other,
+}
+
+pub fn body_kind(content_type: Option<&str>, body: &str) -> BodyKind {
+    let ct = content_type.unwrap_or("").to_lowercase();
+    if ct.contains("json") || serde_json::from_str::<Value>(body).is_ok_and(|v| v.is_object() || v.is_array()) {
+        BodyKind::Json
+    } else if ct.contains("x-www-form-urlencoded") || (!body.trim().is_empty() && body.split('&').all(|pair| pair.contains('='))) {
+        BodyKind::Form
+    } else {
+        BodyKind::Other
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Top-level fields as name/value pairs; nested values stay JSON.
+fn json_pairs(body: &str) -> Option<Vec<(String, String)>> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    Some(value.as_object()?.iter().map(|(k, v)| (k.clone(), scalar(v))).collect())
+}
+
+fn form_pairs(body: &str) -> Vec<(String, String)> {
+    url::form_urlencoded::parse(body.as_bytes()).map(|(k, v)| (k.into_owned(), v.into_owned())).collect()
+}
+
+pub fn to_form(pairs: &[(String, String)]) -> String {
+    url::form_urlencoded::Serializer::new(String::new()).extend_pairs(pairs).finish()
+}
+
+pub fn to_json(pairs: &[(String, String)]) -> String {
+    let object: serde_json::Map<String, Value> = pairs.iter().map(|(k, v)| (k.clone(), Value::String(v.clone()))).collect();
+    Value::Object(object).to_string()
+}
+
+pub fn to_xml(pairs: &[(String, String)]) -> String {
+    let fields: String = pairs
+        .iter()
+        .filter(|(k, _)| !k.is_empty() && k.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-'))
+        .map(|(k, v)| format!("<{0}>{1}</{0}>", k, xml_escape(v)))
+        .collect();
+    format!("<?xml version=\"1.0\"?><root>{}</root>", fields)
+}
+
+/// The body relabelled, re-encoded into other formats, and sent without
+/// a Content-Type.
+pub fn variants(content_type: Option<&str>, body: &str) -> Vec<Variant> {
+    let variant = |label: &str, content_type: Option<&str>, body: String| Variant {
+        label: label.to_string(),
+        content_type: content_type.map(str::to_string),
+        body,
+    };
+    let kind = body_kind(content_type, body);
+    let pairs = match kind {
+        BodyKind::Json => json_pairs(body),
+        BodyKind::Form => Some(form_pairs(body)),
+        BodyKind::Other => None,
+    };
+
+    let mut out = vec![
+        variant("missing content-type", None, body.to_string()),
+        variant("as text/plain", Some("text/plain"), body.to_string()),
+    ];
+    match kind {
+        BodyKind::Json => {
+            out.push(variant("json as application/xml", Some("application/xml"), body.to_string()));
+            out.push(variant("json charset utf-7", Some("application/json; charset=utf-7"), body.to_string()));
+            out.push(variant("json charset ibm037", Some("application/json; charset=ibm037"), body.to_string()));
+            out.push(variant("json as text/json", Some("text/json"), body.to_string()));
+        }
+        BodyKind::Form => {
+            out.push(variant("form as application/json", Some("application/json"), body.to_string()));
+            out.push(variant("form charset utf-7", Some("application/x-www-form-urlencoded; charset=utf-7"), body.to_string()));
+        }
+        BodyKind::Other => {}
+    }
+    if let Some(pairs) = pairs.filter(|p| !p.is_empty()) {
+        if kind == BodyKind::Json {
+            out.push(variant("converted to form", Some("application/x-www-form-urlencoded"), to_form(&pairs)));
+        } else {
+            out.push(variant("converted to json", Some("application/json"), to_json(&pairs)));
+        }
+        out.push(variant("converted to xml", Some("application/xml"), to_xml(&pairs)));
+    }
+    out
+}
+
+fn confusion_finding(severity: FindingSeverity, description: String, variant: &Variant) -> Finding {
+    Finding {
+        id: None,
+        rule_id: "ACTIVE-CONTENT-TYPE-CONFUSION".to_string(),
+        name: "Content-Type Confusion".to_string(),
+        description,
+        severity,
+        match_content: variant.content_type.clone().unwrap_or_else(|| "(none)".to_string()),
+        notes: Some(variant.label.clone()),
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+/// Judges one variant against the responses to the original request.
+pub fn judge_variant(baseline_status: u16, variant: &Variant, status: u16, diff: &ResponseDiff) -> Option<Finding> {
+    let ok = |s: u16| (200..300).contains(&s);
+    let ct = variant.content_type.as_deref().unwrap_or("no Content-Type");
+    if ok(status) && !ok(baseline_status) {
+        return Some(confusion_finding(
+            FindingSeverity::Medium,
+            format!("The request was refused with {} but accepted with {} when sent as {}; another parser skips the original's validation.", baseline_status, status, ct),
+            variant,
+        ));
+    }
+    if ok(status) && diff.interesting {
+        return Some(confusion_finding(
+            FindingSeverity::Medium,
+            format!("Sent as {} the request succeeded but was handled differently ({}).", ct, diff.reasons.join("; ")),
+            variant,
+        ));
+    }
+    if status >= 500 && baseline_status < 500 {
+        return Some(confusion_finding(
+            FindingSeverity::Low,
+            format!("Sending the body as {} caused a {} server error, so it reached a parser that failed on it.", ct, status),
+            variant,
+        ));
+    }
+    None
+}
+
+fn original_content_type(task: &FuzzTask) -> Option<String> {
+    task.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type")).map(|(_, v)| v.clone())
+}
+
+/// The variants `run_content_type_test` will send for `task`.
+pub fn task_variants(task: &FuzzTask) -> Vec<Variant> {
+    let task = crate::fuzzer::render_task(task, usize::MAX, "");
+    match task.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        Some(body) => variants(original_content_type(&task).as_deref(), body),
+        None => Vec::new(),
+    }
+}
+
+fn with_content_type(task: &FuzzTask, content_type: Option<&str>, body: &str) -> FuzzTask {
+    let mut headers: std::collections::HashMap<String, String> = task
+        .headers
+        .iter()
+        .filter(|(k, _)| !k.eq_ignore_ascii_case("content-type") && !k.eq_ignore_ascii_case("content-length"))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if let Some(ct) = content_type {
+        headers.insert("Content-Type".to_string(), ct.to_string());
+    }
+    FuzzTask { headers, body: Some(body.to_string()), ..task.clone() }
+}
+
+/// Resends the task's body under other Content-Types and encodings and
+/// flags variants the server parses differently from the original.
+pub async fn run_content_type_test(app_handle: tauri::AppHandle, task: FuzzTask, cancel: &AtomicBool) -> Result<Vec<FuzzResult>, String> {
+    let variants = task_variants(&task);
+    let task = crate::fuzzer::render_task(&task, usize::MAX, "");
+    let body = task.body.clone().filter(|_| !variants.is_empty()).ok_or("Content-Type confusion needs a request body")?;
+    let original_ct = original_content_type(&task);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut pacer = Pacer::new(&task.pacing);
+
+    let original = with_content_type(&task, original_ct.as_deref(), &body);
+    let mut samples = Vec::new();
+    for _ in 0..SAMPLES {
+        if let Ok((status, _, text, _)) = crate::fuzzer::send_paced(&client, &original, "content_type", &mut pacer, cancel).await {
+            samples.push((status, text));
+        }
+    }
+    let baseline_status = samples.first().map_or(0, |(status, _)| *status);
+    let baseline = Baseline::from_samples(samples).ok_or("Baseline request failed")?;
+
+    let total = variants.len();
+    let mut results = Vec::new();
+    for (i, variant) in variants.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let request = with_content_type(&task, variant.content_type.as_deref(), &variant.body);
+        let (status, time_ms, diff) = match crate::fuzzer::send_paced(&client, &request, "content_type", &mut pacer, cancel).await {
+            Ok((status, _, text, ms)) => (status, ms, Some(baseline.diff(status, &text))),
+            Err(_) => (0, 0, None),
+        };
+        let mut finding = diff.as_ref().and_then(|d| judge_variant(baseline_status, variant, status, d));
+        crate::analysis::attach_rule_metadata(finding.as_mut_slice());
+        let res = FuzzResult { payload: variant.label.clone(), status, time_ms, finding, position: None, diff };
+        results.push(res.clone());
+        let _ = app_handle.emit("fuzz-progress", (i + 1, total, res));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants() {
+        let json = r#"{"name": "a<b", "qty": 2}"#;
+        let labels: Vec<String> = variants(Some("application/json"), json).into_iter().map(|v| v.label).collect();
+        assert!(labels.contains(&"converted to form".to_string()) && labels.contains(&"json charset utf-7".to_string()));
+        let form = variants(Some("application/json"), json).into_iter().find(|v| v.label == "converted to form").unwrap();
+        assert_eq!(form.body, "name=a%3Cb&qty=2");
+        let xml = variants(None, json).into_iter().find(|v| v.label == "converted to xml").unwrap();
+        assert_eq!(xml.body, "<?xml version=\"1.0\"?><root><name>a&lt;b</name><qty>2</qty></root>");
+
+        let from_form = variants(None, "user=bob&role=admin");
+        assert_eq!(body_kind(None, "user=bob&role=admin"), BodyKind::Form);
+        let json = from_form.iter().find(|v| v.label == "converted to json").unwrap();
+        assert_eq!(json.body, r#"{"role":"admin","user":"bob"}"#);
+        assert_eq!(variants(None, "plain words").len(), 2);
+    }
+
+    #[test]
+    fn test_judge_variant() {
+        let body = r#"{"ok": true, "items": [1, 2, 3]}"#.to_string();
+        let baseline = Baseline::from_samples(vec![(200, body.clone()), (200, body.clone())]).unwrap();
+        let relabel = variants(Some("application/json"), &body);
+        let by_label = |label: &str| relabel.iter().find(|v| v.label == label).unwrap();
+
+        let same = baseline.diff(200, &body);
+        assert!(judge_variant(200, by_label("json charset utf-7"), 200, &same).is_none());
+        // Tolerating another type is only a finding if the handling changes
+        assert!(judge_variant(200, by_label("as text/plain"), 200, &same).is_none());
+        assert!(judge_variant(200, by_label("as text/plain"), 415, &baseline.diff(415, "Unsupported Media Type")).is_none());
+
+        let changed = baseline.diff(200, r#"{"ok": false, "error": "role ignored"}"#);
+        assert_eq!(judge_variant(200, by_label("json as application/xml"), 200, &changed).unwrap().severity, FindingSeverity::Medium);
+        let bypass = judge_variant(403, by_label("converted to form"), 200, &same).unwrap();
+        assert_eq!(bypass.severity, FindingSeverity::Medium);
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OperationCoverage {
+    pub method: String,
+    /// Path as documented, e.g. `/users/{id}`
+    pub path: String,
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    pub deprecated: bool,
+    /// Captured assets matching this operation
+    pub hits: i64,
+    pub last_seen: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SpecCoverage {
+    pub spec_id: i64,
+    pub total_operations: usize,
+    pub observed_operations: usize,
+    /// 0-100
+    pub coverage_percent: f64,
+    /// Documented but never seen in traffic: forgotten, "zombie" endpoints
+    pub never_seen: Vec<OperationCoverage>,
+    pub observed: Vec<OperationCoverage>,
+}
+
+/// A captured request: method, URL and when it was last seen.
+pub struct Observation {
+    pub method: String,
+    pub url: String,
+    pub last_seen: String,
+}
+
+/// Path prefixes the spec's servers put in front of every documented path.
+fn base_paths(spec: &Value) -> Vec<String> {
+    let mut paths: Vec<String> = crate::import_engine::openapi_base_urls(spec)
+        .iter()
+        .map(|u| url::Url::parse(u).map(|u| u.path().trim_end_matches('/').to_string()).unwrap_or_default())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Matches every documented operation against the observations by method
+/// and path (behind any server base path). Hosts are ignored so traffic
+/// captured against another environment still counts.
+pub fn compute_coverage(spec: &Value, observations: &[Observation]) -> Vec<OperationCoverage> {
+    let Some(paths) = spec.get("paths").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+    let bases = base_paths(spec);
+    let observed: Vec<(String, String, &str)> = observations
+        .iter()
+        .filter_map(|o| {
+            let url = url::Url::parse(&o.url).ok()?;
+            Some((o.method.to_uppercase(), url.path().to_string(), o.last_seen.as_str()))
+        })
+        .collect();
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        for method in crate::import_engine::OPENAPI_METHODS {
+            let Some(op) = item.get(*method) else {
+                continue;
+            };
+            let method = method.to_uppercase();
+            let templates: Vec<String> = bases.iter().map(|b| format!("{}{}", b, path)).collect();
+            let matches: Vec<&str> = observed
+                .iter()
+                .filter(|(m, p, _)| *m == method && templates.iter().any(|t| crate::drift::path_matches(t, p)))
+                .map(|(_, _, seen)| *seen)
+                .collect();
+            operations.push(OperationCoverage {
+                method,
+                path: path.clone(),
+                operation_id: op.get("operationId").and_then(|v| v.as_str()).map(str::to_string),
+                summary: op.get("summary").and_then(|v| v.as_str()).map(str::to_string),
+                deprecated: op.get("deprecated").and_then(|v| v.as_bool()).unwrap_or(false),
+                hits: matches.len() as i64,
+                last_seen: matches.iter().max().map(|s| s.to_string()),
+            });
+        }
+    }
+    operations
+}
+
+/// Cross-references a stored spec with captured traffic and reports the
+/// documented operations that were never observed. Assets seeded from a
+/// spec only count once real traffic has given them a status code.
+#[tauri::command]
+pub async fn get_spec_coverage(spec_id: i64) -> Result<SpecCoverage, String> {
+    let pool = get_db();
+    let (content,): (String,) = sqlx::query_as("SELECT content FROM specs WHERE id = ?")
+        .bind(spec_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Spec {} not found", spec_id))?;
+    let spec = crate::drift::parse_spec(&content).map_err(|e| format!("Spec {} could not be parsed: {}", spec_id, e))?;
+
+    let rows: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT COALESCE(method, 'GET'), url, last_seen FROM assets \
+         WHERE deleted_at IS NULL AND (status_code IS NOT NULL OR COALESCE(source, '') <> 'openapi')",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let observations: Vec<Observation> = rows
+        .into_iter()
+        .map(|(method, url, last_seen)| Observation { method, url, last_seen })
+        .collect();
+
+    let (observed, never_seen): (Vec<_>, Vec<_>) = compute_coverage(&spec, &observations).into_iter().partition(|op| op.hits > 0);
+    let total_operations = observed.len() + never_seen.len();
+    Ok(SpecCoverage {
+        spec_id,
+        total_operations,
+        observed_operations: observed.len(),
+        coverage_percent: if total_operations == 0 { 0.0 } else { observed.len() as f64 * 100.0 / total_operations as f64 },
+        never_seen,
+        observed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compute_coverage() {
+        let spec = json!({
+            "servers": [{"url": "https://api.example.com/v1"}],
+            "paths": {
+                "/users/{id}": {"get": {"operationId": "getUser"}, "delete": {"deprecated": true}},
+                "/health": {"get": {}}
+            }
+        });
+        let seen = |method: &str, url: &str, last_seen: &str| Observation {
+            method: method.to_string(),
+            url: url.to_string(),
+            last_seen: last_seen.to_string(),
+        };
+        let ops = compute_coverage(&spec, &[
+            seen("GET", "https://staging.example.com/v1/users/1", "2024-01-01 00:00:00"),
+            seen("get", "https://api.example.com/v1/users/2?x=1", "2024-02-01 00:00:00"),
+            seen("GET", "https://api.example.com/health", "2024-03-01 00:00:00"),
+        ]);
+
+        let find = |m: &str, p: &str| ops.iter().find(|o| o.method == m && o.path == p).unwrap();
+        let get_user = find("GET", "/users/{id}");
+        assert_eq!(get_user.hits, 2);
+        assert_eq!(get_user.last_seen.as_deref(), Some("2024-02-01 00:00:00"));
+        assert_eq!(get_user.operation_id.as_deref(), Some("getUser"));
+        assert_eq!(find("DELETE", "/users/{id}").hits, 0);
+        assert!(find("DELETE", "/users/{id}").deprecated);
+        // Missing the /v1 base path
+        assert_eq!(find("GET", "/health").hits, 0);
+    }
+}
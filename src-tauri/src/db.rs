@@ -1,10 +1,17 @@
-use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite, migrate::MigrateDatabase, Row};
+use sqlx::{sqlite::{SqliteConnectOptions, SqlitePoolOptions}, ConnectOptions, Connection, Pool, Sqlite, Row};
 use std::fs;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 use std::sync::{RwLock, OnceLock};
 
 static DB_POOL: OnceLock<RwLock<Option<Pool<Sqlite>>>> = OnceLock::new();
 static CURRENT_WORKSPACE: OnceLock<RwLock<String>> = OnceLock::new();
+/// SQLCipher key of the active workspace, kept so copies of it can be keyed too.
+static WORKSPACE_KEY: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+
+/// Opened at startup, before any passphrase could be asked for, so it is
+/// never encrypted.
+pub const DEFAULT_WORKSPACE: &str = "Main Workspace";
 
 fn get_pool_lock() -> &'static RwLock<Option<Pool<Sqlite>>> {
     DB_POOL.get_or_init(|| RwLock::new(None))
@@ -14,35 +21,79 @@ fn get_workspace_lock() -> &'static RwLock<String> {
     CURRENT_WORKSPACE.get_or_init(|| RwLock::new(String::new()))
 }
 
-pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(), sqlx::Error> {
+fn get_key_lock() -> &'static RwLock<Option<String>> {
+    WORKSPACE_KEY.get_or_init(|| RwLock::new(None))
+}
+
+pub fn workspace_path(app_handle: &AppHandle, workspace_name: &str) -> PathBuf {
     let app_dir = app_handle.path().app_data_dir().unwrap();
     if !app_dir.exists() {
         fs::create_dir_all(&app_dir).unwrap();
     }
-    
+
     let safe_name = workspace_name.replace(|c: char| !c.is_alphanumeric(), "_");
-    let db_path = app_dir.join(format!("{}.db", safe_name));
-    let db_url = format!("sqlite://{}", db_path.to_string_lossy());
+    app_dir.join(format!("{}.db", safe_name))
+}
 
-    if !Sqlite::database_exists(&db_url).await.unwrap_or(false) {
-        Sqlite::create_database(&db_url).await?;
+/// Plain SQLite files start with a fixed magic string; SQLCipher files are
+/// indistinguishable from random bytes. Missing or empty files count as plain.
+pub fn is_encrypted_file(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    match fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)) {
+        Ok(()) => &header != b"SQLite format 3\0",
+        Err(_) => false,
     }
+}
+
+fn connect_options(db_path: &Path, passphrase: Option<&str>) -> SqliteConnectOptions {
+    let options = SqliteConnectOptions::new().filename(db_path).create_if_missing(true);
+    match passphrase {
+        Some(key) => options.pragma("key", format!("'{}'", key.replace('\'', "''"))),
+        None => options,
+    }
+}
+
+/// Opens (creating if needed) a workspace and makes it the active one.
+/// `passphrase` is the SQLCipher key for encrypted workspaces; a new
+/// workspace opened with a passphrase is created encrypted.
+pub async fn init_db(app_handle: &AppHandle, workspace_name: &str, passphrase: Option<&str>) -> Result<(), sqlx::Error> {
+    let db_path = workspace_path(app_handle, workspace_name);
 
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
-        .connect(&db_url)
+        .connect_with(connect_options(&db_path, passphrase))
         .await?;
 
+    // A wrong key only surfaces on first read; fail before touching the schema
+    if sqlx::query("SELECT count(*) FROM sqlite_master").execute(&pool).await.is_err() {
+        pool.close().await;
+        return Err(sqlx::Error::Configuration(
+            format!("Could not open workspace {}: wrong passphrase or corrupt file", workspace_name).into(),
+        ));
+    }
+
     // Create tables
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS assets (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             url TEXT NOT NULL,
+            identity TEXT,
+            path_template TEXT,
             method TEXT,
             source TEXT,
+            status_code INTEGER,
+            folder_id INTEGER,
+            first_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
             last_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
             req_body TEXT,
-            res_body TEXT
+            res_body TEXT,
+            req_headers TEXT,
+            res_headers TEXT,
+            notes TEXT,
+            spec_id INTEGER,
+            deleted_at DATETIME,
+            risk_score INTEGER NOT NULL DEFAULT 0
         );",
     )
     .execute(&pool)
@@ -60,7 +111,10 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             notes TEXT,
             is_false_positive INTEGER DEFAULT 0,
             severity_override TEXT,
-            FOREIGN KEY(asset_id) REFERENCES assets(id)
+            refs TEXT,
+            remediation TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(asset_id) REFERENCES assets(id) ON DELETE CASCADE
         );",
     )
     .execute(&pool)
@@ -83,8 +137,8 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             asset_id INTEGER NOT NULL,
             tag_id INTEGER NOT NULL,
             PRIMARY KEY (asset_id, tag_id),
-            FOREIGN KEY (asset_id) REFERENCES assets(id),
-            FOREIGN KEY (tag_id) REFERENCES tags(id)
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
         );",
     )
     .execute(&pool)
@@ -94,6 +148,35 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
     let _ = sqlx::query("ALTER TABLE findings ADD COLUMN notes TEXT").execute(&pool).await;
     let _ = sqlx::query("ALTER TABLE findings ADD COLUMN is_false_positive INTEGER DEFAULT 0").execute(&pool).await;
     let _ = sqlx::query("ALTER TABLE findings ADD COLUMN severity_override TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN spec_id INTEGER").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN status_code INTEGER").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN folder_id INTEGER").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN notes TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN req_headers TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN res_headers TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN identity TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN deleted_at DATETIME").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE assets ADD COLUMN path_template TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE findings ADD COLUMN refs TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE findings ADD COLUMN remediation TEXT").execute(&pool).await;
+    // Suggestions from AI triage, as TriageSuggestion JSON
+    let _ = sqlx::query("ALTER TABLE findings ADD COLUMN ai_triage TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE findings ADD COLUMN ai_triaged_at DATETIME").execute(&pool).await;
+    // Added columns can't default to CURRENT_TIMESTAMP; older rows date from their asset's last sighting
+    if sqlx::query("ALTER TABLE assets ADD COLUMN first_seen DATETIME").execute(&pool).await.is_ok() {
+        sqlx::query("UPDATE assets SET first_seen = last_seen").execute(&pool).await?;
+    }
+    if sqlx::query("ALTER TABLE findings ADD COLUMN created_at DATETIME").execute(&pool).await.is_ok() {
+        sqlx::query("UPDATE findings SET created_at = (SELECT last_seen FROM assets WHERE assets.id = findings.asset_id)").execute(&pool).await?;
+    }
+    // Repeat drift sightings bump these instead of inserting duplicates
+    let _ = sqlx::query("ALTER TABLE findings ADD COLUMN occurrences INTEGER NOT NULL DEFAULT 1").execute(&pool).await;
+    if sqlx::query("ALTER TABLE findings ADD COLUMN last_seen DATETIME").execute(&pool).await.is_ok() {
+        sqlx::query("UPDATE findings SET last_seen = created_at").execute(&pool).await?;
+    }
+    if sqlx::query("ALTER TABLE assets ADD COLUMN risk_score INTEGER NOT NULL DEFAULT 0").execute(&pool).await.is_ok() {
+        crate::risk::refresh_risk_scores(&pool, None).await.map_err(sqlx::Error::Protocol)?;
+    }
 
     // Folders table
     sqlx::query(
@@ -114,11 +197,15 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             description TEXT,
             regex TEXT NOT NULL,
             severity TEXT NOT NULL,
-            rule_id TEXT NOT NULL UNIQUE
+            rule_id TEXT NOT NULL UNIQUE,
+            refs TEXT,
+            remediation TEXT
         );",
     )
     .execute(&pool)
     .await?;
+    let _ = sqlx::query("ALTER TABLE custom_rules ADD COLUMN refs TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE custom_rules ADD COLUMN remediation TEXT").execute(&pool).await;
 
     // App Settings Table
     sqlx::query(
@@ -137,11 +224,26 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             name TEXT NOT NULL,
             content TEXT NOT NULL,
             version TEXT,
+            validation_status TEXT,
+            validation_error TEXT,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         );",
     )
     .execute(&pool)
     .await?;
+    let _ = sqlx::query("ALTER TABLE specs ADD COLUMN validation_error TEXT").execute(&pool).await;
+    if sqlx::query("ALTER TABLE specs ADD COLUMN validation_status TEXT").execute(&pool).await.is_ok() {
+        let specs: Vec<(i64, String)> = sqlx::query_as("SELECT id, content FROM specs").fetch_all(&pool).await?;
+        for (id, content) in specs {
+            let (status, error) = spec_validation(&content);
+            sqlx::query("UPDATE specs SET validation_status = ?, validation_error = ? WHERE id = ?")
+                .bind(status)
+                .bind(error)
+                .bind(id)
+                .execute(&pool)
+                .await?;
+        }
+    }
 
     // Users Table (for multi-user support)
     sqlx::query(
@@ -150,12 +252,14 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             email TEXT NOT NULL UNIQUE,
             name TEXT NOT NULL,
             role TEXT NOT NULL DEFAULT 'Analyst',
+            password_hash TEXT,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
             last_login DATETIME
         );",
     )
     .execute(&pool)
     .await?;
+    let _ = sqlx::query("ALTER TABLE users ADD COLUMN password_hash TEXT").execute(&pool).await;
 
     // Audit Log Table
     sqlx::query(
@@ -172,6 +276,10 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
     )
     .execute(&pool)
     .await?;
+    // Hash chain making edits to the audit trail evident
+    let _ = sqlx::query("ALTER TABLE audit_log ADD COLUMN prev_hash TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE audit_log ADD COLUMN hash TEXT").execute(&pool).await;
+    crate::audit::seal_unchained(&pool).await?;
 
     // Asset History table
     sqlx::query(
@@ -180,12 +288,17 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             asset_id INTEGER NOT NULL,
             status_code INTEGER,
             res_body TEXT,
+            req_headers TEXT,
+            res_headers TEXT,
             timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (asset_id) REFERENCES assets(id)
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
         );",
     )
     .execute(&pool)
     .await?;
+    let _ = sqlx::query("ALTER TABLE asset_history ADD COLUMN req_headers TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE asset_history ADD COLUMN res_headers TEXT").execute(&pool).await;
+    backfill_headers(&pool).await?;
 
     // Finding Assignments Table
     sqlx::query(
@@ -196,7 +309,7 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
             assigned_by INTEGER NOT NULL,
             assigned_at DATETIME DEFAULT CURRENT_TIMESTAMP,
             status TEXT DEFAULT 'Open',
-            FOREIGN KEY (finding_id) REFERENCES findings(id),
+            FOREIGN KEY (finding_id) REFERENCES findings(id) ON DELETE CASCADE,
             FOREIGN KEY (assigned_to) REFERENCES users(id),
             FOREIGN KEY (assigned_by) REFERENCES users(id)
         );",
@@ -204,6 +317,473 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
     .execute(&pool)
     .await?;
 
+    // Protobuf descriptor registry (populated by .proto imports)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS proto_messages (
+            full_name TEXT PRIMARY KEY,
+            fields TEXT NOT NULL,
+            source TEXT
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS proto_enums (
+            full_name TEXT PRIMARY KEY,
+            enum_values TEXT NOT NULL,
+            source TEXT
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS proto_rpcs (
+            path TEXT PRIMARY KEY,
+            input_type TEXT NOT NULL,
+            output_type TEXT NOT NULL,
+            client_streaming INTEGER NOT NULL DEFAULT 0,
+            server_streaming INTEGER NOT NULL DEFAULT 0,
+            source TEXT
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Saved repeater tabs and every response they produced
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS repeater_sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            asset_id INTEGER,
+            url TEXT NOT NULL,
+            method TEXT NOT NULL,
+            headers TEXT,
+            body TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE SET NULL
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS repeater_attempts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id INTEGER NOT NULL,
+            url TEXT NOT NULL,
+            method TEXT NOT NULL,
+            headers TEXT,
+            body TEXT,
+            status INTEGER,
+            res_headers TEXT,
+            res_body TEXT,
+            time_ms INTEGER,
+            error TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (session_id) REFERENCES repeater_sessions(id) ON DELETE CASCADE
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Steps are stored as one JSON array so their order travels with them
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS request_chains (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            steps TEXT NOT NULL DEFAULT '[]',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Payloads are stored as one JSON array, in file order
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS wordlists (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            category TEXT NOT NULL,
+            payloads TEXT NOT NULL DEFAULT '[]',
+            source TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Drift accepted as expected for an asset, keyed like drift findings
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS drift_baseline (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            asset_id INTEGER NOT NULL,
+            rule_id TEXT NOT NULL,
+            match_content TEXT NOT NULL,
+            note TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(asset_id, rule_id, match_content),
+            FOREIGN KEY(asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Tokens sent in out-of-band payloads, and the callbacks they caused
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS oob_payloads (
+            token TEXT PRIMARY KEY,
+            job_id TEXT NOT NULL,
+            url TEXT NOT NULL,
+            method TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            position TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS oob_interactions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            token TEXT NOT NULL,
+            protocol TEXT NOT NULL,
+            remote_addr TEXT NOT NULL,
+            details TEXT NOT NULL,
+            finding_id INTEGER,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(finding_id) REFERENCES findings(id) ON DELETE SET NULL
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // The finding raised for a token, one per kind however many callbacks race
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS oob_findings (
+            token TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            finding_id INTEGER,
+            PRIMARY KEY (token, kind),
+            FOREIGN KEY(finding_id) REFERENCES findings(id) ON DELETE CASCADE
+        );",
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        "INSERT OR IGNORE INTO oob_findings (token, kind, finding_id) \
+         SELECT token, 'ACTIVE-OOB-INTERACTION', MIN(finding_id) FROM oob_interactions WHERE finding_id IS NOT NULL GROUP BY token",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Cached per finding; recomputed when the model or embedded text changes
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS finding_embeddings (
+            finding_id INTEGER PRIMARY KEY,
+            model TEXT NOT NULL,
+            text TEXT NOT NULL,
+            vector TEXT NOT NULL,
+            FOREIGN KEY(finding_id) REFERENCES findings(id) ON DELETE CASCADE
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // The first message of a chat is the system prompt carrying the asset context
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS ai_chats (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            asset_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(asset_id) REFERENCES assets(id) ON DELETE CASCADE
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS ai_chat_messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(chat_id) REFERENCES ai_chats(id) ON DELETE CASCADE
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // One row per model call; tokens are null when the server doesn't report them
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS ai_usage (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task TEXT NOT NULL,
+            model TEXT NOT NULL,
+            prompt_tokens INTEGER,
+            completion_tokens INTEGER,
+            latency_ms INTEGER NOT NULL,
+            success BOOLEAN NOT NULL,
+            error TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // One row per listening (host, port); asset_id is set once promoted
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS open_ports (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host TEXT NOT NULL,
+            ip TEXT NOT NULL,
+            port INTEGER NOT NULL,
+            service TEXT,
+            banner TEXT,
+            http_status INTEGER,
+            http_title TEXT,
+            url TEXT,
+            asset_id INTEGER,
+            first_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
+            last_seen DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(host, port),
+            FOREIGN KEY(asset_id) REFERENCES assets(id) ON DELETE SET NULL
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Latest HTTP probe per recon host
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS host_probes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host TEXT NOT NULL UNIQUE,
+            alive BOOLEAN NOT NULL,
+            scheme TEXT,
+            url TEXT,
+            status_code INTEGER,
+            redirect TEXT,
+            server TEXT,
+            content_length INTEGER,
+            title TEXT,
+            error TEXT,
+            probed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    let _ = sqlx::query("ALTER TABLE host_probes ADD COLUMN favicon_hash INTEGER").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE host_probes ADD COLUMN cert_subject TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE host_probes ADD COLUMN cert_issuer TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE host_probes ADD COLUMN cert_sans TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE host_probes ADD COLUMN cert_not_after TEXT").execute(&pool).await;
+    let _ = sqlx::query("ALTER TABLE host_probes ADD COLUMN cert_self_signed BOOLEAN").execute(&pool).await;
+
+    // Replaced as a whole each time a host is fingerprinted
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS host_technologies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host TEXT NOT NULL,
+            name TEXT NOT NULL,
+            category TEXT NOT NULL,
+            evidence TEXT NOT NULL,
+            detected_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(host, name)
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Subdomain enumerations; finished_at stays NULL while one is running
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS recon_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            domain TEXT NOT NULL,
+            wordlist_id INTEGER,
+            total INTEGER NOT NULL DEFAULT 0,
+            checked INTEGER NOT NULL DEFAULT 0,
+            found INTEGER NOT NULL DEFAULT 0,
+            wildcard_ips TEXT NOT NULL DEFAULT '[]',
+            suppressed INTEGER NOT NULL DEFAULT 0,
+            cancelled BOOLEAN NOT NULL DEFAULT 0,
+            started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            finished_at DATETIME
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Live names found by a recon run; asset_id is set once promoted
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS recon_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id INTEGER NOT NULL,
+            subdomain TEXT NOT NULL,
+            ip TEXT,
+            ips TEXT NOT NULL DEFAULT '[]',
+            status TEXT NOT NULL,
+            asset_id INTEGER,
+            FOREIGN KEY(run_id) REFERENCES recon_runs(id) ON DELETE CASCADE,
+            FOREIGN KEY(asset_id) REFERENCES assets(id) ON DELETE SET NULL
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Recurring subdomain enumerations, at most one per domain
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS recon_schedules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            domain TEXT NOT NULL UNIQUE,
+            wordlist_id INTEGER,
+            concurrency INTEGER,
+            interval_hours INTEGER NOT NULL,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            last_run_at DATETIME,
+            last_run_id INTEGER,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Latest PTR / AS lookup per address
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS ip_info (
+            ip TEXT PRIMARY KEY,
+            ptr TEXT,
+            asn INTEGER,
+            as_name TEXT,
+            prefix TEXT,
+            country TEXT,
+            cloud TEXT,
+            looked_up_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Only customized prompts are stored; the rest use the built-in default
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS prompt_templates (
+            kind TEXT PRIMARY KEY,
+            template TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Finished rate limit tests, keyed by job id
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS rate_limit_tests (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            duration_secs INTEGER NOT NULL,
+            total_requests INTEGER NOT NULL,
+            success_count INTEGER NOT NULL,
+            rate_limited_count INTEGER NOT NULL,
+            error_count INTEGER NOT NULL,
+            avg_latency_ms INTEGER NOT NULL,
+            target_rps INTEGER NOT NULL,
+            achieved_rps REAL NOT NULL,
+            is_vulnerable BOOLEAN NOT NULL,
+            cancelled BOOLEAN NOT NULL DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS rate_limit_bypass_tests (
+            job_id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            limit_after INTEGER,
+            attempts TEXT NOT NULL,
+            cancelled BOOLEAN NOT NULL DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    // Which active checks scans may run, with their payload and rate limits
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scan_profiles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            checks TEXT NOT NULL,
+            wordlist_ids TEXT NOT NULL DEFAULT '[]',
+            concurrency INTEGER NOT NULL DEFAULT 1,
+            pacing TEXT NOT NULL,
+            is_default BOOLEAN NOT NULL DEFAULT 0
+        );",
+    )
+    .execute(&pool)
+    .await?;
+    crate::scan_profiles::seed(&pool).await?;
+    crate::environments::init_tables(&pool).await?;
+
+    // Finished batch scans, keyed by job id
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scan_reports (
+            id TEXT PRIMARY KEY,
+            profile_name TEXT NOT NULL,
+            request TEXT NOT NULL,
+            asset_count INTEGER NOT NULL,
+            check_count INTEGER NOT NULL,
+            completed INTEGER NOT NULL,
+            failed INTEGER NOT NULL,
+            high INTEGER NOT NULL DEFAULT 0,
+            medium INTEGER NOT NULL DEFAULT 0,
+            low INTEGER NOT NULL DEFAULT 0,
+            info INTEGER NOT NULL DEFAULT 0,
+            skipped_checks TEXT NOT NULL DEFAULT '[]',
+            outcomes TEXT NOT NULL DEFAULT '[]',
+            cancelled BOOLEAN NOT NULL DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );",
+    )
+    .execute(&pool)
+    .await?;
+
+    migrate_cascading_keys(&pool).await?;
+
+    for (name, table, column) in INDEXES {
+        sqlx::query(&format!("CREATE INDEX IF NOT EXISTS {} ON {}({})", name, table, column))
+            .execute(&pool)
+            .await?;
+    }
+
+    // Assets from before identity keys existed are keyed and deduplicated once
+    let (unkeyed,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM assets WHERE identity IS NULL")
+        .fetch_one(&pool)
+        .await?;
+    let keyed: Option<(String,)> = sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'index' AND name = 'idx_assets_identity'")
+        .fetch_optional(&pool)
+        .await?;
+    if unkeyed > 0 || keyed.is_none() {
+        let mode = crate::assets::identity_query_mode(&pool).await.unwrap_or_default();
+        let merged = crate::assets::reindex_asset_identities(&pool, mode).await?;
+        if merged > 0 {
+            eprintln!("Merged {} duplicate assets into their method + URL identity", merged);
+        }
+    }
+
+    crate::templates::retemplate_assets(&pool, true).await.map_err(sqlx::Error::Protocol)?;
+
+    init_search_index(&pool).await?;
+
     // Update global state
     {
         let mut pool_guard = get_pool_lock().write().unwrap();
@@ -213,18 +793,451 @@ pub async fn init_db(app_handle: &AppHandle, workspace_name: &str) -> Result<(),
         let mut ws_guard = get_workspace_lock().write().unwrap();
         *ws_guard = workspace_name.to_string();
     }
+    *get_key_lock().write().unwrap() = passphrase.map(|p| p.to_string());
     
     println!("Database initialized: {}", workspace_name);
     Ok(())
 }
 
+/// Fills the header columns of assets captured before they existed, for
+/// bodies that were stored with their HTTP head still attached. Rows with no
+/// recoverable headers get an empty object so they aren't rescanned.
+async fn backfill_headers(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
+    let rows: Vec<(i64, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT id, req_body, res_body FROM assets \
+         WHERE req_headers IS NULL AND res_headers IS NULL \
+         AND (req_body LIKE '% HTTP/1%' OR res_body LIKE 'HTTP/%')"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for (id, req_body, res_body) in rows {
+        let req = req_body.as_deref().and_then(crate::import_engine::leading_header_block).unwrap_or_default();
+        let res = res_body.as_deref().and_then(crate::import_engine::leading_header_block).unwrap_or_default();
+        sqlx::query("UPDATE assets SET req_headers = ?, res_headers = ? WHERE id = ?")
+            .bind(sqlx::types::Json(req))
+            .bind(sqlx::types::Json(res))
+            .bind(id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Tables whose rows belong to an asset (directly or through a finding).
+const CASCADING_TABLES: [&str; 4] = ["findings", "asset_tags", "asset_history", "finding_assignments"];
+
+const INDEXES: [(&str, &str, &str); 17] = [
+    ("idx_assets_url", "assets", "url"),
+    ("idx_assets_last_seen", "assets", "last_seen"),
+    ("idx_assets_deleted_at", "assets", "deleted_at"),
+    ("idx_assets_path_template", "assets", "path_template"),
+    ("idx_assets_folder_id", "assets", "folder_id"),
+    ("idx_folders_parent_id", "folders", "parent_id"),
+    ("idx_findings_asset_id", "findings", "asset_id"),
+    ("idx_findings_rule_match", "findings", "rule_id, match_content"),
+    ("idx_asset_history_asset_id", "asset_history", "asset_id"),
+    ("idx_asset_tags_tag_id", "asset_tags", "tag_id"),
+    ("idx_finding_assignments_finding_id", "finding_assignments", "finding_id"),
+    ("idx_repeater_sessions_asset_id", "repeater_sessions", "asset_id"),
+    ("idx_repeater_attempts_session_id", "repeater_attempts", "session_id"),
+    ("idx_assets_risk_score", "assets", "risk_score"),
+    ("idx_oob_payloads_job_id", "oob_payloads", "job_id"),
+    ("idx_oob_interactions_token", "oob_interactions", "token"),
+    ("idx_recon_results_run_id", "recon_results", "run_id"),
+];
+
+/// Workspaces created before foreign keys cascaded still carry the old
+/// table definitions, and SQLite can't alter a constraint in place. Each
+/// such table is rebuilt from its stored schema with `ON DELETE CASCADE`
+/// added, and rows orphaned by earlier deletes are dropped on the way.
+async fn migrate_cascading_keys(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
+    let parent_ref = regex::Regex::new(r"(?i)REFERENCES\s+(assets|findings|tags)\s*\(\s*id\s*\)(\s+ON\s+DELETE\s+\w+)?").unwrap();
+
+    let mut conn = pool.acquire().await?;
+    let mut rebuilt = false;
+    for table in CASCADING_TABLES {
+        let (sql,): (String,) = sqlx::query_as("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_one(&mut *conn)
+            .await?;
+        if parent_ref.captures_iter(&sql).all(|c| c.get(2).is_some()) {
+            continue;
+        }
+
+        if !rebuilt {
+            // Must be set outside a transaction, and only for this connection
+            sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+            rebuilt = true;
+        }
+        let new_sql = parent_ref
+            .replace_all(&sql, "REFERENCES $1(id) ON DELETE CASCADE")
+            .replacen(&format!("CREATE TABLE {}", table), &format!("CREATE TABLE {}_migrated", table), 1);
+
+        let mut tx = conn.begin().await?;
+        sqlx::query(&new_sql).execute(&mut *tx).await?;
+        sqlx::query(&format!("INSERT INTO {0}_migrated SELECT * FROM {0}", table)).execute(&mut *tx).await?;
+        sqlx::query(&format!("DROP TABLE {}", table)).execute(&mut *tx).await?;
+        sqlx::query(&format!("ALTER TABLE {0}_migrated RENAME TO {0}", table)).execute(&mut *tx).await?;
+        tx.commit().await?;
+    }
+
+    if rebuilt {
+        let orphans: Vec<(String, Option<i64>, String)> = sqlx::query_as("SELECT \"table\", rowid, parent FROM pragma_foreign_key_check")
+            .fetch_all(&mut *conn)
+            .await?;
+        for (table, rowid, parent) in orphans {
+            let Some(rowid) = rowid else { continue };
+            if !CASCADING_TABLES.contains(&table.as_str()) || !["assets", "findings", "tags"].contains(&parent.as_str()) {
+                continue;
+            }
+            sqlx::query(&format!("DELETE FROM {} WHERE rowid = ?", table))
+                .bind(rowid)
+                .execute(&mut *conn)
+                .await?;
+        }
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Full-text indexes over assets and findings. They are external-content
+/// FTS5 tables, so triggers keep them in step with the base rows; whenever
+/// the triggers are (re)created the index is rebuilt from the tables.
+async fn init_search_index(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
+    let indexes: [(&str, &str, &[&str]); 2] = [
+        ("assets_fts", "assets", &["url", "req_body", "res_body", "notes", "req_headers", "res_headers"]),
+        ("findings_fts", "findings", &["name", "description", "match_content", "notes"]),
+    ];
+
+    for (fts, table, columns) in indexes {
+        // An index from before a column was added has to be recreated
+        let existing: Option<(String,)> = sqlx::query_as("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(fts)
+            .fetch_optional(pool)
+            .await?;
+        if existing.is_some_and(|(sql,)| !columns.iter().all(|c| sql.contains(c))) {
+            for suffix in ["_ai", "_ad", "_au"] {
+                sqlx::query(&format!("DROP TRIGGER IF EXISTS {}{}", fts, suffix)).execute(pool).await?;
+            }
+            sqlx::query(&format!("DROP TABLE {}", fts)).execute(pool).await?;
+        }
+
+        // Triggers also vanish when their table is rebuilt, leaving the index stale
+        let synced: Option<(String,)> = sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'trigger' AND name = ?")
+            .bind(format!("{}_ai", fts))
+            .fetch_optional(pool)
+            .await?;
+
+        let cols = columns.join(", ");
+        let new_cols = columns.iter().map(|c| format!("new.{}", c)).collect::<Vec<_>>().join(", ");
+        let old_cols = columns.iter().map(|c| format!("old.{}", c)).collect::<Vec<_>>().join(", ");
+
+        sqlx::query(&format!(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS {fts} USING fts5({cols}, content='{table}', content_rowid='id')"
+        ))
+        .execute(pool)
+        .await?;
+
+        sqlx::query(&format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts}_ai AFTER INSERT ON {table} BEGIN
+                INSERT INTO {fts}(rowid, {cols}) VALUES (new.id, {new_cols});
+            END;"
+        ))
+        .execute(pool)
+        .await?;
+
+        sqlx::query(&format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts}_ad AFTER DELETE ON {table} BEGIN
+                INSERT INTO {fts}({fts}, rowid, {cols}) VALUES ('delete', old.id, {old_cols});
+            END;"
+        ))
+        .execute(pool)
+        .await?;
+
+        // Only re-index when searchable text changes, not on every last_seen bump
+        sqlx::query(&format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts}_au AFTER UPDATE OF {cols} ON {table} BEGIN
+                INSERT INTO {fts}({fts}, rowid, {cols}) VALUES ('delete', old.id, {old_cols});
+                INSERT INTO {fts}(rowid, {cols}) VALUES (new.id, {new_cols});
+            END;"
+        ))
+        .execute(pool)
+        .await?;
+
+        if synced.is_none() {
+            sqlx::query(&format!("INSERT INTO {fts}({fts}) VALUES ('rebuild')"))
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
 pub fn get_db() -> Pool<Sqlite> {
     get_pool_lock().read().unwrap().clone().expect("Database not initialized")
 }
 
+/// Switches to (or creates) a workspace, along with the CA the proxy signs
+/// with. Encrypted workspaces need their passphrase; passing one for a new
+/// workspace creates it encrypted.
+#[tauri::command]
+pub async fn switch_workspace(app_handle: AppHandle, name: String, passphrase: Option<String>) -> Result<(), String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    if passphrase.is_none() && is_encrypted_file(&workspace_path(&app_handle, &name)) {
+        return Err(format!("Workspace {} is encrypted; a passphrase is required", name));
+    }
+    init_db(&app_handle, &name, passphrase.as_deref()).await.map_err(|e| e.to_string())?;
+    crate::users::clear_current_user();
+    crate::certs::load_workspace_ca(&app_handle).await
+}
+
+/// Read-only connection to any workspace, active or not, without running
+/// migrations on it.
+pub async fn open_workspace_read_only(app_handle: &AppHandle, name: &str, passphrase: Option<&str>) -> Result<sqlx::SqliteConnection, String> {
+    let db_path = workspace_path(app_handle, name);
+    if !db_path.exists() {
+        return Err(format!("Workspace {} does not exist", name));
+    }
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    if passphrase.is_none() && is_encrypted_file(&db_path) {
+        return Err(format!("Workspace {} is encrypted; a passphrase is required", name));
+    }
+    let mut conn = connect_options(&db_path, passphrase)
+        .create_if_missing(false)
+        .read_only(true)
+        .connect()
+        .await
+        .map_err(|e| e.to_string())?;
+    if sqlx::query("SELECT count(*) FROM sqlite_master").execute(&mut conn).await.is_err() {
+        return Err(format!("Could not open workspace {}: wrong passphrase or corrupt file", name));
+    }
+    Ok(conn)
+}
+
+#[tauri::command]
+pub fn is_workspace_encrypted(app_handle: AppHandle, name: String) -> bool {
+    is_encrypted_file(&workspace_path(&app_handle, &name))
+}
+
+/// Closes the pool if `name` is the active workspace so its file can be
+/// replaced. Returns whether it was active. The closed pool stays installed
+/// until `init_db` swaps in the reopened one, so commands and schedulers
+/// running meanwhile get an error instead of finding no pool at all.
+pub async fn release_workspace(name: &str) -> bool {
+    if *get_workspace_lock().read().unwrap() != name {
+        return false;
+    }
+    let pool = get_pool_lock().read().unwrap().clone();
+    if let Some(pool) = pool {
+        pool.close().await;
+    }
+    true
+}
+
+pub fn remove_sidecar_files(db_path: &Path) {
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(sidecar));
+    }
+}
+
+/// Migrates an existing plaintext workspace to an encrypted one by exporting
+/// it into a new SQLCipher database and swapping the files. The plaintext
+/// copy is deleted once the encrypted one is in place.
+#[tauri::command]
+pub async fn encrypt_workspace(app_handle: AppHandle, name: String, passphrase: String) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    if passphrase.is_empty() {
+        return Err("Passphrase must not be empty".to_string());
+    }
+    if name == DEFAULT_WORKSPACE {
+        return Err(format!("{} is opened at startup and can't be encrypted", DEFAULT_WORKSPACE));
+    }
+    let db_path = workspace_path(&app_handle, &name);
+    if !db_path.exists() {
+        return Err(format!("Workspace {} does not exist", name));
+    }
+    if is_encrypted_file(&db_path) {
+        return Err(format!("Workspace {} is already encrypted", name));
+    }
+
+    let was_active = release_workspace(&name).await;
+    let result = encrypt_file(&db_path, &passphrase).await;
+
+    if was_active {
+        let key = result.is_ok().then_some(passphrase.as_str());
+        init_db(&app_handle, &name, key).await.map_err(|e| e.to_string())?;
+    }
+    result
+}
+
+/// Exports the plaintext database at `db_path` into a SQLCipher copy keyed
+/// with `passphrase`, then swaps it into place. The plaintext file is only
+/// replaced once the export has succeeded.
+async fn encrypt_file(db_path: &Path, passphrase: &str) -> Result<(), String> {
+    let encrypted_path = db_path.with_extension("db.encrypting");
+    let _ = fs::remove_file(&encrypted_path);
+
+    let export = async {
+        let mut conn = connect_options(db_path, None).connect().await?;
+        sqlx::query("ATTACH DATABASE ? AS encrypted KEY ?")
+            .bind(encrypted_path.to_string_lossy().to_string())
+            .bind(passphrase)
+            .execute(&mut conn)
+            .await?;
+        sqlx::query("SELECT sqlcipher_export('encrypted')").execute(&mut conn).await?;
+        sqlx::query("DETACH DATABASE encrypted").execute(&mut conn).await?;
+        conn.close().await
+    }
+    .await;
+
+    match export {
+        Ok(()) => {
+            remove_sidecar_files(db_path);
+            fs::rename(&encrypted_path, db_path).map_err(|e| e.to_string())
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&encrypted_path);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Changes the key of the encrypted database `conn` is open on.
+async fn rekey(conn: &mut sqlx::SqliteConnection, passphrase: &str) -> Result<(), String> {
+    sqlx::query(&format!("PRAGMA rekey = '{}'", passphrase.replace('\'', "''")))
+        .execute(conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Re-keys an encrypted workspace. The workspace must be the active one, so
+/// the current passphrase has already been proven.
+#[tauri::command]
+pub async fn change_workspace_passphrase(app_handle: AppHandle, name: String, passphrase: String) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    if passphrase.is_empty() {
+        return Err("Passphrase must not be empty".to_string());
+    }
+    if *get_workspace_lock().read().unwrap() != name || !is_encrypted_file(&workspace_path(&app_handle, &name)) {
+        return Err(format!("Workspace {} must be open and encrypted to change its passphrase", name));
+    }
+
+    let pool = get_db();
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    rekey(&mut conn, &passphrase).await?;
+    drop(conn);
+
+    // Pooled connections still hold the old key; reopen with the new one
+    release_workspace(&name).await;
+    init_db(&app_handle, &name, Some(&passphrase)).await.map_err(|e| e.to_string())
+}
+
+/// Writes a consistent copy of the database `conn` is open on to `path`.
+/// `VACUUM INTO` can't carry a SQLCipher key over, so encrypted databases
+/// are exported into a copy attached with `key` instead.
+pub async fn copy_database(conn: &mut sqlx::SqliteConnection, path: &Path, key: Option<&str>) -> Result<(), sqlx::Error> {
+    let path = path.to_string_lossy().to_string();
+    match key {
+        Some(key) => {
+            sqlx::query("ATTACH DATABASE ? AS copy KEY ?").bind(path).bind(key).execute(&mut *conn).await?;
+            let exported = sqlx::query("SELECT sqlcipher_export('copy')").execute(&mut *conn).await;
+            sqlx::query("DETACH DATABASE copy").execute(&mut *conn).await?;
+            exported.map(|_| ())
+        }
+        None => sqlx::query("VACUUM INTO ?").bind(path).execute(&mut *conn).await.map(|_| ()),
+    }
+}
+
+/// Key of the active workspace, `None` when it isn't encrypted.
+pub fn workspace_key() -> Option<String> {
+    get_key_lock().read().unwrap().clone()
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct MaintenanceReport {
+    pub integrity_ok: bool,
+    /// Problems reported by `PRAGMA integrity_check`, empty when healthy
+    pub integrity_errors: Vec<String>,
+    /// Whether the file was rebuilt; skipped when corruption was found
+    pub vacuumed: bool,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+}
+
+/// Size of a workspace on disk including its write-ahead log.
+fn workspace_file_size(db_path: &Path) -> u64 {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    [db_path.to_path_buf(), PathBuf::from(wal)]
+        .iter()
+        .filter_map(|p| fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Checks the active workspace for corruption, refreshes query planner
+/// statistics and rebuilds the file to hand free pages back to the disk.
+/// The rebuild is written to a temporary file that replaces the workspace
+/// only once complete. A corrupt workspace is reported but left untouched.
+/// Encrypted workspaces need their passphrase to reopen after the swap.
 #[tauri::command]
-pub async fn switch_workspace(app_handle: AppHandle, name: String) -> Result<(), String> {
-    init_db(&app_handle, &name).await.map_err(|e| e.to_string())
+pub async fn maintain_workspace(app_handle: AppHandle, passphrase: Option<String>) -> Result<MaintenanceReport, String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    let name = get_current_workspace();
+    let db_path = workspace_path(&app_handle, &name);
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let encrypted = is_encrypted_file(&db_path);
+    if encrypted && passphrase.is_none() {
+        return Err(format!("Workspace {} is encrypted; a passphrase is required", name));
+    }
+
+    let pool = get_db();
+    let mut report = MaintenanceReport {
+        bytes_before: workspace_file_size(&db_path),
+        ..Default::default()
+    };
+
+    let rows: Vec<(String,)> = sqlx::query_as("PRAGMA integrity_check")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    report.integrity_errors = rows.into_iter().map(|(r,)| r).filter(|r| r != "ok").collect();
+    report.integrity_ok = report.integrity_errors.is_empty();
+
+    sqlx::query("ANALYZE").execute(&pool).await.map_err(|e| e.to_string())?;
+
+    if !report.integrity_ok {
+        report.bytes_after = report.bytes_before;
+        return Ok(report);
+    }
+
+    let rebuilt_path = db_path.with_extension("db.vacuum");
+    let _ = fs::remove_file(&rebuilt_path);
+    let rebuild = async {
+        let mut conn = pool.acquire().await?;
+        copy_database(&mut conn, &rebuilt_path, passphrase.as_deref().filter(|_| encrypted)).await
+    }
+    .await;
+    if let Err(e) = rebuild {
+        let _ = fs::remove_file(&rebuilt_path);
+        return Err(e.to_string());
+    }
+
+    release_workspace(&name).await;
+    remove_sidecar_files(&db_path);
+    let swapped = fs::rename(&rebuilt_path, &db_path).map_err(|e| e.to_string());
+    init_db(&app_handle, &name, passphrase.as_deref()).await.map_err(|e| e.to_string())?;
+    swapped?;
+
+    report.vacuumed = true;
+    report.bytes_after = workspace_file_size(&db_path);
+    report.bytes_reclaimed = report.bytes_before.saturating_sub(report.bytes_after);
+    Ok(report)
 }
 
 #[tauri::command]
@@ -325,6 +1338,11 @@ pub struct CustomRule {
     pub regex: String,
     pub severity: String,
     pub rule_id: String,
+    #[serde(default)]
+    #[sqlx(rename = "refs", json(nullable))]
+    pub references: Option<Vec<String>>,
+    #[serde(default)]
+    pub remediation: Option<String>,
 }
 
 #[tauri::command]
@@ -340,12 +1358,14 @@ pub async fn get_custom_rules() -> Result<Vec<CustomRule>, String> {
 #[tauri::command]
 pub async fn add_custom_rule(rule: CustomRule) -> Result<i64, String> {
     let pool = get_db();
-    let res = sqlx::query("INSERT INTO custom_rules (name, description, regex, severity, rule_id) VALUES (?, ?, ?, ?, ?)")
+    let res = sqlx::query("INSERT INTO custom_rules (name, description, regex, severity, rule_id, refs, remediation) VALUES (?, ?, ?, ?, ?, ?, ?)")
         .bind(rule.name)
         .bind(rule.description)
         .bind(rule.regex)
         .bind(rule.severity)
         .bind(rule.rule_id)
+        .bind(rule.references.map(sqlx::types::Json))
+        .bind(rule.remediation)
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -354,6 +1374,7 @@ pub async fn add_custom_rule(rule: CustomRule) -> Result<i64, String> {
 
 #[tauri::command]
 pub async fn delete_custom_rule(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
     let pool = get_db();
     sqlx::query("DELETE FROM custom_rules WHERE id = ?")
         .bind(id)
@@ -369,15 +1390,33 @@ pub struct ApiSpec {
     pub name: String,
     pub content: String,
     pub version: Option<String>,
+    /// `valid`, or `invalid` with the reason in `validation_error`
+    #[sqlx(default)]
+    pub validation_status: Option<String>,
+    #[sqlx(default)]
+    pub validation_error: Option<String>,
+}
+
+/// Status and error stored for a spec's content.
+fn spec_validation(content: &str) -> (&'static str, Option<String>) {
+    match crate::drift::parse_spec(content) {
+        Ok(_) => ("valid", None),
+        Err(e) => ("invalid", Some(e)),
+    }
 }
 
+/// Stores a spec even when it doesn't parse, so the problem shows up in its
+/// validation status rather than as drift checks silently skipping it.
 #[tauri::command]
 pub async fn add_api_spec(name: String, content: String, version: Option<String>) -> Result<i64, String> {
     let pool = get_db();
-    let res = sqlx::query("INSERT INTO specs (name, content, version) VALUES (?, ?, ?)")
+    let (status, error) = spec_validation(&content);
+    let res = sqlx::query("INSERT INTO specs (name, content, version, validation_status, validation_error) VALUES (?, ?, ?, ?, ?)")
         .bind(name)
         .bind(content)
         .bind(version)
+        .bind(status)
+        .bind(error)
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -387,7 +1426,7 @@ pub async fn add_api_spec(name: String, content: String, version: Option<String>
 #[tauri::command]
 pub async fn get_api_specs() -> Result<Vec<ApiSpec>, String> {
     let pool = get_db();
-    let specs = sqlx::query_as::<_, ApiSpec>("SELECT id, name, content, version FROM specs")
+    let specs = sqlx::query_as::<_, ApiSpec>("SELECT id, name, content, version, validation_status, validation_error FROM specs")
         .fetch_all(&pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -396,6 +1435,7 @@ pub async fn get_api_specs() -> Result<Vec<ApiSpec>, String> {
 
 #[tauri::command]
 pub async fn delete_api_spec(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
     let pool = get_db();
     sqlx::query("DELETE FROM specs WHERE id = ?")
         .bind(id)
@@ -407,34 +1447,18 @@ pub async fn delete_api_spec(id: i64) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn get_webhook() -> Result<Option<String>, String> {
-    let pool = get_db();
-    let row: Option<(String,)> = sqlx::query_as("SELECT value FROM app_settings WHERE key = 'webhook'")
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(row.map(|r| r.0))
+    let url: String = crate::settings::get("webhook").await?;
+    Ok(Some(url).filter(|u| !u.is_empty()))
 }
 
 #[tauri::command]
 pub async fn set_webhook(url: String) -> Result<(), String> {
-    let pool = get_db();
-    sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES ('webhook', ?)")
-        .bind(url)
-        .execute(&pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    crate::settings::write(&get_db(), "webhook", serde_json::Value::String(url.trim().to_string())).await
 }
 #[tauri::command]
 pub async fn send_notification(title: String, message: String) -> Result<(), String> {
-    let pool = get_db();
-    let row: Option<(String,)> = sqlx::query_as("SELECT value FROM app_settings WHERE key = 'webhook'")
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    let webhook_url = match row {
-        Some(r) => r.0,
+    let webhook_url = match get_webhook().await? {
+        Some(url) => url,
         None => return Err("Webhook URL not configured in settings".to_string()),
     };
 
@@ -451,3 +1475,43 @@ pub async fn send_notification(title: String, message: String) -> Result<(), Str
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read_secret(db_path: &Path, passphrase: Option<&str>) -> Result<String, sqlx::Error> {
+        let mut conn = connect_options(db_path, passphrase).create_if_missing(false).connect().await?;
+        let (value,): (String,) = sqlx::query_as("SELECT value FROM secrets").fetch_one(&mut conn).await?;
+        conn.close().await?;
+        Ok(value)
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_and_rekey_round_trip() {
+        let dir = std::env::temp_dir().join(format!("apisec-encrypt-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("workspace.db");
+
+        let mut conn = connect_options(&db_path, None).connect().await.unwrap();
+        sqlx::query("CREATE TABLE secrets (value TEXT NOT NULL)").execute(&mut conn).await.unwrap();
+        sqlx::query("INSERT INTO secrets (value) VALUES ('kept')").execute(&mut conn).await.unwrap();
+        conn.close().await.unwrap();
+        assert!(!is_encrypted_file(&db_path));
+
+        encrypt_file(&db_path, "first pass'phrase").await.unwrap();
+        assert!(is_encrypted_file(&db_path));
+        assert!(!db_path.with_extension("db.encrypting").exists());
+        assert_eq!(read_secret(&db_path, Some("first pass'phrase")).await.unwrap(), "kept");
+        assert!(read_secret(&db_path, Some("wrong")).await.is_err());
+        assert!(read_secret(&db_path, None).await.is_err());
+
+        let mut conn = connect_options(&db_path, Some("first pass'phrase")).connect().await.unwrap();
+        rekey(&mut conn, "second").await.unwrap();
+        conn.close().await.unwrap();
+        assert_eq!(read_secret(&db_path, Some("second")).await.unwrap(), "kept");
+        assert!(read_secret(&db_path, Some("first pass'phrase")).await.is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
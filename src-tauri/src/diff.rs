@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Above this many line pairs the LCS table gets too large; the differing
+/// middle is reported as one removal plus one addition instead.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Added,
+    Removed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonOp {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonChange {
+    /// `$`-rooted path such as `$.user.roles[0]`
+    pub path: String,
+    pub op: JsonOp,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HeaderChange {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// JSON bodies are compared per key; anything else per line.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum BodyDiff {
+    Json { changes: Vec<JsonChange> },
+    Text { lines: Vec<DiffLine> },
+}
+
+/// Line diff of two texts. Common leading and trailing lines are matched
+/// directly; the rest goes through a longest-common-subsequence table.
+pub fn diff_lines(before: &str, after: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let line = |op, text: &str| DiffLine { op, text: text.to_string() };
+    let mut out: Vec<DiffLine> = a[..prefix].iter().map(|t| line(DiffOp::Equal, t)).collect();
+
+    if mid_a.len().saturating_mul(mid_b.len()) > MAX_LCS_CELLS {
+        out.extend(mid_a.iter().map(|t| line(DiffOp::Removed, t)));
+        out.extend(mid_b.iter().map(|t| line(DiffOp::Added, t)));
+    } else {
+        // lcs[i][j] = LCS length of mid_a[i..] and mid_b[j..]
+        let (n, m) = (mid_a.len(), mid_b.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if mid_a[i] == mid_b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && mid_a[i] == mid_b[j] {
+                out.push(line(DiffOp::Equal, mid_a[i]));
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                out.push(line(DiffOp::Added, mid_b[j]));
+                j += 1;
+            } else {
+                out.push(line(DiffOp::Removed, mid_a[i]));
+                i += 1;
+            }
+        }
+    }
+
+    out.extend(a[a.len() - suffix..].iter().map(|t| line(DiffOp::Equal, t)));
+    out
+}
+
+/// Per-key differences between two JSON documents. Objects are compared
+/// key by key and arrays index by index; only changed leaves are listed.
+pub fn diff_json(before: &Value, after: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    walk_json("$", before, after, &mut changes);
+    changes
+}
+
+fn walk_json(path: &str, before: &Value, after: &Value, out: &mut Vec<JsonChange>) {
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let child = format!("{}.{}", path, key);
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => walk_json(&child, x, y, out),
+                    (x, y) => out.push(change(child, x, y)),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => walk_json(&child, x, y, out),
+                    (x, y) => out.push(change(child, x, y)),
+                }
+            }
+        }
+        (a, b) if a != b => out.push(change(path.to_string(), Some(a), Some(b))),
+        _ => {}
+    }
+}
+
+fn change(path: String, before: Option<&Value>, after: Option<&Value>) -> JsonChange {
+    let op = match (before, after) {
+        (None, _) => JsonOp::Added,
+        (_, None) => JsonOp::Removed,
+        _ => JsonOp::Changed,
+    };
+    JsonChange {
+        path,
+        op,
+        before: before.cloned(),
+        after: after.cloned(),
+    }
+}
+
+/// Compares two bodies as JSON when both parse, otherwise line by line.
+pub fn diff_bodies(before: Option<&str>, after: Option<&str>) -> BodyDiff {
+    let (before, after) = (before.unwrap_or(""), after.unwrap_or(""));
+    match (serde_json::from_str::<Value>(before), serde_json::from_str::<Value>(after)) {
+        (Ok(a), Ok(b)) => BodyDiff::Json { changes: diff_json(&a, &b) },
+        _ => BodyDiff::Text { lines: diff_lines(before, after) },
+    }
+}
+
+/// Headers that were added, removed or changed, matched case-insensitively.
+pub fn diff_headers(before: &HashMap<String, String>, after: &HashMap<String, String>) -> Vec<HeaderChange> {
+    let lower = |h: &HashMap<String, String>| -> BTreeMap<String, String> {
+        h.iter().map(|(k, v)| (k.to_lowercase(), v.clone())).collect()
+    };
+    let (a, b) = (lower(before), lower(after));
+    let names: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| a.get(*name) != b.get(*name))
+        .map(|name| HeaderChange {
+            name: name.clone(),
+            before: a.get(name).cloned(),
+            after: b.get(name).cloned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = diff_lines("a\nb\nc\nd", "a\nc\nx\nd");
+        let ops: Vec<(DiffOp, &str)> = lines.iter().map(|l| (l.op, l.text.as_str())).collect();
+        assert_eq!(ops, vec![
+            (DiffOp::Equal, "a"),
+            (DiffOp::Removed, "b"),
+            (DiffOp::Equal, "c"),
+            (DiffOp::Added, "x"),
+            (DiffOp::Equal, "d"),
+        ]);
+        assert!(diff_lines("same\ntext", "same\ntext").iter().all(|l| l.op == DiffOp::Equal));
+    }
+
+    #[test]
+    fn test_diff_json() {
+        let before = json!({"id": 1, "roles": ["user"], "profile": {"name": "a", "email": "x@y"}});
+        let after = json!({"id": 1, "roles": ["user", "admin"], "profile": {"name": "b"}});
+        let changes = diff_json(&before, &after);
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.profile.email", "$.profile.name", "$.roles[1]"]);
+        assert_eq!(changes[0].op, JsonOp::Removed);
+        assert_eq!(changes[1].op, JsonOp::Changed);
+        assert_eq!(changes[1].before, Some(json!("a")));
+        assert_eq!(changes[2].after, Some(json!("admin")));
+    }
+
+    #[test]
+    fn test_diff_headers() {
+        let before = HashMap::from([("Content-Type".to_string(), "text/html".to_string()), ("X-Old".to_string(), "1".to_string())]);
+        let after = HashMap::from([("content-type".to_string(), "application/json".to_string())]);
+        let changes = diff_headers(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].name, "content-type");
+        assert_eq!(changes[1].after, None);
+    }
+}
@@ -1,10 +1,112 @@
 use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sqlx::{FromRow, Pool, Sqlite};
+use std::collections::{HashMap, HashSet};
 use url::Url;
 
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct DriftBaselineEntry {
+    pub id: i64,
+    pub asset_id: i64,
+    pub url: String,
+    pub rule_id: String,
+    /// Field path, status, method or path template the drift was raised on
+    pub match_content: String,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+pub fn is_drift_rule(rule_id: &str) -> bool {
+    rule_id.starts_with("DRIFT-")
+}
+
+/// (rule, match) pairs accepted into an asset's drift baseline.
+pub async fn baseline_keys(pool: &Pool<Sqlite>, asset_id: i64) -> Result<HashSet<(String, String)>, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT rule_id, match_content FROM drift_baseline WHERE asset_id = ?")
+        .bind(asset_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Accepts a drift finding as expected: it is removed, and the same drift
+/// on that asset is no longer reported.
+#[tauri::command]
+pub async fn accept_drift_baseline(finding_id: i64, note: Option<String>) -> Result<i64, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    let (asset_id, rule_id, match_content): (i64, String, String) =
+        sqlx::query_as("SELECT asset_id, rule_id, match_content FROM findings WHERE id = ?")
+            .bind(finding_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Finding {} not found", finding_id))?;
+    if !is_drift_rule(&rule_id) {
+        return Err(format!("Finding {} is not a drift finding", finding_id));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO drift_baseline (asset_id, rule_id, match_content, note) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(asset_id, rule_id, match_content) DO UPDATE SET note = excluded.note RETURNING id",
+    )
+    .bind(asset_id)
+    .bind(&rule_id)
+    .bind(&match_content)
+    .bind(note.filter(|n| !n.trim().is_empty()))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM findings WHERE asset_id = ? AND rule_id = ? AND match_content = ?")
+        .bind(asset_id)
+        .bind(&rule_id)
+        .bind(&match_content)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    crate::audit::record("accept_drift_baseline", "finding", Some(finding_id), Some(format!("{} at '{}'", rule_id, match_content))).await;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn list_drift_baseline(asset_id: Option<i64>) -> Result<Vec<DriftBaselineEntry>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, DriftBaselineEntry>(
+        "SELECT b.id, b.asset_id, a.url, b.rule_id, b.match_content, b.note, b.created_at \
+         FROM drift_baseline b JOIN assets a ON a.id = b.asset_id \
+         WHERE a.deleted_at IS NULL AND (?1 IS NULL OR b.asset_id = ?1) ORDER BY b.created_at DESC",
+    )
+    .bind(asset_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Stops suppressing a baselined drift; it is reported again on the next capture.
+#[tauri::command]
+pub async fn remove_drift_baseline(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM drift_baseline WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 pub fn detect_drift(
     url_str: &str,
     method: &str,
+    status_code: Option<i64>,
+    req_headers: &HashMap<String, String>,
     res_body: Option<&str>,
     specs: Vec<crate::db::ApiSpec>,
 ) -> Vec<Finding> {
@@ -20,37 +122,90 @@ pub fn detect_drift(
     let path = parsed_url.path();
 
     for spec in specs {
-        let openapi: Value = match serde_json::from_str(&spec.content) {
-            Ok(v) => v,
-            Err(_) => continue,
+        // Specs that fail to parse are flagged by their validation status
+        let Ok(openapi) = parse_spec(&spec.content) else {
+            continue;
         };
-
-        let paths = match openapi.get("paths").and_then(|p| p.as_object()) {
-            Some(p) => p,
-            None => continue,
+        let Some(paths) = openapi.get("paths").and_then(|p| p.as_object()) else {
+            continue;
         };
 
         // Try to find a matching path template (e.g. /users/{id} matches /users/123)
         for (tmpl, methods) in paths {
             if path_matches(tmpl, path) {
+                let methods = resolve_ref(&openapi, methods, 0).unwrap_or(methods);
                 matched_path = true;
                 let method_lower = method.to_lowercase();
 
                 if let Some(op) = methods.get(&method_lower) {
                     matched_spec = true;
 
-                    // Check for Undocumented Response Body if present
-                    if let Some(actual_body) = res_body {
-                        if !actual_body.is_empty() && actual_body.starts_with("{") {
-                            let schema = op
-                                .get("responses")
-                                .and_then(|r| r.get("200"))
-                                .and_then(|r200| r200.get("content"))
-                                .and_then(|c| c.get("application/json"))
-                                .and_then(|aj| aj.get("schema"));
-
-                            if let Some(s) = schema {
-                                findings.extend(compare_schema_to_body(s, actual_body));
+                    // Auth drift only matters when the API actually served the request
+                    if status_code.is_some_and(|s| (200..300).contains(&s)) {
+                        let finding = match security_drift(&openapi, op, &parsed_url, req_headers) {
+                            Some(SecurityDrift::NotEnforced) => Some((
+                                "DRIFT-AUTH-NOT-ENFORCED",
+                                "Documented Authentication Not Enforced",
+                                format!(
+                                    "'{}' requires authentication for {} {}, but the request succeeded without credentials.",
+                                    spec.name, method.to_uppercase(), tmpl
+                                ),
+                                FindingSeverity::High,
+                            )),
+                            Some(SecurityDrift::SchemeMismatch { documented }) => Some((
+                                "DRIFT-AUTH-SCHEME-MISMATCH",
+                                "Undocumented Authentication Scheme",
+                                format!(
+                                    "{} {} succeeded with credentials that match none of the schemes '{}' documents for it ({}).",
+                                    method.to_uppercase(), tmpl, spec.name, documented.join(", ")
+                                ),
+                                FindingSeverity::Medium,
+                            )),
+                            None => None,
+                        };
+                        if let Some((rule_id, name, description, severity)) = finding {
+                            findings.push(Finding {
+                                id: None,
+                                rule_id: rule_id.to_string(),
+                                name: name.to_string(),
+                                description,
+                                severity,
+                                match_content: tmpl.to_string(),
+                                notes: None,
+                                is_false_positive: Some(false),
+                                severity_override: None,
+                                references: None,
+                                remediation: None,
+                            });
+                        }
+                    }
+
+                    let responses = op.get("responses").unwrap_or(&Value::Null);
+                    match select_response(responses, status_code) {
+                        Some(response) => {
+                            let schema = resolve_ref(&openapi, response, 0).and_then(response_schema);
+                            if let (Some(schema), Some(actual_body)) = (schema, res_body.filter(|b| !b.trim().is_empty())) {
+                                findings.extend(compare_schema_to_body(&openapi, schema, actual_body));
+                            }
+                        }
+                        None => {
+                            if let Some(status) = status_code.filter(|_| responses.is_object()) {
+                                findings.push(Finding {
+                                    id: None,
+                                    rule_id: "DRIFT-UNDOCUMENTED-STATUS".to_string(),
+                                    name: "Undocumented Status Code".to_string(),
+                                    description: format!(
+                                        "{} {} returned status {}, which '{}' does not document for this operation.",
+                                        method.to_uppercase(), tmpl, status, spec.name
+                                    ),
+                                    severity: FindingSeverity::Low,
+                                    match_content: status.to_string(),
+                                    notes: None,
+                                    is_false_positive: Some(false),
+                                    severity_override: None,
+                                    references: None,
+                                    remediation: None,
+                                });
                             }
                         }
                     }
@@ -69,6 +224,8 @@ pub fn detect_drift(
                         notes: None,
                         is_false_positive: Some(false),
                         severity_override: None,
+                        references: None,
+                        remediation: None,
                     });
                 }
                 break;
@@ -83,79 +240,735 @@ pub fn detect_drift(
         // Only flag if we have at least one spec in the system
     }
 
+    crate::analysis::attach_rule_metadata(&mut findings);
     findings
 }
 
-fn path_matches(tmpl: &str, path: &str) -> bool {
-    // Basic path parameter matching: replace {param} with [^/]+
-    let mut regex_str = String::from("^");
-    let parts: Vec<&str> = tmpl.split('/').collect();
-    for (i, part) in parts.iter().enumerate() {
-        if i > 0 {
-            regex_str.push('/');
-        }
-        if part.starts_with('{') && part.ends_with('}') {
-            regex_str.push_str("[^/]+");
-        } else {
-            regex_str.push_str(&regex::escape(part));
-        }
-    }
-    regex_str.push_str("$");
-
-    if let Ok(re) = regex::Regex::new(&regex_str) {
-        re.is_match(path)
-    } else {
-        tmpl == path
-    }
-}
-
-fn compare_schema_to_body(schema: &Value, body_str: &str) -> Vec<Finding> {
-    let mut findings = Vec::new();
-    let body: Value = match serde_json::from_str(body_str) {
-        Ok(v) => v,
-        Err(_) => return findings,
+/// Drift of a captured gRPC call against registered `.proto` descriptors:
+/// an rpc its service doesn't define, or message fields the descriptors
+/// don't know. Bodies are the JSON the proxy decoded them to.
+pub async fn detect_grpc_drift(url_str: &str, req_body: Option<&str>, res_body: Option<&str>) -> Vec<Finding> {
+    let Ok(url) = Url::parse(url_str) else {
+        return Vec::new();
     };
+    let path = url.path();
+    let mut findings = Vec::new();
 
-    if let Some(props) = schema.get("properties").and_then(|p| p.as_object()) {
-        if let Some(body_obj) = body.as_object() {
-            for (key, _val) in body_obj {
-                if !props.contains_key(key) {
+    match crate::proto::lookup_rpc(path).await {
+        Ok(Some((rpc, _, _))) => {
+            let Ok(registry) = crate::proto::load_registry().await else {
+                return findings;
+            };
+            for (label, body, full_name) in [("request", req_body, &rpc.input_type), ("response", res_body, &rpc.output_type)] {
+                let Some(body) = body.and_then(|b| serde_json::from_str::<Value>(b).ok()) else {
+                    continue;
+                };
+                let mut pointers = Vec::new();
+                registry.undocumented_fields(full_name, &body, "", &mut pointers);
+                for pointer in pointers {
                     findings.push(Finding {
                         id: None,
                         rule_id: "DRIFT-EXTRA-FIELD".to_string(),
-                        name: "Undocumented Field in Response".to_string(),
-                        description: format!("The field '{}' was found in the response but is not documented in the schema.", key),
+                        name: format!("Undocumented Field in gRPC {}", if label == "request" { "Request" } else { "Response" }),
+                        description: format!("At '{}' in the {}: field number {} is not defined by {}.", pointer, label, pointer.rsplit('/').next().unwrap_or_default(), full_name),
                         severity: FindingSeverity::Low,
-                        match_content: key.to_string(),
+                        match_content: if label == "request" { format!("request{}", pointer) } else { pointer },
                         notes: None,
                         is_false_positive: Some(false),
                         severity_override: None,
+                        references: None,
+                        remediation: None,
                     });
                 }
             }
+        }
+        Ok(None) => {
+            // A method missing from a service whose other rpcs are registered
+            let service = path.rsplit_once('/').map(|(service, _)| format!("{}/", service)).unwrap_or_default();
+            let rpcs = crate::proto::get_proto_rpcs().await.unwrap_or_default();
+            if service.len() > 1 && rpcs.iter().any(|rpc| rpc.path.starts_with(&service)) {
+                findings.push(Finding {
+                    id: None,
+                    rule_id: "DRIFT-UNDOCUMENTED-METHOD".to_string(),
+                    name: "Undocumented gRPC Method".to_string(),
+                    description: format!("The service '{}' is registered from a .proto file, but the rpc '{}' is not.", service.trim_matches('/'), path),
+                    severity: FindingSeverity::Medium,
+                    match_content: path.to_string(),
+                    notes: None,
+                    is_false_positive: Some(false),
+                    severity_override: None,
+                    references: None,
+                    remediation: None,
+                });
+            }
+        }
+        Err(_) => {}
+    }
+
+    crate::analysis::attach_rule_metadata(&mut findings);
+    findings
+}
+
+/// Parses an OpenAPI/Swagger document written as JSON or YAML.
+pub fn parse_spec(content: &str) -> Result<Value, String> {
+    let spec: Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(json_err) => serde_yml::from_str(content).map_err(|yaml_err| {
+            if content.trim_start().starts_with('{') {
+                format!("Invalid JSON: {}", json_err)
+            } else {
+                format!("Invalid YAML: {}", yaml_err)
+            }
+        })?,
+    };
+    if !spec.is_object() {
+        return Err("Spec is not a JSON or YAML object".to_string());
+    }
+    // OpenAPI 3.1 made `paths` optional for webhook- or component-only documents
+    let is_31 = spec.get("openapi").and_then(|v| v.as_str()).is_some_and(|v| v.starts_with("3.1"));
+    let paths_optional = is_31 && (spec.get("webhooks").is_some() || spec.get("components").is_some());
+    if !spec.get("paths").is_some_and(|p| p.is_object()) && !paths_optional {
+        return Err("Spec has no 'paths' object".to_string());
+    }
+    Ok(spec)
+}
+
+/// Whether `path` fits the template `tmpl`, where a `{param}` segment
+/// matches any one non-empty segment. Compared segment by segment, since
+/// coverage runs this for every asset against every documented operation.
+pub(crate) fn path_matches(tmpl: &str, path: &str) -> bool {
+    let (mut tmpl_parts, mut path_parts) = (tmpl.split('/'), path.split('/'));
+    loop {
+        match (tmpl_parts.next(), path_parts.next()) {
+            (None, None) => return true,
+            (Some(t), Some(p)) if t.starts_with('{') && t.ends_with('}') => {
+                if p.is_empty() {
+                    return false;
+                }
+            }
+            (Some(t), Some(p)) if t == p => {}
+            _ => return false,
+        }
+    }
+}
+
+/// The documented response for a status: the exact code, then its range
+/// (`4XX`), then `default`. Without a captured status the first documented
+/// success is used.
+pub fn select_response(responses: &Value, status: Option<i64>) -> Option<&Value> {
+    let responses = responses.as_object()?;
+    let key = match status {
+        Some(code) => {
+            let range = format!("{}XX", code / 100);
+            responses
+                .keys()
+                .find(|k| *k == &code.to_string())
+                .or_else(|| responses.keys().find(|k| k.eq_ignore_ascii_case(&range)))
+        }
+        None => responses
+            .keys()
+            .filter(|k| k.starts_with('2'))
+            .min_by_key(|k| (k.eq_ignore_ascii_case("2XX"), k.as_str())),
+    };
+    key.or_else(|| responses.keys().find(|k| *k == "default")).and_then(|k| responses.get(k))
+}
+
+/// JSON schema of a response: OpenAPI 3 `content` (any JSON media type)
+/// or a Swagger 2 `schema`.
+fn response_schema(response: &Value) -> Option<&Value> {
+    match response.get("content").and_then(|c| c.as_object()) {
+        Some(content) => content
+            .iter()
+            .find(|(media, _)| {
+                let media = media.split(';').next().unwrap_or("").trim().to_lowercase();
+                media == "application/json" || media.ends_with("+json") || media == "*/*"
+            })
+            .and_then(|(_, m)| m.get("schema")),
+        None => response.get("schema"),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SecurityDrift {
+    /// The operation requires credentials but the request carried none
+    NotEnforced,
+    /// The request carried credentials, but not for any documented scheme
+    SchemeMismatch { documented: Vec<String> },
+}
+
+/// Whether a request carries the credentials a security scheme describes.
+/// `None` when the scheme can't be judged from a captured request (e.g.
+/// mutual TLS) or is unknown.
+fn scheme_satisfied(scheme: &Value, url: &Url, req_headers: &HashMap<String, String>) -> Option<bool> {
+    let header = |name: &str| req_headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str());
+    let auth_prefix = |prefix: &str| {
+        header("authorization")
+            .and_then(|v| v.split_whitespace().next())
+            .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+    };
+    let scheme_type = scheme.get("type").and_then(|t| t.as_str())?;
+    match scheme_type {
+        "apiKey" => {
+            let name = scheme.get("name").and_then(|n| n.as_str())?;
+            Some(match scheme.get("in").and_then(|i| i.as_str())? {
+                "header" => header(name).is_some(),
+                "query" => url.query_pairs().any(|(k, _)| k == name),
+                "cookie" => header("cookie").is_some_and(|c| c.split(';').any(|p| p.trim().split('=').next() == Some(name))),
+                _ => return None,
+            })
+        }
+        // OpenAPI 3 `http` names the Authorization scheme; Swagger 2 only has `basic`
+        "http" => Some(auth_prefix(scheme.get("scheme").and_then(|s| s.as_str())?)),
+        "basic" => Some(auth_prefix("basic")),
+        "oauth2" | "openIdConnect" => Some(auth_prefix("bearer")),
+        _ => None,
+    }
+}
+
+/// Compares the operation's effective security requirements (its own
+/// `security`, else the document's) with the credentials the request sent.
+pub fn security_drift(spec: &Value, op: &Value, url: &Url, req_headers: &HashMap<String, String>) -> Option<SecurityDrift> {
+    let requirements = op.get("security").or_else(|| spec.get("security"))?.as_array()?;
+    let schemes = spec
+        .get("components")
+        .and_then(|c| c.get("securitySchemes"))
+        .or_else(|| spec.get("securityDefinitions"))
+        .and_then(|s| s.as_object());
+
+    // Alternatives are OR'ed, the schemes within one are AND'ed. An empty
+    // requirement makes authentication optional.
+    let mut documented = Vec::new();
+    for requirement in requirements {
+        let names: Vec<&String> = requirement.as_object()?.keys().collect();
+        if names.is_empty() {
+            return None;
+        }
+        let mut satisfied = true;
+        for name in names {
+            let scheme = schemes.and_then(|s| s.get(name)).map(|s| resolve_ref(spec, s, 0).unwrap_or(s))?;
+            satisfied &= scheme_satisfied(scheme, url, req_headers)?;
+            documented.push(name.clone());
+        }
+        if satisfied {
+            return None;
+        }
+    }
+    if documented.is_empty() {
+        return None;
+    }
+    // The same scheme can appear in several alternatives; keep spec order
+    let mut seen = HashSet::new();
+    documented.retain(|name| seen.insert(name.clone()));
+    if crate::risk::is_authenticated(url.as_str(), req_headers) {
+        Some(SecurityDrift::SchemeMismatch { documented })
+    } else {
+        Some(SecurityDrift::NotEnforced)
+    }
+}
+
+/// Nesting allowed while following `$ref`s and sub-schemas; guards against cyclic specs.
+const MAX_SCHEMA_DEPTH: usize = 32;
+
+/// Violations reported per response; repeats across array items count once.
+const MAX_VIOLATIONS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ViolationKind {
+    ExtraField,
+    MissingField,
+    TypeMismatch,
+    EnumViolation,
+    FormatViolation,
+}
+
+/// One place where a response body departs from its documented schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub kind: ViolationKind,
+    /// JSON pointer into the body, e.g. `/items/0/id`; empty for the root
+    pub pointer: String,
+    pub detail: String,
+}
+
+fn pointer_child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Decodes `%XX` escapes, which references use for characters such as `{`
+/// in path keys. Invalid escapes are kept as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Follows local `#/...` references, returning `None` for dangling or remote
+/// ones. Reference chains longer than `MAX_SCHEMA_DEPTH` count as cycles.
+fn resolve_ref<'a>(root: &'a Value, schema: &'a Value, depth: usize) -> Option<&'a Value> {
+    match schema.get("$ref").and_then(|r| r.as_str()) {
+        Some(r) if depth < MAX_SCHEMA_DEPTH => resolve_ref(root, root.pointer(&percent_decode(r.strip_prefix('#')?))?, depth + 1),
+        Some(_) => None,
+        None => Some(schema),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_allows(expected: &str, value: &Value) -> bool {
+    match (expected, value) {
+        ("number", Value::Number(_)) => true,
+        ("integer", Value::Number(n)) => n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0),
+        (t, v) => t == json_type(v),
+    }
+}
+
+fn format_allows(format: &str, s: &str) -> bool {
+    match format {
+        "date-time" => chrono::DateTime::parse_from_rfc3339(s).is_ok(),
+        "date" => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+        "uuid" => uuid::Uuid::parse_str(s).is_ok(),
+        "email" => s.split_once('@').is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.') && !domain.contains('@')),
+        "ipv4" => s.parse::<std::net::Ipv4Addr>().is_ok(),
+        "ipv6" => s.parse::<std::net::Ipv6Addr>().is_ok(),
+        "uri" | "url" => Url::parse(s).is_ok(),
+        _ => true,
+    }
+}
+
+/// `allOf` branches folded into one schema: properties and required keys
+/// are combined, other keywords taken from the first branch that has them.
+/// Refs already in `visited` are skipped, and every ref merged is added.
+fn merge_all_of(root: &Value, schema: &Value, depth: usize, visited: &mut Vec<String>) -> Value {
+    let mut merged = schema.clone();
+    let Some(branches) = schema.get("allOf").and_then(|a| a.as_array()) else {
+        return merged;
+    };
+    let obj = merged.as_object_mut().unwrap();
+    obj.remove("allOf");
+    for branch in branches {
+        if let Some(r) = branch.get("$ref").and_then(|r| r.as_str()) {
+            if visited.iter().any(|v| v == r) {
+                continue;
+            }
+            visited.push(r.to_string());
+        }
+        let Some(branch) = resolve_ref(root, branch, depth) else {
+            continue;
+        };
+        let branch = merge_all_of(root, branch, depth + 1, visited);
+        let Some(branch) = branch.as_object() else {
+            continue;
+        };
+        for (key, value) in branch {
+            match (key.as_str(), obj.get_mut(key)) {
+                ("properties", Some(Value::Object(props))) => {
+                    if let Some(more) = value.as_object() {
+                        props.extend(more.clone());
+                    }
+                }
+                ("required", Some(Value::Array(required))) => {
+                    if let Some(more) = value.as_array() {
+                        required.extend(more.iter().cloned());
+                    }
+                }
+                (_, Some(_)) => {}
+                (_, None) => {
+                    obj.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Recursively checks `value` against `schema`, resolving local `$ref`s
+/// against `root` (the whole spec).
+pub fn validate_schema(root: &Value, schema: &Value, value: &Value) -> Vec<SchemaViolation> {
+    let mut out = Vec::new();
+    walk_schema(root, schema, value, "", 0, &[], &mut out);
+
+    // Array items tend to repeat the same problem; keep the first of each
+    let mut seen = std::collections::HashSet::new();
+    out.retain(|v| {
+        let shape: Vec<&str> = v
+            .pointer
+            .split('/')
+            .map(|seg| if !seg.is_empty() && seg.chars().all(|c| c.is_ascii_digit()) { "*" } else { seg })
+            .collect();
+        seen.insert((v.kind, shape.join("/")))
+    });
+    out.truncate(MAX_VIOLATIONS);
+    out
+}
+
+/// `visited` holds the refs already entered for this same `value`; meeting
+/// one again means a cycle (e.g. `oneOf` inside a recursive `allOf`) that
+/// would never consume any of the value, so that branch is dropped.
+fn walk_schema(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    pointer: &str,
+    depth: usize,
+    visited: &[String],
+    out: &mut Vec<SchemaViolation>,
+) {
+    if depth >= MAX_SCHEMA_DEPTH || out.len() >= MAX_VIOLATIONS * 4 {
+        return;
+    }
+    let mut visited = visited.to_vec();
+    if let Some(r) = schema.get("$ref").and_then(|r| r.as_str()) {
+        if visited.iter().any(|v| v == r) {
+            return;
+        }
+        // With siblings the ref becomes an `allOf` branch, recorded when merged
+        if schema.as_object().is_some_and(|obj| obj.len() == 1) {
+            visited.push(r.to_string());
+        }
+    }
+    // OpenAPI 3.1 applies keywords next to a `$ref` alongside its target
+    let with_siblings;
+    let schema = match schema.as_object() {
+        Some(obj) if obj.contains_key("$ref") && obj.len() > 1 => {
+            let mut rest = obj.clone();
+            let reference = rest.remove("$ref").unwrap_or_default();
+            let mut all_of = vec![serde_json::json!({ "$ref": reference })];
+            if let Some(Value::Array(existing)) = rest.remove("allOf") {
+                all_of.extend(existing);
+            }
+            rest.insert("allOf".to_string(), Value::Array(all_of));
+            with_siblings = Value::Object(rest);
+            &with_siblings
+        }
+        _ => schema,
+    };
+    let Some(schema) = resolve_ref(root, schema, depth) else {
+        return;
+    };
+    let schema = &merge_all_of(root, schema, depth, &mut visited);
+    let violation = |kind, detail: String| SchemaViolation { kind, pointer: pointer.to_string(), detail };
 
-            // Check for missing required fields
+    // oneOf/anyOf: fine if any branch fits, otherwise report the closest one
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(|b| b.as_array()) {
+            let best = branches
+                .iter()
+                .map(|branch| {
+                    let mut found = Vec::new();
+                    walk_schema(root, branch, value, pointer, depth + 1, &visited, &mut found);
+                    found
+                })
+                .min_by_key(|found| found.len());
+            if let Some(best) = best {
+                out.extend(best);
+            }
+        }
+    }
+
+    if value.is_null() && schema.get("nullable").and_then(|n| n.as_bool()).unwrap_or(false) {
+        return;
+    }
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| type_allows(t, value)) {
+        out.push(violation(
+            ViolationKind::TypeMismatch,
+            format!("expected {} but found {}", types.join(" or "), json_type(value)),
+        ));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            out.push(violation(ViolationKind::EnumViolation, format!("{} is not one of the documented values", value)));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            out.push(violation(ViolationKind::EnumViolation, format!("{} is not the documented constant {}", value, expected)));
+        }
+    }
+    if let (Some(format), Some(s)) = (schema.get("format").and_then(|f| f.as_str()), value.as_str()) {
+        if !format_allows(format, s) {
+            out.push(violation(ViolationKind::FormatViolation, format!("'{}' is not a valid {}", s, format)));
+        }
+    }
+
+    match value {
+        Value::Object(obj) => {
+            let props = schema.get("properties").and_then(|p| p.as_object());
+            // 3.1 schemas may close objects with `unevaluatedProperties` instead
+            let additional = schema.get("additionalProperties").or_else(|| schema.get("unevaluatedProperties"));
             if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
-                for req in required {
-                    if let Some(field_name) = req.as_str() {
-                        if !body_obj.contains_key(field_name) {
-                            findings.push(Finding {
-                                id: None,
-                                rule_id: "DRIFT-MISSING-FIELD".to_string(),
-                                name: "Missing Required Field".to_string(),
-                                description: format!("The required field '{}' is documented but missing from the actual response.", field_name),
-                                severity: FindingSeverity::Medium,
-                                match_content: field_name.to_string(),
-                                notes: None,
-                                is_false_positive: Some(false),
-                                severity_override: None,
-                            });
-                        }
+                for field in required.iter().filter_map(|r| r.as_str()) {
+                    if !obj.contains_key(field) {
+                        out.push(SchemaViolation {
+                            kind: ViolationKind::MissingField,
+                            pointer: pointer_child(pointer, field),
+                            detail: format!("required field '{}' is missing", field),
+                        });
                     }
                 }
             }
+            for (key, child) in obj {
+                let child_pointer = pointer_child(pointer, key);
+                match (props.and_then(|p| p.get(key)), additional) {
+                    (Some(child_schema), _) => walk_schema(root, child_schema, child, &child_pointer, depth + 1, &[], out),
+                    (None, Some(extra)) if extra.is_object() => walk_schema(root, extra, child, &child_pointer, depth + 1, &[], out),
+                    (None, Some(Value::Bool(true))) => {}
+                    // Open-ended objects (no properties listed) accept anything
+                    (None, _) if props.is_none() && additional.is_none() => {}
+                    (None, _) => out.push(SchemaViolation {
+                        kind: ViolationKind::ExtraField,
+                        pointer: child_pointer,
+                        detail: format!("field '{}' is not documented", key),
+                    }),
+                }
+            }
         }
+        Value::Array(items) => {
+            // 3.1 tuples: `prefixItems` covers the leading items, `items` the rest
+            let prefix = schema.get("prefixItems").and_then(|p| p.as_array());
+            for (i, item) in items.iter().enumerate() {
+                let item_schema = match prefix {
+                    Some(prefix) if i < prefix.len() => Some(&prefix[i]),
+                    Some(_) => schema.get("items").filter(|s| s.is_object()),
+                    None => schema.get("items"),
+                };
+                if let Some(item_schema) = item_schema {
+                    walk_schema(root, item_schema, item, &pointer_child(pointer, &i.to_string()), depth + 1, &[], out);
+                }
+            }
+        }
+        _ => {}
     }
+}
 
-    findings
+fn compare_schema_to_body(root: &Value, schema: &Value, body_str: &str) -> Vec<Finding> {
+    let body: Value = match serde_json::from_str(body_str) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    validate_schema(root, schema, &body)
+        .into_iter()
+        .map(|v| {
+            let (rule_id, name, severity) = match v.kind {
+                ViolationKind::ExtraField => ("DRIFT-EXTRA-FIELD", "Undocumented Field in Response", FindingSeverity::Low),
+                ViolationKind::MissingField => ("DRIFT-MISSING-FIELD", "Missing Required Field", FindingSeverity::Medium),
+                ViolationKind::TypeMismatch => ("DRIFT-TYPE-MISMATCH", "Response Type Mismatch", FindingSeverity::Medium),
+                ViolationKind::EnumViolation => ("DRIFT-ENUM-VIOLATION", "Undocumented Enum Value", FindingSeverity::Low),
+                ViolationKind::FormatViolation => ("DRIFT-FORMAT-VIOLATION", "Response Format Violation", FindingSeverity::Low),
+            };
+            let location = if v.pointer.is_empty() { "the response root".to_string() } else { format!("'{}'", v.pointer) };
+            Finding {
+                id: None,
+                rule_id: rule_id.to_string(),
+                name: name.to_string(),
+                description: format!("At {} in the response: {}.", location, v.detail),
+                severity,
+                match_content: v.pointer,
+                notes: None,
+                is_false_positive: Some(false),
+                severity_override: None,
+                references: None,
+                remediation: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_matches_parameter_segments() {
+        assert!(path_matches("https://api.example.com/users/{id}", "https://api.example.com/users/42"));
+        assert!(path_matches("/users/{id}/orders", "/users/a.b/orders"));
+        assert!(!path_matches("/users/{id}", "/users/"));
+        assert!(!path_matches("/users/{id}", "/users/1/orders"));
+        assert!(!path_matches("/users/me", "/users/you"));
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let yaml = "openapi: 3.0.0\npaths:\n  /users/{id}:\n    get:\n      responses:\n        '200':\n          description: ok\n";
+        let spec = parse_spec(yaml).unwrap();
+        assert!(spec["paths"]["/users/{id}"]["get"].is_object());
+        assert!(parse_spec(r#"{"openapi": "3.0.0", "paths": {}}"#).is_ok());
+        assert_eq!(parse_spec("openapi: 3.0.0\ninfo: {}\n").unwrap_err(), "Spec has no 'paths' object");
+        assert!(parse_spec("paths: [unclosed").unwrap_err().starts_with("Invalid YAML"));
+    }
+
+    #[test]
+    fn test_select_response() {
+        let responses = serde_json::json!({
+            "201": {"description": "created"},
+            "4XX": {"description": "client error"},
+            "default": {"description": "other"}
+        });
+        assert_eq!(select_response(&responses, Some(201)).unwrap()["description"], "created");
+        assert_eq!(select_response(&responses, Some(404)).unwrap()["description"], "client error");
+        assert_eq!(select_response(&responses, Some(500)).unwrap()["description"], "other");
+        assert_eq!(select_response(&responses, None).unwrap()["description"], "created");
+        let strict = serde_json::json!({"200": {"description": "ok"}});
+        assert!(select_response(&strict, Some(500)).is_none());
+    }
+
+    #[test]
+    fn test_security_drift() {
+        let spec = serde_json::json!({
+            "security": [{"bearer": []}],
+            "components": {"securitySchemes": {
+                "bearer": {"type": "http", "scheme": "bearer"},
+                "key": {"type": "apiKey", "in": "query", "name": "api_key"}
+            }},
+            "paths": {}
+        });
+        let url = Url::parse("https://api.example.com/users").unwrap();
+        let op = serde_json::json!({});
+        let bearer = HashMap::from([("Authorization".to_string(), "Bearer abc".to_string())]);
+        assert_eq!(security_drift(&spec, &op, &url, &bearer), None);
+        assert_eq!(security_drift(&spec, &op, &url, &HashMap::new()), Some(SecurityDrift::NotEnforced));
+
+        let cookie = HashMap::from([("Cookie".to_string(), "session=1".to_string())]);
+        assert_eq!(
+            security_drift(&spec, &op, &url, &cookie),
+            Some(SecurityDrift::SchemeMismatch { documented: vec!["bearer".to_string()] })
+        );
+
+        let alternatives = serde_json::json!({"security": [{"bearer": []}, {"key": []}, {"bearer": [], "key": []}]});
+        assert_eq!(
+            security_drift(&spec, &alternatives, &url, &cookie),
+            Some(SecurityDrift::SchemeMismatch { documented: vec!["bearer".to_string(), "key".to_string()] })
+        );
+
+        let keyed = Url::parse("https://api.example.com/users?api_key=1").unwrap();
+        let key_op = serde_json::json!({"security": [{"key": []}]});
+        assert_eq!(security_drift(&spec, &key_op, &keyed, &HashMap::new()), None);
+        let public = serde_json::json!({"security": []});
+        assert_eq!(security_drift(&spec, &public, &url, &HashMap::new()), None);
+        let optional = serde_json::json!({"security": [{"bearer": []}, {}]});
+        assert_eq!(security_drift(&spec, &optional, &url, &HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_openapi_31() {
+        let spec = parse_spec(r##"{"openapi": "3.1.0", "webhooks": {}, "components": {"schemas": {
+            "Node": {"type": "object", "properties": {"id": {"type": "integer"}, "next": {"$ref": "#/components/schemas/Node"}}},
+            "Loop": {"$ref": "#/components/schemas/Loop"},
+            "Kind": {"type": ["string", "null"], "const": "node"}
+        }}}"##)
+        .unwrap();
+        let schema = serde_json::json!({
+            "$ref": "#/components/schemas/Node",
+            "properties": {"kind": {"$ref": "#/components/schemas/Kind"}},
+            "unevaluatedProperties": false
+        });
+        let body = serde_json::json!({"id": 1, "kind": "node", "next": {"id": 2, "next": {"id": "3"}}});
+        let found: Vec<(String, ViolationKind)> = validate_schema(&spec, &schema, &body).into_iter().map(|v| (v.pointer, v.kind)).collect();
+        assert_eq!(found, vec![("/next/next/id".to_string(), ViolationKind::TypeMismatch)]);
+
+        let bad = serde_json::json!({"id": 1, "kind": "edge", "extra": true});
+        let mut found: Vec<(String, ViolationKind)> = validate_schema(&spec, &schema, &bad).into_iter().map(|v| (v.pointer, v.kind)).collect();
+        found.sort();
+        assert_eq!(found, vec![("/extra".to_string(), ViolationKind::ExtraField), ("/kind".to_string(), ViolationKind::EnumViolation)]);
+
+        // Cyclic references resolve to nothing instead of recursing forever
+        assert!(validate_schema(&spec, &serde_json::json!({"$ref": "#/components/schemas/Loop"}), &body).is_empty());
+
+        let tuple = serde_json::json!({"prefixItems": [{"type": "string"}], "items": {"type": "integer"}});
+        let found: Vec<String> = validate_schema(&spec, &tuple, &serde_json::json!(["a", 1, "b"])).into_iter().map(|v| v.pointer).collect();
+        assert_eq!(found, vec!["/2".to_string()]);
+
+        let paths = serde_json::json!({"paths": {"/users/{id}": {"get": {}}}});
+        assert_eq!(resolve_ref(&paths, &serde_json::json!({"$ref": "#/paths/~1users~1%7Bid%7D"}), 0), Some(&serde_json::json!({"get": {}})));
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let spec = serde_json::json!({
+            "components": {"schemas": {
+                "User": {
+                    "type": "object",
+                    "required": ["id", "role"],
+                    "properties": {
+                        "id": {"type": "integer"},
+                        "email": {"type": "string", "format": "email"},
+                        "role": {"type": "string", "enum": ["user", "admin"]},
+                        "tags": {"type": "array", "items": {"type": "string"}}
+                    }
+                }
+            }}
+        });
+        let schema = serde_json::json!({"type": "array", "items": {"$ref": "#/components/schemas/User"}});
+        let body = serde_json::json!([
+            {"id": "7", "email": "not-an-email", "role": "root", "tags": ["a", 1], "debug": true},
+            {"id": 8, "tags": [2]}
+        ]);
+        let violations = validate_schema(&spec, &schema, &body);
+        let mut found: Vec<(&str, ViolationKind)> = violations.iter().map(|v| (v.pointer.as_str(), v.kind)).collect();
+        found.sort();
+        // The second item's `tags/0` repeats the first item's `tags/1` problem
+        assert_eq!(found, vec![
+            ("/0/debug", ViolationKind::ExtraField),
+            ("/0/email", ViolationKind::FormatViolation),
+            ("/0/id", ViolationKind::TypeMismatch),
+            ("/0/role", ViolationKind::EnumViolation),
+            ("/0/tags/1", ViolationKind::TypeMismatch),
+            ("/1/role", ViolationKind::MissingField),
+        ]);
+
+        let nullable = serde_json::json!({"allOf": [{"$ref": "#/components/schemas/User"}, {"properties": {"debug": {"type": "boolean"}}}], "nullable": true});
+        assert!(validate_schema(&spec, &nullable, &serde_json::json!({"id": 1, "role": "user", "debug": false})).is_empty());
+        assert!(validate_schema(&spec, &nullable, &Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_validate_schema_recursive_one_of() {
+        let spec = serde_json::json!({
+            "components": {"schemas": {
+                "Node": {
+                    "allOf": [
+                        {"oneOf": [{"$ref": "#/components/schemas/Node"}, {"$ref": "#/components/schemas/Node"}, {"type": "object"}]},
+                        {"properties": {"name": {"type": "string"}, "children": {"type": "array", "items": {"$ref": "#/components/schemas/Node"}}}}
+                    ]
+                }
+            }}
+        });
+        let schema = serde_json::json!({"$ref": "#/components/schemas/Node"});
+        // Each branch re-enters Node for the same value; without tracking
+        // visited refs this fans out 3^32 ways before the depth limit
+        let body = serde_json::json!({"name": "root", "children": [{"name": "leaf", "children": [{"name": 3}]}]});
+        let found: Vec<String> = validate_schema(&spec, &schema, &body).into_iter().map(|v| v.pointer).collect();
+        assert_eq!(found, vec!["/children/0/children/0/name".to_string()]);
+    }
 }
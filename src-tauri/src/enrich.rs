@@ -0,0 +1,347 @@
+//! Who hosts an address: reverse DNS, the origin AS from Team Cymru's DNS
+//! service, and the cloud or CDN provider behind it.
+
+use crate::db::get_db;
+use hickory_resolver::TokioAsyncResolver;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+/// Lookups in flight at once.
+const CONCURRENCY: usize = 20;
+
+/// Saved lookups younger than this are reused.
+const CACHE_DAYS: i64 = 7;
+
+/// Origin AS numbers of each provider's own networks.
+const CLOUD_ASNS: &[(u32, &str)] = &[
+    (16509, "AWS"),
+    (14618, "AWS"),
+    (15169, "GCP"),
+    (396982, "GCP"),
+    (8075, "Azure"),
+    (13335, "Cloudflare"),
+    (209242, "Cloudflare"),
+    (54113, "Fastly"),
+    (20940, "Akamai"),
+    (16625, "Akamai"),
+    (19551, "Imperva"),
+];
+
+/// Published Cloudflare and Fastly ranges, which also cover addresses they
+/// proxy for customers announcing their own AS. More can be added through
+/// the `cloud_ranges` setting.
+const CLOUD_RANGES: &[(&str, &str)] = &[
+    ("173.245.48.0/20", "Cloudflare"),
+    ("103.21.244.0/22", "Cloudflare"),
+    ("103.22.200.0/22", "Cloudflare"),
+    ("103.31.4.0/22", "Cloudflare"),
+    ("141.101.64.0/18", "Cloudflare"),
+    ("108.162.192.0/18", "Cloudflare"),
+    ("190.93.240.0/20", "Cloudflare"),
+    ("188.114.96.0/20", "Cloudflare"),
+    ("197.234.240.0/22", "Cloudflare"),
+    ("198.41.128.0/17", "Cloudflare"),
+    ("162.158.0.0/15", "Cloudflare"),
+    ("104.16.0.0/13", "Cloudflare"),
+    ("104.24.0.0/14", "Cloudflare"),
+    ("172.64.0.0/13", "Cloudflare"),
+    ("131.0.72.0/22", "Cloudflare"),
+    ("2400:cb00::/32", "Cloudflare"),
+    ("2606:4700::/32", "Cloudflare"),
+    ("2803:f800::/32", "Cloudflare"),
+    ("2405:b500::/32", "Cloudflare"),
+    ("2405:8100::/32", "Cloudflare"),
+    ("2a06:98c0::/29", "Cloudflare"),
+    ("2c0f:f248::/32", "Cloudflare"),
+    ("23.235.32.0/20", "Fastly"),
+    ("43.249.72.0/22", "Fastly"),
+    ("103.244.50.0/24", "Fastly"),
+    ("103.245.222.0/23", "Fastly"),
+    ("103.245.224.0/24", "Fastly"),
+    ("104.156.80.0/20", "Fastly"),
+    ("140.248.64.0/18", "Fastly"),
+    ("140.248.128.0/17", "Fastly"),
+    ("146.75.0.0/17", "Fastly"),
+    ("151.101.0.0/16", "Fastly"),
+    ("157.52.64.0/18", "Fastly"),
+    ("167.82.0.0/17", "Fastly"),
+    ("167.82.128.0/20", "Fastly"),
+    ("167.82.160.0/20", "Fastly"),
+    ("167.82.224.0/20", "Fastly"),
+    ("172.111.64.0/18", "Fastly"),
+    ("185.31.16.0/22", "Fastly"),
+    ("199.27.72.0/21", "Fastly"),
+    ("199.232.0.0/16", "Fastly"),
+    ("2a04:4e40::/32", "Fastly"),
+    ("2a04:4e42::/32", "Fastly"),
+];
+
+/// A range attributed to a provider on top of the built-in ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CloudRange {
+    /// e.g. `203.0.113.0/24`
+    pub cidr: String,
+    pub provider: String,
+}
+
+/// The `cloud_ranges` setting.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CloudRangeSettings {
+    /// Checked before the built-in ranges
+    pub ranges: Vec<CloudRange>,
+}
+
+#[derive(Serialize, Debug, Clone, FromRow)]
+pub struct IpInfo {
+    pub ip: String,
+    /// Reverse DNS name, without the trailing dot
+    pub ptr: Option<String>,
+    pub asn: Option<i64>,
+    /// AS holder, e.g. `CLOUDFLARENET - Cloudflare, Inc., US`
+    pub as_name: Option<String>,
+    /// Announced prefix containing the address
+    pub prefix: Option<String>,
+    pub country: Option<String>,
+    /// `AWS`, `GCP`, `Azure`, `Cloudflare`, `Fastly`, `Akamai`, `Imperva`
+    /// or a provider from the `cloud_ranges` setting
+    pub cloud: Option<String>,
+    pub looked_up_at: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Origin {
+    pub asn: u32,
+    pub prefix: String,
+    pub country: String,
+}
+
+/// Name whose TXT record holds the origin of `ip`.
+pub fn origin_query(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let o = v4.octets();
+            format!("{}.{}.{}.{}.origin.asn.cymru.com", o[3], o[2], o[1], o[0])
+        }
+        IpAddr::V6(v6) => {
+            let nibbles: Vec<String> = v6
+                .octets()
+                .iter()
+                .rev()
+                .flat_map(|b| [b & 0x0f, b >> 4])
+                .map(|n| format!("{:x}", n))
+                .collect();
+            format!("{}.origin6.asn.cymru.com", nibbles.join("."))
+        }
+    }
+}
+
+/// Reads `13335 | 104.16.0.0/13 | US | arin | 2014-03-28`. With several
+/// origin ASes the first is kept.
+pub fn parse_origin(txt: &str) -> Option<Origin> {
+    let fields: Vec<&str> = txt.split('|').map(str::trim).collect();
+    let asn = fields.first()?.split_whitespace().next()?.parse().ok()?;
+    Some(Origin {
+        asn,
+        prefix: fields.get(1)?.to_string(),
+        country: fields.get(2).copied().unwrap_or_default().to_string(),
+    })
+}
+
+/// Holder name from `13335 | US | arin | 2010-07-14 | CLOUDFLARENET - Cloudflare, Inc., US`.
+pub fn parse_as_name(txt: &str) -> Option<String> {
+    let name = txt.splitn(5, '|').nth(4)?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Network address and prefix length of `203.0.113.0/24`.
+pub fn parse_cidr(cidr: &str) -> Option<(IpAddr, u32)> {
+    let (network, bits) = cidr.trim().split_once('/')?;
+    let (network, bits) = (network.parse::<IpAddr>().ok()?, bits.parse::<u32>().ok()?);
+    let max = if network.is_ipv4() { 32 } else { 128 };
+    (bits <= max).then_some((network, bits))
+}
+
+fn in_range(ip: IpAddr, (network, bits): (IpAddr, u32)) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(net)) => {
+            let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(net) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(net)) => {
+            let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(net) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Cloud or CDN provider hosting `ip`, by configured range, published range
+/// or origin AS.
+pub fn cloud_provider(ip: IpAddr, asn: Option<u32>, custom: &[CloudRange]) -> Option<String> {
+    custom
+        .iter()
+        .map(|range| (range.cidr.as_str(), range.provider.as_str()))
+        .chain(CLOUD_RANGES.iter().copied())
+        .find(|(cidr, _)| parse_cidr(cidr).is_some_and(|network| in_range(ip, network)))
+        .map(|(_, provider)| provider)
+        .or_else(|| CLOUD_ASNS.iter().find(|(n, _)| Some(*n) == asn).map(|(_, provider)| *provider))
+        .map(str::to_string)
+}
+
+async fn first_txt(resolver: &TokioAsyncResolver, name: &str) -> Option<String> {
+    let lookup = resolver.txt_lookup(name).await.ok()?;
+    let txt = lookup.iter().next()?.to_string();
+    Some(txt)
+}
+
+async fn ptr(resolver: &TokioAsyncResolver, ip: IpAddr) -> Option<String> {
+    let lookup = resolver.reverse_lookup(ip).await.ok()?;
+    let name = lookup.iter().next()?.to_string();
+    Some(name.trim_end_matches('.').to_string())
+}
+
+async fn store(conn: &mut sqlx::SqliteConnection, info: &IpInfo) -> Result<IpInfo, String> {
+    sqlx::query_as::<_, IpInfo>(
+        "INSERT INTO ip_info (ip, ptr, asn, as_name, prefix, country, cloud, looked_up_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+         ON CONFLICT(ip) DO UPDATE SET ptr = excluded.ptr, asn = excluded.asn, as_name = excluded.as_name,
+             prefix = excluded.prefix, country = excluded.country, cloud = excluded.cloud, looked_up_at = excluded.looked_up_at
+         RETURNING ip, ptr, asn, as_name, prefix, country, cloud, looked_up_at",
+    )
+    .bind(&info.ip)
+    .bind(&info.ptr)
+    .bind(info.asn)
+    .bind(&info.as_name)
+    .bind(&info.prefix)
+    .bind(&info.country)
+    .bind(&info.cloud)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// PTR name, origin AS and cloud or CDN provider for each address. Lookups
+/// from the last week are reused unless `refresh` is set; addresses that
+/// aren't IPs are ignored.
+#[tauri::command]
+pub async fn enrich_ips(ips: Vec<String>, refresh: Option<bool>) -> Result<Vec<IpInfo>, String> {
+    let mut seen = HashSet::new();
+    let ips: Vec<IpAddr> = ips.iter().filter_map(|ip| ip.trim().parse().ok()).filter(|ip| seen.insert(*ip)).collect();
+    let pool = get_db();
+
+    let mut known: HashMap<String, IpInfo> = HashMap::new();
+    if !refresh.unwrap_or(false) {
+        let cached: Vec<IpInfo> = sqlx::query_as(
+            "SELECT ip, ptr, asn, as_name, prefix, country, cloud, looked_up_at FROM ip_info WHERE looked_up_at >= datetime('now', ?)",
+        )
+            .bind(format!("-{} days", CACHE_DAYS))
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        known.extend(cached.into_iter().map(|info| (info.ip.clone(), info)));
+    }
+
+    let resolver = crate::recon::resolver();
+    let mut pending = ips.iter().copied().filter(|ip| !known.contains_key(&ip.to_string()));
+    let mut lookups = tokio::task::JoinSet::new();
+    let mut found = Vec::new();
+    loop {
+        while lookups.len() < CONCURRENCY {
+            let Some(ip) = pending.next() else {
+                break;
+            };
+            let resolver = resolver.clone();
+            lookups.spawn(async move {
+                let origin = first_txt(&resolver, &origin_query(ip)).await.and_then(|txt| parse_origin(&txt));
+                (ip, ptr(&resolver, ip).await, origin)
+            });
+        }
+        let Some(joined) = lookups.join_next().await else {
+            break;
+        };
+        if let Ok(result) = joined {
+            found.push(result);
+        }
+    }
+
+    let mut as_names: HashMap<u32, Option<String>> = HashMap::new();
+    let mut infos = Vec::new();
+    for (ip, ptr, origin) in found {
+        let as_name = match &origin {
+            Some(origin) => match as_names.get(&origin.asn) {
+                Some(name) => name.clone(),
+                None => {
+                    let name = first_txt(&resolver, &format!("AS{}.asn.cymru.com", origin.asn)).await.and_then(|txt| parse_as_name(&txt));
+                    as_names.insert(origin.asn, name.clone());
+                    name
+                }
+            },
+            None => None,
+        };
+        let info = IpInfo {
+            ip: ip.to_string(),
+            ptr,
+            asn: origin.as_ref().map(|o| o.asn as i64),
+            as_name,
+            cloud: None,
+            prefix: origin.as_ref().map(|o| o.prefix.clone()),
+            country: origin.map(|o| o.country).filter(|c| !c.is_empty()),
+            looked_up_at: String::new(),
+        };
+        infos.push((ip, info));
+    }
+
+    // Every address is checked against every range, which adds up for a
+    // large batch and a long custom list
+    let custom = crate::settings::get::<CloudRangeSettings>("cloud_ranges").await?.ranges;
+    let infos = tauri::async_runtime::spawn_blocking(move || {
+        infos
+            .into_iter()
+            .map(|(ip, info)| IpInfo { cloud: cloud_provider(ip, info.asn.map(|n| n as u32), &custom), ..info })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for info in &infos {
+        let stored = store(&mut tx, info).await?;
+        known.insert(stored.ip.clone(), stored);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(ips.iter().filter_map(|ip| known.remove(&ip.to_string())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrichment() {
+        assert_eq!(origin_query("104.16.1.2".parse().unwrap()), "2.1.16.104.origin.asn.cymru.com");
+        assert!(origin_query("2606:4700::1".parse().unwrap()).starts_with("1.0.0.0.0.0.0.0."));
+        assert!(origin_query("2606:4700::1".parse().unwrap()).ends_with(".0.0.7.4.6.0.6.2.origin6.asn.cymru.com"));
+
+        assert_eq!(
+            parse_origin("13335 15169 | 104.16.0.0/13 | US | arin | 2014-03-28"),
+            Some(Origin { asn: 13335, prefix: "104.16.0.0/13".to_string(), country: "US".to_string() })
+        );
+        assert_eq!(parse_origin("no data"), None);
+        assert_eq!(parse_as_name("13335 | US | arin | 2010-07-14 | CLOUDFLARENET - Cloudflare, Inc., US").as_deref(), Some("CLOUDFLARENET - Cloudflare, Inc., US"));
+
+        assert_eq!(cloud_provider("104.17.0.1".parse().unwrap(), None, &[]).as_deref(), Some("Cloudflare"));
+        assert_eq!(cloud_provider("2606:4700:10::1".parse().unwrap(), None, &[]).as_deref(), Some("Cloudflare"));
+        assert_eq!(cloud_provider("151.101.1.1".parse().unwrap(), None, &[]).as_deref(), Some("Fastly"));
+        assert_eq!(cloud_provider("3.5.1.1".parse().unwrap(), Some(16509), &[]).as_deref(), Some("AWS"));
+        assert_eq!(cloud_provider("23.1.1.1".parse().unwrap(), Some(20940), &[]).as_deref(), Some("Akamai"));
+        assert_eq!(cloud_provider("10.0.0.1".parse().unwrap(), Some(64512), &[]), None);
+        let custom = vec![CloudRange { cidr: "10.0.0.0/8".to_string(), provider: "Internal CDN".to_string() }];
+        assert_eq!(cloud_provider("10.0.0.1".parse().unwrap(), Some(64512), &custom).as_deref(), Some("Internal CDN"));
+        assert_eq!(parse_cidr("10.0.0.0/33"), None);
+        assert_eq!(parse_cidr("2001:db8::/129"), None);
+        assert_eq!(parse_cidr("10.0.0.0"), None);
+    }
+}
@@ -0,0 +1,451 @@
+//! Credentials attached to an environment and injected into requests sent
+//! while it is active. OAuth2 tokens are fetched on first use, cached for
+//! the session and fetched again when the API answers 401.
+
+use crate::db::get_db;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Tokens are renewed this long before the server says they expire.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Stands in for stored secrets sent to the frontend; saving it back keeps
+/// the stored value.
+const MASK: &str = "••••••••";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthGrant {
+    ClientCredentials,
+    Password,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuthConfig {
+    #[default]
+    None,
+    Bearer { token: String },
+    ApiKey { header: String, value: String },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        grant: OAuthGrant,
+        token_url: String,
+        client_id: String,
+        #[serde(default)]
+        client_secret: String,
+        /// Resource owner credentials for the password grant
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+        #[serde(default)]
+        scope: String,
+    },
+}
+
+impl AuthConfig {
+    /// The header this config sets; OAuth2 tokens are filled in separately.
+    fn header_name(&self) -> Option<&str> {
+        match self {
+            AuthConfig::None => None,
+            AuthConfig::ApiKey { header, .. } => Some(header),
+            AuthConfig::Bearer { .. } | AuthConfig::OAuth2 { .. } => Some("Authorization"),
+        }
+    }
+
+    /// A copy with the token, key value, client secret and password masked.
+    fn masked(&self) -> Self {
+        let mask = |secret: &String| if secret.is_empty() { String::new() } else { MASK.to_string() };
+        let mut config = self.clone();
+        match &mut config {
+            AuthConfig::None => {}
+            AuthConfig::Bearer { token } => *token = mask(token),
+            AuthConfig::ApiKey { value, .. } => *value = mask(value),
+            AuthConfig::OAuth2 { client_secret, password, .. } => {
+                *client_secret = mask(client_secret);
+                *password = mask(password);
+            }
+        }
+        config
+    }
+
+    /// Puts back the secrets of `stored` wherever this config still carries
+    /// the mask from `masked`.
+    fn unmask(mut self, stored: &AuthConfig) -> Self {
+        let keep = |secret: &mut String, old: &String| {
+            if secret == MASK {
+                *secret = old.clone();
+            }
+        };
+        match (&mut self, stored) {
+            (AuthConfig::Bearer { token }, AuthConfig::Bearer { token: old }) => keep(token, old),
+            (AuthConfig::ApiKey { value, .. }, AuthConfig::ApiKey { value: old, .. }) => keep(value, old),
+            (AuthConfig::OAuth2 { client_secret, password, .. }, AuthConfig::OAuth2 { client_secret: old_secret, password: old_password, .. }) => {
+                keep(client_secret, old_secret);
+                keep(password, old_password);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    fn check(&self) -> Result<(), String> {
+        match self {
+            AuthConfig::None => Ok(()),
+            AuthConfig::Bearer { token } if token.trim().is_empty() => Err("The bearer token is empty".to_string()),
+            AuthConfig::ApiKey { header, .. } if reqwest::header::HeaderName::from_bytes(header.as_bytes()).is_err() => {
+                Err(format!("'{}' is not a valid header name", header))
+            }
+            AuthConfig::OAuth2 { token_url, .. } if url::Url::parse(token_url).is_err() => Err(format!("'{}' is not a valid token URL", token_url)),
+            AuthConfig::OAuth2 { grant: OAuthGrant::Password, username, .. } if username.is_empty() => {
+                Err("The password grant needs a username".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    access_token: String,
+    expires_at: Option<Instant>,
+    refresh_token: Option<String>,
+}
+
+impl Token {
+    fn is_fresh(&self) -> bool {
+        self.expires_at.is_none_or(|at| Instant::now() + EXPIRY_MARGIN < at)
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+fn parse_token(body: &str, now: Instant) -> Result<Token, String> {
+    let res: TokenResponse = serde_json::from_str(body).map_err(|_| format!("Token endpoint returned no access_token: {}", body.chars().take(200).collect::<String>()))?;
+    Ok(Token {
+        access_token: res.access_token,
+        expires_at: res.expires_in.map(|secs| now + Duration::from_secs(secs)),
+        refresh_token: res.refresh_token,
+    })
+}
+
+/// Form body for the token request. With a refresh token the grant is
+/// renewed rather than repeated.
+fn token_form(config: &AuthConfig, refresh_token: Option<&str>) -> String {
+    let AuthConfig::OAuth2 { grant, client_id, client_secret, username, password, scope, .. } = config else {
+        return String::new();
+    };
+    let mut form = url::form_urlencoded::Serializer::new(String::new());
+    match (refresh_token, grant) {
+        (Some(refresh), _) => {
+            form.append_pair("grant_type", "refresh_token").append_pair("refresh_token", refresh);
+        }
+        (None, OAuthGrant::ClientCredentials) => {
+            form.append_pair("grant_type", "client_credentials");
+        }
+        (None, OAuthGrant::Password) => {
+            form.append_pair("grant_type", "password").append_pair("username", username).append_pair("password", password);
+        }
+    }
+    form.append_pair("client_id", client_id);
+    if !client_secret.is_empty() {
+        form.append_pair("client_secret", client_secret);
+    }
+    if !scope.is_empty() {
+        form.append_pair("scope", scope);
+    }
+    form.finish()
+}
+
+async fn request_token(config: &AuthConfig, refresh_token: Option<&str>) -> Result<Token, String> {
+    let AuthConfig::OAuth2 { token_url, .. } = config else {
+        return Err("Not an OAuth2 config".to_string());
+    };
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let res = client
+        .post(token_url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("Accept", "application/json")
+        .body(token_form(config, refresh_token))
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+    let status = res.status();
+    let body = res.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("Token endpoint returned {}: {}", status.as_u16(), body.chars().take(200).collect::<String>()));
+    }
+    parse_token(&body, Instant::now())
+}
+
+/// The token in use for one environment.
+#[derive(Default)]
+struct TokenState {
+    current: Option<Token>,
+    /// `current` replaced a token the API rejected; if it is rejected too
+    /// the credentials are at fault, and renewing again would not help.
+    after_rejection: bool,
+}
+
+/// Tokens of one environment. `state` is held while a token is fetched so
+/// concurrent requests renew once between them; `issued` remembers every
+/// token handed out so requests still carrying an older one can be
+/// recognised as ours without waiting on that fetch.
+#[derive(Default)]
+struct EnvTokens {
+    issued: std::sync::Mutex<Vec<String>>,
+    state: Mutex<TokenState>,
+}
+
+type TokenMap = std::sync::Mutex<HashMap<(String, i64), Arc<EnvTokens>>>;
+
+static TOKENS: OnceLock<TokenMap> = OnceLock::new();
+
+fn tokens() -> &'static TokenMap {
+    TOKENS.get_or_init(Default::default)
+}
+
+fn env_tokens(environment_id: i64) -> Arc<EnvTokens> {
+    let mut all = tokens().lock().unwrap();
+    all.entry((crate::db::get_current_workspace(), environment_id)).or_default().clone()
+}
+
+/// Drops cached tokens, e.g. after the config they came from changed.
+fn forget_tokens(environment_id: i64) {
+    tokens().lock().unwrap().remove(&(crate::db::get_current_workspace(), environment_id));
+}
+
+/// Renews with the refresh token when there is one, falling back to
+/// repeating the grant.
+async fn fetch(config: &AuthConfig, env: &EnvTokens, state: &mut TokenState) -> Result<String, String> {
+    let refreshed = match state.current.as_ref().and_then(|t| t.refresh_token.as_deref()) {
+        Some(refresh) => request_token(config, Some(refresh)).await.ok(),
+        None => None,
+    };
+    let token = match refreshed {
+        Some(token) => token,
+        None => request_token(config, None).await?,
+    };
+    let access_token = token.access_token.clone();
+    env.issued.lock().unwrap().push(access_token.clone());
+    state.current = Some(token);
+    state.after_rejection = false;
+    Ok(access_token)
+}
+
+async fn load_config(environment_id: i64) -> Result<AuthConfig, String> {
+    let auth: Option<Option<String>> = sqlx::query_scalar("SELECT auth FROM environments WHERE id = ?")
+        .bind(environment_id)
+        .fetch_optional(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    match auth.flatten() {
+        Some(auth) => serde_json::from_str(&auth).map_err(|e| e.to_string()),
+        None => Ok(AuthConfig::None),
+    }
+}
+
+/// The Authorization value to use instead of `authorization`, if it carries
+/// a token an environment issued: the current token when it is an older
+/// one, or a new token when the current one expired or was `rejected`. A
+/// token that was itself issued after a rejection is not renewed again.
+/// Other credentials under test are never touched.
+pub async fn replacement(authorization: &str, rejected: bool) -> Option<String> {
+    let sent = authorization.strip_prefix("Bearer ")?;
+    let workspace = crate::db::get_current_workspace();
+    let (environment_id, env) = tokens()
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|((ws, _), env)| *ws == workspace && env.issued.lock().unwrap().iter().any(|t| t == sent))
+        .map(|((_, id), env)| (*id, env.clone()))?;
+    let mut state = env.state.lock().await;
+    let current = state.current.as_ref()?;
+    if current.access_token != sent {
+        return Some(format!("Bearer {}", current.access_token));
+    }
+    let expired = !current.is_fresh();
+    if !expired && (!rejected || state.after_rejection) {
+        return None;
+    }
+    let config = load_config(environment_id).await.ok()?;
+    let token = fetch(&config, &env, &mut state).await.ok()?;
+    state.after_rejection = rejected && !expired;
+    Some(format!("Bearer {}", token))
+}
+
+/// `headers` with their Authorization value swapped per `replacement`.
+pub async fn renewed_headers(headers: &HashMap<String, String>, rejected: bool) -> Option<HashMap<String, String>> {
+    let (name, value) = headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("authorization"))?;
+    let fresh = replacement(value, rejected).await?;
+    let mut headers = headers.clone();
+    headers.insert(name.clone(), fresh);
+    Some(headers)
+}
+
+/// The auth config of an environment.
+pub struct EnvAuth {
+    environment_id: i64,
+    config: AuthConfig,
+}
+
+impl EnvAuth {
+    /// `None` when no environment is active or it has no auth configured.
+    pub async fn active() -> Result<Option<Self>, String> {
+        let id: Option<i64> = sqlx::query_scalar("SELECT id FROM environments WHERE is_active = 1 LIMIT 1")
+            .fetch_optional(&get_db())
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(environment_id) = id else { return Ok(None) };
+        let config = load_config(environment_id).await?;
+        Ok((config != AuthConfig::None).then_some(EnvAuth { environment_id, config }))
+    }
+
+    /// The header to send, fetching an OAuth2 token if there's no fresh one.
+    pub async fn header(&self) -> Result<(String, String), String> {
+        let value = match &self.config {
+            AuthConfig::None => return Err("No auth configured".to_string()),
+            AuthConfig::Bearer { token } => format!("Bearer {}", token),
+            AuthConfig::ApiKey { value, .. } => value.clone(),
+            AuthConfig::OAuth2 { .. } => {
+                let env = env_tokens(self.environment_id);
+                let mut state = env.state.lock().await;
+                let token = match &state.current {
+                    Some(token) if token.is_fresh() => token.access_token.clone(),
+                    _ => fetch(&self.config, &env, &mut state).await?,
+                };
+                format!("Bearer {}", token)
+            }
+        };
+        Ok((self.config.header_name().unwrap_or_default().to_string(), value))
+    }
+
+    /// Adds the auth header unless `headers` already carry one of that name;
+    /// credentials set on the request itself win.
+    pub async fn inject(&self, headers: &mut HashMap<String, String>) -> Result<(), String> {
+        let Some(name) = self.config.header_name() else { return Ok(()) };
+        if !headers.keys().any(|k| k.eq_ignore_ascii_case(name)) {
+            let (name, value) = self.header().await?;
+            headers.insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// Injects the active environment's auth header, if it has one.
+    pub async fn inject_active(headers: &mut HashMap<String, String>) -> Result<(), String> {
+        match Self::active().await? {
+            Some(auth) => auth.inject(headers).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_environment_auth(environment_id: i64) -> Result<AuthConfig, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    Ok(load_config(environment_id).await?.masked())
+}
+
+#[tauri::command]
+pub async fn set_environment_auth(environment_id: i64, auth: AuthConfig) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let auth = auth.unmask(&load_config(environment_id).await?);
+    auth.check()?;
+    let json = serde_json::to_string(&auth).map_err(|e| e.to_string())?;
+    let updated = sqlx::query("UPDATE environments SET auth = ? WHERE id = ?")
+        .bind(json)
+        .bind(environment_id)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    if updated.rows_affected() == 0 {
+        return Err(format!("Environment {} not found", environment_id));
+    }
+    forget_tokens(environment_id);
+    Ok(())
+}
+
+/// Fetches a fresh token (or builds the static header) for an environment
+/// and returns the header it would send, with the value masked.
+#[tauri::command]
+pub async fn test_environment_auth(environment_id: i64) -> Result<String, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let config = load_config(environment_id).await?;
+    forget_tokens(environment_id);
+    let (name, value) = EnvAuth { environment_id, config }.header().await?;
+    let shown = match value.split_once(' ') {
+        Some((scheme, _)) => format!("{} {}", scheme, MASK),
+        None => MASK.to_string(),
+    };
+    Ok(format!("{}: {} ({} chars)", name, shown, value.chars().count()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_form_and_response() {
+        let config = AuthConfig::OAuth2 {
+            grant: OAuthGrant::Password,
+            token_url: "https://auth.example.com/token".to_string(),
+            client_id: "cli".to_string(),
+            client_secret: String::new(),
+            username: "ann".to_string(),
+            password: "p&ss".to_string(),
+            scope: "read write".to_string(),
+        };
+        assert_eq!(token_form(&config, None), "grant_type=password&username=ann&password=p%26ss&client_id=cli&scope=read+write");
+        assert_eq!(token_form(&config, Some("r1")), "grant_type=refresh_token&refresh_token=r1&client_id=cli&scope=read+write");
+        assert!(config.check().is_ok());
+
+        let now = Instant::now();
+        let token = parse_token(r#"{"access_token": "t1", "token_type": "Bearer", "expires_in": 10}"#, now).unwrap();
+        assert_eq!(token.expires_at, Some(now + Duration::from_secs(10)));
+        // Inside the expiry margin already
+        assert!(!token.is_fresh());
+        assert!(parse_token(r#"{"error": "invalid_client"}"#, now).unwrap_err().contains("invalid_client"));
+
+        let parsed: AuthConfig = serde_json::from_str(r#"{"type": "api_key", "header": "X-Api-Key", "value": "k"}"#).unwrap();
+        assert_eq!(parsed.header_name(), Some("X-Api-Key"));
+        assert!(AuthConfig::ApiKey { header: "bad header".to_string(), value: String::new() }.check().is_err());
+    }
+
+    #[test]
+    fn test_masked_secrets_round_trip() {
+        let stored = AuthConfig::OAuth2 {
+            grant: OAuthGrant::Password,
+            token_url: "https://auth.example.com/token".to_string(),
+            client_id: "cli".to_string(),
+            client_secret: "s3cret".to_string(),
+            username: "ann".to_string(),
+            password: String::new(),
+            scope: String::new(),
+        };
+        let masked = stored.masked();
+        let AuthConfig::OAuth2 { client_secret, password, client_id, .. } = &masked else { unreachable!() };
+        assert_eq!((client_secret.as_str(), password.as_str(), client_id.as_str()), (MASK, "", "cli"));
+        assert_eq!(masked.unmask(&stored), stored);
+
+        let edited = AuthConfig::Bearer { token: "new".to_string() };
+        assert_eq!(edited.clone().unmask(&AuthConfig::Bearer { token: "old".to_string() }), edited);
+        // A mask can't leak into a config of another type
+        let switched = AuthConfig::ApiKey { header: "X-Api-Key".to_string(), value: MASK.to_string() };
+        assert_eq!(switched.clone().unmask(&stored), switched);
+    }
+}
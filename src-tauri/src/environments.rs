@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::db::get_db;
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Environment {
@@ -8,10 +10,13 @@ pub struct Environment {
     pub base_url: String,
     pub variables: String, // JSON string of key-value pairs
     pub is_active: bool,
+    /// Environment whose variables and base URL this one inherits
+    #[serde(default)]
+    pub parent_id: Option<i64>,
 }
 
-pub async fn init_environments_table() -> Result<(), String> {
-    let pool = get_db();
+/// Creates the environment, auth profile and matrix tables in a workspace.
+pub async fn init_tables(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS environments (
@@ -23,9 +28,54 @@ pub async fn init_environments_table() -> Result<(), String> {
         )
         "#,
     )
-    .execute(&pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    .execute(pool)
+    .await?;
+
+    let _ = sqlx::query("ALTER TABLE environments ADD COLUMN parent_id INTEGER REFERENCES environments(id) ON DELETE SET NULL")
+        .execute(pool)
+        .await;
+
+    // `env_auth::AuthConfig` JSON injected into requests while active
+    let _ = sqlx::query("ALTER TABLE environments ADD COLUMN auth TEXT").execute(pool).await;
+
+    // Per-environment credentials for authorization matrix runs
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS auth_profiles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            environment_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            privilege INTEGER NOT NULL DEFAULT 0,
+            headers TEXT NOT NULL DEFAULT '{}',
+            UNIQUE(environment_id, name),
+            FOREIGN KEY(environment_id) REFERENCES environments(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS auth_matrix_cells (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id TEXT NOT NULL,
+            asset_id INTEGER NOT NULL,
+            profile_id INTEGER NOT NULL,
+            status INTEGER NOT NULL,
+            length INTEGER NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(asset_id) REFERENCES assets(id) ON DELETE CASCADE,
+            FOREIGN KEY(profile_id) REFERENCES auth_profiles(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_auth_matrix_cells_run_id ON auth_matrix_cells(run_id)")
+        .execute(pool)
+        .await?;
 
     Ok(())
 }
@@ -33,8 +83,8 @@ pub async fn init_environments_table() -> Result<(), String> {
 #[tauri::command]
 pub async fn get_environments() -> Result<Vec<Environment>, String> {
     let pool = get_db();
-    let rows = sqlx::query_as::<_, (i64, String, String, String, bool)>(
-        "SELECT id, name, base_url, variables, is_active FROM environments ORDER BY name"
+    let rows = sqlx::query_as::<_, (i64, String, String, String, bool, Option<i64>)>(
+        "SELECT id, name, base_url, variables, is_active, parent_id FROM environments ORDER BY name"
     )
     .fetch_all(&pool)
     .await
@@ -42,12 +92,13 @@ pub async fn get_environments() -> Result<Vec<Environment>, String> {
 
     Ok(rows
         .into_iter()
-        .map(|(id, name, base_url, variables, is_active)| Environment {
+        .map(|(id, name, base_url, variables, is_active, parent_id)| Environment {
             id: Some(id),
             name,
             base_url,
             variables,
             is_active,
+            parent_id,
         })
         .collect())
 }
@@ -55,31 +106,221 @@ pub async fn get_environments() -> Result<Vec<Environment>, String> {
 #[tauri::command]
 pub async fn get_active_environment() -> Result<Option<Environment>, String> {
     let pool = get_db();
-    let row = sqlx::query_as::<_, (i64, String, String, String, bool)>(
-        "SELECT id, name, base_url, variables, is_active FROM environments WHERE is_active = 1 LIMIT 1"
+    let row = sqlx::query_as::<_, (i64, String, String, String, bool, Option<i64>)>(
+        "SELECT id, name, base_url, variables, is_active, parent_id FROM environments WHERE is_active = 1 LIMIT 1"
     )
     .fetch_optional(&pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(row.map(|(id, name, base_url, variables, is_active)| Environment {
+    Ok(row.map(|(id, name, base_url, variables, is_active, parent_id)| Environment {
         id: Some(id),
         name,
         base_url,
         variables,
         is_active,
+        parent_id,
     }))
 }
 
+fn parse_variables(json: &str) -> HashMap<String, String> {
+    let parsed: serde_json::Value = serde_json::from_str(json).unwrap_or_default();
+    parsed
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .map(|(k, v)| {
+                    let value = match v {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (k.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Environment `id` followed by its ancestors, nearest first.
+fn ancestry(envs: &[Environment], id: i64) -> Result<Vec<&Environment>, String> {
+    let mut chain: Vec<&Environment> = Vec::new();
+    let mut next = Some(id);
+    while let Some(id) = next {
+        let env = envs.iter().find(|e| e.id == Some(id)).ok_or_else(|| format!("Environment {} not found", id))?;
+        if chain.iter().any(|e| e.id == env.id) {
+            let names: Vec<&str> = chain.iter().chain([&env]).map(|e| e.name.as_str()).collect();
+            return Err(format!("Environment inheritance cycle: {}", names.join(" -> ")));
+        }
+        chain.push(env);
+        next = env.parent_id;
+    }
+    Ok(chain)
+}
+
+/// Variables of `chain` (as returned by `ancestry`) with nearer environments
+/// overriding their ancestors. The nearest non-empty base URL is available
+/// as `base_url` unless a variable of that name overrides it.
+fn layer(chain: &[&Environment]) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for env in chain.iter().rev() {
+        vars.extend(parse_variables(&env.variables));
+    }
+    if let Some(env) = chain.iter().find(|e| !e.base_url.is_empty()) {
+        vars.entry("base_url".to_string()).or_insert(env.base_url.clone());
+    }
+    vars
+}
+
+/// Variables of the active environment, layered over those it inherits, as
+/// a flat map (empty when none is active).
+pub async fn active_variables() -> Result<HashMap<String, String>, String> {
+    let envs = get_environments().await?;
+    let Some(active) = envs.iter().find(|e| e.is_active) else {
+        return Ok(HashMap::new());
+    };
+    Ok(layer(&ancestry(&envs, active.id.unwrap_or_default())?))
+}
+
+/// Rejects a parent that doesn't exist or would make `id` its own ancestor.
+async fn check_parent(id: Option<i64>, parent_id: Option<i64>) -> Result<(), String> {
+    let Some(parent_id) = parent_id else { return Ok(()) };
+    let mut envs = get_environments().await?;
+    let start = match (id, envs.iter_mut().find(|e| e.id.is_some() && e.id == id)) {
+        (Some(id), Some(env)) => {
+            env.parent_id = Some(parent_id);
+            id
+        }
+        _ => parent_id,
+    };
+    ancestry(&envs, start).map(|_| ())
+}
+
+/// A Postman environment file, as exported from Postman's environment menu.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PostmanEnvironment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub values: Vec<PostmanVariable>,
+    #[serde(rename = "_postman_variable_scope", default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(rename = "_postman_exported_at", default, skip_serializing_if = "Option::is_none")]
+    pub exported_at: Option<String>,
+    #[serde(rename = "_postman_exported_using", default, skip_serializing_if = "Option::is_none")]
+    pub exported_using: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PostmanVariable {
+    pub key: String,
+    #[serde(default)]
+    pub value: serde_json::Value,
+    /// `default` or `secret`
+    #[serde(rename = "type", default = "default_variable_type")]
+    pub kind: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_variable_type() -> String {
+    "default".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// `env` as a Postman environment. The base URL becomes a `base_url`
+/// variable, and variables with credential-like names are typed `secret`.
+pub fn to_postman(env: &Environment) -> PostmanEnvironment {
+    let parsed: serde_json::Value = serde_json::from_str(&env.variables).unwrap_or_default();
+    let vars = parsed.as_object().cloned().unwrap_or_default();
+    let mut values = Vec::new();
+    if !env.base_url.is_empty() && !vars.contains_key("base_url") {
+        values.push(PostmanVariable { key: "base_url".to_string(), value: env.base_url.clone().into(), kind: default_variable_type(), enabled: true });
+    }
+    for (key, value) in vars {
+        let value = match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        let kind = if crate::ai::is_sensitive(&key) { "secret" } else { "default" };
+        values.push(PostmanVariable { key, value: value.into(), kind: kind.to_string(), enabled: true });
+    }
+    PostmanEnvironment {
+        id: Some(uuid::Uuid::new_v4().to_string()),
+        name: env.name.clone(),
+        values,
+        scope: Some("environment".to_string()),
+        exported_at: Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        exported_using: Some("apisec".to_string()),
+    }
+}
+
+/// The base URL and variables JSON of a Postman environment. Disabled
+/// variables are dropped and a `base_url` variable becomes the base URL.
+pub fn from_postman(env: &PostmanEnvironment) -> (String, String) {
+    let mut base_url = String::new();
+    let mut vars = serde_json::Map::new();
+    for var in env.values.iter().filter(|v| v.enabled && !v.key.is_empty()) {
+        let value = match &var.value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        if var.key == "base_url" {
+            base_url = value;
+        } else {
+            vars.insert(var.key.clone(), value.into());
+        }
+    }
+    (base_url, serde_json::Value::Object(vars).to_string())
+}
+
+/// Postman environment JSON for environment `id`.
+#[tauri::command]
+pub async fn export_environment_postman(id: i64) -> Result<String, String> {
+    let env = get_environments()
+        .await?
+        .into_iter()
+        .find(|e| e.id == Some(id))
+        .ok_or_else(|| format!("Environment {} not found", id))?;
+    serde_json::to_string_pretty(&to_postman(&env)).map_err(|e| e.to_string())
+}
+
+/// Imports a Postman environment file. An environment with the same name
+/// has its base URL and variables replaced.
 #[tauri::command]
-pub async fn create_environment(name: String, base_url: String, variables: String) -> Result<i64, String> {
+pub async fn import_environment_postman(content: String) -> Result<i64, String> {
+    let env: PostmanEnvironment = serde_json::from_str(&content).map_err(|e| format!("Not a Postman environment: {}", e))?;
+    if env.name.trim().is_empty() {
+        return Err("The environment has no name".to_string());
+    }
+    let (base_url, variables) = from_postman(&env);
+    let pool = get_db();
+    sqlx::query_scalar(
+        "INSERT INTO environments (name, base_url, variables, is_active) VALUES (?, ?, ?, 0)
+         ON CONFLICT(name) DO UPDATE SET base_url = excluded.base_url, variables = excluded.variables RETURNING id",
+    )
+    .bind(env.name.trim())
+    .bind(&base_url)
+    .bind(&variables)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_environment(name: String, base_url: String, variables: String, parent_id: Option<i64>) -> Result<i64, String> {
+    check_parent(None, parent_id).await?;
     let pool = get_db();
     let result = sqlx::query(
-        "INSERT INTO environments (name, base_url, variables, is_active) VALUES (?, ?, ?, 0)"
+        "INSERT INTO environments (name, base_url, variables, is_active, parent_id) VALUES (?, ?, ?, 0, ?)"
     )
     .bind(&name)
     .bind(&base_url)
     .bind(&variables)
+    .bind(parent_id)
     .execute(&pool)
     .await
     .map_err(|e| e.to_string())?;
@@ -109,6 +350,7 @@ pub async fn set_active_environment(id: i64) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn delete_environment(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
     let pool = get_db();
     sqlx::query("DELETE FROM environments WHERE id = ?")
         .bind(id)
@@ -120,12 +362,14 @@ pub async fn delete_environment(id: i64) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn update_environment(id: i64, name: String, base_url: String, variables: String) -> Result<(), String> {
+pub async fn update_environment(id: i64, name: String, base_url: String, variables: String, parent_id: Option<i64>) -> Result<(), String> {
+    check_parent(Some(id), parent_id).await?;
     let pool = get_db();
-    sqlx::query("UPDATE environments SET name = ?, base_url = ?, variables = ? WHERE id = ?")
+    sqlx::query("UPDATE environments SET name = ?, base_url = ?, variables = ?, parent_id = ? WHERE id = ?")
         .bind(&name)
         .bind(&base_url)
         .bind(&variables)
+        .bind(parent_id)
         .bind(id)
         .execute(&pool)
         .await
@@ -133,3 +377,62 @@ pub async fn update_environment(id: i64, name: String, base_url: String, variabl
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postman_round_trip() {
+        let env = Environment {
+            id: Some(1),
+            name: "Staging".to_string(),
+            base_url: "https://staging.example.com".to_string(),
+            variables: r#"{"api_token": "abc", "page_size": 50}"#.to_string(),
+            is_active: false,
+            parent_id: None,
+        };
+        let exported = to_postman(&env);
+        let kinds: Vec<_> = exported.values.iter().map(|v| (v.key.as_str(), v.kind.as_str())).collect();
+        assert_eq!(kinds, [("base_url", "default"), ("api_token", "secret"), ("page_size", "default")]);
+        assert_eq!(exported.values[2].value, "50");
+
+        let json = serde_json::to_string(&exported).unwrap();
+        let mut imported: PostmanEnvironment = serde_json::from_str(&json).unwrap();
+        imported.values.push(PostmanVariable { key: "old".to_string(), value: "x".into(), kind: default_variable_type(), enabled: false });
+        let (base_url, variables) = from_postman(&imported);
+        assert_eq!(base_url, env.base_url);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&variables).unwrap(), serde_json::json!({"api_token": "abc", "page_size": "50"}));
+
+        // Hand-written files often leave out `type` and `enabled`
+        let minimal: PostmanEnvironment = serde_json::from_str(r#"{"name": "Dev", "values": [{"key": "host", "value": "localhost"}]}"#).unwrap();
+        assert_eq!(from_postman(&minimal), (String::new(), r#"{"host":"localhost"}"#.to_string()));
+    }
+
+    #[test]
+    fn test_inheritance() {
+        let env = |id, name: &str, base_url: &str, variables: &str, parent_id| Environment {
+            id: Some(id),
+            name: name.to_string(),
+            base_url: base_url.to_string(),
+            variables: variables.to_string(),
+            is_active: false,
+            parent_id,
+        };
+        let mut envs = vec![
+            env(1, "Base", "https://api.example.com", r#"{"version": "v1", "timeout": 30}"#, None),
+            env(2, "Staging", "", r#"{"version": "v2", "token": "s"}"#, Some(1)),
+            env(3, "Staging EU", "https://eu.example.com", "{}", Some(2)),
+        ];
+        let chain = ancestry(&envs, 3).unwrap();
+        assert_eq!(chain.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["Staging EU", "Staging", "Base"]);
+        let vars = layer(&chain);
+        assert_eq!(vars["version"], "v2");
+        assert_eq!(vars["timeout"], "30");
+        assert_eq!(vars["base_url"], "https://eu.example.com");
+        assert_eq!(layer(&ancestry(&envs, 2).unwrap())["base_url"], "https://api.example.com");
+
+        envs[0].parent_id = Some(3);
+        assert_eq!(ancestry(&envs, 2).unwrap_err(), "Environment inheritance cycle: Staging -> Base -> Staging EU -> Staging");
+    }
+}
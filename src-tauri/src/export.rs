@@ -0,0 +1,143 @@
+use crate::db::get_db;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Headers whose values are replaced when exporting with `redact`.
+const REDACTED_HEADERS: [&str; 5] = ["authorization", "proxy-authorization", "cookie", "x-api-key", "x-auth-token"];
+
+const REDACTED: &str = "REDACTED";
+
+/// A stored request ready to be rendered as a client snippet.
+pub struct ExportRequest {
+    pub method: String,
+    pub url: String,
+    /// Sorted by name; length and HTTP/2 pseudo-headers already dropped
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl ExportRequest {
+    pub fn new(method: Option<String>, url: String, headers: HashMap<String, String>, body: Option<String>, redact: bool) -> Self {
+        let mut headers: Vec<(String, String)> = headers
+            .into_iter()
+            // The client recomputes the length, and may have to after edits
+            .filter(|(k, _)| !k.starts_with(':') && !k.eq_ignore_ascii_case("content-length"))
+            .map(|(k, v)| {
+                let v = if redact && REDACTED_HEADERS.contains(&k.to_lowercase().as_str()) { REDACTED.to_string() } else { v };
+                (k, v)
+            })
+            .collect();
+        headers.sort_by_key(|(k, _)| k.to_lowercase());
+        Self {
+            method: method.filter(|m| !m.trim().is_empty()).map(|m| m.to_uppercase()).unwrap_or_else(|| "GET".to_string()),
+            url,
+            headers,
+            body: body.filter(|b| !b.is_empty()),
+        }
+    }
+}
+
+/// POSIX shell single-quoting.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+pub fn to_curl(req: &ExportRequest) -> String {
+    let mut parts = vec![format!("curl -X {} {}", req.method, shell_quote(&req.url))];
+    parts.extend(req.headers.iter().map(|(k, v)| format!("-H {}", shell_quote(&format!("{}: {}", k, v)))));
+    if let Some(body) = &req.body {
+        parts.push(format!("--data-raw {}", shell_quote(body)));
+    }
+    parts.join(" \\\n  ")
+}
+
+/// HTTPie command; the body is passed verbatim with `--raw`.
+pub fn to_httpie(req: &ExportRequest) -> String {
+    let mut parts = vec![format!("http {} {}", req.method, shell_quote(&req.url))];
+    parts.extend(req.headers.iter().map(|(k, v)| shell_quote(&format!("{}:{}", k, v))));
+    if let Some(body) = &req.body {
+        parts.push(format!("--raw {}", shell_quote(body)));
+    }
+    parts.join(" \\\n  ")
+}
+
+/// JavaScript `fetch` call. Strings are emitted as JSON literals, which are valid JS.
+pub fn to_fetch(req: &ExportRequest) -> String {
+    let mut options = Map::new();
+    options.insert("method".to_string(), json!(req.method));
+    if !req.headers.is_empty() {
+        let headers: Map<String, Value> = req.headers.iter().map(|(k, v)| (k.clone(), json!(v))).collect();
+        options.insert("headers".to_string(), Value::Object(headers));
+    }
+    if let Some(body) = &req.body {
+        options.insert("body".to_string(), json!(body));
+    }
+    let options = serde_json::to_string_pretty(&Value::Object(options)).unwrap_or_default();
+    format!("await fetch({}, {});", json!(req.url), options)
+}
+
+async fn load_request(asset_id: i64, redact: Option<bool>) -> Result<ExportRequest, String> {
+    let pool = get_db();
+    let (url, method, headers, body): (String, Option<String>, Option<String>, Option<String>) =
+        sqlx::query_as("SELECT url, method, req_headers, req_body FROM assets WHERE id = ?")
+            .bind(asset_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Asset {} not found", asset_id))?;
+    let headers = headers.and_then(|h| serde_json::from_str(&h).ok()).unwrap_or_default();
+    Ok(ExportRequest::new(method, url, headers, body, redact.unwrap_or(false)))
+}
+
+/// Exports an asset's captured request as a curl command. With `redact`,
+/// credential headers such as Authorization and Cookie are masked.
+#[tauri::command]
+pub async fn export_as_curl(asset_id: i64, redact: Option<bool>) -> Result<String, String> {
+    Ok(to_curl(&load_request(asset_id, redact).await?))
+}
+
+#[tauri::command]
+pub async fn export_as_fetch(asset_id: i64, redact: Option<bool>) -> Result<String, String> {
+    Ok(to_fetch(&load_request(asset_id, redact).await?))
+}
+
+#[tauri::command]
+pub async fn export_as_httpie(asset_id: i64, redact: Option<bool>) -> Result<String, String> {
+    Ok(to_httpie(&load_request(asset_id, redact).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(redact: bool) -> ExportRequest {
+        let headers = HashMap::from([
+            ("Authorization".to_string(), "Bearer abc".to_string()),
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("Content-Length".to_string(), "13".to_string()),
+        ]);
+        ExportRequest::new(Some("post".to_string()), "https://api.example.com/it's".to_string(), headers, Some(r#"{"a":"b'c"}"#.to_string()), redact)
+    }
+
+    #[test]
+    fn test_to_curl() {
+        assert_eq!(
+            to_curl(&request(false)),
+            "curl -X POST 'https://api.example.com/it'\\''s' \\\n  -H 'Authorization: Bearer abc' \\\n  -H 'Content-Type: application/json' \\\n  --data-raw '{\"a\":\"b'\\''c\"}'"
+        );
+        assert!(to_curl(&request(true)).contains("-H 'Authorization: REDACTED'"));
+    }
+
+    #[test]
+    fn test_to_fetch_and_httpie() {
+        let fetch = to_fetch(&request(true));
+        assert!(fetch.starts_with("await fetch(\"https://api.example.com/it's\", {"));
+        assert!(fetch.contains("\"Authorization\": \"REDACTED\""));
+        assert!(fetch.contains(r#""body": "{\"a\":\"b'c\"}""#));
+        assert!(!fetch.contains("Content-Length"));
+
+        let httpie = to_httpie(&request(false));
+        assert!(httpie.starts_with("http POST 'https://api.example.com/it'\\''s' \\\n  'Authorization:Bearer abc'"));
+        assert!(httpie.ends_with("--raw '{\"a\":\"b'\\''c\"}'"));
+    }
+}
@@ -0,0 +1,549 @@
+use crate::analysis::{Finding, FindingSeverity};
+use crate::assets::CreateAssetRequest;
+use crate::db::get_db;
+use crate::recon::ReconProgress;
+use crate::JobState;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+
+const DEFAULT_CONCURRENCY: usize = 10;
+const MAX_CONCURRENCY: usize = 100;
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Bytes of a response body kept for matching and as asset evidence.
+const MAX_BODY: usize = 64 * 1024;
+
+/// Characters of a matched line kept as a finding's evidence.
+const MAX_EVIDENCE_CHARS: usize = 200;
+
+const OWASP_API8: &str = "https://owasp.org/API-Security/editions/2023/en/0xa8-security-misconfiguration/";
+const OWASP_API9: &str = "https://owasp.org/API-Security/editions/2023/en/0xa9-improper-inventory-management/";
+
+/// `(header, lowercase substring of its value or None for mere presence, technology, category)`.
+const HEADER_SIGNATURES: &[(&str, Option<&str>, &str, &str)] = &[
+    ("server", Some("nginx"), "nginx", "server"),
+    ("server", Some("apache"), "Apache httpd", "server"),
+    ("server", Some("microsoft-iis"), "IIS", "server"),
+    ("server", Some("envoy"), "Envoy", "gateway"),
+    ("server", Some("kong"), "Kong", "gateway"),
+    ("server", Some("awselb"), "AWS ELB", "gateway"),
+    ("server", Some("cloudflare"), "Cloudflare", "cdn"),
+    ("server", Some("gunicorn"), "Gunicorn", "server"),
+    ("server", Some("uvicorn"), "Uvicorn", "server"),
+    ("server", Some("kestrel"), "Kestrel", "server"),
+    ("server", Some("jetty"), "Jetty", "server"),
+    ("x-powered-by", Some("express"), "Express", "framework"),
+    ("x-powered-by", Some("next.js"), "Next.js", "framework"),
+    ("x-powered-by", Some("php"), "PHP", "language"),
+    ("x-powered-by", Some("asp.net"), "ASP.NET", "framework"),
+    ("x-aspnet-version", None, "ASP.NET", "framework"),
+    ("x-kong-upstream-latency", None, "Kong", "gateway"),
+    ("x-kong-proxy-latency", None, "Kong", "gateway"),
+    ("x-envoy-upstream-service-time", None, "Envoy", "gateway"),
+    ("x-amz-apigw-id", None, "AWS API Gateway", "gateway"),
+    ("apigw-requestid", None, "AWS API Gateway", "gateway"),
+    ("x-amzn-requestid", None, "AWS", "cloud"),
+    ("x-azure-ref", None, "Azure Front Door", "cdn"),
+    ("x-ms-request-id", None, "Azure", "cloud"),
+    ("x-cloud-trace-context", None, "Google Cloud", "cloud"),
+    ("cf-ray", None, "Cloudflare", "cdn"),
+    ("x-served-by", Some("cache-"), "Fastly", "cdn"),
+    ("x-tyk-api-expires", None, "Tyk", "gateway"),
+    ("x-apigee-fault-code", None, "Apigee", "gateway"),
+    ("set-cookie", Some("jsessionid"), "Java servlet", "language"),
+    ("set-cookie", Some("phpsessid"), "PHP", "language"),
+    ("set-cookie", Some("laravel_session"), "Laravel", "framework"),
+    ("set-cookie", Some("csrftoken"), "Django", "framework"),
+    ("set-cookie", Some("asp.net_sessionid"), "ASP.NET", "framework"),
+];
+
+/// Shodan-style favicon hashes of well-known applications.
+const FAVICON_HASHES: &[(i32, &str, &str)] = &[
+    (116323821, "Spring Boot", "framework"),
+    (81586312, "Jenkins", "app"),
+    (1278323681, "GitLab", "app"),
+];
+
+/// Markers in a response body; any one matching (case-insensitively)
+/// confirms the path.
+struct WellKnown {
+    path: &'static str,
+    markers: &'static [&'static str],
+    name: &'static str,
+    category: &'static str,
+    exposure: Option<Exposure>,
+}
+
+struct Exposure {
+    rule_id: &'static str,
+    title: &'static str,
+    severity: FindingSeverity,
+    description: &'static str,
+    remediation: &'static str,
+    references: &'static [&'static str],
+}
+
+const API_DOCS: Exposure = Exposure {
+    rule_id: "RECON-EXPOSED-API-DOCS",
+    title: "Exposed API documentation",
+    severity: FindingSeverity::Low,
+    description: "Machine-readable API documentation is publicly reachable, listing every operation and parameter for an attacker.",
+    remediation: "Serve API documentation only to authenticated users or internal networks, or remove it from production.",
+    references: &[OWASP_API9],
+};
+
+const WELL_KNOWN: &[WellKnown] = &[
+    WellKnown { path: "/swagger.json", markers: &["\"swagger\"", "\"openapi\""], name: "OpenAPI docs", category: "docs", exposure: Some(API_DOCS) },
+    WellKnown { path: "/openapi.json", markers: &["\"swagger\"", "\"openapi\""], name: "OpenAPI docs", category: "docs", exposure: Some(API_DOCS) },
+    WellKnown { path: "/v2/api-docs", markers: &["\"swagger\""], name: "OpenAPI docs", category: "docs", exposure: Some(API_DOCS) },
+    WellKnown { path: "/v3/api-docs", markers: &["\"openapi\""], name: "OpenAPI docs", category: "docs", exposure: Some(API_DOCS) },
+    WellKnown { path: "/swagger-ui.html", markers: &["swagger-ui"], name: "Swagger UI", category: "docs", exposure: Some(API_DOCS) },
+    WellKnown { path: "/graphql?query=%7B__typename%7D", markers: &["__typename", "\"errors\""], name: "GraphQL", category: "api", exposure: None },
+    WellKnown { path: "/.well-known/openid-configuration", markers: &["\"issuer\""], name: "OpenID Connect", category: "auth", exposure: None },
+    WellKnown {
+        path: "/actuator",
+        markers: &["\"_links\""],
+        name: "Spring Boot Actuator",
+        category: "framework",
+        exposure: Some(Exposure {
+            rule_id: "RECON-ACTUATOR",
+            title: "Spring Boot Actuator exposed",
+            severity: FindingSeverity::Medium,
+            description: "The Actuator index is publicly reachable and lists management endpoints that may leak configuration or allow state changes.",
+            remediation: "Expose only the health endpoint publicly (management.endpoints.web.exposure.include=health) and protect the rest.",
+            references: &[OWASP_API8],
+        }),
+    },
+    WellKnown {
+        path: "/actuator/env",
+        markers: &["propertysources", "activeprofiles"],
+        name: "Spring Boot Actuator",
+        category: "framework",
+        exposure: Some(Exposure {
+            rule_id: "RECON-ACTUATOR-ENV",
+            title: "Spring Boot Actuator environment exposed",
+            severity: FindingSeverity::High,
+            description: "The Actuator env endpoint returns the application's configuration, often including credentials and internal hostnames.",
+            remediation: "Disable the env endpoint or restrict it to an authenticated management port, and rotate any secrets it exposed.",
+            references: &[OWASP_API8],
+        }),
+    },
+    WellKnown {
+        path: "/.env",
+        markers: &["app_key=", "db_password=", "database_url=", "secret_key=", "aws_secret_access_key="],
+        name: "Dotenv file",
+        category: "config",
+        exposure: Some(Exposure {
+            rule_id: "RECON-DOTENV",
+            title: "Environment file exposed",
+            severity: FindingSeverity::High,
+            description: "A .env file with application secrets is served from the web root.",
+            remediation: "Block dotfiles at the web server, move the file out of the document root and rotate every secret it contains.",
+            references: &["https://cwe.mitre.org/data/definitions/538.html", OWASP_API8],
+        }),
+    },
+    WellKnown {
+        path: "/.git/config",
+        markers: &["[core]"],
+        name: "Git repository",
+        category: "config",
+        exposure: Some(Exposure {
+            rule_id: "RECON-GIT-CONFIG",
+            title: "Git metadata exposed",
+            severity: FindingSeverity::High,
+            description: "The .git directory is served, so the application's source code and history can be downloaded.",
+            remediation: "Deny access to .git at the web server and deploy build artifacts instead of working copies.",
+            references: &["https://cwe.mitre.org/data/definitions/527.html", OWASP_API8],
+        }),
+    },
+    WellKnown {
+        path: "/server-status",
+        markers: &["apache server status"],
+        name: "Apache mod_status",
+        category: "server",
+        exposure: Some(Exposure {
+            rule_id: "RECON-SERVER-STATUS",
+            title: "Apache server-status exposed",
+            severity: FindingSeverity::Medium,
+            description: "mod_status is publicly reachable and shows client addresses and the URLs currently being requested.",
+            remediation: "Restrict /server-status to localhost or an administrative network.",
+            references: &[OWASP_API8],
+        }),
+    },
+    WellKnown {
+        path: "/metrics",
+        markers: &["# help ", "# type "],
+        name: "Prometheus metrics",
+        category: "monitoring",
+        exposure: Some(Exposure {
+            rule_id: "RECON-METRICS",
+            title: "Metrics endpoint exposed",
+            severity: FindingSeverity::Low,
+            description: "Prometheus metrics are publicly reachable and reveal internal routes, dependencies and load.",
+            remediation: "Serve metrics on an internal port or require authentication for the scrape endpoint.",
+            references: &[OWASP_API8],
+        }),
+    },
+    WellKnown {
+        path: "/debug/pprof/",
+        markers: &["goroutine"],
+        name: "Go pprof",
+        category: "monitoring",
+        exposure: Some(Exposure {
+            rule_id: "RECON-PPROF",
+            title: "Go pprof profiler exposed",
+            severity: FindingSeverity::Medium,
+            description: "The pprof debug handlers are publicly reachable, leaking memory contents and allowing expensive profiling requests.",
+            remediation: "Do not register net/http/pprof on the public listener.",
+            references: &[OWASP_API8],
+        }),
+    },
+];
+
+#[derive(Deserialize, Debug)]
+pub struct FingerprintRequest {
+    pub hosts: Vec<String>,
+    pub concurrency: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    /// Profile the run must be allowed by; the default one when unset
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Technology {
+    pub name: String,
+    /// `server`, `framework`, `gateway`, `docs`, ...
+    pub category: String,
+    /// Header, path or favicon hash it was detected from
+    pub evidence: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+pub struct HostTechnology {
+    pub id: i64,
+    pub host: String,
+    pub name: String,
+    pub category: String,
+    pub evidence: String,
+    pub detected_at: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HostFingerprint {
+    pub host: String,
+    pub base_url: Option<String>,
+    pub favicon_hash: Option<i32>,
+    pub technologies: Vec<Technology>,
+    /// URLs imported as assets with a finding
+    pub exposures: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FingerprintSummary {
+    pub job_id: String,
+    pub total: usize,
+    pub checked: usize,
+    pub exposures: usize,
+    pub cancelled: bool,
+}
+
+struct Fetched {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// MurmurHash3 x86 32-bit.
+pub fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    let mut h = seed;
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        h ^= mix(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    if !tail.is_empty() {
+        h ^= mix(tail.iter().enumerate().fold(0u32, |k, (i, b)| k | (*b as u32) << (8 * i)));
+    }
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+/// The hash Shodan indexes favicons by: MurmurHash3 of the MIME-style
+/// base64 encoding (76-character lines, trailing newline), as signed.
+pub fn favicon_hash(icon: &[u8]) -> i32 {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(icon);
+    let mut wrapped = String::with_capacity(encoded.len() + encoded.len() / 76 + 1);
+    for line in encoded.as_bytes().chunks(76) {
+        wrapped.push_str(std::str::from_utf8(line).unwrap_or_default());
+        wrapped.push('\n');
+    }
+    murmur3_32(wrapped.as_bytes(), 0) as i32
+}
+
+/// Technologies revealed by response headers, keyed by lowercase name.
+pub fn header_technologies(headers: &HashMap<String, String>) -> Vec<Technology> {
+    let mut found: Vec<Technology> = Vec::new();
+    for (header, needle, name, category) in HEADER_SIGNATURES {
+        let Some(value) = headers.get(*header) else {
+            continue;
+        };
+        if needle.is_some_and(|n| !value.to_lowercase().contains(n)) || found.iter().any(|t| t.name == *name) {
+            continue;
+        }
+        found.push(Technology { name: name.to_string(), category: category.to_string(), evidence: format!("{}: {}", header, value) });
+    }
+    found
+}
+
+/// The first body line containing one of `markers`, if any does.
+pub fn marker_line(body: &str, markers: &[&str]) -> Option<String> {
+    body.lines().find_map(|line| {
+        let lower = line.to_lowercase();
+        markers
+            .iter()
+            .any(|m| lower.contains(m))
+            .then(|| line.trim().chars().take(MAX_EVIDENCE_CHARS).collect())
+    })
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Fetched, String> {
+    let mut response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for (name, value) in response.headers() {
+        if let Ok(v) = value.to_str() {
+            headers.entry(name.as_str().to_string()).and_modify(|e| *e = format!("{}, {}", e, v)).or_insert_with(|| v.to_string());
+        }
+    }
+    let body = crate::portscan::read_body(&mut response, MAX_BODY).await;
+    Ok(Fetched { status, headers, body })
+}
+
+async fn import_exposure(url: String, page: &Fetched, exposure: &Exposure, evidence: String) -> Result<(), String> {
+    let finding = Finding {
+        id: None,
+        rule_id: exposure.rule_id.to_string(),
+        name: exposure.title.to_string(),
+        description: exposure.description.to_string(),
+        severity: exposure.severity,
+        match_content: evidence,
+        notes: None,
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: Some(exposure.references.iter().map(|r| r.to_string()).collect()),
+        remediation: Some(exposure.remediation.to_string()),
+    };
+    let asset_id = crate::assets::add_asset(CreateAssetRequest {
+        url,
+        source: "Fingerprint".to_string(),
+        method: Some("GET".to_string()),
+        status_code: Some(page.status as i64),
+        req_headers: HashMap::new(),
+        res_headers: page.headers.clone(),
+        req_body: None,
+        res_body: Some(String::from_utf8_lossy(&page.body).into_owned()),
+        findings: Vec::new(),
+    })
+    .await?;
+    // A repeat of an exposure already on the asset counts as an occurrence
+    let pool = get_db();
+    crate::assets::store_finding(&pool, asset_id, &finding).await?;
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    Ok(())
+}
+
+async fn store(host: &str, fingerprint: &HostFingerprint) -> Result<(), String> {
+    let pool = get_db();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM host_technologies WHERE host = ?")
+        .bind(host)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for tech in &fingerprint.technologies {
+        sqlx::query("INSERT OR IGNORE INTO host_technologies (host, name, category, evidence) VALUES (?, ?, ?, ?)")
+            .bind(host)
+            .bind(&tech.name)
+            .bind(&tech.category)
+            .bind(&tech.evidence)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    sqlx::query("UPDATE host_probes SET favicon_hash = ? WHERE host = ?")
+        .bind(fingerprint.favicon_hash)
+        .bind(host)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Probes the host, then matches its headers, favicon and well-known paths.
+async fn fingerprint_host(client: &reqwest::Client, host: String) -> HostFingerprint {
+    let mut result = HostFingerprint { host, base_url: None, favicon_hash: None, technologies: Vec::new(), exposures: Vec::new(), error: None };
+    let outcome = crate::probe::probe_host(client, &result.host).await;
+    if let Err(e) = crate::probe::store(&result.host, &outcome).await {
+        eprintln!("Failed to store probe of {}: {}", result.host, e);
+    }
+    let Some(base) = outcome.url else {
+        result.error = outcome.error.or_else(|| Some("No HTTP service".to_string()));
+        return result;
+    };
+    let base = base.trim_end_matches('/').to_string();
+
+    match fetch(client, &format!("{}/", base)).await {
+        Ok(root) => result.technologies = header_technologies(&root.headers),
+        Err(e) => result.error = Some(e),
+    }
+
+    if let Ok(icon) = fetch(client, &format!("{}/favicon.ico", base)).await {
+        let is_html = icon.headers.get("content-type").is_some_and(|t| t.contains("html"));
+        if icon.status == 200 && !icon.body.is_empty() && !is_html {
+            let hash = favicon_hash(&icon.body);
+            result.favicon_hash = Some(hash);
+            if let Some((_, name, category)) = FAVICON_HASHES.iter().find(|(h, _, _)| *h == hash) {
+                if !result.technologies.iter().any(|t| t.name == *name) {
+                    result.technologies.push(Technology { name: name.to_string(), category: category.to_string(), evidence: format!("favicon hash {}", hash) });
+                }
+            }
+        }
+    }
+
+    for known in WELL_KNOWN {
+        let url = format!("{}{}", base, known.path);
+        let Ok(page) = fetch(client, &url).await else {
+            continue;
+        };
+        if page.status != 200 {
+            continue;
+        }
+        let Some(evidence) = marker_line(&String::from_utf8_lossy(&page.body), known.markers) else {
+            continue;
+        };
+        if !result.technologies.iter().any(|t| t.name == known.name) {
+            result.technologies.push(Technology { name: known.name.to_string(), category: known.category.to_string(), evidence: url.clone() });
+        }
+        if let Some(exposure) = &known.exposure {
+            match import_exposure(url.clone(), &page, exposure, evidence).await {
+                Ok(()) => result.exposures.push(url),
+                Err(e) => eprintln!("Failed to record exposure {}: {}", url, e),
+            }
+        }
+    }
+    result
+}
+
+/// Fingerprints hosts in the background and returns the job id: server,
+/// framework, gateway and CDN from headers, the favicon hash, and
+/// documentation or debug endpoints at well-known paths. Dangerous
+/// exposures are imported as assets with a finding. Each host's result
+/// streams through `fingerprint-result` as `(job_id, fingerprint)`,
+/// `fingerprint-progress` reports counts and a `FingerprintSummary`
+/// arrives with `fingerprint-complete`. Stop it with `cancel_recon`.
+#[tauri::command]
+pub async fn start_fingerprint(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<JobState>>,
+    request: FingerprintRequest,
+) -> Result<String, String> {
+    crate::scan_profiles::authorize(request.scan_profile_id, crate::scan_profiles::ActiveCheck::Discovery).await?;
+    let mut hosts: Vec<String> = Vec::new();
+    for input in request.hosts.iter().filter(|h| !h.trim().is_empty()) {
+        let host = crate::portscan::scan_host(input)?;
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    if hosts.is_empty() {
+        return Err("No hosts to fingerprint".to_string());
+    }
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let client = crate::probe::probe_client(Duration::from_millis(request.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).clamp(100, 60_000)))?;
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "fingerprint-complete",
+        move |app_handle, job_id, cancel| async move {
+            let total = hosts.len();
+            let mut workers = crate::jobs::Workers::new(hosts, concurrency, |host: String| {
+                let client = client.clone();
+                async move { fingerprint_host(&client, host).await }
+            });
+            let (mut checked, mut exposures) = (0, 0);
+            while let Some(joined) = workers.next(&cancel).await {
+                checked += 1;
+                if let Ok(fingerprint) = joined {
+                    if let Err(e) = store(&fingerprint.host, &fingerprint).await {
+                        eprintln!("Failed to store fingerprint of {}: {}", fingerprint.host, e);
+                    }
+                    exposures += fingerprint.exposures.len();
+                    let _ = app_handle.emit("fingerprint-result", (&job_id, fingerprint));
+                }
+                let _ = app_handle.emit("fingerprint-progress", ReconProgress { job_id: job_id.clone(), checked, total, found: exposures });
+            }
+            FingerprintSummary { job_id, total, checked, exposures, cancelled: false }
+        },
+        |_, summary, cancelled| FingerprintSummary { cancelled, ..summary },
+    ))
+}
+
+/// Detected technologies, optionally for one host.
+#[tauri::command]
+pub async fn list_host_technologies(host: Option<String>) -> Result<Vec<HostTechnology>, String> {
+    sqlx::query_as::<_, HostTechnology>(
+        "SELECT id, host, name, category, evidence, detected_at FROM host_technologies
+         WHERE ? IS NULL OR host = ? ORDER BY host, category, name",
+    )
+    .bind(&host)
+    .bind(&host)
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprints() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"hello", 0), 613153351);
+        assert_eq!(murmur3_32(b"The quick brown fox jumps over the lazy dog", 0), 0x2e4f_f723);
+        // Hash of "aGVsbG8=\n", the MIME base64 of "hello"
+        assert_eq!(favicon_hash(b"hello"), murmur3_32(b"aGVsbG8=\n", 0) as i32);
+
+        let headers: HashMap<String, String> = [
+            ("server", "nginx/1.25"),
+            ("x-powered-by", "Express"),
+            ("x-kong-upstream-latency", "3"),
+            ("x-kong-proxy-latency", "1"),
+            ("set-cookie", "session=1"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let names: Vec<String> = header_technologies(&headers).into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["nginx", "Express", "Kong"]);
+
+        assert_eq!(marker_line("{\n  \"openapi\": \"3.0.0\",\n}", &["\"openapi\""]).as_deref(), Some("\"openapi\": \"3.0.0\","));
+        assert_eq!(marker_line("<html>Not found</html>", &["[core]"]), None);
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use sqlx::FromRow;
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct Folder {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    /// Assets filed directly in this folder (not in subfolders)
+    pub asset_count: i64,
+}
+
+/// Recursive CTE yielding `id` for folder `?1` and every folder below it.
+pub const FOLDER_SUBTREE: &str = "WITH RECURSIVE subtree(id) AS ( \
+        SELECT id FROM folders WHERE id = ?1 \
+        UNION SELECT f.id FROM folders f JOIN subtree s ON f.parent_id = s.id \
+    )";
+
+pub(crate) async fn ensure_folder(id: i64) -> Result<(), String> {
+    let pool = get_db();
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM folders WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    exists.map(|_| ()).ok_or_else(|| format!("Folder {} not found", id))
+}
+
+#[tauri::command]
+pub async fn get_folders() -> Result<Vec<Folder>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, Folder>(
+        "SELECT f.id, f.name, f.parent_id, COUNT(a.id) as asset_count \
+         FROM folders f \
+         LEFT JOIN assets a ON a.folder_id = f.id AND a.deleted_at IS NULL \
+         GROUP BY f.id \
+         ORDER BY f.name"
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_folder(name: String, parent_id: Option<i64>) -> Result<i64, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name must not be empty".to_string());
+    }
+    if let Some(parent) = parent_id {
+        ensure_folder(parent).await?;
+    }
+    let pool = get_db();
+    let res = sqlx::query("INSERT INTO folders (name, parent_id) VALUES (?, ?)")
+        .bind(name)
+        .bind(parent_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn rename_folder(id: i64, name: String) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name must not be empty".to_string());
+    }
+    ensure_folder(id).await?;
+    let pool = get_db();
+    sqlx::query("UPDATE folders SET name = ? WHERE id = ?")
+        .bind(name)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Re-parents a folder; `parent_id` of `None` moves it to the top level.
+/// Moving a folder into itself or one of its own subfolders is rejected.
+#[tauri::command]
+pub async fn move_folder(id: i64, parent_id: Option<i64>) -> Result<(), String> {
+    ensure_folder(id).await?;
+    let pool = get_db();
+    if let Some(parent) = parent_id {
+        ensure_folder(parent).await?;
+        let (cyclic,): (bool,) = sqlx::query_as(&format!("{} SELECT EXISTS (SELECT 1 FROM subtree WHERE id = ?2)", FOLDER_SUBTREE))
+            .bind(id)
+            .bind(parent)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if cyclic {
+            return Err("A folder can't be moved into itself or one of its subfolders".to_string());
+        }
+    }
+    sqlx::query("UPDATE folders SET parent_id = ? WHERE id = ?")
+        .bind(parent_id)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Deletes a folder. Its subfolders and assets move up to its parent
+/// rather than being deleted with it.
+#[tauri::command]
+pub async fn delete_folder(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    let (parent_id,): (Option<i64>,) = sqlx::query_as("SELECT parent_id FROM folders WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Folder {} not found", id))?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE folders SET parent_id = ? WHERE parent_id = ?")
+        .bind(parent_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE assets SET folder_id = ? WHERE folder_id = ?")
+        .bind(parent_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM folders WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Files assets into a folder, or back to the top level when `folder_id` is `None`.
+#[tauri::command]
+pub async fn move_assets_to_folder(asset_ids: Vec<i64>, folder_id: Option<i64>) -> Result<u64, String> {
+    if let Some(folder) = folder_id {
+        ensure_folder(folder).await?;
+    }
+    let pool = get_db();
+    let ids = serde_json::to_string(&asset_ids).map_err(|e| e.to_string())?;
+    let res = sqlx::query("UPDATE assets SET folder_id = ? WHERE id IN (SELECT value FROM json_each(?))")
+        .bind(folder_id)
+        .bind(ids)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected())
+}
@@ -0,0 +1,116 @@
+use crate::bola::body_similarity;
+use serde::Serialize;
+
+/// Unmodified requests sent before fuzzing to learn the normal response.
+pub const BASELINE_SAMPLES: usize = 3;
+
+/// Length deviations are only anomalous past this many standard deviations,
+/// and never below `MIN_LENGTH_DELTA` bytes or `MIN_LENGTH_RATIO` of the mean.
+const LENGTH_Z: f64 = 3.0;
+const MIN_LENGTH_DELTA: f64 = 32.0;
+const MIN_LENGTH_RATIO: f64 = 0.1;
+/// How far below the baseline's own self-similarity a response must fall.
+const SIMILARITY_MARGIN: f64 = 0.15;
+
+/// The normal response to the unmodified request, with its natural jitter.
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    statuses: Vec<u16>,
+    bodies: Vec<String>,
+    mean_length: f64,
+    length_stddev: f64,
+    /// Lowest similarity between two baseline samples; 1 for static pages
+    self_similarity: f64,
+}
+
+/// How a fuzz response differs from the baseline.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ResponseDiff {
+    pub status_changed: bool,
+    /// Bytes longer (or shorter, if negative) than the baseline mean
+    pub length_delta: i64,
+    /// 0-1, similarity to the closest baseline sample
+    pub similarity: f64,
+    /// Outside the baseline's normal variation even without a signature match
+    pub interesting: bool,
+    pub reasons: Vec<String>,
+}
+
+impl Baseline {
+    /// Builds a baseline from `(status, body)` samples; failed requests
+    /// (status 0) are ignored. Returns `None` when nothing usable is left.
+    pub fn from_samples(samples: Vec<(u16, String)>) -> Option<Self> {
+        let samples: Vec<(u16, String)> = samples.into_iter().filter(|(status, _)| *status != 0).collect();
+        if samples.is_empty() {
+            return None;
+        }
+        let lengths: Vec<f64> = samples.iter().map(|(_, body)| body.len() as f64).collect();
+        let mean_length = lengths.iter().sum::<f64>() / lengths.len() as f64;
+        let variance = lengths.iter().map(|l| (l - mean_length).powi(2)).sum::<f64>() / lengths.len() as f64;
+
+        let mut self_similarity: f64 = 1.0;
+        for (i, (_, a)) in samples.iter().enumerate() {
+            for (_, b) in &samples[i + 1..] {
+                self_similarity = self_similarity.min(body_similarity(a, b));
+            }
+        }
+        let (mut statuses, bodies): (Vec<u16>, Vec<String>) = samples.into_iter().unzip();
+        statuses.sort_unstable();
+        statuses.dedup();
+        Some(Self { statuses, bodies, mean_length, length_stddev: variance.sqrt(), self_similarity })
+    }
+
+    pub fn diff(&self, status: u16, body: &str) -> ResponseDiff {
+        let similarity = self.bodies.iter().map(|b| body_similarity(b, body)).fold(0.0, f64::max);
+        let length_delta = body.len() as f64 - self.mean_length;
+        let status_changed = status != 0 && !self.statuses.contains(&status);
+
+        let length_threshold = (LENGTH_Z * self.length_stddev).max(MIN_LENGTH_DELTA).max(MIN_LENGTH_RATIO * self.mean_length);
+        let mut reasons = Vec::new();
+        if status_changed {
+            reasons.push(format!("status {} differs from baseline {:?}", status, self.statuses));
+        }
+        if status != 0 && length_delta.abs() > length_threshold {
+            reasons.push(format!("length changed by {:+} bytes", length_delta.round() as i64));
+        }
+        if status != 0 && similarity < self.self_similarity - SIMILARITY_MARGIN {
+            reasons.push(format!("only {:.0}% similar to baseline", similarity * 100.0));
+        }
+        ResponseDiff {
+            status_changed,
+            length_delta: length_delta.round() as i64,
+            similarity,
+            interesting: !reasons.is_empty(),
+            reasons,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_baseline() {
+        let body = r#"{"items": [{"id": 1, "name": "widget"}], "total": 1}"#.to_string();
+        let baseline = Baseline::from_samples(vec![(200, body.clone()), (200, body.clone()), (0, String::new())]).unwrap();
+        assert!(!baseline.diff(200, &body).interesting);
+
+        let error = baseline.diff(500, "Internal Server Error");
+        assert!(error.interesting && error.status_changed);
+        assert_eq!(error.reasons.len(), 2);
+
+        let blind = baseline.diff(200, r#"{"items": [], "total": 0}"#);
+        assert!(blind.interesting && !blind.status_changed);
+        assert!(blind.similarity < 0.8);
+    }
+
+    #[test]
+    fn test_dynamic_baseline() {
+        let page = |n: u32| format!("<html><body>Welcome back. Request {} served at {}</body></html>", n, 1000 + n);
+        let baseline = Baseline::from_samples(vec![(200, page(1)), (200, page(2)), (200, page(3))]).unwrap();
+        assert!(!baseline.diff(200, &page(4)).interesting);
+        assert!(baseline.diff(200, &"SQL syntax error near '".repeat(10)).interesting);
+        assert!(Baseline::from_samples(vec![(0, String::new())]).is_none());
+    }
+}
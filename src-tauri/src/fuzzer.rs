@@ -1,7 +1,17 @@
 use serde::{Deserialize, Serialize};
 use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::fuzz_baseline::{Baseline, ResponseDiff, BASELINE_SAMPLES};
+use crate::pacing::{Pacer, Pacing};
+use crate::JobState;
+use sqlx::FromRow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
+
+/// Upper bound on payloads per list, so one huge file can't stall a run.
+const MAX_WORDLIST_PAYLOADS: usize = 100_000;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FuzzTask {
@@ -9,6 +19,31 @@ pub struct FuzzTask {
     pub method: String,
     pub headers: std::collections::HashMap<String, String>,
     pub body: Option<String>,
+    #[serde(default)]
+    pub pacing: Pacing,
+}
+
+/// Surrounds a value in a task's URL, header values or body to mark it for
+/// fuzzing, e.g. `?id=§42§`.
+pub const MARKER: char = '§';
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InsertionLocation {
+    Url,
+    Header { name: String },
+    Body,
+}
+
+/// One marked position. Indexes run through the URL, then headers by
+/// name, then the body.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InsertionPoint {
+    pub index: usize,
+    #[serde(flatten)]
+    pub location: InsertionLocation,
+    /// The value between the markers, sent at every other position
+    pub original: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -17,6 +52,117 @@ pub struct FuzzResult {
     pub status: u16,
     pub time_ms: u64,
     pub finding: Option<Finding>,
+    /// Where the payload went; unset when the task had no markers
+    pub position: Option<InsertionPoint>,
+    /// Comparison with the unmodified response; unset without a baseline
+    pub diff: Option<ResponseDiff>,
+}
+
+/// Sent with `fuzz-complete` when a run ends.
+#[derive(Debug, Serialize, Clone)]
+pub struct FuzzSummary {
+    pub job_id: String,
+    pub total: usize,
+    pub sent: usize,
+    /// Payloads never sent because the run was cancelled or failed
+    pub skipped: usize,
+    pub findings: usize,
+    /// Results without a finding that differed from the baseline
+    pub interesting: usize,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+impl FuzzSummary {
+    /// Summary of a run over `total` payloads; `counts` is the number sent,
+    /// with findings and with interesting differences.
+    pub fn new(job_id: String, total: usize, counts: Result<(usize, usize, usize), String>, cancelled: bool) -> Self {
+        let ((sent, findings, interesting), error) = match counts {
+            Ok(counts) => (counts, None),
+            Err(e) => ((0, 0, 0), Some(e)),
+        };
+        FuzzSummary { job_id, total, sent, skipped: total - sent, findings, interesting, cancelled, error }
+    }
+}
+
+fn marked_values(text: &str) -> Result<Vec<&str>, String> {
+    let parts: Vec<&str> = text.split(MARKER).collect();
+    if parts.len().is_multiple_of(2) {
+        return Err(format!("Unbalanced {} marker in '{}'", MARKER, text));
+    }
+    Ok(parts.into_iter().skip(1).step_by(2).collect())
+}
+
+/// Percent-encodes everything but unreserved characters, keeping the `%XX`
+/// escapes a payload already carries (e.g. `..%2f`) as they are.
+fn encode_url_payload(payload: &str) -> String {
+    let bytes = payload.as_bytes();
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| {
+            let escaped = b == b'%' && bytes.get(i + 1..i + 3).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+            if escaped || b.is_ascii_alphanumeric() || b"-._~".contains(&b) { (b as char).to_string() } else { format!("%{:02X}", b) }
+        })
+        .collect()
+}
+
+/// Replaces each marked value numbered in `payloads` with its payload and
+/// strips the markers from the rest. `first` is the number of the text's
+/// first marker.
+fn fill_markers(text: &str, first: usize, payloads: &[(usize, String)]) -> String {
+    text.split(MARKER)
+        .enumerate()
+        .map(|(i, part)| match payloads.iter().find(|(index, _)| i % 2 == 1 && *index == first + i / 2) {
+            Some((_, payload)) => payload.as_str(),
+            None => part,
+        })
+        .collect()
+}
+
+fn sorted_headers(task: &FuzzTask) -> Vec<(&String, &String)> {
+    let mut headers: Vec<(&String, &String)> = task.headers.iter().collect();
+    headers.sort();
+    headers
+}
+
+pub fn insertion_points(task: &FuzzTask) -> Result<Vec<InsertionPoint>, String> {
+    let mut fields: Vec<(InsertionLocation, &str)> = vec![(InsertionLocation::Url, task.url.as_str())];
+    fields.extend(sorted_headers(task).into_iter().map(|(name, value)| (InsertionLocation::Header { name: name.clone() }, value.as_str())));
+    if let Some(body) = &task.body {
+        fields.push((InsertionLocation::Body, body.as_str()));
+    }
+    let mut points = Vec::new();
+    for (location, text) in fields {
+        for original in marked_values(text)? {
+            points.push(InsertionPoint { index: points.len(), location: location.clone(), original: original.to_string() });
+        }
+    }
+    Ok(points)
+}
+
+/// The task with position `target` set to `payload` (URL-encoded in the
+/// URL) and every other position restored to its original value.
+pub fn render_task(task: &FuzzTask, target: usize, payload: &str) -> FuzzTask {
+    render_positions(task, &[(target, payload)])
+}
+
+/// The task with each listed position set to its payload and the rest
+/// restored, like `render_task` for several positions at once.
+pub fn render_positions(task: &FuzzTask, payloads: &[(usize, &str)]) -> FuzzTask {
+    let count = |text: &str| text.matches(MARKER).count() / 2;
+    let encoded: Vec<(usize, String)> = payloads.iter().map(|(i, p)| (*i, encode_url_payload(p))).collect();
+    let raw: Vec<(usize, String)> = payloads.iter().map(|(i, p)| (*i, p.to_string())).collect();
+    let mut next = 0;
+    let mut fill = |text: &str, payloads: &[(usize, String)]| {
+        let filled = fill_markers(text, next, payloads);
+        next += count(text);
+        filled
+    };
+    let url = fill(&task.url, &encoded);
+    let headers = sorted_headers(task).into_iter().map(|(name, value)| (name.clone(), fill(value, &raw))).collect();
+    let body = task.body.as_deref().map(|body| fill(body, &raw));
+    FuzzTask { url, method: task.method.clone(), headers, body, pacing: task.pacing.clone() }
 }
 
 pub const SQLI_PAYLOADS: &[&str] = &[
@@ -32,105 +178,592 @@ pub const XSS_PAYLOADS: &[&str] = &[
     "javascript:alert(1)",
 ];
 
+pub const TRAVERSAL_PAYLOADS: &[&str] = &[
+    "../../../../../../etc/passwd",
+    "..%2f..%2f..%2f..%2f..%2f..%2fetc%2fpasswd",
+    "....//....//....//....//....//etc/passwd",
+    "/etc/passwd",
+    "..\\..\\..\\..\\..\\windows\\win.ini",
+];
+
+/// Each evaluates to `SSTI_MARKER` in a different template language.
+pub const SSTI_PAYLOADS: &[&str] = &[
+    "{{1337*1337}}",
+    "${1337*1337}",
+    "<%= 1337*1337 %>",
+    "#{1337*1337}",
+    "${{1337*1337}}",
+    "{{= 1337*1337}}",
+];
+
+const SSTI_MARKER: &str = "1787569";
+
+/// Echo probes print `CMDI_MARKER` only when the shell evaluates the
+/// arithmetic; sleep probes are judged by response time.
+pub const CMDI_PAYLOADS: &[&str] = &[
+    ";echo apisec$((1000+337))",
+    "|echo apisec$((1000+337))",
+    "`echo apisec$((1000+337))`",
+    "$(echo apisec$((1000+337)))",
+    ";sleep 3",
+    "|sleep 3",
+    "$(sleep 3)",
+    "& ping -n 4 127.0.0.1 &",
+    ";curl -s {oob_url}",
+    "$(wget -qO- {oob_url})",
+    "|nslookup {oob_host}",
+];
+
+const CMDI_MARKER: &str = "apisec1337";
+
+/// How much slower than the fastest other response a sleep probe must be.
+const CMDI_DELAY_MS: u64 = 2800;
+
+pub const CRLF_PAYLOADS: &[&str] = &[
+    "\r\nX-Apisec-Injected: crlf",
+    "%0d%0aX-Apisec-Injected: crlf",
+    "\r\nSet-Cookie: apisec_injected=crlf",
+];
+
+const CRLF_HEADER: &str = "x-apisec-injected";
+
+pub const XXE_PAYLOADS: &[&str] = &[
+    "<?xml version=\"1.0\"?><!DOCTYPE r [<!ENTITY x SYSTEM \"file:///etc/passwd\">]><r>&x;</r>",
+    "<?xml version=\"1.0\"?><!DOCTYPE r [<!ENTITY x SYSTEM \"file:///c:/windows/win.ini\">]><r>&x;</r>",
+    "<?xml version=\"1.0\"?><!DOCTYPE r [<!ENTITY % x SYSTEM \"file:///etc/passwd\"> %x;]><r/>",
+    "<?xml version=\"1.0\"?><!DOCTYPE r [<!ENTITY % x SYSTEM \"{oob_url}\"> %x;]><r/>",
+];
+
+/// Blind SSRF is only confirmed by a callback to the OOB listener.
+pub const SSRF_PAYLOADS: &[&str] = &[
+    "{oob_url}",
+    "http://{oob_host}/",
+    "//{oob_host}/",
+];
+
+/// Domain the open redirect payloads send visitors to.
+const REDIRECT_HOST: &str = "apisec-redirect.example";
+
+/// External and protocol-relative URLs plus encodings that slip past
+/// naive same-origin checks.
+pub const REDIRECT_PAYLOADS: &[&str] = &[
+    "https://apisec-redirect.example/",
+    "//apisec-redirect.example/",
+    "///apisec-redirect.example/",
+    "/\\apisec-redirect.example/",
+    "\\\\apisec-redirect.example/",
+    "https:/\\apisec-redirect.example/",
+    "%2f%2fapisec-redirect.example/",
+    "https://localhost@apisec-redirect.example/",
+    "https://apisec-redirect.example%23.localhost/",
+    "https://apisec-redirect.example\\.localhost/",
+];
+
+/// Parameter names (lowercased, without `_` or `-`) that usually hold a
+/// redirect target.
+const REDIRECT_PARAMS: &[&str] = &[
+    "url", "uri", "next", "return", "returnto", "returnurl", "returnpath", "redirect", "redirecturi", "redirecturl",
+    "redirectto", "redir", "dest", "destination", "continue", "goto", "target", "callback", "callbackurl", "forward",
+    "to", "out", "back", "backurl", "successurl", "cancelurl", "checkouturl",
+];
+
+fn is_redirect_param(name: &str) -> bool {
+    let name: String = name.chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_lowercase();
+    REDIRECT_PARAMS.contains(&name.as_str())
+}
+
+/// Marks the values of redirect-like `name=value` pairs in a query string
+/// or form body.
+fn mark_pairs(text: &str) -> String {
+    text.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if is_redirect_param(name) => format!("{}={}{}{}", name, MARKER, value, MARKER),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The task with redirect-like query parameters, form fields and JSON
+/// string fields marked for fuzzing, so `open_redirect` runs target them
+/// without manual markers.
+pub fn mark_redirect_params(task: &FuzzTask) -> FuzzTask {
+    let url = match task.url.split_once('?') {
+        Some((path, query)) => format!("{}?{}", path, mark_pairs(query)),
+        None => task.url.clone(),
+    };
+    let body = task.body.as_deref().map(|body| {
+        if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+            let re = regex::Regex::new(r#""([A-Za-z0-9_\-]+)"(\s*:\s*)"([^"\\]*)""#).expect("valid regex");
+            re.replace_all(body, |caps: &regex::Captures| {
+                if is_redirect_param(&caps[1]) {
+                    format!("\"{}\"{}\"{}{}{}\"", &caps[1], &caps[2], MARKER, &caps[3], MARKER)
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .into_owned()
+        } else if body.contains('=') && !body.contains(char::is_whitespace) {
+            mark_pairs(body)
+        } else {
+            body.to_string()
+        }
+    });
+    FuzzTask { url, body, ..task.clone() }
+}
+
+/// Whether a redirect target resolves to `REDIRECT_HOST` the way a browser
+/// would read it.
+fn redirects_offsite(target: &str) -> bool {
+    url::Url::parse("https://target.invalid/")
+        .and_then(|base| base.join(target.trim().trim_matches(|c| c == '\'' || c == '"')))
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase))
+        .is_some_and(|host| host == REDIRECT_HOST || host.ends_with(&format!(".{}", REDIRECT_HOST)))
+}
+
+/// The URL of a `Refresh: 0; url=...` header.
+fn refresh_target(refresh: &str) -> Option<&str> {
+    refresh.to_ascii_lowercase().find("url=").map(|i| &refresh[i + 4..])
+}
+
+/// What came back for one payload.
+pub struct ProbeResponse<'a> {
+    pub status: u16,
+    /// Lowercased names
+    pub headers: &'a std::collections::HashMap<String, String>,
+    pub body: &'a str,
+    pub time_ms: u64,
+    /// Fastest response so far to a payload that doesn't sleep
+    pub baseline_ms: Option<u64>,
+}
+
+fn is_sleep_probe(payload: &str) -> bool {
+    payload.contains("sleep ") || payload.contains("ping -n")
+}
+
+static PASSWD_ROOT: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r"root:[^:\n]*:0:0:").unwrap());
+
+/// Contents of /etc/passwd or win.ini in a response.
+fn leaks_system_file(body: &str) -> bool {
+    PASSWD_ROOT.is_match(body)
+        || body.contains("; for 16-bit app support")
+        || (body.contains("[fonts]") && body.contains("[extensions]"))
+}
+
+/// A stored payload list, without its payloads.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WordlistSummary {
+    pub id: i64,
+    pub name: String,
+    /// Free-form, e.g. `sql_injection`, `xss` or `traversal`
+    pub category: String,
+    pub payload_count: i64,
+    /// File the list was imported from
+    pub source: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Wordlist {
+    pub id: i64,
+    pub name: String,
+    pub category: String,
+    #[sqlx(json)]
+    pub payloads: Vec<String>,
+    pub source: Option<String>,
+    pub created_at: String,
+}
+
+/// Parses a wordlist file in the SecLists layout: one payload per line.
+/// Blank lines and `# ` comment lines are skipped, duplicates dropped, and
+/// payloads otherwise kept verbatim (leading spaces can matter).
+pub fn parse_wordlist(content: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .filter(|line| !line.trim().is_empty() && *line != "#" && !line.starts_with("# "))
+        .filter(|line| seen.insert(*line))
+        .take(MAX_WORDLIST_PAYLOADS)
+        .map(str::to_string)
+        .collect()
+}
+
+fn builtin_payloads(attack_type: &str) -> Vec<String> {
+    let payloads: &[&str] = match attack_type {
+        "sql_injection" => SQLI_PAYLOADS,
+        "xss" => XSS_PAYLOADS,
+        "path_traversal" => TRAVERSAL_PAYLOADS,
+        "ssti" => SSTI_PAYLOADS,
+        "command_injection" => CMDI_PAYLOADS,
+        "crlf" => CRLF_PAYLOADS,
+        "xxe" => XXE_PAYLOADS,
+        "ssrf" => SSRF_PAYLOADS,
+        "open_redirect" => REDIRECT_PAYLOADS,
+        "header_injection" => crate::header_attacks::HEADER_PAYLOADS,
+        "graphql" => crate::graphql::FIELD_GUESSES,
+        "content_type" => &[],
+        _ => &["test"],
+    };
+    payloads.iter().map(|p| p.to_string()).collect()
+}
+
+pub(crate) async fn insert_wordlist(name: &str, category: &str, payloads: &[String], source: Option<&str>) -> Result<i64, String> {
+    if name.trim().is_empty() {
+        return Err("Wordlist name must not be empty".to_string());
+    }
+    if category.trim().is_empty() {
+        return Err("Wordlist category must not be empty".to_string());
+    }
+    if payloads.is_empty() {
+        return Err("Wordlist has no payloads".to_string());
+    }
+    if payloads.len() > MAX_WORDLIST_PAYLOADS {
+        return Err(format!("Wordlists are limited to {} payloads", MAX_WORDLIST_PAYLOADS));
+    }
+    let pool = get_db();
+    let res = sqlx::query("INSERT INTO wordlists (name, category, payloads, source) VALUES (?, ?, ?, ?)")
+        .bind(name.trim())
+        .bind(category.trim().to_lowercase())
+        .bind(sqlx::types::Json(payloads))
+        .bind(source)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn create_wordlist(name: String, category: String, payloads: Vec<String>) -> Result<i64, String> {
+    let payloads: Vec<String> = payloads.into_iter().filter(|p| !p.is_empty()).collect();
+    insert_wordlist(&name, &category, &payloads, None).await
+}
+
+/// Imports a payload file such as one from SecLists. The name defaults to
+/// the file name. Files that aren't valid UTF-8 are read lossily.
+#[tauri::command]
+pub async fn import_wordlist(path: String, name: Option<String>, category: String) -> Result<i64, String> {
+    let bytes = tokio::fs::read(&path).await.map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let payloads = parse_wordlist(&String::from_utf8_lossy(&bytes));
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+        std::path::Path::new(&path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| path.clone())
+    });
+    insert_wordlist(&name, &category, &payloads, Some(&path)).await
+}
+
+#[tauri::command]
+pub async fn list_wordlists(category: Option<String>) -> Result<Vec<WordlistSummary>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, WordlistSummary>(
+        "SELECT id, name, category, json_array_length(payloads) AS payload_count, source, created_at FROM wordlists \
+         WHERE ?1 IS NULL OR category = lower(?1) ORDER BY category, name",
+    )
+    .bind(category)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_wordlist(id: i64) -> Result<Wordlist, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, Wordlist>("SELECT id, name, category, payloads, source, created_at FROM wordlists WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Wordlist {} not found", id))
+}
+
+#[tauri::command]
+pub async fn delete_wordlist(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM wordlists WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn active_finding(rule_id: &str, name: &str, description: String, payload: &str, notes: String) -> Finding {
+    Finding {
+        id: None,
+        rule_id: rule_id.to_string(),
+        name: name.to_string(),
+        description,
+        severity: FindingSeverity::High,
+        match_content: payload.to_string(),
+        notes: Some(notes),
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+/// Judges one response for the given attack type.
+pub fn detect(attack_type: &str, payload: &str, res: &ProbeResponse) -> Option<Finding> {
+    let status = res.status;
+    match attack_type {
+        "sql_injection" if res.body.contains("SQL syntax") || res.body.contains("mysql_fetch") || res.body.contains("sqlite3") => Some(active_finding(
+            "ACTIVE-SQLI",
+            "Active SQL Injection Confirmed",
+            format!("Target returned a database error when injected with payload: {}", payload),
+            payload,
+            format!("Error found in response body. Status: {}", status),
+        )),
+        "xss" if res.body.contains(payload) => Some(active_finding(
+            "ACTIVE-XSS",
+            "Reflected XSS Confirmed",
+            format!("Active payload was reflected in the response body: {}", payload),
+            payload,
+            "Payload was echoed in response without escaping.".to_string(),
+        )),
+        "path_traversal" if leaks_system_file(res.body) => Some(active_finding(
+            "ACTIVE-PATH-TRAVERSAL",
+            "Path Traversal Confirmed",
+            format!("The response contained system file contents after injecting: {}", payload),
+            payload,
+            format!("passwd or win.ini markers found in response body. Status: {}", status),
+        )),
+        // The product only shows up if the expression was evaluated
+        "ssti" if res.body.contains(SSTI_MARKER) => Some(active_finding(
+            "ACTIVE-SSTI",
+            "Server-Side Template Injection Confirmed",
+            format!("The template expression {} was evaluated by the server.", payload),
+            payload,
+            format!("Found {} (1337*1337) in response body. Status: {}", SSTI_MARKER, status),
+        )),
+        "command_injection" if res.body.contains(CMDI_MARKER) => Some(active_finding(
+            "ACTIVE-CMDI",
+            "OS Command Injection Confirmed",
+            format!("The shell evaluated the injected command: {}", payload),
+            payload,
+            format!("Found {} in response body. Status: {}", CMDI_MARKER, status),
+        )),
+        "command_injection"
+            if is_sleep_probe(payload) && res.baseline_ms.is_some_and(|b| res.time_ms >= b + CMDI_DELAY_MS) =>
+        {
+            Some(active_finding(
+                "ACTIVE-CMDI",
+                "OS Command Injection (Time-Based)",
+                format!("The response was delayed after injecting the sleep command: {}", payload),
+                payload,
+                format!("Took {} ms against a {} ms baseline. Status: {}", res.time_ms, res.baseline_ms.unwrap_or_default(), status),
+            ))
+        }
+        "crlf" if res.headers.contains_key(CRLF_HEADER) || res.headers.get("set-cookie").is_some_and(|c| c.contains("apisec_injected=crlf")) => {
+            Some(active_finding(
+                "ACTIVE-CRLF",
+                "CRLF Header Injection Confirmed",
+                format!("Injected line breaks created a response header with payload: {}", payload.escape_debug()),
+                payload,
+                format!("Injected header present in the response. Status: {}", status),
+            ))
+        }
+        "xxe" if leaks_system_file(res.body) => Some(active_finding(
+            "ACTIVE-XXE",
+            "XML External Entity Injection Confirmed",
+            "An external entity in the submitted XML was resolved and its file contents returned.".to_string(),
+            payload,
+            format!("passwd or win.ini markers found in response body. Status: {}", status),
+        )),
+        "open_redirect"
+            if ((300..400).contains(&status) && res.headers.get("location").is_some_and(|l| redirects_offsite(l)))
+                || res.headers.get("refresh").and_then(|r| refresh_target(r)).is_some_and(redirects_offsite) =>
+        {
+            Some(active_finding(
+                "ACTIVE-OPEN-REDIRECT",
+                "Open Redirect Confirmed",
+                format!("The endpoint redirected to an attacker-controlled domain after injecting: {}", payload),
+                payload,
+                format!(
+                    "Redirect target: {}. Status: {}",
+                    res.headers.get("location").or_else(|| res.headers.get("refresh")).map_or("", String::as_str),
+                    status
+                ),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Status, lowercased headers, body and elapsed milliseconds of a response.
+pub(crate) type Probe = (u16, std::collections::HashMap<String, String>, String, u64);
+
+/// Sends one rendered request.
+async fn send_probe(client: &reqwest::Client, task: &FuzzTask, attack_type: &str) -> Result<Probe, reqwest::Error> {
+    let start = std::time::Instant::now();
+    let method = reqwest::Method::from_bytes(task.method.as_bytes()).unwrap_or(reqwest::Method::GET);
+    let mut req = client.request(method, &task.url);
+    for (k, v) in &task.headers {
+        req = req.header(k, v);
+    }
+    if attack_type == "xxe" && !task.headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+        req = req.header("Content-Type", "application/xml");
+    }
+    if let Some(body) = &task.body {
+        req = req.body(body.clone());
+    }
+
+    let response = req.send().await?;
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.as_str().to_lowercase(), String::from_utf8_lossy(v.as_bytes()).into_owned()))
+        .collect();
+    let body = response.text().await.unwrap_or_default();
+    Ok((status, headers, body, start.elapsed().as_millis() as u64))
+}
+
+/// `send_probe` spaced out by `pacer`, retrying responses that ask us to
+/// slow down until its retries run out. Deferred `$` builtins get fresh
+/// values. An environment token that expired or got a 401 is renewed, the
+/// latter once.
+pub(crate) async fn send_paced(
+    client: &reqwest::Client,
+    task: &FuzzTask,
+    attack_type: &str,
+    pacer: &mut Pacer,
+    cancel: &AtomicBool,
+) -> Result<Probe, reqwest::Error> {
+    let throttle = |probe: &Result<Probe, reqwest::Error>| probe.as_ref().map_or((0, None), |p| (p.0, p.1.get("retry-after").cloned()));
+    let mut task = crate::variables::fresh_task(task);
+    if let Some(headers) = crate::env_auth::renewed_headers(&task.headers, false).await {
+        task.headers = headers;
+    }
+    let sent: &FuzzTask = &task;
+    let probe = pacer.send(Some(cancel), move || send_probe(client, sent, attack_type), throttle).await?;
+    if probe.0 == 401 {
+        if let Some(headers) = crate::env_auth::renewed_headers(&task.headers, true).await {
+            task.headers = headers;
+            let sent: &FuzzTask = &task;
+            return pacer.send(Some(cancel), move || send_probe(client, sent, attack_type), throttle).await;
+        }
+    }
+    Ok(probe)
+}
+
+/// Fuzzes every insertion point with every payload. Responses are judged
+/// by `attack_type`'s signatures and diffed against a baseline of the
+/// unmodified request.
 pub async fn run_fuzz_test(
     app_handle: tauri::AppHandle,
     task: FuzzTask,
     attack_type: &str,
+    payloads: Vec<String>,
+    job_id: &str,
+    cancel: &AtomicBool,
 ) -> Result<Vec<FuzzResult>, String> {
+    // Redirects are the finding, so they must not be followed
+    let redirect = if attack_type == "open_redirect" { reqwest::redirect::Policy::none() } else { reqwest::redirect::Policy::default() };
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(5))
         .danger_accept_invalid_certs(true)
+        .redirect(redirect)
         .build()
         .map_err(|e| e.to_string())?;
 
-    let payloads = match attack_type {
-        "sql_injection" => SQLI_PAYLOADS,
-        "xss" => XSS_PAYLOADS,
-        _ => &["test"],
-    };
+    // With markers every position is fuzzed in turn; without, the payload
+    // goes into an extra `fuzz` parameter and empty body strings
+    let points = insertion_points(&task)?;
+    let targets: Vec<Option<&InsertionPoint>> = if points.is_empty() { vec![None] } else { points.iter().map(Some).collect() };
 
     let mut results = Vec::new();
-    let total = payloads.len();
-
-    for (i, payload) in payloads.iter().enumerate() {
-        let f_payload = payload.to_string();
-        
-        // Simple parameter injection for URL-encoded params or URL path
-        let target_url = if task.url.contains('?') {
-            format!("{}&fuzz={}", task.url, urlencoding::encode(&f_payload))
-        } else {
-            format!("{}?fuzz={}", task.url, urlencoding::encode(&f_payload))
-        };
-
-        let start = std::time::Instant::now();
-        let method = reqwest::Method::from_bytes(task.method.as_bytes()).unwrap_or(reqwest::Method::GET);
-        
-        let mut req = client.request(method, &target_url);
-        for (k, v) in &task.headers {
-            req = req.header(k, v);
+    let total = payloads.len() * targets.len();
+    // Sampled a few times so dynamic content isn't mistaken for an anomaly
+    let original = render_task(&task, usize::MAX, "");
+    let mut pacer = Pacer::new(&task.pacing);
+    let mut samples = Vec::new();
+    let mut baseline_ms: Option<u64> = None;
+    for _ in 0..BASELINE_SAMPLES {
+        if let Ok((status, _, body, ms)) = send_paced(&client, &original, attack_type, &mut pacer, cancel).await {
+            samples.push((status, body));
+            baseline_ms = Some(baseline_ms.map_or(ms, |b| b.min(ms)));
         }
+    }
+    let baseline = Baseline::from_samples(samples);
+    // Callback payloads are skipped unless the listener can receive them
+    let oob_running = app_handle.state::<Arc<crate::OobState>>().is_running();
+    let oob: Option<crate::oob::OobSettings> = if oob_running { crate::settings::get("oob").await.ok() } else { None };
+    let probes: Vec<(Option<&InsertionPoint>, &String)> =
+        targets.iter().flat_map(|point| payloads.iter().map(move |payload| (*point, payload))).collect();
 
-        if let Some(body) = &task.body {
-             // Basic body fuzzing: if body is JSON, try to inject into first string value
-             let f_body = body.replace("\"\"", &format!("\"{}\"", f_payload));
-             req = req.body(f_body);
+    for (i, (point, payload)) in probes.into_iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let mut f_payload = payload.to_string();
+        if crate::oob::has_placeholder(payload) {
+            let token = crate::oob::new_token();
+            let Some(with_token) = oob.as_ref().and_then(|settings| crate::oob::render_payload(payload, settings, &token)) else {
+                continue;
+            };
+            crate::oob::register(&token, job_id, &original, &with_token, point).await?;
+            f_payload = with_token;
         }
+        let rendered = match point {
+            Some(point) => render_task(&task, point.index, &f_payload),
+            None => {
+                // Simple parameter injection for URL-encoded params or URL path
+                let separator = if task.url.contains('?') { '&' } else { '?' };
+                let body = if attack_type == "xxe" {
+                    // XXE payloads are whole documents
+                    Some(f_payload.clone())
+                } else {
+                    // Basic body fuzzing: if body is JSON, try to inject into first string value
+                    task.body.as_ref().map(|body| body.replace("\"\"", &format!("\"{}\"", f_payload)))
+                };
+                FuzzTask {
+                    url: format!("{}{}fuzz={}", task.url, separator, encode_url_payload(&f_payload)),
+                    method: task.method.clone(),
+                    headers: task.headers.clone(),
+                    body,
+                    pacing: task.pacing.clone(),
+                }
+            }
+        };
 
-        let response = match req.send().await {
+        let (status, headers, body_text, duration) = match send_paced(&client, &rendered, attack_type, &mut pacer, cancel).await {
             Ok(r) => r,
-            Err(e) => {
+            Err(_) => {
                 results.push(FuzzResult {
                     payload: f_payload.clone(),
                     status: 0,
                     time_ms: 0,
                     finding: None,
+                    position: point.cloned(),
+                    diff: None,
                 });
                 continue;
             }
         };
 
-        let status = response.status().as_u16();
-        let duration = start.elapsed().as_millis() as u64;
-        let body_text = response.text().await.unwrap_or_default();
-
-        let mut finding = None;
-
-        // Detection logic
-        if attack_type == "sql_injection" {
-            if body_text.contains("SQL syntax") || body_text.contains("mysql_fetch") || body_text.contains("sqlite3") {
-                 finding = Some(Finding {
-                    id: None,
-                    rule_id: "ACTIVE-SQLI".to_string(),
-                    name: "Active SQL Injection Confirmed".to_string(),
-                    description: format!("Target returned a database error when injected with payload: {}", f_payload),
-                    severity: FindingSeverity::High,
-                    match_content: f_payload.clone(),
-                    notes: Some(format!("Error found in response body. Status: {}", status)),
-                    is_false_positive: Some(false),
-                    severity_override: None,
-                });
-            }
-        } else if attack_type == "xss" {
-             if body_text.contains(&f_payload) {
-                  finding = Some(Finding {
-                    id: None,
-                    rule_id: "ACTIVE-XSS".to_string(),
-                    name: "Reflected XSS Confirmed".to_string(),
-                    description: format!("Active payload was reflected in the response body: {}", f_payload),
-                    severity: FindingSeverity::High,
-                    match_content: f_payload.clone(),
-                    notes: Some("Payload was echoed in response without escaping.".to_string()),
-                    is_false_positive: Some(false),
-                    severity_override: None,
-                });
-             }
+        let mut finding = detect(attack_type, &f_payload, &ProbeResponse {
+            status,
+            headers: &headers,
+            body: &body_text,
+            time_ms: duration,
+            baseline_ms,
+        });
+        if !is_sleep_probe(&f_payload) {
+            baseline_ms = Some(baseline_ms.map_or(duration, |b| b.min(duration)));
         }
 
+        crate::analysis::attach_rule_metadata(finding.as_mut_slice());
+
         let res = FuzzResult {
             payload: f_payload,
             status,
             time_ms: duration,
             finding,
+            position: point.cloned(),
+            diff: baseline.as_ref().map(|b| b.diff(status, &body_text)),
         };
 
         results.push(res.clone());
@@ -142,11 +775,186 @@ pub async fn run_fuzz_test(
     Ok(results)
 }
 
+/// Starts fuzzing in the background with the payloads of `wordlist_id`, or
+/// the built-in ones for `attack_type`, and returns the job id. `attack_type`
+/// also picks how responses are judged; `header_injection` mutates headers
+/// and `graphql` runs its own probes, with the payloads as field guesses,
+/// instead of filling insertion points; `content_type` resends the body
+/// under other Content-Types and ignores the payloads. `open_redirect` marks
+/// redirect-like parameters itself when the task has no markers. Results stream through
+/// `fuzz-progress` events and a `FuzzSummary` arrives with `fuzz-complete`.
+/// The scan profile must allow fuzzing and the wordlist, and caps the pacing.
 #[tauri::command]
 pub async fn run_active_fuzz(
     app_handle: tauri::AppHandle,
-    task: FuzzTask,
-    attack_type: String
-) -> Result<Vec<FuzzResult>, String> {
-    run_fuzz_test(app_handle, task, &attack_type).await
+    state: tauri::State<'_, Arc<JobState>>,
+    mut task: FuzzTask,
+    attack_type: String,
+    wordlist_id: Option<i64>,
+    scan_profile_id: Option<i64>,
+) -> Result<String, String> {
+    let profile = crate::scan_profiles::authorize(scan_profile_id, crate::scan_profiles::ActiveCheck::Fuzz).await?;
+    if let Some(id) = wordlist_id.filter(|id| !profile.wordlist_ids.is_empty() && !profile.wordlist_ids.contains(id)) {
+        return Err(format!("The '{}' scan profile doesn't allow wordlist {}", profile.name, id));
+    }
+    task.pacing = profile.cap_pacing(&task.pacing);
+    crate::env_auth::EnvAuth::inject_active(&mut task.headers).await?;
+    crate::variables::Resolver::active().await?.defer_builtins().resolve_task(&mut task)?;
+    let payloads = match wordlist_id {
+        Some(id) => get_wordlist(id).await?.payloads,
+        None => builtin_payloads(&attack_type),
+    };
+    let task = if attack_type == "open_redirect" && insertion_points(&task)?.is_empty() {
+        let marked = mark_redirect_params(&task);
+        if insertion_points(&marked)?.is_empty() {
+            return Err(format!("No redirect parameters found; mark the target value with {}", MARKER));
+        }
+        marked
+    } else {
+        task
+    };
+    let total = match attack_type.as_str() {
+        "header_injection" => payloads.len(),
+        "graphql" => crate::graphql::PROBE_COUNT + payloads.len(),
+        "content_type" => crate::content_type::task_variants(&task).len(),
+        _ => payloads.len() * insertion_points(&task)?.len().max(1),
+    };
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "fuzz-complete",
+        move |app_handle, job_id, cancel| async move {
+            match attack_type.as_str() {
+                "header_injection" => crate::header_attacks::run_header_test(app_handle, task, payloads, &cancel).await,
+                "graphql" => crate::graphql::run_graphql_test(app_handle, task, payloads, &cancel).await,
+                "content_type" => crate::content_type::run_content_type_test(app_handle, task, &cancel).await,
+                _ => run_fuzz_test(app_handle, task, &attack_type, payloads, &job_id, &cancel).await,
+            }
+        },
+        move |job_id, outcome, cancelled| {
+            let counts = outcome.map(|results| {
+                (
+                    results.len(),
+                    results.iter().filter(|r| r.finding.is_some()).count(),
+                    results.iter().filter(|r| r.finding.is_none() && r.diff.as_ref().is_some_and(|d| d.interesting)).count(),
+                )
+            });
+            FuzzSummary::new(job_id, total, counts, cancelled)
+        },
+    ))
+}
+
+/// Stops a fuzz run before its next request; results so far are kept.
+#[tauri::command]
+pub fn cancel_fuzz(state: tauri::State<'_, Arc<JobState>>, job_id: String) -> Result<(), String> {
+    match state.cancel_flags.get(&job_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!("Fuzz job {} is not running", job_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insertion_points() {
+        let task = FuzzTask {
+            url: "https://api.example.com/users/§42§?sort=§name§".to_string(),
+            method: "POST".to_string(),
+            headers: std::collections::HashMap::from([
+                ("X-Tenant".to_string(), "§acme§".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ]),
+            body: Some(r#"{"q": "§x§"}"#.to_string()),
+            pacing: Pacing::default(),
+        };
+        let points = insertion_points(&task).unwrap();
+        let originals: Vec<&str> = points.iter().map(|p| p.original.as_str()).collect();
+        assert_eq!(originals, vec!["42", "name", "acme", "x"]);
+        assert_eq!(points[2].location, InsertionLocation::Header { name: "X-Tenant".to_string() });
+        assert_eq!(points[3].location, InsertionLocation::Body);
+
+        let first = render_task(&task, 0, "1 OR 1=1");
+        assert_eq!(first.url, "https://api.example.com/users/1%20OR%201%3D1?sort=name");
+        assert_eq!(first.headers["X-Tenant"], "acme");
+        assert_eq!(first.body.as_deref(), Some(r#"{"q": "x"}"#));
+        let header = render_task(&task, 2, "' OR 1=1");
+        assert_eq!(header.headers["X-Tenant"], "' OR 1=1");
+        assert_eq!(header.url, "https://api.example.com/users/42?sort=name");
+        assert_eq!(render_task(&task, 3, "<b>").body.as_deref(), Some(r#"{"q": "<b>"}"#));
+
+        let unbalanced = FuzzTask { url: "https://api.example.com/?id=§1".to_string(), ..task };
+        assert!(insertion_points(&unbalanced).is_err());
+    }
+
+    #[test]
+    fn test_detect() {
+        let headers = std::collections::HashMap::new();
+        let probe = |body: &'static str, time_ms: u64| ProbeResponse { status: 200, headers: &headers, body, time_ms, baseline_ms: Some(100) };
+        let rule = |attack: &str, payload: &str, res: &ProbeResponse| detect(attack, payload, res).map(|f| f.rule_id);
+
+        assert_eq!(rule("path_traversal", "/etc/passwd", &probe("root:x:0:0:root:/root:/bin/bash", 50)).as_deref(), Some("ACTIVE-PATH-TRAVERSAL"));
+        assert_eq!(rule("ssti", "{{1337*1337}}", &probe("Hello 1787569", 50)).as_deref(), Some("ACTIVE-SSTI"));
+        // A raw reflection of the expression is not evaluation
+        assert_eq!(rule("ssti", "{{1337*1337}}", &probe("Hello {{1337*1337}}", 50)), None);
+        assert_eq!(rule("command_injection", ";echo apisec$((1000+337))", &probe("apisec$((1000+337))", 50)), None);
+        assert_eq!(rule("command_injection", ";echo apisec$((1000+337))", &probe("apisec1337", 50)).as_deref(), Some("ACTIVE-CMDI"));
+        assert_eq!(rule("command_injection", ";sleep 3", &probe("", 3200)).as_deref(), Some("ACTIVE-CMDI"));
+        assert_eq!(rule("command_injection", ";sleep 3", &probe("", 1200)), None);
+        assert_eq!(rule("xxe", XXE_PAYLOADS[0], &probe("<r>root:*:0:0:System Administrator</r>", 50)).as_deref(), Some("ACTIVE-XXE"));
+
+        let injected = std::collections::HashMap::from([(CRLF_HEADER.to_string(), "crlf".to_string())]);
+        let res = ProbeResponse { status: 302, headers: &injected, body: "", time_ms: 10, baseline_ms: None };
+        assert_eq!(rule("crlf", CRLF_PAYLOADS[0], &res).as_deref(), Some("ACTIVE-CRLF"));
+        assert_eq!(rule("crlf", CRLF_PAYLOADS[0], &probe("", 10)), None);
+
+        let redirect = |status: u16, header: &str, target: &str| {
+            let headers = std::collections::HashMap::from([(header.to_string(), target.to_string())]);
+            rule("open_redirect", "//x", &ProbeResponse { status, headers: &headers, body: "", time_ms: 10, baseline_ms: None })
+        };
+        assert_eq!(redirect(302, "location", "https://apisec-redirect.example/").as_deref(), Some("ACTIVE-OPEN-REDIRECT"));
+        assert_eq!(redirect(301, "location", "/\\apisec-redirect.example/").as_deref(), Some("ACTIVE-OPEN-REDIRECT"));
+        assert_eq!(redirect(200, "refresh", "0; url=//apisec-redirect.example/").as_deref(), Some("ACTIVE-OPEN-REDIRECT"));
+        assert_eq!(redirect(302, "location", "/login?next=https://apisec-redirect.example/"), None);
+        assert_eq!(redirect(302, "location", "https://localhost@apisec-redirect.example.com/"), None);
+        assert_eq!(redirect(200, "location", "https://apisec-redirect.example/"), None);
+        assert_eq!(refresh_target("0; URL=\u{130}/x"), Some("\u{130}/x"));
+        assert_eq!(refresh_target("\u{130}0; url=/x"), Some("/x"));
+    }
+
+    #[test]
+    fn test_encode_url_payload() {
+        assert_eq!(encode_url_payload("%2f%2fapisec-redirect.example/"), "%2f%2fapisec-redirect.example%2F");
+        assert_eq!(encode_url_payload("..%2f..%2fetc/passwd"), "..%2f..%2fetc%2Fpasswd");
+        assert_eq!(encode_url_payload("100% a&b"), "100%25%20a%26b");
+        assert_eq!(encode_url_payload("%4"), "%254");
+    }
+
+    #[test]
+    fn test_mark_redirect_params() {
+        let task = FuzzTask {
+            url: "https://api.example.com/login?returnTo=%2Fhome&page=2".to_string(),
+            method: "POST".to_string(),
+            headers: std::collections::HashMap::new(),
+            body: Some(r#"{"redirect_uri": "https://app.example.com/cb", "name": "x"}"#.to_string()),
+            pacing: Pacing::default(),
+        };
+        let marked = mark_redirect_params(&task);
+        assert_eq!(marked.url, "https://api.example.com/login?returnTo=§%2Fhome§&page=2");
+        assert_eq!(marked.body.as_deref(), Some(r#"{"redirect_uri": "§https://app.example.com/cb§", "name": "x"}"#));
+        let form = FuzzTask { url: "https://api.example.com/out".to_string(), body: Some("next=%2F&id=1".to_string()), ..task };
+        assert_eq!(mark_redirect_params(&form).body.as_deref(), Some("next=§%2F§&id=1"));
+    }
+
+    #[test]
+    fn test_parse_wordlist() {
+        let file = "\u{feff}# SQLi payloads\r\n' OR 1=1--\r\n\r\n admin'--\n#\n' OR 1=1--\n#{7*7}\n";
+        assert_eq!(parse_wordlist(file), vec!["' OR 1=1--", " admin'--", "#{7*7}"]);
+        assert!(parse_wordlist("\n\n").is_empty());
+    }
 }
@@ -0,0 +1,364 @@
+use crate::analysis::{Finding, FindingSeverity};
+use crate::fuzzer::{FuzzResult, FuzzTask};
+use crate::pacing::Pacer;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// Nesting depths tried along a recursive path of the schema, smallest
+/// first. Probing stops at the first refusal; the largest being accepted
+/// means there is no effective limit. Alias counts and batch sizes alike.
+const DEPTHS: [usize; 3] = [5, 10, 20];
+const ALIAS_COUNTS: [usize; 3] = [10, 100, 500];
+const BATCH_SIZES: [usize; 2] = [5, 50];
+
+/// Field names misspelled on purpose to provoke "Did you mean" suggestions.
+pub const FIELD_GUESSES: &[&str] = &["user", "users", "me", "admin", "account", "order", "password", "token", "email", "config"];
+
+/// Requests sent besides the suggestion probes.
+pub const PROBE_COUNT: usize = 1 + DEPTHS.len() + ALIAS_COUNTS.len() + BATCH_SIZES.len();
+
+const INTROSPECTION_QUERY: &str = "query { __schema { queryType { name } types { name kind fields { name args { type { kind } } type { kind name ofType { kind name ofType { kind name ofType { kind name } } } } } } } }";
+
+/// Whether a response carries data and no errors.
+pub fn accepted(body: &Value) -> bool {
+    let errors = body.get("errors").and_then(Value::as_array).is_some_and(|e| !e.is_empty());
+    !errors && body.get("data").is_some_and(|d| !d.is_null())
+}
+
+/// The named type under any list and non-null wrappers.
+fn named_type(ty: &Value) -> Option<&str> {
+    match ty.get("name").and_then(Value::as_str) {
+        Some(name) => Some(name),
+        None => named_type(ty.get("ofType")?),
+    }
+}
+
+/// Field names from the query root down to a type already visited, then
+/// around that cycle once: the prefix and the loop to repeat for depth.
+/// Fields with required arguments are skipped.
+pub fn find_cycle(introspection: &Value) -> Option<(Vec<String>, Vec<String>)> {
+    let schema = introspection.pointer("/data/__schema")?;
+    let root = schema.pointer("/queryType/name")?.as_str()?;
+    let mut edges: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+    for ty in schema.get("types")?.as_array()? {
+        let (Some(name), Some(fields)) = (ty.get("name").and_then(Value::as_str), ty.get("fields").and_then(Value::as_array)) else {
+            continue;
+        };
+        for field in fields {
+            let required_args = field
+                .get("args")
+                .and_then(Value::as_array)
+                .is_some_and(|args| args.iter().any(|a| a.pointer("/type/kind").and_then(Value::as_str) == Some("NON_NULL")));
+            let target = field.get("type").and_then(named_type);
+            if let (false, Some(field_name), Some(target)) = (required_args, field.get("name").and_then(Value::as_str), target) {
+                if !target.starts_with("__") {
+                    edges.entry(name).or_default().push((field_name, target));
+                }
+            }
+        }
+    }
+
+    // Breadth-first, so the first revisit gives a short prefix
+    let mut paths: HashMap<&str, Vec<(&str, &str)>> = HashMap::from([(root, Vec::new())]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(ty) = queue.pop_front() {
+        let path = paths[ty].clone();
+        for &(field, target) in edges.get(ty).map(Vec::as_slice).unwrap_or_default() {
+            if let Some(target_path) = paths.get(target) {
+                // Only a target on our own path closes a loop we can repeat
+                let on_path = target == root || path.iter().any(|(_, t)| *t == target);
+                if on_path && target_path.len() <= path.len() {
+                    let start = target_path.len();
+                    let names = |p: &[(&str, &str)]| p.iter().map(|(f, _)| f.to_string()).collect::<Vec<_>>();
+                    let mut cycle = names(&path[start..]);
+                    cycle.push(field.to_string());
+                    return Some((names(&path[..start]), cycle));
+                }
+                continue;
+            }
+            let mut next = path.clone();
+            next.push((field, target));
+            paths.insert(target, next);
+            queue.push_back(target);
+        }
+    }
+    None
+}
+
+/// A query `depth` object fields deep, walking the prefix then the cycle.
+pub fn nested_query(prefix: &[String], cycle: &[String], depth: usize) -> String {
+    let fields: Vec<&String> = prefix.iter().chain(cycle.iter().cycle()).take(depth).collect();
+    let open: String = fields.iter().map(|f| format!("{} {{ ", f)).collect();
+    format!("query {{ {}__typename {}}}", open, "} ".repeat(fields.len()))
+}
+
+pub fn alias_query(count: usize) -> String {
+    let aliases: Vec<String> = (0..count).map(|i| format!("a{}: __typename", i)).collect();
+    format!("query {{ {} }}", aliases.join(" "))
+}
+
+/// A near miss for `field`, with its last letter dropped.
+pub fn misspell(field: &str) -> String {
+    let mut chars: Vec<char> = field.chars().collect();
+    if chars.len() > 2 {
+        chars.pop();
+    } else {
+        chars.push('x');
+    }
+    chars.into_iter().collect()
+}
+
+static DID_YOU_MEAN: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r"(?i)did you mean (.+?)\?").unwrap());
+static QUOTED_NAME: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r#"["'“]([_A-Za-z][_0-9A-Za-z]*)["'”]"#).unwrap());
+
+/// Field and type names offered by "Did you mean ..." error messages.
+pub fn parse_suggestions(body: &Value) -> Vec<String> {
+    let mut seen = HashSet::new();
+    body.get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.get("message").and_then(Value::as_str))
+        .flat_map(|message| DID_YOU_MEAN.captures_iter(message).map(|c| c[1].to_string()).collect::<Vec<_>>())
+        .flat_map(|list| QUOTED_NAME.captures_iter(&list).map(|c| c[1].to_string()).collect::<Vec<_>>())
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+fn graphql_finding(rule_id: &str, name: &str, severity: FindingSeverity, description: String, match_content: String) -> Finding {
+    Finding {
+        id: None,
+        rule_id: rule_id.to_string(),
+        name: name.to_string(),
+        description,
+        severity,
+        match_content,
+        notes: None,
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+struct Prober<'a> {
+    app_handle: tauri::AppHandle,
+    client: reqwest::Client,
+    task: &'a FuzzTask,
+    pacer: Pacer,
+    cancel: &'a AtomicBool,
+    results: Vec<FuzzResult>,
+    total: usize,
+}
+
+impl Prober<'_> {
+    /// Posts a GraphQL body and returns the status and parsed JSON, or
+    /// `None` once cancelled.
+    async fn send(&mut self, body: Value) -> Option<(u16, Value, u64)> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut headers = self.task.headers.clone();
+        if !headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+            headers.insert("Content-Type".to_string(), "application/json".to_string());
+        }
+        let request = FuzzTask {
+            url: self.task.url.clone(),
+            method: "POST".to_string(),
+            headers,
+            body: Some(body.to_string()),
+            pacing: self.task.pacing.clone(),
+        };
+        match crate::fuzzer::send_paced(&self.client, &request, "graphql", &mut self.pacer, self.cancel).await {
+            Ok((status, _, text, ms)) => Some((status, serde_json::from_str(&text).unwrap_or(Value::Null), ms)),
+            Err(_) => Some((0, Value::Null, 0)),
+        }
+    }
+
+    fn record(&mut self, label: String, status: u16, time_ms: u64, finding: Option<Finding>) {
+        let mut finding = finding;
+        crate::analysis::attach_rule_metadata(finding.as_mut_slice());
+        let res = FuzzResult { payload: label, status, time_ms, finding, position: None, diff: None };
+        self.results.push(res.clone());
+        let _ = self.app_handle.emit("fuzz-progress", (self.results.len(), self.total, res));
+    }
+}
+
+/// Probes a GraphQL endpoint for introspection, missing depth, alias and
+/// batch limits, and field suggestions. `guesses` seed the suggestion
+/// probes; results stream through `fuzz-progress` like other fuzz runs.
+pub async fn run_graphql_test(app_handle: tauri::AppHandle, task: FuzzTask, guesses: Vec<String>, cancel: &AtomicBool) -> Result<Vec<FuzzResult>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut p = Prober {
+        app_handle,
+        client,
+        pacer: Pacer::new(&task.pacing),
+        task: &task,
+        cancel,
+        results: Vec::new(),
+        total: PROBE_COUNT + guesses.len(),
+    };
+
+    let Some((status, schema, ms)) = p.send(json!({ "query": INTROSPECTION_QUERY })).await else {
+        return Ok(p.results);
+    };
+    let introspection = schema.pointer("/data/__schema").is_some_and(|s| !s.is_null());
+    let type_count = schema.pointer("/data/__schema/types").and_then(Value::as_array).map_or(0, Vec::len);
+    p.record(
+        "introspection".to_string(),
+        status,
+        ms,
+        introspection.then(|| {
+            graphql_finding(
+                "ACTIVE-GRAPHQL-INTROSPECTION",
+                "GraphQL Introspection Enabled",
+                FindingSeverity::Medium,
+                format!("The endpoint answered an introspection query with its full schema ({} types).", type_count),
+                "__schema".to_string(),
+            )
+        }),
+    );
+
+    // Depth needs a recursive path, which only the schema reveals
+    if let Some((prefix, cycle)) = introspection.then(|| find_cycle(&schema)).flatten() {
+        for depth in DEPTHS {
+            let Some((status, body, ms)) = p.send(json!({ "query": nested_query(&prefix, &cycle, depth) })).await else {
+                return Ok(p.results);
+            };
+            let ok = accepted(&body);
+            let finding = (ok && depth == DEPTHS[DEPTHS.len() - 1]).then(|| {
+                graphql_finding(
+                    "ACTIVE-GRAPHQL-DEPTH",
+                    "No GraphQL Query Depth Limit",
+                    FindingSeverity::Medium,
+                    format!("A query nested {} levels deep through {} was executed.", depth, cycle.join(" > ")),
+                    format!("depth {}", depth),
+                )
+            });
+            p.record(format!("depth {}", depth), status, ms, finding);
+            if !ok {
+                break;
+            }
+        }
+    }
+
+    for count in ALIAS_COUNTS {
+        let Some((status, body, ms)) = p.send(json!({ "query": alias_query(count) })).await else {
+            return Ok(p.results);
+        };
+        let answered = body.get("data").and_then(Value::as_object).map_or(0, |d| d.len());
+        let ok = accepted(&body) && answered >= count;
+        let finding = (ok && count == ALIAS_COUNTS[ALIAS_COUNTS.len() - 1]).then(|| {
+            graphql_finding(
+                "ACTIVE-GRAPHQL-ALIAS",
+                "GraphQL Alias Batching Not Limited",
+                FindingSeverity::Medium,
+                format!("A single query with {} aliased fields was executed, allowing brute force within one request.", count),
+                format!("{} aliases", count),
+            )
+        });
+        p.record(format!("aliases {}", count), status, ms, finding);
+        if !ok {
+            break;
+        }
+    }
+
+    for size in BATCH_SIZES {
+        let batch = Value::Array(vec![json!({ "query": "query { __typename }" }); size]);
+        let Some((status, body, ms)) = p.send(batch).await else {
+            return Ok(p.results);
+        };
+        let ok = body.as_array().is_some_and(|items| items.len() == size && items.iter().all(accepted));
+        let finding = (ok && size == BATCH_SIZES[BATCH_SIZES.len() - 1]).then(|| {
+            graphql_finding(
+                "ACTIVE-GRAPHQL-BATCH",
+                "GraphQL Array Batching Not Limited",
+                FindingSeverity::Medium,
+                format!("An array of {} operations was executed in one request.", size),
+                format!("batch {}", size),
+            )
+        });
+        p.record(format!("batch {}", size), status, ms, finding);
+        if !ok {
+            break;
+        }
+    }
+
+    // Suggestions only matter when introspection is off, so report once
+    let mut reported = introspection;
+    for guess in &guesses {
+        let typo = misspell(guess);
+        let Some((status, body, ms)) = p.send(json!({ "query": format!("query {{ {} }}", typo) })).await else {
+            return Ok(p.results);
+        };
+        let suggestions = parse_suggestions(&body);
+        let finding = (!reported && !suggestions.is_empty()).then(|| {
+            graphql_finding(
+                "ACTIVE-GRAPHQL-SUGGESTIONS",
+                "GraphQL Field Suggestions Enabled",
+                FindingSeverity::Low,
+                format!("Error messages suggest field names, leaking the schema although introspection is off, e.g. {}", suggestions.join(", ")),
+                "Did you mean".to_string(),
+            )
+        });
+        reported |= finding.is_some();
+        let label = match suggestions.is_empty() {
+            true => format!("suggest {}", typo),
+            false => format!("suggest {} -> {}", typo, suggestions.join(", ")),
+        };
+        p.record(label, status, ms, finding);
+    }
+    Ok(p.results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, ty: Value) -> Value {
+        json!({ "name": name, "args": [], "type": ty })
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let object = |name: &str| json!({ "kind": "OBJECT", "name": name });
+        let list = |name: &str| json!({ "kind": "NON_NULL", "name": null, "ofType": { "kind": "LIST", "name": null, "ofType": object(name) } });
+        let schema = json!({ "data": { "__schema": {
+            "queryType": { "name": "Query" },
+            "types": [
+                { "name": "Query", "kind": "OBJECT", "fields": [field("version", json!({ "kind": "SCALAR", "name": "String" })), field("me", object("User"))] },
+                { "name": "User", "kind": "OBJECT", "fields": [field("posts", list("Post"))] },
+                { "name": "Post", "kind": "OBJECT", "fields": [field("title", json!({ "kind": "SCALAR", "name": "String" })), field("author", object("User"))] },
+                { "name": "__Type", "kind": "OBJECT", "fields": [field("ofType", object("__Type"))] }
+            ]
+        } } });
+        let (prefix, cycle) = find_cycle(&schema).unwrap();
+        assert_eq!(prefix, vec!["me"]);
+        assert_eq!(cycle, vec!["posts", "author"]);
+        assert_eq!(nested_query(&prefix, &cycle, 4), "query { me { posts { author { posts { __typename } } } } }");
+        assert_eq!(find_cycle(&json!({ "data": null })), None);
+    }
+
+    #[test]
+    fn test_queries_and_suggestions() {
+        assert_eq!(alias_query(2), "query { a0: __typename a1: __typename }");
+        assert_eq!(misspell("users"), "user");
+        assert_eq!(misspell("me"), "mex");
+        let body = json!({ "errors": [
+            { "message": "Cannot query field \"user\" on type \"Query\". Did you mean \"users\" or \"userById\"?" },
+            { "message": "Cannot query field \"ordr\" on type \"Query\". Did you mean \"order\", \"orders\", or \"users\"?" }
+        ] });
+        assert_eq!(parse_suggestions(&body), vec!["users", "userById", "order", "orders"]);
+        assert!(!accepted(&body));
+        assert!(accepted(&json!({ "data": { "__typename": "Query" } })));
+        assert!(!accepted(&json!({ "data": null, "errors": [] })));
+    }
+}
@@ -0,0 +1,439 @@
+use crate::analysis::{Finding, FindingSeverity};
+use crate::fuzz_baseline::{Baseline, ResponseDiff, BASELINE_SAMPLES};
+use crate::fuzzer::{FuzzResult, FuzzTask};
+use crate::pacing::Pacer;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Header mutations, one `Name: value` per line. `{len}` is replaced with
+/// the body's real length.
+pub const HEADER_PAYLOADS: &[&str] = &[
+    "X-Forwarded-For: 127.0.0.1",
+    "X-Real-IP: 127.0.0.1",
+    "X-Client-IP: 127.0.0.1",
+    "X-Forwarded-Host: apisec-injected.example",
+    "Host: apisec-injected.example",
+    "X-Original-URL: /admin",
+    "X-Rewrite-URL: /admin",
+    "X-HTTP-Method-Override: DELETE",
+    "X-HTTP-Method-Override: PUT",
+    "X-Method-Override: DELETE",
+    "Content-Length: {len}\nContent-Length: 0",
+];
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Responses are read up to this many bytes; the rest is dropped.
+const MAX_RESPONSE: usize = 1024 * 1024;
+
+pub struct RawResponse {
+    pub status: u16,
+    /// Lowercased names; the last of repeated headers wins
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// Parses a mutation payload into headers; lines without a colon are ignored.
+pub fn parse_mutation(payload: &str, body_len: usize) -> Vec<(String, String)> {
+    payload
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().replace("{len}", &body_len.to_string())))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// An HTTP/1.1 request with the mutation's headers replacing any of the
+/// same name, repeated names kept as they are.
+pub fn build_raw_request(task: &FuzzTask, mutation: &[(String, String)]) -> Result<Vec<u8>, String> {
+    let url = url::Url::parse(&task.url).map_err(|e| e.to_string())?;
+    let host = url.host_str().ok_or("URL has no host")?;
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    let body = task.body.as_deref().unwrap_or("");
+    let target = match url.query() {
+        Some(q) => format!("{}?{}", url.path(), q),
+        None => url.path().to_string(),
+    };
+
+    let overridden = |name: &str| mutation.iter().any(|(m, _)| m.eq_ignore_ascii_case(name));
+    let mut headers: Vec<(String, String)> = task.headers.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    headers.sort();
+    if !headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("host")) {
+        headers.insert(0, ("Host".to_string(), host));
+    }
+    if !body.is_empty() {
+        headers.push(("Content-Length".to_string(), body.len().to_string()));
+    }
+    headers.retain(|(name, _)| !overridden(name) && !name.eq_ignore_ascii_case("connection"));
+    headers.extend(mutation.iter().cloned());
+    headers.push(("Connection".to_string(), "close".to_string()));
+
+    let mut raw = format!("{} {} HTTP/1.1\r\n", task.method.to_uppercase(), target);
+    for (name, value) in headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    raw.push_str("\r\n");
+    raw.push_str(body);
+    Ok(raw.into_bytes())
+}
+
+fn dechunk(mut data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some(end) = data.windows(2).position(|w| w == b"\r\n") {
+        let size_line = String::from_utf8_lossy(&data[..end]);
+        let Ok(size) = usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16) else {
+            break;
+        };
+        if size == 0 {
+            break;
+        }
+        let start = end + 2;
+        let Some(chunk) = data.get(start..start + size) else {
+            out.extend_from_slice(&data[start.min(data.len())..]);
+            break;
+        };
+        out.extend_from_slice(chunk);
+        data = data.get(start + size + 2..).unwrap_or_default();
+    }
+    out
+}
+
+fn header_end(bytes: &[u8]) -> Option<usize> {
+    bytes.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+pub fn parse_raw_response(bytes: &[u8]) -> Option<RawResponse> {
+    let split = header_end(bytes)?;
+    let head = String::from_utf8_lossy(&bytes[..split]);
+    let mut lines = head.split("\r\n");
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+    let headers: HashMap<String, String> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+    let raw_body = &bytes[split + 4..];
+    let body = if headers.get("transfer-encoding").is_some_and(|t| t.to_lowercase().contains("chunked")) {
+        dechunk(raw_body)
+    } else {
+        raw_body.to_vec()
+    };
+    Some(RawResponse { status, headers, body: String::from_utf8_lossy(&body).into_owned() })
+}
+
+/// Whether the whole response has arrived, for servers that ignore
+/// `Connection: close`.
+fn response_complete(bytes: &[u8]) -> bool {
+    let Some(split) = header_end(bytes) else {
+        return false;
+    };
+    let head = String::from_utf8_lossy(&bytes[..split]).to_lowercase();
+    let body = &bytes[split + 4..];
+    if head.contains("transfer-encoding: chunked") {
+        return body.ends_with(b"0\r\n\r\n");
+    }
+    head.lines()
+        .find_map(|l| l.strip_prefix("content-length:"))
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .is_some_and(|len| body.len() >= len)
+}
+
+/// Header attacks target servers that should be tested regardless of
+/// their certificate, as the reqwest-based fuzzer already does.
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<rustls::crypto::CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// TLS client settings that complete the handshake whatever certificate
+/// the server presents.
+pub(crate) fn accept_any_cert_config() -> Result<rustls::ClientConfig, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    Ok(rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+        .with_no_client_auth())
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, raw: &[u8]) -> Result<Vec<u8>, String> {
+    stream.write_all(raw).await.map_err(|e| e.to_string())?;
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        match stream.read(&mut chunk).await {
+            Ok(0) => break,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            // TLS servers often close without close_notify
+            Err(_) if !buf.is_empty() => break,
+            Err(e) => return Err(e.to_string()),
+        }
+        if response_complete(&buf) || buf.len() >= MAX_RESPONSE {
+            break;
+        }
+    }
+    buf.truncate(MAX_RESPONSE);
+    Ok(buf)
+}
+
+/// Sends a raw request over plain TCP or TLS. Reqwest normalizes `Host`
+/// and `Content-Length`, which these attacks need to control.
+pub async fn send_raw(url: &str, raw: &[u8]) -> Result<RawResponse, String> {
+    let url = url::Url::parse(url).map_err(|e| e.to_string())?;
+    let host = url.host_str().ok_or("URL has no host")?.trim_matches(['[', ']']).to_string();
+    let port = url.port_or_known_default().ok_or("URL has no port")?;
+
+    let bytes = tokio::time::timeout(TIMEOUT, async {
+        let tcp = tokio::net::TcpStream::connect((host.as_str(), port)).await.map_err(|e| e.to_string())?;
+        if url.scheme() == "https" {
+            let config = accept_any_cert_config()?;
+            let name = ServerName::try_from(host.clone()).map_err(|e| e.to_string())?;
+            let tls = tokio_rustls::TlsConnector::from(Arc::new(config)).connect(name, tcp).await.map_err(|e| e.to_string())?;
+            exchange(tls, raw).await
+        } else {
+            exchange(tcp, raw).await
+        }
+    })
+    .await
+    .map_err(|_| "Timed out".to_string())??;
+
+    parse_raw_response(&bytes).ok_or_else(|| "Malformed HTTP response".to_string())
+}
+
+/// `send_raw` spaced out by `pacer`, retrying throttled responses.
+async fn send_paced(url: &str, raw: &[u8], pacer: &mut Pacer, cancel: &AtomicBool) -> Result<RawResponse, String> {
+    let throttle = |res: &Result<RawResponse, String>| res.as_ref().map_or((0, None), |r| (r.status, r.headers.get("retry-after").cloned()));
+    pacer.send(Some(cancel), || send_raw(url, raw), throttle).await
+}
+
+fn header_finding(rule_id: &str, name: &str, severity: FindingSeverity, description: String, payload: &str) -> Finding {
+    Finding {
+        id: None,
+        rule_id: rule_id.to_string(),
+        name: name.to_string(),
+        description,
+        severity,
+        match_content: payload.to_string(),
+        notes: None,
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+/// Compares the response to a mutated request with the unmodified baseline
+/// `samples`; `diff` is the response measured against their `Baseline`.
+pub fn judge_mutation(payload: &str, mutation: &[(String, String)], samples: &[RawResponse], diff: &ResponseDiff, res: &RawResponse) -> Option<Finding> {
+    let ok = |s: u16| (200..300).contains(&s);
+    let summary = mutation.iter().map(|(k, v)| format!("{}: {}", k, v)).collect::<Vec<_>>().join(", ");
+    let location = |r: &RawResponse| r.headers.get("location").cloned().unwrap_or_default();
+
+    // Repeating the same length (e.g. 0 for an empty body) is no conflict
+    let mut content_lengths: Vec<&str> =
+        mutation.iter().filter(|(k, _)| k.eq_ignore_ascii_case("content-length")).map(|(_, v)| v.trim()).collect();
+    content_lengths.sort_unstable();
+    content_lengths.dedup();
+    if content_lengths.len() > 1 {
+        return (res.status < 400).then(|| {
+            header_finding(
+                "ACTIVE-CONTENT-LENGTH-CONFLICT",
+                "Conflicting Content-Length Accepted",
+                FindingSeverity::Medium,
+                format!("The server answered {} to a request with conflicting Content-Length headers instead of rejecting it.", res.status),
+                payload,
+            )
+        });
+    }
+
+    let host_values = mutation
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("host") || k.eq_ignore_ascii_case("x-forwarded-host"))
+        .map(|(_, v)| v.as_str());
+    for value in host_values {
+        let reflected = |r: &RawResponse| location(r).contains(value) || r.body.contains(value);
+        if reflected(res) && !samples.iter().any(reflected) {
+            return Some(header_finding(
+                "ACTIVE-HOST-INJECTION",
+                "Host Header Injection",
+                FindingSeverity::Medium,
+                format!("The injected host '{}' was reflected in the response's {}.", value, if location(res).contains(value) { "Location header" } else { "body" }),
+                payload,
+            ));
+        }
+    }
+
+    let first = samples.first()?;
+    if samples.iter().all(|s| matches!(s.status, 401 | 403)) && ok(res.status) {
+        return Some(header_finding(
+            "ACTIVE-HEADER-TRUST",
+            "Access Control Bypassed via Client Header",
+            FindingSeverity::High,
+            format!("A request refused with {} succeeded with {} after adding {}.", first.status, res.status, summary),
+            payload,
+        ));
+    }
+    let changed = if samples.iter().all(|s| s.status / 100 != res.status / 100) {
+        Some((FindingSeverity::Medium, format!("status {} instead of {}", res.status, first.status)))
+    } else if samples.iter().all(|s| location(s) != location(res)) {
+        Some((FindingSeverity::Low, format!("a redirect to '{}'", location(res))))
+    } else if diff.interesting {
+        Some((FindingSeverity::Low, format!("a substantially different body ({})", diff.reasons.join(", "))))
+    } else {
+        None
+    };
+    changed.map(|(severity, what)| {
+        header_finding(
+            "ACTIVE-HEADER-TRUST",
+            "Behaviour Changed by Client Header",
+            severity,
+            format!("Adding {} produced {}, so the server trusts this client-controlled header.", summary, what),
+            payload,
+        )
+    })
+}
+
+/// Sends the task once unmodified, then once per header mutation, and
+/// reports mutations that change the server's behaviour.
+pub async fn run_header_test(app_handle: tauri::AppHandle, task: FuzzTask, payloads: Vec<String>, cancel: &AtomicBool) -> Result<Vec<FuzzResult>, String> {
+    // Markers mean nothing here; send their original values
+    let task = crate::fuzzer::render_task(&task, usize::MAX, "");
+    let body_len = task.body.as_deref().map_or(0, str::len);
+    let mut pacer = Pacer::new(&task.pacing);
+    // Sampled a few times so dynamic content isn't mistaken for a change.
+    // Each request gets fresh values for deferred `$` builtins.
+    let mut samples = Vec::new();
+    let mut last_error = String::new();
+    for _ in 0..BASELINE_SAMPLES {
+        let original = build_raw_request(&crate::variables::fresh_task(&task), &[])?;
+        match send_paced(&task.url, &original, &mut pacer, cancel).await {
+            Ok(res) => samples.push(res),
+            Err(e) => last_error = e,
+        }
+    }
+    let baseline = Baseline::from_samples(samples.iter().map(|r| (r.status, r.body.clone())).collect())
+        .ok_or_else(|| format!("Baseline request failed: {}", last_error))?;
+
+    let mut results = Vec::new();
+    let total = payloads.len();
+    for (i, payload) in payloads.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let mutation = parse_mutation(payload, body_len);
+        let start = std::time::Instant::now();
+        let response = match build_raw_request(&crate::variables::fresh_task(&task), &mutation) {
+            Ok(raw) if !mutation.is_empty() => send_paced(&task.url, &raw, &mut pacer, cancel).await.ok(),
+            _ => None,
+        };
+        let time_ms = start.elapsed().as_millis() as u64;
+
+        let diff = response.as_ref().map(|r| baseline.diff(r.status, &r.body));
+        let mut finding = response.as_ref().zip(diff.as_ref()).and_then(|(res, diff)| judge_mutation(payload, &mutation, &samples, diff, res));
+        crate::analysis::attach_rule_metadata(finding.as_mut_slice());
+        let res = FuzzResult {
+            payload: payload.clone(),
+            status: response.as_ref().map_or(0, |r| r.status),
+            time_ms,
+            finding,
+            position: None,
+            diff,
+        };
+        results.push(res.clone());
+        let _ = app_handle.emit("fuzz-progress", (i + 1, total, res));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, location: Option<&str>, body: &str) -> RawResponse {
+        let headers = location.map(|l| HashMap::from([("location".to_string(), l.to_string())])).unwrap_or_default();
+        RawResponse { status, headers, body: body.to_string() }
+    }
+
+    #[test]
+    fn test_build_raw_request() {
+        let task = FuzzTask {
+            url: "https://api.example.com:8443/items?id=1".to_string(),
+            method: "post".to_string(),
+            headers: HashMap::from([("Host".to_string(), "ignored".to_string()), ("Accept".to_string(), "*/*".to_string())]),
+            body: Some("abcd".to_string()),
+            pacing: Default::default(),
+        };
+        let raw = String::from_utf8(build_raw_request(&task, &parse_mutation("Content-Length: {len}\nContent-Length: 0", 4)).unwrap()).unwrap();
+        assert_eq!(
+            raw,
+            "POST /items?id=1 HTTP/1.1\r\nAccept: */*\r\nHost: ignored\r\nContent-Length: 4\r\nContent-Length: 0\r\nConnection: close\r\n\r\nabcd"
+        );
+        let raw = String::from_utf8(build_raw_request(&task, &parse_mutation("Host: evil.example", 4)).unwrap()).unwrap();
+        assert!(raw.contains("Host: evil.example\r\n") && !raw.contains("ignored"));
+    }
+
+    #[test]
+    fn test_parse_raw_response() {
+        let res = parse_raw_response(b"HTTP/1.1 302 Found\r\nLocation: /x\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n").unwrap();
+        assert_eq!(res.status, 302);
+        assert_eq!(res.headers["location"], "/x");
+        assert_eq!(res.body, "Wikipedia");
+        assert!(response_complete(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok"));
+        assert!(!response_complete(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nok"));
+    }
+
+    #[test]
+    fn test_judge_mutation() {
+        let rule = |payload: &str, baseline: &RawResponse, res: &RawResponse| {
+            let samples = [RawResponse { status: baseline.status, headers: baseline.headers.clone(), body: baseline.body.clone() }];
+            let diff = Baseline::from_samples(vec![(baseline.status, baseline.body.clone())]).unwrap().diff(res.status, &res.body);
+            judge_mutation(payload, &parse_mutation(payload, 0), &samples, &diff, res).map(|f| (f.rule_id, f.severity))
+        };
+        let denied = response(403, None, "forbidden");
+        let allowed = response(200, None, r#"{"users": []}"#);
+        assert!(matches!(rule("X-Forwarded-For: 127.0.0.1", &denied, &allowed), Some((r, FindingSeverity::High)) if r == "ACTIVE-HEADER-TRUST"));
+        assert_eq!(rule("X-Forwarded-For: 127.0.0.1", &allowed, &response(200, None, r#"{"users": []}"#)), None);
+
+        let redirect = response(302, Some("https://apisec-injected.example/login"), "");
+        let original = response(302, Some("https://api.example.com/login"), "");
+        assert!(matches!(rule("Host: apisec-injected.example", &original, &redirect), Some((r, _)) if r == "ACTIVE-HOST-INJECTION"));
+
+        assert!(matches!(rule("Content-Length: 4\nContent-Length: 0", &allowed, &allowed), Some((r, _)) if r == "ACTIVE-CONTENT-LENGTH-CONFLICT"));
+        assert_eq!(rule("Content-Length: 4\nContent-Length: 0", &allowed, &response(400, None, "bad request")), None);
+        // An empty body renders `{len}` as 0, so both headers agree
+        assert_eq!(rule("Content-Length: 0\nContent-Length: 0", &allowed, &allowed), None);
+    }
+}
@@ -1,15 +1,36 @@
 use crate::analysis::{self, Finding};
 use anyhow::Result;
 use calamine::{open_workbook, DataType, Reader, Xlsx};
+use rayon::prelude::*;
 use regex::Regex;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{LazyLock, OnceLock};
+
+/// Upper bound on scanner threads so a large import doesn't starve the proxy and UI.
+const MAX_SCAN_WORKERS: usize = 8;
+
+/// Shared scanner pool, built on first use. `None` if rayon couldn't spawn
+/// its threads, in which case entries are scanned on the calling thread.
+static SCAN_POOL: OnceLock<Option<rayon::ThreadPool>> = OnceLock::new();
+
+/// Entries buffered before a streaming HAR import scans and reports progress.
+const HAR_STREAM_BATCH: usize = 500;
+
+pub const IMPORT_CANCELLED: &str = "Import cancelled";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ImportEntry {
     pub url: String,
     pub method: String,
     pub status_code: Option<i64>,
+    /// Header pairs in capture order; repeated names are kept.
+    #[serde(default)]
+    pub req_headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub res_headers: Vec<(String, String)>,
     pub req_body: Option<String>,
     pub res_body: Option<String>,
     pub findings: Vec<Finding>,
@@ -19,6 +40,76 @@ pub struct ImportEntry {
 pub struct ImportResult {
     pub entries: Vec<ImportEntry>,
     pub source_type: String, // "text", "excel", "har"
+    /// Set instead of `entries` when the import was a dry run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<ImportPreview>,
+}
+
+/// Summary of what an import would add, computed without touching the workspace.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImportPreview {
+    pub total_entries: usize,
+    pub unique_urls: usize,
+    pub hosts: Vec<String>,
+    pub methods: HashMap<String, usize>,
+    pub findings: usize,
+    pub new_assets: usize,
+    pub existing_assets: usize,
+}
+
+impl ImportResult {
+    /// Drops the entries in favour of an `ImportPreview`. `existing` holds the
+    /// asset identities already in the workspace, used to project duplicates.
+    pub fn into_preview(self, existing: &HashSet<String>, mode: crate::assets::IdentityQueryMode) -> ImportResult {
+        let mut preview = ImportPreview {
+            total_entries: self.entries.len(),
+            ..Default::default()
+        };
+        let mut urls = HashSet::new();
+        let mut identities = HashSet::new();
+        let mut hosts = HashSet::new();
+        for entry in &self.entries {
+            *preview.methods.entry(entry.method.to_uppercase()).or_insert(0) += 1;
+            preview.findings += entry.findings.len();
+            if let Some(host) = url::Url::parse(&entry.url).ok().and_then(|u| u.host_str().map(|h| h.to_string())) {
+                hosts.insert(host);
+            }
+            urls.insert(entry.url.as_str());
+            let identity = crate::assets::asset_identity(&entry.method, &entry.url, mode);
+            if identities.insert(identity.clone()) {
+                if existing.contains(&identity) {
+                    preview.existing_assets += 1;
+                } else {
+                    preview.new_assets += 1;
+                }
+            }
+        }
+        preview.unique_urls = urls.len();
+        preview.hosts = hosts.into_iter().collect();
+        preview.hosts.sort();
+
+        ImportResult {
+            entries: Vec::new(),
+            source_type: self.source_type,
+            preview: Some(preview),
+        }
+    }
+}
+
+/// Outcome of one file visited by `Parser::parse_directory`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileImportReport {
+    pub path: String,
+    pub source_type: Option<String>,
+    pub entries: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirectoryImportResult {
+    #[serde(flatten)]
+    pub result: ImportResult,
+    pub files: Vec<FileImportReport>,
 }
 
 pub struct Parser;
@@ -45,6 +136,8 @@ impl Parser {
                 url,
                 method: "GET".to_string(),
                 status_code: None,
+                req_headers: Vec::new(),
+                res_headers: Vec::new(),
                 req_body: None,
                 res_body: None,
                 findings: Vec::new(), // We'll add global findings later or leave empty
@@ -60,74 +153,97 @@ impl Parser {
         ImportResult {
             entries,
             source_type: "text".to_string(),
+            preview: None,
         }
     }
 
-    pub fn parse_har(
+    /// Parses a HAR document, reporting `(scanned, total)` as entries finish scanning.
+    pub fn parse_har_with_progress(
         content: &str,
         custom_rules: &[crate::db::CustomRule],
         plugins: &[crate::plugins::PluginPack],
+        on_progress: &(dyn Fn(usize, usize) + Sync),
     ) -> Result<ImportResult> {
         let har: serde_json::Value = serde_json::from_str(content)?;
+        let mut entries: Vec<ImportEntry> = har
+            .get("log")
+            .and_then(|log| log.get("entries"))
+            .and_then(|e| e.as_array())
+            .map(|har_entries| har_entries.iter().filter_map(har_entry).collect())
+            .unwrap_or_default();
+
+        Self::scan_entries(&mut entries, custom_rules, plugins, on_progress);
+
+        Ok(ImportResult {
+            entries,
+            source_type: "har".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Streams `log.entries` out of a HAR file on disk instead of loading the
+    /// whole document, scanning entries in batches of `HAR_STREAM_BATCH`.
+    /// `on_progress` receives `(entries_done, bytes_read, total_bytes)` after
+    /// each batch; setting `cancel` aborts the import at the next entry.
+    pub fn parse_har_file(
+        path: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+        cancel: &AtomicBool,
+        on_progress: &(dyn Fn(usize, u64, u64) + Sync),
+    ) -> Result<ImportResult> {
+        let file = std::fs::File::open(path)?;
+        let total_bytes = file.metadata()?.len();
+        let bytes_read = AtomicU64::new(0);
+        let reader = std::io::BufReader::with_capacity(
+            1 << 20,
+            ProgressReader {
+                inner: file,
+                bytes_read: &bytes_read,
+            },
+        );
+
         let mut entries = Vec::new();
+        let mut pending = Vec::with_capacity(HAR_STREAM_BATCH);
+        let flush = |pending: &mut Vec<ImportEntry>, entries: &mut Vec<ImportEntry>| {
+            Self::scan_entries(pending, custom_rules, plugins, &|_, _| {});
+            entries.append(pending);
+            on_progress(entries.len(), bytes_read.load(Ordering::Relaxed), total_bytes);
+        };
 
-        if let Some(log) = har.get("log") {
-            if let Some(har_entries) = log.get("entries").and_then(|e| e.as_array()) {
-                for entry in har_entries {
-                    let request = entry.get("request");
-                    let response = entry.get("response");
-
-                    if let (Some(req), Some(res)) = (request, response) {
-                        let url = req
-                            .get("url")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let method = req
-                            .get("method")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("GET")
-                            .to_string();
-                        let status_code = res.get("status").and_then(|v| v.as_i64());
-
-                        let req_body = req
-                            .get("postData")
-                            .and_then(|p| p.get("text"))
-                            .and_then(|t| t.as_str())
-                            .map(|s| s.to_string());
-                        let res_body = res
-                            .get("content")
-                            .and_then(|c| c.get("text"))
-                            .and_then(|t| t.as_str())
-                            .map(|s| s.to_string());
-
-                        // Scan bodies for findings
-                        let mut findings = Vec::new();
-                        if let Some(ref b) = req_body {
-                            findings.extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
-                        }
-                        if let Some(ref b) = res_body {
-                            findings.extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
-                        }
-                        // Also scan URL just in case
-                        findings.extend(analysis::Scanner::scan_text(&url, custom_rules, plugins));
-
-                        entries.push(ImportEntry {
-                            url,
-                            method,
-                            status_code,
-                            req_body,
-                            res_body,
-                            findings,
-                        });
-                    }
+        let mut on_entry = |value: serde_json::Value| -> std::result::Result<(), String> {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(IMPORT_CANCELLED.to_string());
+            }
+            if let Some(entry) = har_entry(&value) {
+                pending.push(entry);
+                if pending.len() >= HAR_STREAM_BATCH {
+                    flush(&mut pending, &mut entries);
                 }
             }
+            Ok(())
+        };
+
+        let mut de = serde_json::Deserializer::from_reader(reader);
+        HarRootSeed(&mut on_entry)
+            .deserialize(&mut de)
+            .map_err(|e| {
+                if cancel.load(Ordering::Relaxed) {
+                    anyhow::anyhow!(IMPORT_CANCELLED)
+                } else {
+                    anyhow::anyhow!(e)
+                }
+            })?;
+        de.end()?;
+
+        if !pending.is_empty() {
+            flush(&mut pending, &mut entries);
         }
 
         Ok(ImportResult {
             entries,
             source_type: "har".to_string(),
+            preview: None,
         })
     }
 
@@ -227,98 +343,1488 @@ impl Parser {
                 }
             }
 
-            let mut findings = Vec::new();
-            findings.extend(analysis::Scanner::scan_text(&url, custom_rules, plugins));
-            if let Some(ref b) = req_body {
-                findings.extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
-            }
-            if let Some(ref b) = res_body {
-                findings.extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
-            }
-
             entries.push(ImportEntry {
                 url,
                 method,
                 status_code: status,
+                req_headers: Vec::new(),
+                res_headers: Vec::new(),
                 req_body,
                 res_body,
-                findings,
+                findings: Vec::new(),
             });
         }
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
         Ok(ImportResult {
             entries,
             source_type: "burp".to_string(),
+            preview: None,
         })
     }
 
+    /// Parses a Postman v2.0/v2.1 collection. Headers, inherited auth and
+    /// structured URLs are resolved against collection variables overlaid with
+    /// `env_vars`, and the first saved example response becomes the response.
     pub fn parse_postman(
         content: &str,
+        env_vars: &HashMap<String, String>,
         custom_rules: &[crate::db::CustomRule],
         plugins: &[crate::plugins::PluginPack],
     ) -> Result<ImportResult> {
         let mut entries = Vec::new();
         let collection: serde_json::Value = serde_json::from_str(content)?;
 
+        // Environment values take precedence over collection variables
+        let mut vars: HashMap<String, String> = HashMap::new();
+        if let Some(list) = collection.get("variable").and_then(|v| v.as_array()) {
+            for v in list {
+                if let (Some(k), Some(val)) = (v.get("key").and_then(|k| k.as_str()), v.get("value")) {
+                    vars.insert(k.to_string(), json_scalar(val));
+                }
+            }
+        }
+        vars.extend(env_vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+
         fn traverse_items(
             val: &serde_json::Value,
+            inherited_auth: Option<&serde_json::Value>,
+            vars: &HashMap<String, String>,
             entries: &mut Vec<ImportEntry>,
-            custom_rules: &[crate::db::CustomRule],
-            plugins: &[crate::plugins::PluginPack],
         ) {
+            let auth = val.get("auth").filter(|a| !a.is_null()).or(inherited_auth);
             if let Some(items) = val.get("item").and_then(|v| v.as_array()) {
                 for item in items {
                     if let Some(request) = item.get("request") {
-                        let method = request
-                            .get("method")
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("GET")
-                            .to_string();
-                        let url = if let Some(url_obj) = request.get("url") {
-                            if let Some(raw) = url_obj.get("raw").and_then(|r| r.as_str()) {
-                                raw.to_string()
-                            } else if let Some(href) = url_obj.as_str() {
-                                href.to_string()
-                            } else {
-                                "unknown".to_string()
-                            }
-                        } else {
-                            "unknown".to_string()
-                        };
-
-                        let req_body = request
-                            .get("body")
-                            .and_then(|b| b.get("raw"))
-                            .and_then(|r| r.as_str())
-                            .map(|s| s.to_string());
-
-                        let mut findings = Vec::new();
-                        findings.extend(analysis::Scanner::scan_text(&url, custom_rules, plugins));
-                        if let Some(ref b) = req_body {
-                            findings.extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
-                        }
-
-                        entries.push(ImportEntry {
-                            url,
-                            method,
-                            status_code: None,
-                            req_body,
-                            res_body: None,
-                            findings,
-                        });
+                        entries.push(postman_entry(item, request, auth, vars));
                     }
                     // Recursive call for nested folders
-                    traverse_items(item, entries, custom_rules, plugins);
+                    traverse_items(item, auth, vars, entries);
                 }
             }
         }
 
-        traverse_items(&collection, &mut entries, custom_rules, plugins);
+        traverse_items(&collection, None, &vars, &mut entries);
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
 
         Ok(ImportResult {
             entries,
             source_type: "postman".to_string(),
+            preview: None,
         })
     }
+
+    /// Expands every path/method of an OpenAPI 3 or Swagger 2 document (JSON or
+    /// YAML) into one entry per server, using documented examples as bodies.
+    pub fn parse_openapi(
+        content: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let spec: serde_json::Value = match serde_json::from_str(content) {
+            Ok(v) => v,
+            Err(_) => serde_yml::from_str(content)?,
+        };
+        let paths = spec
+            .get("paths")
+            .and_then(|p| p.as_object())
+            .ok_or_else(|| anyhow::anyhow!("Document has no 'paths' object"))?;
+
+        let base_urls = openapi_base_urls(&spec);
+        let mut entries = Vec::new();
+
+        for (path, item) in paths {
+            for method in OPENAPI_METHODS {
+                let op = match item.get(*method) {
+                    Some(op) => op,
+                    None => continue,
+                };
+                let req_body = openapi_request_example(op);
+                let res_body = openapi_response_example(op);
+
+                for base in &base_urls {
+                    entries.push(ImportEntry {
+                        url: format!("{}{}", base.trim_end_matches('/'), path),
+                        method: method.to_uppercase(),
+                        status_code: None,
+                        req_headers: Vec::new(),
+                        res_headers: Vec::new(),
+                        req_body: req_body.clone(),
+                        res_body: res_body.clone(),
+                        findings: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
+
+        Ok(ImportResult {
+            entries,
+            source_type: "openapi".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Creates one POST entry per rpc of a `.proto` file, addressed as
+    /// `<base>/package.Service/Method` with a zero-valued JSON request body.
+    pub fn parse_proto(
+        content: &str,
+        base_url: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let file = crate::proto::parse_proto(content)?;
+        let mut entries: Vec<ImportEntry> = file
+            .rpcs
+            .iter()
+            .map(|rpc| ImportEntry {
+                url: format!("{}{}", base_url.trim_end_matches('/'), rpc.path),
+                method: "POST".to_string(),
+                status_code: None,
+                req_headers: vec![("Content-Type".to_string(), "application/grpc".to_string())],
+                res_headers: Vec::new(),
+                req_body: serde_json::to_string_pretty(&file.skeleton(&rpc.input_type, 0)).ok(),
+                res_body: None,
+                findings: Vec::new(),
+            })
+            .collect();
+
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
+
+        Ok(ImportResult {
+            entries,
+            source_type: "proto".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Parses a pasted `curl ...` command into a single entry.
+    pub fn parse_curl(
+        content: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let req = parse_curl_command(content)?;
+
+        let mut entries = vec![ImportEntry {
+            url: req.url,
+            method: req.method,
+            status_code: None,
+            req_headers: req.headers,
+            res_headers: Vec::new(),
+            req_body: req.body,
+            res_body: None,
+            findings: Vec::new(),
+        }];
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
+
+        Ok(ImportResult {
+            entries,
+            source_type: "curl".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Parses a pasted raw HTTP request, optionally followed by its response,
+    /// into a single entry. The response header block is scanned as well.
+    pub fn parse_raw_http(
+        content: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let msg = parse_raw_http_message(content)?;
+
+        let mut findings = Vec::new();
+        if let Some(ref head) = msg.res_head {
+            // Includes the status line so header-policy checks see a response block
+            findings.extend(analysis::Scanner::scan_text(head, custom_rules, plugins));
+        }
+
+        let mut entries = vec![ImportEntry {
+            url: msg.url,
+            method: msg.method,
+            status_code: msg.status_code,
+            req_headers: msg.req_headers,
+            res_headers: msg
+                .res_head
+                .as_deref()
+                .map(|head| parse_header_lines(head.lines().skip(1)))
+                .unwrap_or_default(),
+            req_body: msg.req_body,
+            res_body: msg.res_body,
+            findings,
+        }];
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
+
+        Ok(ImportResult {
+            entries,
+            source_type: "raw-http".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Turns every `<url><loc>` of a sitemap into a GET entry. Child sitemaps
+    /// listed by a sitemap index are returned as entries too, so they show up
+    /// in the inventory even when not fetched.
+    pub fn parse_sitemap(
+        content: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let (urls, sitemaps) = sitemap_locations(content);
+        if urls.is_empty() && sitemaps.is_empty() {
+            return Err(anyhow::anyhow!("No <loc> entries found in sitemap"));
+        }
+
+        let mut seen = HashSet::new();
+        let mut entries: Vec<ImportEntry> = urls
+            .into_iter()
+            .chain(sitemaps)
+            .filter(|u| seen.insert(u.clone()))
+            .map(|url| ImportEntry {
+                url,
+                method: "GET".to_string(),
+                status_code: None,
+                req_headers: Vec::new(),
+                res_headers: Vec::new(),
+                req_body: None,
+                res_body: None,
+                findings: Vec::new(),
+            })
+            .collect();
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
+
+        Ok(ImportResult {
+            entries,
+            source_type: "sitemap".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Resolves the Allow/Disallow paths and Sitemap links of a robots.txt
+    /// against `base_url`. Wildcard rules are cut at the first `*` or `$`.
+    pub fn parse_robots(
+        content: &str,
+        base_url: &str,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let base = url::Url::parse(base_url)?;
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+
+        for rule in robots_rules(content) {
+            let resolved = match rule {
+                RobotsRule::Path(path) => base.join(&path).ok().map(|u| u.to_string()),
+                RobotsRule::Sitemap(url) => Some(url),
+            };
+            let Some(url) = resolved else { continue };
+            if !seen.insert(url.clone()) {
+                continue;
+            }
+            entries.push(ImportEntry {
+                url,
+                method: "GET".to_string(),
+                status_code: None,
+                req_headers: Vec::new(),
+                res_headers: Vec::new(),
+                req_body: None,
+                res_body: None,
+                findings: Vec::new(),
+            });
+        }
+        Self::scan_entries(&mut entries, custom_rules, plugins, &|_, _| {});
+
+        Ok(ImportResult {
+            entries,
+            source_type: "robots".to_string(),
+            preview: None,
+        })
+    }
+
+    /// Walks `dir` (optionally recursing into sub-folders), detects the format
+    /// of each file and runs the matching parser. Files that fail to parse are
+    /// reported individually rather than aborting the whole import; files with
+    /// no recognisable format are skipped.
+    pub fn parse_directory(
+        dir: &str,
+        recursive: bool,
+        env_vars: &HashMap<String, String>,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<DirectoryImportResult> {
+        let mut paths = Vec::new();
+        collect_files(std::path::Path::new(dir), recursive, &mut paths)?;
+        paths.sort();
+
+        let mut entries = Vec::new();
+        let mut files = Vec::new();
+        for path in paths {
+            let display = path.to_string_lossy().to_string();
+            let parsed = match detect_file_format(&path) {
+                Ok(Some(format)) => Some((format, Self::parse_file(&path, format, env_vars, custom_rules, plugins))),
+                Ok(None) => None,
+                Err(e) => Some(("unknown", Err(e))),
+            };
+            let Some((format, parsed)) = parsed else {
+                continue;
+            };
+
+            match parsed {
+                Ok(result) => {
+                    files.push(FileImportReport {
+                        path: display,
+                        source_type: Some(format.to_string()),
+                        entries: result.entries.len(),
+                        error: None,
+                    });
+                    entries.extend(result.entries);
+                }
+                Err(e) => files.push(FileImportReport {
+                    path: display,
+                    source_type: Some(format.to_string()),
+                    entries: 0,
+                    error: Some(e.to_string()),
+                }),
+            }
+        }
+
+        Ok(DirectoryImportResult {
+            result: ImportResult {
+                entries,
+                source_type: "directory".to_string(),
+                preview: None,
+            },
+            files,
+        })
+    }
+
+    fn parse_file(
+        path: &std::path::Path,
+        format: &str,
+        env_vars: &HashMap<String, String>,
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+    ) -> Result<ImportResult> {
+        let path_str = path.to_string_lossy();
+        match format {
+            // Stream HAR from disk; captures are routinely hundreds of MB
+            "har" => Self::parse_har_file(&path_str, custom_rules, plugins, &AtomicBool::new(false), &|_, _, _| {}),
+            "excel" => Self::parse_excel(&path_str, custom_rules, plugins),
+            _ => {
+                let content = std::fs::read_to_string(path)?;
+                match format {
+                    "burp" => Self::parse_burp_xml(&content, custom_rules, plugins),
+                    "sitemap" => Self::parse_sitemap(&content, custom_rules, plugins),
+                    "postman" => Self::parse_postman(&content, env_vars, custom_rules, plugins),
+                    "openapi" => Self::parse_openapi(&content, custom_rules, plugins),
+                    "proto" => Self::parse_proto(&content, "grpc://localhost", custom_rules, plugins),
+                    "curl" => Self::parse_curl(&content, custom_rules, plugins),
+                    "raw-http" => Self::parse_raw_http(&content, custom_rules, plugins),
+                    _ => Ok(Self::parse_text(&content, custom_rules, plugins)),
+                }
+            }
+        }
+    }
+
+    /// Scans the URL, request headers and bodies of every entry on a bounded
+    /// rayon pool, appending the findings to each entry. `on_progress`
+    /// receives `(scanned, total)`.
+    pub fn scan_entries(
+        entries: &mut [ImportEntry],
+        custom_rules: &[crate::db::CustomRule],
+        plugins: &[crate::plugins::PluginPack],
+        on_progress: &(dyn Fn(usize, usize) + Sync),
+    ) {
+        let total = entries.len();
+        let scanned = AtomicUsize::new(0);
+
+        let mut scan_all = || {
+            entries.par_iter_mut().for_each(|entry| {
+                entry
+                    .findings
+                    .extend(analysis::Scanner::scan_text(&entry.url, custom_rules, plugins));
+                if !entry.req_headers.is_empty() {
+                    let header_block = entry
+                        .req_headers
+                        .iter()
+                        .map(|(k, v)| format!("{}: {}", k, v))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    entry
+                        .findings
+                        .extend(analysis::Scanner::scan_text(&header_block, custom_rules, plugins));
+                }
+                if let Some(ref b) = entry.req_body {
+                    entry
+                        .findings
+                        .extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
+                }
+                if let Some(ref b) = entry.res_body {
+                    entry
+                        .findings
+                        .extend(analysis::Scanner::scan_text(b, custom_rules, plugins));
+                }
+                on_progress(scanned.fetch_add(1, Ordering::Relaxed) + 1, total);
+            });
+        };
+
+        let pool = SCAN_POOL.get_or_init(|| {
+            let workers = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4)
+                .min(MAX_SCAN_WORKERS);
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(workers)
+                .thread_name(|i| format!("import-scan-{}", i))
+                .build()
+                .ok()
+        });
+        match pool {
+            Some(pool) => pool.install(scan_all),
+            None => scan_all(),
+        }
+    }
+}
+
+pub(crate) const OPENAPI_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Base URLs from OpenAPI 3 `servers` (variables set to their defaults) or
+/// Swagger 2 `schemes`/`host`/`basePath`. Falls back to localhost.
+pub(crate) fn openapi_base_urls(spec: &serde_json::Value) -> Vec<String> {
+    let mut urls = Vec::new();
+
+    if let Some(servers) = spec.get("servers").and_then(|s| s.as_array()) {
+        for server in servers {
+            let mut url = match server.get("url").and_then(|u| u.as_str()) {
+                Some(u) => u.to_string(),
+                None => continue,
+            };
+            if let Some(vars) = server.get("variables").and_then(|v| v.as_object()) {
+                for (name, var) in vars {
+                    if let Some(default) = var.get("default").and_then(|d| d.as_str()) {
+                        url = url.replace(&format!("{{{}}}", name), default);
+                    }
+                }
+            }
+            if url.starts_with('/') {
+                url = format!("http://localhost{}", url);
+            }
+            urls.push(url);
+        }
+    } else if let Some(host) = spec.get("host").and_then(|h| h.as_str()) {
+        let base_path = spec.get("basePath").and_then(|b| b.as_str()).unwrap_or("");
+        let scheme = spec
+            .get("schemes")
+            .and_then(|s| s.as_array())
+            .and_then(|s| s.first())
+            .and_then(|s| s.as_str())
+            .unwrap_or("https");
+        urls.push(format!("{}://{}{}", scheme, host, base_path));
+    }
+
+    if urls.is_empty() {
+        urls.push("http://localhost".to_string());
+    }
+    urls
+}
+
+/// Picks an example out of a media type object: `example`, the first of
+/// `examples`, or a `schema.example`.
+fn media_example(media: &serde_json::Value) -> Option<String> {
+    let example = media
+        .get("example")
+        .cloned()
+        .or_else(|| {
+            media
+                .get("examples")
+                .and_then(|e| e.as_object())
+                .and_then(|m| m.values().next())
+                .and_then(|ex| ex.get("value").cloned())
+        })
+        .or_else(|| media.get("schema").and_then(|s| s.get("example")).cloned())?;
+
+    match example {
+        serde_json::Value::String(s) => Some(s),
+        other => serde_json::to_string_pretty(&other).ok(),
+    }
+}
+
+/// Prefers the JSON media type, otherwise the first one declared.
+fn preferred_media(content: &serde_json::Value) -> Option<&serde_json::Value> {
+    let map = content.as_object()?;
+    map.get("application/json").or_else(|| map.values().next())
+}
+
+fn openapi_request_example(op: &serde_json::Value) -> Option<String> {
+    // OpenAPI 3
+    if let Some(content) = op.get("requestBody").and_then(|b| b.get("content")) {
+        return preferred_media(content).and_then(media_example);
+    }
+    // Swagger 2 body parameter
+    op.get("parameters")
+        .and_then(|p| p.as_array())
+        .and_then(|params| {
+            params
+                .iter()
+                .find(|p| p.get("in").and_then(|i| i.as_str()) == Some("body"))
+        })
+        .and_then(media_example)
+}
+
+fn openapi_response_example(op: &serde_json::Value) -> Option<String> {
+    let responses = op.get("responses")?.as_object()?;
+    let mut codes: Vec<&String> = responses.keys().filter(|c| c.starts_with('2')).collect();
+    codes.sort();
+    let response = responses.get(*codes.first()?)?;
+
+    if let Some(content) = response.get("content") {
+        return preferred_media(content).and_then(media_example);
+    }
+    // Swagger 2 keeps examples keyed by mime type
+    response
+        .get("examples")
+        .and_then(preferred_media)
+        .map(|ex| match ex {
+            serde_json::Value::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurlRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// Splits a shell command line into words, honouring single quotes, double
+/// quotes, `$'...'` strings and backslash/caret line continuations.
+fn shell_words(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some('\r') => {}
+                Some(other) => {
+                    current.push(other);
+                    in_word = true;
+                }
+                None => {}
+            },
+            // Windows cmd line continuation
+            '^' if matches!(chars.peek(), Some('\n') | Some('\r')) => {
+                while matches!(chars.peek(), Some('\n') | Some('\r')) {
+                    chars.next();
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err(anyhow::anyhow!("Unterminated single quote")),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('r') => current.push('\r'),
+                            Some('t') => current.push('\t'),
+                            Some(ch) => current.push(ch),
+                            None => return Err(anyhow::anyhow!("Unterminated $'' string")),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err(anyhow::anyhow!("Unterminated $'' string")),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => current.push(ch),
+                            Some('\n') => {}
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err(anyhow::anyhow!("Unterminated double quote")),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err(anyhow::anyhow!("Unterminated double quote")),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// curl options that take a value but don't affect the captured request.
+const CURL_IGNORED_VALUE_OPTS: &[&str] = &[
+    "-o", "--output", "-x", "--proxy", "-m", "--max-time", "--connect-timeout", "--cacert",
+    "--cert", "-E", "--key", "-w", "--write-out", "--retry", "-c", "--cookie-jar", "-T",
+    "--upload-file", "--resolve", "--limit-rate", "-K", "--config", "--max-redirs",
+];
+
+/// Boundary of multipart bodies rebuilt from `-F` fields. Fixed so the same
+/// command always imports the same body.
+const CURL_FORM_BOUNDARY: &str = "------------------------apisecformboundary";
+
+/// One `-F name=value` field. `name=@path` and `name=<path` refer to local
+/// files, which aren't read: an upload keeps its file name with an empty
+/// body, and a field read from a file is left empty.
+#[derive(Debug, Clone, PartialEq)]
+struct FormField {
+    name: String,
+    value: String,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+impl FormField {
+    fn parse(spec: &str) -> Self {
+        let (name, rest) = spec.split_once('=').unwrap_or((spec, ""));
+        let mut options = rest.split(';');
+        let content = options.next().unwrap_or_default();
+        let mut field = FormField { name: name.to_string(), value: content.to_string(), file_name: None, content_type: None };
+        if let Some(path) = content.strip_prefix('@') {
+            field.value = String::new();
+            field.file_name = Some(path.rsplit(['/', '\\']).next().unwrap_or(path).to_string());
+        } else if content.starts_with('<') {
+            field.value = String::new();
+        }
+        for option in options {
+            match option.trim().split_once('=') {
+                Some(("type", t)) => field.content_type = Some(t.to_string()),
+                Some(("filename", f)) => field.file_name = Some(f.trim_matches('"').to_string()),
+                _ => {}
+            }
+        }
+        field
+    }
+}
+
+fn multipart_body(fields: &[FormField]) -> String {
+    let mut body = String::new();
+    for field in fields {
+        body.push_str(&format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"", CURL_FORM_BOUNDARY, field.name));
+        if let Some(file_name) = &field.file_name {
+            body.push_str(&format!("; filename=\"{}\"", file_name));
+        }
+        body.push_str("\r\n");
+        let content_type = field.content_type.as_deref().or(field.file_name.as_ref().map(|_| "application/octet-stream"));
+        if let Some(content_type) = content_type {
+            body.push_str(&format!("Content-Type: {}\r\n", content_type));
+        }
+        body.push_str(&format!("\r\n{}\r\n", field.value));
+    }
+    body.push_str(&format!("--{}--\r\n", CURL_FORM_BOUNDARY));
+    body
+}
+
+fn encode_form_component(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Parses a curl command line into method, URL, headers and body.
+pub fn parse_curl_command(input: &str) -> Result<CurlRequest> {
+    let words = shell_words(input.trim())?;
+    let mut iter = words.into_iter().peekable();
+    match iter.next() {
+        Some(w) if w == "curl" || w.ends_with("/curl") || w.eq_ignore_ascii_case("curl.exe") => {}
+        _ => return Err(anyhow::anyhow!("Input does not start with a curl command")),
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut form: Vec<FormField> = Vec::new();
+    let mut force_get = false;
+
+    while let Some(word) = iter.next() {
+        // Split attached values: --opt=value and -Xvalue
+        let (opt, attached) = if word.starts_with("--") {
+            match word.split_once('=') {
+                Some((o, v)) if !o.contains(':') => (o.to_string(), Some(v.to_string())),
+                _ => (word.clone(), None),
+            }
+        } else if let Some((flag, rest)) = word
+            .strip_prefix('-')
+            .and_then(|rest| rest.chars().next().map(|c| (c, &rest[c.len_utf8()..])))
+            .filter(|(c, rest)| "XHdbuAeFx".contains(*c) && !rest.is_empty())
+        {
+            (format!("-{}", flag), Some(rest.to_string()))
+        } else {
+            (word.clone(), None)
+        };
+        let mut value = |attached: Option<String>| -> Result<String> {
+            attached
+                .or_else(|| iter.next())
+                .ok_or_else(|| anyhow::anyhow!("Missing value for curl option {}", opt))
+        };
+
+        match opt.as_str() {
+            "-X" | "--request" => method = Some(value(attached)?.to_uppercase()),
+            "-H" | "--header" => {
+                let h = value(attached)?;
+                if let Some((k, v)) = h.split_once(':') {
+                    headers.push((k.trim().to_string(), v.trim().to_string()));
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => data.push(value(attached)?),
+            "--data-urlencode" => {
+                let v = value(attached)?;
+                let encoded = match v.split_once('=') {
+                    Some(("", content)) => encode_form_component(content),
+                    Some((name, content)) => format!("{}={}", name, encode_form_component(content)),
+                    None => encode_form_component(&v),
+                };
+                data.push(encoded);
+            }
+            "--json" => {
+                data.push(value(attached)?);
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                headers.push(("Accept".to_string(), "application/json".to_string()));
+            }
+            "-F" | "--form" => form.push(FormField::parse(&value(attached)?)),
+            "--form-string" => {
+                let v = value(attached)?;
+                let (name, content) = v.split_once('=').unwrap_or((&v, ""));
+                form.push(FormField { name: name.to_string(), value: content.to_string(), file_name: None, content_type: None });
+            }
+            "-u" | "--user" => {
+                use base64::{engine::general_purpose, Engine as _};
+                let creds = value(attached)?;
+                headers.push(("Authorization".to_string(), format!("Basic {}", general_purpose::STANDARD.encode(creds))));
+            }
+            "-b" | "--cookie" => headers.push(("Cookie".to_string(), value(attached)?)),
+            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value(attached)?)),
+            "-e" | "--referer" => headers.push(("Referer".to_string(), value(attached)?)),
+            "--url" => url = Some(value(attached)?),
+            "-G" | "--get" => force_get = true,
+            "-I" | "--head" => method = Some("HEAD".to_string()),
+            o if CURL_IGNORED_VALUE_OPTS.contains(&o) => {
+                value(attached)?;
+            }
+            o if o.starts_with('-') && o.len() > 1 => {
+                // Boolean flags (-s, -k, -L, --compressed, ...) or bundles like -sSL
+            }
+            _ => {
+                if url.is_none() {
+                    url = Some(word);
+                }
+            }
+        }
+    }
+
+    let mut url = url.ok_or_else(|| anyhow::anyhow!("No URL found in curl command"))?;
+    if !url.contains("://") {
+        url = format!("http://{}", url);
+    }
+
+    let joined = if !form.is_empty() {
+        if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("Content-Type")) {
+            headers.push(("Content-Type".to_string(), format!("multipart/form-data; boundary={}", CURL_FORM_BOUNDARY)));
+        }
+        Some(multipart_body(&form))
+    } else if data.is_empty() {
+        None
+    } else {
+        Some(data.join("&"))
+    };
+    let (method, body) = if force_get {
+        if let Some(q) = joined {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&q);
+        }
+        (method.unwrap_or_else(|| "GET".to_string()), None)
+    } else {
+        let default = if joined.is_some() { "POST" } else { "GET" };
+        (method.unwrap_or_else(|| default.to_string()), joined)
+    };
+
+    Ok(CurlRequest { method, url, headers, body })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawHttpMessage {
+    pub method: String,
+    pub url: String,
+    pub req_headers: Vec<(String, String)>,
+    pub req_body: Option<String>,
+    pub status_code: Option<i64>,
+    /// Status line plus response headers, verbatim
+    pub res_head: Option<String>,
+    pub res_body: Option<String>,
+}
+
+fn is_status_line(line: &str) -> bool {
+    let mut parts = line.split_whitespace();
+    matches!(
+        (parts.next(), parts.next()),
+        (Some(v), Some(code)) if v.starts_with("HTTP/") && code.len() == 3 && code.chars().all(|c| c.is_ascii_digit())
+    )
+}
+
+/// Headers of a body that still begins with its HTTP head (a request or
+/// status line, then header lines), as raw captures used to be stored.
+pub fn leading_header_block(text: &str) -> Option<HashMap<String, String>> {
+    let normalized = text.replace("\r\n", "\n");
+    let mut lines = normalized.trim_start().lines();
+    let first = lines.next()?;
+    let is_request_line = first.split_whitespace().nth(2).is_some_and(|v| v.starts_with("HTTP/"));
+    if !is_request_line && !is_status_line(first) {
+        return None;
+    }
+    let headers: HashMap<String, String> = parse_header_lines(lines.take_while(|l| !l.trim().is_empty()))
+        .into_iter()
+        .collect();
+    (!headers.is_empty()).then_some(headers)
+}
+
+/// Collapses repeated header names into one comma-separated value, for the
+/// JSON object the headers are stored as.
+pub fn fold_headers(pairs: Vec<(String, String)>) -> HashMap<String, String> {
+    let mut folded: HashMap<String, String> = HashMap::new();
+    for (name, value) in pairs {
+        match folded.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(&name)) {
+            Some((_, existing)) => {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            None => {
+                folded.insert(name, value);
+            }
+        }
+    }
+    folded
+}
+
+fn parse_header_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<(String, String)> {
+    lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+fn non_empty_body(lines: &[&str]) -> Option<String> {
+    let body = lines.join("\n");
+    let body = body.trim_matches('\n');
+    if body.trim().is_empty() {
+        None
+    } else {
+        Some(body.to_string())
+    }
+}
+
+/// Parses a raw HTTP/1.x request (origin- or absolute-form) with an optional
+/// response following it. The URL is rebuilt from the request target and Host.
+pub fn parse_raw_http_message(input: &str) -> Result<RawHttpMessage> {
+    let normalized = input.replace("\r\n", "\n");
+    let lines: Vec<&str> = normalized.trim_start().lines().collect();
+
+    let request_line = lines.first().ok_or_else(|| anyhow::anyhow!("Empty HTTP message"))?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts
+        .next()
+        .filter(|m| m.chars().all(|c| c.is_ascii_uppercase()))
+        .ok_or_else(|| anyhow::anyhow!("Invalid request line: {}", request_line))?
+        .to_string();
+    let target = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Request line has no target: {}", request_line))?;
+
+    let head_end = lines.iter().position(|l| l.trim().is_empty()).unwrap_or(lines.len());
+    let req_headers = parse_header_lines(lines[1..head_end].iter().copied());
+
+    let rest = if head_end < lines.len() { &lines[head_end + 1..] } else { &[][..] };
+    let res_start = rest.iter().position(|l| is_status_line(l));
+    let (req_body_lines, res_lines) = match res_start {
+        Some(i) => (&rest[..i], Some(&rest[i..])),
+        None => (rest, None),
+    };
+
+    let url = if target.contains("://") {
+        target.to_string()
+    } else {
+        let host = req_headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("host"))
+            .map(|(_, v)| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Request has no Host header to build a URL from"))?;
+        let scheme = if host.ends_with(":80") { "http" } else { "https" };
+        let host = host.strip_suffix(":443").unwrap_or(host);
+        format!("{}://{}{}", scheme, host, target)
+    };
+
+    let (status_code, res_head, res_body) = match res_lines {
+        Some(res) => {
+            let status_code = res[0].split_whitespace().nth(1).and_then(|c| c.parse::<i64>().ok());
+            let end = res.iter().position(|l| l.trim().is_empty()).unwrap_or(res.len());
+            let body = if end < res.len() { non_empty_body(&res[end + 1..]) } else { None };
+            (status_code, Some(res[..end].join("\n")), body)
+        }
+        None => (None, None, None),
+    };
+
+    Ok(RawHttpMessage {
+        method,
+        url,
+        req_headers,
+        req_body: non_empty_body(req_body_lines),
+        status_code,
+        res_head,
+        res_body,
+    })
+}
+
+/// Splits a sitemap into page URLs (`<urlset>`) and child sitemap URLs
+/// (`<sitemapindex>`). Entities in `<loc>` are unescaped.
+pub fn sitemap_locations(content: &str) -> (Vec<String>, Vec<String>) {
+    let block_re = Regex::new(r"(?is)<(url|sitemap)\b[^>]*>(.*?)</(?:url|sitemap)>").unwrap();
+    let loc_re = Regex::new(r"(?is)<loc>\s*(?:<!\[CDATA\[)?(.*?)(?:\]\]>)?\s*</loc>").unwrap();
+
+    let mut urls = Vec::new();
+    let mut sitemaps = Vec::new();
+    for block in block_re.captures_iter(content) {
+        let Some(loc) = loc_re.captures(&block[2]) else { continue };
+        let loc = loc[1]
+            .trim()
+            .replace("&amp;", "&")
+            .replace("&apos;", "'")
+            .replace("&quot;", "\"")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">");
+        if loc.is_empty() {
+            continue;
+        }
+        if block[1].eq_ignore_ascii_case("sitemap") {
+            sitemaps.push(loc);
+        } else {
+            urls.push(loc);
+        }
+    }
+    (urls, sitemaps)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RobotsRule {
+    /// Allow/Disallow path, wildcards stripped
+    Path(String),
+    Sitemap(String),
+}
+
+pub fn robots_rules(content: &str) -> Vec<RobotsRule> {
+    let mut rules = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let Some((field, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match field.trim().to_ascii_lowercase().as_str() {
+            "allow" | "disallow" => {
+                let path = value.split(['*', '$']).next().unwrap_or("");
+                // An empty Disallow means "allow everything"; "/" adds nothing new
+                if path.len() > 1 && path.starts_with('/') {
+                    rules.push(RobotsRule::Path(path.to_string()));
+                }
+            }
+            "sitemap" if !value.is_empty() => rules.push(RobotsRule::Sitemap(value.to_string())),
+            _ => {}
+        }
+    }
+    rules
+}
+
+/// Extensions read as plain text when no structured format matches.
+const TEXT_EXTENSIONS: &[&str] = &["txt", "log", "md", "csv", "http", "sh"];
+
+fn collect_files(dir: &std::path::Path, recursive: bool, out: &mut Vec<std::path::PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        // Skip dotfiles and VCS/tooling folders
+        if path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.')) {
+            continue;
+        }
+        if path.is_dir() {
+            if recursive {
+                collect_files(&path, recursive, out)?;
+            }
+        } else if path.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Picks a parser for a file from its extension, sniffing the content of
+/// ambiguous JSON/YAML/XML/text files. Returns `None` for files to skip.
+pub fn detect_file_format(path: &std::path::Path) -> Result<Option<&'static str>> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "har" => return Ok(Some("har")),
+        "xlsx" => return Ok(Some("excel")),
+        "proto" => return Ok(Some("proto")),
+        "json" | "yaml" | "yml" | "xml" => {}
+        e if TEXT_EXTENSIONS.contains(&e) => {}
+        _ => return Ok(None),
+    }
+
+    // Only the head of the file is needed to tell formats apart
+    let mut head = Vec::new();
+    std::io::Read::read_to_end(&mut std::io::Read::take(std::fs::File::open(path)?, 64 * 1024), &mut head)?;
+    Ok(Some(sniff_format(&ext, &String::from_utf8_lossy(&head))))
+}
+
+/// Guesses the parser for `head` (the start of a file or response body),
+/// using `ext` to break ties between JSON, YAML, XML and plain text.
+pub fn sniff_format(ext: &str, head: &str) -> &'static str {
+    let trimmed = head.trim_start();
+    match ext {
+        "xml" if head.contains("<items") => "burp",
+        "xml" if head.contains("<urlset") || head.contains("<sitemapindex") => "sitemap",
+        "json" if head.contains("\"log\"") && head.contains("\"entries\"") => "har",
+        "json" if head.contains("schema.getpostman.com") || head.contains("\"_postman_id\"") => "postman",
+        "json" | "yaml" | "yml"
+            if head.contains("\"openapi\"")
+                || head.contains("\"swagger\"")
+                || trimmed.starts_with("openapi:")
+                || trimmed.starts_with("swagger:") =>
+        {
+            "openapi"
+        }
+        _ if trimmed.starts_with("curl ") => "curl",
+        _ if trimmed.lines().next().is_some_and(is_request_line) => "raw-http",
+        _ => "text",
+    }
+}
+
+fn is_request_line(line: &str) -> bool {
+    let mut parts = line.split_whitespace();
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(m), Some(_), Some(v)) if v.starts_with("HTTP/") && m.chars().all(|c| c.is_ascii_uppercase())
+    )
+}
+
+/// Converts one HAR `log.entries[]` item; entries missing a request or
+/// response are skipped.
+fn har_entry(entry: &serde_json::Value) -> Option<ImportEntry> {
+    let req = entry.get("request")?;
+    let res = entry.get("response")?;
+
+    let url = req
+        .get("url")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let method = req
+        .get("method")
+        .and_then(|v| v.as_str())
+        .unwrap_or("GET")
+        .to_string();
+    let status_code = res.get("status").and_then(|v| v.as_i64());
+    let req_headers = har_headers(req);
+    let res_headers = har_headers(res);
+
+    let req_body = req
+        .get("postData")
+        .and_then(|p| p.get("text"))
+        .and_then(|t| t.as_str())
+        .map(|s| s.to_string());
+    let res_body = res
+        .get("content")
+        .and_then(|c| c.get("text"))
+        .and_then(|t| t.as_str())
+        .map(|s| s.to_string());
+
+    Some(ImportEntry {
+        url,
+        method,
+        status_code,
+        req_headers,
+        res_headers,
+        req_body,
+        res_body,
+        findings: Vec::new(),
+    })
+}
+
+fn har_headers(message: &serde_json::Value) -> Vec<(String, String)> {
+    message
+        .get("headers")
+        .and_then(|h| h.as_array())
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|h| {
+                    let name = h.get("name")?.as_str()?;
+                    let value = h.get("value")?.as_str()?;
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Counts bytes pulled from the underlying file so streaming imports can
+/// report progress without knowing the entry count up front.
+struct ProgressReader<'a, R> {
+    inner: R,
+    bytes_read: &'a AtomicU64,
+}
+
+impl<R: std::io::Read> std::io::Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+type HarEntrySink<'a> = &'a mut dyn FnMut(serde_json::Value) -> std::result::Result<(), String>;
+
+/// Walks `{ "log": { "entries": [...] } }` handing each entry to the sink as
+/// soon as it is deserialized; every other field is skipped without buffering.
+struct HarRootSeed<'a>(HarEntrySink<'a>);
+struct HarLogSeed<'a>(HarEntrySink<'a>);
+struct HarEntriesSeed<'a>(HarEntrySink<'a>);
+
+impl<'de> DeserializeSeed<'de> for HarRootSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for HarRootSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a HAR document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "log" {
+                map.next_value_seed(HarLogSeed(&mut *self.0))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for HarLogSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for HarLogSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a HAR log object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "entries" {
+                map.next_value_seed(HarEntriesSeed(&mut *self.0))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for HarEntriesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for HarEntriesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an array of HAR entries")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        while let Some(entry) = seq.next_element::<serde_json::Value>()? {
+            (self.0)(entry).map_err(serde::de::Error::custom)?;
+        }
+        Ok(())
+    }
+}
+
+fn json_scalar(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+static POSTMAN_VAR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap());
+
+/// Replaces `{{name}}` placeholders with known variables, leaving unknown ones intact.
+fn resolve_postman_vars(input: &str, vars: &HashMap<String, String>) -> String {
+    POSTMAN_VAR.replace_all(input, |caps: &regex::Captures| {
+        vars.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+    })
+    .into_owned()
+}
+
+/// Reads a parameter from an auth block, accepting both the v2.1 list form
+/// (`[{key, value}]`) and the v2.0 object form.
+fn postman_auth_param(auth: &serde_json::Value, kind: &str, key: &str) -> Option<String> {
+    let params = auth.get(kind)?;
+    if let Some(list) = params.as_array() {
+        list.iter()
+            .find(|p| p.get("key").and_then(|k| k.as_str()) == Some(key))
+            .and_then(|p| p.get("value"))
+            .map(json_scalar)
+    } else {
+        params.get(key).map(json_scalar)
+    }
+}
+
+fn postman_url(url: &serde_json::Value) -> String {
+    if let Some(href) = url.as_str() {
+        return href.to_string();
+    }
+    if let Some(raw) = url.get("raw").and_then(|r| r.as_str()) {
+        return raw.to_string();
+    }
+
+    let join = |v: Option<&serde_json::Value>, sep: &str| -> String {
+        match v {
+            Some(serde_json::Value::Array(parts)) => parts.iter().map(json_scalar).collect::<Vec<_>>().join(sep),
+            Some(other) => json_scalar(other),
+            None => String::new(),
+        }
+    };
+    let host = join(url.get("host"), ".");
+    if host.is_empty() {
+        return "unknown".to_string();
+    }
+    let mut built = format!(
+        "{}://{}",
+        url.get("protocol").and_then(|p| p.as_str()).unwrap_or("https"),
+        host
+    );
+    if let Some(port) = url.get("port") {
+        built.push_str(&format!(":{}", json_scalar(port)));
+    }
+    let path = join(url.get("path"), "/");
+    if !path.is_empty() {
+        built.push('/');
+        built.push_str(path.trim_start_matches('/'));
+    }
+    let query: Vec<String> = url
+        .get("query")
+        .and_then(|q| q.as_array())
+        .map(|params| {
+            params
+                .iter()
+                .filter(|p| !p.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false))
+                .filter_map(|p| {
+                    let key = p.get("key")?.as_str()?;
+                    let value = p.get("value").map(json_scalar).unwrap_or_default();
+                    Some(format!("{}={}", key, value))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if !query.is_empty() {
+        built.push('?');
+        built.push_str(&query.join("&"));
+    }
+    built
+}
+
+fn postman_body(body: &serde_json::Value) -> Option<String> {
+    let pairs = |field: &str| -> Option<String> {
+        let list = body.get(field)?.as_array()?;
+        Some(
+            list.iter()
+                .filter(|p| !p.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false))
+                .filter_map(|p| {
+                    let key = p.get("key")?.as_str()?;
+                    Some(format!("{}={}", key, p.get("value").map(json_scalar).unwrap_or_default()))
+                })
+                .collect::<Vec<_>>()
+                .join("&"),
+        )
+    };
+    match body.get("mode").and_then(|m| m.as_str()).unwrap_or("raw") {
+        "urlencoded" => pairs("urlencoded"),
+        "formdata" => pairs("formdata"),
+        "graphql" => {
+            let gql = body.get("graphql")?;
+            let variables = gql
+                .get("variables")
+                .and_then(|v| v.as_str())
+                .and_then(|v| serde_json::from_str::<serde_json::Value>(v).ok())
+                .unwrap_or(serde_json::Value::Null);
+            Some(serde_json::json!({ "query": gql.get("query"), "variables": variables }).to_string())
+        }
+        _ => body.get("raw").and_then(|r| r.as_str()).map(|s| s.to_string()),
+    }
+    .filter(|b| !b.is_empty())
+}
+
+fn postman_entry(
+    item: &serde_json::Value,
+    request: &serde_json::Value,
+    collection_auth: Option<&serde_json::Value>,
+    vars: &HashMap<String, String>,
+) -> ImportEntry {
+    let method = request
+        .get("method")
+        .and_then(|m| m.as_str())
+        .unwrap_or("GET")
+        .to_string();
+    let mut url = request.get("url").map(postman_url).unwrap_or_else(|| "unknown".to_string());
+
+    let mut req_headers: Vec<(String, String)> = Vec::new();
+    match request.get("header") {
+        Some(serde_json::Value::Array(headers)) => {
+            for h in headers {
+                if h.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false) {
+                    continue;
+                }
+                if let (Some(k), Some(v)) = (h.get("key").and_then(|k| k.as_str()), h.get("value")) {
+                    req_headers.push((k.to_string(), resolve_postman_vars(&json_scalar(v), vars)));
+                }
+            }
+        }
+        Some(serde_json::Value::String(raw)) => {
+            for (k, v) in parse_header_lines(raw.lines()) {
+                req_headers.push((k, resolve_postman_vars(&v, vars)));
+            }
+        }
+        _ => {}
+    }
+
+    // Request-level auth overrides whatever the collection/folders define
+    let auth = request.get("auth").filter(|a| !a.is_null()).or(collection_auth);
+    let mut set_header = |name: String, value: String| {
+        req_headers.retain(|(k, _)| !k.eq_ignore_ascii_case(&name));
+        req_headers.push((name, value));
+    };
+    if let Some(auth) = auth {
+        let param = |kind: &str, key: &str| postman_auth_param(auth, kind, key).map(|v| resolve_postman_vars(&v, vars));
+        match auth.get("type").and_then(|t| t.as_str()).unwrap_or("") {
+            "bearer" => {
+                if let Some(token) = param("bearer", "token") {
+                    set_header("Authorization".to_string(), format!("Bearer {}", token));
+                }
+            }
+            "basic" => {
+                use base64::{engine::general_purpose, Engine as _};
+                let user = param("basic", "username").unwrap_or_default();
+                let pass = param("basic", "password").unwrap_or_default();
+                let encoded = general_purpose::STANDARD.encode(format!("{}:{}", user, pass));
+                set_header("Authorization".to_string(), format!("Basic {}", encoded));
+            }
+            "apikey" => {
+                let key = param("apikey", "key").unwrap_or_else(|| "X-API-Key".to_string());
+                let value = param("apikey", "value").unwrap_or_default();
+                if param("apikey", "in").as_deref() == Some("query") {
+                    url.push(if url.contains('?') { '&' } else { '?' });
+                    url.push_str(&format!("{}={}", key, value));
+                } else {
+                    set_header(key, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let req_body = request
+        .get("body")
+        .and_then(postman_body)
+        .map(|b| resolve_postman_vars(&b, vars));
+
+    let example = item
+        .get("response")
+        .and_then(|r| r.as_array())
+        .and_then(|r| r.first());
+    let status_code = example.and_then(|e| e.get("code")).and_then(|c| c.as_i64());
+    let res_body = example
+        .and_then(|e| e.get("body"))
+        .and_then(|b| b.as_str())
+        .filter(|b| !b.is_empty())
+        .map(|b| b.to_string());
+    let res_headers = example
+        .and_then(|e| e.get("header"))
+        .and_then(|h| h.as_array())
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|h| Some((h.get("key")?.as_str()?.to_string(), json_scalar(h.get("value")?))))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ImportEntry {
+        url: resolve_postman_vars(&url, vars),
+        method,
+        status_code,
+        req_headers,
+        res_headers,
+        req_body,
+        res_body,
+        findings: Vec::new(),
+    }
 }
 
 fn base64_decode(input: &str) -> Result<String> {
@@ -326,3 +1832,212 @@ fn base64_decode(input: &str) -> Result<String> {
     let bytes = general_purpose::STANDARD.decode(input.replace("\n", "").replace("\r", ""))?;
     Ok(String::from_utf8_lossy(&bytes).to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sitemap_and_robots_rules() {
+        let sitemap = "<urlset><url><loc>https://a.example/docs?x=1&amp;y=2</loc></url></urlset>\
+            <sitemapindex><sitemap><loc><![CDATA[https://a.example/sm2.xml]]></loc></sitemap></sitemapindex>";
+        let (urls, sitemaps) = sitemap_locations(sitemap);
+        assert_eq!(urls, vec!["https://a.example/docs?x=1&y=2"]);
+        assert_eq!(sitemaps, vec!["https://a.example/sm2.xml"]);
+
+        let robots = "User-agent: *\nDisallow: /admin/*.php # old panel\nDisallow:\nAllow: /\nAllow: /api/v2$\nSitemap: https://a.example/sitemap.xml";
+        assert_eq!(
+            robots_rules(robots),
+            vec![
+                RobotsRule::Path("/admin/".to_string()),
+                RobotsRule::Path("/api/v2".to_string()),
+                RobotsRule::Sitemap("https://a.example/sitemap.xml".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(sniff_format("json", r#"{"log": {"version": "1.2", "entries": []}}"#), "har");
+        assert_eq!(sniff_format("json", r#"{"info": {"schema": "https://schema.getpostman.com/json/collection/v2.1.0/"}}"#), "postman");
+        assert_eq!(sniff_format("yaml", "openapi: 3.0.0\ninfo: {}"), "openapi");
+        assert_eq!(sniff_format("xml", "<?xml version=\"1.0\"?><items burpVersion=\"2023\">"), "burp");
+        assert_eq!(sniff_format("txt", "curl -X GET https://a.example"), "curl");
+        assert_eq!(sniff_format("http", "POST /login HTTP/1.1\nHost: a.example"), "raw-http");
+        assert_eq!(sniff_format("txt", "see https://a.example/api"), "text");
+    }
+
+    #[test]
+    fn test_postman_entry_resolves_auth_vars_and_example() {
+        let collection: serde_json::Value = serde_json::from_str(r#"{
+            "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}"}]},
+            "item": [{
+                "name": "Get user",
+                "request": {
+                    "method": "GET",
+                    "header": [{"key": "X-Trace", "value": "1"}, {"key": "X-Trace", "value": "2"},
+                               {"key": "Authorization", "value": "stale"}, {"key": "X-Off", "value": "x", "disabled": true}],
+                    "url": {"protocol": "https", "host": ["{{host}}"], "path": ["users", ":id"],
+                            "query": [{"key": "expand", "value": "all"}]}
+                },
+                "response": [{"code": 200, "body": "{\"id\": 1}"}]
+            }]
+        }"#).unwrap();
+        let vars = HashMap::from([
+            ("host".to_string(), "api.example.com".to_string()),
+            ("token".to_string(), "s3cr3t".to_string()),
+        ]);
+        let item = &collection["item"][0];
+        let entry = postman_entry(item, &item["request"], collection.get("auth"), &vars);
+        assert_eq!(entry.url, "https://api.example.com/users/:id?expand=all");
+        assert_eq!(
+            entry.req_headers,
+            vec![
+                ("X-Trace".to_string(), "1".to_string()),
+                ("X-Trace".to_string(), "2".to_string()),
+                ("Authorization".to_string(), "Bearer s3cr3t".to_string()),
+            ]
+        );
+        assert_eq!(fold_headers(entry.req_headers).get("X-Trace").map(String::as_str), Some("1, 2"));
+        assert_eq!(entry.status_code, Some(200));
+        assert_eq!(entry.res_body.as_deref(), Some("{\"id\": 1}"));
+    }
+
+    #[test]
+    fn test_parse_openapi_servers_and_examples() {
+        let spec = r#"{
+            "openapi": "3.0.0",
+            "servers": [
+                {"url": "https://{env}.example.com:{port}/v1", "variables": {"env": {"default": "api"}, "port": {"default": "8443"}}},
+                {"url": "/internal"}
+            ],
+            "paths": {
+                "/users/{id}": {
+                    "get": {"responses": {"404": {}, "200": {"content": {"application/json": {"example": {"id": 1}}}}}}
+                },
+                "/users": {
+                    "post": {
+                        "requestBody": {"content": {"application/json": {"examples": {"basic": {"value": {"name": "a"}}}}}},
+                        "responses": {"201": {"content": {"application/xml": {"schema": {"example": "<user/>"}}}}}
+                    }
+                }
+            }
+        }"#;
+        let result = Parser::parse_openapi(spec, &[], &[]).unwrap();
+        assert_eq!(result.source_type, "openapi");
+        assert_eq!(result.entries.len(), 4);
+        let urls: Vec<&str> = result.entries.iter().map(|e| e.url.as_str()).collect();
+        assert!(urls.contains(&"https://api.example.com:8443/v1/users/{id}"));
+        assert!(urls.contains(&"http://localhost/internal/users"));
+
+        let get = result.entries.iter().find(|e| e.method == "GET").unwrap();
+        assert_eq!(get.res_body.as_deref().map(|b| serde_json::from_str::<serde_json::Value>(b).unwrap()), Some(serde_json::json!({"id": 1})));
+        let post = result.entries.iter().find(|e| e.method == "POST").unwrap();
+        assert_eq!(post.req_body.as_deref().map(|b| serde_json::from_str::<serde_json::Value>(b).unwrap()), Some(serde_json::json!({"name": "a"})));
+        assert_eq!(post.res_body.as_deref(), Some("<user/>"));
+    }
+
+    #[test]
+    fn test_parse_swagger2_host_and_examples() {
+        let spec = "swagger: '2.0'\nhost: api.example.com\nbasePath: /v2\nschemes: [http, https]\npaths:\n  /search:\n    post:\n      parameters:\n        - in: query\n          name: q\n        - in: body\n          name: body\n          schema:\n            example: {term: x}\n      responses:\n        '200':\n          examples:\n            application/json: {ok: true}\n";
+        let result = Parser::parse_openapi(spec, &[], &[]).unwrap();
+        assert_eq!(result.entries.len(), 1);
+        let entry = &result.entries[0];
+        assert_eq!(entry.url, "http://api.example.com/v2/search");
+        assert_eq!(entry.req_body.as_deref().map(|b| serde_json::from_str::<serde_json::Value>(b).unwrap()), Some(serde_json::json!({"term": "x"})));
+        assert_eq!(entry.res_body.as_deref().map(|b| serde_json::from_str::<serde_json::Value>(b).unwrap()), Some(serde_json::json!({"ok": true})));
+
+        let bare = Parser::parse_openapi(r#"{"paths": {"/health": {"get": {}}}}"#, &[], &[]).unwrap();
+        assert_eq!(bare.entries[0].url, "http://localhost/health");
+        assert!(bare.entries[0].req_body.is_none() && bare.entries[0].res_body.is_none());
+        assert!(Parser::parse_openapi(r#"{"openapi": "3.0.0"}"#, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_scan_entries_in_parallel_matches_serial_scan() {
+        let mut entries: Vec<ImportEntry> = (0..200u32)
+            .map(|i| ImportEntry {
+                url: format!("https://api.example.com/users/{}?contact=user{}@example.com", i, i),
+                method: "GET".to_string(),
+                status_code: Some(200),
+                req_headers: Vec::new(),
+                res_headers: Vec::new(),
+                req_body: None,
+                res_body: i.is_multiple_of(2).then(|| format!("{{\"email\": \"owner{}@example.org\"}}", i)),
+                findings: Vec::new(),
+            })
+            .collect();
+        let expected: Vec<usize> = entries
+            .iter()
+            .map(|e| {
+                analysis::Scanner::scan_text(&e.url, &[], &[]).len()
+                    + e.res_body.as_deref().map_or(0, |b| analysis::Scanner::scan_text(b, &[], &[]).len())
+            })
+            .collect();
+
+        let reported = std::sync::Mutex::new(Vec::new());
+        Parser::scan_entries(&mut entries, &[], &[], &|scanned, total| reported.lock().unwrap().push((scanned, total)));
+
+        assert_eq!(entries.iter().map(|e| e.findings.len()).collect::<Vec<_>>(), expected);
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(reported, (1..=200).map(|n| (n, 200)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_parse_curl_multiline_with_headers_and_body() {
+        let cmd = "curl -X PUT 'https://api.example.com/users/1' \\\n  -H 'Authorization: Bearer abc' \\\n  -H \"Content-Type: application/json\" \\\n  --data-raw '{\"name\": \"o'\\''brien\"}'";
+        let req = parse_curl_command(cmd).unwrap();
+        assert_eq!(req.method, "PUT");
+        assert_eq!(req.url, "https://api.example.com/users/1");
+        assert_eq!(req.headers.len(), 2);
+        assert_eq!(req.headers[0], ("Authorization".to_string(), "Bearer abc".to_string()));
+        assert_eq!(req.body.as_deref(), Some("{\"name\": \"o'brien\"}"));
+    }
+
+    #[test]
+    fn test_parse_curl_data_defaults_and_urlencode() {
+        let req = parse_curl_command("curl -sSL example.com/login -d user=a --data-urlencode 'q=a b&c'").unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.url, "http://example.com/login");
+        assert_eq!(req.body.as_deref(), Some("user=a&q=a+b%26c"));
+
+        let get = parse_curl_command("curl -G https://example.com/search --data-urlencode 'term=x y'").unwrap();
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "https://example.com/search?term=x+y");
+        assert!(get.body.is_none());
+    }
+
+    #[test]
+    fn test_parse_curl_form_builds_multipart() {
+        let req = parse_curl_command("curl https://example.com/upload -F 'note=hi there' -F 'doc=@/tmp/report.pdf;type=application/pdf'").unwrap();
+        assert_eq!(req.method, "POST");
+        let (_, content_type) = req.headers.iter().find(|(k, _)| k == "Content-Type").unwrap();
+        assert_eq!(content_type, &format!("multipart/form-data; boundary={}", CURL_FORM_BOUNDARY));
+        let body = req.body.unwrap();
+        assert!(body.contains("Content-Disposition: form-data; name=\"note\"\r\n\r\nhi there\r\n"));
+        assert!(body.contains("name=\"doc\"; filename=\"report.pdf\"\r\nContent-Type: application/pdf\r\n"));
+        assert!(body.ends_with(&format!("--{}--\r\n", CURL_FORM_BOUNDARY)));
+        assert!(!body.contains("&"));
+    }
+
+    #[test]
+    fn test_parse_curl_non_ascii_short_option() {
+        let req = parse_curl_command("curl -é https://example.com/ -Hé:ü").unwrap();
+        assert_eq!(req.url, "https://example.com/");
+        assert_eq!(req.headers, vec![("é".to_string(), "ü".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_raw_http_request_and_response() {
+        let raw = "POST /api/login?next=1 HTTP/1.1\r\nHost: shop.example.com\r\nContent-Type: application/json\r\n\r\n{\"user\":\"a\"}\r\n\r\nHTTP/1.1 401 Unauthorized\r\nServer: nginx\r\n\r\n{\"error\":\"bad\"}";
+        let msg = parse_raw_http_message(raw).unwrap();
+        assert_eq!(msg.method, "POST");
+        assert_eq!(msg.url, "https://shop.example.com/api/login?next=1");
+        assert_eq!(msg.req_headers.len(), 2);
+        assert_eq!(msg.req_body.as_deref(), Some("{\"user\":\"a\"}"));
+        assert_eq!(msg.status_code, Some(401));
+        assert_eq!(msg.res_head.as_deref(), Some("HTTP/1.1 401 Unauthorized\nServer: nginx"));
+        assert_eq!(msg.res_body.as_deref(), Some("{\"error\":\"bad\"}"));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::import_engine::{ImportResult, Parser};
+use crate::ImportState;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::Emitter;
+
+/// Only the first errors are kept so a broken 100k-entry import can't bloat the job record.
+const MAX_JOB_ERRORS: usize = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportJob {
+    pub id: String,
+    pub source_type: String,
+    pub status: String, // "queued", "parsing", "importing", "completed", "failed", "cancelled"
+    pub current: usize,
+    pub total: usize,
+    pub added: i32,
+    pub skipped: i32,
+    pub errors: Vec<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Either `content` (pasted/uploaded text) or `path` (a file or, for
+/// `source_type` "directory", a folder) must be provided.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportJobRequest {
+    pub source_type: String,
+    pub content: Option<String>,
+    pub path: Option<String>,
+    /// Asset source label; defaults to `source_type`
+    pub source: Option<String>,
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+fn update_job(app: &tauri::AppHandle, state: &ImportState, id: &str, f: impl FnOnce(&mut ImportJob)) {
+    if let Some(mut job) = state.jobs.get_mut(id) {
+        f(&mut job);
+        let _ = app.emit("import-job-progress", job.clone());
+    }
+}
+
+fn record_error(job: &mut ImportJob, error: String) {
+    if job.errors.len() < MAX_JOB_ERRORS {
+        job.errors.push(error);
+    }
+}
+
+async fn parse_job_input(
+    app: &tauri::AppHandle,
+    request: ImportJobRequest,
+    cancel: &Arc<AtomicBool>,
+) -> Result<(ImportResult, Vec<String>), String> {
+    match (request.content, request.path) {
+        (Some(content), _) => Ok((crate::parse_content(app.clone(), content, request.source_type, None).await?, Vec::new())),
+        (None, Some(path)) => {
+            let custom_rules = crate::db::get_custom_rules().await?;
+            let plugins = crate::plugins::load_plugins(app);
+            let env_vars = crate::environments::active_variables().await?;
+            let cancel = cancel.clone();
+            let source_type = request.source_type;
+            let recursive = request.recursive;
+            tauri::async_runtime::spawn_blocking(move || match source_type.as_str() {
+                "har" => Parser::parse_har_file(&path, &custom_rules, &plugins, &cancel, &|_, _, _| {})
+                    .map(|r| (r, Vec::new())),
+                "excel" => Parser::parse_excel(&path, &custom_rules, &plugins).map(|r| (r, Vec::new())),
+                "directory" => Parser::parse_directory(&path, recursive, &env_vars, &custom_rules, &plugins).map(|d| {
+                    let errors = d
+                        .files
+                        .iter()
+                        .filter_map(|f| f.error.as_ref().map(|e| format!("{}: {}", f.path, e)))
+                        .collect();
+                    (d.result, errors)
+                }),
+                _ => Err(anyhow::anyhow!("Use `content` for {} imports", source_type)),
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())
+        }
+        (None, None) => Err("Import job needs either content or a path".to_string()),
+    }
+}
+
+async fn run_job(app: tauri::AppHandle, state: Arc<ImportState>, id: String, request: ImportJobRequest, cancel: Arc<AtomicBool>) {
+    let source = request.source.clone().unwrap_or_else(|| request.source_type.clone());
+    update_job(&app, &state, &id, |job| job.status = "parsing".to_string());
+
+    let outcome = match parse_job_input(&app, request, &cancel).await {
+        Ok((parsed, parse_errors)) if !cancel.load(Ordering::Relaxed) => {
+            update_job(&app, &state, &id, |job| {
+                job.status = "importing".to_string();
+                job.total = parsed.entries.len();
+                for e in parse_errors {
+                    record_error(job, e);
+                }
+            });
+
+            let progress_app = app.clone();
+            let progress_state = state.clone();
+            let progress_id = id.clone();
+            let on_progress: crate::assets::ImportProgressFn = Arc::new(move |current, total| {
+                if current == total || current.is_multiple_of(50) {
+                    update_job(&progress_app, &progress_state, &progress_id, |job| job.current = current);
+                }
+            });
+            crate::assets::import_entries(parsed.entries, source, cancel.clone(), on_progress).await
+        }
+        Ok(_) => Ok((crate::assets::BatchImportResult { added: 0, skipped: 0 }, Vec::new())),
+        Err(e) => Err(e),
+    };
+
+    let cancelled = cancel.load(Ordering::Relaxed);
+    update_job(&app, &state, &id, |job| {
+        match outcome {
+            Ok((result, errors)) => {
+                job.added = result.added;
+                job.skipped = result.skipped;
+                for e in errors {
+                    record_error(job, e);
+                }
+                job.status = if cancelled { "cancelled" } else { "completed" }.to_string();
+            }
+            Err(e) => {
+                record_error(job, e);
+                job.status = if cancelled { "cancelled" } else { "failed" }.to_string();
+            }
+        }
+        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+
+    state.cancel_flags.remove(&id);
+    if let Some(job) = state.jobs.get(&id) {
+        let _ = app.emit("import-job-complete", job.clone());
+    }
+}
+
+/// Queues a parse-and-import on a background task and returns its job id
+/// immediately. Progress is reported through `import-job-progress` events
+/// and `get_import_job_status`; `import-job-complete` fires at the end.
+#[tauri::command]
+pub async fn start_import_job(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<ImportState>>,
+    request: ImportJobRequest,
+) -> Result<String, String> {
+    if request.content.is_none() && request.path.is_none() {
+        return Err("Import job needs either content or a path".to_string());
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    state.cancel_flags.insert(id.clone(), cancel.clone());
+    state.jobs.insert(
+        id.clone(),
+        ImportJob {
+            id: id.clone(),
+            source_type: request.source_type.clone(),
+            status: "queued".to_string(),
+            current: 0,
+            total: 0,
+            added: 0,
+            skipped: 0,
+            errors: Vec::new(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+        },
+    );
+
+    let state = state.inner().clone();
+    let job_id = id.clone();
+    tauri::async_runtime::spawn(async move {
+        run_job(app, state, job_id, request, cancel).await;
+    });
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn get_import_job_status(state: tauri::State<'_, Arc<ImportState>>, job_id: String) -> Result<ImportJob, String> {
+    state
+        .jobs
+        .get(&job_id)
+        .map(|job| job.clone())
+        .ok_or_else(|| format!("Unknown import job {}", job_id))
+}
+
+#[tauri::command]
+pub fn list_import_jobs(state: tauri::State<'_, Arc<ImportState>>) -> Vec<ImportJob> {
+    let mut jobs: Vec<ImportJob> = state.jobs.iter().map(|j| j.clone()).collect();
+    jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    jobs
+}
+
+/// Stops a queued or running job; entries already written stay in the inventory.
+#[tauri::command]
+pub fn cancel_import_job(state: tauri::State<'_, Arc<ImportState>>, job_id: String) -> Result<(), String> {
+    match state.cancel_flags.get(&job_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!("Import job {} is not running", job_id)),
+    }
+}
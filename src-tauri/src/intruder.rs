@@ -0,0 +1,295 @@
+use crate::analysis::Finding;
+use crate::fuzz_baseline::{Baseline, ResponseDiff, BASELINE_SAMPLES};
+use crate::fuzzer::{FuzzSummary, FuzzTask, ProbeResponse, MARKER};
+use crate::pacing::Pacer;
+use crate::JobState;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// Upper bound on requests per attack, so a cluster bomb over long lists
+/// is rejected up front instead of running for days.
+const MAX_REQUESTS: usize = 100_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackStrategy {
+    /// One payload set; each position in turn, the others left original
+    Sniper,
+    /// One set per position, advanced together
+    Pitchfork,
+    /// One set per position, every combination
+    ClusterBomb,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtractRule {
+    pub name: String,
+    /// Regex; its first capture group, or the whole match, is kept
+    pub pattern: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct IntruderRequest {
+    /// Positions are marked with `§` as for fuzz runs
+    pub task: FuzzTask,
+    pub strategy: AttackStrategy,
+    pub payload_sets: Vec<Vec<String>>,
+    /// Regexes reported when they match a response
+    #[serde(default)]
+    pub grep_match: Vec<String>,
+    #[serde(default)]
+    pub grep_extract: Vec<ExtractRule>,
+    /// Also judge responses with this fuzz attack type's signatures
+    pub attack_type: Option<String>,
+    /// Scan profile the attack must be allowed by; the default one when unset
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IntruderResult {
+    pub index: usize,
+    /// Payload per position; `None` where the original value was sent
+    pub payloads: Vec<Option<String>>,
+    pub status: u16,
+    pub time_ms: u64,
+    pub length: usize,
+    /// `grep_match` patterns found in the response
+    pub matches: Vec<String>,
+    pub extracts: HashMap<String, String>,
+    pub finding: Option<Finding>,
+    pub diff: Option<ResponseDiff>,
+}
+
+/// Payloads per request for `positions` marked positions.
+pub fn combinations(strategy: AttackStrategy, positions: usize, sets: &[Vec<String>]) -> Result<Vec<Vec<Option<String>>>, String> {
+    if positions == 0 {
+        return Err(format!("Mark at least one position with {}", MARKER));
+    }
+    if sets.is_empty() || sets.iter().any(Vec::is_empty) {
+        return Err("Payload sets must not be empty".to_string());
+    }
+    let too_many = || format!("The attack would send more than {} requests", MAX_REQUESTS);
+    match strategy {
+        AttackStrategy::Sniper => {
+            let [set] = sets else {
+                return Err("Sniper takes exactly one payload set".to_string());
+            };
+            if positions * set.len() > MAX_REQUESTS {
+                return Err(too_many());
+            }
+            Ok((0..positions)
+                .flat_map(|target| set.iter().map(move |payload| (0..positions).map(|p| (p == target).then(|| payload.clone())).collect()))
+                .collect())
+        }
+        AttackStrategy::Pitchfork | AttackStrategy::ClusterBomb if sets.len() != positions => {
+            Err(format!("Expected one payload set per position ({} sets for {} positions)", sets.len(), positions))
+        }
+        AttackStrategy::Pitchfork => {
+            let rows = sets.iter().map(Vec::len).min().unwrap_or(0);
+            if rows > MAX_REQUESTS {
+                return Err(too_many());
+            }
+            Ok((0..rows).map(|row| sets.iter().map(|set| Some(set[row].clone())).collect()).collect())
+        }
+        AttackStrategy::ClusterBomb => {
+            let total = sets.iter().try_fold(1usize, |acc, set| acc.checked_mul(set.len())).filter(|n| *n <= MAX_REQUESTS).ok_or_else(too_many)?;
+            // Odometer with the last position turning fastest
+            Ok((0..total)
+                .map(|mut n| {
+                    let mut combo = vec![None; sets.len()];
+                    for (p, set) in sets.iter().enumerate().rev() {
+                        combo[p] = Some(set[n % set.len()].clone());
+                        n /= set.len();
+                    }
+                    combo
+                })
+                .collect())
+        }
+    }
+}
+
+/// Compiled grep-match and grep-extract rules.
+pub struct Grep {
+    matches: Vec<(String, Regex)>,
+    extracts: Vec<(String, Regex)>,
+}
+
+impl Grep {
+    pub fn new(matches: &[String], extracts: &[ExtractRule]) -> Result<Self, String> {
+        let compile = |pattern: &str| Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e));
+        Ok(Grep {
+            matches: matches.iter().map(|p| Ok((p.clone(), compile(p)?))).collect::<Result<_, String>>()?,
+            extracts: extracts.iter().map(|r| Ok((r.name.clone(), compile(&r.pattern)?))).collect::<Result<_, String>>()?,
+        })
+    }
+
+    /// Patterns that matched and values extracted from `response`.
+    pub fn apply(&self, response: &str) -> (Vec<String>, HashMap<String, String>) {
+        let matched = self.matches.iter().filter(|(_, re)| re.is_match(response)).map(|(p, _)| p.clone()).collect();
+        let extracted = self
+            .extracts
+            .iter()
+            .filter_map(|(name, re)| {
+                let caps = re.captures(response)?;
+                let value = caps.get(1).or_else(|| caps.get(0))?.as_str().to_string();
+                Some((name.clone(), value))
+            })
+            .collect();
+        (matched, extracted)
+    }
+}
+
+/// Headers then body, the text grep rules run against.
+fn response_text(headers: &HashMap<String, String>, body: &str) -> String {
+    let mut lines: Vec<String> = headers.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+    lines.sort();
+    format!("{}\r\n\r\n{}", lines.join("\r\n"), body)
+}
+
+async fn run_attack(
+    app_handle: tauri::AppHandle,
+    request: IntruderRequest,
+    combos: Vec<Vec<Option<String>>>,
+    grep: Grep,
+    cancel: &AtomicBool,
+) -> Result<Vec<IntruderResult>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let attack_type = request.attack_type.as_deref().unwrap_or("intruder");
+    let task = &request.task;
+    let mut pacer = Pacer::new(&task.pacing);
+
+    let original = crate::fuzzer::render_task(task, usize::MAX, "");
+    let mut samples = Vec::new();
+    let mut baseline_ms: Option<u64> = None;
+    for _ in 0..BASELINE_SAMPLES {
+        if let Ok((status, _, body, ms)) = crate::fuzzer::send_paced(&client, &original, attack_type, &mut pacer, cancel).await {
+            samples.push((status, body));
+            baseline_ms = Some(baseline_ms.map_or(ms, |b| b.min(ms)));
+        }
+    }
+    let baseline = Baseline::from_samples(samples);
+
+    let total = combos.len();
+    let mut results = Vec::new();
+    for (i, payloads) in combos.into_iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let positions: Vec<(usize, &str)> = payloads.iter().enumerate().filter_map(|(p, v)| Some((p, v.as_deref()?))).collect();
+        let rendered = crate::fuzzer::render_positions(task, &positions);
+        let mut res = IntruderResult {
+            index: i,
+            payloads: payloads.clone(),
+            status: 0,
+            time_ms: 0,
+            length: 0,
+            matches: Vec::new(),
+            extracts: HashMap::new(),
+            finding: None,
+            diff: None,
+        };
+        if let Ok((status, headers, body, ms)) = crate::fuzzer::send_paced(&client, &rendered, attack_type, &mut pacer, cancel).await {
+            (res.matches, res.extracts) = grep.apply(&response_text(&headers, &body));
+            if let Some(attack) = &request.attack_type {
+                let probe = ProbeResponse { status, headers: &headers, body: &body, time_ms: ms, baseline_ms };
+                res.finding = positions.iter().find_map(|(_, payload)| crate::fuzzer::detect(attack, payload, &probe));
+                crate::analysis::attach_rule_metadata(res.finding.as_mut_slice());
+            }
+            res.diff = baseline.as_ref().map(|b| b.diff(status, &body));
+            (res.status, res.time_ms, res.length) = (status, ms, body.len());
+        }
+        results.push(res.clone());
+        let _ = app_handle.emit("intruder-progress", (i + 1, total, res));
+    }
+    Ok(results)
+}
+
+/// Starts an intruder attack in the background and returns its job id.
+/// Results stream through `intruder-progress` events and a `FuzzSummary`
+/// arrives with `fuzz-complete`; `cancel_fuzz` stops it.
+#[tauri::command]
+pub async fn run_intruder(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<JobState>>, mut request: IntruderRequest) -> Result<String, String> {
+    let profile = crate::scan_profiles::authorize(request.scan_profile_id, crate::scan_profiles::ActiveCheck::Fuzz).await?;
+    request.task.pacing = profile.cap_pacing(&request.task.pacing);
+    crate::env_auth::EnvAuth::inject_active(&mut request.task.headers).await?;
+    crate::variables::Resolver::active().await?.defer_builtins().resolve_task(&mut request.task)?;
+    let positions = crate::fuzzer::insertion_points(&request.task)?.len();
+    let combos = combinations(request.strategy, positions, &request.payload_sets)?;
+    let grep = Grep::new(&request.grep_match, &request.grep_extract)?;
+    let total = combos.len();
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "fuzz-complete",
+        move |app_handle, _, cancel| async move { run_attack(app_handle, request, combos, grep, &cancel).await },
+        move |job_id, outcome, cancelled| {
+            let counts = outcome.map(|results| {
+                let findings = results.iter().filter(|r| r.finding.is_some()).count();
+                let interesting = results
+                    .iter()
+                    .filter(|r| r.finding.is_none() && (!r.matches.is_empty() || r.diff.as_ref().is_some_and(|d| d.interesting)))
+                    .count();
+                (results.len(), findings, interesting)
+            });
+            FuzzSummary::new(job_id, total, counts, cancelled)
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn flat(combos: Vec<Vec<Option<String>>>) -> Vec<String> {
+        combos.into_iter().map(|c| c.into_iter().map(|p| p.unwrap_or_else(|| "-".to_string())).collect::<Vec<_>>().join(",")).collect()
+    }
+
+    #[test]
+    fn test_combinations() {
+        let sniper = combinations(AttackStrategy::Sniper, 2, &[set(&["a", "b"])]).unwrap();
+        assert_eq!(flat(sniper), vec!["a,-", "b,-", "-,a", "-,b"]);
+        let pitchfork = combinations(AttackStrategy::Pitchfork, 2, &[set(&["u1", "u2", "u3"]), set(&["p1", "p2"])]).unwrap();
+        assert_eq!(flat(pitchfork), vec!["u1,p1", "u2,p2"]);
+        let bomb = combinations(AttackStrategy::ClusterBomb, 2, &[set(&["u1", "u2"]), set(&["p1", "p2", "p3"])]).unwrap();
+        assert_eq!(flat(bomb), vec!["u1,p1", "u1,p2", "u1,p3", "u2,p1", "u2,p2", "u2,p3"]);
+
+        assert!(combinations(AttackStrategy::Sniper, 0, &[set(&["a"])]).is_err());
+        assert!(combinations(AttackStrategy::Sniper, 1, &[set(&["a"]), set(&["b"])]).is_err());
+        assert!(combinations(AttackStrategy::Pitchfork, 3, &[set(&["a"]), set(&["b"])]).is_err());
+        let big: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        assert!(combinations(AttackStrategy::ClusterBomb, 2, &[big.clone(), big]).is_err());
+    }
+
+    #[test]
+    fn test_grep() {
+        let grep = Grep::new(
+            &set(&["(?i)welcome", "error"]),
+            &[
+                ExtractRule { name: "token".to_string(), pattern: r#""token":\s*"([^"]+)""#.to_string() },
+                ExtractRule { name: "server".to_string(), pattern: r"server: \S+".to_string() },
+            ],
+        )
+        .unwrap();
+        let headers = HashMap::from([("server".to_string(), "nginx".to_string())]);
+        let (matched, extracted) = grep.apply(&response_text(&headers, r#"{"msg": "Welcome back", "token": "abc123"}"#));
+        assert_eq!(matched, vec!["(?i)welcome"]);
+        assert_eq!(extracted["token"], "abc123");
+        assert_eq!(extracted["server"], "server: nginx");
+        assert!(Grep::new(&set(&["("]), &[]).is_err());
+    }
+}
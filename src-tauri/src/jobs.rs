@@ -0,0 +1,176 @@
+//! Background jobs started from commands: each gets an id and a cancel flag
+//! registered while it runs, and emits one completion event when it ends.
+
+use crate::JobState;
+use dashmap::mapref::entry::Entry;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::task::{JoinError, JoinSet};
+
+/// A running job's id and cancel flag, registered with `JobState` until
+/// dropped.
+pub struct Job {
+    pub id: String,
+    pub cancel: Arc<AtomicBool>,
+    state: Arc<JobState>,
+}
+
+impl Job {
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.state.cancel_flags.remove(&self.id);
+    }
+}
+
+/// Registers a job under a new id.
+pub fn register(state: &Arc<JobState>) -> Job {
+    let job = Job { id: uuid::Uuid::new_v4().to_string(), cancel: Arc::new(AtomicBool::new(false)), state: state.clone() };
+    state.cancel_flags.insert(job.id.clone(), job.cancel.clone());
+    job
+}
+
+/// Registers a job under a fixed id, for jobs that run one at a time.
+/// Fails while a job with that id is still running.
+pub fn register_single(state: &Arc<JobState>, id: &str) -> Result<Job, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    match state.cancel_flags.entry(id.to_string()) {
+        Entry::Occupied(_) => return Err(format!("Job {} is already running", id)),
+        Entry::Vacant(entry) => entry.insert(cancel.clone()),
+    };
+    Ok(Job { id: id.to_string(), cancel, state: state.clone() })
+}
+
+/// Runs `work` in the background under a new job id and cancel flag, then
+/// emits `event` with the payload `complete` builds from the job id, the
+/// work's output and whether it was cancelled. Returns the job id.
+pub fn spawn_job<T, Fut, P>(
+    app_handle: AppHandle,
+    state: &Arc<JobState>,
+    event: &'static str,
+    work: impl FnOnce(AppHandle, String, Arc<AtomicBool>) -> Fut + Send + 'static,
+    complete: impl FnOnce(String, T, bool) -> P + Send + 'static,
+) -> String
+where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    P: Serialize + Clone,
+{
+    let job = register(state);
+    let id = job.id.clone();
+    run(app_handle, job, event, work, complete);
+    id
+}
+
+/// `spawn_job` under a fixed id; see `register_single`.
+pub fn spawn_single_job<T, Fut, P>(
+    app_handle: AppHandle,
+    state: &Arc<JobState>,
+    id: &str,
+    event: &'static str,
+    work: impl FnOnce(AppHandle, String, Arc<AtomicBool>) -> Fut + Send + 'static,
+    complete: impl FnOnce(String, T, bool) -> P + Send + 'static,
+) -> Result<(), String>
+where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    P: Serialize + Clone,
+{
+    run(app_handle, register_single(state, id)?, event, work, complete);
+    Ok(())
+}
+
+/// Asks a running job to stop.
+pub fn cancel_job(state: &JobState, id: &str) -> Result<(), String> {
+    let flag = state.cancel_flags.get(id).ok_or_else(|| format!("Job {} is not running", id))?;
+    flag.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+fn run<T, Fut, P>(
+    app_handle: AppHandle,
+    job: Job,
+    event: &'static str,
+    work: impl FnOnce(AppHandle, String, Arc<AtomicBool>) -> Fut + Send + 'static,
+    complete: impl FnOnce(String, T, bool) -> P + Send + 'static,
+) where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    P: Serialize + Clone,
+{
+    tauri::async_runtime::spawn(async move {
+        let output = work(app_handle.clone(), job.id.clone(), job.cancel.clone()).await;
+        let cancelled = job.cancelled();
+        let id = job.id.clone();
+        drop(job);
+        let _ = app_handle.emit(event, complete(id, output, cancelled));
+    });
+}
+
+/// A queue of items worked on by at most `concurrency` tasks at a time.
+/// More items can be queued while it drains.
+pub struct Workers<I, O, F> {
+    queue: VecDeque<I>,
+    running: JoinSet<O>,
+    concurrency: usize,
+    spawn: F,
+}
+
+impl<I, O, F, Fut> Workers<I, O, F>
+where
+    O: Send + 'static,
+    F: FnMut(I) -> Fut,
+    Fut: Future<Output = O> + Send + 'static,
+{
+    pub fn new(items: impl IntoIterator<Item = I>, concurrency: usize, spawn: F) -> Self {
+        Workers { queue: items.into_iter().collect(), running: JoinSet::new(), concurrency: concurrency.max(1), spawn }
+    }
+
+    pub fn push(&mut self, item: I) {
+        self.queue.push_back(item);
+    }
+
+    /// Starts queued items up to the concurrency limit, unless `cancel` is
+    /// set, and waits for the next one to finish. `None` once nothing is
+    /// left running.
+    pub async fn next(&mut self, cancel: &AtomicBool) -> Option<Result<O, JoinError>> {
+        while self.running.len() < self.concurrency && !cancel.load(Ordering::Relaxed) {
+            let Some(item) = self.queue.pop_front() else {
+                break;
+            };
+            self.running.spawn((self.spawn)(item));
+        }
+        self.running.join_next().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_workers() {
+        let cancel = AtomicBool::new(false);
+        let mut workers = Workers::new([1, 2, 3], 1, |n: u32| async move { n * 10 });
+        let mut done = Vec::new();
+        while let Some(joined) = workers.next(&cancel).await {
+            let n = joined.unwrap();
+            match n {
+                10 => workers.push(4),
+                30 => cancel.store(true, Ordering::Relaxed),
+                _ => {}
+            }
+            done.push(n);
+        }
+        // 4 was queued behind 3 and never started once cancelled
+        assert_eq!(done, vec![10, 20, 30]);
+    }
+}
@@ -1,4 +1,6 @@
+mod plugins;
 mod import_engine;
+mod import_jobs;
 mod analysis;
 mod db;
 mod assets;
@@ -11,6 +13,52 @@ mod active_scan;
 mod drift;
 mod fuzzer;
 mod environments;
+mod proto;
+mod folders;
+mod retention;
+mod backups;
+mod settings;
+mod users;
+mod trash;
+mod stats;
+mod diff;
+mod templates;
+mod sitemap;
+mod repeater;
+mod openapi;
+mod chains;
+mod risk;
+mod export;
+mod compare;
+mod coverage;
+mod bola;
+mod header_attacks;
+mod fuzz_baseline;
+mod oob;
+mod pacing;
+mod authz;
+mod graphql;
+mod content_type;
+mod intruder;
+mod clustering;
+mod prompts;
+mod chat;
+mod portscan;
+mod archive;
+mod probe;
+mod fingerprint;
+mod x509;
+mod enrich;
+mod pkcs12;
+mod security_headers;
+mod methods;
+mod unauth;
+mod cache_probe;
+mod scan_profiles;
+mod batch_scan;
+mod variables;
+mod env_auth;
+mod jobs;
 use crate::import_engine::Parser;
 use tauri::Emitter;
 use tauri_plugin_clipboard_manager::ClipboardExt;
@@ -41,9 +89,25 @@ pub struct ClipboardMonitorState {
     pub running: AtomicBool,
 }
 
+/// Cancellation flags for in-flight file imports and background jobs, keyed
+/// by import/job id, plus the status of every job started this session.
+pub struct ImportState {
+    pub cancel_flags: DashMap<String, Arc<AtomicBool>>,
+    pub jobs: DashMap<String, import_jobs::ImportJob>,
+}
+
+/// Cancellation flags for running background jobs, keyed by job id.
+pub struct JobState {
+    pub cancel_flags: DashMap<String, Arc<AtomicBool>>,
+}
+
+/// Stops the out-of-band callback listener; set only while it runs.
+pub struct OobState {
+    pub listener: std::sync::Mutex<Option<tokio_util::sync::CancellationToken>>,
+}
+
 pub struct ProxyState {
     pub running: AtomicBool,
-    pub port: u16,
     pub capture_body: AtomicBool,
     pub intercept_requests: AtomicBool,
     pub intercept_responses: AtomicBool,
@@ -54,32 +118,310 @@ pub struct ProxyState {
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
-async fn parse_content(app: tauri::AppHandle, content: String, source_type: String) -> Result<import_engine::ImportResult, String> {
+async fn parse_content(
+    app: tauri::AppHandle,
+    content: String,
+    source_type: String,
+    preview: Option<bool>,
+) -> Result<import_engine::ImportResult, String> {
     let custom_rules = db::get_custom_rules().await?;
     let plugins = crate::plugins::load_plugins(&app);
-    if source_type == "text" {
+    let result = if source_type == "text" {
         Ok(Parser::parse_text(&content, &custom_rules, &plugins))
     } else if source_type == "excel" {
        Parser::parse_excel(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
     } else if source_type == "har" {
-        Parser::parse_har(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
+        // Scanning thousands of entries is CPU-bound; keep it off the async executor.
+        tauri::async_runtime::spawn_blocking(move || {
+            Parser::parse_har_with_progress(&content, &custom_rules, &plugins, &|scanned, total| {
+                if scanned == total || scanned.is_multiple_of(50) {
+                    let _ = app.emit("scan-progress", serde_json::json!({
+                        "current": scanned,
+                        "total": total
+                    }));
+                }
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
     } else if source_type == "burp" {
         Parser::parse_burp_xml(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
     } else if source_type == "postman" {
-        Parser::parse_postman(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
+        let env_vars = environments::active_variables().await?;
+        Parser::parse_postman(&content, &env_vars, &custom_rules, &plugins).map_err(|e| e.to_string())
+    } else if source_type == "openapi" {
+        Parser::parse_openapi(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
+    } else if source_type == "curl" {
+        Parser::parse_curl(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
+    } else if source_type == "raw-http" {
+        Parser::parse_raw_http(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
+    } else if source_type == "sitemap" {
+        Parser::parse_sitemap(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
+    } else if source_type == "robots" {
+        // Pasted robots.txt has no origin of its own; borrow it from a Sitemap line
+        let base = import_engine::robots_rules(&content)
+            .into_iter()
+            .find_map(|r| match r {
+                import_engine::RobotsRule::Sitemap(u) => url::Url::parse(&u).ok(),
+                _ => None,
+            })
+            .ok_or("robots.txt has no Sitemap line to resolve paths against; use fetch_site_maps with the site URL")?;
+        Parser::parse_robots(&content, base.origin().ascii_serialization().as_str(), &custom_rules, &plugins).map_err(|e| e.to_string())
+    } else if source_type == "proto" {
+        Parser::parse_proto(&content, "grpc://localhost", &custom_rules, &plugins).map_err(|e| e.to_string())
     } else {
         Err("Unsupported source type".to_string())
+    }?;
+    apply_preview(result, preview).await
+}
+
+/// Downloads a spec, collection or HAR and runs it through `parse_content`.
+/// `auth_header` is either a full `Name: value` header or a bare bearer token.
+/// A `source_type` of "auto" picks the parser from the URL and content.
+#[tauri::command]
+async fn fetch_and_import(
+    app: tauri::AppHandle,
+    url: String,
+    source_type: String,
+    auth_header: Option<String>,
+    preview: Option<bool>,
+) -> Result<import_engine::ImportResult, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut request = client.get(&url);
+    if let Some(auth) = auth_header.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        request = match auth.split_once(':') {
+            Some((name, value)) if !name.contains(' ') => request.header(name.trim(), value.trim()),
+            _ if auth.to_ascii_lowercase().starts_with("bearer ") => request.header("Authorization", auth),
+            _ => request.bearer_auth(auth),
+        };
+    }
+
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Fetching {} failed with HTTP {}", url, status));
+    }
+    let content = response.text().await.map_err(|e| e.to_string())?;
+
+    let source_type = if source_type == "auto" {
+        let ext = url::Url::parse(&url)
+            .ok()
+            .and_then(|u| {
+                let file = u.path_segments()?.next_back()?.to_string();
+                file.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase())
+            })
+            .unwrap_or_else(|| "json".to_string());
+        import_engine::sniff_format(&ext, &content).to_string()
+    } else {
+        source_type
+    };
+
+    parse_content(app, content, source_type, preview).await
+}
+
+/// In preview mode the parsed entries are swapped for an `ImportPreview` so
+/// a large import can be sanity-checked before anything is written.
+async fn apply_preview(result: import_engine::ImportResult, preview: Option<bool>) -> Result<import_engine::ImportResult, String> {
+    if !preview.unwrap_or(false) {
+        return Ok(result);
+    }
+    let existing = assets::existing_asset_identities().await?;
+    let mode = assets::identity_query_mode(&db::get_db()).await?;
+    Ok(result.into_preview(&existing, mode))
+}
+
+/// Upper bound on sitemaps followed from robots.txt and sitemap indexes.
+const MAX_SITEMAPS: usize = 25;
+
+/// Fetches `robots.txt` and `sitemap.xml` from a site (following sitemap
+/// indexes) and merges their URLs into one import.
+#[tauri::command]
+async fn fetch_site_maps(app: tauri::AppHandle, base_url: String) -> Result<import_engine::ImportResult, String> {
+    let custom_rules = db::get_custom_rules().await?;
+    let plugins = crate::plugins::load_plugins(&app);
+    let base = url::Url::parse(&base_url).map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let fetch = |url: String| {
+        let client = client.clone();
+        async move {
+            let resp = client.get(&url).send().await.ok()?;
+            if !resp.status().is_success() {
+                return None;
+            }
+            resp.text().await.ok()
+        }
+    };
+
+    let mut entries = Vec::new();
+    let mut queue = vec![base.join("/sitemap.xml").map_err(|e| e.to_string())?.to_string()];
+
+    if let Some(robots) = fetch(base.join("/robots.txt").map_err(|e| e.to_string())?.to_string()).await {
+        for rule in import_engine::robots_rules(&robots) {
+            if let import_engine::RobotsRule::Sitemap(u) = rule {
+                queue.push(u);
+            }
+        }
+        let result = Parser::parse_robots(&robots, base.as_str(), &custom_rules, &plugins).map_err(|e| e.to_string())?;
+        entries.extend(result.entries);
+    }
+
+    let mut visited = std::collections::HashSet::new();
+    while let Some(sitemap_url) = queue.pop() {
+        if visited.len() >= MAX_SITEMAPS || !visited.insert(sitemap_url.clone()) {
+            continue;
+        }
+        let Some(xml) = fetch(sitemap_url).await else { continue };
+        let (_, children) = import_engine::sitemap_locations(&xml);
+        queue.extend(children);
+        if let Ok(result) = Parser::parse_sitemap(&xml, &custom_rules, &plugins) {
+            entries.extend(result.entries);
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    entries.retain(|e| seen.insert(e.url.clone()));
+    if entries.is_empty() {
+        return Err(format!("No robots.txt or sitemap URLs found for {}", base_url));
     }
+
+    Ok(import_engine::ImportResult {
+        entries,
+        source_type: "sitemap".to_string(),
+        preview: None,
+    })
 }
 
 #[tauri::command]
-async fn parse_binary_content(app: tauri::AppHandle, content: Vec<u8>, source_type: String) -> Result<import_engine::ImportResult, String> {
+async fn parse_binary_content(
+    app: tauri::AppHandle,
+    content: Vec<u8>,
+    source_type: String,
+    preview: Option<bool>,
+) -> Result<import_engine::ImportResult, String> {
     let custom_rules = db::get_custom_rules().await?;
     let plugins = crate::plugins::load_plugins(&app);
-    if source_type == "excel" {
+    let result = if source_type == "excel" {
        Parser::parse_excel_bytes(&content, &custom_rules, &plugins).map_err(|e| e.to_string())
     } else {
         Err("Unsupported source type for binary parsing".to_string())
+    }?;
+    apply_preview(result, preview).await
+}
+
+/// Streams and scans a HAR file on disk, emitting `import-progress` per
+/// scanned batch. `import_id` can be passed to `cancel_import`.
+async fn parse_har_path(app: &tauri::AppHandle, path: String, import_id: &str, cancel: Arc<AtomicBool>) -> Result<import_engine::ImportResult, String> {
+    let custom_rules = db::get_custom_rules().await?;
+    let plugins = crate::plugins::load_plugins(app);
+    let progress_app = app.clone();
+    let id = import_id.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        Parser::parse_har_file(&path, &custom_rules, &plugins, &cancel, &|current, bytes_read, total_bytes| {
+            let _ = progress_app.emit("import-progress", serde_json::json!({
+                "import_id": id,
+                "current": current,
+                "bytes_read": bytes_read,
+                "total_bytes": total_bytes
+            }));
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Imports a HAR file straight from disk. Entries are parsed, scanned and
+/// written to the workspace here, so large captures never cross the IPC
+/// boundary. Emits `import-progress` while scanning, then per 50 entries
+/// written.
+#[tauri::command]
+async fn import_har_file(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<ImportState>>,
+    path: String,
+    import_id: String,
+    source: Option<String>,
+) -> Result<assets::BatchImportResult, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    state.cancel_flags.insert(import_id.clone(), cancel.clone());
+
+    let result = async {
+        let parsed = parse_har_path(&app, path, &import_id, cancel.clone()).await?;
+        let progress_app = app.clone();
+        let progress_id = import_id.clone();
+        let on_progress: assets::ImportProgressFn = Arc::new(move |current, total| {
+            if current == total || current.is_multiple_of(50) {
+                let _ = progress_app.emit("import-progress", serde_json::json!({
+                    "import_id": progress_id,
+                    "current": current,
+                    "total": total
+                }));
+            }
+        });
+        let source = source.unwrap_or_else(|| parsed.source_type.clone());
+        assets::import_entries(parsed.entries, source, cancel.clone(), on_progress).await
+    }
+    .await;
+
+    state.cancel_flags.remove(&import_id);
+    result.map(|(result, _)| result)
+}
+
+/// What `import_har_file` would add, without sending the entries back.
+#[tauri::command]
+async fn preview_har_file(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<ImportState>>,
+    path: String,
+    import_id: String,
+) -> Result<import_engine::ImportResult, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    state.cancel_flags.insert(import_id.clone(), cancel.clone());
+    let result = parse_har_path(&app, path, &import_id, cancel).await;
+    state.cancel_flags.remove(&import_id);
+    apply_preview(result?, Some(true)).await
+}
+
+/// Parses every recognised file under `path` and returns the merged entries
+/// together with a per-file report.
+#[tauri::command]
+async fn import_directory(
+    app: tauri::AppHandle,
+    path: String,
+    recursive: bool,
+    preview: Option<bool>,
+) -> Result<import_engine::DirectoryImportResult, String> {
+    let custom_rules = db::get_custom_rules().await?;
+    let plugins = crate::plugins::load_plugins(&app);
+    let env_vars = environments::active_variables().await?;
+    let mut imported = tauri::async_runtime::spawn_blocking(move || {
+        Parser::parse_directory(&path, recursive, &env_vars, &custom_rules, &plugins)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+    imported.result = apply_preview(imported.result, preview).await?;
+    Ok(imported)
+}
+
+#[tauri::command]
+fn cancel_import(state: tauri::State<'_, Arc<ImportState>>, import_id: String) -> bool {
+    match state.cancel_flags.get(&import_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
     }
 }
 
@@ -101,9 +443,9 @@ async fn start_proxy_server(
     if state.running.load(Ordering::Relaxed) {
         return Err("Proxy is already running".to_string());
     }
+    let port: u16 = settings::get("proxy_port").await?;
     state.running.store(true, Ordering::Relaxed);
     let running_flag = Arc::clone(state.inner());
-    let port = state.port;
     
     tauri::async_runtime::spawn(async move {
         proxy::start_proxy(app, port, running_flag).await;
@@ -146,49 +488,6 @@ async fn resolve_interception(
     }
 }
 
-#[tauri::command]
-async fn run_rate_limit_test(
-    app: tauri::AppHandle,
-    url: String,
-    rps: usize,
-    duration: u64
-) -> Result<active_scan::RateLimitResult, String> {
-    active_scan::test_rate_limit(app, url, rps, duration).await
-}
-
-#[tauri::command]
-async fn export_as_curl(asset_id: i64) -> Result<String, String> {
-    use crate::db::get_db;
-    let pool = get_db();
-    
-    let asset = sqlx::query_as::<_, (String, Option<String>, Option<String>, Option<String>)>(
-        "SELECT url, method, req_headers, req_body FROM assets WHERE id = ?"
-    )
-    .bind(asset_id)
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let (url, method, headers, body) = asset;
-    let method = method.unwrap_or("GET".to_string());
-    
-    let mut curl = format!("curl -X {} '{}'", method, url);
-    
-    if let Some(h) = headers {
-        if let Ok(headers_map) = serde_json::from_str::<std::collections::HashMap<String, String>>(&h) {
-            for (k, v) in headers_map {
-                curl.push_str(&format!(" \\\n  -H '{}: {}'", k, v));
-            }
-        }
-    }
-    
-    if let Some(b) = body {
-        curl.push_str(&format!(" \\\n  -d '{}'", b.replace("'", "'\\''")));
-    }
-    
-    Ok(curl)
-}
-
 #[tauri::command]
 async fn export_as_postman_link(asset_id: i64) -> Result<String, String> {
     use crate::db::get_db;
@@ -206,7 +505,7 @@ async fn export_as_postman_link(asset_id: i64) -> Result<String, String> {
     let method = method.unwrap_or("GET".to_string());
     
     // Postman deep link format
-    let encoded_url = urlencoding::encode(&url);
+    let encoded_url: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
     Ok(format!("https://www.postman.com/api-request?method={}&url={}", method, encoded_url))
 }
 
@@ -223,7 +522,6 @@ pub fn run() {
 
     let proxy_state = Arc::new(ProxyState {
         running: AtomicBool::new(false),
-        port: 8080, // Default proxy port
         capture_body: AtomicBool::new(false),
         intercept_requests: AtomicBool::new(false),
         intercept_responses: AtomicBool::new(false),
@@ -232,19 +530,49 @@ pub fn run() {
         cert_manager: Arc::new(certs::CertManager::new()),
     });
 
+    let import_state = Arc::new(ImportState {
+        cancel_flags: DashMap::new(),
+        jobs: DashMap::new(),
+    });
+
+    let job_state = Arc::new(JobState {
+        cancel_flags: DashMap::new(),
+    });
+
+    let oob_state = Arc::new(OobState {
+        listener: std::sync::Mutex::new(None),
+    });
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(monitor_state.clone())
         .manage(proxy_state.clone())
+        .manage(import_state)
+        .manage(job_state)
+        .manage(oob_state)
         .setup(move |app| {
             let handle = app.handle().clone();
             
             // Initialize Database
             tauri::async_runtime::block_on(async {
-                db::init_db(&handle, "Main Workspace").await.unwrap();
-                environments::init_environments_table().await.unwrap();
-            });
+                db::init_db(&handle, db::DEFAULT_WORKSPACE, None).await?;
+                if let Err(e) = certs::load_workspace_ca(&handle).await {
+                    eprintln!("Failed to load the interception CA: {}", e);
+                }
+                match retention::purge(&handle).await {
+                    Ok(report) if report.assets_deleted > 0 || report.history_deleted > 0 => {
+                        eprintln!("Retention purge: {} assets, {} history entries removed", report.assets_deleted, report.history_deleted);
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Retention purge failed: {}", e),
+                }
+                Ok::<(), sqlx::Error>(())
+            })?;
+
+            tauri::async_runtime::spawn(backups::run_scheduler(handle.clone()));
+            tauri::async_runtime::spawn(recon::run_scheduler(handle.clone()));
+            tauri::async_runtime::spawn(certs::run_expiry_watch(handle.clone()));
 
             let state = monitor_state.clone();
             
@@ -272,19 +600,47 @@ pub fn run() {
             greet, 
             parse_content, 
             parse_binary_content, 
+            fetch_site_maps,
+            fetch_and_import,
+            import_har_file,
+            preview_har_file,
+            import_directory,
+            cancel_import,
+            import_jobs::start_import_job,
+            import_jobs::get_import_job_status,
+            import_jobs::list_import_jobs,
+            import_jobs::cancel_import_job,
             set_clipboard_monitor,
             assets::add_asset,
             assets::get_assets,
             assets::batch_add_assets,
             assets::batch_import_full,
+            assets::get_asset_identity_mode,
+            assets::set_asset_identity_mode,
+            assets::import_spec_to_inventory,
+            proto::import_proto_file,
+            proto::get_proto_rpcs,
+            proto::describe_grpc_path,
+            folders::get_folders,
+            folders::create_folder,
+            folders::rename_folder,
+            folders::move_folder,
+            folders::delete_folder,
+            folders::move_assets_to_folder,
             assets::get_findings,
             assets::update_finding_annotation,
             assets::global_search,
             assets::delete_asset,
+            assets::update_asset,
+            assets::diff_asset_versions,
             assets::clear_inventory,
             assets::get_all_findings_full,
             db::switch_workspace,
+            db::is_workspace_encrypted,
+            db::encrypt_workspace,
+            db::change_workspace_passphrase,
             db::get_current_workspace,
+            db::maintain_workspace,
             db::list_workspaces,
             db::add_asset_tag,
             db::remove_asset_tag,
@@ -300,26 +656,154 @@ pub fn run() {
             db::get_api_specs,
             db::delete_api_spec,
             fuzzer::run_active_fuzz,
+            fuzzer::cancel_fuzz,
+            bola::run_bola_fuzz,
+            oob::start_oob_listener,
+            oob::stop_oob_listener,
+            oob::get_oob_status,
+            oob::list_oob_interactions,
+            authz::list_auth_profiles,
+            authz::save_auth_profile,
+            authz::delete_auth_profile,
+            authz::run_auth_matrix,
+            authz::get_auth_matrix,
+            unauth::run_unauth_scan,
+            intruder::run_intruder,
+            fuzzer::create_wordlist,
+            fuzzer::import_wordlist,
+            fuzzer::list_wordlists,
+            fuzzer::get_wordlist,
+            fuzzer::delete_wordlist,
             start_proxy_server,
             stop_proxy_server,
             audit::get_audit_log,
-            audit::log_action,
+            audit::verify_audit_integrity,
+            audit::get_audit_facets,
+            audit::export_audit_log,
             ai::ai_triage_finding,
+            ai::get_ai_triage,
+            ai::start_batch_triage,
+            ai::cancel_batch_triage,
+            ai::ai_describe_openapi,
+            ai::get_ai_usage_stats,
+            clustering::get_finding_clusters,
+            prompts::get_prompt_templates,
+            prompts::set_prompt_template,
+            prompts::reset_prompt_template,
+            chat::start_ai_chat,
+            chat::send_ai_chat_message,
+            chat::list_ai_chats,
+            chat::get_ai_chat,
+            chat::delete_ai_chat,
+            ai::ai_generate_report,
+            ai::ai_suggest_payloads,
             ai::check_llm_availability,
             ai::get_available_models,
             recon::enumerate_subdomains,
+            recon::start_subdomain_bruteforce,
+            recon::cancel_recon,
+            recon::list_recon_runs,
+            recon::get_recon_results,
+            recon::delete_recon_run,
+            recon::promote_recon_to_assets,
+            recon::list_recon_schedules,
+            recon::save_recon_schedule,
+            recon::delete_recon_schedule,
+            portscan::start_port_scan,
+            portscan::list_open_ports,
+            portscan::promote_open_ports,
+            archive::harvest_archive_urls,
+            archive::import_archive_urls,
+            probe::start_host_probe,
+            probe::list_host_probes,
+            fingerprint::start_fingerprint,
+            fingerprint::list_host_technologies,
+            enrich::enrich_ips,
             set_proxy_interception_config,
             resolve_interception,
             get_root_ca,
-            run_rate_limit_test,
-            export_as_curl,
+            certs::export_root_ca,
+            certs::install_ca_to_system,
+            certs::uninstall_ca_from_system,
+            certs::get_ca_info,
+            certs::import_ca,
+            certs::reset_ca,
+            certs::rotate_ca,
+            active_scan::run_rate_limit_test,
+            active_scan::list_rate_limit_tests,
+            active_scan::run_rate_limit_bypass,
+            active_scan::list_rate_limit_bypasses,
+            scan_profiles::list_scan_profiles,
+            scan_profiles::save_scan_profile,
+            scan_profiles::delete_scan_profile,
+            scan_profiles::set_default_scan_profile,
+            batch_scan::run_batch_scan,
+            batch_scan::list_scan_reports,
+            batch_scan::delete_scan_report,
+            variables::preview_substitution,
+            security_headers::audit_security_headers,
+            methods::enumerate_http_methods,
+            cache_probe::probe_web_cache,
+            export::export_as_curl,
+            export::export_as_fetch,
+            export::export_as_httpie,
             export_as_postman_link,
             environments::get_environments,
             environments::get_active_environment,
             environments::create_environment,
             environments::set_active_environment,
             environments::delete_environment,
-            environments::update_environment
+            environments::update_environment,
+            environments::export_environment_postman,
+            environments::import_environment_postman,
+            env_auth::get_environment_auth,
+            env_auth::set_environment_auth,
+            env_auth::test_environment_auth,
+            retention::get_retention_settings,
+            retention::set_retention_settings,
+            retention::run_retention_purge,
+            backups::get_backup_settings,
+            backups::set_backup_settings,
+            backups::create_backup,
+            backups::list_backups,
+            backups::restore_backup,
+            settings::get_setting,
+            settings::set_setting,
+            settings::get_all_settings,
+            users::get_users,
+            users::create_user,
+            users::update_user,
+            users::delete_user,
+            users::set_user_password,
+            users::set_current_user,
+            users::get_current_user,
+            trash::list_trash,
+            trash::restore_assets,
+            trash::restore_asset,
+            trash::empty_trash,
+            stats::get_workspace_stats,
+            templates::get_grouped_assets,
+            sitemap::get_site_map,
+            repeater::create_repeater_session,
+            repeater::list_repeater_sessions,
+            repeater::get_repeater_session,
+            repeater::send_repeater_request,
+            repeater::diff_repeater_attempts,
+            repeater::rename_repeater_session,
+            repeater::delete_repeater_session,
+            openapi::export_openapi,
+            chains::create_chain,
+            chains::list_chains,
+            chains::get_chain,
+            chains::update_chain,
+            chains::delete_chain,
+            chains::run_chain,
+            risk::recompute_risk_scores,
+            compare::compare_workspaces,
+            coverage::get_spec_coverage,
+            drift::accept_drift_baseline,
+            drift::list_drift_baseline,
+            drift::remove_drift_baseline
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,221 @@
+//! HTTP verb enumeration: what an endpoint advertises through OPTIONS
+//! against what it actually accepts.
+
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::pacing::{Pacer, Pacing};
+use crate::scan_profiles::ActiveCheck;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Verbs tried against every asset, after OPTIONS.
+pub const METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "TRACE", "PROPFIND"];
+
+/// Verbs that change or expose server state and shouldn't be open by accident.
+const DANGEROUS: &[&str] = &["PUT", "PATCH", "DELETE", "PROPFIND"];
+
+/// Verbs that read without side effects; the rest are sent without the
+/// captured credentials unless the run opts in.
+const SAFE: &[&str] = &["GET", "HEAD", "OPTIONS", "TRACE"];
+
+/// Header echoed back by an enabled TRACE.
+const TRACE_MARKER: &str = "X-Apisec-Trace";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MethodResult {
+    pub method: String,
+    /// 0 when the request failed
+    pub status: u16,
+    /// Listed in the OPTIONS Allow header
+    pub advertised: bool,
+    /// Sent with the captured credentials
+    pub authenticated: bool,
+}
+
+impl MethodResult {
+    pub fn accepted(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn rejected(&self) -> bool {
+        matches!(self.status, 405 | 501)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MethodScan {
+    /// Methods from the OPTIONS Allow header, if one was sent
+    pub allow: Option<Vec<String>>,
+    pub results: Vec<MethodResult>,
+    pub findings: Vec<Finding>,
+}
+
+/// Uppercased methods from an `Allow` header.
+pub fn parse_allow(value: &str) -> Vec<String> {
+    value.split(',').map(|m| m.trim().to_uppercase()).filter(|m| !m.is_empty()).collect()
+}
+
+/// Flags TRACE, dangerous verbs other than the captured one, and methods
+/// whose acceptance contradicts the Allow header. `trace_echoed` is set
+/// when the TRACE response reflected the request.
+pub fn judge(captured_method: &str, allow: Option<&[String]>, results: &[MethodResult], trace_echoed: bool) -> Vec<Finding> {
+    let mut out = Vec::new();
+    for r in results {
+        if r.method == "TRACE" && r.accepted() {
+            let (severity, how) = if trace_echoed {
+                (FindingSeverity::Medium, "and echoed the request headers back, which exposes HttpOnly cookies and auth headers to cross-site tracing")
+            } else {
+                (FindingSeverity::Low, "although the request wasn't echoed back")
+            };
+            out.push(Finding::new("ACTIVE-HTTP-TRACE", "TRACE Enabled", severity, format!("TRACE returned {} {}.", r.status, how), "TRACE"));
+        } else if DANGEROUS.contains(&r.method.as_str()) && r.method != captured_method && r.accepted() {
+            out.push(Finding::new(
+                "ACTIVE-HTTP-DANGEROUS-VERB",
+                "Dangerous HTTP Method Allowed",
+                FindingSeverity::Medium,
+                format!(
+                    "{} succeeded with {}{} on an endpoint captured as {}.",
+                    r.method,
+                    r.status,
+                    if r.authenticated { "" } else { " without credentials" },
+                    captured_method
+                ),
+                &r.method,
+            ));
+        }
+
+        // HEAD and OPTIONS are implied and rarely listed
+        let Some(allow) = allow.filter(|_| !matches!(r.method.as_str(), "HEAD" | "OPTIONS")) else {
+            continue;
+        };
+        let description = if r.advertised && r.rejected() {
+            format!("{} is listed in Allow ({}) but was refused with {}.", r.method, allow.join(", "), r.status)
+        } else if !r.advertised && r.accepted() {
+            format!("{} isn't listed in Allow ({}) but succeeded with {}.", r.method, allow.join(", "), r.status)
+        } else {
+            continue;
+        };
+        out.push(Finding::new("ACTIVE-HTTP-VERB-MISMATCH", "Advertised Methods Mismatch", FindingSeverity::Low, description, &r.method));
+    }
+    crate::analysis::attach_rule_metadata(&mut out);
+    out
+}
+
+/// Sends OPTIONS and then each of `METHODS` to the asset with its captured
+/// headers and no body, and stores the findings from `judge` on it.
+/// Mutating verbs are really sent, but without the captured credentials
+/// unless `authenticated_writes` is set, so only opt in against test data.
+#[tauri::command]
+pub async fn enumerate_http_methods(
+    asset_id: i64,
+    pacing: Option<Pacing>,
+    scan_profile_id: Option<i64>,
+    authenticated_writes: Option<bool>,
+) -> Result<MethodScan, String> {
+    let profile = crate::scan_profiles::authorize(scan_profile_id, ActiveCheck::HttpMethods).await?;
+    let pacer = Pacer::new(&profile.cap_pacing(&pacing.unwrap_or_default()));
+    scan_asset(asset_id, &pacer, authenticated_writes.unwrap_or(false)).await
+}
+
+pub(crate) async fn scan_asset(asset_id: i64, pacer: &Pacer, authenticated_writes: bool) -> Result<MethodScan, String> {
+    let pool = get_db();
+    let asset: Option<(String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT url, method, req_headers FROM assets WHERE id = ? AND deleted_at IS NULL")
+            .bind(asset_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let (url, captured_method, captured) = asset.ok_or("Asset not found")?;
+    let captured_method = captured_method.unwrap_or_else(|| "GET".to_string()).to_uppercase();
+    let captured = crate::assets::captured_headers(captured);
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let marker = format!("apisec-{}", chrono::Utc::now().timestamp_millis());
+    let headers = crate::assets::replay_headers(&captured, false);
+    let anonymous = crate::unauth::stripped_headers(&captured);
+    let authenticated = |method: &str| authenticated_writes || SAFE.contains(&method);
+    let send = |method: &str| {
+        let mut req = client.request(reqwest::Method::from_bytes(method.as_bytes()).unwrap_or(reqwest::Method::GET), &url);
+        for (k, v) in if authenticated(method) { &headers } else { &anonymous } {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        req.header(TRACE_MARKER, marker.as_str()).send()
+    };
+
+    pacer.wait().await;
+    let options = send("OPTIONS").await.map_err(|e| e.to_string())?;
+    let allow = options.headers().get("allow").and_then(|v| v.to_str().ok()).map(parse_allow);
+    let mut results = Vec::new();
+    let mut trace_echoed = false;
+    for method in METHODS {
+        pacer.wait().await;
+        let status = match send(method).await {
+            Ok(res) => {
+                let status = res.status().as_u16();
+                if *method == "TRACE" {
+                    trace_echoed = res.text().await.is_ok_and(|body| body.contains(&marker));
+                }
+                status
+            }
+            Err(_) => 0,
+        };
+        let advertised = allow.as_ref().is_some_and(|a| a.iter().any(|m| m == method));
+        results.push(MethodResult { method: method.to_string(), status, advertised, authenticated: authenticated(method) });
+    }
+
+    let findings = judge(&captured_method, allow.as_deref(), &results, trace_echoed);
+    for f in &findings {
+        crate::assets::store_finding(&pool, asset_id, f).await?;
+    }
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    Ok(MethodScan { allow, results, findings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(method: &str, status: u16, allow: &[String]) -> MethodResult {
+        MethodResult { method: method.to_string(), status, advertised: allow.iter().any(|m| m == method), authenticated: true }
+    }
+
+    #[test]
+    fn test_judge() {
+        let allow = parse_allow("get, HEAD,POST ,");
+        assert_eq!(allow, ["GET", "HEAD", "POST"]);
+        let results = vec![
+            result("GET", 200, &allow),
+            result("HEAD", 200, &allow),
+            result("POST", 405, &allow),
+            result("PUT", 405, &allow),
+            // A redirect to a login page isn't acceptance
+            result("PATCH", 302, &allow),
+            result("DELETE", 204, &allow),
+            result("TRACE", 200, &allow),
+            result("PROPFIND", 501, &allow),
+        ];
+        let found = judge("GET", Some(&allow), &results, true);
+        let rules: Vec<(&str, &str)> = found.iter().map(|f| (f.rule_id.as_str(), f.match_content.as_str())).collect();
+        assert_eq!(
+            rules,
+            [
+                ("ACTIVE-HTTP-VERB-MISMATCH", "POST"),
+                ("ACTIVE-HTTP-DANGEROUS-VERB", "DELETE"),
+                ("ACTIVE-HTTP-VERB-MISMATCH", "DELETE"),
+                ("ACTIVE-HTTP-TRACE", "TRACE"),
+                ("ACTIVE-HTTP-VERB-MISMATCH", "TRACE"),
+            ]
+        );
+        assert_eq!(found[3].severity, FindingSeverity::Medium);
+        assert!(found.iter().all(|f| f.remediation.is_some()));
+
+        // The captured verb is expected, and without Allow there's nothing to contradict
+        let put = [result("PUT", 200, &[]), result("TRACE", 405, &[])];
+        assert!(judge("PUT", None, &put, false).is_empty());
+    }
+}
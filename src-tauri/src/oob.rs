@@ -0,0 +1,452 @@
+use serde::{Deserialize, Serialize};
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::fuzzer::{FuzzTask, InsertionPoint};
+use crate::OobState;
+use sqlx::FromRow;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+/// Replaced in payloads with a callback URL unique to one request.
+pub const OOB_URL: &str = "{oob_url}";
+/// Replaced with a hostname unique to one request; needs `domain`.
+pub const OOB_HOST: &str = "{oob_host}";
+
+/// Where the callback listener runs and how targets reach it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OobSettings {
+    /// Address or hostname targets use to reach this machine
+    pub public_host: String,
+    pub http_port: u16,
+    /// 0 leaves the DNS listener off
+    pub dns_port: u16,
+    /// Zone delegated to the DNS listener, e.g. `oob.example.com`
+    pub domain: String,
+}
+
+impl Default for OobSettings {
+    fn default() -> Self {
+        Self { public_host: String::new(), http_port: 8081, dns_port: 0, domain: String::new() }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OobStatus {
+    pub running: bool,
+    pub settings: OobSettings,
+}
+
+#[derive(Debug, Serialize, Clone, FromRow)]
+pub struct OobInteraction {
+    pub id: i64,
+    pub token: String,
+    /// `http` or `dns`
+    pub protocol: String,
+    pub remote_addr: String,
+    /// Request head or queried name
+    pub details: String,
+    pub job_id: Option<String>,
+    pub url: Option<String>,
+    pub method: Option<String>,
+    pub payload: Option<String>,
+    pub finding_id: Option<i64>,
+    pub created_at: String,
+}
+
+pub fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// Fills the OOB placeholders in a payload, or `None` when it needs a host
+/// name and no domain is configured.
+pub fn render_payload(payload: &str, settings: &OobSettings, token: &str) -> Option<String> {
+    let domain = settings.domain.trim_matches('.');
+    if payload.contains(OOB_HOST) && domain.is_empty() {
+        return None;
+    }
+    let url = format!("http://{}:{}/{}", settings.public_host, settings.http_port, token);
+    Some(payload.replace(OOB_URL, &url).replace(OOB_HOST, &format!("{}.{}", token, domain)))
+}
+
+pub fn has_placeholder(payload: &str) -> bool {
+    payload.contains(OOB_URL) || payload.contains(OOB_HOST)
+}
+
+/// The label right before the configured domain, so `x.<token>.<domain>`
+/// still correlates.
+pub fn token_from_name(name: &str, domain: &str) -> Option<String> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    let domain = domain.trim_matches('.').to_lowercase();
+    if domain.is_empty() {
+        return None;
+    }
+    let prefix = name.strip_suffix(&domain)?.strip_suffix('.')?;
+    prefix.rsplit('.').next().filter(|t| !t.is_empty()).map(str::to_string)
+}
+
+/// Token from an HTTP request head: the first path segment, else the Host's
+/// subdomain.
+pub fn token_from_http(head: &str, domain: &str) -> Option<String> {
+    let mut lines = head.lines();
+    let path = lines.next()?.split_whitespace().nth(1)?;
+    let segment = path.trim_start_matches('/').split(['/', '?']).next().unwrap_or("");
+    if !segment.is_empty() {
+        return Some(segment.to_lowercase());
+    }
+    let host = lines.find_map(|l| l.split_once(':').filter(|(k, _)| k.trim().eq_ignore_ascii_case("host")).map(|(_, v)| v.trim()))?;
+    token_from_name(host.split(':').next().unwrap_or(host), domain)
+}
+
+/// Parses the first question of a DNS query into its id, lowercased name
+/// and the offset just past the question.
+pub fn parse_dns_query(packet: &[u8]) -> Option<(u16, String, usize)> {
+    if packet.len() < 12 || u16::from_be_bytes([packet[4], packet[5]]) == 0 {
+        return None;
+    }
+    let id = u16::from_be_bytes([packet[0], packet[1]]);
+    let mut labels = Vec::new();
+    let mut pos = 12;
+    loop {
+        let len = *packet.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            break;
+        }
+        // Compression pointers never appear in a question we can answer
+        if len & 0xC0 != 0 {
+            return None;
+        }
+        labels.push(String::from_utf8_lossy(packet.get(pos..pos + len)?).to_lowercase());
+        pos += len;
+    }
+    let end = pos + 4;
+    packet.get(pos..end)?;
+    Some((id, labels.join("."), end))
+}
+
+/// Answers a query with an A record for `answer` when it asked for one,
+/// otherwise with an empty NOERROR response.
+pub fn dns_response(query: &[u8], question_end: usize, answer: Option<Ipv4Addr>) -> Vec<u8> {
+    let qtype = u16::from_be_bytes([query[question_end - 4], query[question_end - 3]]);
+    let answer = answer.filter(|_| qtype == 1);
+    let mut out = Vec::with_capacity(question_end + 16);
+    out.extend_from_slice(&query[0..2]);
+    // QR and AA set, RD copied from the query
+    out.extend_from_slice(&[0x84 | (query[2] & 0x01), 0x00]);
+    out.extend_from_slice(&[0, 1, 0, answer.is_some() as u8, 0, 0, 0, 0]);
+    out.extend_from_slice(&query[12..question_end]);
+    if let Some(ip) = answer {
+        out.extend_from_slice(&[0xC0, 0x0C, 0, 1, 0, 1, 0, 0, 0, 0, 0, 4]);
+        out.extend_from_slice(&ip.octets());
+    }
+    out
+}
+
+/// Remembers which request a token was sent in so a later callback can be
+/// traced back to it.
+pub async fn register(token: &str, job_id: &str, task: &FuzzTask, payload: &str, position: Option<&InsertionPoint>) -> Result<(), String> {
+    let pool = get_db();
+    sqlx::query("INSERT INTO oob_payloads (token, job_id, url, method, payload, position) VALUES (?, ?, ?, ?, ?, ?)")
+        .bind(token)
+        .bind(job_id)
+        .bind(&task.url)
+        .bind(task.method.to_uppercase())
+        .bind(payload)
+        .bind(position.map(sqlx::types::Json))
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Rule of the finding a callback raises, and its kind in `oob_findings`.
+const OOB_RULE: &str = "ACTIVE-OOB-INTERACTION";
+
+fn oob_finding(protocol: &str, method: &str, url: &str, payload: &str, remote_addr: &str) -> Finding {
+    Finding {
+        id: None,
+        rule_id: OOB_RULE.to_string(),
+        name: "Out-of-Band Interaction Received".to_string(),
+        description: format!(
+            "{} {} made the server contact the callback listener over {} after injecting: {}",
+            method, url, protocol.to_uppercase(), payload
+        ),
+        severity: FindingSeverity::High,
+        match_content: payload.to_string(),
+        notes: Some(format!("First callback from {}", remote_addr)),
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: None,
+        remediation: None,
+    }
+}
+
+/// Stores a callback and raises a finding for the request that carried its
+/// token, once per token. Tokens we never sent are ignored.
+async fn record_interaction(app_handle: &tauri::AppHandle, token: &str, protocol: &str, remote: SocketAddr, details: String) -> Result<(), String> {
+    let pool = get_db();
+    let origin: Option<(String, String, String, String)> = sqlx::query_as("SELECT job_id, url, method, payload FROM oob_payloads WHERE token = ?")
+        .bind(token)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some((_, url, method, payload)) = origin else {
+        return Ok(());
+    };
+
+    let asset: Option<(i64,)> = sqlx::query_as("SELECT id FROM assets WHERE url = ? AND UPPER(method) = ? AND deleted_at IS NULL LIMIT 1")
+        .bind(&url)
+        .bind(&method)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Claiming the (token, kind) key first takes the write lock, so a DNS and
+    // an HTTP callback arriving together raise one finding between them
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let claimed = sqlx::query("INSERT INTO oob_findings (token, kind) VALUES (?, ?) ON CONFLICT DO NOTHING")
+        .bind(token)
+        .bind(OOB_RULE)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected()
+        == 1;
+    let finding_id = if claimed {
+        let mut findings = vec![oob_finding(protocol, &method, &url, &payload, &remote.to_string())];
+        crate::analysis::attach_rule_metadata(&mut findings);
+        let f = findings.remove(0);
+        let res = sqlx::query("INSERT INTO findings (asset_id, rule_id, name, severity, description, match_content, notes, is_false_positive, refs, remediation, created_at, last_seen) VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+            .bind(asset.map(|(id,)| id))
+            .bind(&f.rule_id)
+            .bind(&f.name)
+            .bind(f.severity)
+            .bind(&f.description)
+            .bind(&f.match_content)
+            .bind(&f.notes)
+            .bind(f.references.map(sqlx::types::Json))
+            .bind(&f.remediation)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        let id = res.last_insert_rowid();
+        sqlx::query("UPDATE oob_findings SET finding_id = ? WHERE token = ? AND kind = ?")
+            .bind(id)
+            .bind(token)
+            .bind(OOB_RULE)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(id)
+    } else {
+        let existing: Option<(Option<i64>,)> = sqlx::query_as("SELECT finding_id FROM oob_findings WHERE token = ? AND kind = ?")
+            .bind(token)
+            .bind(OOB_RULE)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        let id = existing.and_then(|(id,)| id);
+        sqlx::query("UPDATE findings SET occurrences = occurrences + 1, last_seen = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        id
+    };
+
+    let id = sqlx::query("INSERT INTO oob_interactions (token, protocol, remote_addr, details, finding_id) VALUES (?, ?, ?, ?, ?)")
+        .bind(token)
+        .bind(protocol)
+        .bind(remote.to_string())
+        .bind(&details)
+        .bind(finding_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+    tx.commit().await.map_err(|e| e.to_string())?;
+    if let (true, Some((asset_id,))) = (claimed, asset) {
+        crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    }
+    let interaction = fetch_interactions("WHERE i.id = ?", Some(id.to_string())).await?;
+    let _ = app_handle.emit("oob-interaction", interaction.first());
+    Ok(())
+}
+
+impl OobState {
+    pub fn is_running(&self) -> bool {
+        self.listener.lock().unwrap().is_some()
+    }
+}
+
+async fn serve_http(app_handle: tauri::AppHandle, listener: tokio::net::TcpListener, settings: OobSettings, cancel: CancellationToken) {
+    loop {
+        let (mut stream, remote) = tokio::select! {
+            _ = cancel.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(_) => continue,
+            },
+        };
+        let handle = app_handle.clone();
+        let domain = settings.domain.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut buf = vec![0u8; 8192];
+            let n = match tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf)).await {
+                Ok(Ok(n)) => n,
+                _ => return,
+            };
+            let request = String::from_utf8_lossy(&buf[..n]).into_owned();
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            if let Some(token) = token_from_http(&request, &domain) {
+                if let Err(e) = record_interaction(&handle, &token, "http", remote, request).await {
+                    eprintln!("OOB interaction not recorded: {}", e);
+                }
+            }
+        });
+    }
+}
+
+async fn serve_dns(app_handle: tauri::AppHandle, socket: tokio::net::UdpSocket, settings: OobSettings, cancel: CancellationToken) {
+    let answer = settings.public_host.parse::<Ipv4Addr>().ok();
+    let mut buf = [0u8; 512];
+    loop {
+        let (n, remote) = tokio::select! {
+            _ = cancel.cancelled() => break,
+            received = socket.recv_from(&mut buf) => match received {
+                Ok(received) => received,
+                Err(_) => continue,
+            },
+        };
+        let Some((_, name, question_end)) = parse_dns_query(&buf[..n]) else {
+            continue;
+        };
+        let _ = socket.send_to(&dns_response(&buf[..n], question_end, answer), remote).await;
+        if let Some(token) = token_from_name(&name, &settings.domain) {
+            if let Err(e) = record_interaction(&app_handle, &token, "dns", remote, name).await {
+                eprintln!("OOB interaction not recorded: {}", e);
+            }
+        }
+    }
+}
+
+/// Starts the callback listeners from the `oob` setting. Both bind on all
+/// interfaces, since the targets calling back are remote.
+#[tauri::command]
+pub async fn start_oob_listener(app_handle: tauri::AppHandle, state: tauri::State<'_, Arc<OobState>>) -> Result<(), String> {
+    if state.is_running() {
+        return Err("OOB listener is already running".to_string());
+    }
+    let settings: OobSettings = crate::settings::get("oob").await?;
+    if settings.public_host.trim().is_empty() {
+        return Err("Set the OOB public host first".to_string());
+    }
+    let http = tokio::net::TcpListener::bind(("0.0.0.0", settings.http_port)).await.map_err(|e| e.to_string())?;
+    let dns = match settings.dns_port {
+        0 => None,
+        port => Some(tokio::net::UdpSocket::bind(("0.0.0.0", port)).await.map_err(|e| e.to_string())?),
+    };
+    // Each run has its own token, so a quick stop and start never leaves
+    // the previous loops serving
+    let cancel = CancellationToken::new();
+    {
+        let mut running = state.listener.lock().unwrap();
+        if running.is_some() {
+            return Err("OOB listener is already running".to_string());
+        }
+        *running = Some(cancel.clone());
+    }
+
+    tauri::async_runtime::spawn(serve_http(app_handle.clone(), http, settings.clone(), cancel.clone()));
+    if let Some(socket) = dns {
+        tauri::async_runtime::spawn(serve_dns(app_handle, socket, settings, cancel));
+    }
+    crate::audit::record("start_oob_listener", "oob", None, None).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_oob_listener(state: tauri::State<'_, Arc<OobState>>) {
+    if let Some(cancel) = state.listener.lock().unwrap().take() {
+        cancel.cancel();
+    }
+}
+
+#[tauri::command]
+pub async fn get_oob_status(state: tauri::State<'_, Arc<OobState>>) -> Result<OobStatus, String> {
+    Ok(OobStatus {
+        running: state.is_running(),
+        settings: crate::settings::get("oob").await?,
+    })
+}
+
+async fn fetch_interactions(filter: &str, bind: Option<String>) -> Result<Vec<OobInteraction>, String> {
+    let pool = get_db();
+    let sql = format!(
+        "SELECT i.id, i.token, i.protocol, i.remote_addr, i.details, p.job_id, p.url, p.method, p.payload, i.finding_id, i.created_at
+         FROM oob_interactions i LEFT JOIN oob_payloads p ON p.token = i.token {} ORDER BY i.id DESC LIMIT 500",
+        filter
+    );
+    let mut query = sqlx::query_as::<_, OobInteraction>(&sql);
+    if let Some(value) = bind {
+        query = query.bind(value);
+    }
+    query.fetch_all(&pool).await.map_err(|e| e.to_string())
+}
+
+/// Latest callbacks, optionally only those caused by one fuzz job.
+#[tauri::command]
+pub async fn list_oob_interactions(job_id: Option<String>) -> Result<Vec<OobInteraction>, String> {
+    match job_id {
+        Some(id) => fetch_interactions("WHERE p.job_id = ?", Some(id)).await,
+        None => fetch_interactions("", None).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_payload() {
+        let mut settings = OobSettings { public_host: "203.0.113.5".to_string(), ..Default::default() };
+        assert_eq!(render_payload("url={oob_url}", &settings, "abc123").unwrap(), "url=http://203.0.113.5:8081/abc123");
+        assert_eq!(render_payload("; nslookup {oob_host}", &settings, "abc123"), None);
+        settings.domain = "oob.example.com.".to_string();
+        assert_eq!(render_payload("; nslookup {oob_host}", &settings, "abc123").unwrap(), "; nslookup abc123.oob.example.com");
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(token_from_name("ABC123.oob.example.com.", "oob.example.com").as_deref(), Some("abc123"));
+        assert_eq!(token_from_name("data.abc123.oob.example.com", "oob.example.com").as_deref(), Some("abc123"));
+        assert_eq!(token_from_name("oob.example.com", "oob.example.com"), None);
+        assert_eq!(token_from_name("abc123.evil.com", "oob.example.com"), None);
+        assert_eq!(token_from_http("GET /abc123/x?y=1 HTTP/1.1\r\nHost: 203.0.113.5\r\n\r\n", "").as_deref(), Some("abc123"));
+        assert_eq!(token_from_http("GET / HTTP/1.1\r\nHost: abc123.oob.example.com:8081\r\n\r\n", "oob.example.com").as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_dns() {
+        // Query id 0x1234, RD set, one question: abc123.oob.test A IN
+        let mut query = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in ["abc123", "oob", "test"] {
+            query.push(label.len() as u8);
+            query.extend_from_slice(label.as_bytes());
+        }
+        query.extend_from_slice(&[0, 0, 1, 0, 1]);
+        let (id, name, end) = parse_dns_query(&query).unwrap();
+        assert_eq!((id, name.as_str(), end), (0x1234, "abc123.oob.test", query.len()));
+
+        let res = dns_response(&query, end, Some(Ipv4Addr::new(203, 0, 113, 5)));
+        assert_eq!(&res[..4], &[0x12, 0x34, 0x85, 0x00]);
+        assert_eq!(&res[6..8], &[0, 1]);
+        assert_eq!(&res[res.len() - 4..], &[203, 0, 113, 5]);
+        assert_eq!(dns_response(&query, end, None).len(), query.len());
+        assert!(parse_dns_query(&query[..20]).is_none());
+    }
+}
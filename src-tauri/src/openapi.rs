@@ -0,0 +1,498 @@
+use crate::db::get_db;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Shape of the JSON seen at one position across all samples.
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    /// Nothing but `null` observed
+    Empty,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array(Box<Schema>),
+    Object(BTreeMap<String, Schema>, BTreeSet<String>),
+    /// Conflicting types; emitted without a type constraint
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    kind: Kind,
+    nullable: bool,
+}
+
+impl Schema {
+    /// Schema of a single JSON value. Every key of an object is required
+    /// until a later sample lacks it.
+    pub fn infer(value: &Value) -> Self {
+        let kind = match value {
+            Value::Null => Kind::Empty,
+            Value::Bool(_) => Kind::Boolean,
+            Value::Number(n) if n.is_f64() => Kind::Number,
+            Value::Number(_) => Kind::Integer,
+            Value::String(_) => Kind::String,
+            Value::Array(items) => Kind::Array(Box::new(
+                items.iter().map(Schema::infer).reduce(Schema::merge).unwrap_or(Schema { kind: Kind::Empty, nullable: false }),
+            )),
+            Value::Object(map) => Kind::Object(
+                map.iter().map(|(k, v)| (k.clone(), Schema::infer(v))).collect(),
+                map.keys().cloned().collect(),
+            ),
+        };
+        Schema { nullable: value.is_null(), kind }
+    }
+
+    /// Widens two schemas to one that accepts both.
+    pub fn merge(self, other: Schema) -> Self {
+        let nullable = self.nullable || other.nullable;
+        let kind = match (self.kind, other.kind) {
+            (Kind::Empty, k) | (k, Kind::Empty) => k,
+            (Kind::Integer, Kind::Number) | (Kind::Number, Kind::Integer) => Kind::Number,
+            (Kind::Array(a), Kind::Array(b)) => Kind::Array(Box::new(a.merge(*b))),
+            (Kind::Object(mut props, required_a), Kind::Object(other_props, required_b)) => {
+                for (key, schema) in other_props {
+                    let merged = match props.remove(&key) {
+                        Some(existing) => existing.merge(schema),
+                        None => schema,
+                    };
+                    props.insert(key, merged);
+                }
+                Kind::Object(props, required_a.intersection(&required_b).cloned().collect())
+            }
+            (a, b) if a == b => a,
+            _ => Kind::Any,
+        };
+        Schema { kind, nullable }
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut out = match &self.kind {
+            Kind::Empty | Kind::Any => json!({}),
+            Kind::Boolean => json!({ "type": "boolean" }),
+            Kind::Integer => json!({ "type": "integer" }),
+            Kind::Number => json!({ "type": "number" }),
+            Kind::String => json!({ "type": "string" }),
+            Kind::Array(items) => json!({ "type": "array", "items": items.to_value() }),
+            Kind::Object(props, required) => {
+                let mut obj = json!({
+                    "type": "object",
+                    "properties": props.iter().map(|(k, s)| (k.clone(), s.to_value())).collect::<Map<_, _>>(),
+                });
+                if !required.is_empty() {
+                    obj["required"] = json!(required);
+                }
+                obj
+            }
+        };
+        if self.nullable {
+            out["nullable"] = json!(true);
+        }
+        out
+    }
+}
+
+/// Type of a path or query parameter judged from its raw values.
+fn scalar_schema<'a>(values: impl Iterator<Item = &'a str>) -> Value {
+    let values: Vec<&str> = values.collect();
+    let all = |f: fn(&str) -> bool| !values.is_empty() && values.iter().all(|v| f(v));
+    if all(|v| v.parse::<i64>().is_ok()) {
+        json!({ "type": "integer" })
+    } else if all(|v| v.parse::<f64>().is_ok()) {
+        json!({ "type": "number" })
+    } else if all(|v| v == "true" || v == "false") {
+        json!({ "type": "boolean" })
+    } else if all(|v| uuid::Uuid::parse_str(v).is_ok()) {
+        json!({ "type": "string", "format": "uuid" })
+    } else {
+        json!({ "type": "string" })
+    }
+}
+
+/// One captured exchange as fed into the generator.
+pub struct SpecSample {
+    pub url: String,
+    /// Templated URL, e.g. `https://api.example.com/users/{id}`
+    pub template: String,
+    pub method: String,
+    pub status_code: Option<i64>,
+    pub req_content_type: Option<String>,
+    pub req_body: Option<String>,
+    pub res_content_type: Option<String>,
+    pub res_body: Option<String>,
+}
+
+#[derive(Default)]
+struct Operation {
+    samples: usize,
+    path_values: BTreeMap<usize, Vec<String>>,
+    query: BTreeMap<String, (usize, Vec<String>)>,
+    request: Option<(String, Option<Schema>)>,
+    responses: BTreeMap<String, Option<(String, Option<Schema>)>>,
+}
+
+fn content_type(header: Option<&str>, default: &str) -> String {
+    header
+        .and_then(|h| h.split(';').next())
+        .map(|h| h.trim().to_lowercase())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Folds a body into an accumulated media type and schema. JSON bodies get
+/// an inferred schema; anything else is described as a plain string.
+fn add_body(slot: &mut Option<(String, Option<Schema>)>, header: Option<&str>, body: Option<&str>) {
+    let Some(body) = body.filter(|b| !b.trim().is_empty()) else {
+        return;
+    };
+    let (media, schema) = match serde_json::from_str::<Value>(body) {
+        Ok(v) => (content_type(header, "application/json"), Some(Schema::infer(&v))),
+        Err(_) => (content_type(header, "text/plain"), None),
+    };
+    *slot = Some(match (slot.take(), schema) {
+        (Some((existing, Some(a))), Some(b)) => (existing, Some(a.merge(b))),
+        (Some(existing), _) => existing,
+        (None, schema) => (media, schema),
+    });
+}
+
+fn media_value(media: &str, schema: &Option<Schema>) -> Value {
+    let schema = schema.as_ref().map(Schema::to_value).unwrap_or_else(|| json!({ "type": "string" }));
+    json!({ media: { "schema": schema } })
+}
+
+/// Builds an OpenAPI 3.0 document from captured traffic. Operations are
+/// keyed by path template and method; identifier placeholders become path
+/// parameters and query parameters seen on every sample are required.
+pub fn build_openapi(title: &str, samples: &[SpecSample]) -> Value {
+    let mut servers: BTreeSet<String> = BTreeSet::new();
+    let mut paths: BTreeMap<String, BTreeMap<String, Operation>> = BTreeMap::new();
+
+    for sample in samples {
+        let (Ok(url), Ok(template)) = (url::Url::parse(&sample.url), url::Url::parse(&sample.template)) else {
+            continue;
+        };
+        servers.insert(url[..url::Position::BeforePath].to_string());
+
+        // Placeholders are all `{id}` in the template (percent-encoded once
+        // parsed); OpenAPI needs them unique per path
+        let mut path = String::new();
+        let mut placeholders = Vec::new();
+        for (i, segment) in template.path().split('/').enumerate().skip(1) {
+            path.push('/');
+            if segment == "%7Bid%7D" || segment == "{id}" {
+                placeholders.push(i);
+                match placeholders.len() {
+                    1 => path.push_str("{id}"),
+                    n => path.push_str(&format!("{{id{}}}", n)),
+                }
+            } else {
+                path.push_str(segment);
+            }
+        }
+
+        let op = paths.entry(path).or_default().entry(sample.method.to_lowercase()).or_default();
+        op.samples += 1;
+        let segments: Vec<&str> = url.path().split('/').collect();
+        for i in placeholders {
+            if let Some(value) = segments.get(i) {
+                op.path_values.entry(i).or_default().push(value.to_string());
+            }
+        }
+        let mut seen = BTreeSet::new();
+        for (name, value) in url.query_pairs() {
+            let entry = op.query.entry(name.to_string()).or_default();
+            if seen.insert(name.to_string()) {
+                entry.0 += 1;
+            }
+            entry.1.push(value.into_owned());
+        }
+        add_body(&mut op.request, sample.req_content_type.as_deref(), sample.req_body.as_deref());
+        let status = sample.status_code.map(|s| s.to_string()).unwrap_or_else(|| "default".to_string());
+        add_body(op.responses.entry(status).or_default(), sample.res_content_type.as_deref(), sample.res_body.as_deref());
+    }
+
+    let paths: Map<String, Value> = paths
+        .into_iter()
+        .map(|(path, ops)| {
+            let ops: Map<String, Value> = ops
+                .into_iter()
+                .map(|(method, op)| {
+                    let mut parameters: Vec<Value> = op
+                        .path_values
+                        .values()
+                        .enumerate()
+                        .map(|(n, values)| {
+                            let name = if n == 0 { "id".to_string() } else { format!("id{}", n + 1) };
+                            json!({ "name": name, "in": "path", "required": true, "schema": scalar_schema(values.iter().map(String::as_str)) })
+                        })
+                        .collect();
+                    parameters.extend(op.query.iter().map(|(name, (count, values))| {
+                        json!({
+                            "name": name,
+                            "in": "query",
+                            "required": *count == op.samples,
+                            "schema": scalar_schema(values.iter().map(String::as_str)),
+                        })
+                    }));
+
+                    let responses: Map<String, Value> = op
+                        .responses
+                        .iter()
+                        .map(|(status, body)| {
+                            let description = status
+                                .parse::<u16>()
+                                .ok()
+                                .and_then(|s| http::StatusCode::from_u16(s).ok())
+                                .and_then(|s| s.canonical_reason())
+                                .unwrap_or("Observed response");
+                            let mut response = json!({ "description": description });
+                            if let Some((media, schema)) = body {
+                                response["content"] = media_value(media, schema);
+                            }
+                            (status.clone(), response)
+                        })
+                        .collect();
+
+                    let mut operation = json!({ "responses": responses });
+                    if !parameters.is_empty() {
+                        operation["parameters"] = json!(parameters);
+                    }
+                    if let Some((media, schema)) = &op.request {
+                        operation["requestBody"] = json!({ "content": media_value(media, schema) });
+                    }
+                    (method, operation)
+                })
+                .collect();
+            (path, Value::Object(ops))
+        })
+        .collect();
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": title, "version": "1.0.0" },
+        "servers": servers.into_iter().map(|url| json!({ "url": url })).collect::<Vec<_>>(),
+        "paths": paths,
+    })
+}
+
+/// Human-written (or model-drafted) documentation for one operation.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct OperationDocs {
+    pub summary: String,
+    pub description: String,
+    /// Parameter name to description
+    pub parameters: HashMap<String, String>,
+    /// Dotted field path (see `field_paths`) to description
+    pub fields: HashMap<String, String>,
+}
+
+/// Schemas of an operation's request and response bodies.
+fn body_schemas(op: &mut Value) -> Vec<&mut Value> {
+    let mut bodies: Vec<&mut Value> = Vec::new();
+    for (key, value) in op.as_object_mut().into_iter().flatten() {
+        match (key.as_str(), value) {
+            ("requestBody", body) => bodies.push(body),
+            ("responses", Value::Object(responses)) => bodies.extend(responses.values_mut()),
+            _ => {}
+        }
+    }
+    bodies
+        .into_iter()
+        .filter_map(|b| b.get_mut("content").and_then(Value::as_object_mut))
+        .flat_map(|content| content.values_mut().filter_map(|m| m.get_mut("schema")))
+        .collect()
+}
+
+/// Dotted paths of every object property under `schema`, with `[]` for
+/// array items, e.g. `user.email` or `items[].id`.
+pub fn field_paths(schema: &Value, prefix: &str, out: &mut Vec<String>) {
+    if let Some(items) = schema.get("items") {
+        field_paths(items, &format!("{}[]", prefix), out);
+    }
+    if let Some(Value::Object(props)) = schema.get("properties") {
+        for (name, prop) in props {
+            let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+            if !out.contains(&path) {
+                out.push(path.clone());
+            }
+            field_paths(prop, &path, out);
+        }
+    }
+}
+
+fn describe_fields(schema: &mut Value, prefix: &str, docs: &HashMap<String, String>) {
+    if let Some(items) = schema.get_mut("items") {
+        describe_fields(items, &format!("{}[]", prefix), docs);
+    }
+    if let Some(Value::Object(props)) = schema.get_mut("properties") {
+        for (name, prop) in props.iter_mut() {
+            let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+            if let Some(doc) = docs.get(&path).filter(|d| !d.trim().is_empty()) {
+                prop["description"] = json!(doc.trim());
+            }
+            describe_fields(prop, &path, docs);
+        }
+    }
+}
+
+/// Field paths across all request and response bodies of an operation.
+pub fn operation_fields(op: &Value) -> Vec<String> {
+    let mut op = op.clone();
+    let mut out = Vec::new();
+    for schema in body_schemas(&mut op) {
+        field_paths(schema, "", &mut out);
+    }
+    out
+}
+
+/// Writes `docs` into an operation generated by `build_openapi`. Blank
+/// entries and names the operation doesn't have are ignored.
+pub fn apply_docs(op: &mut Value, docs: &OperationDocs) {
+    for (key, text) in [("summary", &docs.summary), ("description", &docs.description)] {
+        if !text.trim().is_empty() {
+            op[key] = json!(text.trim());
+        }
+    }
+    if let Some(Value::Array(params)) = op.get_mut("parameters") {
+        for param in params {
+            let doc = param["name"].as_str().and_then(|n| docs.parameters.get(n)).filter(|d| !d.trim().is_empty());
+            if let Some(doc) = doc {
+                param["description"] = json!(doc.trim());
+            }
+        }
+    }
+    for schema in body_schemas(op) {
+        describe_fields(schema, "", &docs.fields);
+    }
+}
+
+/// An asset row: url, path template, method, status code, then request
+/// headers and body and response headers and body.
+type InventoryRow = (String, Option<String>, Option<String>, Option<i64>, Option<String>, Option<String>, Option<String>, Option<String>);
+
+/// OpenAPI document for the live inventory, optionally limited to one host.
+pub(crate) async fn inventory_spec(host_filter: Option<String>) -> Result<Value, String> {
+    let pool = get_db();
+    let rows: Vec<InventoryRow> =
+        sqlx::query_as(
+            "SELECT url, path_template, method, status_code, req_headers, req_body, res_headers, res_body \
+             FROM assets WHERE deleted_at IS NULL ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let host_filter = host_filter.map(|h| h.trim().to_lowercase()).filter(|h| !h.is_empty());
+    let header = |headers: &Option<String>| -> Option<String> {
+        let map: HashMap<String, String> = serde_json::from_str(headers.as_deref()?).ok()?;
+        map.into_iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type")).map(|(_, v)| v)
+    };
+
+    let samples: Vec<SpecSample> = rows
+        .into_iter()
+        .filter(|(url, ..)| match &host_filter {
+            Some(host) => url::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h == host)).unwrap_or(false),
+            None => true,
+        })
+        .map(|(url, template, method, status_code, req_headers, req_body, res_headers, res_body)| SpecSample {
+            template: template.unwrap_or_else(|| url.clone()),
+            url,
+            method: method.unwrap_or_else(|| "GET".to_string()),
+            status_code,
+            req_content_type: header(&req_headers),
+            req_body,
+            res_content_type: header(&res_headers),
+            res_body,
+        })
+        .collect();
+
+    if samples.is_empty() {
+        return Err("No assets to export".to_string());
+    }
+    let title = host_filter.as_deref().unwrap_or("Captured API");
+    Ok(build_openapi(title, &samples))
+}
+
+/// Synthesizes an OpenAPI 3.0 document (pretty-printed JSON) from the live
+/// inventory, optionally limited to one host.
+#[tauri::command]
+pub async fn export_openapi(host_filter: Option<String>) -> Result<String, String> {
+    serde_json::to_string_pretty(&inventory_spec(host_filter).await?).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_merge() {
+        let a = Schema::infer(&json!({"id": 1, "name": "a", "tags": []}));
+        let b = Schema::infer(&json!({"id": 2.5, "tags": ["x"], "email": null}));
+        let merged = a.merge(b).to_value();
+        assert_eq!(merged["properties"]["id"], json!({"type": "number"}));
+        assert_eq!(merged["properties"]["tags"]["items"], json!({"type": "string"}));
+        assert_eq!(merged["properties"]["email"], json!({"nullable": true}));
+        assert_eq!(merged["required"], json!(["id", "tags"]));
+    }
+
+    #[test]
+    fn test_build_openapi() {
+        let sample = |url: &str, template: &str, method: &str, status, res_body: Option<&str>| SpecSample {
+            url: url.to_string(),
+            template: template.to_string(),
+            method: method.to_string(),
+            status_code: status,
+            req_content_type: None,
+            req_body: None,
+            res_content_type: None,
+            res_body: res_body.map(str::to_string),
+        };
+        let spec = build_openapi("test", &[
+            sample("https://api.example.com/users/1?expand=true", "https://api.example.com/users/{id}", "GET", Some(200), Some(r#"{"id":1}"#)),
+            sample("https://api.example.com/users/2", "https://api.example.com/users/{id}", "GET", Some(404), None),
+            sample("https://api.example.com/users/1/orders/7", "https://api.example.com/users/{id}/orders/{id}", "DELETE", None, None),
+        ]);
+
+        assert_eq!(spec["servers"], json!([{"url": "https://api.example.com"}]));
+        let get = &spec["paths"]["/users/{id}"]["get"];
+        assert_eq!(get["parameters"][0]["schema"], json!({"type": "integer"}));
+        assert_eq!(get["parameters"][1]["name"], "expand");
+        assert_eq!(get["parameters"][1]["required"], false);
+        assert_eq!(get["parameters"][1]["schema"], json!({"type": "boolean"}));
+        assert_eq!(get["responses"]["200"]["content"]["application/json"]["schema"]["required"], json!(["id"]));
+        assert_eq!(get["responses"]["404"]["description"], "Not Found");
+
+        let delete = &spec["paths"]["/users/{id}/orders/{id2}"]["delete"];
+        assert_eq!(delete["parameters"][1]["name"], "id2");
+        assert!(delete["responses"]["default"].is_object());
+    }
+
+    #[test]
+    fn test_apply_docs() {
+        let mut op = json!({
+            "parameters": [{"name": "id", "in": "path"}],
+            "requestBody": {"content": {"application/json": {"schema": Schema::infer(&json!({"user": {"email": "a"}})).to_value()}}},
+            "responses": {"200": {"description": "OK", "content": {"application/json": {"schema": Schema::infer(&json!([{"id": 1}])).to_value()}}}},
+        });
+        assert_eq!(operation_fields(&op), vec!["user", "user.email", "[].id"]);
+
+        let docs: OperationDocs = serde_json::from_value(json!({
+            "summary": "Update a user",
+            "parameters": {"id": "User ID", "missing": "ignored"},
+            "fields": {"user.email": "Contact address", "[].id": "Order ID", "user": " "},
+        }))
+        .unwrap();
+        apply_docs(&mut op, &docs);
+        assert_eq!(op["summary"], "Update a user");
+        assert!(op.get("description").is_none());
+        assert_eq!(op["parameters"][0]["description"], "User ID");
+        let request = &op["requestBody"]["content"]["application/json"]["schema"]["properties"]["user"];
+        assert!(request.get("description").is_none());
+        assert_eq!(request["properties"]["email"]["description"], "Contact address");
+        assert_eq!(op["responses"]["200"]["content"]["application/json"]["schema"]["items"]["properties"]["id"]["description"], "Order ID");
+    }
+}
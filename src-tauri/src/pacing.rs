@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Longest pause taken for one throttled response.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Longest gap a rate can ask for; lower rates are treated as this one.
+const MAX_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How fast a fuzz run may send, so fragile targets aren't overwhelmed.
+/// All zeros sends as fast as responses come back.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Pacing {
+    /// 0 for no limit
+    pub requests_per_second: f64,
+    /// Minimum gap between the starts of two requests; with a rate as
+    /// well, the longer of the two applies
+    pub delay_ms: u64,
+    /// Random extra pause of up to this many ms
+    pub jitter_ms: u64,
+    /// Retries after a 429 or 503, waiting for `Retry-After` or
+    /// exponentially longer each time; 0 disables backoff
+    pub max_retries: u32,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Self { requests_per_second: 0.0, delay_ms: 0, jitter_ms: 0, max_retries: 3 }
+    }
+}
+
+impl Pacing {
+    /// Minimum gap between the starts of two requests, before jitter.
+    pub fn interval(&self) -> Duration {
+        let rate = if self.requests_per_second > 0.0 {
+            // Tiny or subnormal rates overflow a Duration
+            Duration::try_from_secs_f64(1.0 / self.requests_per_second).map_or(MAX_INTERVAL, |d| d.min(MAX_INTERVAL))
+        } else {
+            Duration::ZERO
+        };
+        rate.max(Duration::from_millis(self.delay_ms))
+    }
+
+    /// Wait before retry `attempt` (0-based) of a throttled response, or
+    /// `None` when the status isn't throttling or retries are used up.
+    pub fn backoff(&self, status: u16, attempt: u32, retry_after: Option<&str>) -> Option<Duration> {
+        if !matches!(status, 429 | 503) || attempt >= self.max_retries {
+            return None;
+        }
+        let hinted = retry_after.and_then(|v| v.trim().parse::<u64>().ok()).map(Duration::from_secs);
+        let exponential = Duration::from_secs(1) * 2u32.saturating_pow(attempt);
+        Some(hinted.unwrap_or(exponential).min(MAX_BACKOFF))
+    }
+}
+
+/// Spaces out the requests of one run according to its `Pacing`. Workers
+/// sharing a pacer are spaced out together.
+pub struct Pacer {
+    pacing: Pacing,
+    last: tokio::sync::Mutex<Option<Instant>>,
+}
+
+impl Pacer {
+    pub fn new(pacing: &Pacing) -> Self {
+        Self { pacing: pacing.clone(), last: tokio::sync::Mutex::new(None) }
+    }
+
+    /// Sleeps until the next request may start.
+    pub async fn wait(&self) {
+        let mut last_start = self.last.lock().await;
+        if let Some(last) = *last_start {
+            let jitter = match self.pacing.jitter_ms {
+                0 => 0,
+                max => (uuid::Uuid::new_v4().as_u128() % (max as u128 + 1)) as u64,
+            };
+            let gap = self.pacing.interval() + Duration::from_millis(jitter);
+            tokio::time::sleep(gap.saturating_sub(last.elapsed())).await;
+        }
+        *last_start = Some(Instant::now());
+    }
+
+    /// Runs `send` once the pacer allows, and again after each throttled
+    /// response for as long as `Pacing::backoff` allows and `cancel` isn't
+    /// set. `throttle` reads a response's status and `Retry-After` value.
+    pub async fn send<T, Fut>(
+        &self,
+        cancel: Option<&AtomicBool>,
+        mut send: impl FnMut() -> Fut,
+        throttle: impl Fn(&T) -> (u16, Option<String>),
+    ) -> T
+    where
+        Fut: Future<Output = T>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.wait().await;
+            let res = send().await;
+            let (status, retry_after) = throttle(&res);
+            match self.pacing.backoff(status, attempt, retry_after.as_deref()) {
+                Some(wait) if !cancel.is_some_and(|c| c.load(Ordering::Relaxed)) => {
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                _ => return res,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(Pacing::default().interval(), Duration::ZERO);
+        let pacing = Pacing { requests_per_second: 4.0, ..Default::default() };
+        assert_eq!(pacing.interval(), Duration::from_millis(250));
+        let pacing = Pacing { requests_per_second: 4.0, delay_ms: 1000, ..Default::default() };
+        assert_eq!(pacing.interval(), Duration::from_secs(1));
+        let pacing = Pacing { requests_per_second: f64::MIN_POSITIVE, ..Default::default() };
+        assert_eq!(pacing.interval(), MAX_INTERVAL);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let pacing = Pacing::default();
+        assert_eq!(pacing.backoff(200, 0, None), None);
+        assert_eq!(pacing.backoff(429, 0, None), Some(Duration::from_secs(1)));
+        assert_eq!(pacing.backoff(503, 2, None), Some(Duration::from_secs(4)));
+        assert_eq!(pacing.backoff(429, 3, None), None);
+        assert_eq!(pacing.backoff(429, 0, Some("7")), Some(Duration::from_secs(7)));
+        assert_eq!(pacing.backoff(429, 0, Some("3600")), Some(MAX_BACKOFF));
+        // HTTP dates aren't worth parsing; fall back to exponential
+        assert_eq!(pacing.backoff(429, 1, Some("Wed, 21 Oct 2015 07:28:00 GMT")), Some(Duration::from_secs(2)));
+        assert_eq!(Pacing { max_retries: 0, ..Default::default() }.backoff(429, 0, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_throttled() {
+        let pacer = Pacer::new(&Pacing::default());
+        let calls = &std::sync::atomic::AtomicU32::new(0);
+        let throttle = |status: &u16| (*status, Some("0".to_string()));
+        let status = pacer.send(None, move || async move { if calls.fetch_add(1, Ordering::Relaxed) < 2 { 429 } else { 200 } }, throttle).await;
+        assert_eq!((status, calls.load(Ordering::Relaxed)), (200, 3));
+
+        calls.store(0, Ordering::Relaxed);
+        let cancel = AtomicBool::new(true);
+        let status = pacer.send(Some(&cancel), move || async move { calls.fetch_add(1, Ordering::Relaxed); 429 }, throttle).await;
+        assert_eq!((status, calls.load(Ordering::Relaxed)), (429, 1));
+    }
+}
@@ -0,0 +1,81 @@
+//! PKCS#12 bundles for handing the interception CA to tools that want a
+//! certificate and key in one file, and for importing one, through
+//! `p12-keystore`. Written keys are encrypted with PBES2 (PBKDF2-HMAC-SHA256,
+//! AES-256-CBC) and the whole bundle is MACed with HMAC-SHA256, matching
+//! what current OpenSSL writes. Reading also accepts the legacy 3DES and
+//! RC2 schemes older tools still produce.
+
+use p12_keystore::error::Error;
+use p12_keystore::{Certificate, KeyStore, KeyStoreEntry, PrivateKeyChain};
+use pkcs8::EncryptedPrivateKeyInfo;
+
+fn describe(error: Error) -> String {
+    match error {
+        Error::MacError(_) => "Wrong passphrase".to_string(),
+        Error::UnsupportedContentType => "Public-key protected PKCS#12 files are not supported".to_string(),
+        Error::DerError(_) | Error::X509Error(_) | Error::InvalidVersion => "Not a valid PKCS#12 file".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Bundles a certificate with its PKCS#8 key, encrypting the key and
+/// MACing the bundle with `password`.
+pub fn build(cert_der: &[u8], key_pkcs8: &[u8], friendly_name: &str, password: &str) -> Result<Vec<u8>, String> {
+    let certificate = Certificate::from_der(cert_der).map_err(describe)?;
+    let key_id = ring::digest::digest(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY, cert_der);
+    let mut keystore = KeyStore::new();
+    keystore.add_entry(
+        friendly_name,
+        KeyStoreEntry::PrivateKeyChain(PrivateKeyChain::new(key_pkcs8, key_id.as_ref(), [certificate])),
+    );
+    keystore.writer(password).write().map_err(describe)
+}
+
+/// Certificates and PKCS#8 keys found in a bundle.
+#[derive(Debug, Default)]
+pub struct Contents {
+    pub certificates: Vec<Vec<u8>>,
+    pub keys: Vec<Vec<u8>>,
+}
+
+/// PKCS#8 key from an `EncryptedPrivateKeyInfo`, as found in
+/// `ENCRYPTED PRIVATE KEY` PEM files.
+pub fn decrypt_private_key(encrypted: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    let info = EncryptedPrivateKeyInfo::try_from(encrypted).map_err(|_| "Not a valid encrypted private key".to_string())?;
+    let key = info.decrypt(password).map_err(|_| "Wrong passphrase".to_string())?;
+    Ok(key.as_bytes().to_vec())
+}
+
+/// Reads a bundle, checking its MAC and decrypting with `password`.
+pub fn parse(pfx: &[u8], password: &str) -> Result<Contents, String> {
+    let keystore = KeyStore::from_pkcs12(pfx, password).map_err(describe)?;
+    let mut contents = Contents::default();
+    for (_, entry) in keystore.entries() {
+        match entry {
+            KeyStoreEntry::PrivateKeyChain(chain) => {
+                contents.keys.push(chain.key().to_vec());
+                contents.certificates.extend(chain.chain().iter().map(|cert| cert.as_der().to_vec()));
+            }
+            KeyStoreEntry::Certificate(cert) => contents.certificates.push(cert.as_der().to_vec()),
+        }
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = rcgen::KeyPair::generate().unwrap();
+        let cert = rcgen::CertificateParams::new(vec!["ca.example.com".to_string()]).unwrap().self_signed(&key).unwrap();
+        let bundle = build(cert.der(), &key.serialize_der(), "Test CA", "s3cret").unwrap();
+
+        let contents = parse(&bundle, "s3cret").unwrap();
+        assert_eq!(contents.certificates, vec![cert.der().to_vec()]);
+        assert_eq!(contents.keys, vec![key.serialize_der()]);
+        assert_eq!(parse(&bundle, "wrong").unwrap_err(), "Wrong passphrase");
+        assert!(parse(b"not a bundle", "s3cret").is_err());
+    }
+}
@@ -3,6 +3,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use tauri::Manager;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RulePlugin {
@@ -11,6 +12,8 @@ pub struct RulePlugin {
     pub severity: String,
     pub regex: String,
     pub description: Option<String>,
+    pub references: Option<Vec<String>>,
+    pub remediation: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -44,6 +47,8 @@ pub fn load_plugins(app_handle: &tauri::AppHandle) -> Vec<PluginPack> {
                 severity: "Info".to_string(),
                 regex: r"(?i)[a-z0-9.-]+\.s3\.amazonaws\.com".to_string(),
                 description: Some("Discovered a reference to an AWS S3 bucket.".to_string()),
+                references: Some(vec!["https://cwe.mitre.org/data/definitions/200.html".to_string()]),
+                remediation: Some("Verify the bucket policy blocks public listing and reads.".to_string()),
             }],
         };
         let yaml = serde_yml::to_string(&sample).unwrap_or_default();
@@ -93,6 +98,8 @@ pub fn scan_with_plugins(content: &str, plugins: &[PluginPack]) -> Vec<Finding>
                         notes: Some(format!("Pack: {} v{}", pack.name, pack.version)),
                         is_false_positive: Some(false),
                         severity_override: None,
+                        references: rule.references.clone(),
+                        remediation: rule.remediation.clone(),
                     });
                 }
             }
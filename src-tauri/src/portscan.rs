@@ -0,0 +1,466 @@
+use crate::assets::{BatchImportResult, CreateAssetRequest};
+use crate::db::get_db;
+use crate::recon::ReconProgress;
+use crate::JobState;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::{BTreeSet, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// Common web, API and infrastructure ports scanned when none are given.
+const DEFAULT_PORTS: &[u16] = &[
+    21, 22, 25, 80, 81, 443, 1433, 2375, 3000, 3306, 4443, 5000, 5432, 5601, 6379, 8000, 8008, 8080, 8081, 8088,
+    8443, 8888, 9000, 9090, 9200, 9443, 11211, 15672, 27017,
+];
+
+const DEFAULT_CONCURRENCY: usize = 200;
+const MAX_CONCURRENCY: usize = 1000;
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+/// Host and port pairs allowed in one job.
+const MAX_PROBES: usize = 200_000;
+
+/// How long an open port gets to speak first before it is sent an HTTP request.
+const BANNER_WAIT: Duration = Duration::from_millis(500);
+
+/// Bytes read from a banner or HTTP response.
+const MAX_READ: usize = 16 * 1024;
+
+const MAX_BANNER_CHARS: usize = 256;
+const MAX_TITLE_CHARS: usize = 200;
+
+/// Probes between `portscan-progress` events.
+const PROGRESS_EVERY: usize = 250;
+
+#[derive(Deserialize, Debug)]
+pub struct PortScanRequest {
+    /// Host names or IP addresses, typically recon results
+    pub hosts: Vec<String>,
+    /// Ports and ranges such as `80,443,8000-8100`; defaults to common ports
+    pub ports: Option<String>,
+    pub concurrency: Option<usize>,
+    /// Connect and read timeout per port
+    pub timeout_ms: Option<u64>,
+    /// Profile the run must be allowed by; the default one when unset
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+pub struct OpenPort {
+    pub id: i64,
+    pub host: String,
+    pub ip: String,
+    pub port: i64,
+    /// `http`, `https`, `ssh`, or a guess from the port number
+    pub service: Option<String>,
+    /// First line a non-HTTP service sent on connect
+    pub banner: Option<String>,
+    pub http_status: Option<i64>,
+    pub http_title: Option<String>,
+    /// Root URL for web services; only these can be promoted to assets
+    pub url: Option<String>,
+    /// Set once promoted
+    pub asset_id: Option<i64>,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PortScanSummary {
+    pub job_id: String,
+    pub hosts: usize,
+    /// Hosts skipped because they did not resolve
+    pub unresolved: Vec<String>,
+    pub total: usize,
+    pub checked: usize,
+    pub open: usize,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HttpProbe {
+    pub status: u16,
+    pub title: Option<String>,
+}
+
+#[derive(Debug)]
+struct Probe {
+    host: String,
+    ip: IpAddr,
+    port: u16,
+    service: Option<String>,
+    banner: Option<String>,
+    http: Option<HttpProbe>,
+    url: Option<String>,
+}
+
+/// Ports from a list like `22, 80,8000-8010`, sorted and deduplicated.
+pub fn parse_ports(spec: &str) -> Result<Vec<u16>, String> {
+    let port = |s: &str| match s.trim().parse::<u16>() {
+        Ok(p) if p > 0 => Ok(p),
+        _ => Err(format!("'{}' is not a port number", s.trim())),
+    };
+    let mut ports = BTreeSet::new();
+    for part in spec.split([',', ' ', '\n']).map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (port(start)?, port(end)?);
+                if start > end {
+                    return Err(format!("Port range '{}' is reversed", part));
+                }
+                ports.extend(start..=end);
+            }
+            None => {
+                ports.insert(port(part)?);
+            }
+        }
+    }
+    if ports.is_empty() {
+        return Err("No ports to scan".to_string());
+    }
+    Ok(ports.into_iter().collect())
+}
+
+/// Host name or IP from user input such as `https://api.example.com:8443/`.
+pub fn scan_host(input: &str) -> Result<String, String> {
+    let trimmed = input.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = trimmed.parse::<IpAddr>() {
+        return Ok(ip.to_string());
+    }
+    crate::recon::normalize_domain(input)
+}
+
+fn service_name(port: u16) -> Option<&'static str> {
+    Some(match port {
+        21 => "ftp",
+        22 => "ssh",
+        25 => "smtp",
+        1433 => "mssql",
+        2375 => "docker",
+        3306 => "mysql",
+        5432 => "postgres",
+        6379 => "redis",
+        9200 => "elasticsearch",
+        11211 => "memcached",
+        15672 => "rabbitmq",
+        27017 => "mongodb",
+        _ => return None,
+    })
+}
+
+/// Printable first line of what a service sent on connect.
+pub fn clean_banner(raw: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(raw);
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line: String = line.chars().filter(|c| !c.is_control()).take(MAX_BANNER_CHARS).collect();
+    if line.is_empty() { None } else { Some(line) }
+}
+
+/// Text of the first `<title>` element, whitespace collapsed.
+pub fn html_title(body: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title: String = body[start..end].split_whitespace().collect::<Vec<_>>().join(" ").chars().take(MAX_TITLE_CHARS).collect();
+    if title.is_empty() { None } else { Some(title) }
+}
+
+/// Status and title from a raw HTTP/1.x response, if it is one.
+pub fn parse_http(raw: &[u8]) -> Option<HttpProbe> {
+    let text = String::from_utf8_lossy(raw);
+    let status = text.strip_prefix("HTTP/")?.split_whitespace().nth(1)?.parse().ok()?;
+    let body = text.split_once("\r\n\r\n").map(|(_, b)| b).unwrap_or("");
+    Some(HttpProbe { status, title: html_title(body) })
+}
+
+/// True when a plain HTTP reply says the port expects TLS.
+pub fn wants_tls(raw: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(raw).to_ascii_lowercase();
+    text.contains("sent to https port") || text.contains("plain http to an ssl")
+}
+
+/// Root URL of a web service, leaving out the scheme's default port.
+pub fn service_url(scheme: &str, host: &str, port: u16) -> String {
+    let host = if host.contains(':') { format!("[{}]", host) } else { host.to_string() };
+    match (scheme, port) {
+        ("http", 80) | ("https", 443) => format!("{}://{}/", scheme, host),
+        _ => format!("{}://{}:{}/", scheme, host, port),
+    }
+}
+
+async fn read_some(stream: &mut TcpStream, wait: Duration) -> Vec<u8> {
+    let mut buf = vec![0u8; MAX_READ];
+    let mut len = 0;
+    let _ = timeout(wait, async {
+        while len < buf.len() {
+            match stream.read(&mut buf[len..]).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => len += n,
+            }
+        }
+    })
+    .await;
+    buf.truncate(len);
+    buf
+}
+
+/// Up to `limit` bytes of a response body; the rest is never downloaded.
+pub async fn read_body(response: &mut reqwest::Response, limit: usize) -> Vec<u8> {
+    let mut body = Vec::new();
+    while body.len() < limit {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            _ => break,
+        }
+    }
+    body.truncate(limit);
+    body
+}
+
+async fn https_probe(client: &reqwest::Client, host: &str, port: u16) -> Option<HttpProbe> {
+    let mut response = client.get(service_url("https", host, port)).send().await.ok()?;
+    let status = response.status().as_u16();
+    let body = read_body(&mut response, MAX_READ).await;
+    Some(HttpProbe { status, title: html_title(&String::from_utf8_lossy(&body)) })
+}
+
+/// Connects to one port and, if open, works out what is listening: a
+/// banner from services that speak first, otherwise an HTTP request, then
+/// HTTPS when plain HTTP gets nowhere.
+async fn probe(client: &reqwest::Client, host: String, ip: IpAddr, port: u16, wait: Duration) -> Option<Probe> {
+    let mut stream = timeout(wait, TcpStream::connect(SocketAddr::new(ip, port))).await.ok()?.ok()?;
+    let mut found = Probe { service: service_name(port).map(str::to_string), host, ip, port, banner: None, http: None, url: None };
+
+    let mut first = vec![0u8; MAX_READ];
+    if let Ok(Ok(n @ 1..)) = timeout(BANNER_WAIT.min(wait), stream.read(&mut first)).await {
+        found.banner = clean_banner(&first[..n]);
+        if found.banner.as_deref().is_some_and(|b| b.starts_with("SSH-")) {
+            found.service = Some("ssh".to_string());
+        }
+        return Some(found);
+    }
+
+    let request = format!("GET / HTTP/1.0\r\nHost: {}\r\nUser-Agent: Mozilla/5.0\r\nConnection: close\r\n\r\n", found.host);
+    let reply = match stream.write_all(request.as_bytes()).await {
+        Ok(()) => read_some(&mut stream, wait).await,
+        Err(_) => Vec::new(),
+    };
+    let plain = parse_http(&reply).filter(|_| !wants_tls(&reply));
+    let (scheme, http) = match plain {
+        Some(http) => ("http", Some(http)),
+        None => ("https", https_probe(client, &found.host, port).await),
+    };
+    if let Some(http) = http {
+        found.service = Some(scheme.to_string());
+        found.url = Some(service_url(scheme, &found.host, port));
+        found.http = Some(http);
+    }
+    Some(found)
+}
+
+async fn resolve(host: &str) -> Option<IpAddr> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Some(ip);
+    }
+    let addrs: Vec<IpAddr> = tokio::net::lookup_host((host, 0)).await.ok()?.map(|a| a.ip()).collect();
+    addrs.iter().find(|ip| ip.is_ipv4()).or(addrs.first()).copied()
+}
+
+async fn store(probe: &Probe) -> Result<OpenPort, String> {
+    sqlx::query_as::<_, OpenPort>(
+        "INSERT INTO open_ports (host, ip, port, service, banner, http_status, http_title, url) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(host, port) DO UPDATE SET ip = excluded.ip, service = excluded.service, banner = excluded.banner,
+             http_status = excluded.http_status, http_title = excluded.http_title, url = excluded.url, last_seen = CURRENT_TIMESTAMP
+         RETURNING id, host, ip, port, service, banner, http_status, http_title, url, asset_id, first_seen, last_seen",
+    )
+    .bind(&probe.host)
+    .bind(probe.ip.to_string())
+    .bind(probe.port as i64)
+    .bind(&probe.service)
+    .bind(&probe.banner)
+    .bind(probe.http.as_ref().map(|h| h.status as i64))
+    .bind(probe.http.as_ref().and_then(|h| h.title.clone()))
+    .bind(&probe.url)
+    .fetch_one(&get_db())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// TCP connect scan over `hosts` in the background; returns the job id.
+/// Open ports are stored and stream through `portscan-open` as
+/// `(job_id, port)`, `portscan-progress` reports counts and a
+/// `PortScanSummary` arrives with `portscan-complete`. Stop it with
+/// `cancel_recon`.
+#[tauri::command]
+pub async fn start_port_scan(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<JobState>>,
+    request: PortScanRequest,
+) -> Result<String, String> {
+    crate::scan_profiles::authorize(request.scan_profile_id, crate::scan_profiles::ActiveCheck::Discovery).await?;
+    let ports = match request.ports.as_deref().filter(|p| !p.trim().is_empty()) {
+        Some(spec) => parse_ports(spec)?,
+        None => DEFAULT_PORTS.to_vec(),
+    };
+    let mut hosts: Vec<String> = Vec::new();
+    for input in request.hosts.iter().filter(|h| !h.trim().is_empty()) {
+        let host = scan_host(input)?;
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    if hosts.is_empty() {
+        return Err("No hosts to scan".to_string());
+    }
+    if hosts.len() * ports.len() > MAX_PROBES {
+        return Err(format!("{} hosts x {} ports is more than {} probes; narrow the port list", hosts.len(), ports.len(), MAX_PROBES));
+    }
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let wait = Duration::from_millis(request.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).clamp(50, 30_000));
+    let client = reqwest::Client::builder()
+        .timeout(wait * 2)
+        .danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "portscan-complete",
+        move |app_handle, job_id, cancel| async move {
+            let mut resolved: HashMap<String, IpAddr> = HashMap::new();
+            let mut lookups = crate::jobs::Workers::new(hosts.clone(), concurrency, |host: String| async move {
+                let ip = resolve(&host).await;
+                (host, ip)
+            });
+            while let Some(joined) = lookups.next(&cancel).await {
+                if let Ok((host, Some(ip))) = joined {
+                    resolved.insert(host, ip);
+                }
+            }
+            let mut targets = Vec::new();
+            let mut unresolved = Vec::new();
+            for host in &hosts {
+                match resolved.get(host) {
+                    Some(&ip) => targets.extend(ports.iter().map(|&port| (host.clone(), ip, port))),
+                    None => unresolved.push(host.clone()),
+                }
+            }
+            let total = targets.len();
+
+            let mut workers = crate::jobs::Workers::new(targets, concurrency, |(host, ip, port): (String, IpAddr, u16)| {
+                let client = client.clone();
+                async move { probe(&client, host, ip, port, wait).await }
+            });
+            let (mut checked, mut open) = (0usize, 0);
+            while let Some(joined) = workers.next(&cancel).await {
+                checked += 1;
+                if let Ok(Some(found)) = joined {
+                    match store(&found).await {
+                        Ok(row) => {
+                            open += 1;
+                            let _ = app_handle.emit("portscan-open", (&job_id, row));
+                        }
+                        Err(e) => eprintln!("Failed to store open port {}:{}: {}", found.host, found.port, e),
+                    }
+                }
+                if checked.is_multiple_of(PROGRESS_EVERY) || checked == total {
+                    let _ = app_handle.emit("portscan-progress", ReconProgress { job_id: job_id.clone(), checked, total, found: open });
+                }
+            }
+            PortScanSummary { job_id, hosts: hosts.len(), unresolved, total, checked, open, cancelled: false }
+        },
+        |_, summary, cancelled| PortScanSummary { cancelled, ..summary },
+    ))
+}
+
+/// Stored open ports, optionally for one host, by host then port.
+#[tauri::command]
+pub async fn list_open_ports(host: Option<String>) -> Result<Vec<OpenPort>, String> {
+    sqlx::query_as::<_, OpenPort>(
+        "SELECT id, host, ip, port, service, banner, http_status, http_title, url, asset_id, first_seen, last_seen
+         FROM open_ports WHERE ? IS NULL OR host = ? ORDER BY host, port",
+    )
+    .bind(&host)
+    .bind(&host)
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Adds the web services among `ids` to the inventory. Ports that don't
+/// serve HTTP, or were already promoted, are skipped.
+#[tauri::command]
+pub async fn promote_open_ports(ids: Vec<i64>) -> Result<BatchImportResult, String> {
+    let pool = get_db();
+    let (mut added, mut skipped) = (0, 0);
+    for id in ids {
+        let row: Option<(Option<String>, Option<i64>, Option<i64>)> = sqlx::query_as(
+            "SELECT p.url, p.http_status, a.id FROM open_ports p LEFT JOIN assets a ON a.id = p.asset_id AND a.deleted_at IS NULL WHERE p.id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        let Some((Some(url), status_code, None)) = row else {
+            skipped += 1;
+            continue;
+        };
+        let asset_id = crate::assets::add_asset(CreateAssetRequest {
+            url,
+            source: "Port Scan".to_string(),
+            method: Some("GET".to_string()),
+            status_code,
+            req_headers: HashMap::new(),
+            res_headers: HashMap::new(),
+            req_body: None,
+            res_body: None,
+            findings: Vec::new(),
+        })
+        .await?;
+        sqlx::query("UPDATE open_ports SET asset_id = ? WHERE id = ?")
+            .bind(asset_id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        added += 1;
+    }
+    Ok(BatchImportResult { added, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ports() {
+        assert_eq!(parse_ports("443, 80,8000-8002 80").unwrap(), vec![80, 443, 8000, 8001, 8002]);
+        assert!(parse_ports("0").is_err());
+        assert!(parse_ports("90-80").is_err());
+        assert!(parse_ports("http").is_err());
+        assert!(parse_ports(" , ").is_err());
+
+        let raw = b"HTTP/1.1 200 OK\r\nServer: nginx\r\n\r\n<html><head><TITLE>\n  Admin   Console </title></head>";
+        assert_eq!(parse_http(raw), Some(HttpProbe { status: 200, title: Some("Admin Console".to_string()) }));
+        assert_eq!(parse_http(b"SSH-2.0-OpenSSH_9.6\r\n"), None);
+        assert!(wants_tls(b"HTTP/1.1 400 Bad Request\r\n\r\nThe plain HTTP request was sent to HTTPS port"));
+        assert_eq!(clean_banner(b"\r\n220 mail.example.com ESMTP\r\n250 ok"), Some("220 mail.example.com ESMTP".to_string()));
+
+        assert_eq!(service_url("https", "api.example.com", 443), "https://api.example.com/");
+        assert_eq!(service_url("http", "::1", 8080), "http://[::1]:8080/");
+        assert_eq!(service_url("https", "::1", 443), "https://[::1]/");
+        assert_eq!(scan_host("https://API.example.com:8443/x").unwrap(), "api.example.com");
+        assert_eq!(scan_host("[::1]").unwrap(), "::1");
+    }
+}
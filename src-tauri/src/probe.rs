@@ -0,0 +1,473 @@
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::recon::ReconProgress;
+use crate::x509::CertSummary;
+use crate::JobState;
+use chrono::{DateTime, Utc};
+use publicsuffix::Psl;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tauri::Emitter;
+
+const DEFAULT_CONCURRENCY: usize = 20;
+const MAX_CONCURRENCY: usize = 200;
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Bytes of a page read to find its title.
+const MAX_BODY: usize = 256 * 1024;
+
+/// Hosts between `probe-progress` events.
+const PROGRESS_EVERY: usize = 10;
+
+/// Names from certificates added to one job's queue at most.
+const MAX_DISCOVERED: usize = 1000;
+
+/// Certificates expiring within this many days get a finding.
+const EXPIRY_WARNING_DAYS: i64 = 30;
+
+const CWE_295: &str = "https://cwe.mitre.org/data/definitions/295.html";
+const CWE_298: &str = "https://cwe.mitre.org/data/definitions/298.html";
+
+#[derive(Deserialize, Debug)]
+pub struct ProbeRequest {
+    pub hosts: Vec<String>,
+    pub concurrency: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    /// Profile the run must be allowed by; the default one when unset
+    #[serde(default)]
+    pub scan_profile_id: Option<i64>,
+}
+
+/// What a host serves at `/`, HTTPS preferred over plain HTTP.
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
+pub struct HostProbe {
+    pub id: i64,
+    pub host: String,
+    pub alive: bool,
+    /// `https` or `http`; unset when neither answered
+    pub scheme: Option<String>,
+    pub url: Option<String>,
+    pub status_code: Option<i64>,
+    /// Absolute `Location` of a redirect response
+    pub redirect: Option<String>,
+    pub server: Option<String>,
+    /// From `Content-Length`, or the bytes read when it is missing
+    pub content_length: Option<i64>,
+    pub title: Option<String>,
+    /// Why the last scheme tried failed, for dead hosts
+    pub error: Option<String>,
+    pub probed_at: String,
+    /// Shodan-style favicon hash, set by fingerprinting
+    #[sqlx(default)]
+    pub favicon_hash: Option<i64>,
+    /// Certificate presented over HTTPS
+    pub cert_subject: Option<String>,
+    pub cert_issuer: Option<String>,
+    #[sqlx(json(nullable))]
+    pub cert_sans: Option<Vec<String>>,
+    pub cert_not_after: Option<String>,
+    pub cert_self_signed: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProbeSummary {
+    pub job_id: String,
+    pub total: usize,
+    pub checked: usize,
+    pub alive: usize,
+    /// Names taken from certificates and probed as well
+    pub discovered: usize,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Outcome {
+    pub scheme: Option<&'static str>,
+    pub url: Option<String>,
+    pub status_code: Option<i64>,
+    pub redirect: Option<String>,
+    pub server: Option<String>,
+    pub content_length: Option<i64>,
+    pub title: Option<String>,
+    pub error: Option<String>,
+    pub cert: Option<CertSummary>,
+}
+
+/// The public-suffix list, for telling registrable domains from the
+/// suffixes they sit under. Refresh `data/public_suffix_list.dat` from
+/// publicsuffix.org now and then.
+static PUBLIC_SUFFIXES: LazyLock<publicsuffix::List> =
+    LazyLock::new(|| include_str!("../data/public_suffix_list.dat").parse().unwrap());
+
+/// Suffixes certificate names must end in to be probed: each host's
+/// registrable domain, or the host itself when it has none (a bare public
+/// suffix or a single label).
+pub fn scope_suffixes(hosts: &[String]) -> Vec<String> {
+    let mut suffixes: Vec<String> = Vec::new();
+    for host in hosts.iter().filter(|h| h.parse::<std::net::IpAddr>().is_err()) {
+        let host = host.trim_end_matches('.').to_lowercase();
+        let suffix = PUBLIC_SUFFIXES
+            .domain(host.as_bytes())
+            .and_then(|domain| std::str::from_utf8(domain.as_bytes()).ok().map(str::to_string))
+            .unwrap_or(host);
+        if !suffixes.contains(&suffix) {
+            suffixes.push(suffix);
+        }
+    }
+    suffixes
+}
+
+/// Host names from a certificate's SANs that fall under `suffixes`.
+/// Wildcards contribute their base domain; IP addresses are skipped.
+pub fn san_hosts(sans: &[String], suffixes: &[String]) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    for san in sans {
+        let name = san.trim().trim_start_matches("*.").trim_end_matches('.').to_lowercase();
+        let in_scope = suffixes.iter().any(|s| name == *s || name.ends_with(&format!(".{}", s)));
+        if in_scope && name.parse::<std::net::IpAddr>().is_err() && !hosts.contains(&name) {
+            hosts.push(name);
+        }
+    }
+    hosts
+}
+
+fn cert_finding(rule_id: &str, name: &str, severity: FindingSeverity, description: String, evidence: String, remediation: &str) -> Finding {
+    Finding {
+        id: None,
+        rule_id: rule_id.to_string(),
+        name: name.to_string(),
+        description,
+        severity,
+        match_content: evidence,
+        notes: None,
+        is_false_positive: Some(false),
+        severity_override: None,
+        references: Some(vec![CWE_295.to_string(), CWE_298.to_string()]),
+        remediation: Some(remediation.to_string()),
+    }
+}
+
+/// Expired, soon-expiring and self-signed certificate findings.
+pub fn cert_findings(cert: &CertSummary, now: DateTime<Utc>) -> Vec<Finding> {
+    let evidence = format!("{} issued by {}, valid until {}", cert.subject, cert.issuer, cert.not_after.format("%Y-%m-%d"));
+    let mut findings = Vec::new();
+    let days_left = (cert.not_after - now).num_days();
+    if cert.not_after < now {
+        findings.push(cert_finding(
+            "TLS-CERT-EXPIRED",
+            "Expired TLS certificate",
+            FindingSeverity::High,
+            format!("The certificate expired on {}. Clients either fail or are trained to click through warnings.", cert.not_after.format("%Y-%m-%d")),
+            evidence.clone(),
+            "Renew the certificate and automate renewal, e.g. with ACME.",
+        ));
+    } else if days_left <= EXPIRY_WARNING_DAYS {
+        findings.push(cert_finding(
+            "TLS-CERT-EXPIRING",
+            "TLS certificate expiring soon",
+            FindingSeverity::Low,
+            format!("The certificate expires in {} days.", days_left),
+            evidence.clone(),
+            "Renew the certificate before it expires and automate renewal, e.g. with ACME.",
+        ));
+    }
+    if cert.self_signed {
+        findings.push(cert_finding(
+            "TLS-CERT-SELF-SIGNED",
+            "Self-signed TLS certificate",
+            FindingSeverity::Medium,
+            "The certificate is signed by itself, so clients cannot verify the server and are open to interception.".to_string(),
+            evidence,
+            "Use a certificate issued by a trusted public or internal CA.",
+        ));
+    }
+    findings
+}
+
+/// `location` resolved against the URL that returned it.
+pub fn redirect_target(base: &str, location: &str) -> Option<String> {
+    let base = url::Url::parse(base).ok()?;
+    base.join(location.trim()).ok().map(|u| u.to_string())
+}
+
+pub(crate) fn probe_client(timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .tls_info(true)
+        .no_proxy()
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn fetch(client: &reqwest::Client, scheme: &'static str, host: &str) -> Result<Outcome, String> {
+    let url = crate::portscan::service_url(scheme, host, if scheme == "https" { 443 } else { 80 });
+    let mut response = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    let header = |name: reqwest::header::HeaderName| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let server = header(reqwest::header::SERVER);
+    let redirect = header(reqwest::header::LOCATION).filter(|_| response.status().is_redirection()).and_then(|l| redirect_target(&url, &l));
+    let declared = header(reqwest::header::CONTENT_LENGTH).and_then(|l| l.parse::<i64>().ok());
+    let status_code = Some(response.status().as_u16() as i64);
+    let cert = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .and_then(crate::x509::parse_certificate);
+
+    let body = crate::portscan::read_body(&mut response, MAX_BODY).await;
+    let title = crate::portscan::html_title(&String::from_utf8_lossy(&body));
+    Ok(Outcome {
+        scheme: Some(scheme),
+        url: Some(url),
+        status_code,
+        redirect,
+        server,
+        content_length: declared.or(Some(body.len() as i64)),
+        title,
+        error: None,
+        cert,
+    })
+}
+
+/// Tries HTTPS, then HTTP.
+pub(crate) async fn probe_host(client: &reqwest::Client, host: &str) -> Outcome {
+    let mut error = None;
+    for scheme in ["https", "http"] {
+        match fetch(client, scheme, host).await {
+            Ok(outcome) => return outcome,
+            Err(e) => error = Some(e),
+        }
+    }
+    Outcome { error, ..Default::default() }
+}
+
+pub(crate) async fn store(host: &str, outcome: &Outcome) -> Result<HostProbe, String> {
+    sqlx::query_as::<_, HostProbe>(
+        "INSERT INTO host_probes (host, alive, scheme, url, status_code, redirect, server, content_length, title, error,
+             cert_subject, cert_issuer, cert_sans, cert_not_after, cert_self_signed, probed_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+         ON CONFLICT(host) DO UPDATE SET alive = excluded.alive, scheme = excluded.scheme, url = excluded.url,
+             status_code = excluded.status_code, redirect = excluded.redirect, server = excluded.server,
+             content_length = excluded.content_length, title = excluded.title, error = excluded.error,
+             cert_subject = excluded.cert_subject, cert_issuer = excluded.cert_issuer, cert_sans = excluded.cert_sans,
+             cert_not_after = excluded.cert_not_after, cert_self_signed = excluded.cert_self_signed, probed_at = CURRENT_TIMESTAMP
+         RETURNING id, host, alive, scheme, url, status_code, redirect, server, content_length, title, error, probed_at, favicon_hash,
+             cert_subject, cert_issuer, cert_sans, cert_not_after, cert_self_signed",
+    )
+    .bind(host)
+    .bind(outcome.scheme.is_some())
+    .bind(outcome.scheme)
+    .bind(&outcome.url)
+    .bind(outcome.status_code)
+    .bind(&outcome.redirect)
+    .bind(&outcome.server)
+    .bind(outcome.content_length)
+    .bind(&outcome.title)
+    .bind(&outcome.error)
+    .bind(outcome.cert.as_ref().map(|c| c.subject.clone()))
+    .bind(outcome.cert.as_ref().map(|c| c.issuer.clone()))
+    .bind(outcome.cert.as_ref().map(|c| sqlx::types::Json(c.sans.clone())))
+    .bind(outcome.cert.as_ref().map(|c| c.not_after.to_rfc3339()))
+    .bind(outcome.cert.as_ref().map(|c| c.self_signed))
+    .fetch_one(&get_db())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Adds certificate findings to the host's root asset, creating the asset
+/// if needed. Findings already recorded for the same certificate are skipped.
+async fn record_cert_findings(url: &str, status_code: Option<i64>, findings: Vec<Finding>) -> Result<(), String> {
+    let pool = get_db();
+    let mode = crate::assets::identity_query_mode(&pool).await?;
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM assets WHERE identity = ?")
+        .bind(crate::assets::asset_identity("GET", url, mode))
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let asset_id = match existing {
+        Some(id) => id,
+        None => {
+            crate::assets::add_asset(crate::assets::CreateAssetRequest {
+                url: url.to_string(),
+                source: "Probe".to_string(),
+                method: Some("GET".to_string()),
+                status_code,
+                req_headers: Default::default(),
+                res_headers: Default::default(),
+                req_body: None,
+                res_body: None,
+                findings: Vec::new(),
+            })
+            .await?
+        }
+    };
+    for f in findings {
+        sqlx::query(
+            "INSERT INTO findings (asset_id, rule_id, name, severity, description, match_content, is_false_positive, refs, remediation, created_at, last_seen)
+             SELECT ?, ?, ?, ?, ?, ?, 0, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+             WHERE NOT EXISTS (SELECT 1 FROM findings WHERE asset_id = ? AND rule_id = ? AND match_content = ?)",
+        )
+        .bind(asset_id)
+        .bind(&f.rule_id)
+        .bind(&f.name)
+        .bind(f.severity)
+        .bind(&f.description)
+        .bind(&f.match_content)
+        .bind(f.references.map(sqlx::types::Json))
+        .bind(&f.remediation)
+        .bind(asset_id)
+        .bind(&f.rule_id)
+        .bind(&f.match_content)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    Ok(())
+}
+
+/// Requests `/` on each host over HTTPS, falling back to HTTP, in the
+/// background and returns the job id. Every host's result is stored and
+/// streams through `probe-result` as `(job_id, probe)`; `probe-progress`
+/// reports counts and a `ProbeSummary` arrives with `probe-complete`. Stop
+/// it with `cancel_recon`.
+///
+/// Names in presented certificates that fall under the probed domains are
+/// queued too and announced through `probe-discovered` as
+/// `(job_id, host)`. Expired, soon-expiring and self-signed certificates
+/// are recorded as findings on the host's root asset.
+#[tauri::command]
+pub async fn start_host_probe(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<JobState>>,
+    request: ProbeRequest,
+) -> Result<String, String> {
+    crate::scan_profiles::authorize(request.scan_profile_id, crate::scan_profiles::ActiveCheck::Discovery).await?;
+    let mut hosts: Vec<String> = Vec::new();
+    for input in request.hosts.iter().filter(|h| !h.trim().is_empty()) {
+        let host = crate::portscan::scan_host(input)?;
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    if hosts.is_empty() {
+        return Err("No hosts to probe".to_string());
+    }
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let client = probe_client(Duration::from_millis(request.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS).clamp(100, 60_000)))?;
+
+    Ok(crate::jobs::spawn_job(
+        app_handle,
+        state.inner(),
+        "probe-complete",
+        move |app_handle, job_id, cancel| async move {
+            let suffixes = scope_suffixes(&hosts);
+            let mut known: HashSet<String> = hosts.iter().cloned().collect();
+            let mut total = hosts.len();
+            let mut workers = crate::jobs::Workers::new(hosts, concurrency, |host: String| {
+                let client = client.clone();
+                async move {
+                    let outcome = probe_host(&client, &host).await;
+                    (host, outcome)
+                }
+            });
+            let (mut checked, mut alive, mut discovered) = (0usize, 0, 0);
+            while let Some(joined) = workers.next(&cancel).await {
+                checked += 1;
+                if let Ok((host, outcome)) = joined {
+                    if let Some(cert) = &outcome.cert {
+                        for name in san_hosts(&cert.sans, &suffixes) {
+                            if discovered < MAX_DISCOVERED && known.insert(name.clone()) {
+                                discovered += 1;
+                                total += 1;
+                                let _ = app_handle.emit("probe-discovered", (&job_id, &name));
+                                workers.push(name);
+                            }
+                        }
+                        let findings = cert_findings(cert, Utc::now());
+                        if let (false, Some(url)) = (findings.is_empty(), &outcome.url) {
+                            if let Err(e) = record_cert_findings(url, outcome.status_code, findings).await {
+                                eprintln!("Failed to record certificate findings for {}: {}", host, e);
+                            }
+                        }
+                    }
+                    match store(&host, &outcome).await {
+                        Ok(row) => {
+                            if row.alive {
+                                alive += 1;
+                            }
+                            let _ = app_handle.emit("probe-result", (&job_id, row));
+                        }
+                        Err(e) => eprintln!("Failed to store probe of {}: {}", host, e),
+                    }
+                }
+                if checked.is_multiple_of(PROGRESS_EVERY) || checked == total {
+                    let _ = app_handle.emit("probe-progress", ReconProgress { job_id: job_id.clone(), checked, total, found: alive });
+                }
+            }
+            ProbeSummary { job_id, total, checked, alive, discovered, cancelled: false }
+        },
+        |_, summary, cancelled| ProbeSummary { cancelled, ..summary },
+    ))
+}
+
+/// Latest probe of every host, live hosts first.
+#[tauri::command]
+pub async fn list_host_probes() -> Result<Vec<HostProbe>, String> {
+    sqlx::query_as::<_, HostProbe>(
+        "SELECT id, host, alive, scheme, url, status_code, redirect, server, content_length, title, error, probed_at, favicon_hash,
+                cert_subject, cert_issuer, cert_sans, cert_not_after, cert_self_signed
+         FROM host_probes ORDER BY alive DESC, host",
+    )
+    .fetch_all(&get_db())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_target() {
+        assert_eq!(redirect_target("http://example.com/", "https://example.com/login").as_deref(), Some("https://example.com/login"));
+        assert_eq!(redirect_target("https://example.com/", "/app/ ").as_deref(), Some("https://example.com/app/"));
+        assert_eq!(redirect_target("https://example.com/a/", "b").as_deref(), Some("https://example.com/a/b"));
+        assert_eq!(redirect_target("not a url", "/x"), None);
+    }
+
+    #[test]
+    fn test_san_hosts() {
+        let hosts: Vec<String> = ["api.example.com", "example.org", "10.0.0.1", "www.example.com"].iter().map(|h| h.to_string()).collect();
+        let suffixes = scope_suffixes(&hosts);
+        assert_eq!(suffixes, vec!["example.com", "example.org"]);
+        let hosts: Vec<String> = ["example.co.uk", "api.shop.example.co.uk", "me.github.io", "localhost"].iter().map(|h| h.to_string()).collect();
+        assert_eq!(scope_suffixes(&hosts), vec!["example.co.uk", "me.github.io", "localhost"]);
+        let sans: Vec<String> = ["*.Dev.Example.com", "example.com", "cdn.other.net", "10.0.0.2", "mail.example.org", "notexample.com"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(san_hosts(&sans, &suffixes), vec!["dev.example.com", "example.com", "mail.example.org"]);
+
+        let now = "2025-06-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut cert = CertSummary {
+            subject: "CN=api.example.com".to_string(),
+            issuer: "CN=Example CA".to_string(),
+            not_before: now - chrono::Duration::days(300),
+            not_after: now + chrono::Duration::days(90),
+            sans: Vec::new(),
+            self_signed: false,
+        };
+        assert!(cert_findings(&cert, now).is_empty());
+        cert.not_after = now + chrono::Duration::days(10);
+        assert_eq!(cert_findings(&cert, now)[0].rule_id, "TLS-CERT-EXPIRING");
+        cert.not_after = now - chrono::Duration::days(1);
+        cert.self_signed = true;
+        let rules: Vec<String> = cert_findings(&cert, now).into_iter().map(|f| f.rule_id).collect();
+        assert_eq!(rules, vec!["TLS-CERT-EXPIRED", "TLS-CERT-SELF-SIGNED"]);
+    }
+}
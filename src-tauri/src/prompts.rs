@@ -0,0 +1,273 @@
+use crate::db::get_db;
+use serde::Serialize;
+
+/// A prompt sent to the model, editable per workspace.
+#[derive(Serialize, Debug, Clone)]
+pub struct PromptDef {
+    pub kind: &'static str,
+    pub description: &'static str,
+    /// Names usable as `{{name}}` in the template
+    pub placeholders: &'static [&'static str],
+    pub default: &'static str,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PromptEntry {
+    #[serde(flatten)]
+    pub def: PromptDef,
+    pub template: String,
+    /// False while the prompt still uses its default
+    pub is_set: bool,
+}
+
+const TRIAGE: &str = r#"You are an expert API security analyst. Analyze this security finding and provide triage guidance.
+Specifically, identify which OWASP Top 10 API Security category it falls under (e.g., API1:2023 Broken Object Level Authorization).
+
+**Finding:** {{name}}
+**Description:** {{description}}
+**Evidence:** {{evidence}}
+**Endpoint:** {{endpoint}}
+
+Provide your analysis in the following JSON format:
+{
+  "severity_assessment": "Critical/High/Medium/Low with justification",
+  "false_positive_likelihood": "High/Medium/Low with reasoning",
+  "owasp_category": "API X:2023 Category Name",
+  "remediation_hint": "Specific actionable fix recommendation",
+  "similar_cves": ["CVE-XXXX-XXXX", ...]
+}
+
+Be concise and actionable. Focus on practical security impact."#;
+
+const REPORT: &str = r#"You are an expert API security consultant writing an engagement report.
+
+**Workspace:** {{workspace}}
+**Assets in scope:** {{assets}}
+**Open findings by severity:** {{severity_counts}}
+
+**Top findings:**
+{{findings}}
+
+Write an executive summary for a non-technical audience (3-5 sentences on overall posture, main risks and priorities), then a short narrative per finding explaining the business impact and how an attacker would exploit it.
+
+Respond with JSON only, in this format:
+{
+  "executive_summary": "...",
+  "findings": [{"rule_id": "...", "narrative": "..."}]
+}"#;
+
+const PAYLOADS: &str = r#"You are an expert API penetration tester preparing fuzzing payloads.
+
+**Request:** {{method}} {{url}}
+**Headers:**
+{{headers}}
+**Body:** {{body}}
+**Parameters:**
+{{parameters}}
+**Focus:** {{focus}}
+
+List the attacks most likely to succeed against this endpoint as short hypotheses, then give concrete payloads to test them, one value per entry, ready to substitute into a parameter. Prefer payloads specific to the parameter names and types above over generic lists.
+
+Respond with JSON only, in this format:
+{
+  "hypotheses": ["..."],
+  "payloads": ["..."]
+}"#;
+
+const OPENAPI: &str = r#"You are a technical writer documenting an undocumented API from captured traffic.
+
+**Operation:** {{method}} {{path}}
+**Inferred OpenAPI operation:**
+{{operation}}
+**Body fields:** {{fields}}
+
+Write a short summary (under 10 words) and a one or two sentence description of what this operation most likely does, a description for each parameter, and a description for each body field listed above. Base everything on the names and types shown; do not invent parameters or fields.
+
+Respond with JSON only, in this format:
+{
+  "summary": "...",
+  "description": "...",
+  "parameters": {"<parameter name>": "..."},
+  "fields": {"<field path>": "..."}
+}"#;
+
+const CHAT: &str = r#"You are an expert API security analyst helping a penetration tester investigate one endpoint. Answer their questions about it concisely, citing the request, response or findings below where relevant. Suggest concrete follow-up tests when useful.
+
+**Request:**
+{{request}}
+
+**Response:**
+{{response}}
+
+**Open findings:**
+{{findings}}"#;
+
+/// Every prompt the app sends. The response format each one asks for must
+/// stay parseable by its caller.
+pub fn registry() -> Vec<PromptDef> {
+    vec![
+        PromptDef {
+            kind: "triage",
+            description: "Triage guidance for a single finding",
+            placeholders: &["name", "description", "evidence", "endpoint"],
+            default: TRIAGE,
+        },
+        PromptDef {
+            kind: "report",
+            description: "Executive summary and per-finding narratives for the engagement report",
+            placeholders: &["workspace", "assets", "severity_counts", "findings"],
+            default: REPORT,
+        },
+        PromptDef {
+            kind: "payloads",
+            description: "Attack hypotheses and fuzz payloads for a request",
+            placeholders: &["method", "url", "headers", "body", "parameters", "focus"],
+            default: PAYLOADS,
+        },
+        PromptDef {
+            kind: "openapi",
+            description: "Summaries and field docs for an operation inferred from traffic",
+            placeholders: &["method", "path", "operation", "fields"],
+            default: OPENAPI,
+        },
+        PromptDef {
+            kind: "chat",
+            description: "Opening context of a chat about an asset",
+            placeholders: &["request", "response", "findings"],
+            default: CHAT,
+        },
+    ]
+}
+
+fn lookup(kind: &str) -> Result<PromptDef, String> {
+    registry()
+        .into_iter()
+        .find(|d| d.kind == kind)
+        .ok_or_else(|| format!("Unknown prompt '{}'", kind))
+}
+
+/// Names of the `{{name}}` placeholders in a template, in order of use.
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    names
+}
+
+/// Fills `{{name}}` placeholders in one pass, so values that themselves
+/// contain braces are left alone. Unknown placeholders stay as written.
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let end = start + 2 + len + 2;
+        let name = rest[start + 2..start + 2 + len].trim();
+        match vars.iter().find(|(k, _)| *k == name) {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+async fn stored(kind: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar("SELECT template FROM prompt_templates WHERE kind = ?")
+        .bind(kind)
+        .fetch_optional(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The workspace's template for `kind`, rendered with `vars`.
+pub async fn prompt(kind: &str, vars: &[(&str, &str)]) -> Result<String, String> {
+    let def = lookup(kind)?;
+    let template = stored(kind).await?.unwrap_or_else(|| def.default.to_string());
+    Ok(render(&template, vars))
+}
+
+/// Every prompt with its placeholders, default and current template.
+#[tauri::command]
+pub async fn get_prompt_templates() -> Result<Vec<PromptEntry>, String> {
+    let mut entries = Vec::new();
+    for def in registry() {
+        let current = stored(def.kind).await?;
+        entries.push(PromptEntry {
+            is_set: current.is_some(),
+            template: current.unwrap_or_else(|| def.default.to_string()),
+            def,
+        });
+    }
+    Ok(entries)
+}
+
+/// Stores a customized template. Placeholders the prompt doesn't provide
+/// are rejected so typos don't reach the model.
+#[tauri::command]
+pub async fn set_prompt_template(kind: String, template: String) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let def = lookup(&kind)?;
+    if template.trim().is_empty() {
+        return Err("Prompt template must not be empty".to_string());
+    }
+    let unknown: Vec<&str> = placeholders(&template).into_iter().filter(|p| !def.placeholders.contains(p)).collect();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Unknown placeholder(s) {}; available: {}",
+            unknown.join(", "),
+            def.placeholders.join(", ")
+        ));
+    }
+    sqlx::query("INSERT OR REPLACE INTO prompt_templates (kind, template, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)")
+        .bind(&kind)
+        .bind(&template)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Restores the built-in template for `kind`.
+#[tauri::command]
+pub async fn reset_prompt_template(kind: String) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    lookup(&kind)?;
+    sqlx::query("DELETE FROM prompt_templates WHERE kind = ?")
+        .bind(&kind)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let out = render("{{ a }} and {{b}}, {{unknown}} {\"k\": 1} {{a", &[("a", "x{{b}}"), ("b", "y")]);
+        assert_eq!(out, "x{{b}} and y, {{unknown}} {\"k\": 1} {{a");
+        assert_eq!(placeholders("{{a}} {{ b }} {{a}}"), vec!["a", "b"]);
+        for def in registry() {
+            let used = placeholders(def.default);
+            assert!(used.iter().all(|p| def.placeholders.contains(p)), "{}", def.kind);
+            assert_eq!(used.len(), def.placeholders.len(), "{}", def.kind);
+        }
+    }
+}
@@ -0,0 +1,991 @@
+use crate::db::get_db;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::Row;
+
+/// Nested messages deeper than this are left undecoded, as base64.
+const MAX_DECODE_DEPTH: usize = 32;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtoField {
+    pub name: String,
+    pub number: u32,
+    pub field_type: String,
+    pub repeated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtoMessage {
+    pub full_name: String,
+    pub fields: Vec<ProtoField>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtoEnumValue {
+    pub name: String,
+    pub number: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtoEnum {
+    pub full_name: String,
+    pub values: Vec<ProtoEnumValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtoRpc {
+    /// gRPC request path, e.g. `/shop.v1.Orders/GetOrder`
+    pub path: String,
+    pub input_type: String,
+    pub output_type: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProtoFile {
+    pub package: Option<String>,
+    pub messages: Vec<ProtoMessage>,
+    #[serde(default)]
+    pub enums: Vec<ProtoEnum>,
+    pub rpcs: Vec<ProtoRpc>,
+}
+
+impl ProtoFile {
+    pub fn message(&self, full_name: &str) -> Option<&ProtoMessage> {
+        self.messages.iter().find(|m| m.full_name == full_name)
+    }
+
+    pub fn enum_type(&self, full_name: &str) -> Option<&ProtoEnum> {
+        self.enums.iter().find(|e| e.full_name == full_name)
+    }
+
+    /// Builds a JSON body with zero values for every field of a message,
+    /// expanding nested message types up to a small depth.
+    pub fn skeleton(&self, full_name: &str, depth: usize) -> Value {
+        let msg = match self.message(full_name) {
+            Some(m) if depth < 4 => m,
+            _ => return json!({}),
+        };
+        let mut obj = Map::new();
+        for f in &msg.fields {
+            let value = match f.field_type.as_str() {
+                "string" | "bytes" => json!(""),
+                "bool" => json!(false),
+                "double" | "float" => json!(0.0),
+                "int32" | "int64" | "uint32" | "uint64" | "sint32" | "sint64" | "fixed32"
+                | "fixed64" | "sfixed32" | "sfixed64" => json!(0),
+                t if t.starts_with("map<") => json!({}),
+                t => {
+                    let nested = self.resolve_type(t, full_name);
+                    match self.enum_type(&nested) {
+                        // Proto3 enums default to their first value
+                        Some(e) => e.values.first().map(|v| json!(v.name)).unwrap_or(json!(0)),
+                        None => self.skeleton(&nested, depth + 1),
+                    }
+                }
+            };
+            obj.insert(f.name.clone(), if f.repeated { json!([value]) } else { value });
+        }
+        Value::Object(obj)
+    }
+
+    /// Resolves a (possibly relative) type name against the enclosing scope,
+    /// mirroring protobuf's innermost-first lookup.
+    fn resolve_type(&self, name: &str, scope: &str) -> String {
+        if let Some(abs) = name.strip_prefix('.') {
+            return abs.to_string();
+        }
+        let mut scope = scope.to_string();
+        loop {
+            let candidate = if scope.is_empty() { name.to_string() } else { format!("{}.{}", scope, name) };
+            if self.message(&candidate).is_some() || self.enum_type(&candidate).is_some() {
+                return candidate;
+            }
+            match scope.rfind('.') {
+                Some(i) => scope.truncate(i),
+                None if !scope.is_empty() => scope.clear(),
+                None => return name.to_string(),
+            }
+        }
+    }
+}
+
+impl ProtoFile {
+    /// Decodes the messages of a gRPC body as `full_name`. A stream, or a
+    /// body of several frames, becomes an array. `None` when the body isn't
+    /// valid gRPC framing or protobuf.
+    pub fn decode_grpc_body(&self, full_name: &str, body: &[u8], streaming: bool) -> Option<Value> {
+        let frames = grpc_frames(body)?;
+        if frames.is_empty() {
+            return None;
+        }
+        let mut messages = frames.iter().map(|frame| self.decode(full_name, frame, 0)).collect::<Option<Vec<_>>>()?;
+        Some(if streaming || messages.len() != 1 { Value::Array(messages) } else { messages.remove(0) })
+    }
+
+    /// Decodes protobuf wire format as JSON. Fields the descriptor doesn't
+    /// define are kept under their field number.
+    pub fn decode(&self, full_name: &str, buf: &[u8], depth: usize) -> Option<Value> {
+        let msg = self.message(full_name);
+        let mut obj = Map::new();
+        let mut pos = 0;
+        while pos < buf.len() {
+            let key = read_varint(buf, &mut pos)?;
+            let (number, wire_type) = ((key >> 3) as u32, key & 7);
+            let field = msg.and_then(|m| m.fields.iter().find(|f| f.number == number));
+            let ty = field.map(|f| self.resolve_field_type(f, full_name)).unwrap_or_default();
+            let values = match wire_type {
+                0 => vec![self.varint_value(&ty, read_varint(buf, &mut pos)?)],
+                1 => vec![fixed64_value(&ty, read_fixed(buf, &mut pos)?)],
+                5 => vec![fixed32_value(&ty, read_fixed(buf, &mut pos)?)],
+                2 => {
+                    let len = read_varint(buf, &mut pos)? as usize;
+                    let end = pos.checked_add(len).filter(|end| *end <= buf.len())?;
+                    let data = &buf[pos..end];
+                    pos = end;
+                    if let Some((key_type, value_type)) = ty.strip_prefix("map<").and_then(|t| t.strip_suffix('>')).and_then(|t| t.split_once(',')) {
+                        let (key, value) = self.decode_map_entry(key_type, value_type, full_name, data, depth)?;
+                        let name = field.map(|f| f.name.clone()).unwrap_or_else(|| number.to_string());
+                        if let Some(map) = obj.entry(name).or_insert_with(|| json!({})).as_object_mut() {
+                            map.insert(key, value);
+                        }
+                        continue;
+                    }
+                    self.length_delimited_values(&ty, data, depth)?
+                }
+                _ => return None,
+            };
+
+            match field {
+                Some(f) if f.repeated => {
+                    let entry = obj.entry(f.name.clone()).or_insert_with(|| json!([]));
+                    if let Some(items) = entry.as_array_mut() {
+                        items.extend(values);
+                    }
+                }
+                Some(f) => {
+                    if let Some(value) = values.into_iter().last() {
+                        obj.insert(f.name.clone(), value);
+                    }
+                }
+                None => {
+                    if let Some(value) = values.into_iter().last() {
+                        obj.insert(number.to_string(), value);
+                    }
+                }
+            }
+        }
+        Some(Value::Object(obj))
+    }
+
+    /// A field's type, fully qualified when it names a message or enum.
+    fn resolve_field_type(&self, field: &ProtoField, scope: &str) -> String {
+        if is_scalar(&field.field_type) || field.field_type.starts_with("map<") {
+            field.field_type.clone()
+        } else {
+            self.resolve_type(&field.field_type, scope)
+        }
+    }
+
+    fn varint_value(&self, ty: &str, raw: u64) -> Value {
+        match ty {
+            "bool" => json!(raw != 0),
+            "int32" => json!(raw as i32),
+            "int64" => json!(raw as i64),
+            "uint32" => json!(raw as u32),
+            "sint32" => json!(zigzag(raw) as i32),
+            "sint64" => json!(zigzag(raw)),
+            _ => match self.enum_type(ty).and_then(|e| e.values.iter().find(|v| v.number == raw as i32)) {
+                Some(value) => json!(value.name),
+                None => json!(raw),
+            },
+        }
+    }
+
+    /// One value, or several for a packed repeated scalar.
+    fn length_delimited_values(&self, ty: &str, data: &[u8], depth: usize) -> Option<Vec<Value>> {
+        let as_base64 = || json!(base64::engine::general_purpose::STANDARD.encode(data));
+        let value = match ty {
+            "string" => json!(String::from_utf8_lossy(data)),
+            "bytes" => as_base64(),
+            "double" | "fixed64" | "sfixed64" => {
+                let mut pos = 0;
+                let mut values = Vec::new();
+                while pos < data.len() {
+                    values.push(fixed64_value(ty, read_fixed(data, &mut pos)?));
+                }
+                return Some(values);
+            }
+            "float" | "fixed32" | "sfixed32" => {
+                let mut pos = 0;
+                let mut values = Vec::new();
+                while pos < data.len() {
+                    values.push(fixed32_value(ty, read_fixed(data, &mut pos)?));
+                }
+                return Some(values);
+            }
+            t if is_scalar(t) || self.enum_type(t).is_some() => {
+                let mut pos = 0;
+                let mut values = Vec::new();
+                while pos < data.len() {
+                    values.push(self.varint_value(ty, read_varint(data, &mut pos)?));
+                }
+                return Some(values);
+            }
+            t if self.message(t).is_some() && depth < MAX_DECODE_DEPTH => self.decode(t, data, depth + 1)?,
+            // Without a descriptor, text is shown as text and anything else as base64
+            _ => match std::str::from_utf8(data) {
+                Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => json!(text),
+                _ => as_base64(),
+            },
+        };
+        Some(vec![value])
+    }
+
+    fn decode_map_entry(&self, key_type: &str, value_type: &str, scope: &str, data: &[u8], depth: usize) -> Option<(String, Value)> {
+        let mut key = Value::Null;
+        let mut value = Value::Null;
+        let mut pos = 0;
+        while pos < data.len() {
+            let tag = read_varint(data, &mut pos)?;
+            let number = tag >> 3;
+            let declared = match number {
+                1 => key_type,
+                2 => value_type,
+                _ => return None,
+            };
+            let ty = match is_scalar(declared) {
+                true => declared.to_string(),
+                false => self.resolve_type(declared, scope),
+            };
+            let decoded = match tag & 7 {
+                0 => self.varint_value(&ty, read_varint(data, &mut pos)?),
+                1 => fixed64_value(&ty, read_fixed(data, &mut pos)?),
+                5 => fixed32_value(&ty, read_fixed(data, &mut pos)?),
+                2 => {
+                    let len = read_varint(data, &mut pos)? as usize;
+                    let end = pos.checked_add(len).filter(|end| *end <= data.len())?;
+                    let part = &data[pos..end];
+                    pos = end;
+                    self.length_delimited_values(&ty, part, depth)?.into_iter().next()?
+                }
+                _ => return None,
+            };
+            match number {
+                1 => key = decoded,
+                _ => value = decoded,
+            }
+        }
+        let key = match key {
+            Value::String(s) => s,
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        Some((key, value))
+    }
+
+    /// JSON pointers of keys in a decoded `full_name` that its descriptor
+    /// doesn't define, i.e. fields the decoder could only name by number.
+    pub fn undocumented_fields(&self, full_name: &str, value: &Value, pointer: &str, out: &mut Vec<String>) {
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.undocumented_fields(full_name, item, &format!("{}/{}", pointer, i), out);
+                }
+            }
+            Value::Object(obj) => {
+                let Some(msg) = self.message(full_name) else {
+                    return;
+                };
+                for (key, child) in obj {
+                    let child_pointer = format!("{}/{}", pointer, key);
+                    match msg.fields.iter().find(|f| &f.name == key) {
+                        Some(f) => {
+                            let ty = self.resolve_field_type(f, full_name);
+                            if self.message(&ty).is_some() {
+                                self.undocumented_fields(&ty, child, &child_pointer, out);
+                            }
+                        }
+                        None => out.push(child_pointer),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_scalar(ty: &str) -> bool {
+    matches!(
+        ty,
+        "string" | "bytes" | "bool" | "double" | "float" | "int32" | "int64" | "uint32" | "uint64" | "sint32" | "sint64"
+            | "fixed32" | "fixed64" | "sfixed32" | "sfixed64"
+    )
+}
+
+fn zigzag(raw: u64) -> i64 {
+    (raw >> 1) as i64 ^ -((raw & 1) as i64)
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn read_fixed<const N: usize>(buf: &[u8], pos: &mut usize) -> Option<[u8; N]> {
+    let bytes = buf.get(*pos..pos.checked_add(N)?)?.try_into().ok()?;
+    *pos += N;
+    Some(bytes)
+}
+
+fn fixed64_value(ty: &str, bytes: [u8; 8]) -> Value {
+    match ty {
+        "double" => json!(f64::from_le_bytes(bytes)),
+        "sfixed64" => json!(i64::from_le_bytes(bytes)),
+        _ => json!(u64::from_le_bytes(bytes)),
+    }
+}
+
+fn fixed32_value(ty: &str, bytes: [u8; 4]) -> Value {
+    match ty {
+        "float" => json!(f32::from_le_bytes(bytes)),
+        "sfixed32" => json!(i32::from_le_bytes(bytes)),
+        _ => json!(u32::from_le_bytes(bytes)),
+    }
+}
+
+/// The uncompressed messages of a gRPC body. Each frame is a compressed
+/// flag, a big-endian length and the message; compressed frames are
+/// skipped since the encoding isn't captured.
+fn grpc_frames(body: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut frames = Vec::new();
+    let mut rest = body;
+    while !rest.is_empty() {
+        let header = rest.get(..5)?;
+        let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+        let end = len.checked_add(5).filter(|end| *end <= rest.len())?;
+        if header[0] == 0 {
+            frames.push(&rest[5..end]);
+        }
+        rest = &rest[end..];
+    }
+    Some(frames)
+}
+
+/// Whether a request's content type marks a gRPC call.
+pub fn is_grpc(content_type: Option<&str>) -> bool {
+    content_type.is_some_and(|v| v.trim_start().starts_with("application/grpc"))
+}
+
+fn tokenize(content: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = content.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(chars[start..i.min(chars.len())].iter().collect());
+        } else if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' || c == '+' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.' || chars[i] == '-' || chars[i] == '+')
+            {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+    tokens
+}
+
+struct ProtoParser {
+    tokens: Vec<String>,
+    pos: usize,
+    package: Option<String>,
+    file: ProtoFile,
+}
+
+impl ProtoParser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|s| s.as_str())
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn expect(&mut self, want: &str) -> Result<()> {
+        match self.next() {
+            Some(t) if t == want => Ok(()),
+            other => Err(anyhow!("Expected '{}' but found {:?}", want, other)),
+        }
+    }
+
+    /// Skips to the end of the current statement, including a trailing `{...}` block.
+    fn skip_statement(&mut self) {
+        while let Some(t) = self.next() {
+            match t.as_str() {
+                ";" => return,
+                "{" => {
+                    self.skip_block();
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips tokens up to the `}` matching an already consumed `{`.
+    fn skip_block(&mut self) {
+        let mut depth = 1;
+        while let Some(t) = self.next() {
+            match t.as_str() {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn qualify(&self, scope: &str, name: &str) -> String {
+        if scope.is_empty() {
+            match &self.package {
+                Some(p) => format!("{}.{}", p, name),
+                None => name.to_string(),
+            }
+        } else {
+            format!("{}.{}", scope, name)
+        }
+    }
+
+    fn parse_file(&mut self) -> Result<()> {
+        while let Some(t) = self.next() {
+            match t.as_str() {
+                "package" => {
+                    self.package = self.next();
+                    self.expect(";")?;
+                }
+                "message" => self.parse_message("")?,
+                "service" => self.parse_service()?,
+                "enum" => self.parse_enum("")?,
+                "extend" => {
+                    self.next();
+                    self.expect("{")?;
+                    self.skip_block();
+                }
+                ";" => {}
+                _ => {
+                    self.pos -= 1;
+                    self.skip_statement();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_message(&mut self, scope: &str) -> Result<()> {
+        let name = self.next().ok_or_else(|| anyhow!("Unexpected end of file after 'message'"))?;
+        let full_name = self.qualify(scope, &name);
+        self.expect("{")?;
+        let fields = self.parse_message_body(&full_name)?;
+        self.file.messages.push(ProtoMessage { full_name, fields });
+        Ok(())
+    }
+
+    /// Parses fields up to the closing brace, recursing into nested messages and oneofs.
+    fn parse_message_body(&mut self, full_name: &str) -> Result<Vec<ProtoField>> {
+        let mut fields = Vec::new();
+        loop {
+            let t = self.next().ok_or_else(|| anyhow!("Unterminated message '{}'", full_name))?;
+            match t.as_str() {
+                "}" => return Ok(fields),
+                ";" => {}
+                "message" => self.parse_message(full_name)?,
+                "enum" => self.parse_enum(full_name)?,
+                "extend" => {
+                    self.next();
+                    self.expect("{")?;
+                    self.skip_block();
+                }
+                "oneof" => {
+                    self.next();
+                    self.expect("{")?;
+                    fields.extend(self.parse_message_body(full_name)?);
+                }
+                "option" | "reserved" | "extensions" => self.skip_statement(),
+                "map" => {
+                    // map<key, value> name = N;
+                    let mut ty = String::from("map");
+                    while let Some(part) = self.next() {
+                        ty.push_str(&part);
+                        if part == ">" {
+                            break;
+                        }
+                    }
+                    fields.push(self.parse_field_tail(ty, false)?);
+                }
+                "repeated" | "optional" | "required" => {
+                    let ty = self.next().ok_or_else(|| anyhow!("Missing field type"))?;
+                    fields.push(self.parse_field_tail(ty, t == "repeated")?);
+                }
+                _ => fields.push(self.parse_field_tail(t, false)?),
+            }
+        }
+    }
+
+    fn parse_field_tail(&mut self, field_type: String, repeated: bool) -> Result<ProtoField> {
+        let name = self.next().ok_or_else(|| anyhow!("Missing field name"))?;
+        self.expect("=")?;
+        let number = self
+            .next()
+            .and_then(|n| n.parse::<u32>().ok())
+            .ok_or_else(|| anyhow!("Invalid field number for '{}'", name))?;
+        self.skip_options();
+        self.expect(";")?;
+        Ok(ProtoField { name, number, field_type, repeated })
+    }
+
+    /// Skips field or enum value options such as `[deprecated = true]`.
+    fn skip_options(&mut self) {
+        if self.peek() == Some("[") {
+            while let Some(t) = self.next() {
+                if t == "]" {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn parse_enum(&mut self, scope: &str) -> Result<()> {
+        let name = self.next().ok_or_else(|| anyhow!("Unexpected end of file after 'enum'"))?;
+        let full_name = self.qualify(scope, &name);
+        self.expect("{")?;
+        let mut values = Vec::new();
+        loop {
+            let t = self.next().ok_or_else(|| anyhow!("Unterminated enum '{}'", full_name))?;
+            match t.as_str() {
+                "}" => break,
+                ";" => {}
+                "option" | "reserved" => self.skip_statement(),
+                _ => {
+                    self.expect("=")?;
+                    let number = self
+                        .next()
+                        .and_then(|n| n.parse::<i32>().ok())
+                        .ok_or_else(|| anyhow!("Invalid value for '{}'", t))?;
+                    self.skip_options();
+                    self.expect(";")?;
+                    values.push(ProtoEnumValue { name: t, number });
+                }
+            }
+        }
+        self.file.enums.push(ProtoEnum { full_name, values });
+        Ok(())
+    }
+
+    fn parse_service(&mut self) -> Result<()> {
+        let name = self.next().ok_or_else(|| anyhow!("Unexpected end of file after 'service'"))?;
+        let service = self.qualify("", &name);
+        self.expect("{")?;
+        loop {
+            let t = self.next().ok_or_else(|| anyhow!("Unterminated service '{}'", service))?;
+            match t.as_str() {
+                "}" => return Ok(()),
+                "rpc" => {
+                    let method = self.next().ok_or_else(|| anyhow!("Missing rpc name"))?;
+                    let (input_type, client_streaming) = self.parse_rpc_type()?;
+                    self.expect("returns")?;
+                    let (output_type, server_streaming) = self.parse_rpc_type()?;
+                    match self.next().as_deref() {
+                        Some("{") => self.skip_block(),
+                        Some(";") => {}
+                        other => return Err(anyhow!("Unexpected token after rpc '{}': {:?}", method, other)),
+                    }
+                    let input_type = self.qualify_ref(&input_type);
+                    let output_type = self.qualify_ref(&output_type);
+                    self.file.rpcs.push(ProtoRpc {
+                        path: format!("/{}/{}", service, method),
+                        input_type,
+                        output_type,
+                        client_streaming,
+                        server_streaming,
+                    });
+                }
+                _ => {
+                    self.pos -= 1;
+                    self.skip_statement();
+                }
+            }
+        }
+    }
+
+    fn parse_rpc_type(&mut self) -> Result<(String, bool)> {
+        self.expect("(")?;
+        let mut streaming = false;
+        let mut ty = self.next().ok_or_else(|| anyhow!("Missing rpc type"))?;
+        if ty == "stream" {
+            streaming = true;
+            ty = self.next().ok_or_else(|| anyhow!("Missing rpc type"))?;
+        }
+        self.expect(")")?;
+        Ok((ty, streaming))
+    }
+
+    /// Types referenced from a service are resolved relative to the package.
+    fn qualify_ref(&self, ty: &str) -> String {
+        if let Some(abs) = ty.strip_prefix('.') {
+            return abs.to_string();
+        }
+        let local = self.qualify("", ty);
+        if self.file.message(&local).is_some() || !ty.contains('.') {
+            local
+        } else {
+            ty.to_string()
+        }
+    }
+}
+
+/// Parses messages and services out of a `.proto` (proto2/proto3) source file.
+pub fn parse_proto(content: &str) -> Result<ProtoFile> {
+    let mut parser = ProtoParser {
+        tokens: tokenize(content),
+        pos: 0,
+        package: None,
+        file: ProtoFile::default(),
+    };
+    parser.parse_file()?;
+    parser.file.package = parser.package.clone();
+    if parser.file.rpcs.is_empty() && parser.file.messages.is_empty() {
+        return Err(anyhow!("No messages or services found in proto file"));
+    }
+    Ok(parser.file)
+}
+
+/// Persists message and rpc descriptors so gRPC traffic can be decoded and checked later.
+pub async fn register_descriptors(file: &ProtoFile, source: &str) -> Result<(), String> {
+    let pool = get_db();
+    for msg in &file.messages {
+        sqlx::query("INSERT OR REPLACE INTO proto_messages (full_name, fields, source) VALUES (?, ?, ?)")
+            .bind(&msg.full_name)
+            .bind(serde_json::to_string(&msg.fields).unwrap_or_default())
+            .bind(source)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    for e in &file.enums {
+        sqlx::query("INSERT OR REPLACE INTO proto_enums (full_name, enum_values, source) VALUES (?, ?, ?)")
+            .bind(&e.full_name)
+            .bind(serde_json::to_string(&e.values).unwrap_or_default())
+            .bind(source)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    for rpc in &file.rpcs {
+        sqlx::query("INSERT OR REPLACE INTO proto_rpcs (path, input_type, output_type, client_streaming, server_streaming, source) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(&rpc.path)
+            .bind(&rpc.input_type)
+            .bind(&rpc.output_type)
+            .bind(rpc.client_streaming)
+            .bind(rpc.server_streaming)
+            .bind(source)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Looks up the registered rpc for a gRPC request path along with its
+/// input and output message descriptors.
+pub async fn lookup_rpc(path: &str) -> Result<Option<(ProtoRpc, Option<ProtoMessage>, Option<ProtoMessage>)>, String> {
+    let pool = get_db();
+    let row = sqlx::query("SELECT path, input_type, output_type, client_streaming, server_streaming FROM proto_rpcs WHERE path = ?")
+        .bind(path)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let rpc = match row {
+        Some(r) => ProtoRpc {
+            path: r.get("path"),
+            input_type: r.get("input_type"),
+            output_type: r.get("output_type"),
+            client_streaming: r.get("client_streaming"),
+            server_streaming: r.get("server_streaming"),
+        },
+        None => return Ok(None),
+    };
+
+    let input = get_message(&rpc.input_type).await?;
+    let output = get_message(&rpc.output_type).await?;
+    Ok(Some((rpc, input, output)))
+}
+
+async fn get_message(full_name: &str) -> Result<Option<ProtoMessage>, String> {
+    let pool = get_db();
+    let row: Option<(String,)> = sqlx::query_as("SELECT fields FROM proto_messages WHERE full_name = ?")
+        .bind(full_name)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(row.map(|(fields,)| ProtoMessage {
+        full_name: full_name.to_string(),
+        fields: serde_json::from_str(&fields).unwrap_or_default(),
+    }))
+}
+
+/// Every registered message and enum, so nested types can be resolved.
+pub async fn load_registry() -> Result<ProtoFile, String> {
+    let pool = get_db();
+    let messages: Vec<(String, String)> = sqlx::query_as("SELECT full_name, fields FROM proto_messages")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let enums: Vec<(String, String)> = sqlx::query_as("SELECT full_name, enum_values FROM proto_enums")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(ProtoFile {
+        package: None,
+        messages: messages
+            .into_iter()
+            .map(|(full_name, fields)| ProtoMessage { full_name, fields: serde_json::from_str(&fields).unwrap_or_default() })
+            .collect(),
+        enums: enums
+            .into_iter()
+            .map(|(full_name, values)| ProtoEnum { full_name, values: serde_json::from_str(&values).unwrap_or_default() })
+            .collect(),
+        rpcs: Vec::new(),
+    })
+}
+
+/// Request and response bodies of a captured gRPC call decoded as JSON,
+/// when the rpc at `path` has registered descriptors. Bodies that can't be
+/// decoded come back as `None`.
+pub async fn decode_grpc_exchange(path: &str, request: Option<&[u8]>, response: Option<&[u8]>) -> Result<Option<(Option<String>, Option<String>)>, String> {
+    let Some((rpc, input, output)) = lookup_rpc(path).await? else {
+        return Ok(None);
+    };
+    if input.is_none() && output.is_none() {
+        return Ok(None);
+    }
+    let registry = load_registry().await?;
+    let decode = |body: Option<&[u8]>, full_name: &str, streaming: bool| {
+        body.and_then(|b| registry.decode_grpc_body(full_name, b, streaming))
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+    };
+    Ok(Some((
+        decode(request, &rpc.input_type, rpc.client_streaming),
+        decode(response, &rpc.output_type, rpc.server_streaming),
+    )))
+}
+
+/// Imports a `.proto` file: registers its descriptors and creates a gRPC-tagged
+/// asset per rpc method.
+#[tauri::command]
+pub async fn import_proto_file(
+    app_handle: tauri::AppHandle,
+    name: String,
+    content: String,
+    base_url: Option<String>,
+) -> Result<crate::assets::BatchImportResult, String> {
+    let file = parse_proto(&content).map_err(|e| e.to_string())?;
+    register_descriptors(&file, &name).await?;
+
+    let custom_rules = crate::db::get_custom_rules().await?;
+    let plugins = crate::plugins::load_plugins(&app_handle);
+    let base_url = base_url.unwrap_or_else(|| "grpc://localhost".to_string());
+    let parsed = crate::import_engine::Parser::parse_proto(&content, &base_url, &custom_rules, &plugins)
+        .map_err(|e| e.to_string())?;
+
+    let mut added = 0;
+    let mut skipped = 0;
+    for entry in parsed.entries {
+        let asset = crate::assets::CreateAssetRequest {
+            url: entry.url,
+            source: "gRPC".to_string(),
+            method: Some(entry.method),
+            status_code: entry.status_code,
+            req_headers: crate::import_engine::fold_headers(entry.req_headers),
+            res_headers: crate::import_engine::fold_headers(entry.res_headers),
+            req_body: entry.req_body,
+            res_body: entry.res_body,
+            findings: entry.findings,
+        };
+        match crate::assets::add_asset(asset).await {
+            Ok(asset_id) => {
+                crate::db::add_asset_tag(asset_id, "gRPC".to_string()).await?;
+                added += 1;
+            }
+            Err(_) => skipped += 1,
+        }
+    }
+
+    Ok(crate::assets::BatchImportResult { added, skipped })
+}
+
+#[tauri::command]
+pub async fn get_proto_rpcs() -> Result<Vec<ProtoRpc>, String> {
+    let pool = get_db();
+    let rows = sqlx::query("SELECT path, input_type, output_type, client_streaming, server_streaming FROM proto_rpcs ORDER BY path")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|r| ProtoRpc {
+            path: r.get("path"),
+            input_type: r.get("input_type"),
+            output_type: r.get("output_type"),
+            client_streaming: r.get("client_streaming"),
+            server_streaming: r.get("server_streaming"),
+        })
+        .collect())
+}
+
+#[derive(Serialize, Debug)]
+pub struct GrpcDescriptor {
+    pub rpc: ProtoRpc,
+    pub input: Option<ProtoMessage>,
+    pub output: Option<ProtoMessage>,
+}
+
+/// Resolves the registered descriptors for a captured gRPC request path.
+#[tauri::command]
+pub async fn describe_grpc_path(path: String) -> Result<Option<GrpcDescriptor>, String> {
+    Ok(lookup_rpc(&path)
+        .await?
+        .map(|(rpc, input, output)| GrpcDescriptor { rpc, input, output }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+        syntax = "proto3";
+        package shop.v1;
+
+        // Order lookups
+        service Orders {
+            rpc GetOrder (GetOrderRequest) returns (Order);
+            rpc Watch (stream GetOrderRequest) returns (stream Order) {
+                option deprecated = true;
+            }
+        }
+
+        message GetOrderRequest { string id = 1; Status status = 2; }
+
+        enum Status {
+            option allow_alias = true;
+            STATUS_UNKNOWN = 0;
+            SHIPPED = 1 [deprecated = true];
+        }
+
+        message Order {
+            string id = 1;
+            repeated Item items = 2 [packed = true];
+            map<string, string> labels = 3;
+            oneof payment { string card = 4; string iban = 5; }
+            message Item {
+                int64 sku = 1;
+                Kind kind = 2;
+                enum Kind { KIND_PHYSICAL = 0; KIND_DIGITAL = 1; }
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_parse_services_and_messages() {
+        let file = parse_proto(SAMPLE).unwrap();
+        assert_eq!(file.package.as_deref(), Some("shop.v1"));
+        assert_eq!(file.rpcs.len(), 2);
+        assert_eq!(file.rpcs[0].path, "/shop.v1.Orders/GetOrder");
+        assert_eq!(file.rpcs[0].input_type, "shop.v1.GetOrderRequest");
+        assert!(file.rpcs[1].client_streaming && file.rpcs[1].server_streaming);
+
+        let order = file.message("shop.v1.Order").unwrap();
+        assert_eq!(order.fields.len(), 5);
+        assert!(file.message("shop.v1.Order.Item").is_some());
+        assert_eq!(file.enum_type("shop.v1.Status").unwrap().values.len(), 2);
+        assert!(file.enum_type("shop.v1.Order.Item.Kind").is_some());
+    }
+
+    #[test]
+    fn test_skeleton_expands_nested_messages() {
+        let file = parse_proto(SAMPLE).unwrap();
+        let body = file.skeleton("shop.v1.Order", 0);
+        assert_eq!(body["items"][0]["sku"], json!(0));
+        assert_eq!(body["card"], json!(""));
+        assert_eq!(body["items"][0]["kind"], json!("KIND_PHYSICAL"));
+        assert_eq!(file.skeleton("shop.v1.GetOrderRequest", 0)["status"], json!("STATUS_UNKNOWN"));
+    }
+
+    #[test]
+    fn test_decode_grpc_body() {
+        let file = parse_proto(SAMPLE).unwrap();
+        let message = [
+            0x0a, 2, b'A', b'1', // id
+            0x12, 4, 0x08, 7, 0x10, 1, // items { sku: 7, kind: KIND_DIGITAL }
+            0x1a, 6, 0x0a, 1, b'k', 0x12, 1, b'v', // labels { k: v }
+            0x48, 5, // unknown field 9
+        ];
+        let mut body = vec![0, 0, 0, 0, message.len() as u8];
+        body.extend(message);
+
+        let decoded = file.decode_grpc_body("shop.v1.Order", &body, false).unwrap();
+        assert_eq!(decoded, json!({"id": "A1", "items": [{"sku": 7, "kind": "KIND_DIGITAL"}], "labels": {"k": "v"}, "9": 5}));
+        let stream = file.decode_grpc_body("shop.v1.Order", &[body.clone(), body].concat(), true).unwrap();
+        assert_eq!(stream.as_array().unwrap().len(), 2);
+        assert!(file.decode_grpc_body("shop.v1.Order", &[0, 0, 0, 9, 1], false).is_none());
+
+        let mut unknown = Vec::new();
+        file.undocumented_fields("shop.v1.Order", &stream, "", &mut unknown);
+        assert_eq!(unknown, vec!["/0/9", "/1/9"]);
+    }
+}
@@ -17,8 +17,9 @@ use hyper::server::conn::Http;
 
 pub async fn start_proxy(app_handle: AppHandle, port: u16, state: Arc<ProxyState>) {
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    
+    let svc_state = state.clone();
     let make_svc = make_service_fn(move |_conn| {
+        let state = svc_state.clone();
         let handle = app_handle.clone();
         let state_clone = state.clone();
         async move {
@@ -66,6 +67,8 @@ async fn handle_request(
 
     let capture_body = state.capture_body.load(Ordering::Relaxed);
     let mut req_body_str = None;
+    // Raw bodies, kept for decoding gRPC messages
+    let mut req_bytes = None;
 
     if capture_body || state.intercept_requests.load(Ordering::Relaxed) {
         let (parts, body) = req.into_parts();
@@ -125,6 +128,7 @@ async fn handle_request(
                 }
             } else {
                 req_body_str = body_str;
+                req_bytes = Some(bytes.clone());
                 req = Request::from_parts(parts, Body::from(bytes));
             }
         } else {
@@ -140,6 +144,9 @@ async fn handle_request(
     // Capture metadata for Apisec
     let url = req.uri().to_string();
     let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let is_grpc = crate::proto::is_grpc(req.headers().get("content-type").and_then(|v| v.to_str().ok()));
+    let req_headers = header_map(req.headers());
     
     // Forward the request
     let mut response = client.request(req).await?;
@@ -188,23 +195,40 @@ async fn handle_request(
                     response = Response::from_parts(res_parts, Body::from(bytes));
                 }
             }
+        } else {
+            response = Response::from_parts(res_parts, Body::empty());
         }
     }
     
     let (res_parts, res_body) = response.into_parts();
     let status = res_parts.status.as_u16();
+    let res_headers = header_map(&res_parts.headers);
     let mut res_body_str = None;
+    let mut res_bytes = None;
     let mut final_res_body = res_body;
 
     if (capture_body || state.intercept_responses.load(Ordering::Relaxed)) && !is_websocket {
         if let Ok(bytes) = to_bytes(final_res_body).await {
             res_body_str = String::from_utf8(bytes.to_vec()).ok();
+            res_bytes = Some(bytes.clone());
             final_res_body = Body::from(bytes);
         } else {
             final_res_body = Body::empty();
         }
     }
 
+    // gRPC bodies are binary; store them as JSON when the rpc is registered
+    if is_grpc {
+        match crate::proto::decode_grpc_exchange(&path, req_bytes.as_deref(), res_bytes.as_deref()).await {
+            Ok(Some((request, response))) => {
+                req_body_str = request.or(req_body_str);
+                res_body_str = response.or(res_body_str);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("gRPC decoding failed for {}: {}", path, e),
+        }
+    }
+
     let custom_rules = db::get_custom_rules().await.unwrap_or_default();
     let plugins = crate::plugins::load_plugins(&app_handle);
     let mut findings = Vec::new();
@@ -217,6 +241,9 @@ async fn handle_request(
     if let Some(ref b) = res_body_str {
         findings.extend(analysis::Scanner::scan_text(b, &custom_rules, &plugins));
     }
+    if is_grpc {
+        findings.extend(crate::drift::detect_grpc_drift(&url, req_body_str.as_deref(), res_body_str.as_deref()).await);
+    }
     let findings_count = findings.len();
 
     // Emit event to UI
@@ -239,6 +266,8 @@ async fn handle_request(
             url: url_clone,
             method: Some(method_clone),
             status_code: Some(status as i64),
+            req_headers,
+            res_headers,
             source: if is_websocket { "Live Proxy (WS)".to_string() } else { "Live Proxy".to_string() },
             req_body: req_body_clone,
             res_body: res_body_clone,
@@ -250,15 +279,31 @@ async fn handle_request(
     Ok(Response::from_parts(res_parts, final_res_body))
 }
 
+/// Flattens a header map for storage; repeated headers are comma-joined.
+fn header_map(headers: &hyper::HeaderMap) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers.iter() {
+        let value = value.to_str().unwrap_or("");
+        map.entry(name.to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    map
+}
+
 fn handle_connect(app_handle: AppHandle, req: Request<Body>, state: Arc<ProxyState>) -> Result<Response<Body>, hyper::Error> {
     if let Some(host_port) = req.uri().authority().map(|auth| auth.to_string()) {
         let host = host_port.split(':').next().unwrap_or(&host_port).to_string();
+        let port = host_port.rsplit_once(':').and_then(|(_, port)| port.parse().ok()).unwrap_or(443);
         
         tokio::task::spawn(async move {
             match hyper::upgrade::on(req).await {
                 Ok(upgraded) => {
                     // Start MITM handshake
-                    if let Err(e) = handle_mitm(app_handle, upgraded, host, state).await {
+                    if let Err(e) = handle_mitm(app_handle, upgraded, host, port, state).await {
                         eprintln!("MITM error: {}", e);
                     }
                 }
@@ -274,8 +319,8 @@ fn handle_connect(app_handle: AppHandle, req: Request<Body>, state: Arc<ProxySta
     }
 }
 
-async fn handle_mitm(app_handle: AppHandle, upgraded: Upgraded, host: String, state: Arc<ProxyState>) -> anyhow::Result<()> {
-    let server_config = state.cert_manager.get_server_config(&host).await;
+async fn handle_mitm(app_handle: AppHandle, upgraded: Upgraded, host: String, port: u16, state: Arc<ProxyState>) -> anyhow::Result<()> {
+    let server_config = state.cert_manager.get_server_config(&host, port).await.map_err(anyhow::Error::msg)?;
     let acceptor = TlsAcceptor::from(server_config);
     
     match acceptor.accept(upgraded).await {
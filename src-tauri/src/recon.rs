@@ -1,43 +1,661 @@
-use hickory_resolver::Resolver;
+use crate::assets::{BatchImportResult, CreateAssetRequest};
+use crate::db::get_db;
+use crate::JobState;
 use hickory_resolver::config::*;
+use hickory_resolver::TokioAsyncResolver;
 use serde::{Deserialize, Serialize};
-use std::net::ToSocketAddrs;
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
 
-#[derive(Serialize, Deserialize, Debug)]
+const DEFAULT_PREFIXES: &[&str] = &[
+    "www", "api", "dev", "staging", "test", "auth", "admin", "mail", "vpn", "corp",
+    "git", "jenkins", "docker", "k8s", "prod", "beta", "demo", "app", "mobile",
+];
+
+/// Lookups in flight at once unless the request says otherwise.
+const DEFAULT_CONCURRENCY: usize = 50;
+const MAX_CONCURRENCY: usize = 500;
+
+/// Random names resolved to detect wildcard DNS.
+const WILDCARD_PROBES: usize = 3;
+
+/// Checked names between `recon-progress` events.
+const PROGRESS_EVERY: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow)]
 pub struct ReconResult {
+    /// Set once the result is saved with its run
+    #[serde(default)]
+    #[sqlx(default)]
+    pub id: Option<i64>,
     pub subdomain: String,
     pub ip: Option<String>,
     pub status: String,
+    /// Every address the name resolved to
+    #[serde(default)]
+    #[sqlx(json)]
+    pub ips: Vec<String>,
+    /// Inventory asset created by `promote_recon_to_assets`
+    #[serde(default)]
+    #[sqlx(default)]
+    pub asset_id: Option<i64>,
+}
+
+/// One subdomain enumeration and its counts; hits are in `recon_results`.
+#[derive(Serialize, Debug, Clone, FromRow)]
+pub struct ReconRun {
+    pub id: i64,
+    pub domain: String,
+    pub wordlist_id: Option<i64>,
+    pub total: i64,
+    pub checked: i64,
+    pub found: i64,
+    #[sqlx(json)]
+    pub wildcard_ips: Vec<String>,
+    pub suppressed: i64,
+    pub cancelled: bool,
+    pub started_at: String,
+    /// Unset while the run is in progress
+    pub finished_at: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubdomainRequest {
+    pub domain: String,
+    /// Saved wordlist to take prefixes from
+    pub wordlist_id: Option<i64>,
+    /// Extra prefixes; with no wordlist either, a short built-in list is used
+    #[serde(default)]
+    pub words: Vec<String>,
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReconProgress {
+    pub job_id: String,
+    pub checked: usize,
+    pub total: usize,
+    pub found: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SubdomainSummary {
+    pub job_id: String,
+    /// Saved run; load its results with `get_recon_results`
+    pub run_id: i64,
+    pub total: usize,
+    pub checked: usize,
+    pub found: usize,
+    /// Addresses random names resolve to; empty unless the domain has
+    /// wildcard DNS
+    pub wildcard_ips: Vec<String>,
+    /// Names dropped because they only resolved to wildcard addresses
+    pub suppressed: usize,
+    pub cancelled: bool,
+}
+
+/// Bare lowercase domain from user input such as `https://Example.com/x`
+/// or `*.example.com`.
+pub fn normalize_domain(input: &str) -> Result<String, String> {
+    let input = input.trim().to_lowercase();
+    let host = input.split_once("://").map(|(_, rest)| rest).unwrap_or(&input);
+    let host = host.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map(|(_, h)| h).unwrap_or(host);
+    let host = host.split(':').next().unwrap_or("");
+    let host = host.trim_start_matches("*.").trim_matches('.');
+    let valid = host.contains('.')
+        && host.split('.').all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    if !valid {
+        return Err(format!("'{}' is not a domain name", input));
+    }
+    Ok(host.to_string())
+}
+
+/// Fully qualified names to try: each word as a label under `domain`.
+/// Words are lowercased and may already end in the domain; blanks,
+/// comments, invalid labels and duplicates are skipped.
+pub fn candidates(domain: &str, words: &[String]) -> Vec<String> {
+    let suffix = format!(".{}", domain);
+    let mut seen = HashSet::new();
+    words
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.starts_with('#'))
+        .map(|w| {
+            let w = w.trim_matches('.');
+            w.strip_suffix(suffix.as_str()).unwrap_or(w).to_string()
+        })
+        .filter(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .map(|w| format!("{}.{}", w, domain))
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+/// True when every address of a hit is also what random names resolve to.
+pub fn is_wildcard_hit(ips: &[IpAddr], wildcard: &HashSet<IpAddr>) -> bool {
+    !wildcard.is_empty() && !ips.is_empty() && ips.iter().all(|ip| wildcard.contains(ip))
+}
+
+pub(crate) fn resolver() -> TokioAsyncResolver {
+    TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+}
+
+async fn lookup(resolver: &TokioAsyncResolver, name: &str) -> Vec<IpAddr> {
+    match resolver.lookup_ip(name).await {
+        Ok(lookup) => lookup.iter().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Addresses that names which can't exist resolve to under `domain`.
+async fn wildcard_ips(resolver: &TokioAsyncResolver, domain: &str) -> HashSet<IpAddr> {
+    let mut ips = HashSet::new();
+    for _ in 0..WILDCARD_PROBES {
+        let probe = format!("{}.{}", uuid::Uuid::new_v4().simple(), domain);
+        ips.extend(lookup(resolver, &probe).await);
+    }
+    ips
+}
+
+struct BruteForce {
+    results: Vec<ReconResult>,
+    checked: usize,
+    suppressed: usize,
+    wildcard: HashSet<IpAddr>,
 }
 
+/// Resolves `names` with at most `concurrency` lookups in flight, calling
+/// `on_hit` for each live name and `on_checked` after every lookup.
+async fn brute_force(
+    domain: &str,
+    names: Vec<String>,
+    concurrency: usize,
+    cancel: &AtomicBool,
+    mut on_hit: impl FnMut(&ReconResult),
+    mut on_checked: impl FnMut(usize, usize),
+) -> BruteForce {
+    let resolver = resolver();
+    let wildcard = wildcard_ips(&resolver, domain).await;
+    let mut run = BruteForce { results: Vec::new(), checked: 0, suppressed: 0, wildcard };
+    let mut workers = crate::jobs::Workers::new(names, concurrency, |name: String| {
+        let resolver = resolver.clone();
+        async move {
+            let ips = lookup(&resolver, &name).await;
+            (name, ips)
+        }
+    });
+
+    while let Some(joined) = workers.next(cancel).await {
+        run.checked += 1;
+        if let Ok((name, ips)) = joined {
+            if is_wildcard_hit(&ips, &run.wildcard) {
+                run.suppressed += 1;
+            } else if !ips.is_empty() {
+                let result = ReconResult {
+                    id: None,
+                    subdomain: name,
+                    ip: ips.first().map(IpAddr::to_string),
+                    status: "Active".to_string(),
+                    ips: ips.iter().map(IpAddr::to_string).collect(),
+                    asset_id: None,
+                };
+                on_hit(&result);
+                run.results.push(result);
+            }
+        }
+        on_checked(run.checked, run.results.len());
+    }
+    run
+}
+
+async fn create_run(domain: &str, wordlist_id: Option<i64>, total: usize) -> Result<i64, String> {
+    let pool = get_db();
+    let result = sqlx::query("INSERT INTO recon_runs (domain, wordlist_id, total) VALUES (?, ?, ?)")
+        .bind(domain)
+        .bind(wordlist_id)
+        .bind(total as i64)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.last_insert_rowid())
+}
+
+/// Stores a run's hits and final counts, filling in each result's id.
+async fn finish_run(run_id: i64, run: &mut BruteForce, cancelled: bool) -> Result<(), String> {
+    let pool = get_db();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for result in &mut run.results {
+        let ips = serde_json::to_string(&result.ips).map_err(|e| e.to_string())?;
+        let inserted = sqlx::query("INSERT INTO recon_results (run_id, subdomain, ip, ips, status) VALUES (?, ?, ?, ?, ?)")
+            .bind(run_id)
+            .bind(&result.subdomain)
+            .bind(&result.ip)
+            .bind(ips)
+            .bind(&result.status)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        result.id = Some(inserted.last_insert_rowid());
+    }
+    let mut wildcard_ips: Vec<String> = run.wildcard.iter().map(IpAddr::to_string).collect();
+    wildcard_ips.sort();
+    sqlx::query(
+        "UPDATE recon_runs SET checked = ?, found = ?, wildcard_ips = ?, suppressed = ?, cancelled = ?, finished_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(run.checked as i64)
+    .bind(run.results.len() as i64)
+    .bind(serde_json::to_string(&wildcard_ips).map_err(|e| e.to_string())?)
+    .bind(run.suppressed as i64)
+    .bind(cancelled)
+    .bind(run_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Resolves a short built-in list of common prefixes and returns the live
+/// ones. Use `start_subdomain_bruteforce` for wordlists.
 #[tauri::command]
 pub async fn enumerate_subdomains(domain: String) -> Result<Vec<ReconResult>, String> {
-    let resolver = Resolver::new(ResolverConfig::default(), ResolverOpts::default())
-        .map_err(|e| e.to_string())?;
+    let domain = normalize_domain(&domain)?;
+    let words: Vec<String> = DEFAULT_PREFIXES.iter().map(|p| p.to_string()).collect();
+    let names = candidates(&domain, &words);
+    let run_id = create_run(&domain, None, names.len()).await?;
+    let mut run = brute_force(&domain, names, DEFAULT_CONCURRENCY, &AtomicBool::new(false), |_| {}, |_, _| {}).await;
+    finish_run(run_id, &mut run, false).await?;
+    Ok(run.results)
+}
 
-    let common_prefixes = vec![
-        "www", "api", "dev", "staging", "test", "auth", "admin", "mail", "vpn", "corp",
-        "git", "jenkins", "docker", "k8s", "prod", "beta", "demo", "app", "mobile"
-    ];
-
-    let mut results = Vec::new();
-
-    for prefix in common_prefixes {
-        let target = format!("{}.{}", prefix, domain);
-        match resolver.lookup_ip(&target).await {
-            Ok(lookup) => {
-                let ip = lookup.iter().next().map(|i| i.to_string());
-                results.push(ReconResult {
-                    subdomain: target,
-                    ip,
-                    status: "Active".to_string(),
-                });
+/// A brute-force ready to run: its run is saved and its job registered.
+struct Job {
+    job: crate::jobs::Job,
+    run_id: i64,
+    domain: String,
+    names: Vec<String>,
+    concurrency: usize,
+}
+
+async fn prepare_job(state: &Arc<JobState>, request: SubdomainRequest) -> Result<Job, String> {
+    let domain = normalize_domain(&request.domain)?;
+    let mut words = match request.wordlist_id {
+        Some(id) => crate::fuzzer::get_wordlist(id).await?.payloads,
+        None => Vec::new(),
+    };
+    words.extend(request.words);
+    if words.is_empty() {
+        words = DEFAULT_PREFIXES.iter().map(|p| p.to_string()).collect();
+    }
+    let names = candidates(&domain, &words);
+    if names.is_empty() {
+        return Err("The wordlist has no usable subdomain labels".to_string());
+    }
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let run_id = create_run(&domain, request.wordlist_id, names.len()).await?;
+
+    Ok(Job { job: crate::jobs::register(state), run_id, domain, names, concurrency })
+}
+
+/// Runs a prepared job to completion, emitting its events, and returns the
+/// saved hits.
+async fn run_job(app_handle: &tauri::AppHandle, job: Job) -> Vec<ReconResult> {
+    let Job { job, run_id, domain, names, concurrency } = job;
+    let job_id = job.id.clone();
+    let total = names.len();
+    let mut run = brute_force(
+        &domain,
+        names,
+        concurrency,
+        &job.cancel,
+        |hit| {
+            let _ = app_handle.emit("recon-subdomain", (&job_id, hit));
+        },
+        |checked, found| {
+            if checked.is_multiple_of(PROGRESS_EVERY) || checked == total {
+                let _ = app_handle.emit("recon-progress", ReconProgress { job_id: job_id.clone(), checked, total, found });
             }
-            Err(_) => {
-                // Not found, skip
+        },
+    )
+    .await;
+    let cancelled = job.cancelled();
+    drop(job);
+    if let Err(e) = finish_run(run_id, &mut run, cancelled).await {
+        eprintln!("Failed to save recon run {}: {}", run_id, e);
+    }
+    let mut wildcard_ips: Vec<String> = run.wildcard.iter().map(IpAddr::to_string).collect();
+    wildcard_ips.sort();
+    let summary = SubdomainSummary {
+        job_id,
+        run_id,
+        total,
+        checked: run.checked,
+        found: run.results.len(),
+        wildcard_ips,
+        suppressed: run.suppressed,
+        cancelled,
+    };
+    let _ = app_handle.emit("recon-complete", summary);
+    run.results
+}
+
+/// Brute-forces subdomains from a wordlist in the background and returns
+/// the job id. Live names stream through `recon-subdomain` as
+/// `(job_id, result)`, `recon-progress` reports counts, and a
+/// `SubdomainSummary` arrives with `recon-complete`. Under wildcard DNS,
+/// names resolving only to the catch-all addresses are dropped.
+#[tauri::command]
+pub async fn start_subdomain_bruteforce(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<JobState>>,
+    request: SubdomainRequest,
+) -> Result<String, String> {
+    let job = prepare_job(state.inner(), request).await?;
+    let id = job.job.id.clone();
+    tauri::async_runtime::spawn(async move {
+        run_job(&app_handle, job).await;
+    });
+    Ok(id)
+}
+
+/// Stops a recon job before its next lookup; hits so far are kept.
+#[tauri::command]
+pub fn cancel_recon(state: tauri::State<'_, Arc<JobState>>, job_id: String) -> Result<(), String> {
+    crate::jobs::cancel_job(&state, &job_id).map_err(|_| format!("Recon job {} is not running", job_id))
+}
+
+/// Saved recon runs, newest first.
+#[tauri::command]
+pub async fn list_recon_runs(domain: Option<String>) -> Result<Vec<ReconRun>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, ReconRun>(
+        "SELECT * FROM recon_runs WHERE (?1 IS NULL OR domain = ?1) ORDER BY started_at DESC, id DESC",
+    )
+    .bind(domain.map(|d| normalize_domain(&d)).transpose()?)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_recon_results(run_id: i64) -> Result<Vec<ReconResult>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, ReconResult>(
+        "SELECT r.id, r.subdomain, r.ip, r.ips, r.status, a.id AS asset_id FROM recon_results r
+         LEFT JOIN assets a ON a.id = r.asset_id AND a.deleted_at IS NULL
+         WHERE r.run_id = ? ORDER BY r.subdomain",
+    )
+    .bind(run_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_recon_run(run_id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM recon_runs WHERE id = ?")
+        .bind(run_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// A saved hit: subdomain, its live asset if promoted, and the probed URL and status.
+type ReconHit = (String, Option<i64>, Option<String>, Option<i64>);
+
+/// Adds saved recon hits to the inventory with source "Recon", using the
+/// URL the host answered on when it has been probed and `https://host/`
+/// otherwise. Results already promoted are skipped.
+#[tauri::command]
+pub async fn promote_recon_to_assets(ids: Vec<i64>) -> Result<BatchImportResult, String> {
+    let pool = get_db();
+    let (mut added, mut skipped) = (0, 0);
+    for id in ids {
+        let row: Option<ReconHit> = sqlx::query_as(
+            "SELECT r.subdomain, a.id, p.url, p.status_code FROM recon_results r
+             LEFT JOIN assets a ON a.id = r.asset_id AND a.deleted_at IS NULL
+             LEFT JOIN host_probes p ON p.host = r.subdomain AND p.alive = 1
+             WHERE r.id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        let Some((host, None, probed_url, status_code)) = row else {
+            skipped += 1;
+            continue;
+        };
+        let asset_id = crate::assets::add_asset(CreateAssetRequest {
+            url: probed_url.unwrap_or_else(|| format!("https://{}/", host)),
+            source: "Recon".to_string(),
+            method: Some("GET".to_string()),
+            status_code,
+            req_headers: HashMap::new(),
+            res_headers: HashMap::new(),
+            req_body: None,
+            res_body: None,
+            findings: Vec::new(),
+        })
+        .await?;
+        sqlx::query("UPDATE recon_results SET asset_id = ? WHERE id = ?")
+            .bind(asset_id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        added += 1;
+    }
+    Ok(BatchImportResult { added, skipped })
+}
+
+/// Names in `results` that are not already `known`.
+pub fn new_subdomains(known: &HashSet<String>, results: &[ReconResult]) -> Vec<String> {
+    results.iter().map(|r| r.subdomain.clone()).filter(|name| !known.contains(name)).collect()
+}
+
+/// Names found by finished runs of `domain` before `run_id`, or `None` when
+/// there is no earlier run to compare against.
+async fn known_subdomains(domain: &str, run_id: i64) -> Result<Option<HashSet<String>>, String> {
+    let pool = get_db();
+    let (earlier,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM recon_runs WHERE domain = ? AND id < ? AND finished_at IS NOT NULL")
+        .bind(domain)
+        .bind(run_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if earlier == 0 {
+        return Ok(None);
+    }
+    let names: Vec<(String,)> = sqlx::query_as(
+        "SELECT DISTINCT r.subdomain FROM recon_results r JOIN recon_runs u ON u.id = r.run_id WHERE u.domain = ? AND u.id < ?",
+    )
+    .bind(domain)
+    .bind(run_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(Some(names.into_iter().map(|(n,)| n).collect()))
+}
+
+/// Recurring enumeration of a monitored domain.
+#[derive(Serialize, Debug, Clone, FromRow)]
+pub struct ReconSchedule {
+    pub id: i64,
+    pub domain: String,
+    pub wordlist_id: Option<i64>,
+    pub concurrency: Option<i64>,
+    pub interval_hours: i64,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub last_run_id: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReconScheduleRequest {
+    pub domain: String,
+    pub wordlist_id: Option<i64>,
+    pub concurrency: Option<usize>,
+    pub interval_hours: i64,
+    pub enabled: bool,
+}
+
+/// Subdomains a scheduled run found that no earlier run of the domain had.
+#[derive(Serialize, Debug, Clone)]
+pub struct NewSubdomains {
+    pub domain: String,
+    pub run_id: i64,
+    pub subdomains: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn list_recon_schedules() -> Result<Vec<ReconSchedule>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, ReconSchedule>(
+        "SELECT id, domain, wordlist_id, concurrency, interval_hours, enabled, last_run_at, last_run_id FROM recon_schedules ORDER BY domain",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Creates or replaces the schedule for a domain.
+#[tauri::command]
+pub async fn save_recon_schedule(request: ReconScheduleRequest) -> Result<ReconSchedule, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let domain = normalize_domain(&request.domain)?;
+    if request.interval_hours < 1 {
+        return Err("The interval must be at least one hour".to_string());
+    }
+    let pool = get_db();
+    sqlx::query_as::<_, ReconSchedule>(
+        "INSERT INTO recon_schedules (domain, wordlist_id, concurrency, interval_hours, enabled) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(domain) DO UPDATE SET wordlist_id = excluded.wordlist_id, concurrency = excluded.concurrency,
+             interval_hours = excluded.interval_hours, enabled = excluded.enabled
+         RETURNING id, domain, wordlist_id, concurrency, interval_hours, enabled, last_run_at, last_run_id",
+    )
+    .bind(domain)
+    .bind(request.wordlist_id)
+    .bind(request.concurrency.map(|c| c.clamp(1, MAX_CONCURRENCY) as i64))
+    .bind(request.interval_hours)
+    .bind(request.enabled)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_recon_schedule(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM recon_schedules WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Runs one scheduled enumeration and reports names no earlier run found
+/// through `recon-new-subdomains` and the notification webhook, if set.
+async fn run_scheduled(app_handle: &tauri::AppHandle, state: &Arc<JobState>, schedule: ReconSchedule) -> Result<(), String> {
+    let request = SubdomainRequest {
+        domain: schedule.domain.clone(),
+        wordlist_id: schedule.wordlist_id,
+        words: Vec::new(),
+        concurrency: schedule.concurrency.map(|c| c as usize),
+    };
+    // Stamped before starting, so a run that cannot start waits out the
+    // interval instead of being retried every minute
+    sqlx::query("UPDATE recon_schedules SET last_run_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(schedule.id)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    let job = prepare_job(state, request).await?;
+    let run_id = job.run_id;
+    sqlx::query("UPDATE recon_schedules SET last_run_id = ? WHERE id = ?")
+        .bind(run_id)
+        .bind(schedule.id)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let results = run_job(app_handle, job).await;
+    let Some(known) = known_subdomains(&schedule.domain, run_id).await? else {
+        return Ok(());
+    };
+    let subdomains = new_subdomains(&known, &results);
+    if subdomains.is_empty() {
+        return Ok(());
+    }
+    let _ = app_handle.emit(
+        "recon-new-subdomains",
+        NewSubdomains { domain: schedule.domain.clone(), run_id, subdomains: subdomains.clone() },
+    );
+    if crate::db::get_webhook().await?.is_some() {
+        crate::db::send_notification(
+            format!("{} new subdomains of {}", subdomains.len(), schedule.domain),
+            subdomains.join("\n"),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Starts each enabled schedule once its interval has passed since the last
+/// run. Runs for the lifetime of the app.
+pub async fn run_scheduler(app_handle: tauri::AppHandle) {
+    let state = app_handle.state::<Arc<JobState>>().inner().clone();
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+
+        let due: Vec<ReconSchedule> = match sqlx::query_as(
+            "SELECT id, domain, wordlist_id, concurrency, interval_hours, enabled, last_run_at, last_run_id FROM recon_schedules
+             WHERE enabled = 1 AND (last_run_at IS NULL OR last_run_at <= datetime('now', '-' || interval_hours || ' hours'))",
+        )
+        .fetch_all(&get_db())
+        .await
+        {
+            Ok(due) => due,
+            Err(_) => continue,
+        };
+        for schedule in due {
+            let domain = schedule.domain.clone();
+            if let Err(e) = run_scheduled(&app_handle, &state, schedule).await {
+                eprintln!("Scheduled recon of {} failed: {}", domain, e);
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        assert_eq!(normalize_domain(" https://User@Example.COM:8443/path ").unwrap(), "example.com");
+        assert_eq!(normalize_domain("*.example.com.").unwrap(), "example.com");
+        assert!(normalize_domain("localhost").is_err());
+        assert!(normalize_domain("exa mple.com").is_err());
 
-    Ok(results)
+        let words: Vec<String> = ["API", "api.example.com", "# comment", "", "dev.eu", "bad/label", " www "].iter().map(|w| w.to_string()).collect();
+        assert_eq!(candidates("example.com", &words), vec!["api.example.com", "dev.eu.example.com", "www.example.com"]);
+
+        let wildcard: HashSet<IpAddr> = ["10.0.0.1".parse().unwrap()].into();
+        assert!(is_wildcard_hit(&["10.0.0.1".parse().unwrap()], &wildcard));
+        assert!(!is_wildcard_hit(&["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()], &wildcard));
+        assert!(!is_wildcard_hit(&["10.0.0.1".parse().unwrap()], &HashSet::new()));
+        assert!(!is_wildcard_hit(&[], &wildcard));
+
+        let hit = |name: &str| ReconResult { id: None, subdomain: name.to_string(), ip: None, status: "Active".to_string(), ips: Vec::new(), asset_id: None };
+        let known: HashSet<String> = ["api.example.com".to_string()].into();
+        assert_eq!(new_subdomains(&known, &[hit("api.example.com"), hit("new.example.com")]), vec!["new.example.com"]);
+    }
 }
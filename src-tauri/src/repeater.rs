@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use crate::assets::{headers_json, ReplayRequest, VersionDiff};
+use crate::db::get_db;
+use sqlx::{FromRow, Pool, Sqlite};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct RepeaterSession {
+    pub id: i64,
+    pub name: String,
+    pub asset_id: Option<i64>,
+    pub url: String,
+    pub method: String,
+    #[sqlx(json(nullable))]
+    pub headers: Option<HashMap<String, String>>,
+    pub body: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub attempt_count: i64,
+}
+
+/// One send of a session. The request is stored as edited, before
+/// environment variables are resolved, so it replays the same way later.
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct RepeaterAttempt {
+    pub id: i64,
+    pub session_id: i64,
+    pub url: String,
+    pub method: String,
+    #[sqlx(json(nullable))]
+    pub headers: Option<HashMap<String, String>>,
+    pub body: Option<String>,
+    pub status: Option<i64>,
+    #[sqlx(json(nullable))]
+    pub res_headers: Option<HashMap<String, String>>,
+    pub res_body: Option<String>,
+    pub time_ms: Option<i64>,
+    /// Set when the request could not be sent at all
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepeaterSessionDetail {
+    pub session: RepeaterSession,
+    /// Newest first
+    pub attempts: Vec<RepeaterAttempt>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttemptResult {
+    pub attempt: RepeaterAttempt,
+    #[serde(flatten)]
+    pub variables: crate::variables::Substitutions,
+    /// Against the previous attempt that got a response
+    pub diff: Option<VersionDiff>,
+}
+
+const SESSION_COLUMNS: &str = "s.id, s.name, s.asset_id, s.url, s.method, s.headers, s.body, s.created_at, s.updated_at, \
+    (SELECT COUNT(*) FROM repeater_attempts r WHERE r.session_id = s.id) AS attempt_count";
+
+const ATTEMPT_COLUMNS: &str = "id, session_id, url, method, headers, body, status, res_headers, res_body, time_ms, error, created_at";
+
+async fn fetch_attempt(pool: &Pool<Sqlite>, id: i64) -> Result<RepeaterAttempt, String> {
+    sqlx::query_as::<_, RepeaterAttempt>(&format!("SELECT {} FROM repeater_attempts WHERE id = ?", ATTEMPT_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Repeater attempt {} not found", id))
+}
+
+fn diff_attempts(a: &RepeaterAttempt, b: &RepeaterAttempt) -> VersionDiff {
+    VersionDiff {
+        timestamp_a: a.created_at.clone(),
+        timestamp_b: b.created_at.clone(),
+        status_a: a.status,
+        status_b: b.status,
+        headers: crate::diff::diff_headers(&a.res_headers.clone().unwrap_or_default(), &b.res_headers.clone().unwrap_or_default()),
+        body: crate::diff::diff_bodies(a.res_body.as_deref(), b.res_body.as_deref()),
+    }
+}
+
+#[tauri::command]
+pub async fn create_repeater_session(name: String, asset_id: Option<i64>, request: ReplayRequest) -> Result<i64, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Session name must not be empty".to_string());
+    }
+    let pool = get_db();
+    let res = sqlx::query("INSERT INTO repeater_sessions (name, asset_id, url, method, headers, body) VALUES (?, ?, ?, ?, ?, ?)")
+        .bind(name)
+        .bind(asset_id)
+        .bind(&request.url)
+        .bind(&request.method)
+        .bind(headers_json(&request.headers))
+        .bind(&request.body)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.last_insert_rowid())
+}
+
+/// Saved sessions, most recently used first, optionally only those opened from one asset.
+#[tauri::command]
+pub async fn list_repeater_sessions(asset_id: Option<i64>) -> Result<Vec<RepeaterSession>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, RepeaterSession>(&format!(
+        "SELECT {} FROM repeater_sessions s WHERE ?1 IS NULL OR s.asset_id = ?1 ORDER BY s.updated_at DESC, s.id DESC",
+        SESSION_COLUMNS
+    ))
+    .bind(asset_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_repeater_session(id: i64) -> Result<RepeaterSessionDetail, String> {
+    let pool = get_db();
+    let session = sqlx::query_as::<_, RepeaterSession>(&format!("SELECT {} FROM repeater_sessions s WHERE s.id = ?", SESSION_COLUMNS))
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Repeater session {} not found", id))?;
+    let attempts = sqlx::query_as::<_, RepeaterAttempt>(&format!(
+        "SELECT {} FROM repeater_attempts WHERE session_id = ? ORDER BY id DESC",
+        ATTEMPT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(RepeaterSessionDetail { session, attempts })
+}
+
+/// Sends `request` for a session, saving it as the session's current
+/// request and recording the outcome. A request that fails to send is still
+/// recorded, with its error, rather than returned as an error.
+#[tauri::command]
+pub async fn send_repeater_request(session_id: i64, request: ReplayRequest) -> Result<AttemptResult, String> {
+    let pool = get_db();
+    let updated = sqlx::query("UPDATE repeater_sessions SET url = ?, method = ?, headers = ?, body = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(&request.url)
+        .bind(&request.method)
+        .bind(headers_json(&request.headers))
+        .bind(&request.body)
+        .bind(session_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if updated.rows_affected() == 0 {
+        return Err(format!("Repeater session {} not found", session_id));
+    }
+
+    let previous: Option<(i64,)> = sqlx::query_as("SELECT id FROM repeater_attempts WHERE session_id = ? AND error IS NULL ORDER BY id DESC LIMIT 1")
+        .bind(session_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let outcome = crate::assets::tamper_request(request.clone()).await;
+    let (status, res_headers, res_body, time_ms, error, variables) = match outcome {
+        Ok(res) => (Some(res.status as i64), res.headers, Some(res.body), Some(res.time_ms as i64), None, res.variables),
+        Err(e) => (None, HashMap::new(), None, None, Some(e), Default::default()),
+    };
+
+    let id = sqlx::query(
+        "INSERT INTO repeater_attempts (session_id, url, method, headers, body, status, res_headers, res_body, time_ms, error) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(session_id)
+    .bind(&request.url)
+    .bind(&request.method)
+    .bind(headers_json(&request.headers))
+    .bind(&request.body)
+    .bind(status)
+    .bind(headers_json(&res_headers))
+    .bind(res_body)
+    .bind(time_ms)
+    .bind(error)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+
+    let attempt = fetch_attempt(&pool, id).await?;
+    let diff = match previous {
+        Some((prev,)) if attempt.error.is_none() => Some(diff_attempts(&fetch_attempt(&pool, prev).await?, &attempt)),
+        _ => None,
+    };
+    Ok(AttemptResult { attempt, variables, diff })
+}
+
+#[tauri::command]
+pub async fn diff_repeater_attempts(attempt_a: i64, attempt_b: i64) -> Result<VersionDiff, String> {
+    let pool = get_db();
+    let a = fetch_attempt(&pool, attempt_a).await?;
+    let b = fetch_attempt(&pool, attempt_b).await?;
+    Ok(diff_attempts(&a, &b))
+}
+
+#[tauri::command]
+pub async fn rename_repeater_session(id: i64, name: String) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Session name must not be empty".to_string());
+    }
+    let pool = get_db();
+    sqlx::query("UPDATE repeater_sessions SET name = ? WHERE id = ?")
+        .bind(name)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Deletes a session together with its recorded attempts.
+#[tauri::command]
+pub async fn delete_repeater_session(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    sqlx::query("DELETE FROM repeater_sessions WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
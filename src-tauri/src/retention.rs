@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use sqlx::{Pool, Sqlite};
+
+/// Days of AI usage metrics kept unless the workspace sets otherwise.
+const DEFAULT_AI_USAGE_AGE_DAYS: i64 = 90;
+
+fn default_ai_usage_age_days() -> Option<i64> {
+    Some(DEFAULT_AI_USAGE_AGE_DAYS)
+}
+
+/// Limits applied by `purge`. Each one is off when unset.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RetentionSettings {
+    /// Assets not seen for this many days are deleted
+    pub max_asset_age_days: Option<i64>,
+    /// Older history versions beyond this count are dropped per asset
+    pub max_history_per_asset: Option<i64>,
+    /// Least recently seen assets are dropped until the workspace fits
+    pub max_db_size_mb: Option<i64>,
+    /// AI usage metrics older than this many days are deleted; 90 unless
+    /// explicitly set, since every model call adds a row
+    #[serde(default = "default_ai_usage_age_days")]
+    pub max_ai_usage_age_days: Option<i64>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        RetentionSettings {
+            max_asset_age_days: None,
+            max_history_per_asset: None,
+            max_db_size_mb: None,
+            max_ai_usage_age_days: default_ai_usage_age_days(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PurgeReport {
+    pub assets_deleted: u64,
+    pub findings_deleted: u64,
+    pub history_deleted: u64,
+    /// Trashed assets past the trash retention window
+    pub trash_purged: u64,
+    pub ai_usage_deleted: u64,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+/// Assets removed per round while shrinking a workspace to its size limit.
+const SIZE_PURGE_BATCH: i64 = 500;
+
+#[tauri::command]
+pub async fn get_retention_settings() -> Result<RetentionSettings, String> {
+    crate::settings::get("retention").await
+}
+
+#[tauri::command]
+pub async fn set_retention_settings(settings: RetentionSettings) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    let value = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    crate::settings::write(&get_db(), "retention", value).await
+}
+
+/// Bytes actually occupied by data, excluding free pages awaiting VACUUM.
+async fn used_bytes(pool: &Pool<Sqlite>) -> Result<i64, String> {
+    let (page_count,): (i64,) = sqlx::query_as("PRAGMA page_count").fetch_one(pool).await.map_err(|e| e.to_string())?;
+    let (freelist,): (i64,) = sqlx::query_as("PRAGMA freelist_count").fetch_one(pool).await.map_err(|e| e.to_string())?;
+    let (page_size,): (i64,) = sqlx::query_as("PRAGMA page_size").fetch_one(pool).await.map_err(|e| e.to_string())?;
+    Ok((page_count - freelist) * page_size)
+}
+
+/// Deletes the given assets; findings, history and tag links cascade.
+async fn delete_assets(pool: &Pool<Sqlite>, ids: &[i64], report: &mut PurgeReport) -> Result<(), String> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let ids = serde_json::to_string(ids).map_err(|e| e.to_string())?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let (findings, history): (i64, i64) = sqlx::query_as(
+        "SELECT \
+            (SELECT COUNT(*) FROM findings WHERE asset_id IN (SELECT value FROM json_each(?1))), \
+            (SELECT COUNT(*) FROM asset_history WHERE asset_id IN (SELECT value FROM json_each(?1)))"
+    )
+    .bind(&ids)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    report.assets_deleted += sqlx::query("DELETE FROM assets WHERE id IN (SELECT value FROM json_each(?))")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+    report.findings_deleted += findings as u64;
+    report.history_deleted += history as u64;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Whether any configured limit would delete assets or history right now.
+async fn limits_exceeded(pool: &Pool<Sqlite>, settings: &RetentionSettings) -> Result<bool, String> {
+    if let Some(days) = settings.max_asset_age_days.filter(|d| *d > 0) {
+        let (stale,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM assets WHERE last_seen < datetime('now', ?))")
+            .bind(format!("-{} days", days))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if stale {
+            return Ok(true);
+        }
+    }
+    if let Some(keep) = settings.max_history_per_asset.filter(|k| *k >= 0) {
+        let (over,): (bool,) = sqlx::query_as("SELECT EXISTS (SELECT 1 FROM asset_history GROUP BY asset_id HAVING COUNT(*) > ?)")
+            .bind(keep)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        if over {
+            return Ok(true);
+        }
+    }
+    if let Some(max_mb) = settings.max_db_size_mb.filter(|m| *m > 0) {
+        if used_bytes(pool).await? > max_mb * 1024 * 1024 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Applies the workspace's retention settings on demand; see `purge`.
+#[tauri::command]
+pub async fn run_retention_purge(app_handle: tauri::AppHandle) -> Result<PurgeReport, String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    purge(&app_handle).await
+}
+
+/// Applies the workspace's retention settings: empties expired trash, ages
+/// out stale assets, caps per-asset history and AI usage metrics, then drops
+/// the least recently seen assets until the workspace fits its size limit.
+/// Runs on startup without a role check. A snapshot is taken first only
+/// when a limit is actually exceeded, so idle startups don't rotate real
+/// backups out.
+pub async fn purge(app_handle: &tauri::AppHandle) -> Result<PurgeReport, String> {
+    let pool = get_db();
+    let settings = get_retention_settings().await?;
+    if limits_exceeded(&pool, &settings).await? {
+        crate::backups::snapshot(app_handle, "pre-purge").await?;
+    }
+    let mut report = PurgeReport {
+        bytes_before: used_bytes(&pool).await?,
+        ..Default::default()
+    };
+
+    report.trash_purged = crate::trash::purge_expired_trash(&pool).await?;
+
+    if let Some(days) = settings.max_asset_age_days.filter(|d| *d > 0) {
+        let stale: Vec<(i64,)> = sqlx::query_as("SELECT id FROM assets WHERE last_seen < datetime('now', ?)")
+            .bind(format!("-{} days", days))
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let ids: Vec<i64> = stale.into_iter().map(|(id,)| id).collect();
+        delete_assets(&pool, &ids, &mut report).await?;
+    }
+
+    if let Some(keep) = settings.max_history_per_asset.filter(|k| *k >= 0) {
+        report.history_deleted += sqlx::query(
+            "DELETE FROM asset_history WHERE id IN ( \
+                SELECT id FROM ( \
+                    SELECT id, ROW_NUMBER() OVER (PARTITION BY asset_id ORDER BY timestamp DESC, id DESC) AS rn \
+                    FROM asset_history \
+                ) WHERE rn > ? \
+             )"
+        )
+        .bind(keep)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+    }
+
+    if let Some(days) = settings.max_ai_usage_age_days.filter(|d| *d > 0) {
+        report.ai_usage_deleted = sqlx::query("DELETE FROM ai_usage WHERE created_at < datetime('now', ?)")
+            .bind(format!("-{} days", days))
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    }
+
+    if let Some(max_mb) = settings.max_db_size_mb.filter(|m| *m > 0) {
+        let limit = max_mb * 1024 * 1024;
+        while used_bytes(&pool).await? > limit {
+            // Trashed assets go first
+            let oldest: Vec<(i64,)> = sqlx::query_as("SELECT id FROM assets ORDER BY deleted_at IS NULL, last_seen ASC, id ASC LIMIT ?")
+                .bind(SIZE_PURGE_BATCH)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            if oldest.is_empty() {
+                break;
+            }
+            let ids: Vec<i64> = oldest.into_iter().map(|(id,)| id).collect();
+            delete_assets(&pool, &ids, &mut report).await?;
+        }
+    }
+
+    // Hand freed pages back to the filesystem
+    if report.assets_deleted > 0 || report.history_deleted > 0 || report.trash_purged > 0 || report.ai_usage_deleted > 0 {
+        sqlx::query("VACUUM").execute(&pool).await.map_err(|e| e.to_string())?;
+    }
+    report.bytes_after = used_bytes(&pool).await?;
+
+    Ok(report)
+}
@@ -0,0 +1,165 @@
+use crate::db::get_db;
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// Request headers that carry credentials.
+pub const AUTH_HEADERS: [&str; 6] = ["authorization", "cookie", "x-api-key", "x-auth-token", "x-access-token", "proxy-authorization"];
+
+/// Query parameters that carry credentials.
+const AUTH_PARAMS: [&str; 5] = ["api_key", "apikey", "access_token", "token", "key"];
+
+/// Host suffixes that only resolve on internal networks.
+const INTERNAL_SUFFIXES: [&str; 7] = [".local", ".localhost", ".internal", ".intranet", ".corp", ".lan", ".home.arpa"];
+
+pub struct RiskInputs<'a> {
+    /// Effective severities of the asset's open findings
+    pub severities: &'a [String],
+    pub authenticated: bool,
+    pub internet_facing: bool,
+    pub days_since_seen: f64,
+}
+
+/// Weight of an open finding by effective severity.
+fn severity_weight(severity: &str) -> i64 {
+    match severity {
+        "High" => 10,
+        "Medium" => 5,
+        "Low" => 2,
+        _ => 0,
+    }
+}
+
+/// 0-100. Findings contribute up to 70 points, and an unauthenticated or
+/// internet-facing endpoint 15 more each; the total then decays for assets
+/// not seen lately so stale captures sink below live ones.
+pub fn risk_score(inputs: &RiskInputs) -> i64 {
+    let findings: i64 = inputs.severities.iter().map(|s| severity_weight(s)).sum();
+    let exposure = if inputs.authenticated { 0 } else { 15 } + if inputs.internet_facing { 15 } else { 0 };
+    let recency = match inputs.days_since_seen {
+        d if d <= 7.0 => 1.0,
+        d if d <= 30.0 => 0.8,
+        d if d <= 90.0 => 0.6,
+        _ => 0.4,
+    };
+    (((findings * 2).min(70) + exposure) as f64 * recency).round() as i64
+}
+
+/// Whether the captured request carried credentials in a header or the query string.
+pub fn is_authenticated(url: &str, req_headers: &HashMap<String, String>) -> bool {
+    req_headers.keys().any(|k| AUTH_HEADERS.contains(&k.to_lowercase().as_str()))
+        || url::Url::parse(url)
+            .map(|u| u.query_pairs().any(|(k, _)| AUTH_PARAMS.contains(&k.to_lowercase().as_str())))
+            .unwrap_or(false)
+}
+
+/// `url` without the query parameters that carry credentials, for replays
+/// that must go out unauthenticated.
+pub fn strip_auth_params(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+    let pairs: Vec<(String, String)> = parsed.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+    let kept: Vec<&(String, String)> = pairs.iter().filter(|(k, _)| !AUTH_PARAMS.contains(&k.to_lowercase().as_str())).collect();
+    if kept.len() == pairs.len() {
+        return url.to_string();
+    }
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    parsed.to_string()
+}
+
+/// False for loopback, private and link-local addresses and for hostnames
+/// that only exist on internal networks.
+pub fn is_internet_facing(url: &str) -> bool {
+    let Some(host) = url::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.trim_matches(['[', ']']).to_lowercase())) else {
+        return false;
+    };
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => !(ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()),
+        // fc00::/7 unique local, fe80::/10 link-local
+        Ok(IpAddr::V6(ip)) => !(ip.is_loopback() || ip.is_unspecified() || (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80),
+        Err(_) => host.contains('.') && host != "localhost" && !INTERNAL_SUFFIXES.iter().any(|s| host.ends_with(s)),
+    }
+}
+
+/// Recomputes and stores `risk_score` for one asset, or for all of them.
+/// Scores reflect recency as of the time they were computed.
+pub async fn refresh_risk_scores(pool: &Pool<Sqlite>, asset_id: Option<i64>) -> Result<u64, String> {
+    let rows: Vec<(i64, String, Option<String>, f64, String)> = sqlx::query_as(
+        "SELECT a.id, a.url, a.req_headers, COALESCE(julianday('now') - julianday(a.last_seen), 0), \
+            (SELECT json_group_array(COALESCE(f.severity_override, f.severity, 'Info')) FROM findings f \
+             WHERE f.asset_id = a.id AND f.is_false_positive = 0) \
+         FROM assets a WHERE ?1 IS NULL OR a.id = ?1",
+    )
+    .bind(asset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (id, url, req_headers, days_since_seen, severities) in &rows {
+        let headers: HashMap<String, String> = req_headers.as_deref().and_then(|h| serde_json::from_str(h).ok()).unwrap_or_default();
+        let severities: Vec<String> = serde_json::from_str(severities).unwrap_or_default();
+        let score = risk_score(&RiskInputs {
+            severities: &severities,
+            authenticated: is_authenticated(url, &headers),
+            internet_facing: is_internet_facing(url),
+            days_since_seen: *days_since_seen,
+        });
+        sqlx::query("UPDATE assets SET risk_score = ? WHERE id = ?")
+            .bind(score)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(rows.len() as u64)
+}
+
+/// Rescores every asset, e.g. so recency reflects today. Returns how many were scored.
+#[tauri::command]
+pub async fn recompute_risk_scores() -> Result<u64, String> {
+    let pool = get_db();
+    refresh_risk_scores(&pool, None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_risk_score() {
+        let high = vec!["High".to_string(), "Medium".to_string()];
+        let exposed = RiskInputs { severities: &high, authenticated: false, internet_facing: true, days_since_seen: 1.0 };
+        assert_eq!(risk_score(&exposed), 60);
+        let stale = RiskInputs { days_since_seen: 45.0, ..exposed };
+        assert_eq!(risk_score(&stale), 36);
+        let many = vec!["High".to_string(); 10];
+        assert_eq!(risk_score(&RiskInputs { severities: &many, authenticated: true, internet_facing: false, days_since_seen: 0.0 }), 70);
+        assert_eq!(risk_score(&RiskInputs { severities: &[], authenticated: true, internet_facing: false, days_since_seen: 0.0 }), 0);
+    }
+
+    #[test]
+    fn test_exposure() {
+        let headers = HashMap::from([("Authorization".to_string(), "Bearer x".to_string())]);
+        assert!(is_authenticated("https://api.example.com/me", &headers));
+        assert!(is_authenticated("https://api.example.com/me?api_key=1", &HashMap::new()));
+        assert!(!is_authenticated("https://api.example.com/me", &HashMap::new()));
+        assert_eq!(strip_auth_params("https://api.example.com/me?API_KEY=1&page=2"), "https://api.example.com/me?page=2");
+        assert_eq!(strip_auth_params("https://api.example.com/me?token=abc"), "https://api.example.com/me");
+        assert_eq!(strip_auth_params("https://api.example.com/me?q=a%20b"), "https://api.example.com/me?q=a%20b");
+
+        assert!(is_internet_facing("https://api.example.com/"));
+        assert!(is_internet_facing("http://8.8.8.8/"));
+        assert!(!is_internet_facing("http://10.0.0.5:8080/"));
+        assert!(!is_internet_facing("http://[::1]/"));
+        assert!(!is_internet_facing("http://localhost:3000/"));
+        assert!(!is_internet_facing("https://billing.corp/"));
+        assert!(!is_internet_facing("http://intranet/"));
+    }
+}
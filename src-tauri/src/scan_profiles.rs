@@ -0,0 +1,274 @@
+//! Named bundles of the active checks a scan may run, with the payloads,
+//! concurrency and pacing it may use. Every active check resolves a profile
+//! before sending anything, so intrusive checks only run where a profile
+//! allowing them was chosen.
+//!
+//! Requests the user writes and sends themselves, through the repeater
+//! (`assets::tamper_request`) or a request chain (`chains::run_chain`), are
+//! not gated: they go out exactly as written, one at a time. Nor is
+//! subdomain recon, which only queries DNS resolvers and certificate
+//! transparency logs and never connects to the hosts it finds.
+
+use crate::db::get_db;
+use crate::pacing::Pacing;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActiveCheck {
+    SecurityHeaders,
+    WebCache,
+    UnauthAccess,
+    AuthMatrix,
+    HttpMethods,
+    RateLimit,
+    /// Fuzzing and intruder attacks, including header, GraphQL and
+    /// Content-Type probes
+    Fuzz,
+    Bola,
+    /// Port scans, TLS probes and fingerprinting of hosts
+    Discovery,
+}
+
+impl ActiveCheck {
+    pub const ALL: [ActiveCheck; 9] = [
+        ActiveCheck::SecurityHeaders,
+        ActiveCheck::WebCache,
+        ActiveCheck::UnauthAccess,
+        ActiveCheck::AuthMatrix,
+        ActiveCheck::HttpMethods,
+        ActiveCheck::RateLimit,
+        ActiveCheck::Fuzz,
+        ActiveCheck::Bola,
+        ActiveCheck::Discovery,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActiveCheck::SecurityHeaders => "security header audits",
+            ActiveCheck::WebCache => "web cache probes",
+            ActiveCheck::UnauthAccess => "unauthenticated replays",
+            ActiveCheck::AuthMatrix => "authorization matrix runs",
+            ActiveCheck::HttpMethods => "HTTP method enumeration",
+            ActiveCheck::RateLimit => "rate limit tests",
+            ActiveCheck::Fuzz => "fuzzing",
+            ActiveCheck::Bola => "BOLA tests",
+            ActiveCheck::Discovery => "port scans, host probes and fingerprinting",
+        }
+    }
+
+    /// Sends GETs only, without attack payloads or unusual volume.
+    pub fn is_safe(&self) -> bool {
+        matches!(self, ActiveCheck::SecurityHeaders)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct ScanProfile {
+    pub id: Option<i64>,
+    pub name: String,
+    #[sqlx(default)]
+    pub description: Option<String>,
+    #[sqlx(json)]
+    pub checks: Vec<ActiveCheck>,
+    /// Wordlists fuzz runs may use; empty allows any
+    #[sqlx(json)]
+    pub wordlist_ids: Vec<i64>,
+    /// Checks run at once by batch scans
+    pub concurrency: i64,
+    /// Ceiling on every run's pacing, and on the rate of rate limit tests
+    #[sqlx(json)]
+    pub pacing: Pacing,
+    /// Used when a scan doesn't name a profile
+    #[sqlx(default)]
+    pub is_default: bool,
+}
+
+impl ScanProfile {
+    pub fn allows(&self, check: ActiveCheck) -> bool {
+        self.checks.contains(&check)
+    }
+
+    /// `pacing` tightened to the profile's: the lower rate, the longer
+    /// delay and jitter, and the fewer retries.
+    pub fn cap_pacing(&self, pacing: &Pacing) -> Pacing {
+        let rate = match (pacing.requests_per_second, self.pacing.requests_per_second) {
+            (a, b) if a > 0.0 && b > 0.0 => a.min(b),
+            (a, b) => a.max(b),
+        };
+        Pacing {
+            requests_per_second: rate,
+            delay_ms: pacing.delay_ms.max(self.pacing.delay_ms),
+            jitter_ms: pacing.jitter_ms.max(self.pacing.jitter_ms),
+            max_retries: pacing.max_retries.min(self.pacing.max_retries),
+        }
+    }
+}
+
+/// Profiles created with a new workspace.
+fn builtin_profiles() -> Vec<ScanProfile> {
+    vec![
+        ScanProfile {
+            id: None,
+            name: "Safe passive+headers".to_string(),
+            description: Some("GET-only checks that are safe against production: security header audits.".to_string()),
+            checks: ActiveCheck::ALL.into_iter().filter(ActiveCheck::is_safe).collect(),
+            wordlist_ids: Vec::new(),
+            concurrency: 2,
+            pacing: Pacing { requests_per_second: 2.0, ..Default::default() },
+            is_default: true,
+        },
+        ScanProfile {
+            id: None,
+            name: "Full active".to_string(),
+            description: Some("Every check, including fuzzing, mutating verbs and load tests. Test environments only.".to_string()),
+            checks: ActiveCheck::ALL.to_vec(),
+            wordlist_ids: Vec::new(),
+            concurrency: 4,
+            pacing: Pacing::default(),
+            is_default: false,
+        },
+    ]
+}
+
+async fn insert(pool: &Pool<Sqlite>, profile: &ScanProfile) -> Result<i64, sqlx::Error> {
+    let res = sqlx::query("INSERT INTO scan_profiles (name, description, checks, wordlist_ids, concurrency, pacing, is_default) VALUES (?, ?, ?, ?, ?, ?, ?)")
+        .bind(profile.name.trim())
+        .bind(&profile.description)
+        .bind(sqlx::types::Json(&profile.checks))
+        .bind(sqlx::types::Json(&profile.wordlist_ids))
+        .bind(profile.concurrency)
+        .bind(sqlx::types::Json(&profile.pacing))
+        .bind(profile.is_default)
+        .execute(pool)
+        .await?;
+    Ok(res.last_insert_rowid())
+}
+
+/// Creates the built-in profiles in a workspace that has none.
+pub async fn seed(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM scan_profiles").fetch_one(pool).await?;
+    if count == 0 {
+        for profile in builtin_profiles() {
+            insert(pool, &profile).await?;
+        }
+    }
+    Ok(())
+}
+
+/// The named profile, or the default one.
+pub(crate) async fn load(profile_id: Option<i64>) -> Result<ScanProfile, String> {
+    let pool = get_db();
+    let profile = match profile_id {
+        Some(id) => sqlx::query_as::<_, ScanProfile>("SELECT * FROM scan_profiles WHERE id = ?").bind(id).fetch_optional(&pool).await,
+        None => sqlx::query_as::<_, ScanProfile>("SELECT * FROM scan_profiles WHERE is_default = 1").fetch_optional(&pool).await,
+    }
+    .map_err(|e| e.to_string())?;
+    profile.ok_or_else(|| match profile_id {
+        Some(_) => "Scan profile not found".to_string(),
+        None => "No default scan profile is set".to_string(),
+    })
+}
+
+/// The named profile, or the default one, provided it allows `check`.
+pub async fn authorize(profile_id: Option<i64>, check: ActiveCheck) -> Result<ScanProfile, String> {
+    let profile = load(profile_id).await?;
+    if !profile.allows(check) {
+        return Err(format!("The '{}' scan profile doesn't allow {}", profile.name, check.label()));
+    }
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn list_scan_profiles() -> Result<Vec<ScanProfile>, String> {
+    sqlx::query_as::<_, ScanProfile>("SELECT * FROM scan_profiles ORDER BY name")
+        .fetch_all(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Creates or updates a profile; `is_default` is changed through
+/// `set_default_scan_profile` only.
+#[tauri::command]
+pub async fn save_scan_profile(profile: ScanProfile) -> Result<i64, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    if profile.name.trim().is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+    if profile.concurrency < 1 {
+        return Err("Concurrency must be at least 1".to_string());
+    }
+    if profile.pacing.requests_per_second < 0.0 {
+        return Err("Rate must not be negative".to_string());
+    }
+    let pool = get_db();
+    match profile.id {
+        Some(id) => {
+            sqlx::query("UPDATE scan_profiles SET name = ?, description = ?, checks = ?, wordlist_ids = ?, concurrency = ?, pacing = ? WHERE id = ?")
+                .bind(profile.name.trim())
+                .bind(&profile.description)
+                .bind(sqlx::types::Json(&profile.checks))
+                .bind(sqlx::types::Json(&profile.wordlist_ids))
+                .bind(profile.concurrency)
+                .bind(sqlx::types::Json(&profile.pacing))
+                .bind(id)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        None => insert(&pool, &ScanProfile { is_default: false, ..profile }).await.map_err(|e| e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_scan_profile(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    if load(Some(id)).await?.is_default {
+        return Err("Choose another default profile before deleting this one".to_string());
+    }
+    sqlx::query("DELETE FROM scan_profiles WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Makes `id` the profile scans use when they don't name one.
+#[tauri::command]
+pub async fn set_default_scan_profile(id: i64) -> Result<(), String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    load(Some(id)).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE scan_profiles SET is_default = (id = ?)")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        let [safe, full] = <[ScanProfile; 2]>::try_from(builtin_profiles()).unwrap();
+        assert!(safe.allows(ActiveCheck::SecurityHeaders) && !safe.allows(ActiveCheck::HttpMethods) && !safe.allows(ActiveCheck::Fuzz));
+        // Cache probes send poisoning headers
+        assert!(!safe.allows(ActiveCheck::WebCache));
+        assert!(ActiveCheck::ALL.iter().all(|c| full.allows(*c)));
+
+        let fast = Pacing { requests_per_second: 50.0, delay_ms: 0, jitter_ms: 100, max_retries: 5 };
+        let capped = safe.cap_pacing(&fast);
+        assert_eq!(capped, Pacing { requests_per_second: 2.0, delay_ms: 0, jitter_ms: 100, max_retries: 3 });
+        assert_eq!(safe.cap_pacing(&Pacing::default()).requests_per_second, 2.0);
+        assert_eq!(full.cap_pacing(&fast), Pacing { max_retries: 3, ..fast });
+        assert_eq!(serde_json::to_string(&ActiveCheck::HttpMethods).unwrap(), "\"http_methods\"");
+    }
+}
@@ -0,0 +1,373 @@
+//! Response-header policy: HSTS, CSP, X-Content-Type-Options, framing,
+//! caching of authenticated responses, and CORS.
+
+use crate::analysis::{Finding, FindingSeverity};
+use crate::db::get_db;
+use crate::pacing::{Pacer, Pacing};
+use crate::scan_profiles::ActiveCheck;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// HSTS max-age below this (180 days) is too short to protect returning clients.
+const MIN_HSTS_MAX_AGE: u64 = 15_552_000;
+
+/// Origin sent by the active audit to see whether CORS reflects it.
+const PROBE_ORIGIN: &str = "https://apisec-origin-probe.example";
+
+/// Rule ids this module reports.
+pub const RULE_IDS: &[&str] = &[
+    "CONF-MISSING-HSTS",
+    "CONF-WEAK-HSTS",
+    "CONF-MISSING-CSP",
+    "CONF-WEAK-CSP",
+    "CONF-MISSING-XCTO",
+    "CONF-MISSING-FRAME-OPTIONS",
+    "CONF-CACHEABLE-RESPONSE",
+    "CONF-CORS-ALL",
+    "CONF-CORS-REFLECT",
+];
+
+/// What the evaluated response was an answer to.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub https: bool,
+    /// The request carried credentials (Authorization or Cookie)
+    pub authenticated: bool,
+    /// Origin header sent with the request
+    pub origin: Option<String>,
+}
+
+/// Header names lowercased; repeated headers joined with `, `.
+pub fn normalize<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> HashMap<String, String> {
+    let mut out: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let value = value.trim();
+        out.entry(name.trim().to_lowercase())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    out
+}
+
+/// Headers following the first `HTTP/x.y` status line, up to the first blank line.
+pub fn parse_header_block(content: &str) -> Option<HashMap<String, String>> {
+    let mut lines = content.lines().skip_while(|l| !l.trim_start().starts_with("HTTP/"));
+    lines.next()?;
+    let pairs: Vec<(&str, &str)> = lines.take_while(|l| !l.trim().is_empty()).filter_map(|l| l.split_once(':')).collect();
+    Some(normalize(pairs))
+}
+
+/// CSP directives by lowercased name.
+fn csp_directives(csp: &str) -> HashMap<String, String> {
+    csp.split(';')
+        .filter_map(|d| {
+            let d = d.trim();
+            let (name, value) = d.split_once(char::is_whitespace).unwrap_or((d, ""));
+            (!name.is_empty()).then(|| (name.to_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn check_hsts(headers: &HashMap<String, String>, ctx: &Context, out: &mut Vec<Finding>) {
+    if !ctx.https {
+        return;
+    }
+    let Some(hsts) = headers.get("strict-transport-security") else {
+        out.push(Finding::new(
+            "CONF-MISSING-HSTS",
+            "Missing HSTS Header",
+            FindingSeverity::Low,
+            "Strict-Transport-Security header is missing. Sensitive data may be sent over HTTP.".to_string(),
+            "Strict-Transport-Security".to_string(),
+        ));
+        return;
+    };
+    let lower = hsts.to_lowercase();
+    let max_age = lower
+        .split(';')
+        .filter_map(|d| d.trim().strip_prefix("max-age="))
+        .find_map(|v| v.trim_matches('"').parse::<u64>().ok());
+    let mut problems = Vec::new();
+    match max_age {
+        None => problems.push("no valid max-age".to_string()),
+        Some(0) => problems.push("max-age=0 clears the policy".to_string()),
+        Some(age) if age < MIN_HSTS_MAX_AGE => problems.push(format!("max-age={} is under 180 days", age)),
+        Some(_) => {}
+    }
+    if !lower.contains("includesubdomains") {
+        problems.push("subdomains are not covered".to_string());
+    }
+    if !problems.is_empty() {
+        out.push(Finding::new(
+            "CONF-WEAK-HSTS",
+            "Weak HSTS Policy",
+            FindingSeverity::Low,
+            format!("Strict-Transport-Security is set but weak: {}.", problems.join("; ")),
+            format!("Strict-Transport-Security: {}", hsts),
+        ));
+    }
+}
+
+fn check_csp(headers: &HashMap<String, String>, out: &mut Vec<Finding>) {
+    let Some(csp) = headers.get("content-security-policy") else {
+        out.push(Finding::new(
+            "CONF-MISSING-CSP",
+            "Missing CSP Header",
+            FindingSeverity::Low,
+            "Content-Security-Policy header is missing. Risk of XSS and data injection.".to_string(),
+            "Content-Security-Policy".to_string(),
+        ));
+        return;
+    };
+    let directives = csp_directives(csp);
+    let scripts = directives.get("script-src").or_else(|| directives.get("default-src"));
+    let mut problems = Vec::new();
+    match scripts {
+        None => problems.push("neither script-src nor default-src is set".to_string()),
+        Some(sources) => {
+            let sources = sources.to_lowercase();
+            for (token, why) in [
+                ("'unsafe-inline'", "'unsafe-inline' allows inline scripts"),
+                ("'unsafe-eval'", "'unsafe-eval' allows eval"),
+            ] {
+                if sources.contains(token) {
+                    problems.push(why.to_string());
+                }
+            }
+            if sources.split_whitespace().any(|s| s == "*" || s == "http:" || s == "https:" || s == "data:") {
+                problems.push("scripts may load from any host".to_string());
+            }
+        }
+    }
+    if !problems.is_empty() {
+        out.push(Finding::new(
+            "CONF-WEAK-CSP",
+            "Weak CSP Policy",
+            FindingSeverity::Low,
+            format!("Content-Security-Policy does not restrict scripts: {}.", problems.join("; ")),
+            format!("Content-Security-Policy: {}", csp),
+        ));
+    }
+}
+
+fn check_content_type_options(headers: &HashMap<String, String>, out: &mut Vec<Finding>) {
+    let value = headers.get("x-content-type-options");
+    if value.is_some_and(|v| v.eq_ignore_ascii_case("nosniff")) {
+        return;
+    }
+    let description = match value {
+        Some(v) => format!("X-Content-Type-Options is `{}` instead of `nosniff`; browsers may sniff responses into executable types.", v),
+        None => "X-Content-Type-Options header is missing; browsers may sniff responses into executable types.".to_string(),
+    };
+    out.push(Finding::new("CONF-MISSING-XCTO", "Missing X-Content-Type-Options", FindingSeverity::Low, description, "X-Content-Type-Options".to_string()));
+}
+
+fn check_framing(headers: &HashMap<String, String>, out: &mut Vec<Finding>) {
+    let xfo = headers.get("x-frame-options").map(|v| v.to_lowercase());
+    if xfo.as_deref().is_some_and(|v| v == "deny" || v == "sameorigin") {
+        return;
+    }
+    let frame_ancestors = headers.get("content-security-policy").and_then(|csp| csp_directives(csp).remove("frame-ancestors"));
+    if frame_ancestors.is_some_and(|v| !v.split_whitespace().any(|s| s == "*")) {
+        return;
+    }
+    let description = match xfo {
+        Some(v) => format!("X-Frame-Options is `{}`, which browsers ignore, and CSP frame-ancestors doesn't restrict framing; the page can be clickjacked.", v),
+        None => "Neither X-Frame-Options nor CSP frame-ancestors restricts framing; the page can be clickjacked.".to_string(),
+    };
+    out.push(Finding::new("CONF-MISSING-FRAME-OPTIONS", "Missing Frame Options", FindingSeverity::Low, description, "X-Frame-Options".to_string()));
+}
+
+fn check_cache_control(headers: &HashMap<String, String>, ctx: &Context, out: &mut Vec<Finding>) {
+    let sets_cookie = headers.contains_key("set-cookie");
+    if !ctx.authenticated && !sets_cookie {
+        return;
+    }
+    let cache_control = headers.get("cache-control").map(|v| v.to_lowercase()).unwrap_or_default();
+    let directives: Vec<&str> = cache_control.split(',').map(str::trim).collect();
+    let public = directives.iter().any(|d| *d == "public" || d.starts_with("s-maxage"));
+    if !public && directives.iter().any(|d| *d == "no-store" || *d == "private") {
+        return;
+    }
+    let why = if ctx.authenticated { "an authenticated response" } else { "a response that sets cookies" };
+    let description = if cache_control.is_empty() {
+        format!("Cache-Control is missing on {}; shared caches and proxies may store it.", why)
+    } else {
+        format!("Cache-Control `{}` lets shared caches store {}.", cache_control, why)
+    };
+    out.push(Finding::new("CONF-CACHEABLE-RESPONSE", "Cacheable Sensitive Response", FindingSeverity::Low, description, "Cache-Control".to_string()));
+}
+
+fn check_cors(headers: &HashMap<String, String>, ctx: &Context, out: &mut Vec<Finding>) {
+    let Some(allow_origin) = headers.get("access-control-allow-origin") else {
+        return;
+    };
+    let credentials = headers.get("access-control-allow-credentials").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    let header = format!("Access-Control-Allow-Origin: {}", allow_origin);
+    if allow_origin == "*" {
+        out.push(Finding::new(
+            "CONF-CORS-ALL",
+            "Permissive CORS Policy",
+            FindingSeverity::Medium,
+            "Access-Control-Allow-Origin is set to *. This allows any domain to access the resource.".to_string(),
+            header,
+        ));
+    } else if allow_origin.eq_ignore_ascii_case("null") || ctx.origin.as_deref() == Some(allow_origin.as_str()) {
+        let (severity, with) = if credentials {
+            (FindingSeverity::High, " together with Access-Control-Allow-Credentials: true, so any site can read it with the user's cookies")
+        } else {
+            (FindingSeverity::Medium, "")
+        };
+        let what = if allow_origin.eq_ignore_ascii_case("null") { "the `null` origin (sandboxed frames, local files)" } else { "an arbitrary requesting origin" };
+        out.push(Finding::new(
+            "CONF-CORS-REFLECT",
+            "CORS Origin Reflection",
+            severity,
+            format!("The response trusts {}{}.", what, with),
+            header,
+        ));
+    }
+}
+
+/// Evaluates the whole response-header policy, one finding per header at fault.
+pub fn evaluate(headers: &HashMap<String, String>, ctx: &Context) -> Vec<Finding> {
+    let mut out = Vec::new();
+    check_hsts(headers, ctx, &mut out);
+    check_csp(headers, &mut out);
+    check_content_type_options(headers, &mut out);
+    check_framing(headers, &mut out);
+    check_cache_control(headers, ctx, &mut out);
+    check_cors(headers, ctx, &mut out);
+    crate::analysis::attach_rule_metadata(&mut out);
+    out
+}
+
+/// Passive check of a raw response header block. The scheme is unknown,
+/// so HTTPS is assumed; wildcard CORS is left to the misconfiguration scan.
+pub fn scan_header_block(content: &str) -> Vec<Finding> {
+    let Some(headers) = parse_header_block(content) else {
+        return Vec::new();
+    };
+    let mut findings = evaluate(&headers, &Context { https: true, ..Default::default() });
+    findings.retain(|f| f.rule_id != "CONF-CORS-ALL");
+    findings
+}
+
+/// Fetches the asset with its captured request headers and a foreign
+/// Origin, evaluates the response headers, and stores a finding per header
+/// at fault. Earlier header findings that no longer apply are removed
+/// unless they were annotated.
+#[tauri::command]
+pub async fn audit_security_headers(asset_id: i64, pacing: Option<Pacing>, scan_profile_id: Option<i64>) -> Result<Vec<Finding>, String> {
+    let profile = crate::scan_profiles::authorize(scan_profile_id, ActiveCheck::SecurityHeaders).await?;
+    audit_asset(asset_id, &Pacer::new(&profile.cap_pacing(&pacing.unwrap_or_default()))).await
+}
+
+pub(crate) async fn audit_asset(asset_id: i64, pacer: &Pacer) -> Result<Vec<Finding>, String> {
+    let pool = get_db();
+    let asset: Option<(String, Option<String>)> = sqlx::query_as("SELECT url, req_headers FROM assets WHERE id = ? AND deleted_at IS NULL")
+        .bind(asset_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (url, captured) = asset.ok_or("Asset not found")?;
+    let captured = crate::assets::captured_headers(captured);
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut req = client.get(&url);
+    for (k, v) in crate::assets::replay_headers(&captured, false) {
+        if !k.eq_ignore_ascii_case("origin") {
+            req = req.header(k, v);
+        }
+    }
+    pacer.wait().await;
+    let res = req.header("Origin", PROBE_ORIGIN).send().await.map_err(|e| e.to_string())?;
+    let headers = normalize(res.headers().iter().filter_map(|(k, v)| Some((k.as_str(), v.to_str().ok()?))));
+
+    let ctx = Context {
+        https: url.to_lowercase().starts_with("https://"),
+        authenticated: captured.keys().any(|k| k.eq_ignore_ascii_case("authorization") || k.eq_ignore_ascii_case("cookie")),
+        origin: Some(PROBE_ORIGIN.to_string()),
+    };
+    let findings = evaluate(&headers, &ctx);
+
+    for rule_id in RULE_IDS {
+        if !findings.iter().any(|f| f.rule_id == *rule_id) {
+            sqlx::query("DELETE FROM findings WHERE asset_id = ? AND rule_id = ? AND is_false_positive = 0 AND notes IS NULL")
+                .bind(asset_id)
+                .bind(rule_id)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    for f in &findings {
+        crate::assets::store_finding(&pool, asset_id, f).await?;
+    }
+    crate::risk::refresh_risk_scores(&pool, Some(asset_id)).await?;
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|f| f.rule_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let https = Context { https: true, ..Default::default() };
+        let bare = evaluate(&HashMap::new(), &https);
+        assert_eq!(rules(&bare), ["CONF-MISSING-HSTS", "CONF-MISSING-CSP", "CONF-MISSING-XCTO", "CONF-MISSING-FRAME-OPTIONS"]);
+        assert!(bare.iter().all(|f| f.remediation.is_some()));
+        assert!(!rules(&evaluate(&HashMap::new(), &Context::default())).contains(&"CONF-MISSING-HSTS"));
+
+        let strict = normalize([
+            ("Strict-Transport-Security", "max-age=63072000; includeSubDomains; preload"),
+            ("Content-Security-Policy", "default-src 'none'; frame-ancestors 'none'"),
+            ("X-Content-Type-Options", "nosniff"),
+            ("Cache-Control", "no-store"),
+        ]);
+        let authed = Context { https: true, authenticated: true, origin: Some(PROBE_ORIGIN.to_string()) };
+        assert!(evaluate(&strict, &authed).is_empty());
+
+        let weak = normalize([
+            ("strict-transport-security", "max-age=300"),
+            ("content-security-policy", "script-src 'self' 'unsafe-inline'"),
+            ("x-frame-options", "ALLOW-FROM https://a.example"),
+            ("cache-control", "public, max-age=600"),
+            ("access-control-allow-origin", PROBE_ORIGIN),
+            ("access-control-allow-credentials", "true"),
+        ]);
+        let found = evaluate(&weak, &authed);
+        assert_eq!(
+            rules(&found),
+            ["CONF-WEAK-HSTS", "CONF-WEAK-CSP", "CONF-MISSING-XCTO", "CONF-MISSING-FRAME-OPTIONS", "CONF-CACHEABLE-RESPONSE", "CONF-CORS-REFLECT"]
+        );
+        assert!(found[0].description.contains("under 180 days") && found[0].description.contains("subdomains"));
+        assert_eq!(found[5].severity, FindingSeverity::High);
+
+        let wildcard = normalize([("Access-Control-Allow-Origin", "*"), ("Set-Cookie", "a=1"), ("Set-Cookie", "b=2")]);
+        let found = evaluate(&wildcard, &Context::default());
+        assert!(rules(&found).contains(&"CONF-CORS-ALL") && rules(&found).contains(&"CONF-CACHEABLE-RESPONSE"));
+        assert_eq!(wildcard["set-cookie"], "a=1, b=2");
+    }
+
+    #[test]
+    fn test_scan_header_block() {
+        let block = "HTTP/1.1 200 OK\r\nStrict-Transport-Security: max-age=31536000; includeSubDomains\r\nAccess-Control-Allow-Origin: *\r\n\r\nContent-Security-Policy: in the body";
+        let found = scan_header_block(block);
+        assert_eq!(rules(&found), ["CONF-MISSING-CSP", "CONF-MISSING-XCTO", "CONF-MISSING-FRAME-OPTIONS"]);
+        assert!(scan_header_block("no status line here").is_empty());
+    }
+}
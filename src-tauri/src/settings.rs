@@ -0,0 +1,382 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::db::get_db;
+use sqlx::{Pool, Sqlite};
+
+/// Shape of a setting's value, sent to the frontend so it can render an editor.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SettingKind {
+    Bool,
+    Integer { min: i64, max: i64 },
+    Text,
+    /// An http(s) URL, or an empty string when unset
+    Url,
+    Choice { options: Vec<String> },
+    /// A structured value owned by one feature, e.g. retention limits
+    Object,
+}
+
+/// Validates a stored value before it is saved.
+type Check = fn(&Value) -> Result<(), String>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SettingDef {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub kind: SettingKind,
+    pub default: Value,
+    /// Extra validation for `Object` settings, normally a typed parse
+    #[serde(skip)]
+    check: Option<Check>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SettingEntry {
+    #[serde(flatten)]
+    pub def: SettingDef,
+    pub value: Value,
+    /// False while the setting still holds its default
+    pub is_set: bool,
+}
+
+fn parses_as<T: DeserializeOwned>(value: &Value) -> Result<(), String> {
+    serde_json::from_value::<T>(value.clone()).map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn check_backup(value: &Value) -> Result<(), String> {
+    let settings: crate::backups::BackupSettings = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    if settings.keep < 1 {
+        return Err("At least one backup must be kept".to_string());
+    }
+    Ok(())
+}
+
+fn check_oob(value: &Value) -> Result<(), String> {
+    let settings: crate::oob::OobSettings = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    if settings.http_port == 0 {
+        return Err("HTTP port must not be 0".to_string());
+    }
+    if settings.dns_port != 0 && settings.domain.trim_matches('.').is_empty() {
+        return Err("The DNS listener needs a domain".to_string());
+    }
+    Ok(())
+}
+
+fn check_path_templates(value: &Value) -> Result<(), String> {
+    let rules: crate::templates::TemplateRules = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    crate::templates::Templater::new(rules).map(|_| ())
+}
+
+fn check_certificates(value: &Value) -> Result<(), String> {
+    let settings: crate::certs::CertificateSettings = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    if settings.ca_valid_days < 1 || settings.leaf_valid_days < 1 {
+        return Err("Validity must be at least one day".to_string());
+    }
+    if settings.leaf_valid_days > crate::certs::MAX_LEAF_VALID_DAYS {
+        return Err(format!("Leaf certificates may be valid for at most {} days", crate::certs::MAX_LEAF_VALID_DAYS));
+    }
+    if settings.ca_valid_days > crate::certs::MAX_VALID_DAYS {
+        return Err(format!("The CA may be valid for at most {} days", crate::certs::MAX_VALID_DAYS));
+    }
+    if settings.expiry_warning_days < 0 {
+        return Err("The warning period can't be negative".to_string());
+    }
+    if settings.expiry_warning_days > crate::certs::MAX_VALID_DAYS {
+        return Err(format!("The warning period may be at most {} days", crate::certs::MAX_VALID_DAYS));
+    }
+    Ok(())
+}
+
+fn check_cloud_ranges(value: &Value) -> Result<(), String> {
+    let settings: crate::enrich::CloudRangeSettings = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    for range in &settings.ranges {
+        if crate::enrich::parse_cidr(&range.cidr).is_none() {
+            return Err(format!("{} is not a CIDR range", range.cidr));
+        }
+        if range.provider.trim().is_empty() {
+            return Err(format!("{} needs a provider name", range.cidr));
+        }
+    }
+    Ok(())
+}
+
+/// Every setting the app knows about. Keys outside this list are rejected.
+pub fn registry() -> Vec<SettingDef> {
+    vec![
+        SettingDef {
+            key: "proxy_port",
+            description: "Port the intercepting proxy listens on",
+            kind: SettingKind::Integer { min: 1, max: 65535 },
+            default: Value::from(8080),
+            check: None,
+        },
+        SettingDef {
+            key: "import_workers",
+            description: "Import entries ingested concurrently",
+            kind: SettingKind::Integer { min: 1, max: 64 },
+            default: Value::from(8),
+            check: None,
+        },
+        SettingDef {
+            key: "search_limit",
+            description: "Maximum hits per section returned by global search",
+            kind: SettingKind::Integer { min: 1, max: 1000 },
+            default: Value::from(100),
+            check: None,
+        },
+        SettingDef {
+            key: "ai_endpoint",
+            description: "Base URL of the local LLM server (Ollama API)",
+            kind: SettingKind::Url,
+            default: Value::from("http://localhost:11434"),
+            check: None,
+        },
+        SettingDef {
+            key: "ai_model",
+            description: "Default model for AI features",
+            kind: SettingKind::Text,
+            default: Value::from("llama3.2:latest"),
+            check: None,
+        },
+        SettingDef {
+            key: "ai_triage_model",
+            description: "Model used for AI triage; empty uses ai_model",
+            kind: SettingKind::Text,
+            default: Value::from(""),
+            check: None,
+        },
+        SettingDef {
+            key: "ai_report_model",
+            description: "Model used for AI report generation; empty uses ai_model",
+            kind: SettingKind::Text,
+            default: Value::from(""),
+            check: None,
+        },
+        SettingDef {
+            key: "ai_embedding_model",
+            description: "Embedding model used to cluster similar findings",
+            kind: SettingKind::Text,
+            default: Value::from("nomic-embed-text"),
+            check: None,
+        },
+        SettingDef {
+            key: "webhook",
+            description: "Slack-compatible webhook for notifications",
+            kind: SettingKind::Url,
+            default: Value::from(""),
+            check: None,
+        },
+        SettingDef {
+            key: "upstream_proxy",
+            description: "Proxy replayed requests are routed through, e.g. a corporate gateway",
+            kind: SettingKind::Url,
+            default: Value::from(""),
+            check: None,
+        },
+        SettingDef {
+            key: "asset_identity_query",
+            description: "How query strings count towards asset identity",
+            kind: SettingKind::Choice {
+                options: vec!["parameterize".to_string(), "strip".to_string(), "keep".to_string()],
+            },
+            default: Value::from("parameterize"),
+            check: None,
+        },
+        SettingDef {
+            key: "variable_substitution",
+            description: "Whether requests with unresolved {{variable}} placeholders are sent as-is or refused",
+            kind: SettingKind::Choice {
+                options: vec!["lenient".to_string(), "strict".to_string()],
+            },
+            default: Value::from("lenient"),
+            check: None,
+        },
+        SettingDef {
+            key: "path_templates",
+            description: "Which path segments are collapsed into {id} when grouping the inventory",
+            kind: SettingKind::Object,
+            default: serde_json::to_value(crate::templates::TemplateRules::default()).unwrap(),
+            check: Some(check_path_templates),
+        },
+        SettingDef {
+            key: "trash_retention_days",
+            description: "Days trashed assets are kept before being deleted for good (0 keeps them)",
+            kind: SettingKind::Integer { min: 0, max: 3650 },
+            default: Value::from(30),
+            check: None,
+        },
+        SettingDef {
+            key: "retention",
+            description: "Limits applied by the retention purge",
+            kind: SettingKind::Object,
+            default: serde_json::to_value(crate::retention::RetentionSettings::default()).unwrap(),
+            check: Some(parses_as::<crate::retention::RetentionSettings>),
+        },
+        SettingDef {
+            key: "backup",
+            description: "Automatic snapshot schedule",
+            kind: SettingKind::Object,
+            default: serde_json::to_value(crate::backups::BackupSettings::default()).unwrap(),
+            check: Some(check_backup),
+        },
+        SettingDef {
+            key: "certificates",
+            description: "Validity of the interception CA and the certificates it signs",
+            kind: SettingKind::Object,
+            default: serde_json::to_value(crate::certs::CertificateSettings::default()).unwrap(),
+            check: Some(check_certificates),
+        },
+        SettingDef {
+            key: "oob",
+            description: "Out-of-band callback listener for blind SSRF, XXE and command injection",
+            kind: SettingKind::Object,
+            default: serde_json::to_value(crate::oob::OobSettings::default()).unwrap(),
+            check: Some(check_oob),
+        },
+        SettingDef {
+            key: "cloud_ranges",
+            description: "Extra address ranges attributed to a cloud or CDN provider when enriching IPs",
+            kind: SettingKind::Object,
+            default: serde_json::to_value(crate::enrich::CloudRangeSettings::default()).unwrap(),
+            check: Some(check_cloud_ranges),
+        },
+    ]
+}
+
+fn lookup(key: &str) -> Result<SettingDef, String> {
+    registry()
+        .into_iter()
+        .find(|d| d.key == key)
+        .ok_or_else(|| format!("Unknown setting '{}'", key))
+}
+
+fn validate(def: &SettingDef, value: &Value) -> Result<(), String> {
+    let ok = match &def.kind {
+        SettingKind::Bool => value.is_boolean(),
+        SettingKind::Integer { min, max } => match value.as_i64() {
+            Some(n) if n < *min || n > *max => {
+                return Err(format!("{} must be between {} and {}", def.key, min, max));
+            }
+            Some(_) => true,
+            None => false,
+        },
+        SettingKind::Text => value.is_string(),
+        SettingKind::Url => match value.as_str() {
+            Some("") => true,
+            Some(s) => match url::Url::parse(s) {
+                Ok(u) if u.scheme() == "http" || u.scheme() == "https" => true,
+                _ => return Err(format!("{} must be an http(s) URL", def.key)),
+            },
+            None => false,
+        },
+        SettingKind::Choice { options } => match value.as_str() {
+            Some(s) if options.iter().any(|o| o == s) => true,
+            Some(_) => return Err(format!("{} must be one of: {}", def.key, options.join(", "))),
+            None => false,
+        },
+        SettingKind::Object => value.is_object(),
+    };
+    if !ok {
+        return Err(format!("Invalid value for {}", def.key));
+    }
+    match def.check {
+        Some(check) => check(value).map_err(|e| format!("Invalid value for {}: {}", def.key, e)),
+        None => Ok(()),
+    }
+}
+
+/// Stored value of a setting, or `None` when it still has its default.
+/// Values are kept as JSON; older rows holding a bare string are read as one.
+async fn stored(pool: &Pool<Sqlite>, key: &str) -> Result<Option<Value>, String> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(row.map(|(raw,)| serde_json::from_str(&raw).unwrap_or(Value::String(raw))))
+}
+
+/// Typed read of a registered setting, falling back to its default.
+pub async fn read<T: DeserializeOwned>(pool: &Pool<Sqlite>, key: &str) -> Result<T, String> {
+    let def = lookup(key)?;
+    let value = stored(pool, key).await?.unwrap_or(def.default);
+    serde_json::from_value(value).map_err(|e| format!("Invalid stored value for {}: {}", key, e))
+}
+
+/// `read` against the active workspace.
+pub async fn get<T: DeserializeOwned>(key: &str) -> Result<T, String> {
+    read(&get_db(), key).await
+}
+
+/// Validates and stores a setting. `null` resets it to its default.
+pub async fn write(pool: &Pool<Sqlite>, key: &str, value: Value) -> Result<(), String> {
+    let def = lookup(key)?;
+    if value.is_null() {
+        sqlx::query("DELETE FROM app_settings WHERE key = ?")
+            .bind(key)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+    validate(&def, &value)?;
+    sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+        .bind(key)
+        .bind(value.to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_setting(key: String) -> Result<Value, String> {
+    get(&key).await
+}
+
+/// Stores a setting. Settings that reshape existing data apply the change
+/// right away, as their dedicated commands do.
+#[tauri::command]
+pub async fn set_setting(
+    state: tauri::State<'_, std::sync::Arc<crate::ProxyState>>,
+    key: String,
+    value: Value,
+) -> Result<(), String> {
+    // Retention limits delete data on the next purge
+    let role = if key == "retention" { crate::users::Role::Admin } else { crate::users::Role::Analyst };
+    crate::users::require_role(role).await?;
+    let pool = get_db();
+    write(&pool, &key, value).await?;
+    match key.as_str() {
+        "asset_identity_query" => {
+            let mode = crate::assets::identity_query_mode(&pool).await?;
+            crate::assets::reindex_asset_identities(&pool, mode).await.map_err(|e| e.to_string())?;
+        }
+        "path_templates" => {
+            crate::templates::retemplate_assets(&pool, false).await?;
+        }
+        "certificates" => {
+            state.cert_manager.configure(read(&pool, &key).await?).await;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Every registered setting with its schema and current value.
+#[tauri::command]
+pub async fn get_all_settings() -> Result<Vec<SettingEntry>, String> {
+    let pool = get_db();
+    let mut entries = Vec::new();
+    for def in registry() {
+        let current = stored(&pool, def.key).await?;
+        entries.push(SettingEntry {
+            is_set: current.is_some(),
+            value: current.unwrap_or_else(|| def.default.clone()),
+            def,
+        });
+    }
+    Ok(entries)
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SiteNodeKind {
+    Host,
+    Path,
+    Method,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SiteNode {
+    pub name: String,
+    pub kind: SiteNodeKind,
+    /// Full URL (or template) up to this node
+    pub url: String,
+    /// Set on method leaves only
+    pub asset_id: Option<i64>,
+    /// Open findings at or below this node
+    pub findings_count: i64,
+    /// Highest effective severity at or below this node
+    pub highest_severity: Option<String>,
+    pub children: Vec<SiteNode>,
+}
+
+/// One asset as fed into the tree.
+pub struct SiteEntry {
+    pub asset_id: i64,
+    pub url: String,
+    pub method: String,
+    pub findings_count: i64,
+    /// 0 = Info .. 3 = High, `None` without open findings
+    pub severity_rank: Option<i64>,
+}
+
+const SEVERITIES: [&str; 4] = ["Info", "Low", "Medium", "High"];
+
+#[derive(Default)]
+struct Branch {
+    url: String,
+    asset_id: Option<i64>,
+    findings_count: i64,
+    severity_rank: Option<i64>,
+    children: BTreeMap<(u8, String), Branch>,
+}
+
+impl Branch {
+    fn child(&mut self, order: u8, name: &str, url: String) -> &mut Branch {
+        self.children
+            .entry((order, name.to_string()))
+            .or_insert_with(|| Branch { url, ..Default::default() })
+    }
+
+    fn add(&mut self, entry: &SiteEntry) {
+        self.findings_count += entry.findings_count;
+        self.severity_rank = self.severity_rank.max(entry.severity_rank);
+    }
+
+    fn into_node(self, name: String, kind: SiteNodeKind) -> SiteNode {
+        SiteNode {
+            name,
+            kind,
+            url: self.url,
+            asset_id: self.asset_id,
+            findings_count: self.findings_count,
+            highest_severity: self.severity_rank.and_then(|r| SEVERITIES.get(r as usize)).map(|s| s.to_string()),
+            children: self
+                .children
+                .into_iter()
+                .map(|((order, name), b)| b.into_node(name, if order == 0 { SiteNodeKind::Method } else { SiteNodeKind::Path }))
+                .collect(),
+        }
+    }
+}
+
+/// Builds the host → path segment → method tree. Methods sort before
+/// subpaths at each level so an endpoint's own operations come first.
+pub fn build_site_map(entries: &[SiteEntry]) -> Vec<SiteNode> {
+    let mut hosts: BTreeMap<String, Branch> = BTreeMap::new();
+    for entry in entries {
+        let (origin, path) = match url::Url::parse(&entry.url) {
+            Ok(u) => (u[..url::Position::BeforePath].to_string(), u.path().to_string()),
+            Err(_) => (entry.url.clone(), String::new()),
+        };
+        let mut node = hosts.entry(origin.clone()).or_insert_with(|| Branch { url: origin.clone(), ..Default::default() });
+        node.add(entry);
+        let mut url = origin;
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            url = format!("{}/{}", url, segment);
+            node = node.child(1, segment, url.clone());
+            node.add(entry);
+        }
+        let leaf = node.child(0, &entry.method, url);
+        leaf.asset_id = Some(entry.asset_id);
+        leaf.add(entry);
+    }
+    hosts.into_iter().map(|(name, b)| b.into_node(name, SiteNodeKind::Host)).collect()
+}
+
+/// Burp-style target tree of the live inventory. With `templated`, URLs
+/// are grouped by path template so `/users/1` and `/users/2` share a node.
+#[tauri::command]
+pub async fn get_site_map(templated: Option<bool>) -> Result<Vec<SiteNode>, String> {
+    let pool = get_db();
+    let rows: Vec<(i64, String, String, i64, Option<i64>)> = sqlx::query_as(
+        "SELECT a.id, \
+            CASE WHEN ? THEN COALESCE(a.path_template, a.url) ELSE a.url END, \
+            COALESCE(UPPER(a.method), 'GET'), \
+            COUNT(f.id), \
+            MAX(CASE COALESCE(f.severity_override, f.severity) WHEN 'High' THEN 3 WHEN 'Medium' THEN 2 WHEN 'Low' THEN 1 WHEN 'Info' THEN 0 END) \
+         FROM assets a \
+         LEFT JOIN findings f ON f.asset_id = a.id AND f.is_false_positive = 0 \
+         WHERE a.deleted_at IS NULL \
+         GROUP BY a.id"
+    )
+    .bind(templated.unwrap_or(false))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let entries: Vec<SiteEntry> = rows
+        .into_iter()
+        .map(|(asset_id, url, method, findings_count, severity_rank)| SiteEntry { asset_id, url, method, findings_count, severity_rank })
+        .collect();
+    Ok(build_site_map(&entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_site_map() {
+        let entry = |asset_id, url: &str, method: &str, findings_count, severity_rank| SiteEntry {
+            asset_id,
+            url: url.to_string(),
+            method: method.to_string(),
+            findings_count,
+            severity_rank,
+        };
+        let tree = build_site_map(&[
+            entry(1, "https://api.example.com/users", "GET", 1, Some(1)),
+            entry(2, "https://api.example.com/users/1", "GET", 2, Some(3)),
+            entry(3, "https://api.example.com/users", "POST", 0, None),
+            entry(4, "http://other.test/", "GET", 0, None),
+        ]);
+
+        // Hosts sort by origin, so http:// comes before https://
+        assert_eq!(tree.len(), 2);
+        let api = &tree[1];
+        assert_eq!(api.name, "https://api.example.com");
+        assert_eq!(api.findings_count, 3);
+        assert_eq!(api.highest_severity.as_deref(), Some("High"));
+
+        let users = &api.children[0];
+        assert_eq!(users.url, "https://api.example.com/users");
+        let names: Vec<&str> = users.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["GET", "POST", "1"]);
+        assert_eq!(users.children[0].asset_id, Some(1));
+        assert_eq!(users.children[2].kind, SiteNodeKind::Path);
+
+        let other = &tree[0];
+        assert_eq!(other.children[0].kind, SiteNodeKind::Method);
+        assert_eq!(other.highest_severity, None);
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use sqlx::{FromRow, Pool, Sqlite};
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct CountBucket {
+    pub key: String,
+    pub count: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct TrendBucket {
+    /// `YYYY-MM-DD`
+    pub day: String,
+    pub new_assets: i64,
+    pub new_findings: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct RiskyEndpoint {
+    pub asset_id: i64,
+    pub url: String,
+    pub method: Option<String>,
+    pub findings_count: i64,
+    pub risk_score: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WorkspaceStats {
+    pub total_assets: i64,
+    pub total_findings: i64,
+    pub assets_by_host: Vec<CountBucket>,
+    pub assets_by_method: Vec<CountBucket>,
+    pub assets_by_source: Vec<CountBucket>,
+    /// Uses the analyst's severity override where one is set
+    pub findings_by_severity: Vec<CountBucket>,
+    pub findings_by_rule: Vec<CountBucket>,
+    /// `open` and `false_positive`
+    pub findings_by_status: Vec<CountBucket>,
+    /// One bucket per day, oldest first, including days with no activity
+    pub trend: Vec<TrendBucket>,
+    pub riskiest_endpoints: Vec<RiskyEndpoint>,
+}
+
+/// Assets and findings outside the trash.
+const LIVE: &str = "WITH live_assets AS (SELECT * FROM assets WHERE deleted_at IS NULL), \
+    live_findings AS (SELECT f.* FROM findings f JOIN live_assets a ON a.id = f.asset_id)";
+
+async fn buckets(pool: &Pool<Sqlite>, query: &str, limit: i64) -> Result<Vec<CountBucket>, String> {
+    sqlx::query_as::<_, CountBucket>(&format!("{} {} ORDER BY count DESC, key LIMIT ?", LIVE, query))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Dashboard metrics aggregated in SQL. `days` sets the trend window
+/// (default 30) and `top` caps host, rule and riskiest-endpoint lists
+/// (default 10).
+#[tauri::command]
+pub async fn get_workspace_stats(days: Option<i64>, top: Option<i64>) -> Result<WorkspaceStats, String> {
+    let pool = get_db();
+    let days = days.unwrap_or(30).clamp(1, 365);
+    let top = top.unwrap_or(10).max(1);
+
+    let (total_assets, total_findings): (i64, i64) = sqlx::query_as(&format!(
+        "{} SELECT (SELECT COUNT(*) FROM live_assets), (SELECT COUNT(*) FROM live_findings)",
+        LIVE
+    ))
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Host is the part between `://` and the next `/`
+    let assets_by_host = buckets(
+        &pool,
+        ", stripped AS (SELECT CASE WHEN instr(url, '://') > 0 THEN substr(url, instr(url, '://') + 3) ELSE url END AS rest FROM live_assets) \
+         SELECT CASE WHEN instr(rest, '/') > 0 THEN substr(rest, 1, instr(rest, '/') - 1) ELSE rest END AS key, COUNT(*) AS count \
+         FROM stripped GROUP BY key",
+        top,
+    )
+    .await?;
+    let assets_by_method = buckets(&pool, "SELECT COALESCE(UPPER(method), 'GET') AS key, COUNT(*) AS count FROM live_assets GROUP BY key", i64::MAX).await?;
+    let assets_by_source = buckets(&pool, "SELECT COALESCE(source, 'unknown') AS key, COUNT(*) AS count FROM live_assets GROUP BY key", i64::MAX).await?;
+    let findings_by_severity = buckets(
+        &pool,
+        "SELECT COALESCE(severity_override, severity, 'Info') AS key, COUNT(*) AS count FROM live_findings WHERE is_false_positive = 0 GROUP BY key",
+        i64::MAX,
+    )
+    .await?;
+    let findings_by_rule = buckets(&pool, "SELECT COALESCE(rule_id, 'unknown') AS key, COUNT(*) AS count FROM live_findings WHERE is_false_positive = 0 GROUP BY key", top).await?;
+    let findings_by_status = buckets(
+        &pool,
+        "SELECT CASE WHEN is_false_positive = 1 THEN 'false_positive' ELSE 'open' END AS key, COUNT(*) AS count FROM live_findings GROUP BY key",
+        i64::MAX,
+    )
+    .await?;
+
+    let trend = sqlx::query_as::<_, TrendBucket>(&format!(
+        "{}, days(day) AS ( \
+            SELECT date('now', ?) UNION ALL SELECT date(day, '+1 day') FROM days WHERE day < date('now') \
+         ), \
+         new_assets AS (SELECT date(first_seen) AS day, COUNT(*) AS n FROM live_assets GROUP BY 1), \
+         new_findings AS (SELECT date(created_at) AS day, COUNT(*) AS n FROM live_findings GROUP BY 1) \
+         SELECT d.day, COALESCE(a.n, 0) AS new_assets, COALESCE(f.n, 0) AS new_findings \
+         FROM days d \
+         LEFT JOIN new_assets a ON a.day = d.day \
+         LEFT JOIN new_findings f ON f.day = d.day \
+         ORDER BY d.day",
+        LIVE.replacen("WITH", "WITH RECURSIVE", 1)
+    ))
+    .bind(format!("-{} days", days - 1))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let riskiest_endpoints = sqlx::query_as::<_, RiskyEndpoint>(&format!(
+        "{} SELECT a.id AS asset_id, a.url, a.method, COUNT(f.id) AS findings_count, a.risk_score \
+         FROM live_assets a \
+         LEFT JOIN live_findings f ON f.asset_id = a.id AND f.is_false_positive = 0 \
+         WHERE a.risk_score > 0 \
+         GROUP BY a.id \
+         ORDER BY a.risk_score DESC, findings_count DESC \
+         LIMIT ?",
+        LIVE
+    ))
+    .bind(top)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(WorkspaceStats {
+        total_assets,
+        total_findings,
+        assets_by_host,
+        assets_by_method,
+        assets_by_source,
+        findings_by_severity,
+        findings_by_rule,
+        findings_by_status,
+        trend,
+        riskiest_endpoints,
+    })
+}
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use regex::Regex;
+use sqlx::{FromRow, Pool, Sqlite};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Last templater built, reused for as long as the rules stay the same.
+static TEMPLATER: OnceLock<Mutex<Option<Arc<Templater>>>> = OnceLock::new();
+
+/// Which path segments count as identifiers when collapsing URLs into
+/// templates such as `/users/{id}/orders`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TemplateRules {
+    /// All-digit segments: `/users/123`
+    pub numeric: bool,
+    /// `8-4-4-4-12` hex UUIDs
+    pub uuid: bool,
+    /// Hex strings of 16+ characters: object ids, MD5/SHA digests
+    pub hex_hash: bool,
+    /// Letter-and-digit tokens of 20+ characters, e.g. base64url ids
+    pub opaque_token: bool,
+    /// Extra regexes; a segment matching any of them in full is an id
+    pub custom_patterns: Vec<String>,
+}
+
+impl Default for TemplateRules {
+    fn default() -> Self {
+        Self {
+            numeric: true,
+            uuid: true,
+            hex_hash: true,
+            opaque_token: false,
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+/// `TemplateRules` with the custom patterns compiled.
+pub struct Templater {
+    rules: TemplateRules,
+    custom: Vec<Regex>,
+}
+
+impl Templater {
+    pub fn new(rules: TemplateRules) -> Result<Self, String> {
+        let custom = rules
+            .custom_patterns
+            .iter()
+            .map(|p| Regex::new(&format!("^(?:{})$", p)).map_err(|e| format!("Invalid pattern '{}': {}", p, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { rules, custom })
+    }
+
+    fn is_identifier(&self, segment: &str) -> bool {
+        let hex = |s: &str| s.chars().all(|c| c.is_ascii_hexdigit());
+        let r = &self.rules;
+        (r.numeric && segment.chars().all(|c| c.is_ascii_digit()))
+            || (r.uuid && segment.len() == 36 && {
+                let parts: Vec<&str> = segment.split('-').collect();
+                parts.iter().map(|p| p.len()).eq([8, 4, 4, 4, 12]) && parts.iter().all(|p| hex(p))
+            })
+            || (r.hex_hash && segment.len() >= 16 && hex(segment) && segment.chars().any(|c| c.is_ascii_digit()))
+            || (r.opaque_token
+                && segment.len() >= 20
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                && segment.chars().any(|c| c.is_ascii_digit())
+                && segment.chars().any(|c| c.is_ascii_alphabetic()))
+            || self.custom.iter().any(|re| re.is_match(segment))
+    }
+
+    /// Scheme, host and path with identifier segments replaced by `{id}`.
+    /// Query and fragment are dropped. Unparseable URLs are returned as-is.
+    pub fn template(&self, url: &str) -> String {
+        let Ok(parsed) = url::Url::parse(url.trim()) else {
+            return url.trim().to_string();
+        };
+        let path: Vec<&str> = parsed
+            .path()
+            .split('/')
+            .map(|seg| if !seg.is_empty() && self.is_identifier(seg) { "{id}" } else { seg })
+            .collect();
+        let mut template = format!("{}{}", &parsed[..url::Position::BeforePath], path.join("/"));
+        if template.len() > 1 && template.ends_with('/') && parsed.path() != "/" {
+            template.pop();
+        }
+        template
+    }
+}
+
+/// The templater for the workspace's current rules. Custom patterns are
+/// only recompiled when the rules change.
+pub async fn templater(pool: &Pool<Sqlite>) -> Result<Arc<Templater>, String> {
+    let rules: TemplateRules = crate::settings::read(pool, "path_templates").await?;
+    let cache = TEMPLATER.get_or_init(|| Mutex::new(None));
+    if let Some(cached) = cache.lock().unwrap().as_ref().filter(|t| t.rules == rules) {
+        return Ok(cached.clone());
+    }
+    let built = Arc::new(Templater::new(rules)?);
+    *cache.lock().unwrap() = Some(built.clone());
+    Ok(built)
+}
+
+/// Recomputes `path_template` for every asset, or only for those that
+/// don't have one yet. Returns how many assets were updated.
+pub async fn retemplate_assets(pool: &Pool<Sqlite>, only_missing: bool) -> Result<u64, String> {
+    let templater = templater(pool).await?;
+    let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, url FROM assets WHERE ? = 0 OR path_template IS NULL")
+        .bind(only_missing)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (id, url) in &rows {
+        sqlx::query("UPDATE assets SET path_template = ? WHERE id = ?")
+            .bind(templater.template(url))
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(rows.len() as u64)
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct AssetGroup {
+    pub path_template: String,
+    #[sqlx(json)]
+    pub methods: Vec<String>,
+    #[sqlx(json)]
+    pub asset_ids: Vec<i64>,
+    pub asset_count: i64,
+    pub findings_count: i64,
+    pub last_seen: String,
+}
+
+/// The inventory collapsed by path template: one row per API operation
+/// shape instead of one per concrete URL.
+#[tauri::command]
+pub async fn get_grouped_assets() -> Result<Vec<AssetGroup>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, AssetGroup>(
+        "SELECT COALESCE(a.path_template, a.url) AS path_template, \
+         json_group_array(DISTINCT COALESCE(UPPER(a.method), 'GET')) AS methods, \
+         json_group_array(a.id) AS asset_ids, \
+         COUNT(*) AS asset_count, \
+         SUM((SELECT COUNT(*) FROM findings f WHERE f.asset_id = a.id)) AS findings_count, \
+         MAX(a.last_seen) AS last_seen \
+         FROM assets a \
+         WHERE a.deleted_at IS NULL \
+         GROUP BY 1 \
+         ORDER BY asset_count DESC, path_template"
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_template() {
+        let t = Templater::new(TemplateRules::default()).unwrap();
+        assert_eq!(t.template("https://api.example.com/users/123/orders?page=2"), "https://api.example.com/users/{id}/orders");
+        assert_eq!(
+            t.template("https://api.example.com/items/3f2504e0-4f89-11d3-9a0c-0305e82c3301"),
+            "https://api.example.com/items/{id}"
+        );
+        assert_eq!(t.template("https://api.example.com/blobs/5f1d7a2b9c8e4d3f2a1b0c9d/"), "https://api.example.com/blobs/{id}");
+        assert_eq!(t.template("https://api.example.com/v2/users/me"), "https://api.example.com/v2/users/me");
+        assert_eq!(t.template("https://api.example.com/"), "https://api.example.com/");
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let rules = TemplateRules { custom_patterns: vec!["[a-z]+-\\d+".to_string()], ..Default::default() };
+        let t = Templater::new(rules).unwrap();
+        assert_eq!(t.template("https://jira.example.com/browse/sec-42"), "https://jira.example.com/browse/{id}");
+        assert!(Templater::new(TemplateRules { custom_patterns: vec!["(".to_string()], ..Default::default() }).is_err());
+    }
+}
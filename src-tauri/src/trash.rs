@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use crate::assets::Asset;
+use crate::db::get_db;
+use sqlx::{FromRow, Pool, Sqlite};
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct TrashedAsset {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub asset: Asset,
+    pub deleted_at: String,
+}
+
+/// Assets in the trash, most recently deleted first.
+#[tauri::command]
+pub async fn list_trash() -> Result<Vec<TrashedAsset>, String> {
+    let pool = get_db();
+    sqlx::query_as::<_, TrashedAsset>(
+        "SELECT a.id, a.url, a.method, a.status_code, a.source, a.folder_id, a.last_seen, a.req_body, a.res_body, a.req_headers, a.res_headers, a.notes, a.deleted_at, \
+         (SELECT COUNT(*) FROM findings f WHERE f.asset_id = a.id) as findings_count \
+         FROM assets a \
+         WHERE a.deleted_at IS NOT NULL \
+         ORDER BY a.deleted_at DESC, a.id DESC"
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Takes assets back out of the trash. Returns how many were restored.
+#[tauri::command]
+pub async fn restore_assets(ids: Vec<i64>) -> Result<u64, String> {
+    crate::users::require_role(crate::users::Role::Analyst).await?;
+    let pool = get_db();
+    let json = serde_json::to_string(&ids).map_err(|e| e.to_string())?;
+    let res = sqlx::query("UPDATE assets SET deleted_at = NULL WHERE deleted_at IS NOT NULL AND id IN (SELECT value FROM json_each(?))")
+        .bind(json)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::audit::record("restore_assets", "asset", None, Some(format!("{} restored", res.rows_affected()))).await;
+    Ok(res.rows_affected())
+}
+
+#[tauri::command]
+pub async fn restore_asset(id: i64) -> Result<(), String> {
+    if restore_assets(vec![id]).await? == 0 {
+        return Err(format!("Asset {} is not in the trash", id));
+    }
+    Ok(())
+}
+
+/// Permanently deletes trashed assets, or only those trashed more than
+/// `older_than_days` ago. Findings, history and tags cascade.
+async fn purge_trash(pool: &Pool<Sqlite>, older_than_days: Option<i64>) -> Result<u64, String> {
+    let res = sqlx::query("DELETE FROM assets WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < datetime('now', '-' || ?1 || ' days'))")
+        .bind(older_than_days)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(res.rows_affected())
+}
+
+#[tauri::command]
+pub async fn empty_trash() -> Result<u64, String> {
+    crate::users::require_role(crate::users::Role::Admin).await?;
+    let deleted = purge_trash(&get_db(), None).await?;
+    crate::audit::record("empty_trash", "asset", None, Some(format!("{} deleted", deleted))).await;
+    Ok(deleted)
+}
+
+/// Drops trash older than the `trash_retention_days` setting; 0 keeps it forever.
+pub async fn purge_expired_trash(pool: &Pool<Sqlite>) -> Result<u64, String> {
+    let days: i64 = crate::settings::read(pool, "trash_retention_days").await?;
+    if days == 0 {
+        return Ok(0);
+    }
+    purge_trash(pool, Some(days)).await
+}
@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use crate::db::get_db;
+use base64::Engine;
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use sqlx::FromRow;
+use std::num::NonZeroU32;
+use std::sync::{OnceLock, RwLock};
+
+static CURRENT_USER: OnceLock<RwLock<Option<i64>>> = OnceLock::new();
+
+fn current_user_lock() -> &'static RwLock<Option<i64>> {
+    CURRENT_USER.get_or_init(|| RwLock::new(None))
+}
+
+/// Id of the user acting in this session, attached to audit log entries.
+pub fn current_user_id() -> Option<i64> {
+    *current_user_lock().read().unwrap()
+}
+
+/// Forgets the acting user; users belong to a workspace, so this runs on switch.
+pub fn clear_current_user() {
+    *current_user_lock().write().unwrap() = None;
+}
+
+/// Ordered from least to most privileged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Viewer,
+    Analyst,
+    Admin,
+}
+
+impl Role {
+    fn parse(role: &str) -> Result<Role, String> {
+        match role {
+            "Viewer" => Ok(Role::Viewer),
+            "Analyst" => Ok(Role::Analyst),
+            "Admin" => Ok(Role::Admin),
+            other => Err(format!("Unknown role '{}'", other)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "Viewer",
+            Role::Analyst => "Analyst",
+            Role::Admin => "Admin",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, FromRow)]
+pub struct User {
+    pub id: i64,
+    pub email: String,
+    pub name: String,
+    pub role: String,
+    pub created_at: Option<String>,
+    pub last_login: Option<String>,
+}
+
+const PASSWORD_ITERATIONS: u32 = 100_000;
+const MIN_PASSWORD_LEN: usize = 8;
+
+/// Stored as `pbkdf2-sha256$<iterations>$<salt>$<hash>` with base64 fields.
+fn hash_password(password: &str) -> Result<String, String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!("Passwords must be at least {} characters", MIN_PASSWORD_LEN));
+    }
+    let mut salt = [0u8; 16];
+    SystemRandom::new().fill(&mut salt).map_err(|_| "Could not generate a salt".to_string())?;
+    let mut hash = [0u8; 32];
+    let iterations = NonZeroU32::new(PASSWORD_ITERATIONS).unwrap();
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, password.as_bytes(), &mut hash);
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(format!("pbkdf2-sha256${}${}${}", PASSWORD_ITERATIONS, b64.encode(salt), b64.encode(hash)))
+}
+
+fn verify_password(password: &str, stored: &str) -> bool {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let mut parts = stored.split('$');
+    let (Some("pbkdf2-sha256"), Some(iterations), Some(salt), Some(hash), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Some(iterations), Ok(salt), Ok(hash)) = (
+        iterations.parse().ok().and_then(NonZeroU32::new),
+        b64.decode(salt),
+        b64.decode(hash),
+    ) else {
+        return false;
+    };
+    pbkdf2::verify(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, password.as_bytes(), &hash).is_ok()
+}
+
+async fn user_count() -> Result<i64, String> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
+        .fetch_one(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Fails unless the current user holds at least `role`. Workspaces without
+/// any users are single-user and allow everything; once users exist, someone
+/// must be selected with `set_current_user`.
+pub async fn require_role(role: Role) -> Result<(), String> {
+    if user_count().await? == 0 {
+        return Ok(());
+    }
+    let Some(id) = current_user_id() else {
+        return Err("No user selected; choose a user before making changes".to_string());
+    };
+    let row: Option<(String,)> = sqlx::query_as("SELECT role FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some((current,)) = row else {
+        return Err(format!("User {} not found", id));
+    };
+    if Role::parse(&current)? < role {
+        return Err(format!("This action requires the {} role", role.as_str()));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_users() -> Result<Vec<User>, String> {
+    sqlx::query_as::<_, User>("SELECT id, email, name, role, created_at, last_login FROM users ORDER BY name")
+        .fetch_all(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Creates a user with the password they sign in with. The first user of a
+/// workspace is always an Admin so the workspace can't be locked out of its
+/// own user management.
+#[tauri::command]
+pub async fn create_user(email: String, name: String, role: Role, password: String) -> Result<i64, String> {
+    let role = if user_count().await? == 0 {
+        Role::Admin
+    } else {
+        require_role(Role::Admin).await?;
+        role
+    };
+    let (email, name) = (email.trim(), name.trim());
+    if email.is_empty() || name.is_empty() {
+        return Err("Email and name must not be empty".to_string());
+    }
+    let password_hash = hash_password(&password)?;
+    let res = sqlx::query("INSERT INTO users (email, name, role, password_hash) VALUES (?, ?, ?, ?)")
+        .bind(email)
+        .bind(name)
+        .bind(role.as_str())
+        .bind(password_hash)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    let id = res.last_insert_rowid();
+    crate::audit::record("create_user", "user", Some(id), Some(format!("{} ({})", email, role.as_str()))).await;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_user(id: i64, email: String, name: String, role: Role) -> Result<(), String> {
+    require_role(Role::Admin).await?;
+    let (email, name) = (email.trim(), name.trim());
+    if email.is_empty() || name.is_empty() {
+        return Err("Email and name must not be empty".to_string());
+    }
+    let pool = get_db();
+    let (admins,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE role = 'Admin' AND id != ?")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if role != Role::Admin && admins == 0 {
+        return Err("At least one Admin must remain".to_string());
+    }
+    let res = sqlx::query("UPDATE users SET email = ?, name = ?, role = ? WHERE id = ?")
+        .bind(email)
+        .bind(name)
+        .bind(role.as_str())
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(format!("User {} not found", id));
+    }
+    crate::audit::record("update_user", "user", Some(id), Some(format!("{} ({})", email, role.as_str()))).await;
+    Ok(())
+}
+
+/// Sets a user's password. Users may change their own; anyone else's needs
+/// an Admin.
+#[tauri::command]
+pub async fn set_user_password(id: i64, password: String) -> Result<(), String> {
+    if current_user_id() != Some(id) {
+        require_role(Role::Admin).await?;
+    }
+    let password_hash = hash_password(&password)?;
+    let res = sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+        .bind(password_hash)
+        .bind(id)
+        .execute(&get_db())
+        .await
+        .map_err(|e| e.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(format!("User {} not found", id));
+    }
+    crate::audit::record("set_user_password", "user", Some(id), None).await;
+    Ok(())
+}
+
+/// Deletes a user. Their audit entries and assignments keep the dangling id.
+#[tauri::command]
+pub async fn delete_user(id: i64) -> Result<(), String> {
+    require_role(Role::Admin).await?;
+    let pool = get_db();
+    let (admins,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE role = 'Admin' AND id != ?")
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if admins == 0 {
+        return Err("At least one Admin must remain".to_string());
+    }
+    sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if current_user_id() == Some(id) {
+        clear_current_user();
+    }
+    crate::audit::record("delete_user", "user", Some(id), None).await;
+    Ok(())
+}
+
+/// Signs a user in with their password and records the login. `None`
+/// signs out. Users without a password can't sign in until one is set.
+#[tauri::command]
+pub async fn set_current_user(id: Option<i64>, password: Option<String>) -> Result<(), String> {
+    let Some(id) = id else {
+        clear_current_user();
+        return Ok(());
+    };
+    let pool = get_db();
+    let row: Option<(Option<String>,)> = sqlx::query_as("SELECT password_hash FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some((stored,)) = row else {
+        return Err(format!("User {} not found", id));
+    };
+    let Some(stored) = stored else {
+        return Err("This user has no password yet; an Admin must set one".to_string());
+    };
+    if !verify_password(password.as_deref().unwrap_or(""), &stored) {
+        crate::audit::record("login_failed", "user", Some(id), None).await;
+        return Err("Incorrect password".to_string());
+    }
+    sqlx::query("UPDATE users SET last_login = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    *current_user_lock().write().unwrap() = Some(id);
+    crate::audit::record("login", "user", Some(id), None).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_current_user() -> Result<Option<User>, String> {
+    let Some(id) = current_user_id() else { return Ok(None) };
+    sqlx::query_as::<_, User>("SELECT id, email, name, role, created_at, last_login FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&get_db())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_round_trip() {
+        let stored = hash_password("correct horse").unwrap();
+        assert!(stored.starts_with("pbkdf2-sha256$100000$"));
+        assert!(verify_password("correct horse", &stored));
+        assert!(!verify_password("correct horsE", &stored));
+        assert!(!verify_password("correct horse", "plain"));
+        assert_ne!(stored, hash_password("correct horse").unwrap(), "salts must differ");
+    }
+
+    #[test]
+    fn test_short_passwords_are_rejected() {
+        assert!(hash_password("short").is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! `{{name}}` placeholder substitution shared by everything that sends
+//! requests. Values may themselves contain placeholders, which resolve in
+//! turn; `\{{name}}` is kept as a literal `{{name}}`. Names starting with
+//! `$` are computed afresh on every use, e.g. `{{$uuid}}`; runs that send
+//! many requests from one template defer them so each request gets its own.
+
+use crate::fuzzer::FuzzTask;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Variables resolving through more nested placeholders than this are
+/// reported as an error rather than followed.
+const MAX_DEPTH: usize = 16;
+
+/// Bytes substitution may add to a text. Variables that each refer to
+/// another several times grow exponentially with depth, so this, not
+/// `MAX_DEPTH`, is what bounds the work.
+const MAX_EXPANSION: usize = 1 << 20;
+
+/// What happens to a placeholder with no variable.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SubstitutionMode {
+    /// Left in the text as-is
+    #[default]
+    Lenient,
+    /// The request isn't sent
+    Strict,
+}
+
+/// Placeholders resolved by a `Resolver`, in order of first use.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Substitutions {
+    pub substituted: Vec<String>,
+    /// Placeholders with no matching variable
+    pub unresolved: Vec<String>,
+}
+
+fn note(list: &mut Vec<String>, name: &str) {
+    if !list.iter().any(|n| n == name) {
+        list.push(name.to_string());
+    }
+}
+
+pub struct Resolver {
+    vars: HashMap<String, String>,
+    mode: SubstitutionMode,
+    /// `$` builtins are checked but left in place for `fresh` to compute
+    deferred: bool,
+    pub seen: Substitutions,
+}
+
+impl Resolver {
+    pub fn new(vars: HashMap<String, String>, mode: SubstitutionMode) -> Self {
+        Self { vars, mode, deferred: false, seen: Substitutions::default() }
+    }
+
+    /// Leaves `$` builtins, and escaped placeholders, for `fresh` to resolve
+    /// when each request is sent.
+    pub fn defer_builtins(mut self) -> Self {
+        self.deferred = true;
+        self
+    }
+
+    /// Resolves against the active environment in the configured mode.
+    pub async fn active() -> Result<Self, String> {
+        Ok(Self::new(crate::environments::active_variables().await?, configured_mode().await?))
+    }
+
+    /// Adds variables, replacing any with the same name.
+    pub fn extend(&mut self, vars: HashMap<String, String>) {
+        self.vars.extend(vars);
+    }
+
+    /// `input` with its placeholders replaced. Fails on a variable that
+    /// refers back to itself, and in strict mode on unresolved placeholders.
+    pub fn resolve(&mut self, input: &str) -> Result<String, String> {
+        let mut missing = Vec::new();
+        let out = self.expand(input, &mut Vec::new(), &mut missing)?;
+        if self.mode == SubstitutionMode::Strict && !missing.is_empty() {
+            return Err(format!("Unresolved variables: {}", missing.join(", ")));
+        }
+        Ok(out)
+    }
+
+    pub fn resolve_headers(&mut self, headers: HashMap<String, String>) -> Result<HashMap<String, String>, String> {
+        headers.into_iter().map(|(k, v)| Ok((self.resolve(&k)?, self.resolve(&v)?))).collect()
+    }
+
+    /// Resolves a fuzz task's URL, headers and body in place.
+    pub fn resolve_task(&mut self, task: &mut FuzzTask) -> Result<(), String> {
+        task.url = self.resolve(&task.url)?;
+        task.headers = self.resolve_headers(std::mem::take(&mut task.headers))?;
+        task.body = task.body.as_deref().map(|b| self.resolve(b)).transpose()?;
+        Ok(())
+    }
+
+    fn expand(&mut self, input: &str, stack: &mut Vec<String>, missing: &mut Vec<String>) -> Result<String, String> {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let call = call_end(after);
+            let Some(close) = call.or_else(|| after.find("}}")) else { break };
+            let raw = &after[..close];
+            // In `{{{name}}}` the placeholder starts at the second brace
+            if call.is_none() && raw.contains('{') {
+                out.push_str(&rest[..start + 1]);
+                rest = &rest[start + 1..];
+                continue;
+            }
+            let placeholder = &rest[start..start + close + 4];
+            if let Some(before) = rest[..start].strip_suffix('\\') {
+                out.push_str(if self.deferred { &rest[..start] } else { before });
+                out.push_str(placeholder);
+                rest = &after[close + 2..];
+                continue;
+            }
+            out.push_str(&rest[..start]);
+            rest = &after[close + 2..];
+
+            let name = raw.trim();
+            if name.is_empty() {
+                out.push_str(placeholder);
+                continue;
+            }
+            if let Some(dynamic) = name.strip_prefix('$') {
+                let (func, arg) = match dynamic.split_once('(') {
+                    Some((func, arg)) => (func, Some(arg.strip_suffix(')').unwrap_or(arg))),
+                    None => (dynamic, None),
+                };
+                let arg = arg.map(|a| self.expand(a, stack, missing)).transpose()?;
+                if let Some(value) = builtin(func, arg.as_deref()) {
+                    note(&mut self.seen.substituted, &format!("${}", func));
+                    let value = value?;
+                    match (&arg, self.deferred) {
+                        (_, false) => out.push_str(&value),
+                        (Some(arg), true) => out.push_str(&format!("{{{{${}({})}}}}", func, arg)),
+                        (None, true) => out.push_str(&format!("{{{{${}}}}}", func)),
+                    }
+                    check_expansion(input, &out)?;
+                    continue;
+                }
+            }
+            let Some(value) = self.vars.get(name).cloned() else {
+                note(&mut self.seen.unresolved, name);
+                note(missing, name);
+                out.push_str(placeholder);
+                continue;
+            };
+            if stack.iter().any(|n| n == name) {
+                return Err(format!("Variable cycle: {} -> {}", stack.join(" -> "), name));
+            }
+            if stack.len() >= MAX_DEPTH {
+                return Err(format!("Variables nested more than {} deep at '{}'", MAX_DEPTH, name));
+            }
+            stack.push(name.to_string());
+            let resolved = self.expand(&value, stack, missing)?;
+            stack.pop();
+            note(&mut self.seen.substituted, name);
+            out.push_str(&resolved);
+            check_expansion(input, &out)?;
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// `input` with the builtins a `defer_builtins` resolver left in it
+/// computed, fresh on every call. They were checked when the template was
+/// resolved, so this doesn't fail; a text that still would is kept as-is.
+pub fn fresh(input: &str) -> String {
+    if !input.contains("{{") {
+        return input.to_string();
+    }
+    Resolver::new(HashMap::new(), SubstitutionMode::Lenient).resolve(input).unwrap_or_else(|_| input.to_string())
+}
+
+pub fn fresh_headers(headers: &HashMap<String, String>) -> HashMap<String, String> {
+    headers.iter().map(|(k, v)| (fresh(k), fresh(v))).collect()
+}
+
+/// A fuzz task ready to send, per `fresh`.
+pub fn fresh_task(task: &FuzzTask) -> FuzzTask {
+    FuzzTask {
+        url: fresh(&task.url),
+        method: task.method.clone(),
+        headers: fresh_headers(&task.headers),
+        body: task.body.as_deref().map(fresh),
+        pacing: task.pacing.clone(),
+    }
+}
+
+fn check_expansion(input: &str, out: &str) -> Result<(), String> {
+    if out.len() > input.len() + MAX_EXPANSION {
+        return Err(format!("Variables expand to more than {} KiB", MAX_EXPANSION >> 10));
+    }
+    Ok(())
+}
+
+/// Where the `}}` closing a `$name(...)` call starts in `after`, the text
+/// following `{{`. Arguments may hold placeholders and parentheses as long
+/// as they balance.
+fn call_end(after: &str) -> Option<usize> {
+    let name = after.strip_prefix('$')?;
+    let open = 1 + name.find(|c: char| !c.is_ascii_alphanumeric() && c != '_')?;
+    if !after[open..].starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, c) in after[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = open + i + 1;
+                    return after[end..].starts_with("}}").then_some(end);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Random bits for values that only need to be unique, not secret.
+fn random() -> u128 {
+    uuid::Uuid::new_v4().as_u128()
+}
+
+/// The value of a computed `$func` variable, or `None` for unknown names.
+fn builtin(func: &str, arg: Option<&str>) -> Option<Result<String, String>> {
+    let value = match func {
+        "uuid" => Ok(uuid::Uuid::new_v4().to_string()),
+        "timestamp" => Ok(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .to_string()),
+        "randomInt" => {
+            let bounds = match arg {
+                None => Some((0, 1000)),
+                Some(arg) => arg
+                    .split_once(',')
+                    .and_then(|(min, max)| Some((min.trim().parse::<i64>().ok()?, max.trim().parse::<i64>().ok()?)))
+                    .filter(|(min, max)| min <= max),
+            };
+            match bounds {
+                Some((min, max)) => {
+                    let span = (max as i128 - min as i128 + 1) as u128;
+                    Ok((min as i128 + (random() % span) as i128).to_string())
+                }
+                None => Err(format!("$randomInt takes (min,max) with min <= max, got '{}'", arg.unwrap_or_default())),
+            }
+        }
+        "randomEmail" => Ok(format!("user{}@example.com", &format!("{:032x}", random())[..10])),
+        "base64" => match arg {
+            Some(arg) => Ok(base64::engine::general_purpose::STANDARD.encode(arg)),
+            None => Err("$base64 takes the text to encode, e.g. {{$base64(user:pass)}}".to_string()),
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// The `variable_substitution` setting.
+pub async fn configured_mode() -> Result<SubstitutionMode, String> {
+    crate::settings::get("variable_substitution").await
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubstitutionPreview {
+    pub output: String,
+    #[serde(flatten)]
+    pub variables: Substitutions,
+}
+
+/// Resolves `input` against the active environment, plus `extra` variables
+/// taking precedence, without sending anything. `mode` defaults to the
+/// configured one.
+#[tauri::command]
+pub async fn preview_substitution(input: String, mode: Option<SubstitutionMode>, extra: Option<HashMap<String, String>>) -> Result<SubstitutionPreview, String> {
+    let mut vars = crate::environments::active_variables().await?;
+    vars.extend(extra.unwrap_or_default());
+    let mode = match mode {
+        Some(mode) => mode,
+        None => configured_mode().await?,
+    };
+    let mut resolver = Resolver::new(vars, mode);
+    let output = resolver.resolve(&input)?;
+    Ok(SubstitutionPreview { output, variables: resolver.seen })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let vars = vars(&[("host", "api.example.com"), ("base_url", "https://{{host}}/v1"), ("token", "abc"), ("a", "{{b}}"), ("b", "{{a}}")]);
+        let mut lenient = Resolver::new(vars.clone(), SubstitutionMode::Lenient);
+        assert_eq!(lenient.resolve("{{base_url}}/users?t={{ token }}").unwrap(), "https://api.example.com/v1/users?t=abc");
+        assert_eq!(lenient.seen.substituted, ["host", "base_url", "token"]);
+
+        assert_eq!(lenient.resolve("{{missing}} and {{token}}").unwrap(), "{{missing}} and abc");
+        assert_eq!(lenient.seen.unresolved, ["missing"]);
+        assert_eq!(lenient.resolve(r"\{{token}} is {{token}}").unwrap(), "{{token}} is abc");
+        assert_eq!(lenient.resolve("{{{token}}} {{ }} {{unclosed").unwrap(), "{abc} {{ }} {{unclosed");
+        assert_eq!(lenient.resolve("{{a}}").unwrap_err(), "Variable cycle: a -> b -> a");
+
+        let mut strict = Resolver::new(vars, SubstitutionMode::Strict);
+        assert_eq!(strict.resolve("{{token}}").unwrap(), "abc");
+        assert_eq!(strict.resolve("{{x}}/{{token}}/{{y}}").unwrap_err(), "Unresolved variables: x, y");
+        assert_eq!(strict.resolve(r"\{{x}}").unwrap(), "{{x}}");
+
+        // Each level doubles the one below: 2^15 copies of the innermost value
+        let mut fan_out: HashMap<String, String> = (0..15).map(|i| (format!("v{}", i), format!("{{{{v{0}}}}}{{{{v{0}}}}}", i + 1))).collect();
+        fan_out.insert("v15".to_string(), "x".repeat(64));
+        let err = Resolver::new(fan_out, SubstitutionMode::Lenient).resolve("{{v0}}").unwrap_err();
+        assert_eq!(err, "Variables expand to more than 1024 KiB");
+    }
+
+    #[test]
+    fn test_dynamic_variables() {
+        let mut resolver = Resolver::new(vars(&[("user", "ann"), ("pass", "s3cret")]), SubstitutionMode::Strict);
+        assert_eq!(resolver.resolve("Basic {{$base64({{user}}:{{pass}})}}").unwrap(), "Basic YW5uOnMzY3JldA==");
+        assert_eq!(resolver.resolve("{{$base64((a))}}").unwrap(), "KGEp");
+        assert_eq!(resolver.seen.substituted, ["user", "pass", "$base64"]);
+
+        let ids = (resolver.resolve("{{$uuid}}").unwrap(), resolver.resolve("{{$uuid}}").unwrap());
+        assert_ne!(ids.0, ids.1);
+        assert!(uuid::Uuid::parse_str(&ids.0).is_ok());
+        assert!(resolver.resolve("{{$timestamp}}").unwrap().parse::<u64>().unwrap() > 1_600_000_000);
+        for _ in 0..20 {
+            let n: i64 = resolver.resolve("{{$randomInt(-2, 2)}}").unwrap().parse().unwrap();
+            assert!((-2..=2).contains(&n));
+        }
+        assert!(resolver.resolve("{{$randomInt(5,1)}}").is_err());
+        assert!(resolver.resolve("{{$randomEmail}}").unwrap().ends_with("@example.com"));
+        assert_eq!(resolver.resolve("{{$nope}}").unwrap_err(), "Unresolved variables: $nope");
+    }
+
+    #[test]
+    fn test_deferred_builtins() {
+        let mut resolver = Resolver::new(vars(&[("user", "ann"), ("id", "{{$uuid}}")]), SubstitutionMode::Strict).defer_builtins();
+        let template = resolver.resolve(r"/{{id}}?auth={{$base64({{user}}:x)}}&raw=\{{$uuid}}").unwrap();
+        assert_eq!(template, r"/{{$uuid}}?auth={{$base64(ann:x)}}&raw=\{{$uuid}}");
+        assert!(resolver.resolve("{{$randomInt(5,1)}}").is_err());
+
+        let (first, second) = (fresh(&template), fresh(&template));
+        assert_ne!(first, second);
+        assert!(first.ends_with("?auth=YW5uOng=&raw={{$uuid}}"));
+        assert!(uuid::Uuid::parse_str(&first[1..37]).is_ok());
+    }
+}
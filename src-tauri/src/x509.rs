@@ -0,0 +1,164 @@
+//! Names, validity and subject alternative names of a certificate, read
+//! with `x509-parser`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use x509_parser::prelude::{FromDer, GeneralName, ParsedExtension, X509Certificate, X509Name};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CertSummary {
+    /// `CN=..., O=...`
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    /// DNS names and IP addresses, as presented
+    pub sans: Vec<String>,
+    /// Issuer and subject are identical
+    pub self_signed: bool,
+}
+
+/// One attribute of a Name, as encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameAttribute {
+    pub oid: Vec<u64>,
+    /// String type tag, e.g. 0x0c for UTF8String
+    pub tag: u8,
+    pub value: Vec<u8>,
+}
+
+/// What it takes to sign as a certificate's subject, or to check a
+/// signature it carries.
+#[derive(Debug, Clone)]
+pub struct SigningDetails {
+    pub subject: Vec<NameAttribute>,
+    /// Encoded Names, for comparing issuer and subject across certificates
+    pub issuer_der: Vec<u8>,
+    pub subject_der: Vec<u8>,
+    /// Content of the subjectPublicKey BIT STRING
+    pub public_key: Vec<u8>,
+    /// Basic constraints mark it as a CA
+    pub is_ca: bool,
+    /// Subject and authority key identifiers, which clients match when
+    /// building a chain
+    pub subject_key_id: Option<Vec<u8>>,
+    pub authority_key_id: Option<Vec<u8>>,
+    /// The signed TBSCertificate, tag and length included
+    pub tbs: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+fn certificate(der: &[u8]) -> Option<X509Certificate<'_>> {
+    X509Certificate::from_der(der).ok().map(|(_, cert)| cert)
+}
+
+/// `CN` and `O` of a Name; empty when it has neither.
+fn name(name: &X509Name) -> String {
+    let common_names = name.iter_common_name().map(|a| ("CN", a));
+    let organizations = name.iter_organization().map(|a| ("O", a));
+    common_names
+        .chain(organizations)
+        .map(|(label, attribute)| format!("{}={}", label, String::from_utf8_lossy(attribute.attr_value().data)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn name_attributes(name: &X509Name) -> Vec<NameAttribute> {
+    name.iter_attributes()
+        .map(|attribute| NameAttribute {
+            oid: attribute.attr_type().iter().map(|arcs| arcs.collect()).unwrap_or_default(),
+            tag: attribute.attr_value().tag().0 as u8,
+            value: attribute.attr_value().data.to_vec(),
+        })
+        .collect()
+}
+
+/// Reads a DER-encoded certificate.
+pub fn parse_certificate(der: &[u8]) -> Option<CertSummary> {
+    let cert = certificate(der)?;
+    let sans = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|extension| {
+            extension
+                .value
+                .general_names
+                .iter()
+                .filter_map(|general_name| match general_name {
+                    GeneralName::DNSName(dns) => Some(dns.to_string()),
+                    GeneralName::IPAddress([a, b, c, d]) => Some(std::net::Ipv4Addr::new(*a, *b, *c, *d).to_string()),
+                    GeneralName::IPAddress(bytes) => <[u8; 16]>::try_from(*bytes).ok().map(|b| std::net::Ipv6Addr::from(b).to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let validity = cert.validity();
+    Some(CertSummary {
+        subject: name(cert.subject()),
+        issuer: name(cert.issuer()),
+        not_before: DateTime::from_timestamp(validity.not_before.timestamp(), 0)?,
+        not_after: DateTime::from_timestamp(validity.not_after.timestamp(), 0)?,
+        sans,
+        self_signed: cert.issuer().as_raw() == cert.subject().as_raw(),
+    })
+}
+
+/// Reads the parts of a DER-encoded certificate needed to sign as it.
+pub fn parse_signing_details(der: &[u8]) -> Option<SigningDetails> {
+    let cert = certificate(der)?;
+    let is_ca = cert.basic_constraints().ok().flatten().is_some_and(|extension| extension.value.ca);
+    let mut subject_key_id = None;
+    let mut authority_key_id = None;
+    for extension in cert.extensions() {
+        match extension.parsed_extension() {
+            ParsedExtension::SubjectKeyIdentifier(id) => subject_key_id = Some(id.0.to_vec()),
+            ParsedExtension::AuthorityKeyIdentifier(aki) => authority_key_id = aki.key_identifier.as_ref().map(|id| id.0.to_vec()),
+            _ => {}
+        }
+    }
+    Some(SigningDetails {
+        subject: name_attributes(cert.subject()),
+        issuer_der: cert.issuer().as_raw().to_vec(),
+        subject_der: cert.subject().as_raw().to_vec(),
+        public_key: cert.public_key().subject_public_key.data.to_vec(),
+        is_ca,
+        subject_key_id,
+        authority_key_id,
+        tbs: cert.tbs_certificate.as_ref().to_vec(),
+        signature: cert.signature_value.data.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_certificate() {
+        let mut params = rcgen::CertificateParams::new(vec!["api.example.com".to_string(), "*.dev.example.com".to_string()]).unwrap();
+        params.subject_alt_names.push(rcgen::SanType::IpAddress("10.0.0.1".parse().unwrap()));
+        params.distinguished_name.push(rcgen::DnType::CommonName, "api.example.com");
+        params.distinguished_name.push(rcgen::DnType::OrganizationName, "Example");
+        params.not_before = rcgen::date_time_ymd(2024, 1, 2);
+        params.not_after = rcgen::date_time_ymd(2051, 3, 4);
+        let key = rcgen::KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+
+        let summary = parse_certificate(cert.der()).unwrap();
+        assert_eq!(summary.sans, vec!["api.example.com", "*.dev.example.com", "10.0.0.1"]);
+        assert_eq!(summary.subject, "CN=api.example.com, O=Example");
+        assert_eq!(summary.issuer, summary.subject);
+        assert!(summary.self_signed);
+        assert_eq!(summary.not_before.format("%Y-%m-%d").to_string(), "2024-01-02");
+        assert_eq!(summary.not_after.format("%Y-%m-%d").to_string(), "2051-03-04");
+        assert_eq!(parse_certificate(&cert.der()[..40]), None);
+
+        let details = parse_signing_details(cert.der()).unwrap();
+        assert!(!details.is_ca);
+        assert_eq!(details.public_key, key.public_key_raw());
+        assert_eq!(details.issuer_der, details.subject_der);
+        assert_eq!(details.subject[0], NameAttribute { oid: vec![2, 5, 4, 3], tag: 0x0c, value: b"api.example.com".to_vec() });
+    }
+}
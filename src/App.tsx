@@ -48,7 +48,12 @@ function App() {
 
   const handleSwitchWorkspace = async (name: string) => {
     try {
-      await invoke("switch_workspace", { name });
+      let passphrase: string | null = null;
+      if (await invoke<boolean>("is_workspace_encrypted", { name })) {
+        passphrase = window.prompt(`Passphrase for ${name}`);
+        if (passphrase === null) return;
+      }
+      await invoke("switch_workspace", { name, passphrase });
       setWorkspace(name);
       setShowWorkspaceMenu(false);
       // Force reload current view data
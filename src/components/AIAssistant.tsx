@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
 import { Button } from "./ui/button";
 import { Brain, Sparkles, Zap, AlertCircle, CheckCircle, Loader2, Server, MessageCircle, Mail, Shield } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
@@ -10,6 +10,8 @@ interface TriageSuggestion {
     owasp_category: string;
     remediation_hint: string;
     similar_cves: string[];
+    /** Fields the model left out, shown as "Not provided" */
+    missing_fields?: string[];
 }
 
 interface AIAssistantProps {
@@ -26,6 +28,12 @@ export default function AIAssistant({ findingId, findingName, description, evide
     const [isAvailable, setIsAvailable] = useState<boolean | null>(null);
     const [error, setError] = useState<string | null>(null);
 
+    // Show a suggestion stored by an earlier or batch triage
+    useEffect(() => {
+        setSuggestion(null);
+        invoke<TriageSuggestion | null>("get_ai_triage", { findingId }).then(setSuggestion).catch(console.error);
+    }, [findingId]);
+
     const checkAvailability = async () => {
         try {
             const available = await invoke<boolean>("check_llm_availability");
@@ -120,6 +128,12 @@ export default function AIAssistant({ findingId, findingName, description, evide
 
             {suggestion && (
                 <div className="space-y-4 animate-in fade-in slide-in-from-bottom-4 duration-500">
+                    {(suggestion.missing_fields?.length ?? 0) > 0 && (
+                        <p className="text-[10px] font-bold text-amber-400/80 uppercase tracking-widest">
+                            Partial answer: the model left out {suggestion.missing_fields!.join(", ")}
+                        </p>
+                    )}
+
                     {/* Severity Assessment */}
                     <div className="p-4 rounded-xl border border-white/5 bg-white/[0.02] space-y-2">
                         <div className="flex items-center gap-2">
@@ -0,0 +1,120 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Cpu, RefreshCw } from "lucide-react";
+import { Button } from "./ui/button";
+import { AiUsageStats } from "../types";
+
+const MODEL_SETTINGS = [
+    { key: "ai_model", label: "Default" },
+    { key: "ai_triage_model", label: "Triage" },
+    { key: "ai_report_model", label: "Reports" },
+    { key: "ai_embedding_model", label: "Embeddings" },
+];
+
+export default function AiModels() {
+    const [models, setModels] = useState<Record<string, string>>({});
+    const [available, setAvailable] = useState<string[]>([]);
+    const [usage, setUsage] = useState<AiUsageStats[]>([]);
+    const [days, setDays] = useState<number | null>(30);
+
+    const loadUsage = async () => {
+        try {
+            setUsage(await invoke<AiUsageStats[]>("get_ai_usage_stats", { days }));
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    useEffect(() => {
+        Promise.all(MODEL_SETTINGS.map(s => invoke<string>("get_setting", { key: s.key }).then(v => [s.key, v] as const)))
+            .then(entries => setModels(Object.fromEntries(entries)))
+            .catch(console.error);
+        invoke<string[]>("get_available_models").then(setAvailable).catch(() => setAvailable([]));
+    }, []);
+
+    useEffect(() => {
+        loadUsage();
+    }, [days]);
+
+    const save = async (key: string, value: string) => {
+        try {
+            await invoke("set_setting", { key, value });
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center gap-3">
+                <Cpu className="text-brand-400 h-6 w-6" />
+                <h3 className="text-xl font-bold text-white italic">AI Models & Usage</h3>
+            </div>
+
+            <div className="grid grid-cols-2 gap-4">
+                {MODEL_SETTINGS.map(s => (
+                    <div key={s.key} className="space-y-1">
+                        <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest ml-1">{s.label}</label>
+                        <input
+                            list="ai-models"
+                            value={models[s.key] ?? ""}
+                            placeholder={s.key === "ai_model" ? "" : "Same as default"}
+                            onChange={e => setModels({ ...models, [s.key]: e.target.value })}
+                            onBlur={e => save(s.key, e.target.value)}
+                            className="w-full bg-zinc-950 border border-white/10 rounded-xl px-4 py-2 text-sm font-mono text-brand-300 focus:border-brand-500 outline-none"
+                        />
+                    </div>
+                ))}
+                <datalist id="ai-models">
+                    {available.map(m => <option key={m} value={m} />)}
+                </datalist>
+            </div>
+
+            <div className="space-y-3">
+                <div className="flex items-center justify-between">
+                    <select
+                        value={days ?? ""}
+                        onChange={e => setDays(e.target.value ? parseInt(e.target.value) : null)}
+                        className="bg-zinc-950 border border-white/10 rounded-lg px-3 py-1.5 text-xs text-white outline-none"
+                    >
+                        <option value="1">Last 24 hours</option>
+                        <option value="7">Last 7 days</option>
+                        <option value="30">Last 30 days</option>
+                        <option value="">All time</option>
+                    </select>
+                    <Button variant="outline" size="sm" onClick={loadUsage} className="border-white/10 text-zinc-400">
+                        <RefreshCw size={12} />
+                    </Button>
+                </div>
+                {usage.length === 0 ? (
+                    <p className="text-sm text-zinc-500">No AI calls recorded in this period.</p>
+                ) : (
+                    <table className="w-full text-xs font-mono">
+                        <thead>
+                            <tr className="text-zinc-500 text-left">
+                                <th className="py-2">Task</th>
+                                <th className="py-2">Model</th>
+                                <th className="py-2">Calls</th>
+                                <th className="py-2">Failed</th>
+                                <th className="py-2">Tokens in/out</th>
+                                <th className="py-2">Avg / max</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {usage.map(u => (
+                                <tr key={`${u.task}-${u.model}`} className="border-t border-white/5 text-zinc-400">
+                                    <td className="py-1 pr-2">{u.task}</td>
+                                    <td className="py-1 pr-2 break-all">{u.model}</td>
+                                    <td className="py-1 pr-2">{u.calls}</td>
+                                    <td className="py-1 pr-2">{u.failures}</td>
+                                    <td className="py-1 pr-2">{u.prompt_tokens} / {u.completion_tokens}</td>
+                                    <td className="py-1 pr-2">{Math.round(u.avg_latency_ms)}ms / {u.max_latency_ms}ms</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                )}
+            </div>
+        </div>
+    );
+}
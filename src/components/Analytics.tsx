@@ -10,6 +10,7 @@ import { ShieldAlert, Globe, Zap, Activity, Info, AlertTriangle, ShieldCheck, Fi
 import { Button } from "./ui/button";
 import { cn } from "../lib/utils";
 import { Asset, Finding } from "../types";
+import FindingClusters from "./FindingClusters";
 
 export default function Analytics() {
     const [stats, setStats] = useState<{
@@ -273,6 +274,8 @@ export default function Analytics() {
                 </div>
             </div>
 
+            <FindingClusters />
+
             <div className="grid grid-cols-1 lg:grid-cols-3 gap-8">
                 {/* Risk Topology */}
                 <div className="lg:col-span-2 glass-card space-y-6">
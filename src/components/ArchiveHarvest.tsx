@@ -0,0 +1,128 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Button } from "./ui/button";
+import { Loader2, History, Download } from "lucide-react";
+import { ArchiveHarvest as Harvest, BatchImportResult } from "../types";
+
+interface ArchiveHarvestProps {
+    domain: string;
+}
+
+const SOURCE_LABELS: Record<string, string> = { wayback: "Wayback", common_crawl: "Common Crawl" };
+
+function archivedDate(timestamp: string | null) {
+    return timestamp ? `${timestamp.slice(0, 4)}-${timestamp.slice(4, 6)}-${timestamp.slice(6, 8)}` : "";
+}
+
+export default function ArchiveHarvest({ domain }: ArchiveHarvestProps) {
+    const [includeSubdomains, setIncludeSubdomains] = useState(false);
+    const [harvest, setHarvest] = useState<Harvest | null>(null);
+    const [selected, setSelected] = useState<Set<string>>(new Set());
+    const [loading, setLoading] = useState(false);
+    const [message, setMessage] = useState<string | null>(null);
+
+    const handleHarvest = async () => {
+        setLoading(true);
+        setMessage(null);
+        try {
+            const result = await invoke<Harvest>("harvest_archive_urls", {
+                request: { domain, include_subdomains: includeSubdomains }
+            });
+            setHarvest(result);
+            setSelected(new Set(result.candidates.filter(c => !c.in_inventory).map(c => c.url)));
+        } catch (e) {
+            setMessage(String(e));
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    const handleImport = async () => {
+        setLoading(true);
+        try {
+            const result = await invoke<BatchImportResult>("import_archive_urls", { urls: [...selected] });
+            setMessage(`Imported ${result.added} assets tagged "archive" (${result.skipped} already present)`);
+            setHarvest(prev => prev && {
+                ...prev,
+                candidates: prev.candidates.map(c => selected.has(c.url) ? { ...c, in_inventory: true } : c)
+            });
+            setSelected(new Set());
+        } catch (e) {
+            setMessage(String(e));
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    const toggle = (url: string) => {
+        setSelected(prev => {
+            const next = new Set(prev);
+            if (next.has(url)) next.delete(url); else next.add(url);
+            return next;
+        });
+    };
+
+    return (
+        <div className="glass rounded-[32px] p-6 border border-white/5 space-y-4">
+            <div className="flex items-center justify-between">
+                <h3 className="text-lg font-bold flex items-center gap-2 italic">
+                    <History className="h-5 w-5 text-brand-400" />
+                    Archived Endpoints
+                </h3>
+                {harvest && (
+                    <span className="text-xs font-mono text-zinc-500 uppercase tracking-widest">
+                        {harvest.candidates.length} API-like of {harvest.captures} captures
+                    </span>
+                )}
+            </div>
+
+            <div className="flex items-center gap-3">
+                <label className="flex items-center gap-2 text-xs text-zinc-400">
+                    <input type="checkbox" checked={includeSubdomains} onChange={e => setIncludeSubdomains(e.target.checked)} />
+                    Include subdomains
+                </label>
+                <div className="flex-1" />
+                <Button
+                    onClick={handleHarvest}
+                    disabled={loading || !domain}
+                    className="h-10 bg-brand-500 hover:bg-brand-400 text-black font-black rounded-xl"
+                >
+                    {loading && !harvest ? <Loader2 size={14} className="animate-spin" /> : "HARVEST"}
+                </Button>
+                {harvest && (
+                    <Button
+                        variant="outline"
+                        onClick={handleImport}
+                        disabled={loading || selected.size === 0}
+                        className="h-10 border-white/10"
+                    >
+                        <Download size={14} className="mr-2" /> Import {selected.size}
+                    </Button>
+                )}
+            </div>
+
+            {message && <p className="text-xs text-zinc-400">{message}</p>}
+            {harvest?.errors.map((e, i) => <p key={i} className="text-xs text-amber-400">{e}</p>)}
+
+            {harvest && harvest.candidates.length > 0 && (
+                <div className="max-h-[420px] overflow-y-auto space-y-1">
+                    {harvest.candidates.map(c => (
+                        <label key={c.url} className="flex items-center gap-3 px-3 py-2 rounded-xl hover:bg-white/5 text-xs">
+                            <input
+                                type="checkbox"
+                                checked={selected.has(c.url)}
+                                disabled={c.in_inventory}
+                                onChange={() => toggle(c.url)}
+                            />
+                            <span className="font-mono text-zinc-200 truncate flex-1" title={c.url}>{c.url}</span>
+                            {c.status !== null && <span className="font-mono text-zinc-500">{c.status}</span>}
+                            <span className="text-[10px] text-zinc-600">{c.sources.map(s => SOURCE_LABELS[s]).join(", ")}</span>
+                            <span className="text-[10px] text-zinc-600 w-20 text-right">{archivedDate(c.last_archived)}</span>
+                            {c.in_inventory && <span className="text-[10px] text-green-400 uppercase">In inventory</span>}
+                        </label>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+}
@@ -0,0 +1,146 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Loader2, MessageSquare, Plus, Send, Trash2 } from "lucide-react";
+import { Button } from "./ui/button";
+import { cn } from "../lib/utils";
+import { AiChat, AiChatDetail } from "../types";
+
+interface AssetChatProps {
+    assetId: number;
+}
+
+export default function AssetChat({ assetId }: AssetChatProps) {
+    const [chats, setChats] = useState<AiChat[]>([]);
+    const [active, setActive] = useState<AiChatDetail | null>(null);
+    const [draft, setDraft] = useState("");
+    const [sending, setSending] = useState(false);
+
+    const loadChats = async () => {
+        try {
+            setChats(await invoke<AiChat[]>("list_ai_chats", { assetId }));
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    useEffect(() => {
+        setActive(null);
+        loadChats();
+    }, [assetId]);
+
+    const open = async (id: number) => {
+        try {
+            setActive(await invoke<AiChatDetail>("get_ai_chat", { id }));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const start = async () => {
+        try {
+            setActive(await invoke<AiChatDetail>("start_ai_chat", { assetId }));
+            loadChats();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const remove = async (id: number) => {
+        if (!confirm("Delete this conversation?")) return;
+        try {
+            await invoke("delete_ai_chat", { id });
+            if (active?.chat.id === id) setActive(null);
+            loadChats();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const send = async () => {
+        if (!active || !draft.trim()) return;
+        setSending(true);
+        try {
+            await invoke("send_ai_chat_message", { chatId: active.chat.id, content: draft });
+            setDraft("");
+            await open(active.chat.id);
+            loadChats();
+        } catch (e) {
+            alert(e);
+        } finally {
+            setSending(false);
+        }
+    };
+
+    return (
+        <div className="space-y-4">
+            <div className="flex items-center justify-between">
+                <h4 className="text-xs font-black uppercase tracking-widest text-zinc-500 flex items-center gap-2">
+                    <MessageSquare className="h-3 w-3" /> AI Conversations
+                </h4>
+                <Button onClick={start} className="h-8 px-3 rounded-lg bg-brand-500 hover:bg-brand-400 text-black text-[10px] font-black uppercase">
+                    <Plus size={12} className="mr-1" /> New Chat
+                </Button>
+            </div>
+
+            <div className="flex gap-2 overflow-x-auto">
+                {chats.map(chat => (
+                    <div
+                        key={chat.id}
+                        onClick={() => open(chat.id)}
+                        className={cn(
+                            "shrink-0 flex items-center gap-2 px-3 py-1.5 rounded-lg text-[10px] font-bold cursor-pointer border transition-all",
+                            active?.chat.id === chat.id ? "border-brand-500 text-brand-400" : "border-white/5 text-zinc-500 hover:text-zinc-300"
+                        )}
+                    >
+                        {new Date(chat.updated_at).toLocaleString()} · {chat.message_count}
+                        <button onClick={(e) => { e.stopPropagation(); remove(chat.id); }} className="text-zinc-600 hover:text-red-400">
+                            <Trash2 size={10} />
+                        </button>
+                    </div>
+                ))}
+            </div>
+
+            {active && (
+                <div className="space-y-3">
+                    <div className="space-y-3 max-h-[420px] overflow-y-auto">
+                        {active.messages.map(m =>
+                            m.role === "system" ? (
+                                <details key={m.id} className="text-[10px] text-zinc-600">
+                                    <summary className="cursor-pointer uppercase font-black tracking-widest">Context sent to the model</summary>
+                                    <pre className="mt-2 p-3 bg-black/40 rounded-xl whitespace-pre-wrap font-mono">{m.content}</pre>
+                                </details>
+                            ) : (
+                                <div
+                                    key={m.id}
+                                    className={cn(
+                                        "p-3 rounded-2xl text-sm whitespace-pre-wrap",
+                                        m.role === "user" ? "bg-brand-500/10 text-zinc-200 ml-8" : "bg-white/[0.03] text-zinc-300 mr-8"
+                                    )}
+                                >
+                                    {m.content}
+                                </div>
+                            )
+                        )}
+                    </div>
+                    <div className="flex gap-2">
+                        <textarea
+                            placeholder="Ask about this endpoint..."
+                            value={draft}
+                            onChange={(e) => setDraft(e.target.value)}
+                            onKeyDown={(e) => {
+                                if (e.key === "Enter" && !e.shiftKey) {
+                                    e.preventDefault();
+                                    send();
+                                }
+                            }}
+                            className="flex-1 h-16 bg-zinc-950 border border-white/5 rounded-2xl p-3 text-sm text-zinc-300 focus:outline-none focus:ring-1 focus:ring-brand-500/50 resize-none"
+                        />
+                        <Button onClick={send} disabled={sending || !draft.trim()} className="h-16 px-4 rounded-2xl bg-brand-500 hover:bg-brand-400 text-black">
+                            {sending ? <Loader2 size={16} className="animate-spin" /> : <Send size={16} />}
+                        </Button>
+                    </div>
+                </div>
+            )}
+        </div>
+    );
+}
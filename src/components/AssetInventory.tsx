@@ -1,11 +1,15 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Asset, Finding } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import { Asset, Finding, TriageProgress } from "../types";
 import {
     Search,
     Filter,
     Globe,
     ShieldAlert,
+    ShieldCheck,
+    ListChecks,
+    Database,
     ArrowUpDown,
     X,
     Code,
@@ -24,6 +28,8 @@ import {
     Zap,
     Terminal,
     ExternalLink as PostmanIcon,
+    Brain,
+    MessageSquare,
 } from "lucide-react";
 import Prism from "prismjs";
 import "prismjs/themes/prism-tomorrow.css";
@@ -37,6 +43,7 @@ import { autoTable } from "jspdf-autotable";
 import { cn } from "../lib/utils";
 import { Button } from "./ui/button";
 import AIAssistant from "./AIAssistant";
+import AssetChat from "./AssetChat";
 
 interface AssetInventoryProps {
     onSendToRepeater?: (data: { url: string; method: string; body?: string; headers?: Record<string, string> }) => void;
@@ -58,13 +65,34 @@ export default function AssetInventory({ onSendToRepeater }: AssetInventoryProps
     const [copied, setCopied] = useState(false);
     const [isSaving, setIsSaving] = useState(false);
     const [history, setHistory] = useState<any[]>([]);
-    const [activeTab, setActiveTab] = useState<"details" | "findings" | "history">("details");
+    const [activeTab, setActiveTab] = useState<"details" | "findings" | "history" | "chat">("details");
     const [selectedIds, setSelectedIds] = useState<number[]>([]);
+    const [triage, setTriage] = useState<TriageProgress | null>(null);
 
     useEffect(() => {
         loadAssets();
+        const unlisten = listen<TriageProgress>("ai-triage-progress", (event) => {
+            setTriage(event.payload);
+            if (event.payload.done && event.payload.error) alert(`AI triage: ${event.payload.failed} failed. Last error: ${event.payload.error}`);
+        });
+        return () => {
+            unlisten.then(f => f());
+        };
     }, []);
 
+    const toggleBatchTriage = async () => {
+        try {
+            if (triage && !triage.done) {
+                await invoke("cancel_batch_triage");
+                return;
+            }
+            const total = await invoke<number>("start_batch_triage", { requestsPerMinute: null });
+            setTriage({ processed: 0, total, reused: 0, failed: 0, done: total === 0, cancelled: false });
+        } catch (e) {
+            alert(e);
+        }
+    };
+
     const loadAssets = async () => {
         setIsLoading(true);
         try {
@@ -379,6 +407,30 @@ export default function AssetInventory({ onSendToRepeater }: AssetInventoryProps
             }
         });
 
+        // Model-written narrative, when an LLM is configured
+        try {
+            if (await invoke<boolean>("check_llm_availability")) {
+                const markdown = await invoke<string>("ai_generate_report", { scope: null });
+                doc.addPage();
+                addHeader("AI Narrative");
+                doc.setFontSize(10);
+                doc.setTextColor(40);
+                let y = 30;
+                for (const line of markdown.split("\n")) {
+                    const wrapped: string[] = doc.splitTextToSize(line.replace(/^#+\s*/, "").replace(/\*\*|`/g, ""), 182);
+                    if (y + wrapped.length * 5 > 285) {
+                        doc.addPage();
+                        addHeader("AI Narrative");
+                        y = 30;
+                    }
+                    doc.text(wrapped, 14, y);
+                    y += Math.max(wrapped.length, 1) * 5;
+                }
+            }
+        } catch (e) {
+            console.error("AI narrative skipped:", e);
+        }
+
         doc.save(`APISec_Report_${currentWorkspace.replace(/\s+/g, '_')}_${date.replace(/\//g, '-')}.pdf`);
     };
 
@@ -504,6 +556,10 @@ export default function AssetInventory({ onSendToRepeater }: AssetInventoryProps
                         <ShieldAlert className="mr-2 h-4 w-4" />
                         {sortBy === "violations" ? "Sorted by Violations" : "Sort by Violations"}
                     </Button>
+                    <Button variant="outline" onClick={toggleBatchTriage} className="h-11 px-5 rounded-xl border-purple-500/20 bg-purple-500/5 text-purple-400 hover:bg-purple-500/10 transition-all">
+                        <Brain className="mr-2 h-4 w-4" />
+                        {triage && !triage.done ? `Triaging ${triage.processed}/${triage.total}` : "AI Triage All"}
+                    </Button>
                     <Button variant="outline" onClick={handleExportPDF} className="h-11 px-5 rounded-xl border-brand-500/20 bg-brand-500/5 text-brand-400 hover:bg-brand-500/10 transition-all">
                         <FileText className="mr-2 h-4 w-4" /> Export Report
                     </Button>
@@ -704,6 +760,57 @@ export default function AssetInventory({ onSendToRepeater }: AssetInventoryProps
                                         <span className="text-[10px] font-bold uppercase tracking-widest hidden md:inline">Replay</span>
                                     </button>
                                 )}
+                                <button
+                                    onClick={async () => {
+                                        try {
+                                            await invoke("audit_security_headers", { assetId: selectedAsset.id });
+                                            setFindings(await invoke<Finding[]>("get_findings", { assetId: selectedAsset.id }));
+                                        } catch (e) {
+                                            console.error("Security header audit failed:", e);
+                                            alert(e);
+                                        }
+                                    }}
+                                    title="Fetch the endpoint and audit its security headers"
+                                    className="h-10 px-3 rounded-xl hover:bg-white/5 flex items-center gap-2 transition-all text-zinc-500 hover:text-brand-400 group/headers"
+                                >
+                                    <ShieldCheck className="h-4 w-4" />
+                                    <span className="text-[10px] font-bold uppercase tracking-widest hidden md:inline">Headers</span>
+                                </button>
+                                <button
+                                    onClick={async () => {
+                                        if (!confirm("Send OPTIONS, PUT, DELETE and other verbs to this endpoint? Mutating verbs are really sent, without the captured credentials.")) return;
+                                        try {
+                                            await invoke("enumerate_http_methods", { assetId: selectedAsset.id });
+                                            setFindings(await invoke<Finding[]>("get_findings", { assetId: selectedAsset.id }));
+                                        } catch (e) {
+                                            console.error("HTTP method enumeration failed:", e);
+                                            alert(e);
+                                        }
+                                    }}
+                                    title="Compare advertised and accepted HTTP methods"
+                                    className="h-10 px-3 rounded-xl hover:bg-white/5 flex items-center gap-2 transition-all text-zinc-500 hover:text-brand-400 group/verbs"
+                                >
+                                    <ListChecks className="h-4 w-4" />
+                                    <span className="text-[10px] font-bold uppercase tracking-widest hidden md:inline">Verbs</span>
+                                </button>
+                                {(selectedAsset.method || "GET").toUpperCase() === "GET" && (
+                                    <button
+                                        onClick={async () => {
+                                            try {
+                                                await invoke("probe_web_cache", { assetId: selectedAsset.id });
+                                                setFindings(await invoke<Finding[]>("get_findings", { assetId: selectedAsset.id }));
+                                            } catch (e) {
+                                                console.error("Web cache probe failed:", e);
+                                                alert(e);
+                                            }
+                                        }}
+                                        title="Probe for web cache deception and poisoning"
+                                        className="h-10 px-3 rounded-xl hover:bg-white/5 flex items-center gap-2 transition-all text-zinc-500 hover:text-brand-400 group/cache"
+                                    >
+                                        <Database className="h-4 w-4" />
+                                        <span className="text-[10px] font-bold uppercase tracking-widest hidden md:inline">Cache</span>
+                                    </button>
+                                )}
                                 <button
                                     onClick={async () => {
                                         try {
@@ -758,6 +865,7 @@ export default function AssetInventory({ onSendToRepeater }: AssetInventoryProps
                                 { id: "details", label: "Overview", icon: Globe },
                                 { id: "findings", label: "Findings", icon: ShieldAlert, count: findings.length },
                                 { id: "history", label: "History", icon: Clock, count: history.length },
+                                { id: "chat", label: "AI Chat", icon: MessageSquare },
                             ].map(tab => (
                                 <button
                                     key={tab.id}
@@ -947,6 +1055,8 @@ export default function AssetInventory({ onSendToRepeater }: AssetInventoryProps
                                 </div>
                             )}
 
+                            {activeTab === "chat" && <AssetChat assetId={selectedAsset.id} />}
+
                             {activeTab === "history" && (
                                 <div className="space-y-4">
                                     <h4 className="text-xs font-black uppercase tracking-widest text-zinc-500 flex items-center gap-2">
@@ -0,0 +1,161 @@
+import { useState, useEffect } from "react";
+import { Button } from "./ui/button";
+import { ScrollText, ShieldCheck, ShieldAlert, Download, ChevronLeft, ChevronRight } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { cn } from "../lib/utils";
+import { AuditFacets, AuditIntegrity, AuditPage, AuditQuery, User } from "../types";
+
+const PAGE_SIZE = 50;
+
+const inputClass = "h-9 bg-zinc-950 border border-white/10 rounded-lg px-3 text-xs text-white outline-none focus:border-brand-500";
+
+export default function AuditLog() {
+    const [query, setQuery] = useState<AuditQuery>({});
+    const [offset, setOffset] = useState(0);
+    const [page, setPage] = useState<AuditPage>({ entries: [], total: 0 });
+    const [facets, setFacets] = useState<AuditFacets>({ actions: [], entity_types: [] });
+    const [users, setUsers] = useState<User[]>([]);
+    const [integrity, setIntegrity] = useState<AuditIntegrity | null>(null);
+
+    useEffect(() => {
+        invoke<AuditFacets>("get_audit_facets").then(setFacets).catch(console.error);
+        invoke<User[]>("get_users").then(setUsers).catch(console.error);
+    }, []);
+
+    useEffect(() => {
+        invoke<AuditPage>("get_audit_log", { query: { ...query, offset, limit: PAGE_SIZE } }).then(setPage).catch(console.error);
+    }, [query, offset]);
+
+    const filter = (patch: AuditQuery) => {
+        setQuery({ ...query, ...patch });
+        setOffset(0);
+    };
+
+    const verify = async () => {
+        try {
+            setIntegrity(await invoke<AuditIntegrity>("verify_audit_integrity"));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const exportLog = async (format: "csv" | "json") => {
+        try {
+            const content = await invoke<string>("export_audit_log", { query, format });
+            const url = URL.createObjectURL(new Blob([content], { type: format === "csv" ? "text/csv;charset=utf-8;" : "application/json" }));
+            const link = document.createElement("a");
+            link.setAttribute("href", url);
+            link.setAttribute("download", `apisec_audit_${new Date().getTime()}.${format}`);
+            document.body.appendChild(link);
+            link.click();
+            document.body.removeChild(link);
+            URL.revokeObjectURL(url);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center justify-between gap-4">
+                <div className="flex items-center gap-3">
+                    <ScrollText className="text-brand-400 h-6 w-6" />
+                    <h3 className="text-xl font-bold text-white italic">Audit Log</h3>
+                </div>
+                <div className="flex gap-2">
+                    <Button variant="outline" size="sm" onClick={verify} className="border-white/10 text-zinc-300">
+                        <ShieldCheck size={12} className="mr-1" /> Verify
+                    </Button>
+                    <Button variant="outline" size="sm" onClick={() => exportLog("csv")} className="border-white/10 text-zinc-300">
+                        <Download size={12} className="mr-1" /> CSV
+                    </Button>
+                    <Button variant="outline" size="sm" onClick={() => exportLog("json")} className="border-white/10 text-zinc-300">
+                        <Download size={12} className="mr-1" /> JSON
+                    </Button>
+                </div>
+            </div>
+
+            {integrity && (
+                <div className={cn("p-3 rounded-xl border text-xs", integrity.valid ? "border-green-500/20 text-green-400" : "border-red-500/20 text-red-400")}>
+                    <div className="flex items-center gap-2 font-bold">
+                        {integrity.valid ? <ShieldCheck size={14} /> : <ShieldAlert size={14} />}
+                        {integrity.valid ? `All ${integrity.entries} entries verified` : `${integrity.breaks.length} breaks in ${integrity.entries} entries`}
+                    </div>
+                    {integrity.head_hash && <div className="font-mono text-[10px] text-zinc-500 break-all mt-1">Head {integrity.head_hash}</div>}
+                    {integrity.breaks.map(b => (
+                        <div key={`${b.id}-${b.reason}`} className="font-mono text-[10px] mt-1">#{b.id}: {b.reason}</div>
+                    ))}
+                </div>
+            )}
+
+            <div className="grid grid-cols-2 md:grid-cols-5 gap-2">
+                <input type="date" className={inputClass} value={query.from ?? ""} onChange={e => filter({ from: e.target.value || undefined })} title="From" />
+                <input type="date" className={inputClass} value={query.to ?? ""} onChange={e => filter({ to: e.target.value || undefined })} title="To" />
+                <select className={inputClass} value={query.user_id ?? ""} onChange={e => filter({ user_id: e.target.value ? Number(e.target.value) : undefined })}>
+                    <option value="">Any user</option>
+                    {users.map(u => (
+                        <option key={u.id} value={u.id}>{u.name}</option>
+                    ))}
+                </select>
+                <select className={inputClass} value={query.action ?? ""} onChange={e => filter({ action: e.target.value || undefined })}>
+                    <option value="">Any action</option>
+                    {facets.actions.map(a => (
+                        <option key={a} value={a}>{a}</option>
+                    ))}
+                </select>
+                <select className={inputClass} value={query.entity_type ?? ""} onChange={e => filter({ entity_type: e.target.value || undefined })}>
+                    <option value="">Any entity</option>
+                    {facets.entity_types.map(t => (
+                        <option key={t} value={t}>{t}</option>
+                    ))}
+                </select>
+            </div>
+
+            <table className="w-full text-xs font-mono">
+                <thead>
+                    <tr className="text-zinc-500 text-left">
+                        <th className="py-2">Time</th>
+                        <th className="py-2">User</th>
+                        <th className="py-2">Action</th>
+                        <th className="py-2">Entity</th>
+                        <th className="py-2">Details</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {page.entries.map(entry => (
+                        <tr key={entry.id} className="border-t border-white/5 text-zinc-300" title={entry.hash}>
+                            <td className="py-2 pr-4 whitespace-nowrap text-zinc-500">{entry.timestamp}</td>
+                            <td className="py-2 pr-4">{entry.user_name ?? "—"}</td>
+                            <td className="py-2 pr-4">{entry.action}</td>
+                            <td className="py-2 pr-4">
+                                {entry.entity_type}
+                                {entry.entity_id != null && ` #${entry.entity_id}`}
+                            </td>
+                            <td className="py-2 text-zinc-400 break-all">{entry.details}</td>
+                        </tr>
+                    ))}
+                </tbody>
+            </table>
+
+            <div className="flex items-center justify-between text-xs text-zinc-500">
+                <span>
+                    {page.total === 0 ? "No entries" : `${offset + 1}–${Math.min(offset + PAGE_SIZE, page.total)} of ${page.total}`}
+                </span>
+                <div className="flex gap-2">
+                    <Button variant="outline" size="sm" disabled={offset === 0} onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))} className="border-white/10">
+                        <ChevronLeft size={12} />
+                    </Button>
+                    <Button
+                        variant="outline"
+                        size="sm"
+                        disabled={offset + PAGE_SIZE >= page.total}
+                        onClick={() => setOffset(offset + PAGE_SIZE)}
+                        className="border-white/10"
+                    >
+                        <ChevronRight size={12} />
+                    </Button>
+                </div>
+            </div>
+        </div>
+    );
+}
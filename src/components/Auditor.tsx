@@ -1,9 +1,13 @@
-import { useState, useEffect } from "react";
+import { useState, useEffect, useRef } from "react";
 import { Button } from "./ui/button";
-import { Play, ShieldAlert, Zap, Globe, Gauge, Activity, Terminal, ChevronRight } from "lucide-react";
+import { Play, ShieldAlert, Zap, Globe, Gauge, Activity, Terminal, ChevronRight, Square } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { cn } from "../lib/utils";
+import UnauthScan from "./UnauthScan";
+import ScanProfiles, { ScanProfileSelect } from "./ScanProfiles";
+import BatchScan from "./BatchScan";
+import RateLimitBypass from "./RateLimitBypass";
 import { FileCode, Trash2, Plus, FileJson, AlertCircle } from "lucide-react";
 
 interface ApiSpec {
@@ -11,24 +15,59 @@ interface ApiSpec {
     name: string;
     content: string;
     version?: string;
+    validation_status?: "valid" | "invalid";
+    validation_error?: string;
 }
 
 interface RateLimitResult {
-    url: String;
+    /** Job id the test ran under */
+    id: string;
+    url: string;
+    duration_secs: number;
+    /** Requests sent; fewer than planned when cancelled */
     total_requests: number;
     success_count: number;
     rate_limited_count: number;
+    /** Timeouts and connection failures */
+    error_count: number;
     avg_latency_ms: number;
+    target_rps: number;
+    /** Requests sent per second over the sending window */
+    achieved_rps: number;
     is_vulnerable: boolean;
+    cancelled: boolean;
+    created_at?: string;
+}
+
+interface RateLimitProgress {
+    job_id: string;
+    sent: number;
+    completed: number;
+    total: number;
+    success_count: number;
+    rate_limited_count: number;
+    error_count: number;
+    /** Requests sent per second since the previous update */
+    current_rps: number;
+}
+
+interface RateLimitComplete {
+    job_id: string;
+    result?: RateLimitResult;
+    error?: string;
 }
 
 export default function Auditor() {
     const [url, setUrl] = useState("");
     const [rps, setRps] = useState(10);
     const [duration, setDuration] = useState(30);
-    const [isTesting, setIsTesting] = useState(false);
-    const [progress, setProgress] = useState({ current: 0, total: 0 });
+    const [scanProfileId, setScanProfileId] = useState<number | null>(null);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [progress, setProgress] = useState<RateLimitProgress | null>(null);
     const [results, setResults] = useState<RateLimitResult | null>(null);
+    const [history, setHistory] = useState<RateLimitResult[]>([]);
+    const jobRef = useRef<string | null>(null);
+    const isTesting = !!jobId;
     const [specs, setSpecs] = useState<ApiSpec[]>([]);
 
     const loadSpecs = async () => {
@@ -40,8 +79,12 @@ export default function Auditor() {
         }
     };
 
+    const loadHistory = () =>
+        invoke<RateLimitResult[]>("list_rate_limit_tests").then(setHistory).catch(console.error);
+
     useEffect(() => {
         loadSpecs();
+        loadHistory();
     }, []);
 
     const handleAddSpec = async () => {
@@ -82,33 +125,46 @@ export default function Auditor() {
     };
 
     useEffect(() => {
-        const unlisten = listen("rate-limit-progress", (event: any) => {
-            setProgress(event.payload);
+        const unlisten = listen<RateLimitProgress>("rate-limit-progress", (event) => {
+            if (event.payload.job_id === jobRef.current) setProgress(event.payload);
+        });
+        const unlistenComplete = listen<RateLimitComplete>("rate-limit-complete", (event) => {
+            if (event.payload.job_id !== jobRef.current) return;
+            jobRef.current = null;
+            setJobId(null);
+            setProgress(null);
+            setResults(event.payload.result ?? null);
+            if (event.payload.error) alert(event.payload.error);
+            loadHistory();
         });
         return () => {
             unlisten.then(f => f());
+            unlistenComplete.then(f => f());
         };
     }, []);
 
     const runTest = async () => {
         if (!url) return;
-        setIsTesting(true);
         setResults(null);
+        setProgress(null);
         try {
-            const res = await invoke<RateLimitResult>("run_rate_limit_test", {
+            const id = await invoke<string>("run_rate_limit_test", {
                 url,
                 rps: Number(rps),
-                duration: Number(duration)
+                duration: Number(duration),
+                scanProfileId
             });
-            setResults(res);
+            jobRef.current = id;
+            setJobId(id);
         } catch (e) {
             alert(e);
-        } finally {
-            setIsTesting(false);
-            setProgress({ current: 0, total: 0 });
         }
     };
 
+    const stopTest = () => {
+        if (jobId) invoke("cancel_fuzz", { jobId }).catch(console.error);
+    };
+
     return (
         <div className="flex flex-col h-full gap-8 animate-in fade-in slide-in-from-bottom-4 duration-700">
             <div className="flex flex-col gap-1">
@@ -162,19 +218,42 @@ export default function Auditor() {
                                 </div>
                             </div>
 
+                            <div className="space-y-2">
+                                <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Scan Profile</label>
+                                <ScanProfileSelect value={scanProfileId} onChange={setScanProfileId} />
+                            </div>
+
                             <Button
-                                onClick={runTest}
-                                disabled={isTesting || !url}
+                                onClick={isTesting ? stopTest : runTest}
+                                disabled={!isTesting && !url}
                                 className={cn(
                                     "w-full h-14 font-black flex gap-3 items-center justify-center rounded-xl shadow-lg transition-all duration-300",
                                     isTesting
-                                        ? "bg-zinc-800 text-zinc-500 cursor-not-allowed"
+                                        ? "bg-zinc-800 hover:bg-zinc-700 text-white"
                                         : "bg-brand-500 hover:bg-brand-400 text-black shadow-brand-500/20"
                                 )}
                             >
-                                {isTesting ? <Activity className="animate-spin h-5 w-5" /> : <Play fill="currentColor" size={16} />}
-                                {isTesting ? "STRESSING TARGET..." : "INITIATE AUDIT"}
+                                {isTesting ? <Square size={16} /> : <Play fill="currentColor" size={16} />}
+                                {isTesting ? "STOP TEST" : "INITIATE AUDIT"}
                             </Button>
+
+                            {history.length > 0 && (
+                                <div className="space-y-1 max-h-40 overflow-y-auto">
+                                    <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Recent Tests</label>
+                                    {history.map(test => (
+                                        <button
+                                            key={test.id}
+                                            onClick={() => setResults(test)}
+                                            className="w-full flex items-center justify-between gap-2 text-[10px] font-mono text-zinc-400 hover:text-white"
+                                        >
+                                            <span className="truncate">{test.url}</span>
+                                            <span className={cn("shrink-0 font-bold", test.is_vulnerable ? "text-red-400" : "text-green-400")}>
+                                                {test.target_rps}/s · {test.rate_limited_count}×429{test.cancelled && " · stopped"}
+                                            </span>
+                                        </button>
+                                    ))}
+                                </div>
+                            )}
                         </div>
                     </div>
 
@@ -214,6 +293,11 @@ export default function Auditor() {
                                         <div className="flex flex-col gap-0.5 min-w-0">
                                             <span className="text-[11px] font-black text-white truncate uppercase italic">{spec.name}</span>
                                             <span className="text-[9px] font-bold text-zinc-600 uppercase">Version {spec.version || "N/A"}</span>
+                                            {spec.validation_status === "invalid" && (
+                                                <span className="text-[9px] font-bold text-red-500 truncate" title={spec.validation_error}>
+                                                    Not parsed: {spec.validation_error}
+                                                </span>
+                                            )}
                                         </div>
                                         <button
                                             onClick={() => handleDeleteSpec(spec.id)}
@@ -238,11 +322,11 @@ export default function Auditor() {
                 {/* Live Console & Results */}
                 <div className="lg:col-span-2 flex flex-col gap-6">
                     <div className="flex-1 glass rounded-[32px] border border-white/5 flex flex-col overflow-hidden shadow-2xl relative">
-                        {isTesting && (
+                        {isTesting && progress && (
                             <div className="absolute top-0 left-0 right-0 h-1 bg-zinc-900 z-30">
                                 <div
                                     className="h-full bg-brand-500 shadow-[0_0_12px_#3b82f6] transition-all duration-300"
-                                    style={{ width: `${(progress.current / progress.total) * 100}%` }}
+                                    style={{ width: `${(progress.sent / progress.total) * 100}%` }}
                                 />
                             </div>
                         )}
@@ -252,9 +336,9 @@ export default function Auditor() {
                                 <Terminal className="text-brand-400 h-5 w-5" />
                                 <h3 className="text-sm font-black text-white italic uppercase tracking-widest">Auditor Real-time Feed</h3>
                             </div>
-                            {isTesting && (
+                            {isTesting && progress && (
                                 <span className="text-[10px] font-black text-brand-400 animate-pulse">
-                                    {progress.current} / {progress.total} REQUESTS SENT
+                                    {progress.sent} / {progress.total} REQUESTS SENT
                                 </span>
                             )}
                         </div>
@@ -270,28 +354,35 @@ export default function Auditor() {
                     </div>
                 </div>
             </div>
+
+            <RateLimitBypass url={url} scanProfileId={scanProfileId} />
+
+            <ScanProfiles />
+
+            <BatchScan />
+
+            <UnauthScan />
         </div>
     );
 }
 
-function leads_results(results: RateLimitResult | null, isTesting: boolean, progress: any) {
+function leads_results(results: RateLimitResult | null, isTesting: boolean, progress: RateLimitProgress | null) {
     if (isTesting) {
         return (
             <div className="space-y-2">
                 <div className="flex items-center gap-2 text-zinc-500">
-                    <span className="text-brand-500">[SYSTEM]</span> Initializing high-concurrency client...
-                </div>
-                <div className="flex items-center gap-2 text-zinc-500">
-                    <span className="text-brand-500">[SYSTEM]</span> Bypassing TLS certificate validation...
-                </div>
-                <div className="flex items-center gap-2 text-zinc-500">
-                    <span className="text-brand-500">[INJECT]</span> Flooding target with GET requests...
+                    <span className="text-brand-500">[SYSTEM]</span> Sending GET requests on a fixed schedule...
                 </div>
-                {[...Array(Math.min(10, Math.floor(progress.current / 5)))].map((_, i) => (
-                    <div key={i} className="flex items-center gap-2 text-brand-400/50">
-                        <ChevronRight size={10} /> REQ {i * 5 + 1} {"->"} 200 OK (latency: {Math.random() * 50 + 20 | 0}ms)
-                    </div>
-                ))}
+                {progress && (
+                    <>
+                        <div className="flex items-center gap-2 text-brand-400/80">
+                            <ChevronRight size={10} /> SENT {progress.sent} / {progress.total} at {progress.current_rps.toFixed(1)} req/s
+                        </div>
+                        <div className="flex items-center gap-2 text-brand-400/80">
+                            <ChevronRight size={10} /> OK {progress.success_count} | 429 {progress.rate_limited_count} | ERR {progress.error_count} | PENDING {progress.sent - progress.completed}
+                        </div>
+                    </>
+                )}
             </div>
         );
     }
@@ -299,7 +390,7 @@ function leads_results(results: RateLimitResult | null, isTesting: boolean, prog
     if (results) {
         return (
             <div className="p-6 space-y-8 animate-in fade-in zoom-in-95 duration-500">
-                <div className="grid grid-cols-2 md:grid-cols-4 gap-6">
+                <div className="grid grid-cols-2 md:grid-cols-5 gap-6">
                     <div className="glass-card flex flex-col items-center p-6 border-brand-500/10">
                         <span className="text-[10px] font-black text-zinc-500 uppercase mb-2">Total Load</span>
                         <span className="text-3xl font-black text-white">{results.total_requests}</span>
@@ -316,6 +407,14 @@ function leads_results(results: RateLimitResult | null, isTesting: boolean, prog
                         <span className="text-[10px] font-black text-zinc-500 uppercase mb-2">Avg Latency</span>
                         <span className="text-3xl font-black text-white">{results.avg_latency_ms}ms</span>
                     </div>
+                    <div className="glass-card flex flex-col items-center p-6 border-zinc-500/10">
+                        <span className="text-[10px] font-black text-zinc-500 uppercase mb-2">Achieved RPS</span>
+                        <span className={cn(
+                            "text-3xl font-black",
+                            results.achieved_rps < results.target_rps * 0.9 ? "text-yellow-400" : "text-white"
+                        )}>{results.achieved_rps.toFixed(1)}</span>
+                        <span className="text-[10px] text-zinc-500 mt-1">target {results.target_rps}{results.error_count > 0 && ` · ${results.error_count} errors`}</span>
+                    </div>
                 </div>
 
                 <div className={cn(
@@ -351,12 +450,12 @@ function leads_results(results: RateLimitResult | null, isTesting: boolean, prog
                 <div className="space-y-4">
                     <h5 className="text-[10px] font-black text-zinc-500 uppercase tracking-[0.3em]">Technical Evidence</h5>
                     <div className="bg-zinc-950 rounded-2xl p-6 border border-white/5 font-mono text-[11px] leading-relaxed text-zinc-500 max-h-48 overflow-y-auto">
-                        [AUDIT START] - {new Date().toISOString()}<br />
-                        [INFO] Client initialized with RPS={results.total_requests / (results.total_requests / results.success_count)}<br />
+                        [AUDIT START] - {results.created_at ?? new Date().toISOString()}<br />
+                        [INFO] Target RPS={results.target_rps} for {results.duration_secs}s<br />
                         [TARGET] {results.url}<br />
-                        [STATS] OK: {results.success_count} | 429: {results.rate_limited_count} | TIMEOUT: 0<br />
+                        [STATS] OK: {results.success_count} | 429: {results.rate_limited_count} | ERRORS: {results.error_count}<br />
                         [RESULT] {results.is_vulnerable ? "VULNERABLE (NONE_RATE_LIMIT)" : "SECURE"}<br />
-                        [AUDIT END]
+                        [AUDIT END]{results.cancelled && " (STOPPED EARLY)"}
                     </div>
                 </div>
             </div>
@@ -0,0 +1,171 @@
+import { useState, useEffect } from "react";
+import { Button } from "./ui/button";
+import { KeyRound, Play, Plus, Trash2 } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { cn } from "../lib/utils";
+import { Asset, AuthProfile, FuzzSummary, MatrixRow } from "../types";
+
+const statusColor = (status: number) =>
+    status >= 200 && status < 300
+        ? "text-emerald-400"
+        : status === 401 || status === 403
+            ? "text-zinc-500"
+            : "text-amber-400";
+
+export default function AuthMatrix({ environmentId }: { environmentId: number }) {
+    const [profiles, setProfiles] = useState<AuthProfile[]>([]);
+    const [assets, setAssets] = useState<Asset[]>([]);
+    const [selected, setSelected] = useState<number[]>([]);
+    const [rows, setRows] = useState<MatrixRow[]>([]);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [draft, setDraft] = useState({ name: "", privilege: 1, headers: '{"Authorization": "Bearer "}' });
+
+    const loadProfiles = () =>
+        invoke<AuthProfile[]>("list_auth_profiles", { environmentId }).then(setProfiles).catch(console.error);
+
+    useEffect(() => {
+        loadProfiles();
+        invoke<Asset[]>("get_assets").then(setAssets).catch(console.error);
+    }, [environmentId]);
+
+    useEffect(() => {
+        const unlisten = listen<[number, number, MatrixRow]>("authz-progress", (event) => {
+            setRows(prev => [...prev, event.payload[2]]);
+        });
+        const unlistenComplete = listen<FuzzSummary>("fuzz-complete", (event) => {
+            setJobId(current => {
+                if (current === event.payload.job_id && event.payload.error) alert(event.payload.error);
+                return current === event.payload.job_id ? null : current;
+            });
+        });
+        return () => {
+            unlisten.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, []);
+
+    const addProfile = async () => {
+        try {
+            await invoke("save_auth_profile", {
+                profile: { environment_id: environmentId, name: draft.name, privilege: draft.privilege, headers: JSON.parse(draft.headers || "{}") }
+            });
+            setDraft({ ...draft, name: "" });
+            loadProfiles();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const deleteProfile = async (id: number) => {
+        try {
+            await invoke("delete_auth_profile", { id });
+            loadProfiles();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const runMatrix = async () => {
+        setRows([]);
+        try {
+            setJobId(await invoke<string>("run_auth_matrix", { request: { environment_id: environmentId, asset_ids: selected } }));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    return (
+        <div className="p-6 rounded-2xl bg-zinc-900/50 border border-white/5 space-y-6">
+            <div className="flex items-center justify-between">
+                <h3 className="text-sm font-black uppercase tracking-widest text-white flex items-center gap-2">
+                    <KeyRound size={16} className="text-brand-400" />
+                    Authorization Matrix
+                </h3>
+                <Button
+                    onClick={runMatrix}
+                    disabled={!!jobId || selected.length === 0 || profiles.length < 2}
+                    className="h-10 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold"
+                >
+                    <Play size={14} className="mr-2" />
+                    {jobId ? "Running..." : `Run on ${selected.length} assets`}
+                </Button>
+            </div>
+
+            <div className="space-y-2">
+                {profiles.map(profile => (
+                    <div key={profile.id} className="flex items-center justify-between text-xs font-mono text-zinc-300">
+                        <span>
+                            <span className="text-brand-400 font-bold">{profile.privilege}</span> {profile.name}
+                            <span className="text-zinc-600"> {Object.keys(profile.headers).join(", ") || "no credentials"}</span>
+                        </span>
+                        <button onClick={() => deleteProfile(profile.id!)} className="text-red-400 hover:text-red-300">
+                            <Trash2 size={12} />
+                        </button>
+                    </div>
+                ))}
+                <div className="flex gap-2">
+                    <input
+                        placeholder="Profile (e.g. admin)"
+                        value={draft.name}
+                        onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+                        className="flex-1 h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs text-white focus:outline-none"
+                    />
+                    <input
+                        type="number"
+                        min={0}
+                        title="Privilege"
+                        value={draft.privilege}
+                        onChange={(e) => setDraft({ ...draft, privilege: Number(e.target.value) || 0 })}
+                        className="w-16 h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs text-white focus:outline-none"
+                    />
+                    <input
+                        placeholder='{"Authorization": "Bearer ..."}'
+                        value={draft.headers}
+                        onChange={(e) => setDraft({ ...draft, headers: e.target.value })}
+                        className="flex-[2] h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-mono text-white focus:outline-none"
+                    />
+                    <Button onClick={addProfile} disabled={!draft.name.trim()} className="h-10 bg-zinc-800 hover:bg-zinc-700 text-white">
+                        <Plus size={14} />
+                    </Button>
+                </div>
+            </div>
+
+            <select
+                multiple
+                value={selected.map(String)}
+                onChange={(e) => setSelected(Array.from(e.target.selectedOptions, o => Number(o.value)))}
+                className="w-full h-32 bg-zinc-950 border border-white/5 rounded-xl p-2 text-xs font-mono text-white focus:outline-none"
+            >
+                {assets.map(asset => (
+                    <option key={asset.id} value={asset.id}>{asset.method} {asset.url}</option>
+                ))}
+            </select>
+
+            {rows.length > 0 && (
+                <table className="w-full text-xs font-mono">
+                    <thead>
+                        <tr className="text-zinc-500 text-left">
+                            <th className="py-2">Endpoint</th>
+                            {rows[0].cells.map(cell => <th key={cell.profile_id} className="py-2">{cell.profile_name}</th>)}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rows.map(row => (
+                            <tr key={row.asset_id} className={cn("border-t border-white/5", row.findings.length > 0 && "bg-red-500/10")}>
+                                <td className="py-2 pr-4 text-zinc-300 break-all" title={row.findings.map(f => f.description).join("\n")}>
+                                    {row.method} {row.url}
+                                </td>
+                                {row.cells.map(cell => (
+                                    <td key={cell.profile_id} className={cn("py-2 font-bold", statusColor(cell.status))}>
+                                        {cell.status || "ERR"}
+                                    </td>
+                                ))}
+                            </tr>
+                        ))}
+                    </tbody>
+                </table>
+            )}
+        </div>
+    );
+}
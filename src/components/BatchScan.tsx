@@ -0,0 +1,189 @@
+import { useState, useEffect, useRef } from "react";
+import { Button } from "./ui/button";
+import { Layers, Play, Square, Trash2 } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { cn } from "../lib/utils";
+import { BatchScanComplete, BatchScanProgress, CheckOutcome, Folder, ScanReport } from "../types";
+import { ScanProfileSelect } from "./ScanProfiles";
+
+const inputClass = "h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs text-white focus:border-brand-500 outline-none";
+
+export default function BatchScan() {
+    const [tag, setTag] = useState("");
+    const [host, setHost] = useState("");
+    const [includeUnsafe, setIncludeUnsafe] = useState(false);
+    const [folderId, setFolderId] = useState<number | null>(null);
+    const [folders, setFolders] = useState<Folder[]>([]);
+    const [scanProfileId, setScanProfileId] = useState<number | null>(null);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [progress, setProgress] = useState({ completed: 0, total: 0 });
+    const [outcomes, setOutcomes] = useState<CheckOutcome[]>([]);
+    const [reports, setReports] = useState<ScanReport[]>([]);
+    const [report, setReport] = useState<ScanReport | null>(null);
+    const jobRef = useRef<string | null>(null);
+
+    const loadReports = () => invoke<ScanReport[]>("list_scan_reports").then(setReports).catch(console.error);
+
+    useEffect(() => {
+        loadReports();
+        invoke<Folder[]>("get_folders").then(setFolders).catch(console.error);
+        const unlisten = listen<BatchScanProgress>("batch-scan-progress", (event) => {
+            if (event.payload.job_id !== jobRef.current) return;
+            setProgress({ completed: event.payload.completed, total: event.payload.total });
+            setOutcomes(prev => [...prev, event.payload.outcome]);
+        });
+        const unlistenComplete = listen<BatchScanComplete>("batch-scan-complete", (event) => {
+            if (event.payload.job_id !== jobRef.current) return;
+            jobRef.current = null;
+            setJobId(null);
+            if (event.payload.error) alert(event.payload.error);
+            if (event.payload.report) setReport(event.payload.report);
+            loadReports();
+        });
+        return () => {
+            unlisten.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, []);
+
+    const runScan = async () => {
+        setOutcomes([]);
+        setReport(null);
+        setProgress({ completed: 0, total: 0 });
+        try {
+            const id = await invoke<string>("run_batch_scan", {
+                request: {
+                    tag: tag || undefined,
+                    host: host || undefined,
+                    folder_id: folderId ?? undefined,
+                    scan_profile_id: scanProfileId ?? undefined,
+                    include_unsafe: includeUnsafe,
+                },
+            });
+            jobRef.current = id;
+            setJobId(id);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const deleteReport = async (id: string) => {
+        try {
+            await invoke("delete_scan_report", { id });
+            if (report?.id === id) setReport(null);
+            loadReports();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const shown = report ? report.outcomes : outcomes;
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                    <div className="h-10 w-10 rounded-xl bg-brand-500/10 border border-brand-500/20 flex items-center justify-center">
+                        <Layers className="text-brand-400 h-5 w-5" />
+                    </div>
+                    <div>
+                        <h3 className="text-sm font-black text-white uppercase italic">Batch Scan</h3>
+                        <p className="text-[10px] font-bold text-zinc-500 uppercase">
+                            Runs the profile's per-asset checks across a tag, folder or host
+                        </p>
+                    </div>
+                </div>
+                {jobId ? (
+                    <Button onClick={() => invoke("cancel_fuzz", { jobId })} className="h-10 px-4 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white font-bold">
+                        <Square size={14} className="mr-2" />
+                        Stop ({progress.completed}/{progress.total})
+                    </Button>
+                ) : (
+                    <Button
+                        onClick={runScan}
+                        disabled={!tag && !host && folderId === null}
+                        className="h-10 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold"
+                    >
+                        <Play size={14} className="mr-2" />
+                        Scan
+                    </Button>
+                )}
+            </div>
+
+            <div className="grid grid-cols-2 md:grid-cols-4 gap-2">
+                <input className={inputClass} placeholder="Tag" value={tag} onChange={e => setTag(e.target.value)} />
+                <input className={inputClass} placeholder="Host, e.g. api.example.com" value={host} onChange={e => setHost(e.target.value)} />
+                <select className={inputClass} value={folderId ?? ""} onChange={e => setFolderId(e.target.value ? Number(e.target.value) : null)}>
+                    <option value="">Any folder</option>
+                    {folders.map(f => (
+                        <option key={f.id} value={f.id}>{f.name}</option>
+                    ))}
+                </select>
+                <ScanProfileSelect value={scanProfileId} onChange={setScanProfileId} />
+            </div>
+            <label className="flex items-center gap-2 text-[10px] font-bold text-zinc-500 uppercase" title="Enumerate HTTP methods with PUT, PATCH and DELETE, and replay assets captured with POST, PUT or DELETE">
+                <input type="checkbox" checked={includeUnsafe} onChange={e => setIncludeUnsafe(e.target.checked)} className="accent-brand-500" />
+                Include mutating requests
+            </label>
+
+            {report && (
+                <p className="text-xs font-mono text-zinc-400">
+                    {report.profile_name}: {report.completed}/{report.check_count} checks on {report.asset_count} assets
+                    {report.failed > 0 && `, ${report.failed} failed`}
+                    {" · "}
+                    <span className="text-red-400">{report.high} high</span>, <span className="text-amber-400">{report.medium} medium</span>, {report.low} low, {report.info} info
+                    {report.cancelled && " · stopped"}
+                    {report.skipped_checks.length > 0 && ` · not batched: ${report.skipped_checks.join(", ")}`}
+                </p>
+            )}
+
+            {shown.length > 0 && (
+                <table className="w-full text-xs font-mono">
+                    <thead>
+                        <tr className="text-zinc-500 text-left">
+                            <th className="py-2">Endpoint</th>
+                            <th className="py-2">Check</th>
+                            <th className="py-2">Findings</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {shown.map((outcome, i) => (
+                            <tr key={i} className={cn("border-t border-white/5", outcome.findings.length > 0 && "bg-red-500/10")}>
+                                <td className="py-2 pr-4 text-zinc-300 break-all">{outcome.url}</td>
+                                <td className="py-2 pr-4 text-zinc-400">{outcome.check}</td>
+                                <td
+                                    className={cn("py-2", outcome.error ? "text-amber-400" : "text-zinc-300")}
+                                    title={outcome.findings.map(f => f.name).join("\n")}
+                                >
+                                    {outcome.error ?? outcome.findings.length}
+                                </td>
+                            </tr>
+                        ))}
+                    </tbody>
+                </table>
+            )}
+
+            {reports.length > 0 && (
+                <div className="space-y-1 max-h-40 overflow-y-auto">
+                    <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Reports</label>
+                    {reports.map(r => (
+                        <div key={r.id} className="flex items-center justify-between gap-2 text-[10px] font-mono text-zinc-400">
+                            <button onClick={() => setReport(r)} className="truncate text-left hover:text-white">
+                                {r.created_at} · {r.profile_name} · {r.asset_count} assets
+                            </button>
+                            <span className="flex items-center gap-2 shrink-0">
+                                <span className={cn("font-bold", r.high > 0 ? "text-red-400" : "text-green-400")}>
+                                    {r.high + r.medium + r.low + r.info} findings
+                                </span>
+                                <button onClick={() => deleteReport(r.id)} title="Delete report" className="hover:text-red-400">
+                                    <Trash2 size={12} />
+                                </button>
+                            </span>
+                        </div>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+}
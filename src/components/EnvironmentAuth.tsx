@@ -0,0 +1,126 @@
+import { useState, useEffect } from "react";
+import { Button } from "./ui/button";
+import { KeyRound } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { AuthConfig } from "../types";
+
+const inputClass = "w-full h-10 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs text-white focus:border-brand-500 outline-none";
+
+const emptyConfig = (type: AuthConfig["type"]): AuthConfig => {
+    switch (type) {
+        case "bearer":
+            return { type, token: "" };
+        case "api_key":
+            return { type, header: "X-Api-Key", value: "" };
+        case "oauth2":
+            return { type, grant: "client_credentials", token_url: "", client_id: "", client_secret: "", username: "", password: "", scope: "" };
+        default:
+            return { type: "none" };
+    }
+};
+
+/** Credentials injected into requests while the environment is active. */
+export default function EnvironmentAuth({ environmentId }: { environmentId: number }) {
+    const [config, setConfig] = useState<AuthConfig>({ type: "none" });
+    const [status, setStatus] = useState<string | null>(null);
+
+    useEffect(() => {
+        setStatus(null);
+        invoke<AuthConfig>("get_environment_auth", { environmentId }).then(setConfig).catch(console.error);
+    }, [environmentId]);
+
+    const save = async () => {
+        try {
+            await invoke("set_environment_auth", { environmentId, auth: config });
+            setStatus("Saved");
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const test = async () => {
+        try {
+            setStatus(await invoke<string>("test_environment_auth", { environmentId }));
+        } catch (e) {
+            setStatus(String(e));
+        }
+    };
+
+    return (
+        <div className="p-6 rounded-2xl bg-zinc-900/50 border border-white/5 space-y-4">
+            <div className="flex items-center justify-between">
+                <h3 className="text-sm font-black uppercase tracking-widest text-white flex items-center gap-2">
+                    <KeyRound size={16} className="text-brand-400" />
+                    Authentication
+                </h3>
+                <select
+                    value={config.type}
+                    onChange={e => setConfig(emptyConfig(e.target.value as AuthConfig["type"]))}
+                    className="h-9 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-bold text-white outline-none"
+                >
+                    <option value="none">None</option>
+                    <option value="bearer">Bearer token</option>
+                    <option value="api_key">API key header</option>
+                    <option value="oauth2">OAuth2</option>
+                </select>
+            </div>
+            <p className="text-[10px] font-bold text-zinc-500 uppercase">
+                Added to replayed, fuzzed and scanned requests that don't set the header themselves
+            </p>
+
+            {config.type === "bearer" && (
+                <input className={inputClass} placeholder="Token, may use {{variables}}" value={config.token} onChange={e => setConfig({ ...config, token: e.target.value })} />
+            )}
+            {config.type === "api_key" && (
+                <div className="grid grid-cols-2 gap-2">
+                    <input className={inputClass} placeholder="Header" value={config.header} onChange={e => setConfig({ ...config, header: e.target.value })} />
+                    <input className={inputClass} placeholder="Value" value={config.value} onChange={e => setConfig({ ...config, value: e.target.value })} />
+                </div>
+            )}
+            {config.type === "oauth2" && (
+                <div className="grid grid-cols-2 gap-2">
+                    <select className={inputClass} value={config.grant} onChange={e => setConfig({ ...config, grant: e.target.value as typeof config.grant })}>
+                        <option value="client_credentials">Client credentials</option>
+                        <option value="password">Password</option>
+                    </select>
+                    <input className={inputClass} placeholder="Token URL" value={config.token_url} onChange={e => setConfig({ ...config, token_url: e.target.value })} />
+                    <input className={inputClass} placeholder="Client ID" value={config.client_id} onChange={e => setConfig({ ...config, client_id: e.target.value })} />
+                    <input
+                        className={inputClass}
+                        type="password"
+                        placeholder="Client secret"
+                        value={config.client_secret}
+                        onChange={e => setConfig({ ...config, client_secret: e.target.value })}
+                    />
+                    {config.grant === "password" && (
+                        <>
+                            <input className={inputClass} placeholder="Username" value={config.username} onChange={e => setConfig({ ...config, username: e.target.value })} />
+                            <input
+                                className={inputClass}
+                                type="password"
+                                placeholder="Password"
+                                value={config.password}
+                                onChange={e => setConfig({ ...config, password: e.target.value })}
+                            />
+                        </>
+                    )}
+                    <input className={inputClass} placeholder="Scope" value={config.scope} onChange={e => setConfig({ ...config, scope: e.target.value })} />
+                </div>
+            )}
+
+            <div className="flex items-center justify-between gap-4">
+                <span className="text-[10px] font-mono text-zinc-400 truncate">{status}</span>
+                <div className="flex gap-2 shrink-0">
+                    {config.type !== "none" && (
+                        <Button onClick={test} className="h-9 px-4 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white font-bold">
+                            Test
+                        </Button>
+                    )}
+                    <Button onClick={save} className="h-9 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">
+                        Save
+                    </Button>
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -1,8 +1,11 @@
 import { useState, useEffect } from "react";
 import { Button } from "./ui/button";
-import { Globe, Plus, Trash2, Check, Zap } from "lucide-react";
+import { Globe, Plus, Trash2, Check, Zap, Download, Upload } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { cn } from "../lib/utils";
+import AuthMatrix from "./AuthMatrix";
+import VariablePreview from "./VariablePreview";
+import EnvironmentAuth from "./EnvironmentAuth";
 
 interface Environment {
     id: number;
@@ -10,13 +13,14 @@ interface Environment {
     base_url: string;
     variables: string;
     is_active: boolean;
+    parent_id?: number;
 }
 
 export default function Environments() {
     const [environments, setEnvironments] = useState<Environment[]>([]);
     const [activeEnv, setActiveEnv] = useState<Environment | null>(null);
     const [showCreate, setShowCreate] = useState(false);
-    const [newEnv, setNewEnv] = useState({ name: "", base_url: "", variables: "{}" });
+    const [newEnv, setNewEnv] = useState({ name: "", base_url: "", variables: "{}", parent_id: null as number | null });
 
     useEffect(() => {
         loadEnvironments();
@@ -38,13 +42,14 @@ export default function Environments() {
             await invoke("create_environment", {
                 name: newEnv.name,
                 baseUrl: newEnv.base_url,
-                variables: newEnv.variables
+                variables: newEnv.variables,
+                parentId: newEnv.parent_id ?? undefined
             });
-            setNewEnv({ name: "", base_url: "", variables: "{}" });
+            setNewEnv({ name: "", base_url: "", variables: "{}", parent_id: null });
             setShowCreate(false);
             loadEnvironments();
         } catch (e) {
-            console.error("Failed to create environment:", e);
+            alert(e);
         }
     };
 
@@ -66,6 +71,34 @@ export default function Environments() {
         }
     };
 
+    const handleExport = async (env: Environment) => {
+        try {
+            const json = await invoke<string>("export_environment_postman", { id: env.id });
+            const url = URL.createObjectURL(new Blob([json], { type: "application/json" }));
+            const link = document.createElement("a");
+            link.setAttribute("href", url);
+            link.setAttribute("download", `${env.name.replace(/\s+/g, "_")}.postman_environment.json`);
+            document.body.appendChild(link);
+            link.click();
+            document.body.removeChild(link);
+            URL.revokeObjectURL(url);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const handleImport = async (files: FileList | null) => {
+        if (!files) return;
+        try {
+            for (const file of Array.from(files)) {
+                await invoke("import_environment_postman", { content: await file.text() });
+            }
+            loadEnvironments();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
     return (
         <div className="h-full flex flex-col gap-8 p-8 animate-in fade-in slide-in-from-bottom-4 duration-700">
             {/* Header */}
@@ -77,13 +110,32 @@ export default function Environments() {
                     </h1>
                     <p className="text-sm text-zinc-500 font-bold mt-1">Manage deployment contexts and variable injection</p>
                 </div>
-                <Button
-                    onClick={() => setShowCreate(!showCreate)}
-                    className="h-12 px-6 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold shadow-lg shadow-brand-500/20"
-                >
-                    <Plus size={16} className="mr-2" />
-                    New Environment
-                </Button>
+                <div className="flex gap-3">
+                    <label
+                        className="h-12 px-6 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white text-sm font-bold flex items-center cursor-pointer"
+                        title="Import Postman environment files"
+                    >
+                        <Upload size={16} className="mr-2" />
+                        Import
+                        <input
+                            type="file"
+                            multiple
+                            accept=".json"
+                            className="hidden"
+                            onChange={e => {
+                                handleImport(e.target.files);
+                                e.target.value = "";
+                            }}
+                        />
+                    </label>
+                    <Button
+                        onClick={() => setShowCreate(!showCreate)}
+                        className="h-12 px-6 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold shadow-lg shadow-brand-500/20"
+                    >
+                        <Plus size={16} className="mr-2" />
+                        New Environment
+                    </Button>
+                </div>
             </div>
 
             {/* Active Environment Badge */}
@@ -102,6 +154,12 @@ export default function Environments() {
                 </div>
             )}
 
+            {activeEnv && <EnvironmentAuth environmentId={activeEnv.id} />}
+
+            {activeEnv && <AuthMatrix environmentId={activeEnv.id} />}
+
+            <VariablePreview />
+
             {/* Create Form */}
             {showCreate && (
                 <div className="p-6 rounded-2xl bg-zinc-900/50 border border-white/5 space-y-4">
@@ -113,9 +171,19 @@ export default function Environments() {
                         onChange={(e) => setNewEnv({ ...newEnv, name: e.target.value })}
                         className="w-full h-12 bg-zinc-950 border border-white/5 rounded-xl px-4 text-white focus:outline-none focus:ring-2 focus:ring-brand-500/50"
                     />
+                    <select
+                        value={newEnv.parent_id ?? ""}
+                        onChange={(e) => setNewEnv({ ...newEnv, parent_id: e.target.value ? Number(e.target.value) : null })}
+                        className="w-full h-12 bg-zinc-950 border border-white/5 rounded-xl px-4 text-white focus:outline-none focus:ring-2 focus:ring-brand-500/50"
+                    >
+                        <option value="">No parent</option>
+                        {environments.map(env => (
+                            <option key={env.id} value={env.id}>Inherits from {env.name}</option>
+                        ))}
+                    </select>
                     <input
                         type="text"
-                        placeholder="Base URL (e.g., https://api.prod.com)"
+                        placeholder="Base URL (e.g., https://api.prod.com; empty inherits the parent's)"
                         value={newEnv.base_url}
                         onChange={(e) => setNewEnv({ ...newEnv, base_url: e.target.value })}
                         className="w-full h-12 bg-zinc-950 border border-white/5 rounded-xl px-4 text-white focus:outline-none focus:ring-2 focus:ring-brand-500/50"
@@ -154,6 +222,11 @@ export default function Environments() {
                             <div className="flex-1">
                                 <div className="text-sm font-black uppercase tracking-widest text-white mb-1">{env.name}</div>
                                 <div className="text-xs text-zinc-500 font-mono break-all">{env.base_url}</div>
+                                {env.parent_id != null && (
+                                    <div className="text-[10px] text-zinc-500 font-bold uppercase mt-1">
+                                        Inherits from {environments.find(p => p.id === env.parent_id)?.name ?? "?"}
+                                    </div>
+                                )}
                             </div>
                             {env.is_active && (
                                 <div className="h-6 w-6 rounded-full bg-brand-500 flex items-center justify-center">
@@ -162,6 +235,16 @@ export default function Environments() {
                             )}
                         </div>
                         <div className="flex gap-2">
+                            <button
+                                onClick={(e) => {
+                                    e.stopPropagation();
+                                    handleExport(env);
+                                }}
+                                title="Export as Postman environment"
+                                className="h-8 px-3 rounded-lg bg-zinc-800 border border-white/5 text-zinc-300 text-xs font-bold hover:bg-zinc-700 transition-all"
+                            >
+                                <Download size={12} />
+                            </button>
                             <button
                                 onClick={(e) => {
                                     e.stopPropagation();
@@ -0,0 +1,79 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Layers, Loader2 } from "lucide-react";
+import { Button } from "./ui/button";
+import { cn } from "../lib/utils";
+import { FindingCluster } from "../types";
+
+const severityColor: Record<string, string> = {
+    Critical: "text-red-500",
+    High: "text-orange-400",
+    Medium: "text-amber-400",
+    Low: "text-brand-400",
+    Info: "text-zinc-400",
+};
+
+export default function FindingClusters() {
+    const [clusters, setClusters] = useState<FindingCluster[] | null>(null);
+    const [threshold, setThreshold] = useState(0.92);
+    const [loading, setLoading] = useState(false);
+
+    const load = async () => {
+        setLoading(true);
+        try {
+            setClusters(await invoke<FindingCluster[]>("get_finding_clusters", { threshold, minSize: null }));
+        } catch (e) {
+            alert(e);
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center justify-between gap-4">
+                <h3 className="text-xl font-bold flex items-center gap-2 italic">
+                    <Layers className="h-5 w-5 text-accent-400" />
+                    Finding Clusters
+                </h3>
+                <div className="flex items-center gap-3">
+                    <label className="flex items-center gap-2 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                        Similarity {threshold.toFixed(2)}
+                        <input
+                            type="range"
+                            min={0.8}
+                            max={0.99}
+                            step={0.01}
+                            value={threshold}
+                            onChange={(e) => setThreshold(parseFloat(e.target.value))}
+                        />
+                    </label>
+                    <Button onClick={load} disabled={loading} className="h-9 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">
+                        {loading && <Loader2 size={14} className="mr-2 animate-spin" />}
+                        {clusters ? "Recluster" : "Cluster Findings"}
+                    </Button>
+                </div>
+            </div>
+
+            {clusters && clusters.length === 0 && (
+                <p className="text-sm text-zinc-500">No near-duplicate findings at this similarity.</p>
+            )}
+            {clusters && clusters.length > 0 && (
+                <div className="max-h-96 overflow-y-auto space-y-2">
+                    {clusters.map(c => (
+                        <div key={c.representative_id} className="p-3 rounded-xl bg-zinc-950 border border-white/5 space-y-1">
+                            <div className="flex items-center justify-between gap-4">
+                                <span className={cn("text-sm font-bold", severityColor[c.severity] ?? "text-zinc-400")}>{c.name}</span>
+                                <span className="text-[10px] font-black text-zinc-500 uppercase tracking-widest shrink-0">
+                                    {c.finding_ids.length} findings · {c.asset_count} assets
+                                </span>
+                            </div>
+                            <div className="text-[10px] font-mono text-zinc-500">{c.rule_ids.join(", ")}</div>
+                            {c.sample_match && <code className="block text-[10px] bg-black/40 p-2 rounded text-brand-300 break-all">{c.sample_match}</code>}
+                        </div>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+}
@@ -1,25 +1,42 @@
 import { useState, useEffect } from "react";
 import { Button } from "./ui/button";
-import { Zap, Play, Activity, ShieldAlert, Terminal, ChevronRight, Search, Bug } from "lucide-react";
+import { Zap, Play, Square, Activity, ShieldAlert, Terminal, ChevronRight, Search, Bug, Radio, Sparkles } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { cn } from "../lib/utils";
-import { Asset } from "../types";
+import Intruder from "./Intruder";
+import { Asset, FuzzSummary, InsertionPoint, OobInteraction, OobStatus, Pacing, PayloadSuggestion, ResponseDiff, WordlistSummary } from "../types";
 
 interface FuzzResult {
     payload: string;
     status: number;
     time_ms: number;
     finding: any | null;
+    position?: InsertionPoint;
+    diff?: ResponseDiff;
 }
 
+const describePosition = (p: InsertionPoint) =>
+    `#${p.index + 1} ${p.type === "header" ? p.name : p.type} (${p.original})`;
+
 export default function Fuzzer() {
     const [assets, setAssets] = useState<Asset[]>([]);
     const [selectedAsset, setSelectedAsset] = useState<Asset | null>(null);
+    const [targetUrl, setTargetUrl] = useState("");
+    const [targetBody, setTargetBody] = useState("");
     const [attackType, setAttackType] = useState("sql_injection");
+    const [wordlists, setWordlists] = useState<WordlistSummary[]>([]);
+    const [wordlistId, setWordlistId] = useState<number | null>(null);
     const [isFuzzing, setIsFuzzing] = useState(false);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [summary, setSummary] = useState<FuzzSummary | null>(null);
     const [results, setResults] = useState<FuzzResult[]>([]);
     const [progress, setProgress] = useState({ current: 0, total: 0 });
+    const [pacing, setPacing] = useState<Pacing>({ requests_per_second: 0, delay_ms: 0, jitter_ms: 0, max_retries: 3 });
+    const [oobStatus, setOobStatus] = useState<OobStatus | null>(null);
+    const [oobHits, setOobHits] = useState<OobInteraction[]>([]);
+    const [suggesting, setSuggesting] = useState(false);
+    const [hypotheses, setHypotheses] = useState<string[]>([]);
 
     useEffect(() => {
         const loadAssets = async () => {
@@ -27,6 +44,9 @@ export default function Fuzzer() {
             setAssets(res);
         };
         loadAssets();
+        invoke<WordlistSummary[]>("list_wordlists").then(setWordlists).catch(console.error);
+        invoke<OobStatus>("get_oob_status").then(setOobStatus).catch(console.error);
+        invoke<OobInteraction[]>("list_oob_interactions").then(setOobHits).catch(console.error);
 
         const unlisten = listen("fuzz-progress", (event: any) => {
             const [current, total, result] = event.payload;
@@ -34,32 +54,87 @@ export default function Fuzzer() {
             setResults(prev => [...prev, result]);
         });
 
+        const unlistenComplete = listen<FuzzSummary>("fuzz-complete", (event) => {
+            setSummary(event.payload);
+            setJobId(null);
+            setIsFuzzing(false);
+            if (event.payload.error) alert(event.payload.error);
+        });
+
+        const unlistenOob = listen<OobInteraction>("oob-interaction", (event) => {
+            setOobHits(prev => [event.payload, ...prev]);
+        });
+
         return () => {
             unlisten.then(f => f());
+            unlistenComplete.then(f => f());
+            unlistenOob.then(f => f());
         };
     }, []);
 
+    const buildTask = (asset: Asset) => ({
+        url: targetUrl || asset.url,
+        method: asset.method || "GET",
+        headers: {},
+        body: targetBody || asset.req_body,
+        pacing
+    });
+
+    const suggestPayloads = async () => {
+        if (!selectedAsset) return;
+        setSuggesting(true);
+        try {
+            const suggestion = await invoke<PayloadSuggestion>("ai_suggest_payloads", {
+                task: buildTask(selectedAsset),
+                attackType,
+                saveAs: `AI ${selectedAsset.method || "GET"} ${targetUrl || selectedAsset.url}`
+            });
+            setHypotheses(suggestion.hypotheses);
+            setWordlists(await invoke<WordlistSummary[]>("list_wordlists"));
+            if (suggestion.wordlist_id) setWordlistId(suggestion.wordlist_id);
+        } catch (e) {
+            alert(e);
+        } finally {
+            setSuggesting(false);
+        }
+    };
+
     const startFuzzing = async () => {
         if (!selectedAsset) return;
         setResults([]);
+        setSummary(null);
         setIsFuzzing(true);
         try {
-            await invoke("run_active_fuzz", {
-                task: {
-                    url: selectedAsset.url,
-                    method: selectedAsset.method || "GET",
-                    headers: {},
-                    body: selectedAsset.req_body
-                },
-                attackType
+            const id = await invoke<string>("run_active_fuzz", {
+                task: buildTask(selectedAsset),
+                attackType,
+                wordlistId
             });
+            setJobId(id);
         } catch (e) {
             alert(e);
-        } finally {
             setIsFuzzing(false);
         }
     };
 
+    const stopFuzzing = async () => {
+        if (!jobId) return;
+        try {
+            await invoke("cancel_fuzz", { jobId });
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    const toggleOob = async () => {
+        try {
+            await invoke(oobStatus?.running ? "stop_oob_listener" : "start_oob_listener");
+            setOobStatus(await invoke<OobStatus>("get_oob_status"));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
     return (
         <div className="flex flex-col h-full gap-8 animate-in fade-in slide-in-from-bottom-4 duration-700">
             <div className="flex flex-col gap-1">
@@ -83,7 +158,12 @@ export default function Fuzzer() {
                                 <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Target Asset</label>
                                 <select
                                     className="w-full h-12 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs font-mono text-white outline-none focus:border-accent-500 transition-all"
-                                    onChange={(e) => setSelectedAsset(assets.find(a => a.id === Number(e.target.value)) || null)}
+                                    onChange={(e) => {
+                                        const asset = assets.find(a => a.id === Number(e.target.value)) || null;
+                                        setSelectedAsset(asset);
+                                        setTargetUrl(asset?.url || "");
+                                        setTargetBody(asset?.req_body || "");
+                                    }}
                                 >
                                     <option value="">Select an asset...</option>
                                     {assets.map(asset => (
@@ -92,12 +172,40 @@ export default function Fuzzer() {
                                 </select>
                             </div>
 
+                            {selectedAsset && (
+                                <div className="space-y-2">
+                                    <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Insertion Points</label>
+                                    <input
+                                        className="w-full h-12 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs font-mono text-white outline-none focus:border-accent-500 transition-all"
+                                        value={targetUrl}
+                                        onChange={(e) => setTargetUrl(e.target.value)}
+                                    />
+                                    <textarea
+                                        className="w-full h-24 bg-zinc-950 border border-white/5 rounded-xl p-4 text-xs font-mono text-white outline-none focus:border-accent-500 transition-all resize-none"
+                                        placeholder="Request body"
+                                        value={targetBody}
+                                        onChange={(e) => setTargetBody(e.target.value)}
+                                    />
+                                    <p className="text-[9px] font-bold text-zinc-600 uppercase">Wrap values in § to fuzz them, e.g. ?id=§42§</p>
+                                </div>
+                            )}
+
                             <div className="space-y-2">
                                 <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Attack Vector</label>
                                 <div className="grid grid-cols-1 gap-2">
                                     {[
                                         { id: "sql_injection", name: "SQL Injection", desc: "Test for database logic leaks" },
                                         { id: "xss", name: "Reflected XSS", desc: "Test for script reflection" },
+                                        { id: "path_traversal", name: "Path Traversal", desc: "Test for file system escapes" },
+                                        { id: "ssti", name: "Template Injection", desc: "Test for server-side evaluation" },
+                                        { id: "command_injection", name: "Command Injection", desc: "Test for shell execution" },
+                                        { id: "crlf", name: "CRLF Injection", desc: "Test for header splitting" },
+                                        { id: "xxe", name: "XML External Entity", desc: "Test for entity expansion" },
+                                        { id: "header_injection", name: "Header Attacks", desc: "Spoof forwarding, Host and override headers" },
+                                        { id: "ssrf", name: "Blind SSRF", desc: "Detect callbacks to the OOB listener" },
+                                        { id: "graphql", name: "GraphQL", desc: "Probe introspection, depth, alias and batch limits" },
+                                        { id: "content_type", name: "Content-Type", desc: "Resend the body as XML, form, text and with charset tricks" },
+                                        { id: "open_redirect", name: "Open Redirect", desc: "Inject external URLs into redirect parameters" },
                                     ].map(type => (
                                         <button
                                             key={type.id}
@@ -116,6 +224,58 @@ export default function Fuzzer() {
                                 </div>
                             </div>
 
+                            <div className="space-y-2">
+                                <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Payload List</label>
+                                <select
+                                    className="w-full h-12 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs font-mono text-white outline-none focus:border-accent-500 transition-all"
+                                    value={wordlistId ?? ""}
+                                    onChange={(e) => setWordlistId(e.target.value ? Number(e.target.value) : null)}
+                                >
+                                    <option value="">Built-in payloads</option>
+                                    {wordlists.map(list => (
+                                        <option key={list.id} value={list.id}>{list.category} / {list.name} ({list.payload_count})</option>
+                                    ))}
+                                </select>
+                                <Button
+                                    onClick={suggestPayloads}
+                                    disabled={!selectedAsset || suggesting}
+                                    variant="outline"
+                                    className="w-full h-10 rounded-xl border-white/5 bg-zinc-950 text-zinc-400 hover:text-white text-xs font-bold"
+                                >
+                                    <Sparkles size={14} className="mr-2" />
+                                    {suggesting ? "Asking model..." : "Suggest with AI"}
+                                </Button>
+                                {hypotheses.length > 0 && (
+                                    <ul className="text-[10px] text-zinc-400 space-y-1 list-disc pl-4">
+                                        {hypotheses.map((h, i) => <li key={i}>{h}</li>)}
+                                    </ul>
+                                )}
+                            </div>
+
+                            <div className="space-y-2">
+                                <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Pacing</label>
+                                <div className="grid grid-cols-4 gap-2">
+                                    {([
+                                        ["requests_per_second", "Req/s"],
+                                        ["delay_ms", "Delay ms"],
+                                        ["jitter_ms", "Jitter ms"],
+                                        ["max_retries", "Retries"],
+                                    ] as [keyof Pacing, string][]).map(([key, label]) => (
+                                        <div key={key} className="space-y-1">
+                                            <input
+                                                type="number"
+                                                min={0}
+                                                className="w-full h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-mono text-white outline-none focus:border-accent-500 transition-all"
+                                                value={pacing[key]}
+                                                onChange={(e) => setPacing({ ...pacing, [key]: Math.max(0, Number(e.target.value) || 0) })}
+                                            />
+                                            <span className="text-[9px] font-bold text-zinc-600 uppercase">{label}</span>
+                                        </div>
+                                    ))}
+                                </div>
+                                <p className="text-[9px] font-bold text-zinc-600 uppercase">0 req/s is unlimited; retries back off on 429 and 503</p>
+                            </div>
+
                             <Button
                                 onClick={startFuzzing}
                                 disabled={isFuzzing || !selectedAsset}
@@ -129,9 +289,43 @@ export default function Fuzzer() {
                                 {isFuzzing ? <Activity className="animate-spin h-5 w-5" /> : <Play fill="currentColor" size={16} />}
                                 {isFuzzing ? "FUZZING TARGET..." : "EXECUTE ATTACK"}
                             </Button>
+                            {isFuzzing && jobId && (
+                                <Button
+                                    onClick={stopFuzzing}
+                                    className="w-full h-12 font-black flex gap-3 items-center justify-center rounded-xl bg-zinc-900 hover:bg-zinc-800 text-red-400 border border-red-500/20"
+                                >
+                                    <Square fill="currentColor" size={14} />
+                                    STOP
+                                </Button>
+                            )}
                         </div>
                     </div>
 
+                    <div className="glass-card space-y-4">
+                        <div className="flex items-center justify-between">
+                            <div className="flex items-center gap-3">
+                                <Radio className={cn("h-5 w-5", oobStatus?.running ? "text-emerald-400 animate-pulse" : "text-zinc-600")} />
+                                <h3 className="text-sm font-black text-white uppercase italic">OOB Listener</h3>
+                            </div>
+                            <Button
+                                onClick={toggleOob}
+                                className="h-8 px-4 text-[10px] font-black rounded-lg bg-zinc-900 hover:bg-zinc-800 text-zinc-300 border border-white/5"
+                            >
+                                {oobStatus?.running ? "STOP" : "START"}
+                            </Button>
+                        </div>
+                        <p className="text-[9px] font-bold text-zinc-600 uppercase">
+                            {oobStatus?.settings.public_host
+                                ? `http://${oobStatus.settings.public_host}:${oobStatus.settings.http_port}${oobStatus.settings.domain ? ` | *.${oobStatus.settings.domain}` : ""}`
+                                : "Set a public host in settings to receive callbacks"}
+                        </p>
+                        {oobHits.slice(0, 5).map(hit => (
+                            <div key={hit.id} className="text-[10px] font-mono text-red-400 truncate">
+                                {hit.protocol.toUpperCase()} from {hit.remote_addr} | {hit.method} {hit.url}
+                            </div>
+                        ))}
+                    </div>
+
                     <div className="p-8 glass rounded-[32px] border border-brand-500/20 bg-brand-500/5 flex flex-col gap-4 relative overflow-hidden group">
                         <div className="absolute -right-8 -bottom-8 w-48 h-48 bg-brand-500/10 blur-[100px] rounded-full group-hover:bg-brand-500/20 transition-all duration-700" />
                         <ShieldAlert className="h-8 w-8 text-brand-400" />
@@ -166,6 +360,14 @@ export default function Fuzzer() {
                                     {progress.current} / {progress.total} VECTORS TESTED
                                 </span>
                             )}
+                            {!isFuzzing && summary && (
+                                <span className="text-[10px] font-black text-zinc-500">
+                                    {summary.sent} / {summary.total} SENT
+                                    {summary.skipped > 0 && ` | ${summary.skipped} SKIPPED`}
+                                    {summary.interesting > 0 && ` | ${summary.interesting} INTERESTING`}
+                                    {summary.cancelled && " | CANCELLED"}
+                                </span>
+                            )}
                         </div>
 
                         <div className="flex-1 overflow-y-auto p-4 flex flex-col font-mono text-[11px] space-y-2">
@@ -180,7 +382,9 @@ export default function Fuzzer() {
                                         "p-3 rounded-xl border flex items-center justify-between transition-all duration-300",
                                         res.finding
                                             ? "bg-red-500/10 border-red-500/30 text-red-400 animate-pulse"
-                                            : "bg-zinc-950 border-white/5 text-zinc-500"
+                                            : res.diff?.interesting
+                                                ? "bg-amber-500/10 border-amber-500/30 text-amber-400"
+                                                : "bg-zinc-950 border-white/5 text-zinc-500"
                                     )}>
                                         <div className="flex items-center gap-4 min-w-0">
                                             <div className={cn(
@@ -191,14 +395,23 @@ export default function Fuzzer() {
                                             </div>
                                             <div className="flex flex-col min-w-0">
                                                 <span className="font-bold truncate uppercase tracking-tighter italic">Payload: {res.payload}</span>
-                                                <span className="text-[9px] opacity-60">Status: {res.status} | Time: {res.time_ms}ms</span>
+                                                <span className="text-[9px] opacity-60">
+                                                    {res.position && <>Position: {describePosition(res.position)} | </>}
+                                                    Status: {res.status} | Time: {res.time_ms}ms
+                                                    {res.diff && <> | Similarity: {Math.round(res.diff.similarity * 100)}% | Δ {res.diff.length_delta}B</>}
+                                                </span>
+                                                {!res.finding && res.diff?.interesting && (
+                                                    <span className="text-[9px] opacity-80">{res.diff.reasons.join("; ")}</span>
+                                                )}
                                             </div>
                                         </div>
-                                        {res.finding && (
+                                        {res.finding ? (
                                             <div className="flex items-center gap-2 shrink-0">
                                                 <span className="text-[10px] font-black uppercase tracking-widest">Confirmed!</span>
                                                 <ChevronRight size={14} />
                                             </div>
+                                        ) : res.diff?.interesting && (
+                                            <span className="text-[10px] font-black uppercase tracking-widest shrink-0">Interesting</span>
                                         )}
                                     </div>
                                 ))
@@ -207,6 +420,8 @@ export default function Fuzzer() {
                     </div>
                 </div>
             </div>
+
+            <Intruder />
         </div>
     );
 }
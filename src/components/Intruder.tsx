@@ -0,0 +1,214 @@
+import { useState, useEffect } from "react";
+import { Button } from "./ui/button";
+import { Crosshair, Play, Plus, Square, Trash2 } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { cn } from "../lib/utils";
+import { AttackStrategy, ExtractRule, FuzzSummary, IntruderResult } from "../types";
+
+const lines = (text: string) => text.split("\n").map(l => l.replace(/\r$/, "")).filter(l => l.length > 0);
+
+/** `name=regex` per line */
+const parseExtracts = (text: string): ExtractRule[] =>
+    lines(text)
+        .filter(l => l.includes("="))
+        .map(l => ({ name: l.slice(0, l.indexOf("=")).trim(), pattern: l.slice(l.indexOf("=") + 1) }));
+
+export default function Intruder() {
+    const [method, setMethod] = useState("GET");
+    const [url, setUrl] = useState("");
+    const [headers, setHeaders] = useState("{}");
+    const [body, setBody] = useState("");
+    const [strategy, setStrategy] = useState<AttackStrategy>("sniper");
+    const [sets, setSets] = useState<string[]>([""]);
+    const [grepMatch, setGrepMatch] = useState("");
+    const [grepExtract, setGrepExtract] = useState("");
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [results, setResults] = useState<IntruderResult[]>([]);
+    const [progress, setProgress] = useState({ current: 0, total: 0 });
+
+    useEffect(() => {
+        const unlisten = listen<[number, number, IntruderResult]>("intruder-progress", (event) => {
+            const [current, total, result] = event.payload;
+            setProgress({ current, total });
+            setResults(prev => [...prev, result]);
+        });
+        const unlistenComplete = listen<FuzzSummary>("fuzz-complete", (event) => {
+            setJobId(current => {
+                if (current === event.payload.job_id && event.payload.error) alert(event.payload.error);
+                return current === event.payload.job_id ? null : current;
+            });
+        });
+        return () => {
+            unlisten.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, []);
+
+    const start = async () => {
+        setResults([]);
+        setProgress({ current: 0, total: 0 });
+        try {
+            setJobId(await invoke<string>("run_intruder", {
+                request: {
+                    task: { url, method, headers: JSON.parse(headers || "{}"), body: body || null },
+                    strategy,
+                    payload_sets: (strategy === "sniper" ? sets.slice(0, 1) : sets).map(lines),
+                    grep_match: lines(grepMatch),
+                    grep_extract: parseExtracts(grepExtract)
+                }
+            }));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const stop = async () => {
+        if (!jobId) return;
+        try {
+            await invoke("cancel_fuzz", { jobId });
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    const extractNames = Array.from(new Set(results.flatMap(r => Object.keys(r.extracts))));
+
+    return (
+        <div className="glass-card space-y-4">
+            <div className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                    <Crosshair className="h-5 w-5 text-brand-400" />
+                    <h3 className="text-sm font-black text-white uppercase italic">Intruder</h3>
+                </div>
+                {jobId ? (
+                    <Button onClick={stop} className="h-10 px-4 rounded-xl bg-red-500 hover:bg-red-400 text-black font-bold">
+                        <Square size={14} className="mr-2" />
+                        Stop ({progress.current}/{progress.total})
+                    </Button>
+                ) : (
+                    <Button onClick={start} disabled={!url.trim()} className="h-10 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">
+                        <Play size={14} className="mr-2" />
+                        Start Attack
+                    </Button>
+                )}
+            </div>
+
+            <div className="flex gap-2">
+                <input
+                    value={method}
+                    onChange={(e) => setMethod(e.target.value.toUpperCase())}
+                    className="w-24 h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-mono text-white focus:outline-none"
+                />
+                <input
+                    placeholder="https://api.example.com/login?user=§admin§"
+                    value={url}
+                    onChange={(e) => setUrl(e.target.value)}
+                    className="flex-1 h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-mono text-white focus:outline-none"
+                />
+                <select
+                    value={strategy}
+                    onChange={(e) => setStrategy(e.target.value as AttackStrategy)}
+                    className="h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs text-white focus:outline-none"
+                >
+                    <option value="sniper">Sniper</option>
+                    <option value="pitchfork">Pitchfork</option>
+                    <option value="cluster_bomb">Cluster Bomb</option>
+                </select>
+            </div>
+            <div className="grid grid-cols-2 gap-2">
+                <textarea
+                    placeholder='Headers (JSON, e.g. {"Authorization": "Bearer §token§"})'
+                    value={headers}
+                    onChange={(e) => setHeaders(e.target.value)}
+                    className="h-20 bg-zinc-950 border border-white/5 rounded-xl p-3 text-xs font-mono text-white focus:outline-none"
+                />
+                <textarea
+                    placeholder='Body, e.g. {"password": "§secret§"}'
+                    value={body}
+                    onChange={(e) => setBody(e.target.value)}
+                    className="h-20 bg-zinc-950 border border-white/5 rounded-xl p-3 text-xs font-mono text-white focus:outline-none"
+                />
+            </div>
+
+            <div className="flex gap-2 overflow-x-auto">
+                {(strategy === "sniper" ? sets.slice(0, 1) : sets).map((set, i) => (
+                    <div key={i} className="flex-1 min-w-40 space-y-1">
+                        <div className="flex items-center justify-between text-[10px] font-black uppercase tracking-widest text-zinc-500">
+                            Payload set {i + 1}
+                            {i > 0 && (
+                                <button onClick={() => setSets(sets.filter((_, j) => j !== i))} className="text-red-400 hover:text-red-300">
+                                    <Trash2 size={12} />
+                                </button>
+                            )}
+                        </div>
+                        <textarea
+                            placeholder="One payload per line"
+                            value={set}
+                            onChange={(e) => setSets(sets.map((s, j) => (j === i ? e.target.value : s)))}
+                            className="w-full h-24 bg-zinc-950 border border-white/5 rounded-xl p-3 text-xs font-mono text-white focus:outline-none"
+                        />
+                    </div>
+                ))}
+                {strategy !== "sniper" && (
+                    <Button onClick={() => setSets([...sets, ""])} className="h-24 mt-5 bg-zinc-800 hover:bg-zinc-700 text-white">
+                        <Plus size={14} />
+                    </Button>
+                )}
+            </div>
+
+            <div className="grid grid-cols-2 gap-2">
+                <textarea
+                    placeholder="Grep match: one regex per line"
+                    value={grepMatch}
+                    onChange={(e) => setGrepMatch(e.target.value)}
+                    className="h-16 bg-zinc-950 border border-white/5 rounded-xl p-3 text-xs font-mono text-white focus:outline-none"
+                />
+                <textarea
+                    placeholder={'Grep extract: name=regex per line, e.g. token="token":"([^"]+)"'}
+                    value={grepExtract}
+                    onChange={(e) => setGrepExtract(e.target.value)}
+                    className="h-16 bg-zinc-950 border border-white/5 rounded-xl p-3 text-xs font-mono text-white focus:outline-none"
+                />
+            </div>
+
+            {results.length > 0 && (
+                <div className="max-h-96 overflow-y-auto">
+                    <table className="w-full text-xs font-mono">
+                        <thead>
+                            <tr className="text-zinc-500 text-left">
+                                <th className="py-2">#</th>
+                                <th className="py-2">Payloads</th>
+                                <th className="py-2">Status</th>
+                                <th className="py-2">Length</th>
+                                <th className="py-2">Time</th>
+                                <th className="py-2">Matches</th>
+                                {extractNames.map(name => <th key={name} className="py-2">{name}</th>)}
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {results.map(res => (
+                                <tr
+                                    key={res.index}
+                                    className={cn(
+                                        "border-t border-white/5",
+                                        res.finding ? "bg-red-500/10 text-red-400" : res.matches.length > 0 || res.diff?.interesting ? "bg-amber-500/10 text-amber-400" : "text-zinc-400"
+                                    )}
+                                    title={res.finding?.description ?? res.diff?.reasons.join("; ")}
+                                >
+                                    <td className="py-1 pr-2">{res.index + 1}</td>
+                                    <td className="py-1 pr-2 break-all">{res.payloads.map(p => p ?? "·").join(" | ")}</td>
+                                    <td className="py-1 pr-2">{res.status || "ERR"}</td>
+                                    <td className="py-1 pr-2">{res.length}</td>
+                                    <td className="py-1 pr-2">{res.time_ms}ms</td>
+                                    <td className="py-1 pr-2">{res.matches.join(", ")}</td>
+                                    {extractNames.map(name => <td key={name} className="py-1 pr-2 break-all">{res.extracts[name] ?? ""}</td>)}
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </div>
+            )}
+        </div>
+    );
+}
@@ -0,0 +1,207 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Button } from "./ui/button";
+import { Loader2, Radar, Square, Plus, Check } from "lucide-react";
+import { BatchImportResult, OpenPort, PortScanSummary, ReconProgress } from "../types";
+
+interface PortScanProps {
+    /** Hosts found by subdomain enumeration */
+    hosts: string[];
+    /** Profile the scan runs under; the default one when null */
+    scanProfileId: number | null;
+}
+
+export default function PortScan({ hosts, scanProfileId }: PortScanProps) {
+    const [ports, setPorts] = useState("");
+    const [concurrency, setConcurrency] = useState(200);
+    const [timeoutMs, setTimeoutMs] = useState(1000);
+    const [open, setOpen] = useState<OpenPort[]>([]);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [progress, setProgress] = useState<ReconProgress | null>(null);
+    const [summary, setSummary] = useState<PortScanSummary | null>(null);
+    const [error, setError] = useState<string | null>(null);
+    const scanning = jobId !== null;
+
+    const hostKey = hosts.join(",");
+
+    useEffect(() => {
+        invoke<OpenPort[]>("list_open_ports", { host: null })
+            .then(rows => setOpen(rows.filter(r => hosts.includes(r.host))))
+            .catch(console.error);
+    }, [hostKey]);
+
+    useEffect(() => {
+        if (!jobId) return;
+        const unlistenOpen = listen<[string, OpenPort]>("portscan-open", (event) => {
+            const [id, port] = event.payload;
+            if (id === jobId) setOpen(prev => [...prev.filter(p => p.id !== port.id), port]);
+        });
+        const unlistenProgress = listen<ReconProgress>("portscan-progress", (event) => {
+            if (event.payload.job_id === jobId) setProgress(event.payload);
+        });
+        const unlistenComplete = listen<PortScanSummary>("portscan-complete", (event) => {
+            if (event.payload.job_id !== jobId) return;
+            setSummary(event.payload);
+            setJobId(null);
+        });
+        return () => {
+            unlistenOpen.then(f => f());
+            unlistenProgress.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, [jobId]);
+
+    const handleScan = async () => {
+        setError(null);
+        setProgress(null);
+        setSummary(null);
+        try {
+            setJobId(await invoke<string>("start_port_scan", {
+                request: { hosts, ports: ports || null, concurrency, timeout_ms: timeoutMs, scan_profile_id: scanProfileId }
+            }));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const handleStop = async () => {
+        if (!jobId) return;
+        try {
+            await invoke("cancel_recon", { jobId });
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    const promote = async (ids: number[]) => {
+        try {
+            await invoke<BatchImportResult>("promote_open_ports", { ids });
+            const rows = await invoke<OpenPort[]>("list_open_ports", { host: null });
+            setOpen(rows.filter(r => hosts.includes(r.host)));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const promotable = open.filter(p => p.url && p.asset_id === null);
+    const sorted = [...open].sort((a, b) => a.host.localeCompare(b.host) || a.port - b.port);
+
+    return (
+        <div className="glass rounded-[32px] p-6 border border-white/5 space-y-4">
+            <div className="flex items-center justify-between">
+                <h3 className="text-lg font-bold flex items-center gap-2 italic">
+                    <Radar className="h-5 w-5 text-brand-400" />
+                    Port Scan
+                </h3>
+                <span className="text-xs font-mono text-zinc-500 uppercase tracking-widest">
+                    {open.length} open
+                    {progress && ` · ${progress.checked}/${progress.total} probed`}
+                    {scanning && <Loader2 size={12} className="inline ml-2 animate-spin" />}
+                </span>
+            </div>
+
+            <div className="flex flex-wrap gap-2">
+                <input
+                    value={ports}
+                    onChange={e => setPorts(e.target.value)}
+                    placeholder="Ports, e.g. 80,443,8000-8100 (default: common ports)"
+                    disabled={scanning}
+                    className="flex-1 min-w-[240px] h-10 bg-zinc-900 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                />
+                <label className="flex items-center gap-2 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                    Threads
+                    <input
+                        type="number"
+                        min={1}
+                        max={1000}
+                        value={concurrency}
+                        onChange={e => setConcurrency(parseInt(e.target.value) || 1)}
+                        disabled={scanning}
+                        className="w-20 h-10 bg-zinc-900 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                    />
+                </label>
+                <label className="flex items-center gap-2 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                    Timeout ms
+                    <input
+                        type="number"
+                        min={50}
+                        value={timeoutMs}
+                        onChange={e => setTimeoutMs(parseInt(e.target.value) || 1000)}
+                        disabled={scanning}
+                        className="w-24 h-10 bg-zinc-900 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                    />
+                </label>
+                {scanning ? (
+                    <Button onClick={handleStop} className="h-10 bg-red-500 hover:bg-red-400 text-black font-black rounded-xl">
+                        <Square size={14} className="mr-2" /> STOP
+                    </Button>
+                ) : (
+                    <Button
+                        onClick={handleScan}
+                        disabled={hosts.length === 0}
+                        className="h-10 bg-brand-500 hover:bg-brand-400 text-black font-black rounded-xl"
+                    >
+                        SCAN {hosts.length} HOSTS
+                    </Button>
+                )}
+            </div>
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+            {summary && summary.unresolved.length > 0 && (
+                <p className="text-xs text-amber-400">Skipped unresolved hosts: {summary.unresolved.join(", ")}</p>
+            )}
+
+            {sorted.length > 0 && (
+                <div className="space-y-2">
+                    <div className="flex justify-end">
+                        <Button
+                            variant="outline"
+                            onClick={() => promote(promotable.map(p => p.id))}
+                            disabled={promotable.length === 0}
+                            className="h-8 text-xs border-white/10"
+                        >
+                            <Plus size={12} className="mr-1" /> Promote {promotable.length} web services
+                        </Button>
+                    </div>
+                    <table className="w-full text-xs">
+                        <thead className="text-[10px] text-zinc-500 uppercase tracking-widest text-left">
+                            <tr>
+                                <th className="py-2">Host</th>
+                                <th>Port</th>
+                                <th>Service</th>
+                                <th>Details</th>
+                                <th />
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {sorted.map(p => (
+                                <tr key={p.id} className="border-t border-white/5">
+                                    <td className="py-2 font-mono text-white">
+                                        {p.host}
+                                        <span className="block text-[10px] text-zinc-500">{p.ip}</span>
+                                    </td>
+                                    <td className="font-mono text-brand-400">{p.port}</td>
+                                    <td className="text-zinc-300">{p.service || "unknown"}</td>
+                                    <td className="text-zinc-400 max-w-[280px] truncate" title={p.banner || p.http_title || ""}>
+                                        {p.http_status !== null && <span className="font-mono mr-2">{p.http_status}</span>}
+                                        {p.http_title || p.banner}
+                                    </td>
+                                    <td className="text-right">
+                                        {p.asset_id !== null ? (
+                                            <span className="inline-flex items-center gap-1 text-green-400"><Check size={12} /> Asset</span>
+                                        ) : p.url && (
+                                            <button onClick={() => promote([p.id])} className="text-zinc-500 hover:text-white">
+                                                <Plus size={14} />
+                                            </button>
+                                        )}
+                                    </td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </div>
+            )}
+        </div>
+    );
+}
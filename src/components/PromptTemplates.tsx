@@ -0,0 +1,102 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { RotateCcw, Save, Sparkles } from "lucide-react";
+import { Button } from "./ui/button";
+import { cn } from "../lib/utils";
+import { PromptTemplate } from "../types";
+
+export default function PromptTemplates() {
+    const [prompts, setPrompts] = useState<PromptTemplate[]>([]);
+    const [kind, setKind] = useState("triage");
+    const [draft, setDraft] = useState("");
+
+    const load = async (select?: string) => {
+        try {
+            const list = await invoke<PromptTemplate[]>("get_prompt_templates");
+            setPrompts(list);
+            const current = list.find(p => p.kind === (select ?? kind)) ?? list[0];
+            if (current) {
+                setKind(current.kind);
+                setDraft(current.template);
+            }
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    useEffect(() => {
+        load();
+    }, []);
+
+    const selected = prompts.find(p => p.kind === kind);
+
+    const save = async () => {
+        try {
+            await invoke("set_prompt_template", { kind, template: draft });
+            await load(kind);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const reset = async () => {
+        if (!confirm("Restore the built-in prompt?")) return;
+        try {
+            await invoke("reset_prompt_template", { kind });
+            await load(kind);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center gap-3">
+                <Sparkles className="text-brand-400 h-6 w-6" />
+                <h3 className="text-xl font-bold text-white italic">AI Prompt Templates</h3>
+            </div>
+
+            <div className="flex gap-2 flex-wrap">
+                {prompts.map(p => (
+                    <button
+                        key={p.kind}
+                        onClick={() => {
+                            setKind(p.kind);
+                            setDraft(p.template);
+                        }}
+                        className={cn(
+                            "px-3 py-1.5 rounded-lg text-[10px] font-black uppercase tracking-widest transition-all",
+                            p.kind === kind ? "bg-brand-500 text-black" : "bg-zinc-900 text-zinc-500 hover:text-white"
+                        )}
+                    >
+                        {p.kind}{p.is_set && " *"}
+                    </button>
+                ))}
+            </div>
+
+            {selected && (
+                <div className="space-y-3">
+                    <p className="text-sm text-zinc-500">{selected.description}</p>
+                    <div className="flex gap-2 flex-wrap">
+                        {selected.placeholders.map(name => (
+                            <code key={name} className="text-[10px] bg-black/40 px-2 py-1 rounded text-brand-300">{`{{${name}}}`}</code>
+                        ))}
+                    </div>
+                    <textarea
+                        value={draft}
+                        onChange={e => setDraft(e.target.value)}
+                        className="w-full h-80 bg-zinc-950 border border-white/10 rounded-xl p-4 text-xs font-mono text-zinc-300 focus:border-brand-500 outline-none"
+                    />
+                    <div className="flex justify-end gap-2">
+                        <Button variant="outline" onClick={reset} disabled={!selected.is_set} className="border-white/10 text-zinc-400">
+                            <RotateCcw size={14} className="mr-2" /> Reset
+                        </Button>
+                        <Button onClick={save} disabled={draft === selected.template} className="bg-brand-500 hover:bg-brand-400 text-black font-black px-6">
+                            <Save size={14} className="mr-2" /> Save
+                        </Button>
+                    </div>
+                </div>
+            )}
+        </div>
+    );
+}
@@ -1,9 +1,10 @@
 import { useState, useEffect } from "react";
 import { Button } from "./ui/button";
-import { Play, Square, Activity, Wifi, WifiOff, Terminal, Clock, ShieldAlert, Zap, Download, ShieldCheck } from "lucide-react";
+import { Play, Square, Activity, Wifi, WifiOff, Terminal, Clock, ShieldAlert, Zap, Download, ShieldCheck, Upload } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { cn } from "../lib/utils";
+import { CaInfo, CertificateSettings, KeyAlgorithm } from "../types";
 
 interface TrafficEntry {
     method: string;
@@ -34,6 +35,17 @@ export default function Proxy() {
     const [heldItems, setHeldItems] = useState<InterceptedItem[]>([]);
     const [selectedHeld, setSelectedHeld] = useState<InterceptedItem | null>(null);
     const [editedItem, setEditedItem] = useState<InterceptedItem | null>(null);
+    const [caInfo, setCaInfo] = useState<CaInfo | null>(null);
+    const [certSettings, setCertSettings] = useState<CertificateSettings | null>(null);
+
+    useEffect(() => {
+        invoke<CaInfo>("get_ca_info").then(setCaInfo).catch(console.error);
+        invoke<CertificateSettings>("get_setting", { key: "certificates" }).then(setCertSettings).catch(console.error);
+        const unlisten = listen<CaInfo>("ca-expiring", event => setCaInfo(event.payload));
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, []);
 
     useEffect(() => {
         const unlistenTraffic = listen("proxy-traffic", (event: any) => {
@@ -99,14 +111,19 @@ export default function Proxy() {
         }
     };
 
-    const handleDownloadCA = async () => {
+    const handleDownloadCA = async (format: "pem" | "der" | "pkcs12") => {
         try {
-            const pem = await invoke<string>("get_root_ca");
-            const blob = new Blob([pem], { type: 'application/x-x509-ca-cert' });
+            let passphrase: string | null = null;
+            if (format === "pkcs12") {
+                passphrase = prompt("Passphrase to protect the CA private key");
+                if (!passphrase) return;
+            }
+            const bytes = await invoke<number[]>("export_root_ca", { format, passphrase });
+            const blob = new Blob([new Uint8Array(bytes)], { type: format === "pkcs12" ? 'application/x-pkcs12' : 'application/x-x509-ca-cert' });
             const url = window.URL.createObjectURL(blob);
             const a = document.createElement('a');
             a.href = url;
-            a.download = 'apisec-root-ca.crt';
+            a.download = { pem: 'apisec-root-ca.crt', der: 'apisec-root-ca.cer', pkcs12: 'apisec-root-ca.p12' }[format];
             document.body.appendChild(a);
             a.click();
             document.body.removeChild(a);
@@ -116,6 +133,78 @@ export default function Proxy() {
         }
     };
 
+    // Either one PKCS#12 bundle, or PEM files holding the certificate and key
+    const handleImportCA = async (files: FileList | null) => {
+        if (!files || files.length === 0) return;
+        try {
+            const list = Array.from(files);
+            const bundle = list.find(f => /\.(p12|pfx)$/i.test(f.name));
+            let info: CaInfo;
+            if (bundle) {
+                const passphrase = prompt("Passphrase for " + bundle.name);
+                if (passphrase === null) return;
+                const pkcs12 = Array.from(new Uint8Array(await bundle.arrayBuffer()));
+                info = await invoke<CaInfo>("import_ca", { pkcs12, passphrase });
+            } else {
+                const texts = await Promise.all(list.map(f => f.text()));
+                const certPem = texts.find(t => t.includes("BEGIN CERTIFICATE"));
+                const keyPem = texts.find(t => t.includes("PRIVATE KEY-----"));
+                if (!certPem || !keyPem) {
+                    alert("Select the CA certificate and its private key (PEM), or a PKCS#12 file");
+                    return;
+                }
+                let passphrase: string | null = null;
+                if (keyPem.includes("ENCRYPTED PRIVATE KEY")) {
+                    passphrase = prompt("Passphrase for the private key");
+                    if (passphrase === null) return;
+                }
+                info = await invoke<CaInfo>("import_ca", { certPem, keyPem, passphrase });
+            }
+            setCaInfo(info);
+            alert(`Now signing with ${info.subject}. Trust it on your clients if they don't already.`);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const handleResetCA = async () => {
+        try {
+            setCaInfo(await invoke<CaInfo>("reset_ca"));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const handleRotateCA = async () => {
+        if (!confirm("Generate a new CA? Clients trusting the current one will need to trust the new one.")) return;
+        try {
+            const info = await invoke<CaInfo>("rotate_ca");
+            setCaInfo(info);
+            if (info.warning) alert(info.warning);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const saveCertSettings = async (next: CertificateSettings) => {
+        try {
+            await invoke("set_setting", { key: "certificates", value: next });
+            setCertSettings(next);
+            setCaInfo(await invoke<CaInfo>("get_ca_info"));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const handleTrustCA = async (install: boolean) => {
+        try {
+            await invoke(install ? "install_ca_to_system" : "uninstall_ca_from_system");
+            alert(install ? "Root CA added to the system trust store" : "Root CA removed from the system trust store");
+        } catch (e) {
+            alert(e);
+        }
+    };
+
     const toggleProxy = async () => {
         try {
             if (isRunning) {
@@ -242,18 +331,131 @@ export default function Proxy() {
                                 </div>
                             </div>
 
-                            <div
-                                onClick={handleDownloadCA}
-                                className="p-4 rounded-xl border border-brand-500/30 bg-brand-500/5 flex items-center justify-between group hover:bg-brand-500/10 transition-all cursor-pointer"
-                            >
+                            <div className="p-4 rounded-xl border border-brand-500/30 bg-brand-500/5 flex flex-col gap-3">
                                 <div className="flex items-center gap-3">
                                     <ShieldCheck size={18} className="text-brand-400" />
                                     <div className="flex flex-col">
                                         <span className="text-xs font-bold text-white">Trust Root CA</span>
-                                        <span className="text-[10px] text-zinc-500 italic">Generate & Download CRT</span>
+                                        <span className="text-[10px] text-zinc-500 italic">Download or install into the OS trust store</span>
+                                    </div>
+                                </div>
+                                {caInfo && (
+                                    <div className="flex flex-col gap-0.5 text-[10px]">
+                                        <span className="font-mono text-zinc-300 truncate" title={caInfo.thumbprint}>
+                                            {caInfo.subject}
+                                            {caInfo.imported && <span className="ml-2 bg-accent-500/10 text-accent-400 px-1.5 py-0.5 rounded uppercase font-black">Imported</span>}
+                                        </span>
+                                        <span className={caInfo.expiring ? "text-red-400 font-bold" : "text-zinc-500"}>
+                                            {new Date(caInfo.not_after) < new Date() ? "Expired" : "Expires"} {new Date(caInfo.not_after).toLocaleDateString()}
+                                            {caInfo.expiring && !caInfo.imported && " — rotate it soon"}
+                                        </span>
                                     </div>
+                                )}
+                                {certSettings && (
+                                    <div className="grid grid-cols-3 gap-2">
+                                        {([
+                                            ["ca_valid_days", "CA days"],
+                                            ["leaf_valid_days", "Leaf days"],
+                                            ["expiry_warning_days", "Warn days"],
+                                        ] as const).map(([field, label]) => (
+                                            <label key={field} className="flex flex-col gap-1 text-[9px] font-black text-zinc-500 uppercase">
+                                                {label}
+                                                <input
+                                                    type="number"
+                                                    min={field === "expiry_warning_days" ? 0 : 1}
+                                                    max={field === "leaf_valid_days" ? 825 : 3650}
+                                                    defaultValue={certSettings[field]}
+                                                    onBlur={e => {
+                                                        const value = Number(e.target.value);
+                                                        if (value !== certSettings[field]) saveCertSettings({ ...certSettings, [field]: value });
+                                                    }}
+                                                    className="h-7 bg-zinc-900 border border-white/5 rounded-lg px-2 text-xs font-mono text-white"
+                                                />
+                                            </label>
+                                        ))}
+                                        {([
+                                            ["ca_key", "CA key (next rotation)"],
+                                            ["leaf_key", "Leaf key"],
+                                        ] as const).map(([field, label]) => (
+                                            <label key={field} className="col-span-3 flex items-center justify-between gap-2 text-[9px] font-black text-zinc-500 uppercase">
+                                                {label}
+                                                <select
+                                                    value={certSettings[field]}
+                                                    onChange={e => saveCertSettings({ ...certSettings, [field]: e.target.value as KeyAlgorithm })}
+                                                    className="h-7 bg-zinc-900 border border-white/5 rounded-lg px-2 text-xs font-mono text-white"
+                                                >
+                                                    <option value="ecdsa_p256">ECDSA P-256</option>
+                                                    <option value="rsa2048">RSA 2048</option>
+                                                    <option value="rsa4096">RSA 4096</option>
+                                                </select>
+                                            </label>
+                                        ))}
+                                        <label
+                                            className="col-span-3 flex items-center justify-between gap-2 text-[9px] font-black text-zinc-500 uppercase"
+                                            title="Copy the subject, names and validity of each server's real certificate"
+                                        >
+                                            Mirror upstream certificates
+                                            <input
+                                                type="checkbox"
+                                                checked={certSettings.mirror_upstream}
+                                                onChange={e => saveCertSettings({ ...certSettings, mirror_upstream: e.target.checked })}
+                                                className="accent-brand-500"
+                                            />
+                                        </label>
+                                    </div>
+                                )}
+                                <div className="flex flex-wrap gap-2">
+                                    {(["pem", "der", "pkcs12"] as const).map(format => (
+                                        <button
+                                            key={format}
+                                            onClick={() => handleDownloadCA(format)}
+                                            className="flex items-center gap-1 px-2 py-1 rounded-lg bg-zinc-900 text-[10px] font-black text-brand-400 uppercase hover:bg-zinc-800"
+                                        >
+                                            <Download size={10} /> {format === "pkcs12" ? "P12" : format}
+                                        </button>
+                                    ))}
+                                    <button
+                                        onClick={() => handleTrustCA(true)}
+                                        className="px-2 py-1 rounded-lg bg-brand-500/20 text-[10px] font-black text-brand-300 uppercase hover:bg-brand-500/30"
+                                    >
+                                        Install
+                                    </button>
+                                    <button
+                                        onClick={() => handleTrustCA(false)}
+                                        className="px-2 py-1 rounded-lg bg-zinc-900 text-[10px] font-black text-zinc-500 uppercase hover:text-red-400"
+                                    >
+                                        Remove
+                                    </button>
+                                    <label className="flex items-center gap-1 px-2 py-1 rounded-lg bg-zinc-900 text-[10px] font-black text-zinc-300 uppercase hover:bg-zinc-800 cursor-pointer">
+                                        <Upload size={10} /> Import CA
+                                        <input
+                                            type="file"
+                                            multiple
+                                            accept=".p12,.pfx,.pem,.crt,.cer,.key"
+                                            className="hidden"
+                                            onChange={e => {
+                                                handleImportCA(e.target.files);
+                                                e.target.value = "";
+                                            }}
+                                        />
+                                    </label>
+                                    {caInfo && !caInfo.imported && (
+                                        <button
+                                            onClick={handleRotateCA}
+                                            className="px-2 py-1 rounded-lg bg-zinc-900 text-[10px] font-black text-zinc-300 uppercase hover:bg-zinc-800"
+                                        >
+                                            Rotate
+                                        </button>
+                                    )}
+                                    {caInfo?.imported && (
+                                        <button
+                                            onClick={handleResetCA}
+                                            className="px-2 py-1 rounded-lg bg-zinc-900 text-[10px] font-black text-zinc-500 uppercase hover:text-red-400"
+                                        >
+                                            Use Generated
+                                        </button>
+                                    )}
                                 </div>
-                                <Download size={14} className="text-brand-500 group-hover:translate-y-0.5 transition-transform" />
                             </div>
                         </div>
                     </div>
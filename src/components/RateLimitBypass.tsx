@@ -0,0 +1,179 @@
+import { useState, useEffect, useRef } from "react";
+import { Button } from "./ui/button";
+import { ShieldOff, Play, Square } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { cn } from "../lib/utils";
+import { BypassAttempt, BypassComplete, BypassReport } from "../types";
+
+const verdictColor: Record<BypassAttempt["verdict"], string> = {
+    evaded: "text-red-400",
+    partial: "text-amber-400",
+    blocked: "text-green-400",
+    inconclusive: "text-zinc-400",
+    not_applicable: "text-zinc-600",
+};
+
+interface RateLimitBypassProps {
+    url: string;
+    scanProfileId: number | null;
+}
+
+/** Parses `Name: value` lines, skipping blank and malformed ones. */
+const parseHeaders = (text: string) =>
+    Object.fromEntries(
+        text
+            .split("\n")
+            .map(line => line.split(/:(.*)/s).map(s => s.trim()))
+            .filter(([name, value]) => name && value)
+            .map(([name, value]) => [name, value])
+    );
+
+export default function RateLimitBypass({ url, scanProfileId }: RateLimitBypassProps) {
+    const [headers, setHeaders] = useState("");
+    const [burst, setBurst] = useState(50);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [attempts, setAttempts] = useState<BypassAttempt[]>([]);
+    const [report, setReport] = useState<BypassReport | null>(null);
+    const [history, setHistory] = useState<BypassReport[]>([]);
+    const jobRef = useRef<string | null>(null);
+
+    const loadHistory = () =>
+        invoke<BypassReport[]>("list_rate_limit_bypasses").then(setHistory).catch(console.error);
+
+    useEffect(() => {
+        loadHistory();
+        const unlisten = listen<[number, number, BypassAttempt]>("rate-limit-bypass-progress", (event) => {
+            if (!jobRef.current) return;
+            setAttempts(prev => [...prev, event.payload[2]]);
+        });
+        const unlistenComplete = listen<BypassComplete>("rate-limit-bypass-complete", (event) => {
+            if (event.payload.job_id !== jobRef.current) return;
+            jobRef.current = null;
+            setJobId(null);
+            if (event.payload.error) alert(event.payload.error);
+            if (event.payload.report) setReport(event.payload.report);
+            loadHistory();
+        });
+        return () => {
+            unlisten.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, []);
+
+    const runTest = async () => {
+        setAttempts([]);
+        setReport(null);
+        try {
+            const id = await invoke<string>("run_rate_limit_bypass", {
+                request: { url, headers: parseHeaders(headers), burst: Number(burst), scan_profile_id: scanProfileId ?? undefined },
+            });
+            jobRef.current = id;
+            setJobId(id);
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                    <div className="h-10 w-10 rounded-xl bg-brand-500/10 border border-brand-500/20 flex items-center justify-center">
+                        <ShieldOff className="text-brand-400 h-5 w-5" />
+                    </div>
+                    <div>
+                        <h3 className="text-sm font-black text-white uppercase italic">Rate Limit Bypass</h3>
+                        <p className="text-[10px] font-bold text-zinc-500 uppercase">
+                            Triggers the limit, then retries with spoofed client IPs, path case, HTTP versions and API key variations
+                        </p>
+                    </div>
+                </div>
+                {jobId ? (
+                    <Button onClick={() => invoke("cancel_fuzz", { jobId })} className="h-10 px-4 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white font-bold">
+                        <Square size={14} className="mr-2" />
+                        Stop ({attempts.length}/5)
+                    </Button>
+                ) : (
+                    <Button onClick={runTest} disabled={!url} className="h-10 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">
+                        <Play size={14} className="mr-2" />
+                        Test Bypasses
+                    </Button>
+                )}
+            </div>
+
+            <div className="grid grid-cols-4 gap-2">
+                <textarea
+                    className="col-span-3 h-16 bg-zinc-950 border border-white/5 rounded-xl px-3 py-2 text-xs font-mono text-white focus:border-brand-500 outline-none"
+                    placeholder={"Headers sent with every request, e.g.\nX-Api-Key: k-123"}
+                    value={headers}
+                    onChange={e => setHeaders(e.target.value)}
+                />
+                <label className="space-y-1 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                    Burst
+                    <input
+                        type="number"
+                        min={1}
+                        className="w-full h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                        value={burst}
+                        onChange={e => setBurst(Number(e.target.value))}
+                    />
+                </label>
+            </div>
+
+            {history.length > 0 && (
+                <div className="space-y-1 max-h-40 overflow-y-auto">
+                    <label className="text-[10px] font-black text-zinc-500 uppercase tracking-widest block">Recent Tests</label>
+                    {history.map(past => (
+                        <button
+                            key={past.job_id}
+                            onClick={() => {
+                                setReport(past);
+                                setAttempts(past.attempts);
+                            }}
+                            disabled={!!jobId}
+                            className="w-full flex items-center justify-between gap-2 text-[10px] font-mono text-zinc-400 hover:text-white"
+                        >
+                            <span className="truncate">{past.url}</span>
+                            <span className={cn("shrink-0 font-bold", past.attempts.some(a => a.verdict === "evaded") ? "text-red-400" : "text-green-400")}>
+                                {past.attempts.filter(a => a.verdict === "evaded" || a.verdict === "partial").length} bypassed{past.cancelled && " · stopped"}
+                            </span>
+                        </button>
+                    ))}
+                </div>
+            )}
+
+            {report && (
+                <p className="text-xs font-mono text-zinc-400">
+                    {report.limit_after == null
+                        ? `No 429 within ${burst} requests; there is no limit to bypass`
+                        : `Limited after ${report.limit_after} requests`}
+                    {report.cancelled && " · stopped"}
+                </p>
+            )}
+
+            {attempts.length > 0 && (
+                <table className="w-full text-xs font-mono">
+                    <thead>
+                        <tr className="text-zinc-500 text-left">
+                            <th className="py-2">Technique</th>
+                            <th className="py-2">Through / 429 / Errors</th>
+                            <th className="py-2">Verdict</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {attempts.map(attempt => (
+                            <tr key={attempt.technique} className={cn("border-t border-white/5", attempt.verdict === "evaded" && "bg-red-500/10")}>
+                                <td className="py-2 pr-4 text-zinc-300" title={attempt.example ?? undefined}>{attempt.technique}</td>
+                                <td className="py-2 pr-4 text-zinc-400">
+                                    {attempt.success_count} / {attempt.rate_limited_count} / {attempt.error_count}
+                                </td>
+                                <td className={cn("py-2 font-bold uppercase", verdictColor[attempt.verdict])}>{attempt.verdict.replace("_", " ")}</td>
+                            </tr>
+                        ))}
+                    </tbody>
+                </table>
+            )}
+        </div>
+    );
+}
@@ -1,31 +1,252 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Button } from "./ui/button";
-import { Loader2, Globe, Shield, Terminal, Zap, ArrowRight, ExternalLink } from "lucide-react";
-
-interface ReconResult {
-    subdomain: string;
-    ip: string | null;
-    status: string;
-}
+import { Loader2, Globe, Shield, Terminal, Zap, ArrowRight, ExternalLink, Square, AlertTriangle, Plus, Trash2, History, Bell } from "lucide-react";
+import PortScan from "./PortScan";
+import ArchiveHarvest from "./ArchiveHarvest";
+import { ScanProfileSelect } from "./ScanProfiles";
+import { BatchImportResult, FingerprintSummary, HostFingerprint, HostProbe, HostTechnology, IpInfo, NewSubdomains, ProbeSummary, Technology, ReconProgress, ReconResult, ReconRun, ReconSchedule, SubdomainSummary, WordlistSummary } from "../types";
 
 export default function Recon() {
     const [domain, setDomain] = useState("");
     const [results, setResults] = useState<ReconResult[]>([]);
-    const [loading, setLoading] = useState(false);
     const [error, setError] = useState<string | null>(null);
+    const [wordlists, setWordlists] = useState<WordlistSummary[]>([]);
+    const [wordlistId, setWordlistId] = useState<number | null>(null);
+    const [concurrency, setConcurrency] = useState(50);
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [progress, setProgress] = useState<ReconProgress | null>(null);
+    const [summary, setSummary] = useState<SubdomainSummary | null>(null);
+    const [probes, setProbes] = useState<Record<string, HostProbe>>({});
+    const [probeJobId, setProbeJobId] = useState<string | null>(null);
+    const [technologies, setTechnologies] = useState<Record<string, Technology[]>>({});
+    const [fingerprintJobId, setFingerprintJobId] = useState<string | null>(null);
+    const [fingerprintSummary, setFingerprintSummary] = useState<FingerprintSummary | null>(null);
+    const [runs, setRuns] = useState<ReconRun[]>([]);
+    const [runId, setRunId] = useState<number | null>(null);
+    const [schedules, setSchedules] = useState<ReconSchedule[]>([]);
+    const [intervalHours, setIntervalHours] = useState(24);
+    const [newSubdomains, setNewSubdomains] = useState<NewSubdomains[]>([]);
+    const [ipInfo, setIpInfo] = useState<Record<string, IpInfo>>({});
+    const [enriching, setEnriching] = useState(false);
+    const [scanProfileId, setScanProfileId] = useState<number | null>(null);
+    const loading = jobId !== null;
+
+    const loadRuns = () => {
+        invoke<ReconRun[]>("list_recon_runs", { domain: null }).then(setRuns).catch(console.error);
+    };
+
+    const loadSchedules = () => {
+        invoke<ReconSchedule[]>("list_recon_schedules").then(setSchedules).catch(console.error);
+    };
+
+    useEffect(() => {
+        const unlisten = listen<NewSubdomains>("recon-new-subdomains", (event) => {
+            setNewSubdomains(prev => [event.payload, ...prev]);
+            loadRuns();
+            loadSchedules();
+        });
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, []);
+
+    useEffect(() => {
+        loadRuns();
+        loadSchedules();
+        invoke<WordlistSummary[]>("list_wordlists", { category: null }).then(setWordlists).catch(console.error);
+        invoke<HostProbe[]>("list_host_probes")
+            .then(rows => setProbes(Object.fromEntries(rows.map(p => [p.host, p]))))
+            .catch(console.error);
+        invoke<HostTechnology[]>("list_host_technologies", { host: null })
+            .then(rows => {
+                const byHost: Record<string, Technology[]> = {};
+                rows.forEach(t => { (byHost[t.host] ||= []).push(t); });
+                setTechnologies(byHost);
+            })
+            .catch(console.error);
+    }, []);
+
+    useEffect(() => {
+        if (!fingerprintJobId) return;
+        const unlistenResult = listen<[string, HostFingerprint]>("fingerprint-result", (event) => {
+            const [id, fingerprint] = event.payload;
+            if (id === fingerprintJobId) setTechnologies(prev => ({ ...prev, [fingerprint.host]: fingerprint.technologies }));
+        });
+        const unlistenComplete = listen<FingerprintSummary>("fingerprint-complete", (event) => {
+            if (event.payload.job_id !== fingerprintJobId) return;
+            setFingerprintSummary(event.payload);
+            setFingerprintJobId(null);
+        });
+        return () => {
+            unlistenResult.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, [fingerprintJobId]);
+
+    useEffect(() => {
+        if (!probeJobId) return;
+        const unlistenResult = listen<[string, HostProbe]>("probe-result", (event) => {
+            const [id, probe] = event.payload;
+            if (id === probeJobId) setProbes(prev => ({ ...prev, [probe.host]: probe }));
+        });
+        const unlistenDiscovered = listen<[string, string]>("probe-discovered", (event) => {
+            const [id, host] = event.payload;
+            if (id !== probeJobId) return;
+            setResults(prev => prev.some(r => r.subdomain === host)
+                ? prev
+                : [...prev, { subdomain: host, ip: null, status: "Certificate", ips: [] }]);
+        });
+        const unlistenComplete = listen<ProbeSummary>("probe-complete", (event) => {
+            if (event.payload.job_id === probeJobId) setProbeJobId(null);
+        });
+        return () => {
+            unlistenResult.then(f => f());
+            unlistenDiscovered.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, [probeJobId]);
+
+    useEffect(() => {
+        if (!jobId) return;
+        const unlistenHit = listen<[string, ReconResult]>("recon-subdomain", (event) => {
+            const [id, result] = event.payload;
+            if (id === jobId) setResults(prev => [...prev, result]);
+        });
+        const unlistenProgress = listen<ReconProgress>("recon-progress", (event) => {
+            if (event.payload.job_id === jobId) setProgress(event.payload);
+        });
+        const unlistenComplete = listen<SubdomainSummary>("recon-complete", (event) => {
+            if (event.payload.job_id !== jobId) return;
+            setSummary(event.payload);
+            setJobId(null);
+            setRunId(event.payload.run_id);
+            invoke<ReconResult[]>("get_recon_results", { runId: event.payload.run_id }).then(setResults).catch(console.error);
+            loadRuns();
+        });
+        return () => {
+            unlistenHit.then(f => f());
+            unlistenProgress.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, [jobId]);
 
     const handleRecon = async () => {
-        if (!domain) return;
-        setLoading(true);
+        if (!domain || loading) return;
         setError(null);
+        setResults([]);
+        setProgress(null);
+        setSummary(null);
+        setRunId(null);
+        try {
+            setJobId(await invoke<string>("start_subdomain_bruteforce", {
+                request: { domain, wordlist_id: wordlistId, concurrency }
+            }));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const handleProbe = async () => {
+        try {
+            setProbeJobId(await invoke<string>("start_host_probe", {
+                request: { hosts: results.map(r => r.subdomain), scan_profile_id: scanProfileId }
+            }));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const handleFingerprint = async () => {
+        setFingerprintSummary(null);
+        try {
+            setFingerprintJobId(await invoke<string>("start_fingerprint", {
+                request: { hosts: results.map(r => r.subdomain), scan_profile_id: scanProfileId }
+            }));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const openRun = async (id: number, runDomain: string) => {
+        if (loading) return;
+        try {
+            setResults(await invoke<ReconResult[]>("get_recon_results", { runId: id }));
+            setDomain(runDomain);
+            setRunId(id);
+            setProgress(null);
+            setSummary(null);
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const deleteRun = async (id: number) => {
+        try {
+            await invoke("delete_recon_run", { runId: id });
+            if (id === runId) {
+                setRunId(null);
+                setResults([]);
+            }
+            loadRuns();
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const promote = async (ids: number[]) => {
+        if (runId === null) return;
+        try {
+            await invoke<BatchImportResult>("promote_recon_to_assets", { ids });
+            setResults(await invoke<ReconResult[]>("get_recon_results", { runId }));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const saveSchedule = async (schedule: { domain: string; wordlist_id: number | null; concurrency: number | null; interval_hours: number; enabled: boolean }) => {
         try {
-            const res = await invoke<ReconResult[]>("enumerate_subdomains", { domain });
-            setResults(res);
+            await invoke<ReconSchedule>("save_recon_schedule", { request: schedule });
+            loadSchedules();
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const deleteSchedule = async (id: number) => {
+        try {
+            await invoke("delete_recon_schedule", { id });
+            loadSchedules();
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const promotable = results.filter(r => r.id != null && r.asset_id == null).map(r => r.id!);
+
+    const handleEnrich = async () => {
+        setEnriching(true);
+        try {
+            const rows = await invoke<IpInfo[]>("enrich_ips", { ips: results.flatMap(r => r.ips), refresh: false });
+            setIpInfo(prev => ({ ...prev, ...Object.fromEntries(rows.map(i => [i.ip, i])) }));
         } catch (e) {
             setError(String(e));
         } finally {
-            setLoading(false);
+            setEnriching(false);
+        }
+    };
+
+    // Hosts behind each address, to spot shared infrastructure
+    const hostsByIp: Record<string, string[]> = {};
+    results.forEach(r => r.ips.forEach(ip => { (hostsByIp[ip] ||= []).push(r.subdomain); }));
+
+    const handleStop = async () => {
+        if (!jobId) return;
+        try {
+            await invoke("cancel_recon", { jobId });
+        } catch (e) {
+            console.error(e);
         }
     };
 
@@ -53,15 +274,49 @@ export default function Recon() {
                                 placeholder="e.g. google.com"
                                 onKeyDown={e => e.key === 'Enter' && handleRecon()}
                             />
-                            <Button
-                                onClick={handleRecon}
-                                disabled={loading || !domain}
-                                className="h-14 px-8 bg-brand-500 hover:bg-brand-400 text-black font-black rounded-2xl"
-                            >
-                                {loading ? <Loader2 className="animate-spin" /> : "SCAN"}
-                            </Button>
+                            {loading ? (
+                                <Button
+                                    onClick={handleStop}
+                                    className="h-14 px-8 bg-red-500 hover:bg-red-400 text-black font-black rounded-2xl"
+                                >
+                                    <Square size={14} className="mr-2" /> STOP
+                                </Button>
+                            ) : (
+                                <Button
+                                    onClick={handleRecon}
+                                    disabled={!domain}
+                                    className="h-14 px-8 bg-brand-500 hover:bg-brand-400 text-black font-black rounded-2xl"
+                                >
+                                    SCAN
+                                </Button>
+                            )}
                         </div>
                     </div>
+                    <div className="flex gap-2 mt-2">
+                        <select
+                            value={wordlistId ?? ""}
+                            onChange={e => setWordlistId(e.target.value ? parseInt(e.target.value) : null)}
+                            disabled={loading}
+                            className="flex-1 h-10 bg-zinc-900 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                        >
+                            <option value="">Built-in prefixes</option>
+                            {wordlists.map(w => (
+                                <option key={w.id} value={w.id}>{w.name} ({w.payload_count})</option>
+                            ))}
+                        </select>
+                        <label className="flex items-center gap-2 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                            Threads
+                            <input
+                                type="number"
+                                min={1}
+                                max={500}
+                                value={concurrency}
+                                onChange={e => setConcurrency(parseInt(e.target.value) || 1)}
+                                disabled={loading}
+                                className="w-20 h-10 bg-zinc-900 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                            />
+                        </label>
+                    </div>
                 </div>
             </div>
 
@@ -83,9 +338,65 @@ export default function Recon() {
                             <Zap className="h-5 w-5 text-accent-400" />
                             Active Infrastructure
                         </h3>
-                        <span className="text-xs font-mono text-zinc-500 uppercase tracking-widest">{results.length} endpoints found</span>
+                        <span className="text-xs font-mono text-zinc-500 uppercase tracking-widest">
+                            {results.length} endpoints found
+                            {progress && ` · ${progress.checked}/${progress.total} checked`}
+                            {loading && <Loader2 size={12} className="inline ml-2 animate-spin" />}
+                            {!loading && results.length > 0 && (
+                                <button
+                                    onClick={handleProbe}
+                                    disabled={probeJobId !== null}
+                                    className="ml-3 text-brand-400 hover:text-brand-300 disabled:opacity-50"
+                                >
+                                    {probeJobId ? "Probing…" : "Probe HTTP"}
+                                </button>
+                            )}
+                            {!loading && results.length > 0 && (
+                                <button
+                                    onClick={handleFingerprint}
+                                    disabled={fingerprintJobId !== null}
+                                    className="ml-3 text-brand-400 hover:text-brand-300 disabled:opacity-50"
+                                >
+                                    {fingerprintJobId ? "Fingerprinting…" : "Fingerprint"}
+                                </button>
+                            )}
+                            {!loading && results.length > 0 && (
+                                <button
+                                    onClick={handleEnrich}
+                                    disabled={enriching}
+                                    className="ml-3 text-brand-400 hover:text-brand-300 disabled:opacity-50"
+                                >
+                                    {enriching ? "Enriching…" : "Enrich IPs"}
+                                </button>
+                            )}
+                            {!loading && promotable.length > 0 && (
+                                <button
+                                    onClick={() => promote(promotable)}
+                                    className="ml-3 text-brand-400 hover:text-brand-300"
+                                >
+                                    Add {promotable.length} to inventory
+                                </button>
+                            )}
+                        </span>
+                        {!loading && results.length > 0 && <ScanProfileSelect value={scanProfileId} onChange={setScanProfileId} />}
                     </div>
 
+                    {summary && summary.wildcard_ips.length > 0 && (
+                        <div className="flex items-start gap-3 p-4 rounded-2xl bg-amber-500/10 border border-amber-500/20 text-amber-400 text-xs">
+                            <AlertTriangle size={16} className="shrink-0" />
+                            <span>
+                                Wildcard DNS detected ({summary.wildcard_ips.join(", ")}). {summary.suppressed} names resolving only to these addresses were hidden.
+                            </span>
+                        </div>
+                    )}
+
+                    {fingerprintSummary && fingerprintSummary.exposures > 0 && (
+                        <div className="flex items-start gap-3 p-4 rounded-2xl bg-red-500/10 border border-red-500/20 text-red-400 text-xs">
+                            <AlertTriangle size={16} className="shrink-0" />
+                            <span>{fingerprintSummary.exposures} exposed endpoints were added to the inventory with findings.</span>
+                        </div>
+                    )}
+
                     <div className="space-y-2">
                         {results.length > 0 ? (
                             results.map((res, i) => (
@@ -98,15 +409,77 @@ export default function Recon() {
                                             <div className="flex flex-col min-w-0">
                                                 <span className="text-sm font-black text-white truncate">{res.subdomain}</span>
                                                 <span className="text-[10px] font-mono text-zinc-500">{res.ip || "N/A"}</span>
+                                                {res.ip && ipInfo[res.ip] && (
+                                                    <span className="text-[10px] text-zinc-400 truncate">
+                                                        {ipInfo[res.ip].cloud && <span className="text-accent-400 font-bold">{ipInfo[res.ip].cloud} · </span>}
+                                                        {ipInfo[res.ip].asn !== null ? `AS${ipInfo[res.ip].asn} ${ipInfo[res.ip].as_name || ""}` : "Unknown AS"}
+                                                        {ipInfo[res.ip].ptr && ` · ${ipInfo[res.ip].ptr}`}
+                                                    </span>
+                                                )}
+                                                {res.ip && hostsByIp[res.ip]?.length > 1 && (
+                                                    <span className="text-[10px] text-zinc-500 truncate" title={hostsByIp[res.ip].join("\n")}>
+                                                        Shares {res.ip} with {hostsByIp[res.ip].length - 1} other hosts
+                                                    </span>
+                                                )}
+                                                {technologies[res.subdomain]?.length > 0 && (
+                                                    <div className="flex flex-wrap gap-1 mt-1">
+                                                        {technologies[res.subdomain].map(t => (
+                                                            <span
+                                                                key={t.name}
+                                                                title={`${t.category}: ${t.evidence}`}
+                                                                className="px-2 py-0.5 rounded-md bg-white/5 border border-white/10 text-[9px] font-bold text-zinc-300"
+                                                            >
+                                                                {t.name}
+                                                            </span>
+                                                        ))}
+                                                    </div>
+                                                )}
+                                                {probes[res.subdomain]?.alive && (
+                                                    <span className="text-[10px] text-zinc-400 truncate">
+                                                        {probes[res.subdomain].scheme} · {probes[res.subdomain].server || "unknown server"}
+                                                        {probes[res.subdomain].content_length !== null && ` · ${probes[res.subdomain].content_length} bytes`}
+                                                        {probes[res.subdomain].title && ` · ${probes[res.subdomain].title}`}
+                                                        {probes[res.subdomain].redirect && ` → ${probes[res.subdomain].redirect}`}
+                                                    </span>
+                                                )}
+                                                {probes[res.subdomain]?.cert_issuer && (
+                                                    <span className={`text-[10px] truncate ${probes[res.subdomain].cert_self_signed ? "text-amber-400" : "text-zinc-500"}`}>
+                                                        TLS {probes[res.subdomain].cert_self_signed ? "self-signed" : probes[res.subdomain].cert_issuer}
+                                                        {probes[res.subdomain].cert_not_after && ` · expires ${probes[res.subdomain].cert_not_after!.slice(0, 10)}`}
+                                                    </span>
+                                                )}
                                             </div>
                                         </div>
                                         <div className="flex items-center gap-3">
                                             <div className="px-3 py-1 rounded-full bg-green-500/10 border border-green-500/20 text-[10px] font-black text-green-400 uppercase italic">
                                                 {res.status}
                                             </div>
-                                            <button className="h-10 w-10 flex items-center justify-center rounded-xl bg-white/5 text-zinc-500 hover:text-white transition-all opacity-0 group-hover/item:opacity-100">
-                                                <ExternalLink size={16} />
-                                            </button>
+                                            {probes[res.subdomain] && (
+                                                <div
+                                                    title={probes[res.subdomain].error || undefined}
+                                                    className={`px-3 py-1 rounded-full border text-[10px] font-black uppercase ${probes[res.subdomain].alive ? "bg-brand-500/10 border-brand-500/20 text-brand-400" : "bg-zinc-500/10 border-zinc-500/20 text-zinc-500"}`}
+                                                >
+                                                    {probes[res.subdomain].alive ? `HTTP ${probes[res.subdomain].status_code}` : "No HTTP"}
+                                                </div>
+                                            )}
+                                            {res.asset_id != null && (
+                                                <div className="px-3 py-1 rounded-full bg-white/5 border border-white/10 text-[10px] font-black text-zinc-400 uppercase">
+                                                    In inventory
+                                                </div>
+                                            )}
+                                            {res.id != null && res.asset_id == null ? (
+                                                <button
+                                                    onClick={() => promote([res.id!])}
+                                                    title="Add to inventory"
+                                                    className="h-10 w-10 flex items-center justify-center rounded-xl bg-white/5 text-zinc-500 hover:text-white transition-all opacity-0 group-hover/item:opacity-100"
+                                                >
+                                                    <Plus size={16} />
+                                                </button>
+                                            ) : (
+                                                <button className="h-10 w-10 flex items-center justify-center rounded-xl bg-white/5 text-zinc-500 hover:text-white transition-all opacity-0 group-hover/item:opacity-100">
+                                                    <ExternalLink size={16} />
+                                                </button>
+                                            )}
                                         </div>
                                     </div>
                                 </div>
@@ -120,7 +493,7 @@ export default function Recon() {
                             )
                         )}
 
-                        {loading && (
+                        {loading && results.length === 0 && (
                             <div className="space-y-2">
                                 {[1, 2, 3].map(i => (
                                     <div key={i} className="glass h-20 rounded-[24px] border border-white/5 animate-pulse" />
@@ -128,10 +501,98 @@ export default function Recon() {
                             </div>
                         )}
                     </div>
+
+                    {!loading && results.length > 0 && <PortScan hosts={results.map(r => r.subdomain)} scanProfileId={scanProfileId} />}
+
+                    {domain && <ArchiveHarvest domain={domain} />}
                 </div>
 
                 {/* Intel Column */}
                 <div className="lg:col-span-1 space-y-8">
+                    {newSubdomains.map(n => (
+                        <div key={n.run_id} className="flex items-start gap-3 p-4 rounded-2xl bg-brand-500/10 border border-brand-500/20 text-brand-300 text-xs">
+                            <Bell size={16} className="shrink-0" />
+                            <div className="min-w-0 flex-1">
+                                <button onClick={() => openRun(n.run_id, n.domain)} className="font-bold text-left">
+                                    {n.subdomains.length} new subdomains of {n.domain}
+                                </button>
+                                <p className="text-[10px] text-brand-400/80 truncate">{n.subdomains.join(", ")}</p>
+                            </div>
+                            <button onClick={() => setNewSubdomains(prev => prev.filter(p => p.run_id !== n.run_id))} className="text-brand-400/60 hover:text-white">×</button>
+                        </div>
+                    ))}
+
+                    <div className="glass rounded-[32px] p-6 border border-white/5 space-y-3">
+                        <h4 className="text-sm font-black text-white uppercase tracking-widest flex items-center gap-2">
+                            <Bell size={14} className="text-brand-400" /> Monitoring
+                        </h4>
+                        <div className="flex gap-2">
+                            <select
+                                value={intervalHours}
+                                onChange={e => setIntervalHours(parseInt(e.target.value))}
+                                className="flex-1 h-9 bg-zinc-900 border border-white/5 rounded-xl px-3 text-xs text-white outline-none"
+                            >
+                                <option value={6}>Every 6 hours</option>
+                                <option value={24}>Daily</option>
+                                <option value={168}>Weekly</option>
+                            </select>
+                            <Button
+                                variant="outline"
+                                disabled={!domain}
+                                onClick={() => saveSchedule({ domain, wordlist_id: wordlistId, concurrency, interval_hours: intervalHours, enabled: true })}
+                                className="h-9 text-xs border-white/10"
+                            >
+                                Monitor {domain || "domain"}
+                            </Button>
+                        </div>
+                        {schedules.map(sc => (
+                            <div key={sc.id} className="flex items-center justify-between gap-2 text-xs">
+                                <label className="flex items-center gap-2 min-w-0">
+                                    <input
+                                        type="checkbox"
+                                        checked={sc.enabled}
+                                        onChange={e => saveSchedule({ ...sc, enabled: e.target.checked })}
+                                    />
+                                    <span className="flex flex-col min-w-0">
+                                        <span className="font-bold text-white truncate">{sc.domain}</span>
+                                        <span className="text-[10px] text-zinc-500">
+                                            every {sc.interval_hours}h · {sc.last_run_at ? `last ${sc.last_run_at.slice(0, 16)}` : "not run yet"}
+                                        </span>
+                                    </span>
+                                </label>
+                                <button onClick={() => deleteSchedule(sc.id)} className="text-zinc-600 hover:text-red-400 shrink-0">
+                                    <Trash2 size={12} />
+                                </button>
+                            </div>
+                        ))}
+                    </div>
+
+                    {runs.length > 0 && (
+                        <div className="glass rounded-[32px] p-6 border border-white/5 space-y-3">
+                            <h4 className="text-sm font-black text-white uppercase tracking-widest flex items-center gap-2">
+                                <History size={14} className="text-brand-400" /> Past Runs
+                            </h4>
+                            <div className="space-y-1 max-h-80 overflow-y-auto">
+                                {runs.map(run => (
+                                    <div
+                                        key={run.id}
+                                        className={`flex items-center justify-between gap-2 px-3 py-2 rounded-xl text-xs transition-all ${run.id === runId ? "bg-brand-500/10 border border-brand-500/20" : "hover:bg-white/5 border border-transparent"}`}
+                                    >
+                                        <button onClick={() => openRun(run.id, run.domain)} disabled={loading} className="flex flex-col items-start min-w-0 text-left">
+                                            <span className="font-bold text-white truncate">{run.domain}</span>
+                                            <span className="text-[10px] text-zinc-500">
+                                                {run.started_at.slice(0, 16)} · {run.found} found
+                                                {run.finished_at === null ? " · running" : run.cancelled ? " · stopped" : ""}
+                                            </span>
+                                        </button>
+                                        <button onClick={() => deleteRun(run.id)} className="text-zinc-600 hover:text-red-400 shrink-0">
+                                            <Trash2 size={12} />
+                                        </button>
+                                    </div>
+                                ))}
+                            </div>
+                        </div>
+                    )}
                     <div className="glass shadow-xl shadow-brand-500/5 rounded-[40px] p-8 border border-white/5 space-y-6 relative overflow-hidden group">
                         <div className="absolute -right-8 -top-8 w-48 h-48 bg-brand-500/5 blur-[100px] rounded-full group-hover:bg-brand-500/10 transition-all duration-700" />
                         <div className="relative">
@@ -3,6 +3,7 @@ import { Button } from "./ui/button";
 import { Clock, Globe, Shield, Terminal, X, Copy, Check, Play, Settings2 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { cn } from "../lib/utils";
+import { RedirectHop } from "../types";
 
 interface RepeaterProps {
     initialRequest?: {
@@ -19,6 +20,10 @@ interface ReplayResponse {
     headers: Record<string, string>;
     body: string;
     time_ms: number;
+    final_url: string;
+    redirects: RedirectHop[];
+    substituted: string[];
+    unresolved: string[];
 }
 
 export default function Repeater({ initialRequest, onClose }: RepeaterProps) {
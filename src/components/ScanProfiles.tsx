@@ -0,0 +1,212 @@
+import { useState, useEffect } from "react";
+import { Button } from "./ui/button";
+import { SlidersHorizontal, Plus, Trash2, Star } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { cn } from "../lib/utils";
+import { ActiveCheck, ScanProfile, WordlistSummary } from "../types";
+
+const CHECKS: { id: ActiveCheck; label: string; safe: boolean }[] = [
+    { id: "security_headers", label: "Security headers", safe: true },
+    { id: "web_cache", label: "Web cache", safe: false },
+    { id: "unauth_access", label: "Unauthenticated access", safe: false },
+    { id: "auth_matrix", label: "Auth matrix", safe: false },
+    { id: "http_methods", label: "HTTP methods", safe: false },
+    { id: "rate_limit", label: "Rate limit", safe: false },
+    { id: "fuzz", label: "Fuzzing & intruder", safe: false },
+    { id: "bola", label: "BOLA", safe: false },
+    { id: "discovery", label: "Port scans, probes & fingerprinting", safe: false },
+];
+
+const emptyProfile = (): ScanProfile => ({
+    name: "",
+    checks: ["security_headers"],
+    wordlist_ids: [],
+    concurrency: 2,
+    pacing: { requests_per_second: 2, delay_ms: 0, jitter_ms: 0, max_retries: 3 },
+});
+
+/** Picks the profile a scan runs under; none means the default one. */
+export function ScanProfileSelect({ value, onChange }: { value: number | null; onChange: (id: number | null) => void }) {
+    const [profiles, setProfiles] = useState<ScanProfile[]>([]);
+
+    useEffect(() => {
+        invoke<ScanProfile[]>("list_scan_profiles").then(setProfiles).catch(console.error);
+    }, []);
+
+    return (
+        <select
+            value={value ?? ""}
+            onChange={e => onChange(e.target.value ? Number(e.target.value) : null)}
+            className="h-10 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-bold text-white focus:border-brand-500 outline-none"
+            title="Scan profile"
+        >
+            <option value="">Default profile</option>
+            {profiles.map(p => (
+                <option key={p.id} value={p.id}>{p.name}</option>
+            ))}
+        </select>
+    );
+}
+
+export default function ScanProfiles() {
+    const [profiles, setProfiles] = useState<ScanProfile[]>([]);
+    const [wordlists, setWordlists] = useState<WordlistSummary[]>([]);
+    const [draft, setDraft] = useState<ScanProfile | null>(null);
+
+    const load = async () => {
+        try {
+            setProfiles(await invoke<ScanProfile[]>("list_scan_profiles"));
+        } catch (e) {
+            console.error("Failed to load scan profiles:", e);
+        }
+    };
+
+    useEffect(() => {
+        load();
+        invoke<WordlistSummary[]>("list_wordlists").then(setWordlists).catch(console.error);
+    }, []);
+
+    const save = async () => {
+        if (!draft) return;
+        try {
+            await invoke("save_scan_profile", { profile: draft });
+            setDraft(null);
+            load();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const run = (command: string, id?: number) => async () => {
+        try {
+            await invoke(command, { id });
+            load();
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const toggle = <T,>(list: T[], item: T) => (list.includes(item) ? list.filter(x => x !== item) : [...list, item]);
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                    <div className="h-10 w-10 rounded-xl bg-brand-500/10 border border-brand-500/20 flex items-center justify-center">
+                        <SlidersHorizontal className="text-brand-400 h-5 w-5" />
+                    </div>
+                    <div>
+                        <h3 className="text-sm font-black text-white uppercase italic">Scan Profiles</h3>
+                        <p className="text-[10px] font-bold text-zinc-500 uppercase">
+                            Active checks are refused unless the chosen profile allows them
+                        </p>
+                    </div>
+                </div>
+                <Button onClick={() => setDraft(emptyProfile())} className="h-10 px-4 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white font-bold">
+                    <Plus size={14} className="mr-2" />
+                    New Profile
+                </Button>
+            </div>
+
+            <div className="space-y-2">
+                {profiles.map(profile => (
+                    <div key={profile.id} className="flex items-center justify-between gap-4 p-3 rounded-xl border border-white/5 bg-zinc-950/50">
+                        <button className="text-left min-w-0" onClick={() => setDraft({ ...profile })}>
+                            <div className="text-xs font-bold text-white">{profile.name}</div>
+                            <div className="text-[10px] font-mono text-zinc-500 truncate">
+                                {profile.checks.map(c => CHECKS.find(x => x.id === c)?.label ?? c).join(", ")}
+                                {" · "}
+                                {profile.pacing.requests_per_second > 0 ? `${profile.pacing.requests_per_second}/s` : "unlimited"}
+                                {" · "}
+                                {profile.concurrency}× concurrent
+                            </div>
+                        </button>
+                        <div className="flex items-center gap-2 shrink-0">
+                            <button
+                                onClick={run("set_default_scan_profile", profile.id)}
+                                title={profile.is_default ? "Default profile" : "Make default"}
+                                className={cn("p-2 rounded-lg hover:bg-white/5", profile.is_default ? "text-amber-400" : "text-zinc-600")}
+                            >
+                                <Star size={14} fill={profile.is_default ? "currentColor" : "none"} />
+                            </button>
+                            {!profile.is_default && (
+                                <button onClick={run("delete_scan_profile", profile.id)} title="Delete" className="p-2 rounded-lg hover:bg-white/5 text-zinc-600 hover:text-red-400">
+                                    <Trash2 size={14} />
+                                </button>
+                            )}
+                        </div>
+                    </div>
+                ))}
+            </div>
+
+            {draft && (
+                <div className="space-y-4 p-4 rounded-xl border border-brand-500/20">
+                    <input
+                        className="w-full h-10 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs text-white focus:border-brand-500 outline-none"
+                        placeholder="Profile name"
+                        value={draft.name}
+                        onChange={e => setDraft({ ...draft, name: e.target.value })}
+                    />
+                    <div className="grid grid-cols-2 gap-2">
+                        {CHECKS.map(check => (
+                            <label key={check.id} className="flex items-center gap-2 text-xs text-zinc-300">
+                                <input
+                                    type="checkbox"
+                                    checked={draft.checks.includes(check.id)}
+                                    onChange={() => setDraft({ ...draft, checks: toggle(draft.checks, check.id) })}
+                                />
+                                {check.label}
+                                {!check.safe && <span className="text-[9px] font-black text-red-400 uppercase">intrusive</span>}
+                            </label>
+                        ))}
+                    </div>
+                    <div className="grid grid-cols-2 gap-4">
+                        <label className="space-y-1 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                            Max Requests /Sec (0 = unlimited)
+                            <input
+                                type="number"
+                                min={0}
+                                className="w-full h-10 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs text-white outline-none"
+                                value={draft.pacing.requests_per_second}
+                                onChange={e => setDraft({ ...draft, pacing: { ...draft.pacing, requests_per_second: Number(e.target.value) } })}
+                            />
+                        </label>
+                        <label className="space-y-1 text-[10px] font-black text-zinc-500 uppercase tracking-widest">
+                            Concurrency
+                            <input
+                                type="number"
+                                min={1}
+                                className="w-full h-10 bg-zinc-950 border border-white/5 rounded-xl px-4 text-xs text-white outline-none"
+                                value={draft.concurrency}
+                                onChange={e => setDraft({ ...draft, concurrency: Number(e.target.value) })}
+                            />
+                        </label>
+                    </div>
+                    {draft.checks.includes("fuzz") && wordlists.length > 0 && (
+                        <div className="space-y-1">
+                            <div className="text-[10px] font-black text-zinc-500 uppercase tracking-widest">Allowed Wordlists (none = any)</div>
+                            <div className="flex flex-wrap gap-2">
+                                {wordlists.map(w => (
+                                    <button
+                                        key={w.id}
+                                        onClick={() => setDraft({ ...draft, wordlist_ids: toggle(draft.wordlist_ids, w.id) })}
+                                        className={cn(
+                                            "px-2 py-1 rounded-lg text-[10px] font-mono border",
+                                            draft.wordlist_ids.includes(w.id) ? "border-brand-500 text-brand-400" : "border-white/5 text-zinc-500"
+                                        )}
+                                    >
+                                        {w.name}
+                                    </button>
+                                ))}
+                            </div>
+                        </div>
+                    )}
+                    <div className="flex justify-end gap-2">
+                        <Button onClick={() => setDraft(null)} className="h-9 px-4 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white font-bold">Cancel</Button>
+                        <Button onClick={save} disabled={!draft.name.trim()} className="h-9 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">Save</Button>
+                    </div>
+                </div>
+            )}
+        </div>
+    );
+}
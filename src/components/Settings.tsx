@@ -3,6 +3,9 @@ import { Button } from "./ui/button";
 import { Palette, Database, Shield, Trash2, Save, RefreshCw, Zap, Plus, X, AlertCircle, Bell, MessageSquare } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { cn } from "../lib/utils";
+import PromptTemplates from "./PromptTemplates";
+import AiModels from "./AiModels";
+import AuditLog from "./AuditLog";
 
 interface CustomRule {
     id?: number;
@@ -223,6 +226,10 @@ export default function Settings() {
                             </div>
                         </div>
                     </div>
+
+                    <div className="md:col-span-2">
+                        <AuditLog />
+                    </div>
                 </div>
             ) : (
                 <div className="grid grid-cols-1 lg:grid-cols-3 gap-8">
@@ -403,6 +410,10 @@ export default function Settings() {
                         </div>
                     </div>
 
+                    <AiModels />
+
+                    <PromptTemplates />
+
                     <div className="p-8 glass rounded-[32px] border border-brand-500/20 bg-brand-500/5 relative overflow-hidden group">
                         <div className="absolute -right-8 -bottom-8 w-48 h-48 bg-brand-500/10 blur-[100px] rounded-full group-hover:bg-brand-500/20 transition-all duration-700" />
                         <div className="relative flex flex-col gap-4">
@@ -0,0 +1,119 @@
+import { useState, useEffect } from "react";
+import { Button } from "./ui/button";
+import { LockOpen, Play, Square } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { cn } from "../lib/utils";
+import { FuzzSummary, UnauthRow } from "../types";
+import { ScanProfileSelect } from "./ScanProfiles";
+
+const statusColor = (status: number) =>
+    status >= 200 && status < 300
+        ? "text-emerald-400"
+        : status === 401 || status === 403
+            ? "text-zinc-500"
+            : "text-amber-400";
+
+export default function UnauthScan() {
+    const [rows, setRows] = useState<UnauthRow[]>([]);
+    const [progress, setProgress] = useState({ current: 0, total: 0 });
+    const [jobId, setJobId] = useState<string | null>(null);
+    const [scanProfileId, setScanProfileId] = useState<number | null>(null);
+    const [includeUnsafe, setIncludeUnsafe] = useState(false);
+
+    useEffect(() => {
+        const unlisten = listen<[number, number, UnauthRow]>("unauth-progress", (event) => {
+            const [current, total, row] = event.payload;
+            setProgress({ current, total });
+            setRows(prev => [...prev, row]);
+        });
+        const unlistenComplete = listen<FuzzSummary>("fuzz-complete", (event) => {
+            setJobId(current => {
+                if (current === event.payload.job_id && event.payload.error) alert(event.payload.error);
+                return current === event.payload.job_id ? null : current;
+            });
+        });
+        return () => {
+            unlisten.then(f => f());
+            unlistenComplete.then(f => f());
+        };
+    }, []);
+
+    const runScan = async () => {
+        setRows([]);
+        setProgress({ current: 0, total: 0 });
+        try {
+            setJobId(await invoke<string>("run_unauth_scan", { request: { asset_ids: [], scan_profile_id: scanProfileId, include_unsafe: includeUnsafe } }));
+        } catch (e) {
+            alert(e);
+        }
+    };
+
+    const flagged = rows.filter(row => row.findings.length > 0);
+
+    return (
+        <div className="glass-card space-y-6">
+            <div className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                    <div className="h-10 w-10 rounded-xl bg-brand-500/10 border border-brand-500/20 flex items-center justify-center">
+                        <LockOpen className="text-brand-400 h-5 w-5" />
+                    </div>
+                    <div>
+                        <h3 className="text-sm font-black text-white uppercase italic">Unauthenticated Access</h3>
+                        <p className="text-[10px] font-bold text-zinc-500 uppercase">
+                            Replays every credentialed GET asset without credentials and with an invalid token
+                        </p>
+                    </div>
+                </div>
+                <div className="flex items-center gap-2">
+                    <label className="flex items-center gap-2 text-[10px] font-bold text-zinc-500 uppercase" title="Replays POST, PUT and DELETE requests three times each">
+                        <input type="checkbox" checked={includeUnsafe} onChange={e => setIncludeUnsafe(e.target.checked)} className="accent-brand-500" />
+                        Unsafe methods
+                    </label>
+                    <ScanProfileSelect value={scanProfileId} onChange={setScanProfileId} />
+                    {jobId ? (
+                        <Button onClick={() => invoke("cancel_fuzz", { jobId })} className="h-10 px-4 rounded-xl bg-zinc-800 hover:bg-zinc-700 text-white font-bold">
+                            <Square size={14} className="mr-2" />
+                            Stop ({progress.current}/{progress.total})
+                        </Button>
+                    ) : (
+                        <Button onClick={runScan} className="h-10 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">
+                            <Play size={14} className="mr-2" />
+                            Scan Inventory
+                        </Button>
+                    )}
+                </div>
+            </div>
+
+            {rows.length > 0 && (
+                <>
+                    <p className="text-xs font-mono text-zinc-400">
+                        {flagged.length} of {rows.length} endpoints served comparable content without valid credentials
+                    </p>
+                    <table className="w-full text-xs font-mono">
+                        <thead>
+                            <tr className="text-zinc-500 text-left">
+                                <th className="py-2">Endpoint</th>
+                                <th className="py-2">Captured</th>
+                                <th className="py-2">Stripped</th>
+                                <th className="py-2">Invalid</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {rows.map(row => (
+                                <tr key={row.asset_id} className={cn("border-t border-white/5", row.findings.length > 0 && "bg-red-500/10")}>
+                                    <td className="py-2 pr-4 text-zinc-300 break-all" title={row.findings.map(f => f.description).join("\n")}>
+                                        {row.method} {row.url}
+                                    </td>
+                                    {[row.baseline_status, row.stripped_status, row.invalid_status].map((status, i) => (
+                                        <td key={i} className={cn("py-2 font-bold", statusColor(status))}>{status || "ERR"}</td>
+                                    ))}
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </>
+            )}
+        </div>
+    );
+}
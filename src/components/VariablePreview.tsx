@@ -0,0 +1,64 @@
+import { useState } from "react";
+import { Button } from "./ui/button";
+import { Braces } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { SubstitutionMode, SubstitutionPreview } from "../types";
+
+/** Shows what a request would look like once `{{variable}}` placeholders are resolved. */
+export default function VariablePreview() {
+    const [input, setInput] = useState("{{base_url}}/users");
+    const [mode, setMode] = useState<SubstitutionMode | "">("");
+    const [preview, setPreview] = useState<SubstitutionPreview | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    const run = async () => {
+        try {
+            setPreview(await invoke<SubstitutionPreview>("preview_substitution", { input, mode: mode || undefined }));
+            setError(null);
+        } catch (e) {
+            setPreview(null);
+            setError(String(e));
+        }
+    };
+
+    return (
+        <div className="p-6 rounded-2xl bg-zinc-900/50 border border-white/5 space-y-4">
+            <div className="flex items-center justify-between">
+                <h3 className="text-sm font-black uppercase tracking-widest text-white flex items-center gap-2">
+                    <Braces size={16} className="text-brand-400" />
+                    Variable Preview
+                </h3>
+                <div className="flex gap-2">
+                    <select
+                        value={mode}
+                        onChange={e => setMode(e.target.value as SubstitutionMode | "")}
+                        className="h-9 bg-zinc-950 border border-white/5 rounded-xl px-3 text-xs font-bold text-white outline-none"
+                    >
+                        <option value="">Configured mode</option>
+                        <option value="lenient">Lenient</option>
+                        <option value="strict">Strict</option>
+                    </select>
+                    <Button onClick={run} className="h-9 px-4 rounded-xl bg-brand-500 hover:bg-brand-400 text-black font-bold">
+                        Preview
+                    </Button>
+                </div>
+            </div>
+            <textarea
+                value={input}
+                onChange={e => setInput(e.target.value)}
+                placeholder={"URL, headers or body with {{variables}} or {{$uuid}}, {{$timestamp}}, {{$randomInt(1,9)}}, {{$randomEmail}}, {{$base64(text)}}; \\{{x}} stays literal"}
+                className="w-full h-20 bg-zinc-950 border border-white/5 rounded-xl p-4 text-white font-mono text-xs focus:outline-none focus:ring-2 focus:ring-brand-500/50"
+            />
+            {error && <p className="text-xs font-mono text-red-400">{error}</p>}
+            {preview && (
+                <div className="space-y-2">
+                    <pre className="p-4 rounded-xl bg-zinc-950 text-xs font-mono text-zinc-300 whitespace-pre-wrap break-all">{preview.output}</pre>
+                    <p className="text-[10px] font-mono text-zinc-500">
+                        {preview.substituted.length > 0 && `Resolved: ${preview.substituted.join(", ")}`}
+                        {preview.unresolved.length > 0 && <span className="text-amber-400"> · Unresolved: {preview.unresolved.join(", ")}</span>}
+                    </p>
+                </div>
+            )}
+        </div>
+    );
+}
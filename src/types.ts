@@ -10,20 +10,37 @@ export interface Finding {
     notes?: string;
     is_false_positive?: boolean;
     severity_override?: "High" | "Medium" | "Low" | "Info";
+    references?: string[];
+    remediation?: string;
+    occurrences?: number;
+    last_seen?: string;
 }
 
 export interface ImportEntry {
     url: string;
     method: string;
     status_code?: number;
+    req_headers?: [string, string][];
+    res_headers?: [string, string][];
     req_body?: string;
     res_body?: string;
     findings: Finding[];
 }
 
+export interface ImportPreview {
+    total_entries: number;
+    unique_urls: number;
+    hosts: string[];
+    methods: Record<string, number>;
+    findings: number;
+    new_assets: number;
+    existing_assets: number;
+}
+
 export interface ImportResult {
     entries: ImportEntry[];
     source_type: string;
+    preview?: ImportPreview;
 }
 
 export interface Asset {
@@ -35,14 +52,26 @@ export interface Asset {
     folder_id?: number;
     last_seen: string;
     notes?: string;
+    path_template?: string;
+    risk_score: number;
     findings_count?: number;
     req_body?: string;
     res_body?: string;
+    req_headers?: Record<string, string>;
+    res_headers?: Record<string, string>;
+}
+
+export interface AssetFilter {
+    folder_id?: number;
+    header_name?: string;
+    header_value?: string;
+    min_risk?: number;
+    sort?: 'last_seen' | 'risk';
 }
 
 export interface SearchResult {
-    assets: Asset[];
-    findings: Finding[];
+    assets: (Asset & { snippet: string })[];
+    findings: (Finding & { snippet: string })[];
 }
 
 
@@ -51,3 +80,1026 @@ export interface BatchImportResult {
     skipped: number;
 }
 
+
+export interface ImportJob {
+    id: string;
+    source_type: string;
+    status: "queued" | "parsing" | "importing" | "completed" | "failed" | "cancelled";
+    current: number;
+    total: number;
+    added: number;
+    skipped: number;
+    errors: string[];
+    started_at: string;
+    finished_at?: string;
+}
+
+export interface RetentionSettings {
+    max_asset_age_days?: number;
+    max_history_per_asset?: number;
+    max_db_size_mb?: number;
+    max_ai_usage_age_days?: number;
+}
+
+export interface PurgeReport {
+    assets_deleted: number;
+    findings_deleted: number;
+    history_deleted: number;
+    trash_purged: number;
+    ai_usage_deleted: number;
+    bytes_before: number;
+    bytes_after: number;
+}
+
+export type IdentityQueryMode = "parameterize" | "strip" | "keep";
+
+export interface Folder {
+    id: number;
+    name: string;
+    parent_id?: number;
+    asset_count: number;
+}
+
+export interface BackupSettings {
+    interval_hours?: number;
+    keep: number;
+}
+
+export interface BackupInfo {
+    file_name: string;
+    reason: string;
+    created_at: string;
+    size_bytes: number;
+}
+
+export type SettingKind =
+    | { type: "bool" }
+    | { type: "integer"; min: number; max: number }
+    | { type: "text" }
+    | { type: "url" }
+    | { type: "choice"; options: string[] }
+    | { type: "object" };
+
+export interface SettingEntry {
+    key: string;
+    description: string;
+    kind: SettingKind;
+    default: unknown;
+    value: unknown;
+    is_set: boolean;
+}
+
+export type Role = "Viewer" | "Analyst" | "Admin";
+
+export interface User {
+    id: number;
+    email: string;
+    name: string;
+    role: Role;
+    created_at?: string;
+    last_login?: string;
+}
+
+export interface TrashedAsset extends Asset {
+    deleted_at: string;
+}
+
+export interface MaintenanceReport {
+    integrity_ok: boolean;
+    integrity_errors: string[];
+    vacuumed: boolean;
+    bytes_before: number;
+    bytes_after: number;
+    bytes_reclaimed: number;
+}
+
+export interface CountBucket {
+    key: string;
+    count: number;
+}
+
+export interface TrendBucket {
+    day: string;
+    new_assets: number;
+    new_findings: number;
+}
+
+export interface RiskyEndpoint {
+    asset_id: number;
+    url: string;
+    method?: string;
+    findings_count: number;
+    risk_score: number;
+}
+
+export interface WorkspaceStats {
+    total_assets: number;
+    total_findings: number;
+    assets_by_host: CountBucket[];
+    assets_by_method: CountBucket[];
+    assets_by_source: CountBucket[];
+    findings_by_severity: CountBucket[];
+    findings_by_rule: CountBucket[];
+    findings_by_status: CountBucket[];
+    trend: TrendBucket[];
+    riskiest_endpoints: RiskyEndpoint[];
+}
+
+/** Omitted fields are left unchanged; `null` clears a nullable field. */
+export interface AssetUpdate {
+    notes?: string | null;
+    method?: string;
+    folder_id?: number | null;
+    status_code?: number | null;
+}
+
+export interface DiffLine {
+    op: "equal" | "added" | "removed";
+    text: string;
+}
+
+export interface JsonChange {
+    path: string;
+    op: "added" | "removed" | "changed";
+    before?: unknown;
+    after?: unknown;
+}
+
+export interface HeaderChange {
+    name: string;
+    before?: string;
+    after?: string;
+}
+
+export type BodyDiff =
+    | { format: "json"; changes: JsonChange[] }
+    | { format: "text"; lines: DiffLine[] };
+
+export interface VersionDiff {
+    timestamp_a: string;
+    timestamp_b: string;
+    status_a?: number;
+    status_b?: number;
+    headers: HeaderChange[];
+    body: BodyDiff;
+}
+
+export interface TemplateRules {
+    numeric: boolean;
+    uuid: boolean;
+    hex_hash: boolean;
+    opaque_token: boolean;
+    custom_patterns: string[];
+}
+
+export interface AssetGroup {
+    path_template: string;
+    methods: string[];
+    asset_ids: number[];
+    asset_count: number;
+    findings_count: number;
+    last_seen: string;
+}
+
+export interface SiteNode {
+    name: string;
+    kind: "host" | "path" | "method";
+    url: string;
+    asset_id?: number;
+    findings_count: number;
+    highest_severity?: string;
+    children: SiteNode[];
+}
+
+export interface RepeaterSession {
+    id: number;
+    name: string;
+    asset_id?: number;
+    url: string;
+    method: string;
+    headers?: Record<string, string>;
+    body?: string;
+    created_at: string;
+    updated_at: string;
+    attempt_count: number;
+}
+
+export interface RepeaterAttempt {
+    id: number;
+    session_id: number;
+    url: string;
+    method: string;
+    headers?: Record<string, string>;
+    body?: string;
+    status?: number;
+    res_headers?: Record<string, string>;
+    res_body?: string;
+    time_ms?: number;
+    error?: string;
+    created_at: string;
+}
+
+export interface RepeaterSessionDetail {
+    session: RepeaterSession;
+    attempts: RepeaterAttempt[];
+}
+
+export interface AttemptResult {
+    attempt: RepeaterAttempt;
+    substituted: string[];
+    unresolved: string[];
+    diff?: VersionDiff;
+}
+
+export interface ReplayOptions {
+    follow_redirects: boolean;
+    max_redirects: number;
+    timeout_ms: number;
+    use_upstream_proxy: boolean;
+    http_version: 'auto' | 'http1' | 'http2';
+}
+
+export interface ReplayRequest {
+    url: string;
+    method: string;
+    headers: Record<string, string>;
+    body?: string;
+    options?: Partial<ReplayOptions>;
+}
+
+export interface RedirectHop {
+    url: string;
+    status: number;
+    location: string;
+}
+
+export type Extractor =
+    | { type: 'json_path'; path: string }
+    | { type: 'regex'; pattern: string; header?: string }
+    | { type: 'header'; name: string };
+
+export type Extraction = Extractor & { variable: string };
+
+export interface ChainStep {
+    name: string;
+    request: ReplayRequest;
+    extract: Extraction[];
+    expect_status?: number;
+}
+
+export interface RequestChain {
+    id: number;
+    name: string;
+    steps: ChainStep[];
+    created_at: string;
+    updated_at: string;
+}
+
+export interface StepResult {
+    name: string;
+    request: ReplayRequest;
+    status?: number;
+    headers: Record<string, string>;
+    body?: string;
+    time_ms?: number;
+    extracted: Record<string, string>;
+    substituted: string[];
+    unresolved: string[];
+    error?: string;
+}
+
+export interface ChainRunResult {
+    steps: StepResult[];
+    completed: boolean;
+    variables: Record<string, string>;
+}
+
+export interface EndpointKey {
+    method: string;
+    path: string;
+}
+
+export interface ShapeDifference {
+    endpoint: EndpointKey;
+    status_codes_a: number[];
+    status_codes_b: number[];
+    schema_changes: JsonChange[];
+}
+
+export interface WorkspaceComparison {
+    only_in_a: EndpointKey[];
+    only_in_b: EndpointKey[];
+    changed: ShapeDifference[];
+    unchanged: number;
+}
+
+export interface OperationCoverage {
+    method: string;
+    path: string;
+    operation_id?: string;
+    summary?: string;
+    deprecated: boolean;
+    hits: number;
+    last_seen?: string;
+}
+
+export interface SpecCoverage {
+    spec_id: number;
+    total_operations: number;
+    observed_operations: number;
+    coverage_percent: number;
+    never_seen: OperationCoverage[];
+    observed: OperationCoverage[];
+}
+
+export interface DriftBaselineEntry {
+    id: number;
+    asset_id: number;
+    url: string;
+    rule_id: string;
+    match_content: string;
+    note?: string;
+    created_at: string;
+}
+
+export interface WordlistSummary {
+    id: number;
+    name: string;
+    category: string;
+    payload_count: number;
+    source?: string;
+    created_at: string;
+}
+
+export interface Wordlist {
+    id: number;
+    name: string;
+    category: string;
+    payloads: string[];
+    source?: string;
+    created_at: string;
+}
+
+export type InsertionLocation =
+    | { type: "url" }
+    | { type: "header"; name: string }
+    | { type: "body" };
+
+export type InsertionPoint = InsertionLocation & {
+    index: number;
+    original: string;
+};
+
+export interface FuzzSummary {
+    job_id: string;
+    total: number;
+    sent: number;
+    skipped: number;
+    findings: number;
+    interesting: number;
+    cancelled: boolean;
+    error?: string;
+}
+
+export interface OobSettings {
+    public_host: string;
+    http_port: number;
+    dns_port: number;
+    domain: string;
+}
+
+export interface OobStatus {
+    running: boolean;
+    settings: OobSettings;
+}
+
+export interface OobInteraction {
+    id: number;
+    token: string;
+    protocol: "http" | "dns";
+    remote_addr: string;
+    details: string;
+    job_id?: string;
+    url?: string;
+    method?: string;
+    payload?: string;
+    finding_id?: number;
+    created_at: string;
+}
+
+export interface ResponseDiff {
+    status_changed: boolean;
+    length_delta: number;
+    similarity: number;
+    interesting: boolean;
+    reasons: string[];
+}
+
+export interface Pacing {
+    /** 0 for no limit */
+    requests_per_second: number;
+    delay_ms: number;
+    jitter_ms: number;
+    /** Retries after a 429 or 503; 0 disables backoff */
+    max_retries: number;
+}
+
+export interface FuzzTask {
+    url: string;
+    method: string;
+    headers: Record<string, string>;
+    body?: string;
+    pacing?: Pacing;
+}
+
+export interface AuthProfile {
+    id?: number;
+    environment_id: number;
+    name: string;
+    /** Higher is more privileged, e.g. 0 anonymous, 1 user, 2 admin */
+    privilege: number;
+    headers: Record<string, string>;
+}
+
+export interface AuthMatrixRequest {
+    environment_id: number;
+    asset_ids: number[];
+    /** Lowest privilege each asset should need, by asset id */
+    required_privilege?: Record<number, number>;
+    pacing?: Pacing;
+    scan_profile_id?: number;
+}
+
+export interface MatrixCell {
+    asset_id: number;
+    profile_id: number;
+    profile_name: string;
+    status: number;
+    length: number;
+}
+
+export interface MatrixRow {
+    asset_id: number;
+    method: string;
+    url: string;
+    cells: MatrixCell[];
+    findings: Finding[];
+}
+
+export interface UnauthRow {
+    asset_id: number;
+    method: string;
+    url: string;
+    baseline_status: number;
+    stripped_status: number;
+    invalid_status: number;
+    findings: Finding[];
+}
+
+export type ActiveCheck =
+    | "security_headers"
+    | "web_cache"
+    | "unauth_access"
+    | "auth_matrix"
+    | "http_methods"
+    | "rate_limit"
+    | "fuzz"
+    | "bola"
+    | "discovery";
+
+export interface ScanProfile {
+    id?: number;
+    name: string;
+    description?: string;
+    checks: ActiveCheck[];
+    /** Wordlists fuzz runs may use; empty allows any */
+    wordlist_ids: number[];
+    concurrency: number;
+    /** Ceiling on the pacing of every run */
+    pacing: Pacing;
+    is_default?: boolean;
+}
+
+export type BypassTechnique = "forwarded_for" | "real_ip" | "path_case" | "http_version" | "api_key_header";
+
+export interface BypassAttempt {
+    technique: BypassTechnique;
+    sent: number;
+    success_count: number;
+    rate_limited_count: number;
+    error_count: number;
+    /** Whether plain requests right after the attempt were still limited */
+    control_limited: boolean;
+    verdict: "evaded" | "partial" | "blocked" | "inconclusive" | "not_applicable";
+    /** How the first request was varied */
+    example?: string;
+}
+
+export interface BypassReport {
+    job_id: string;
+    url: string;
+    /** Plain requests answered before the first 429; null when never limited */
+    limit_after?: number | null;
+    attempts: BypassAttempt[];
+    cancelled: boolean;
+    created_at?: string;
+}
+
+export interface BypassComplete {
+    job_id: string;
+    report?: BypassReport;
+    error?: string;
+}
+
+export interface BatchScanRequest {
+    asset_ids?: number[];
+    tag?: string;
+    folder_id?: number;
+    /** Only assets on this host or its subdomains */
+    host?: string;
+    scan_profile_id?: number;
+    /** Also send mutating verbs and replay assets captured with unsafe methods */
+    include_unsafe?: boolean;
+}
+
+export interface CheckOutcome {
+    asset_id: number;
+    url: string;
+    check: ActiveCheck;
+    findings: Finding[];
+    error?: string;
+}
+
+export interface BatchScanProgress {
+    job_id: string;
+    completed: number;
+    total: number;
+    outcome: CheckOutcome;
+}
+
+export interface ScanReport {
+    id: string;
+    profile_name: string;
+    request: BatchScanRequest;
+    asset_count: number;
+    check_count: number;
+    completed: number;
+    failed: number;
+    high: number;
+    medium: number;
+    low: number;
+    info: number;
+    /** Allowed by the profile but not run per asset */
+    skipped_checks: ActiveCheck[];
+    outcomes: CheckOutcome[];
+    cancelled: boolean;
+    created_at?: string;
+}
+
+export interface BatchScanComplete {
+    job_id: string;
+    report?: ScanReport;
+    error?: string;
+}
+
+export interface BolaTask {
+    /** Object id wrapped in § markers, e.g. /orders/§1001§ */
+    task: FuzzTask;
+    victim_headers: Record<string, string>;
+    attacker_headers: Record<string, string>;
+    ids?: string[];
+    neighbours?: number;
+    scan_profile_id?: number;
+}
+
+export interface BolaResult {
+    object_id: string;
+    victim_status: number;
+    attacker_status: number;
+    similarity: number;
+    verdict: "vulnerable" | "enforced" | "inconclusive";
+    finding?: Finding;
+}
+
+export type AttackStrategy = "sniper" | "pitchfork" | "cluster_bomb";
+
+export interface ExtractRule {
+    name: string;
+    /** Regex; its first capture group, or the whole match, is kept */
+    pattern: string;
+}
+
+export interface IntruderRequest {
+    /** Positions wrapped in § markers */
+    task: FuzzTask;
+    strategy: AttackStrategy;
+    /** One set for sniper, otherwise one per position */
+    payload_sets: string[][];
+    grep_match?: string[];
+    grep_extract?: ExtractRule[];
+    attack_type?: string;
+    scan_profile_id?: number;
+}
+
+export interface IntruderResult {
+    index: number;
+    /** Payload per position; null where the original value was sent */
+    payloads: (string | null)[];
+    status: number;
+    time_ms: number;
+    length: number;
+    matches: string[];
+    extracts: Record<string, string>;
+    finding?: Finding;
+    diff?: ResponseDiff;
+}
+
+export interface ReportScope {
+    folder_id?: number;
+    /** Only assets whose URL contains this host */
+    host?: string;
+    /** Finding groups narrated, most severe first (default 10) */
+    top_findings?: number;
+}
+
+export interface PayloadSuggestion {
+    hypotheses: string[];
+    payloads: string[];
+    /** Set when the payloads were saved as a wordlist */
+    wordlist_id?: number;
+}
+
+export interface TriageProgress {
+    processed: number;
+    total: number;
+    /** Groups answered from an earlier suggestion without calling the model */
+    reused: number;
+    failed: number;
+    done: boolean;
+    cancelled: boolean;
+    error?: string;
+}
+
+export interface FindingCluster {
+    representative_id: number;
+    name: string;
+    severity: string;
+    rule_ids: string[];
+    finding_ids: number[];
+    asset_count: number;
+    sample_match: string;
+}
+
+export interface PromptTemplate {
+    kind: string;
+    description: string;
+    /** Names usable as {{name}} in the template */
+    placeholders: string[];
+    default: string;
+    template: string;
+    /** False while the prompt still uses its default */
+    is_set: boolean;
+}
+
+export interface AiChat {
+    id: number;
+    asset_id: number;
+    title: string;
+    created_at: string;
+    updated_at: string;
+    /** Excluding the context message */
+    message_count: number;
+}
+
+export interface AiChatMessage {
+    id: number;
+    chat_id: number;
+    role: "system" | "user" | "assistant";
+    content: string;
+    created_at: string;
+}
+
+export interface AiChatDetail {
+    chat: AiChat;
+    /** Oldest first, starting with the context message */
+    messages: AiChatMessage[];
+}
+
+export interface AiUsageStats {
+    task: string;
+    model: string;
+    calls: number;
+    failures: number;
+    /** Summed over calls where the server reported them */
+    prompt_tokens: number;
+    completion_tokens: number;
+    avg_latency_ms: number;
+    max_latency_ms: number;
+    last_used: string;
+}
+
+export interface ReconResult {
+    /** Set once the result is saved with its run */
+    id?: number | null;
+    subdomain: string;
+    ip: string | null;
+    status: string;
+    /** Every address the name resolved to */
+    ips: string[];
+    /** Inventory asset created by promote_recon_to_assets */
+    asset_id?: number | null;
+}
+
+export interface ReconRun {
+    id: number;
+    domain: string;
+    wordlist_id: number | null;
+    total: number;
+    checked: number;
+    found: number;
+    wildcard_ips: string[];
+    suppressed: number;
+    cancelled: boolean;
+    started_at: string;
+    /** Unset while the run is in progress */
+    finished_at: string | null;
+}
+
+export interface CloudRange {
+    /** e.g. `203.0.113.0/24` */
+    cidr: string;
+    provider: string;
+}
+
+/** The `cloud_ranges` setting */
+export interface CloudRangeSettings {
+    /** Checked before the built-in ranges */
+    ranges: CloudRange[];
+}
+
+export interface IpInfo {
+    ip: string;
+    /** Reverse DNS name, without the trailing dot */
+    ptr: string | null;
+    asn: number | null;
+    as_name: string | null;
+    prefix: string | null;
+    country: string | null;
+    /** `AWS`, `GCP`, `Azure`, `Cloudflare`, `Fastly`, `Akamai`, `Imperva` or a provider from the `cloud_ranges` setting */
+    cloud: string | null;
+    looked_up_at: string;
+}
+
+/** The CA signing certificates for intercepted hosts */
+export interface CaInfo {
+    subject: string;
+    issuer: string;
+    not_before: string;
+    not_after: string;
+    /** Uppercase hex SHA-1 */
+    thumbprint: string;
+    /** Imported by the user rather than generated */
+    imported: boolean;
+    /** Within the configured warning period of `not_after`, or past it */
+    expiring: boolean;
+    /** Something about the change just made needs the user's attention */
+    warning: string | null;
+}
+
+/** Value of the `certificates` setting */
+export interface CertificateSettings {
+    /** At most 3650 */
+    ca_valid_days: number;
+    /** At most 825; leaves never outlive the CA */
+    leaf_valid_days: number;
+    /** At most 3650 */
+    expiry_warning_days: number;
+    /** Used for CAs generated or rotated from now on */
+    ca_key: KeyAlgorithm;
+    leaf_key: KeyAlgorithm;
+    /** Copy the subject, names and validity of the server's real certificate */
+    mirror_upstream: boolean;
+}
+
+export type KeyAlgorithm = "ecdsa_p256" | "rsa2048" | "rsa4096";
+
+/** Recurring enumeration of a monitored domain */
+export interface ReconSchedule {
+    id: number;
+    domain: string;
+    wordlist_id: number | null;
+    concurrency: number | null;
+    interval_hours: number;
+    enabled: boolean;
+    last_run_at: string | null;
+    last_run_id: number | null;
+}
+
+/** Subdomains a scheduled run found that no earlier run of the domain had */
+export interface NewSubdomains {
+    domain: string;
+    run_id: number;
+    subdomains: string[];
+}
+
+export interface ReconProgress {
+    job_id: string;
+    checked: number;
+    total: number;
+    found: number;
+}
+
+export interface SubdomainSummary {
+    job_id: string;
+    /** Saved run; load its results with get_recon_results */
+    run_id: number;
+    total: number;
+    checked: number;
+    found: number;
+    /** Addresses random names resolve to; empty unless the domain has wildcard DNS */
+    wildcard_ips: string[];
+    /** Names dropped because they only resolved to wildcard addresses */
+    suppressed: number;
+    cancelled: boolean;
+}
+
+export interface OpenPort {
+    id: number;
+    host: string;
+    ip: string;
+    port: number;
+    /** `http`, `https`, `ssh`, or a guess from the port number */
+    service: string | null;
+    banner: string | null;
+    http_status: number | null;
+    http_title: string | null;
+    /** Root URL for web services; only these can be promoted to assets */
+    url: string | null;
+    asset_id: number | null;
+    first_seen: string;
+    last_seen: string;
+}
+
+export interface PortScanSummary {
+    job_id: string;
+    hosts: number;
+    unresolved: string[];
+    total: number;
+    checked: number;
+    open: number;
+    cancelled: boolean;
+}
+
+export type ArchiveSource = "wayback" | "common_crawl";
+
+export interface ArchiveCandidate {
+    url: string;
+    sources: ArchiveSource[];
+    /** Status code when last archived */
+    status: number | null;
+    mime: string | null;
+    /** `YYYYMMDDhhmmss` */
+    last_archived: string | null;
+    in_inventory: boolean;
+}
+
+export interface ArchiveHarvest {
+    candidates: ArchiveCandidate[];
+    /** Captures returned by the indexes before filtering */
+    captures: number;
+    /** Sources that failed; the others are still used */
+    errors: string[];
+}
+
+export interface HostProbe {
+    id: number;
+    host: string;
+    alive: boolean;
+    /** `https` or `http`; null when neither answered */
+    scheme: string | null;
+    url: string | null;
+    status_code: number | null;
+    redirect: string | null;
+    server: string | null;
+    content_length: number | null;
+    title: string | null;
+    error: string | null;
+    probed_at: string;
+    /** Shodan-style favicon hash, set by fingerprinting */
+    favicon_hash: number | null;
+    /** Certificate presented over HTTPS */
+    cert_subject: string | null;
+    cert_issuer: string | null;
+    cert_sans: string[] | null;
+    cert_not_after: string | null;
+    cert_self_signed: boolean | null;
+}
+
+export interface ProbeSummary {
+    job_id: string;
+    total: number;
+    checked: number;
+    alive: number;
+    /** Names taken from certificates and probed as well */
+    discovered: number;
+    cancelled: boolean;
+}
+
+export interface Technology {
+    name: string;
+    /** `server`, `framework`, `gateway`, `docs`, ... */
+    category: string;
+    /** Header, path or favicon hash it was detected from */
+    evidence: string;
+}
+
+export interface HostTechnology extends Technology {
+    id: number;
+    host: string;
+    detected_at: string;
+}
+
+export interface HostFingerprint {
+    host: string;
+    base_url: string | null;
+    favicon_hash: number | null;
+    technologies: Technology[];
+    /** URLs imported as assets with a finding */
+    exposures: string[];
+    error: string | null;
+}
+
+export interface FingerprintSummary {
+    job_id: string;
+    total: number;
+    checked: number;
+    exposures: number;
+    cancelled: boolean;
+}
+
+export type SubstitutionMode = "lenient" | "strict";
+
+export interface SubstitutionPreview {
+    output: string;
+    substituted: string[];
+    unresolved: string[];
+}
+
+export type OAuthGrant = "client_credentials" | "password";
+
+export type AuthConfig =
+    | { type: "none" }
+    | { type: "bearer"; token: string }
+    | { type: "api_key"; header: string; value: string }
+    | {
+          type: "oauth2";
+          grant: OAuthGrant;
+          token_url: string;
+          client_id: string;
+          client_secret: string;
+          username: string;
+          password: string;
+          scope: string;
+      };
+
+export interface AuditEntry {
+    id: number;
+    user_id?: number;
+    user_name?: string;
+    user_email?: string;
+    action: string;
+    entity_type: string;
+    entity_id?: number;
+    details?: string;
+    timestamp: string;
+    prev_hash?: string;
+    hash?: string;
+}
+
+export interface AuditQuery {
+    from?: string;
+    to?: string;
+    user_id?: number;
+    action?: string;
+    entity_type?: string;
+    entity_id?: number;
+    offset?: number;
+    limit?: number;
+}
+
+export interface AuditPage {
+    entries: AuditEntry[];
+    total: number;
+}
+
+export interface AuditFacets {
+    actions: string[];
+    entity_types: string[];
+}
+
+export interface AuditIntegrity {
+    entries: number;
+    valid: boolean;
+    head_hash?: string;
+    breaks: { id: number; reason: string }[];
+}